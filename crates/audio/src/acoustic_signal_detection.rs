@@ -0,0 +1,128 @@
+use std::{f32::consts::PI, sync::Arc};
+
+use color_eyre::Result;
+use context_attribute::context;
+use filtering::statistics::{mean, standard_deviation};
+use framework::{deserialize_not_implemented, MainOutput};
+use rustfft::{
+    num_complex::{Complex32, ComplexFloat},
+    num_traits::Zero,
+    Fft, FftPlanner,
+};
+use serde::{Deserialize, Serialize};
+use types::{
+    acoustic_signals::{AcousticCode, AcousticDetection, AcousticEvent},
+    parameters::AcousticSignalingParameters,
+    samples::Samples,
+};
+
+use crate::whistle_detection::NUMBER_OF_AUDIO_SAMPLES;
+
+const NUMBER_OF_FREQUENCY_SAMPLES: usize = NUMBER_OF_AUDIO_SAMPLES / 2;
+
+#[derive(Deserialize, Serialize)]
+pub struct AcousticSignalDetection {
+    #[serde(skip, default = "deserialize_not_implemented")]
+    fft: Arc<dyn Fft<f32>>,
+    #[serde(skip)]
+    scratch: Vec<Complex32>,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    parameters: Parameter<AcousticSignalingParameters, "acoustic_signaling">,
+
+    samples: Input<Samples, "samples">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub detected_acoustic_signals: MainOutput<AcousticDetection>,
+}
+
+impl AcousticSignalDetection {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        let mut planner = FftPlanner::new();
+        let fft = planner.plan_fft_forward(NUMBER_OF_AUDIO_SAMPLES);
+        let scratch = vec![Complex32::zero(); fft.get_inplace_scratch_len()];
+        Ok(Self { fft, scratch })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        let parameters = context.parameters;
+        if !parameters.enable {
+            return Ok(MainOutputs::default());
+        }
+
+        let mut detection = AcousticDetection::default();
+        for buffer in context.samples.channels_of_samples.iter() {
+            let spectrum = self.spectrum(buffer);
+            detection.striker |= contains_code(
+                &spectrum,
+                context.samples.rate,
+                parameters.code(AcousticEvent::Striker),
+                parameters.tone_scaling,
+            );
+            detection.ball_near_own_goal |= contains_code(
+                &spectrum,
+                context.samples.rate,
+                parameters.code(AcousticEvent::BallNearOwnGoal),
+                parameters.tone_scaling,
+            );
+        }
+
+        Ok(MainOutputs {
+            detected_acoustic_signals: detection.into(),
+        })
+    }
+
+    fn spectrum(&mut self, buffer: &[f32]) -> Vec<f32> {
+        let mut buffer: Vec<_> = buffer
+            .iter()
+            .enumerate()
+            .map(|(i, &sample)| {
+                let hann = (PI * i as f32 / NUMBER_OF_AUDIO_SAMPLES as f32)
+                    .sin()
+                    .powi(2);
+                Complex32::new(hann * sample, 0.0)
+            })
+            .collect();
+        self.fft
+            .process_with_scratch(&mut buffer, &mut self.scratch);
+        buffer
+            .iter()
+            .take(NUMBER_OF_FREQUENCY_SAMPLES)
+            .map(|sample| (sample / (NUMBER_OF_FREQUENCY_SAMPLES as f32).sqrt()).abs())
+            .collect()
+    }
+}
+
+fn contains_code(
+    spectrum: &[f32],
+    sample_rate: u32,
+    code: &AcousticCode,
+    tone_scaling: f32,
+) -> bool {
+    let overall_mean = mean(spectrum);
+    let threshold = overall_mean + tone_scaling * standard_deviation(spectrum, overall_mean);
+    [code.low_frequency, code.high_frequency]
+        .into_iter()
+        .all(|frequency| tone_magnitude(spectrum, sample_rate, frequency) > threshold)
+}
+
+/// Maximum magnitude around the bin of `frequency`, tolerating leakage into neighboring bins.
+/// Tones above the Nyquist frequency of downsampled buffers have no bin.
+fn tone_magnitude(spectrum: &[f32], sample_rate: u32, frequency: f32) -> f32 {
+    let frequency_resolution = sample_rate as f32 / NUMBER_OF_AUDIO_SAMPLES as f32;
+    let index = (frequency / frequency_resolution).round() as usize;
+    spectrum
+        .iter()
+        .skip(index.saturating_sub(1))
+        .take(3)
+        .copied()
+        .fold(0.0, f32::max)
+}
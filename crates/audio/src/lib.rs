@@ -1,2 +1,4 @@
+pub mod acoustic_signal_detection;
 pub mod microphone_recorder;
+pub mod rate_limiter;
 pub mod whistle_detection;
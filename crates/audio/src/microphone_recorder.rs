@@ -1,12 +1,19 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
 use color_eyre::{eyre::WrapErr, Result};
 use context_attribute::context;
 use framework::MainOutput;
-use hardware::MicrophoneInterface;
+use hardware::{MicrophoneInterface, TimeInterface};
 use serde::{Deserialize, Serialize};
 use types::samples::Samples;
 
 #[derive(Deserialize, Serialize)]
-pub struct MicrophoneRecorder {}
+pub struct MicrophoneRecorder {
+    last_cycle_start: Option<SystemTime>,
+}
 
 #[context]
 pub struct CreationContext {}
@@ -14,6 +21,7 @@ pub struct CreationContext {}
 #[context]
 pub struct CycleContext {
     hardware_interface: HardwareInterface,
+    minimum_cycle_interval: CyclerState<Duration, "minimum_cycle_interval">,
 }
 
 #[context]
@@ -24,19 +32,68 @@ pub struct MainOutputs {
 
 impl MicrophoneRecorder {
     pub fn new(_context: CreationContext) -> Result<Self> {
-        Ok(Self {})
+        Ok(Self {
+            last_cycle_start: None,
+        })
     }
 
     pub fn cycle(
         &mut self,
-        context: CycleContext<impl MicrophoneInterface>,
+        context: CycleContext<impl MicrophoneInterface + TimeInterface>,
     ) -> Result<MainOutputs> {
-        let samples = context
+        let mut buffers = vec![context
             .hardware_interface
             .read_from_microphones()
-            .wrap_err("failed to read from microphones")?;
+            .wrap_err("failed to read from microphones")?];
+        // the microphones keep recording while downscaled, all buffers of the cycle interval are
+        // downsampled into one such that a whistle during it is not missed
+        while let Some(last_cycle_start) = self.last_cycle_start {
+            let elapsed = context
+                .hardware_interface
+                .get_now()
+                .duration_since(last_cycle_start)
+                .unwrap_or_default();
+            if elapsed >= *context.minimum_cycle_interval {
+                break;
+            }
+            buffers.push(
+                context
+                    .hardware_interface
+                    .read_from_microphones()
+                    .wrap_err("failed to read from microphones")?,
+            );
+        }
+        self.last_cycle_start = Some(context.hardware_interface.get_now());
         Ok(MainOutputs {
-            samples: samples.into(),
+            samples: downsample(buffers).into(),
+        })
+    }
+}
+
+/// Averages consecutive samples of the concatenated buffers down to the length of a single
+/// buffer, the averaging filters frequencies above the reduced rate
+fn downsample(mut buffers: Vec<Samples>) -> Samples {
+    let factor = buffers.len();
+    if factor == 1 {
+        return buffers.remove(0);
+    }
+    let number_of_channels = buffers[0].channels_of_samples.len();
+    let channels_of_samples = (0..number_of_channels)
+        .map(|channel| {
+            let concatenated: Vec<f32> = buffers
+                .iter()
+                .filter_map(|buffer| buffer.channels_of_samples.get(channel))
+                .flatten()
+                .copied()
+                .collect();
+            concatenated
+                .chunks_exact(factor)
+                .map(|chunk| chunk.iter().sum::<f32>() / factor as f32)
+                .collect()
         })
+        .collect();
+    Samples {
+        rate: buffers[0].rate / factor as u32,
+        channels_of_samples: Arc::new(channels_of_samples),
     }
 }
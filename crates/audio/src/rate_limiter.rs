@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use color_eyre::Result;
+use context_attribute::context;
+use framework::MainOutput;
+use hardware::TimeInterface;
+use serde::{Deserialize, Serialize};
+use types::cycler_rates::{CyclerRate, CyclerRateLimiter, CyclerRateLimits};
+
+/// Hands the rate granted by the control cycler to the setup node of this cycler
+#[derive(Deserialize, Serialize)]
+pub struct RateLimiter {
+    rate_limiter: CyclerRateLimiter,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    cycler_rate_limits: Input<CyclerRateLimits, "Control", "cycler_rate_limits">,
+    smoothing_factor: Parameter<f32, "cycler_rates.smoothing_factor">,
+
+    minimum_cycle_interval: CyclerState<Duration, "minimum_cycle_interval">,
+    hardware_interface: HardwareInterface,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub cycler_rate: MainOutput<CyclerRate>,
+}
+
+impl RateLimiter {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            rate_limiter: Default::default(),
+        })
+    }
+
+    pub fn cycle(&mut self, mut context: CycleContext<impl TimeInterface>) -> Result<MainOutputs> {
+        let cycler_rate = self.rate_limiter.update(
+            context.hardware_interface.get_now(),
+            context.cycler_rate_limits.audio,
+            *context.smoothing_factor,
+        );
+        *context.minimum_cycle_interval = cycler_rate.minimum_cycle_interval();
+
+        Ok(MainOutputs {
+            cycler_rate: cycler_rate.into(),
+        })
+    }
+}
@@ -65,6 +65,7 @@ impl WhistleDetection {
             .map(|buffer| {
                 self.is_whistle_detected_in_buffer(
                     buffer,
+                    context.samples.rate,
                     context.parameters,
                     &mut context.audio_spectrums,
                     &mut context.detection_infos,
@@ -79,11 +80,12 @@ impl WhistleDetection {
     fn is_whistle_detected_in_buffer(
         &mut self,
         buffer: &[f32],
+        sample_rate: u32,
         detection_parameters: &WhistleDetectionParameters,
         audio_spectrums: &mut AdditionalOutput<Vec<Vec<(f32, f32)>>>,
         detection_infos: &mut AdditionalOutput<Vec<DetectionInfo>>,
     ) -> bool {
-        let frequency_resolution = AUDIO_SAMPLE_RATE as f32 / NUMBER_OF_AUDIO_SAMPLES as f32;
+        let frequency_resolution = sample_rate as f32 / NUMBER_OF_AUDIO_SAMPLES as f32;
         let mut buffer: Vec<_> = buffer
             .iter()
             .enumerate()
@@ -113,6 +113,7 @@ fn generate_struct(cycler: &Cycler, cyclers: &Cyclers, mode: Execution) -> Token
         quote! {
             recording_sender: std::sync::mpsc::SyncSender<crate::cyclers::RecordingFrame>,
             enable_recording: bool,
+            state_snapshot: framework::StateSnapshot,
         }
     } else {
         Default::default()
@@ -201,7 +202,7 @@ fn generate_node_fields(cycler: &Cycler) -> TokenStream {
 fn generate_implementation(cycler: &Cycler, cyclers: &Cyclers, mode: Execution) -> TokenStream {
     let new_method = generate_new_method(cycler, cyclers, mode);
     let start_method = match mode {
-        Execution::None | Execution::Run => generate_start_method(cycler.kind),
+        Execution::None | Execution::Run => generate_start_method(cycler, mode),
         Execution::Replay => Default::default(),
     };
     let cycle_method = generate_cycle_method(cycler, cyclers, mode);
@@ -230,6 +231,7 @@ fn generate_new_method(cycler: &Cycler, cyclers: &Cyclers, mode: Execution) -> T
         quote! {
             recording_sender: std::sync::mpsc::SyncSender<crate::cyclers::RecordingFrame>,
             enable_recording: bool,
+            state_snapshot_path: std::path::PathBuf,
         }
     } else {
         Default::default()
@@ -238,10 +240,16 @@ fn generate_new_method(cycler: &Cycler, cyclers: &Cyclers, mode: Execution) -> T
         quote! {
             recording_sender,
             enable_recording,
+            state_snapshot,
         }
     } else {
         Default::default()
     };
+    let restore_state_snapshot = if mode == Execution::Run {
+        generate_restore_state_snapshot(cycler)
+    } else {
+        Default::default()
+    };
 
     quote! {
         pub(crate) fn new(
@@ -257,6 +265,7 @@ fn generate_new_method(cycler: &Cycler, cyclers: &Cyclers, mode: Execution) -> T
             let parameters = parameters_reader.next().clone();
             let mut cycler_state = crate::structs::#cycler_module_name::CyclerState::default();
             #node_initializers
+            #restore_state_snapshot
             Ok(Self {
                 instance,
                 hardware_interface,
@@ -281,7 +290,7 @@ fn generate_node_initializers(cycler: &Cycler) -> TokenStream {
         let field_initializers = generate_node_field_initializers(node, cycler);
         let error_message = format!("failed to create node `{}`", node.name);
         quote! {
-            let #node_name_snake_case = #node_module::#node_name::new(
+            let mut #node_name_snake_case = #node_module::#node_name::new(
                 #node_module::CreationContext::new(
                     #field_initializers
                 )
@@ -346,6 +355,57 @@ fn generate_node_field_initializers(node: &Node, cycler: &Cycler) -> TokenStream
         .collect()
 }
 
+fn generate_restore_state_snapshot(cycler: &Cycler) -> TokenStream {
+    let restorations = cycler.iter_persisted_nodes().map(|node| {
+        let node_name = &node.name;
+        let node_name_snake_case = format_ident!("{}", node.name.to_case(Case::Snake));
+        quote! {
+            state_snapshot.restore(#node_name, &mut #node_name_snake_case);
+        }
+    });
+    quote! {
+        let mut state_snapshot = framework::StateSnapshot::load(state_snapshot_path);
+        #(#restorations)*
+    }
+}
+
+fn generate_state_snapshot_additions(cycler: &Cycler) -> Vec<TokenStream> {
+    cycler
+        .iter_persisted_nodes()
+        .map(|node| {
+            let node_name = &node.name;
+            let node_member = format_ident!("{}", node.name.to_case(Case::Snake));
+            quote! {
+                self.state_snapshot.add(#node_name, &self.#node_member)?;
+            }
+        })
+        .collect()
+}
+
+fn generate_finish_state_snapshot(cycler: &Cycler) -> TokenStream {
+    let additions = generate_state_snapshot_additions(cycler);
+    if additions.is_empty() {
+        return Default::default();
+    }
+    quote! {
+        #(#additions)*
+        self.state_snapshot.finish();
+    }
+}
+
+fn generate_write_state_snapshot(cycler: &Cycler) -> TokenStream {
+    let additions = generate_state_snapshot_additions(cycler);
+    if additions.is_empty() {
+        return Default::default();
+    }
+    quote! {
+        if self.state_snapshot.is_due() {
+            #(#additions)*
+            self.state_snapshot.write();
+        }
+    }
+}
+
 fn generate_input_output_identifiers(cycler: &Cycler, cyclers: &Cyclers) -> TokenStream {
     match cycler.kind {
         CyclerKind::Perception => {
@@ -380,8 +440,8 @@ fn generate_consumer_identifiers(cyclers: &Cyclers) -> Vec<Ident> {
         .collect()
 }
 
-fn generate_start_method(cycler_kind: CyclerKind) -> TokenStream {
-    let scheduler_tokens = match cycler_kind {
+fn generate_start_method(cycler: &Cycler, mode: Execution) -> TokenStream {
+    let scheduler_tokens = match cycler.kind {
         CyclerKind::Perception => TokenStream::new(),
         CyclerKind::RealTime => quote! {
             #[cfg(feature = "realtime")]
@@ -401,6 +461,11 @@ fn generate_start_method(cycler_kind: CyclerKind) -> TokenStream {
             }
         },
     };
+    // the last state is persisted on a graceful shutdown, the periodic snapshot may be outdated
+    let finish_state_snapshot = match mode {
+        Execution::Run => generate_finish_state_snapshot(cycler),
+        Execution::None | Execution::Replay => Default::default(),
+    };
 
     quote! {
         pub(crate) fn start(
@@ -420,6 +485,7 @@ fn generate_start_method(cycler_kind: CyclerKind) -> TokenStream {
                             });
                         }
                     }
+                    #finish_state_snapshot
                     Ok(())
                 })
                 .wrap_err_with(|| {
@@ -525,8 +591,11 @@ fn generate_cycle_method(cycler: &Cycler, cyclers: &Cyclers, mode: Execution) ->
                 }
             });
 
+            let write_state_snapshot = generate_write_state_snapshot(cycler);
+
             quote! {
                 #after_remaining_nodes
+                #write_state_snapshot
                 let recording_duration = recording_timestamp.elapsed().expect("time ran backwards");
 
                 if enable_recording {
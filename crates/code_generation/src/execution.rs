@@ -53,6 +53,7 @@ pub fn generate_run_function(cyclers: &Cyclers) -> TokenStream {
                 addresses, parameters_directory, body_id, head_id, #number_of_parameter_slots, keep_running.clone())
                 .wrap_err("failed to start communication server")?;
 
+            let state_snapshot_directory = log_path.as_ref().join("state_snapshots");
             let recording_thread = #recording_thread;
 
             #construct_cyclers
@@ -259,6 +260,14 @@ fn generate_recording_thread(cyclers: &Cyclers) -> TokenStream {
         }
     });
 
+    let file_flushes = cyclers.instances().map(|(_cycler, instance)| {
+        let instance_name_snake_case = format_ident!("{}", instance.to_case(Case::Snake));
+        let error_message = format!("failed to flush recording file for {instance}");
+        quote! {
+            #instance_name_snake_case.flush().wrap_err(#error_message)?;
+        }
+    });
+
     quote! {
         {
             let keep_running = keep_running.clone();
@@ -275,6 +284,7 @@ fn generate_recording_thread(cyclers: &Cyclers) -> TokenStream {
                                 #(#frame_writes)*
                             }
                         }
+                        #(#file_flushes)*
                         Ok(())
                     })();
 
@@ -338,9 +348,11 @@ fn generate_cycler_constructors(cyclers: &Cyclers, mode: Execution) -> TokenStre
                 },
             });
         let recording_parameters = if mode == Execution::Run {
+            let state_snapshot_file_name = format!("{instance}.bincode");
             quote! {
                 recording_sender.clone(),
                 enable_recording,
+                state_snapshot_directory.join(#state_snapshot_file_name),
             }
         } else {
             Default::default()
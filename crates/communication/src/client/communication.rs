@@ -189,4 +189,12 @@ impl Communication {
             .await
             .unwrap();
     }
+
+    /// Updates all values at once, the robot either applies all of them or none
+    pub async fn update_parameter_values(&self, updates: Vec<(String, Value)>) {
+        self.parameter_subscription_manager
+            .send(parameter_subscription_manager::Message::UpdateParameterValues { updates })
+            .await
+            .unwrap();
+    }
 }
@@ -47,6 +47,9 @@ pub enum Message {
         path: String,
         value: Value,
     },
+    UpdateParameterValues {
+        updates: Vec<(String, Value)>,
+    },
 }
 
 #[derive(Default)]
@@ -173,9 +176,30 @@ pub async fn parameter_subscription_manager(
             }
             Message::UpdateParameterValue { path, value } => {
                 if let Some(some_requester) = requester {
-                    match update_parameter_value(
-                        path,
-                        value,
+                    match update_parameters(
+                        |id| ParametersRequest::Update {
+                            id,
+                            path,
+                            data: value,
+                        },
+                        &id_tracker,
+                        &responder,
+                        &some_requester,
+                    )
+                    .await
+                    {
+                        Ok(_) => requester = Some(some_requester),
+                        Err(error) => {
+                            error!("{error}");
+                            requester = None
+                        }
+                    }
+                }
+            }
+            Message::UpdateParameterValues { updates } => {
+                if let Some(some_requester) = requester {
+                    match update_parameters(
+                        |id| ParametersRequest::UpdateTransaction { id, updates },
                         &id_tracker,
                         &responder,
                         &some_requester,
@@ -228,9 +252,8 @@ async fn query_parameter_hierarchy(
     });
 }
 
-async fn update_parameter_value(
-    path: String,
-    value: Value,
+async fn update_parameters(
+    request: impl FnOnce(usize) -> ParametersRequest,
     id_tracker: &mpsc::Sender<id_tracker::Message>,
     responder: &mpsc::Sender<responder::Message>,
     requester: &mpsc::Sender<Request>,
@@ -244,11 +267,7 @@ async fn update_parameter_value(
         })
         .await?;
     requester
-        .send(Request::Parameters(ParametersRequest::Update {
-            id: message_id,
-            path,
-            data: value,
-        }))
+        .send(Request::Parameters(request(message_id)))
         .await?;
     spawn(async move {
         let response = response_receiver.await.unwrap();
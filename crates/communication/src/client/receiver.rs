@@ -83,7 +83,8 @@ pub async fn receiver(
                                     error!("{error}");
                                 }
                             }
-                            ParametersResponse::Update { id, result } => {
+                            ParametersResponse::Update { id, result }
+                            | ParametersResponse::UpdateTransaction { id, result } => {
                                 respond(&responder, id, Response::Update(result)).await
                             }
                             ParametersResponse::GetCurrent { id: _, result: _ } => todo!(),
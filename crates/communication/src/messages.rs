@@ -131,14 +131,39 @@ pub enum BinaryOutputsResponse {
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum ParametersRequest {
-    GetFields { id: usize },
-    GetCurrent { id: usize, path: Path },
-    Subscribe { id: usize, path: Path },
-    Unsubscribe { id: usize, subscription_id: usize },
+    GetFields {
+        id: usize,
+    },
+    GetCurrent {
+        id: usize,
+        path: Path,
+    },
+    Subscribe {
+        id: usize,
+        path: Path,
+    },
+    Unsubscribe {
+        id: usize,
+        subscription_id: usize,
+    },
     UnsubscribeEverything,
-    Update { id: usize, path: Path, data: Value },
-    LoadFromDisk { id: usize },
-    StoreToDisk { id: usize, scope: Scope, path: Path },
+    Update {
+        id: usize,
+        path: Path,
+        data: Value,
+    },
+    UpdateTransaction {
+        id: usize,
+        updates: Vec<(Path, Value)>,
+    },
+    LoadFromDisk {
+        id: usize,
+    },
+    StoreToDisk {
+        id: usize,
+        scope: Scope,
+        path: Path,
+    },
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -167,6 +192,10 @@ pub enum ParametersResponse {
         id: usize,
         result: Result<(), Reason>,
     },
+    UpdateTransaction {
+        id: usize,
+        result: Result<(), Reason>,
+    },
     LoadFromDisk {
         id: usize,
         result: Result<(), Reason>,
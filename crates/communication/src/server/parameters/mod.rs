@@ -16,6 +16,11 @@ pub enum StorageRequest {
         path: Path,
         data: Value,
     },
+    UpdateParameters {
+        client: Client,
+        id: usize,
+        updates: Vec<(Path, Value)>,
+    },
     LoadFromDisk {
         client: Client,
         id: usize,
@@ -95,6 +95,46 @@ async fn handle_request<Parameters>(
 
             respond(client, ParametersResponse::Update { id, result: Ok(()) }).await;
         }
+        StorageRequest::UpdateParameters {
+            client,
+            id,
+            updates,
+        } => {
+            // updates are applied to a copy, cyclers only ever see all or none of them
+            let mut updated_parameters = parameters.clone();
+            let result = updates.into_iter().try_for_each(|(path, data)| {
+                if !Parameters::exists(&path) {
+                    return Err(format!("path {path:?} does not exist"));
+                }
+                updated_parameters
+                    .deserialize_path(&path, data)
+                    .map_err(|error| format!("failed to deserialize {path:?}: {error:?}"))
+            });
+            if let Err(error) = result {
+                respond(
+                    client,
+                    ParametersResponse::UpdateTransaction {
+                        id,
+                        result: Err(error),
+                    },
+                )
+                .await;
+                return;
+            }
+
+            *parameters = updated_parameters;
+            {
+                let mut slot = parameters_writer.next();
+                *slot = parameters.clone();
+            }
+            parameters_changed.notify_one();
+
+            respond(
+                client,
+                ParametersResponse::UpdateTransaction { id, result: Ok(()) },
+            )
+            .await;
+        }
         StorageRequest::LoadFromDisk { client, id } => {
             let parameters = match deserialize(parameters_directory, body_id, head_id).await {
                 Ok(parameters) => parameters,
@@ -297,4 +337,53 @@ mod tests {
         drop(request_sender);
         subscriptions_task.await.unwrap();
     }
+
+    #[tokio::test]
+    async fn failing_transaction_leaves_parameters_untouched() {
+        let path = "a.b.c".to_string();
+        let (parameters_writer, parameters_reader) = multiple_buffer_with_slots([ParametersFake {
+            existing_fields: [(path.clone(), 42)].into(),
+        }]);
+        let parameters_changed = Arc::new(Notify::new());
+        let (request_sender, request_receiver) = channel(1);
+        let subscriptions_task = storage(
+            parameters_writer,
+            parameters_changed,
+            request_receiver,
+            ".",
+            Default::default(),
+            Default::default(),
+        );
+
+        let (response_sender, mut response_receiver) = channel(1);
+        request_sender
+            .send(StorageRequest::UpdateParameters {
+                client: Client {
+                    id: 1337,
+                    response_sender: response_sender.clone(),
+                },
+                id: 42,
+                updates: vec![
+                    (path.clone(), Value::from(1337)),
+                    ("d.e.f".to_string(), Value::from(1337)),
+                ],
+            })
+            .await
+            .unwrap();
+        let response = response_receiver.recv().await.unwrap();
+        assert!(matches!(
+            response,
+            Response::Textual(TextualResponse::Parameters(
+                ParametersResponse::UpdateTransaction {
+                    id: 42,
+                    result: Err(_),
+                }
+            )),
+        ));
+        let parameters = parameters_reader.next();
+        assert_eq!(parameters.existing_fields.get(&path), Some(42).as_ref());
+
+        drop(request_sender);
+        subscriptions_task.await.unwrap();
+    }
 }
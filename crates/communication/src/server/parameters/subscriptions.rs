@@ -200,6 +200,16 @@ async fn handle_request<Parameters>(
                 .await
                 .expect("receiver should always wait for all senders");
         }
+        ParametersRequest::UpdateTransaction { id, updates } => {
+            storage_request_sender
+                .send(StorageRequest::UpdateParameters {
+                    client: request.client,
+                    id,
+                    updates,
+                })
+                .await
+                .expect("receiver should always wait for all senders");
+        }
         ParametersRequest::LoadFromDisk { id } => {
             storage_request_sender
                 .send(StorageRequest::LoadFromDisk {
@@ -0,0 +1,174 @@
+use std::time::{Duration, SystemTime};
+
+use color_eyre::Result;
+use context_attribute::context;
+use framework::{MainOutput, PerceptionInput};
+use hardware::SpeakerInterface;
+use linear_algebra::{distance, point};
+use log::info;
+use serde::{Deserialize, Serialize};
+use types::{
+    acoustic_signals::{AcousticDetection, AcousticEvent, HeardAcousticSignals},
+    audio::SpeakerRequest,
+    cycle_time::CycleTime,
+    field_dimensions::FieldDimensions,
+    messages::IncomingMessage,
+    parameters::AcousticSignalingParameters,
+    primary_state::PrimaryState,
+    roles::Role,
+    world_state::BallState,
+};
+
+#[derive(Deserialize, Serialize)]
+pub struct AcousticSignaling {
+    last_network_message: Option<SystemTime>,
+    last_own_chirp: Option<SystemTime>,
+    consecutive_striker_detections: usize,
+    consecutive_ball_near_own_goal_detections: usize,
+    heard_acoustic_signals: HeardAcousticSignals,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    ball_state: Input<Option<BallState>, "ball_state?">,
+    cycle_time: Input<CycleTime, "cycle_time">,
+    primary_state: Input<PrimaryState, "primary_state">,
+    role: Input<Role, "role">,
+
+    detected_acoustic_signals:
+        PerceptionInput<AcousticDetection, "Audio", "detected_acoustic_signals">,
+    network_message: PerceptionInput<Option<IncomingMessage>, "SplNetwork", "filtered_message?">,
+
+    field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
+    parameters: Parameter<AcousticSignalingParameters, "acoustic_signaling">,
+
+    hardware_interface: HardwareInterface,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub heard_acoustic_signals: MainOutput<HeardAcousticSignals>,
+}
+
+impl AcousticSignaling {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            last_network_message: None,
+            last_own_chirp: None,
+            consecutive_striker_detections: 0,
+            consecutive_ball_near_own_goal_detections: 0,
+            heard_acoustic_signals: HeardAcousticSignals::default(),
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext<impl SpeakerInterface>) -> Result<MainOutputs> {
+        let parameters = context.parameters;
+        let now = context.cycle_time.start_time;
+
+        if context
+            .network_message
+            .persistent
+            .values()
+            .flatten()
+            .any(|message| matches!(message, Some(IncomingMessage::Spl(..))))
+        {
+            self.last_network_message = Some(now);
+        }
+
+        if !parameters.enable {
+            return Ok(MainOutputs {
+                heard_acoustic_signals: self.heard_acoustic_signals.into(),
+            });
+        }
+
+        // the microphones pick up our own chirps as well
+        let is_suppressed = self.last_own_chirp.is_some_and(|last_own_chirp| {
+            elapsed_since(now, last_own_chirp) < parameters.own_chirp_suppression
+        });
+        for detection in context
+            .detected_acoustic_signals
+            .persistent
+            .values()
+            .flatten()
+        {
+            if is_suppressed {
+                self.consecutive_striker_detections = 0;
+                self.consecutive_ball_near_own_goal_detections = 0;
+                continue;
+            }
+            if count_detection(
+                &mut self.consecutive_striker_detections,
+                detection.striker,
+                parameters.minimum_detections,
+            ) {
+                self.heard_acoustic_signals.last_striker = Some(now);
+            }
+            if count_detection(
+                &mut self.consecutive_ball_near_own_goal_detections,
+                detection.ball_near_own_goal,
+                parameters.minimum_detections,
+            ) {
+                self.heard_acoustic_signals.last_ball_near_own_goal = Some(now);
+            }
+        }
+
+        let is_network_silent = self
+            .last_network_message
+            .map_or(true, |last_network_message| {
+                elapsed_since(now, last_network_message) > parameters.network_silence_timeout
+            });
+        let may_chirp = self.last_own_chirp.map_or(true, |last_own_chirp| {
+            elapsed_since(now, last_own_chirp) > parameters.chirp_interval
+        });
+        if is_network_silent && may_chirp && *context.primary_state == PrimaryState::Playing {
+            let own_goal_center = point![-context.field_dimensions.length / 2.0, 0.0];
+            let ball_near_own_goal = context.ball_state.is_some_and(|ball_state| {
+                distance(ball_state.ball_in_field, own_goal_center)
+                    < parameters.ball_near_own_goal_distance
+            });
+            let event = match (*context.role, ball_near_own_goal) {
+                (Role::Striker, _) => Some(AcousticEvent::Striker),
+                (_, true) => Some(AcousticEvent::BallNearOwnGoal),
+                _ => None,
+            };
+            if let Some(event) = event {
+                info!("network is silent, chirping {event:?}");
+                let code = parameters.code(event);
+                context
+                    .hardware_interface
+                    .write_to_speakers(SpeakerRequest::PlayTones {
+                        frequencies: [code.low_frequency, code.high_frequency],
+                        duration: parameters.chirp_duration,
+                    });
+                self.last_own_chirp = Some(now);
+            }
+        }
+
+        Ok(MainOutputs {
+            heard_acoustic_signals: self.heard_acoustic_signals.into(),
+        })
+    }
+}
+
+fn elapsed_since(now: SystemTime, earlier: SystemTime) -> Duration {
+    now.duration_since(earlier).unwrap_or_default()
+}
+
+/// Returns true when a chirp has been heard for exactly `minimum_detections` consecutive buffers.
+fn count_detection(
+    consecutive_detections: &mut usize,
+    is_detected: bool,
+    minimum_detections: usize,
+) -> bool {
+    if is_detected {
+        *consecutive_detections += 1;
+        *consecutive_detections == minimum_detections
+    } else {
+        *consecutive_detections = 0;
+        false
+    }
+}
@@ -0,0 +1,290 @@
+use std::{mem::discriminant, time::SystemTime};
+
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use context_attribute::context;
+use coordinate_systems::Ground;
+use framework::AdditionalOutput;
+use linear_algebra::{distance, Point2};
+use types::{
+    action_latency::{ActionLatency, ActionLatencyReport, LatencyTrigger},
+    ball_position::BallPosition,
+    cycle_time::CycleTime,
+    filtered_game_controller_state::FilteredGameControllerState,
+    filtered_game_state::FilteredGameState,
+    motion_command::MotionCommand,
+    parameters::ActionLatencyParameters,
+    planned_path::PathSegment,
+};
+
+/// Measures how long the behavior takes to react to world state changes, i.e. the time from a
+/// trigger to the first motion command change reacting to it.
+#[derive(Deserialize, Serialize)]
+pub struct ActionLatencyMonitor {
+    report: ActionLatencyReport,
+    pending_triggers: Vec<(LatencyTrigger, SystemTime)>,
+    was_ball_visible: bool,
+    last_game_state: Option<FilteredGameState>,
+    last_motion_command: MotionCommand,
+}
+
+#[context]
+pub struct CreationContext {
+    parameters: Parameter<ActionLatencyParameters, "action_latency">,
+}
+
+#[context]
+pub struct CycleContext {
+    ball_position: Input<Option<BallPosition<Ground>>, "ball_position?">,
+    cycle_time: Input<CycleTime, "cycle_time">,
+    filtered_game_controller_state:
+        Input<Option<FilteredGameControllerState>, "filtered_game_controller_state?">,
+    motion_command: Input<MotionCommand, "motion_command">,
+
+    parameters: Parameter<ActionLatencyParameters, "action_latency">,
+
+    action_latencies: AdditionalOutput<Vec<ActionLatency>, "action_latencies">,
+    action_latency_report: AdditionalOutput<ActionLatencyReport, "action_latency_report">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {}
+
+impl ActionLatencyMonitor {
+    pub fn new(context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            report: ActionLatencyReport::new(
+                context.parameters.bin_width,
+                context.parameters.number_of_bins,
+            ),
+            pending_triggers: Vec::new(),
+            was_ball_visible: false,
+            last_game_state: None,
+            last_motion_command: MotionCommand::default(),
+        })
+    }
+
+    pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
+        let parameters = context.parameters;
+        let now = context.cycle_time.start_time;
+
+        let histogram = &self.report.ball_appeared;
+        if histogram.bin_width != parameters.bin_width
+            || histogram.counts.len() != parameters.number_of_bins
+        {
+            self.report = ActionLatencyReport::new(parameters.bin_width, parameters.number_of_bins);
+        }
+
+        let is_ball_visible = context.ball_position.is_some();
+        if is_ball_visible && !self.was_ball_visible {
+            self.trigger(LatencyTrigger::BallAppeared, now);
+        }
+        self.was_ball_visible = is_ball_visible;
+
+        let game_state = context
+            .filtered_game_controller_state
+            .map(|game_controller_state| game_controller_state.game_state);
+        if self.last_game_state.is_some() && game_state != self.last_game_state {
+            self.trigger(LatencyTrigger::GameStateChanged, now);
+        }
+        self.last_game_state = game_state;
+
+        let mut latencies = Vec::new();
+        let report = &mut self.report;
+        let last_motion_command = &self.last_motion_command;
+        self.pending_triggers.retain(|&(trigger, trigger_time)| {
+            let histogram = report.histogram_mut(trigger);
+            let latency = now.duration_since(trigger_time).unwrap_or_default();
+            if is_reaction_to(
+                trigger,
+                last_motion_command,
+                context.motion_command,
+                parameters.walk_target_change_threshold,
+            ) {
+                histogram.add(latency);
+                latencies.push(ActionLatency { trigger, latency });
+                return false;
+            }
+            let is_unanswered = latency >= histogram.maximum_latency();
+            if is_unanswered {
+                histogram.number_of_unanswered_triggers += 1;
+            }
+            !is_unanswered
+        });
+        self.last_motion_command = context.motion_command.clone();
+
+        context.action_latencies.fill_if_subscribed(|| latencies);
+        context
+            .action_latency_report
+            .fill_if_subscribed(|| self.report.clone());
+
+        Ok(MainOutputs {})
+    }
+
+    /// Only the first of several triggers of the same kind is measured until the behavior reacts.
+    fn trigger(&mut self, trigger: LatencyTrigger, now: SystemTime) {
+        if self
+            .pending_triggers
+            .iter()
+            .all(|(pending_trigger, _)| *pending_trigger != trigger)
+        {
+            self.pending_triggers.push((trigger, now));
+        }
+    }
+}
+
+/// A new ball is answered by any change of the command, e.g. looking at or walking to it. A game
+/// state change is only answered by a different kind of motion, e.g. standing instead of walking,
+/// such that a head movement caused by a ball does not count as the reaction to the game state.
+fn is_reaction_to(
+    trigger: LatencyTrigger,
+    last_motion_command: &MotionCommand,
+    motion_command: &MotionCommand,
+    walk_target_change_threshold: f32,
+) -> bool {
+    match trigger {
+        LatencyTrigger::BallAppeared => has_changed(
+            last_motion_command,
+            motion_command,
+            walk_target_change_threshold,
+        ),
+        LatencyTrigger::GameStateChanged => {
+            discriminant(last_motion_command) != discriminant(motion_command)
+        }
+    }
+}
+
+/// Walk paths are replanned every cycle, only a jump of the walk target counts as a new command.
+fn has_changed(
+    last_motion_command: &MotionCommand,
+    motion_command: &MotionCommand,
+    walk_target_change_threshold: f32,
+) -> bool {
+    let head_motion_changed = last_motion_command
+        .head_motion()
+        .map(|head| discriminant(&head))
+        != motion_command.head_motion().map(|head| discriminant(&head));
+    if discriminant(last_motion_command) != discriminant(motion_command) || head_motion_changed {
+        return true;
+    }
+    match (last_motion_command, motion_command) {
+        (
+            MotionCommand::Walk {
+                path: last_path, ..
+            },
+            MotionCommand::Walk { path, .. },
+        ) => match (walk_target(last_path), walk_target(path)) {
+            (Some(last_target), Some(target)) => {
+                distance(last_target, target) > walk_target_change_threshold
+            }
+            (None, None) => false,
+            _ => true,
+        },
+        (
+            MotionCommand::InWalkKick {
+                kick: last_kick,
+                kicking_side: last_kicking_side,
+                ..
+            },
+            MotionCommand::InWalkKick {
+                kick, kicking_side, ..
+            },
+        ) => last_kick != kick || last_kicking_side != kicking_side,
+        _ => false,
+    }
+}
+
+fn walk_target(path: &[PathSegment]) -> Option<Point2<Ground>> {
+    match path.last()? {
+        PathSegment::LineSegment(line_segment) => Some(line_segment.1),
+        PathSegment::Arc(arc, _) => Some(arc.end),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geometry::line_segment::LineSegment;
+    use linear_algebra::point;
+    use types::motion_command::{ArmMotion, HeadMotion, OrientationMode, WalkSpeed};
+
+    use super::*;
+
+    fn walk_to(target: Point2<Ground>, head: HeadMotion) -> MotionCommand {
+        MotionCommand::Walk {
+            head,
+            path: vec![PathSegment::LineSegment(LineSegment(
+                Point2::origin(),
+                target,
+            ))],
+            left_arm: ArmMotion::Swing,
+            right_arm: ArmMotion::Swing,
+            orientation_mode: OrientationMode::AlignWithPath,
+            speed: WalkSpeed::Normal,
+        }
+    }
+
+    #[test]
+    fn replanned_path_to_the_same_target_is_no_change() {
+        let last = walk_to(point![2.0, 1.0], HeadMotion::LookAround);
+        let current = walk_to(point![2.05, 1.0], HeadMotion::LookAround);
+
+        assert!(!has_changed(&last, &current, 0.1));
+    }
+
+    #[test]
+    fn jumping_walk_target_is_a_change() {
+        let last = walk_to(point![2.0, 1.0], HeadMotion::LookAround);
+        let current = walk_to(point![2.0, -1.0], HeadMotion::LookAround);
+
+        assert!(has_changed(&last, &current, 0.1));
+    }
+
+    #[test]
+    fn different_head_motion_is_a_change() {
+        let last = walk_to(point![2.0, 1.0], HeadMotion::LookAround);
+        let current = walk_to(
+            point![2.0, 1.0],
+            HeadMotion::LookAt {
+                target: point![1.0, 0.0],
+                camera: None,
+            },
+        );
+
+        assert!(has_changed(&last, &current, 0.1));
+        assert!(is_reaction_to(
+            LatencyTrigger::BallAppeared,
+            &last,
+            &current,
+            0.1
+        ));
+        assert!(!is_reaction_to(
+            LatencyTrigger::GameStateChanged,
+            &last,
+            &current,
+            0.1
+        ));
+    }
+
+    #[test]
+    fn different_kind_of_motion_answers_every_trigger() {
+        let last = MotionCommand::Stand {
+            head: HeadMotion::Center,
+        };
+        let current = walk_to(point![2.0, 1.0], HeadMotion::Center);
+
+        assert!(is_reaction_to(
+            LatencyTrigger::BallAppeared,
+            &last,
+            &current,
+            0.1
+        ));
+        assert!(is_reaction_to(
+            LatencyTrigger::GameStateChanged,
+            &last,
+            &current,
+            0.1
+        ));
+    }
+}
@@ -1,4 +1,4 @@
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 use color_eyre::Result;
 use ordered_float::NotNan;
@@ -6,14 +6,14 @@ use serde::{Deserialize, Serialize};
 
 use context_attribute::context;
 use coordinate_systems::{Field, Ground};
-use framework::MainOutput;
+use framework::{AdditionalOutput, MainOutput};
 use linear_algebra::{point, Isometry2, Point2, Vector2};
 use types::{
     cycle_time::CycleTime,
     field_dimensions::FieldDimensions,
     obstacles::{Obstacle, ObstacleKind},
     parameters::LookActionParameters,
-    point_of_interest::PointOfInterest,
+    point_of_interest::{LookReason, LookTarget, PointOfInterest},
     world_state::BallState,
 };
 
@@ -21,7 +21,16 @@ use types::{
 pub struct ActiveVision {
     field_mark_positions: Vec<Point2<Field>>,
     last_point_of_interest_switch: Option<SystemTime>,
-    current_point_of_interest: PointOfInterest,
+    current_look_target: LookTarget,
+    last_looked_at: LastLookedAt,
+}
+
+/// When each kind of point of interest was last looked at
+#[derive(Default, Deserialize, Serialize)]
+struct LastLookedAt {
+    ball: Option<SystemTime>,
+    field_mark: Option<SystemTime>,
+    obstacle: Option<SystemTime>,
 }
 
 #[context]
@@ -37,6 +46,9 @@ pub struct CycleContext {
     obstacles: Input<Vec<Obstacle>, "obstacles">,
     parameters: Parameter<LookActionParameters, "behavior.look_action">,
     ground_to_field: Input<Option<Isometry2<Ground, Field>>, "ground_to_field?">,
+    localization_uncertainty: Input<Option<f32>, "localization_uncertainty?">,
+
+    look_target: AdditionalOutput<LookTarget, "look_target">,
 }
 
 #[context]
@@ -50,31 +62,47 @@ impl ActiveVision {
         Ok(Self {
             field_mark_positions: generate_field_mark_positions(context.field_dimensions),
             last_point_of_interest_switch: None,
-            current_point_of_interest: PointOfInterest::default(),
+            current_look_target: LookTarget::default(),
+            last_looked_at: LastLookedAt::default(),
         })
     }
 
-    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+    pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
         let cycle_start_time = context.cycle_time.start_time;
 
+        match self.current_look_target.point_of_interest {
+            PointOfInterest::Forward => {}
+            PointOfInterest::FieldMark { .. } => {
+                self.last_looked_at.field_mark = Some(cycle_start_time)
+            }
+            PointOfInterest::Ball => self.last_looked_at.ball = Some(cycle_start_time),
+            PointOfInterest::Obstacle { .. } => {
+                self.last_looked_at.obstacle = Some(cycle_start_time)
+            }
+        }
+
         if let Some(&ground_to_field) = context.ground_to_field {
             if self.last_point_of_interest_switch.is_none()
                 || cycle_start_time.duration_since(self.last_point_of_interest_switch.unwrap())?
                     > context.parameters.position_of_interest_switch_interval
             {
-                self.current_point_of_interest = next_point_of_interest(
-                    self.current_point_of_interest,
-                    &self.field_mark_positions,
+                let look_target = self.most_informative_look_target(
                     context.obstacles,
                     *context.parameters,
                     ground_to_field,
                     context.rule_ball.or(context.ball),
+                    context.localization_uncertainty.copied(),
+                    cycle_start_time,
                 );
+                self.current_look_target = look_target;
 
                 self.last_point_of_interest_switch = Some(cycle_start_time);
             }
+            context
+                .look_target
+                .fill_if_subscribed(|| self.current_look_target);
 
-            let position_of_interest = match self.current_point_of_interest {
+            let position_of_interest = match self.current_look_target.point_of_interest {
                 PointOfInterest::Forward => context.parameters.look_forward_position,
                 PointOfInterest::FieldMark { absolute_position } => {
                     ground_to_field.inverse() * absolute_position
@@ -95,11 +123,91 @@ impl ActiveVision {
                 position_of_interest: position_of_interest.into(),
             })
         } else {
+            context
+                .look_target
+                .fill_if_subscribed(|| self.current_look_target);
             Ok(MainOutputs {
                 position_of_interest: context.parameters.look_forward_position.into(),
             })
         }
     }
+
+    /// Scores every candidate by how much looking at it is expected to tell: the longer a kind
+    /// of target has not been looked at and the more uncertain the corresponding estimate is,
+    /// the higher the gain. Looking forward is the baseline every other target has to beat.
+    fn most_informative_look_target(
+        &self,
+        obstacles: &[Obstacle],
+        parameters: LookActionParameters,
+        ground_to_field: Isometry2<Ground, Field>,
+        ball: Option<&BallState>,
+        localization_uncertainty: Option<f32>,
+        now: SystemTime,
+    ) -> LookTarget {
+        let staleness = |last_looked_at: Option<SystemTime>| {
+            saturated_ratio(
+                last_looked_at.map(|time| now.duration_since(time).unwrap_or_default()),
+                parameters.staleness_saturation,
+            )
+        };
+        let forward = LookTarget {
+            point_of_interest: PointOfInterest::Forward,
+            reason: LookReason::NothingToObserve,
+            information_gain: parameters.forward_information_gain,
+        };
+
+        let ball_target = ball.map(|ball| {
+            let ball_age = saturated_ratio(
+                Some(now.duration_since(ball.last_seen_ball).unwrap_or_default()),
+                parameters.ball_age_saturation,
+            );
+            scored_target(
+                PointOfInterest::Ball,
+                parameters.ball_weight,
+                staleness(self.last_looked_at.ball),
+                ball_age,
+                LookReason::BallAge,
+            )
+        });
+        let field_mark_target =
+            closest_field_mark_visible(&self.field_mark_positions, parameters, ground_to_field)
+                .map(|position| {
+                    let uncertainty = localization_uncertainty.map_or(1.0, |uncertainty| {
+                        (uncertainty / parameters.localization_uncertainty_saturation).min(1.0)
+                    });
+                    scored_target(
+                        PointOfInterest::FieldMark {
+                            absolute_position: ground_to_field * position,
+                        },
+                        parameters.localization_weight,
+                        staleness(self.last_looked_at.field_mark),
+                        uncertainty,
+                        LookReason::LocalizationUncertainty,
+                    )
+                });
+        let obstacle_target =
+            closest_interesting_obstacle_visible(obstacles, parameters).map(|position| {
+                LookTarget {
+                    point_of_interest: PointOfInterest::Obstacle {
+                        absolute_position: ground_to_field * position,
+                    },
+                    reason: LookReason::Staleness,
+                    information_gain: parameters.obstacle_weight
+                        * staleness(self.last_looked_at.obstacle),
+                }
+            });
+
+        [ball_target, field_mark_target, obstacle_target]
+            .into_iter()
+            .flatten()
+            .fold(forward, |best, candidate| {
+                if candidate.information_gain > best.information_gain {
+                    candidate
+                } else {
+                    best
+                }
+            })
+    }
 }
 
 fn is_position_visible(position: Point2<Ground>, parameters: LookActionParameters) -> bool {
@@ -164,61 +272,31 @@ fn generate_field_mark_positions(field_dimensions: &FieldDimensions) -> Vec<Poin
     ]
 }
 
-fn next_point_of_interest(
-    current_point_of_interest: PointOfInterest,
-    field_mark_positions: &[Point2<Field>],
-    obstacles: &[Obstacle],
-    parameters: LookActionParameters,
-    ground_to_field: Isometry2<Ground, Field>,
-    ball: Option<&BallState>,
-) -> PointOfInterest {
-    match current_point_of_interest {
-        PointOfInterest::Forward => {
-            let field_mark_of_interest =
-                closest_field_mark_visible(field_mark_positions, parameters, ground_to_field);
-
-            match (field_mark_of_interest, ball) {
-                (Some(field_mark_position), _) => PointOfInterest::FieldMark {
-                    absolute_position: ground_to_field * field_mark_position,
-                },
-                (_, Some(_)) => PointOfInterest::Ball,
-                (None, None) => {
-                    let closest_interesting_obstacle_position =
-                        closest_interesting_obstacle_visible(obstacles, parameters);
-                    match closest_interesting_obstacle_position {
-                        Some(interesting_obstacle_position) => PointOfInterest::Obstacle {
-                            absolute_position: ground_to_field * interesting_obstacle_position,
-                        },
-                        None => PointOfInterest::Forward,
-                    }
-                }
-            }
-        }
-        PointOfInterest::FieldMark { .. } => match ball {
-            Some(_) => PointOfInterest::Ball,
-            None => {
-                let closest_interesting_obstacle_position =
-                    closest_interesting_obstacle_visible(obstacles, parameters);
-
-                match closest_interesting_obstacle_position {
-                    Some(interesting_obstacle_position) => PointOfInterest::Obstacle {
-                        absolute_position: ground_to_field * interesting_obstacle_position,
-                    },
-                    None => PointOfInterest::Forward,
-                }
-            }
-        },
-        PointOfInterest::Ball => {
-            let closest_interesting_obstacle_position =
-                closest_interesting_obstacle_visible(obstacles, parameters);
-
-            match closest_interesting_obstacle_position {
-                Some(interesting_obstacle_position) => PointOfInterest::Obstacle {
-                    absolute_position: ground_to_field * interesting_obstacle_position,
-                },
-                None => PointOfInterest::Forward,
-            }
+fn scored_target(
+    point_of_interest: PointOfInterest,
+    weight: f32,
+    staleness: f32,
+    uncertainty: f32,
+    uncertainty_reason: LookReason,
+) -> LookTarget {
+    let reason = if uncertainty > staleness {
+        uncertainty_reason
+    } else {
+        LookReason::Staleness
+    };
+    LookTarget {
+        point_of_interest,
+        reason,
+        information_gain: weight * staleness.max(uncertainty),
+    }
+}
+
+/// Maps a duration to `[0, 1]`, reaching 1 at the saturation. Unknown durations are saturated.
+fn saturated_ratio(duration: Option<Duration>, saturation: Duration) -> f32 {
+    match duration {
+        Some(duration) if !saturation.is_zero() => {
+            (duration.as_secs_f32() / saturation.as_secs_f32()).min(1.0)
         }
-        PointOfInterest::Obstacle { .. } => PointOfInterest::Forward,
+        _ => 1.0,
     }
 }
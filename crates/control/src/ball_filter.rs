@@ -7,19 +7,20 @@ use serde::{Deserialize, Serialize};
 use context_attribute::context;
 use coordinate_systems::{Ground, Pixel};
 use filtering::kalman_filter::KalmanFilter;
-use framework::{AdditionalOutput, HistoricInput, MainOutput, PerceptionInput};
+use framework::{AdditionalOutput, HistoricInput, MainOutput, PerceptionInput, PersistentState};
 use geometry::circle::Circle;
 use linear_algebra::Point2;
 use projection::{camera_matrices::CameraMatrices, camera_matrix::CameraMatrix, Projection};
 use types::{
     ball::Ball,
-    ball_filter::Hypothesis,
+    ball_filter::{Hypothesis, MeasurementCovariance},
     ball_position::BallPosition,
+    camera_position::CameraPosition,
     cycle_time::CycleTime,
     field_dimensions::FieldDimensions,
     limb::{is_above_limbs, Limb, ProjectedLimbs},
     multivariate_normal_distribution::MultivariateNormalDistribution,
-    parameters::BallFilterParameters,
+    parameters::{BallFilterParameters, BallMeasurementNoiseParameters},
 };
 
 #[derive(Deserialize, Serialize)]
@@ -40,6 +41,8 @@ pub struct CycleContext {
         AdditionalOutput<Vec<Circle<Pixel>>, "filtered_balls_in_image_bottom">,
     filtered_balls_in_image_top:
         AdditionalOutput<Vec<Circle<Pixel>>, "filtered_balls_in_image_top">,
+    measurement_covariances:
+        AdditionalOutput<Vec<MeasurementCovariance>, "ball_measurement_covariances">,
 
     current_odometry_to_last_odometry:
         HistoricInput<Option<nalgebra::Isometry2<f32>>, "current_odometry_to_last_odometry?">,
@@ -71,18 +74,15 @@ impl BallFilter {
 
     fn persistent_balls_in_control_cycle<'a>(
         context: &'a CycleContext,
-    ) -> Vec<(&'a SystemTime, Vec<&'a Ball>)> {
+    ) -> Vec<(&'a SystemTime, Vec<(&'a Ball, CameraPosition)>)> {
         context
             .balls_top
             .persistent
             .iter()
             .zip(context.balls_bottom.persistent.values())
             .map(|((detection_time, balls_top), balls_bottom)| {
-                let balls = balls_top
-                    .iter()
-                    .chain(balls_bottom.iter())
-                    .filter_map(|data| data.as_ref())
-                    .flat_map(|data| data.iter())
+                let balls = balls_from_camera(balls_top, CameraPosition::Top)
+                    .chain(balls_from_camera(balls_bottom, CameraPosition::Bottom))
                     .collect();
                 (detection_time, balls)
             })
@@ -91,9 +91,10 @@ impl BallFilter {
 
     fn advance_all_hypotheses(
         &mut self,
-        measurements: Vec<(&SystemTime, Vec<&Ball>)>,
+        measurements: Vec<(&SystemTime, Vec<(&Ball, CameraPosition)>)>,
         context: &CycleContext,
-    ) {
+    ) -> Vec<MeasurementCovariance> {
+        let mut measurement_covariances = Vec::new();
         for (detection_time, balls) in measurements {
             let current_odometry_to_last_odometry = context
                 .current_odometry_to_last_odometry
@@ -119,12 +120,19 @@ impl BallFilter {
                 context.ball_filter_configuration,
             );
 
-            for ball in balls {
+            for (ball, camera) in balls {
+                let measurement_covariance = measurement_covariance(
+                    ball.position,
+                    camera,
+                    &context.ball_filter_configuration.measurement_noise,
+                );
                 self.update_hypotheses_with_measurement(
                     ball.position,
+                    &measurement_covariance,
                     *detection_time,
                     context.ball_filter_configuration,
                 );
+                measurement_covariances.push(measurement_covariance);
             }
         }
 
@@ -133,11 +141,15 @@ impl BallFilter {
             context.ball_filter_configuration,
             context.field_dimensions,
         );
+        measurement_covariances
     }
 
     pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
         let persistent_updates = Self::persistent_balls_in_control_cycle(&context);
-        self.advance_all_hypotheses(persistent_updates, &context);
+        let measurement_covariances = self.advance_all_hypotheses(persistent_updates, &context);
+        context
+            .measurement_covariances
+            .fill_if_subscribed(|| measurement_covariances);
 
         context
             .ball_filter_hypotheses
@@ -263,20 +275,19 @@ impl BallFilter {
     fn update_hypothesis_with_measurement(
         hypothesis: &mut Hypothesis,
         detected_position: Point2<Ground>,
+        measurement_covariance: &MeasurementCovariance,
         detection_time: SystemTime,
         configuration: &BallFilterParameters,
     ) {
         hypothesis.moving_state.update(
             Matrix2x4::identity(),
             detected_position.inner.coords,
-            Matrix2::from_diagonal(&configuration.measurement_noise_moving)
-                * detected_position.coords().norm_squared(),
+            measurement_covariance.moving,
         );
         hypothesis.resting_state.update(
             Matrix2x4::identity(),
             detected_position.inner.coords,
-            Matrix2::from_diagonal(&configuration.measurement_noise_resting)
-                * detected_position.coords().norm_squared(),
+            measurement_covariance.resting,
         );
 
         if !hypothesis.is_resting(configuration) {
@@ -289,6 +300,7 @@ impl BallFilter {
     fn update_hypotheses_with_measurement(
         &mut self,
         detected_position: Point2<Ground>,
+        measurement_covariance: &MeasurementCovariance,
         detection_time: SystemTime,
         configuration: &BallFilterParameters,
     ) {
@@ -311,6 +323,7 @@ impl BallFilter {
             Self::update_hypothesis_with_measurement(
                 hypothesis,
                 detected_position,
+                measurement_covariance,
                 detection_time,
                 configuration,
             )
@@ -400,6 +413,44 @@ impl BallFilter {
     }
 }
 
+impl PersistentState for BallFilter {
+    type State = Vec<Hypothesis>;
+
+    fn persistent_state(&self) -> Self::State {
+        self.hypotheses.clone()
+    }
+
+    fn restore_persistent_state(&mut self, hypotheses: Self::State) {
+        self.hypotheses = hypotheses;
+    }
+}
+
+fn balls_from_camera<'a>(
+    balls: &'a [Option<&'a Vec<Ball>>],
+    camera: CameraPosition,
+) -> impl Iterator<Item = (&'a Ball, CameraPosition)> {
+    balls
+        .iter()
+        .flatten()
+        .flat_map(|balls| balls.iter())
+        .map(move |ball| (ball, camera))
+}
+
+fn measurement_covariance(
+    detected_position: Point2<Ground>,
+    camera: CameraPosition,
+    noise: &BallMeasurementNoiseParameters,
+) -> MeasurementCovariance {
+    let distance = detected_position.coords().norm();
+    let noise = noise.for_measurement(camera, distance);
+    MeasurementCovariance {
+        camera,
+        distance,
+        moving: Matrix2::from_diagonal(&noise.moving) * distance.powi(2),
+        resting: Matrix2::from_diagonal(&noise.resting) * distance.powi(2),
+    }
+}
+
 fn project_to_image(
     ball_position: &[BallPosition<Ground>],
     camera_matrix: &CameraMatrix,
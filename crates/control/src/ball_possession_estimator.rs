@@ -0,0 +1,212 @@
+use std::time::{Duration, SystemTime};
+
+use color_eyre::Result;
+use context_attribute::context;
+use coordinate_systems::{Field, Ground};
+use framework::MainOutput;
+use linear_algebra::{distance, Isometry2, Point2};
+use serde::{Deserialize, Serialize};
+use spl_network_messages::Team;
+use types::{
+    ball_possession::BallPossession,
+    cycle_time::CycleTime,
+    filtered_game_controller_state::FilteredGameControllerState,
+    filtered_game_state::FilteredGameState,
+    obstacles::{opponents, Obstacle},
+    parameters::BallPossessionParameters,
+    world_state::BallState,
+};
+
+/// Estimates which team controls the ball from the distances of the own team and the opponent
+/// robot obstacles to it, and how much of the game the own team had the ball.
+#[derive(Deserialize, Serialize)]
+pub struct BallPossessionEstimator {
+    team: Team,
+    since: SystemTime,
+    candidate: Option<(Team, SystemTime)>,
+    own_duration: Duration,
+    opponent_duration: Duration,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    ball: Input<Option<BallState>, "ball_state?">,
+    cycle_time: Input<CycleTime, "cycle_time">,
+    filtered_game_controller_state:
+        Input<Option<FilteredGameControllerState>, "filtered_game_controller_state?">,
+    ground_to_field: Input<Option<Isometry2<Ground, Field>>, "ground_to_field?">,
+    obstacles: Input<Vec<Obstacle>, "obstacles">,
+    teammate_positions: Input<Vec<Point2<Field>>, "teammate_positions">,
+
+    parameters: Parameter<BallPossessionParameters, "ball_possession">,
+    teammate_matching_distance: Parameter<f32, "obstacle_filter.teammate_matching_distance">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub ball_possession: MainOutput<BallPossession>,
+}
+
+impl BallPossessionEstimator {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            team: Team::Uncertain,
+            since: SystemTime::UNIX_EPOCH,
+            candidate: None,
+            own_duration: Duration::ZERO,
+            opponent_duration: Duration::ZERO,
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        let now = context.cycle_time.start_time;
+        let game_state = context
+            .filtered_game_controller_state
+            .map(|game_controller_state| game_controller_state.game_state);
+
+        let estimate = match (context.ball, context.ground_to_field) {
+            (Some(ball), Some(ground_to_field)) => {
+                let own_team = context
+                    .teammate_positions
+                    .iter()
+                    .copied()
+                    .chain([ground_to_field.as_pose().position()]);
+                let opponents = opponents(
+                    context.obstacles,
+                    context.teammate_positions,
+                    *ground_to_field,
+                    *context.teammate_matching_distance,
+                )
+                .map(|opponent| *ground_to_field * opponent.position);
+                estimate_possession(ball.ball_in_field, own_team, opponents, context.parameters)
+            }
+            _ => Team::Uncertain,
+        };
+        self.update(estimate, now, context.parameters.switch_duration);
+
+        match game_state {
+            Some(FilteredGameState::Initial) => {
+                self.own_duration = Duration::ZERO;
+                self.opponent_duration = Duration::ZERO;
+            }
+            Some(FilteredGameState::Playing { .. }) => match self.team {
+                Team::Hulks => self.own_duration += context.cycle_time.last_cycle_duration,
+                Team::Opponent => self.opponent_duration += context.cycle_time.last_cycle_duration,
+                Team::Uncertain => {}
+            },
+            _ => {}
+        }
+        let controlled_duration = self.own_duration + self.opponent_duration;
+        let own_share = (!controlled_duration.is_zero())
+            .then(|| self.own_duration.as_secs_f32() / controlled_duration.as_secs_f32());
+
+        Ok(MainOutputs {
+            ball_possession: BallPossession {
+                team: self.team,
+                duration: now.duration_since(self.since).unwrap_or_default(),
+                own_share,
+            }
+            .into(),
+        })
+    }
+
+    /// The team only switches after the estimate persisted for the switch duration
+    fn update(&mut self, estimate: Team, now: SystemTime, switch_duration: Duration) {
+        if estimate == self.team {
+            self.candidate = None;
+            return;
+        }
+        match self.candidate {
+            Some((candidate, candidate_since)) if candidate == estimate => {
+                if now
+                    .duration_since(candidate_since)
+                    .is_ok_and(|duration| duration >= switch_duration)
+                {
+                    self.team = estimate;
+                    self.since = candidate_since;
+                    self.candidate = None;
+                }
+            }
+            _ => self.candidate = Some((estimate, now)),
+        }
+    }
+}
+
+/// A team controls the ball if its closest robot is near the ball and clearly closer than the
+/// closest robot of the other team
+fn estimate_possession(
+    ball: Point2<Field>,
+    own_team: impl IntoIterator<Item = Point2<Field>>,
+    opponents: impl IntoIterator<Item = Point2<Field>>,
+    parameters: &BallPossessionParameters,
+) -> Team {
+    let closest = |robots: &mut dyn Iterator<Item = Point2<Field>>| {
+        robots
+            .map(|robot| distance(robot, ball))
+            .fold(f32::INFINITY, f32::min)
+    };
+    let own_distance = closest(&mut own_team.into_iter());
+    let opponent_distance = closest(&mut opponents.into_iter());
+    if own_distance.min(opponent_distance) > parameters.possession_distance {
+        Team::Uncertain
+    } else if own_distance + parameters.contest_margin < opponent_distance {
+        Team::Hulks
+    } else if opponent_distance + parameters.contest_margin < own_distance {
+        Team::Opponent
+    } else {
+        Team::Uncertain
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use linear_algebra::point;
+
+    use super::*;
+
+    fn parameters() -> BallPossessionParameters {
+        BallPossessionParameters {
+            possession_distance: 0.5,
+            contest_margin: 0.2,
+            switch_duration: Duration::from_secs(1),
+        }
+    }
+
+    #[test]
+    fn possession_needs_a_close_and_clearly_closer_robot() {
+        let ball = point![0.0, 0.0];
+        let parameters = parameters();
+
+        let team = estimate_possession(ball, [point![0.2, 0.0]], [point![1.0, 0.0]], &parameters);
+        assert_eq!(team, Team::Hulks);
+        let team = estimate_possession(ball, [point![1.0, 0.0]], [point![0.0, 0.3]], &parameters);
+        assert_eq!(team, Team::Opponent);
+        let team = estimate_possession(ball, [point![0.2, 0.0]], [point![0.0, 0.3]], &parameters);
+        assert_eq!(team, Team::Uncertain);
+        let team = estimate_possession(ball, [point![2.0, 0.0]], [], &parameters);
+        assert_eq!(team, Team::Uncertain);
+    }
+
+    #[test]
+    fn team_switches_only_after_the_switch_duration() {
+        let mut estimator = BallPossessionEstimator::new(CreationContext {}).unwrap();
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+        let switch_duration = Duration::from_secs(1);
+
+        estimator.update(Team::Opponent, start, switch_duration);
+        assert_eq!(estimator.team, Team::Uncertain);
+        estimator.update(
+            Team::Opponent,
+            start + Duration::from_millis(500),
+            switch_duration,
+        );
+        assert_eq!(estimator.team, Team::Uncertain);
+        estimator.update(Team::Opponent, start + switch_duration, switch_duration);
+        assert_eq!(estimator.team, Team::Opponent);
+        assert_eq!(estimator.since, start);
+    }
+}
@@ -0,0 +1,332 @@
+use std::time::SystemTime;
+
+use color_eyre::Result;
+use context_attribute::context;
+use coordinate_systems::{Field, Ground};
+use framework::{AdditionalOutput, MainOutput, PerceptionInput};
+use linear_algebra::{distance, point, Isometry2, Point2};
+use serde::{Deserialize, Serialize};
+use spl_network_messages::PlayerNumber;
+use types::{
+    acoustic_signals::HeardAcousticSignals,
+    ball_position::BallPosition,
+    ball_search::{BallSearchHeatmap, SearchSectorClaim},
+    cycle_time::CycleTime,
+    field_coverage::FieldCoverage,
+    field_dimensions::FieldDimensions,
+    field_grid::FieldGrid,
+    messages::IncomingMessage,
+    parameters::{BallSearchParameters, SearchParameters},
+    primary_state::PrimaryState,
+    roles::Role,
+};
+
+#[derive(Deserialize, Serialize)]
+pub struct BallSearch {
+    heatmap: BallSearchHeatmap,
+    teammate_claims: Vec<SearchSectorClaim>,
+    own_claim: Option<usize>,
+    /// Field coverage cell searched within the own sector
+    search_cell: Option<usize>,
+    last_ball_near_own_goal_chirp: Option<SystemTime>,
+}
+
+#[context]
+pub struct CreationContext {
+    parameters: Parameter<BallSearchParameters, "ball_search">,
+}
+
+#[context]
+pub struct CycleContext {
+    ball_position: Input<Option<BallPosition<Ground>>, "ball_position?">,
+    team_ball: Input<Option<BallPosition<Field>>, "team_ball?">,
+    ground_to_field: Input<Option<Isometry2<Ground, Field>>, "ground_to_field?">,
+    cycle_time: Input<CycleTime, "cycle_time">,
+    field_coverage: Input<FieldCoverage, "field_coverage">,
+    primary_state: Input<PrimaryState, "primary_state">,
+    role: Input<Role, "role">,
+    heard_acoustic_signals: Input<HeardAcousticSignals, "heard_acoustic_signals">,
+    network_message: PerceptionInput<Option<IncomingMessage>, "SplNetwork", "filtered_message?">,
+
+    field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
+    parameters: Parameter<BallSearchParameters, "ball_search">,
+    search_parameters: Parameter<SearchParameters, "behavior.search">,
+    player_number: Parameter<PlayerNumber, "player_number">,
+
+    search_sector: CyclerState<Option<u8>, "search_sector">,
+
+    search_sector_claims: AdditionalOutput<Vec<SearchSectorClaim>, "search_sector_claims">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub ball_search_heatmap: MainOutput<BallSearchHeatmap>,
+    pub search_sector_position: MainOutput<Option<Point2<Field>>>,
+}
+
+impl BallSearch {
+    pub fn new(context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            heatmap: BallSearchHeatmap::new(FieldGrid::new(
+                context.parameters.number_of_columns,
+                context.parameters.number_of_rows,
+            )),
+            teammate_claims: Vec::new(),
+            own_claim: None,
+            search_cell: None,
+            last_ball_near_own_goal_chirp: None,
+        })
+    }
+
+    pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
+        let now = context.cycle_time.start_time;
+        let parameters = context.parameters;
+        let field_dimensions = context.field_dimensions;
+
+        for message in context
+            .network_message
+            .persistent
+            .values()
+            .flatten()
+            .filter_map(|message| match message {
+                Some(IncomingMessage::Spl(message)) => Some(message),
+                _ => None,
+            })
+            .filter(|message| message.player_number != *context.player_number)
+        {
+            self.teammate_claims
+                .retain(|claim| claim.player_number != message.player_number);
+            if let Some(sector) = message.search_sector {
+                self.teammate_claims.push(SearchSectorClaim {
+                    player_number: message.player_number,
+                    sector: sector as usize,
+                    searcher_position: message.pose.position(),
+                    last_update: now,
+                });
+            }
+        }
+        self.teammate_claims.retain(|claim| {
+            now.duration_since(claim.last_update).unwrap_or_default() < parameters.claim_timeout
+        });
+
+        let own_ball = context
+            .ball_position
+            .zip(context.ground_to_field)
+            .map(|(ball, ground_to_field)| *ground_to_field * ball.position);
+        let known_ball = own_ball.or(context.team_ball.map(|ball| ball.position));
+
+        match known_ball {
+            Some(ball_position) => {
+                self.heatmap
+                    .reset_around(ball_position, parameters.ball_spread, field_dimensions);
+            }
+            None if context.heard_acoustic_signals.last_ball_near_own_goal
+                != self.last_ball_near_own_goal_chirp =>
+            {
+                // a teammate reported the ball near our goal via chirps
+                self.last_ball_near_own_goal_chirp =
+                    context.heard_acoustic_signals.last_ball_near_own_goal;
+                self.heatmap.reset_around(
+                    point![-field_dimensions.length / 2.0, 0.0],
+                    parameters.ball_spread,
+                    field_dimensions,
+                );
+            }
+            None => {
+                let cycle_duration = context.cycle_time.last_cycle_duration.as_secs_f32();
+                self.heatmap
+                    .diffuse(cycle_duration / parameters.uniform_convergence_time.as_secs_f32());
+                self.heatmap.observe(
+                    context.field_coverage,
+                    now,
+                    parameters.observation_timeout,
+                    parameters.observation_decay_rate * cycle_duration,
+                    field_dimensions,
+                );
+                self.heatmap.normalize();
+            }
+        }
+
+        let is_searching = known_ball.is_none()
+            && *context.primary_state == PrimaryState::Playing
+            && *context.role == Role::Searcher;
+        self.own_claim = match (is_searching, context.ground_to_field) {
+            (true, Some(ground_to_field)) => self.select_sector(
+                ground_to_field.as_pose().position(),
+                *context.player_number,
+                parameters,
+                field_dimensions,
+            ),
+            _ => None,
+        };
+        *context.search_sector = self.own_claim.map(|sector| sector as u8);
+        let search_sector_position = match (self.own_claim, context.ground_to_field) {
+            (Some(sector), Some(ground_to_field)) => Some(self.position_in_sector(
+                sector,
+                ground_to_field.as_pose().position(),
+                context.field_coverage,
+                now,
+                context.search_parameters,
+                field_dimensions,
+            )),
+            _ => {
+                self.search_cell = None;
+                None
+            }
+        };
+
+        context
+            .search_sector_claims
+            .fill_if_subscribed(|| self.teammate_claims.clone());
+
+        Ok(MainOutputs {
+            search_sector_position: search_sector_position.into(),
+            ball_search_heatmap: self.heatmap.clone().into(),
+        })
+    }
+
+    /// Searchers sweep the parts of their sector nobody looked at for the longest time.
+    fn position_in_sector(
+        &mut self,
+        sector: usize,
+        own_position: Point2<Field>,
+        coverage: &FieldCoverage,
+        now: SystemTime,
+        search_parameters: &SearchParameters,
+        field_dimensions: &FieldDimensions,
+    ) -> Point2<Field> {
+        let cells_in_sector: Vec<_> = (0..coverage.number_of_cells())
+            .filter(|cell| {
+                self.heatmap.grid.cell_containing(
+                    coverage.cell_center(*cell, field_dimensions),
+                    field_dimensions,
+                ) == sector
+            })
+            .collect();
+        self.search_cell = coverage.stalest_cell_among(
+            cells_in_sector,
+            own_position,
+            search_parameters.coverage_distance_weight,
+            self.search_cell,
+            search_parameters.coverage_cell_hysteresis,
+            now,
+            search_parameters.maximum_coverage_age,
+            field_dimensions,
+        );
+        match self.search_cell {
+            Some(cell) => coverage.cell_center(cell, field_dimensions),
+            None => self.heatmap.sector_center(sector, field_dimensions),
+        }
+    }
+
+    fn select_sector(
+        &self,
+        own_position: Point2<Field>,
+        own_player_number: PlayerNumber,
+        parameters: &BallSearchParameters,
+        field_dimensions: &FieldDimensions,
+    ) -> Option<usize> {
+        let is_available = |sector: usize| {
+            self.teammate_claims.iter().all(|claim| {
+                claim.sector != sector
+                    // on conflicting claims, the lower player number keeps the sector
+                    || (self.own_claim == Some(sector) && own_player_number < claim.player_number)
+            })
+        };
+        let score = |sector: usize| {
+            self.heatmap.likelihoods[sector]
+                - parameters.distance_weight
+                    * distance(
+                        own_position,
+                        self.heatmap.sector_center(sector, field_dimensions),
+                    )
+        };
+
+        let is_in_own_region = |sector: usize| {
+            self.heatmap.is_in_own_region(
+                sector,
+                own_position,
+                self.teammate_claims
+                    .iter()
+                    .map(|claim| claim.searcher_position),
+                field_dimensions,
+            )
+        };
+        let best_sector_where = |is_candidate: &dyn Fn(usize) -> bool| {
+            (0..self.heatmap.number_of_sectors())
+                .filter(|sector| is_available(*sector) && is_candidate(*sector))
+                .map(|sector| (sector, score(sector)))
+                .max_by(|(_, left), (_, right)| left.total_cmp(right))
+        };
+        // stay within the own region unless all of its sectors are claimed already
+        let (best_sector, best_score) =
+            best_sector_where(&is_in_own_region).or_else(|| best_sector_where(&|_| true))?;
+
+        match self.own_claim {
+            Some(own_claim)
+                if is_available(own_claim)
+                    && score(own_claim) + parameters.claim_switch_margin >= best_score =>
+            {
+                Some(own_claim)
+            }
+            _ => Some(best_sector),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::*;
+
+    fn field_dimensions() -> FieldDimensions {
+        FieldDimensions {
+            length: 9.0,
+            width: 6.0,
+            ..Default::default()
+        }
+    }
+
+    fn search_parameters() -> SearchParameters {
+        SearchParameters {
+            coverage_distance_weight: 1.0,
+            coverage_cell_hysteresis: Duration::from_secs(5),
+            maximum_coverage_age: Duration::from_secs(60),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn searcher_goes_to_the_stalest_part_of_its_sector() {
+        let mut ball_search = BallSearch {
+            heatmap: BallSearchHeatmap::new(FieldGrid::new(2, 1)),
+            teammate_claims: Vec::new(),
+            own_claim: Some(1),
+            search_cell: None,
+            last_ball_near_own_goal_chirp: None,
+        };
+        let mut coverage = FieldCoverage::new(FieldGrid::new(4, 1));
+        let now = UNIX_EPOCH + Duration::from_secs(100);
+        // the stalest cell overall lies in the other sector
+        coverage.last_observed = vec![
+            None,
+            Some(now),
+            Some(now - Duration::from_secs(2)),
+            Some(now - Duration::from_secs(40)),
+        ];
+
+        let position = ball_search.position_in_sector(
+            1,
+            point![0.0, 0.0],
+            &coverage,
+            now,
+            &search_parameters(),
+            &field_dimensions(),
+        );
+
+        assert_eq!(ball_search.search_cell, Some(3));
+        assert_eq!(position, coverage.cell_center(3, &field_dimensions()));
+    }
+}
@@ -1,4 +1,4 @@
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 use color_eyre::Result;
 use context_attribute::context;
@@ -9,8 +9,8 @@ use linear_algebra::{point, Isometry2, Point2, Vector2};
 use serde::{Deserialize, Serialize};
 use spl_network_messages::{SubState, Team};
 use types::{
-    ball_position::BallPosition, cycle_time::CycleTime, field_dimensions::FieldDimensions,
-    filtered_game_controller_state::FilteredGameControllerState,
+    ball_motion::BallMotion, ball_position::BallPosition, cycle_time::CycleTime,
+    field_dimensions::FieldDimensions, filtered_game_controller_state::FilteredGameControllerState,
     penalty_shot_direction::PenaltyShotDirection, primary_state::PrimaryState, support_foot::Side,
     world_state::BallState,
 };
@@ -34,12 +34,15 @@ pub struct CycleContext {
     filtered_game_controller_state:
         Input<Option<FilteredGameControllerState>, "filtered_game_controller_state?">,
     field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
+    resting_ball_velocity_threshold: Parameter<f32, "ball_filter.resting_ball_velocity_threshold">,
+    velocity_decay_factor: Parameter<f32, "ball_filter.velocity_decay_factor">,
 }
 
 #[context]
 #[derive(Default)]
 pub struct MainOutputs {
     pub ball_state: MainOutput<Option<BallState>>,
+    pub ball_motion: MainOutput<Option<BallMotion>>,
     pub rule_ball_state: MainOutput<Option<BallState>>,
 }
 
@@ -75,6 +78,18 @@ impl BallStateComposer {
             _ => None,
         };
 
+        let ball_motion = match (ball, context.ground_to_field) {
+            (Some(ball), Some(ground_to_field)) => Some(create_ball_motion(
+                ball.ball_in_field,
+                ground_to_field * ball.ball_in_ground_velocity,
+                context.field_dimensions,
+                *context.resting_ball_velocity_threshold,
+                *context.velocity_decay_factor,
+                context.cycle_time.last_cycle_duration,
+            )),
+            _ => None,
+        };
+
         let rule_ball = match (
             context.primary_state,
             context.ground_to_field,
@@ -120,6 +135,7 @@ impl BallStateComposer {
 
         Ok(MainOutputs {
             ball_state: ball.into(),
+            ball_motion: ball_motion.into(),
             rule_ball_state: rule_ball.into(),
         })
     }
@@ -152,3 +168,61 @@ fn create_ball_state(
         penalty_shot_direction,
     }
 }
+
+fn create_ball_motion(
+    position: Point2<Field>,
+    velocity: Vector2<Field>,
+    field_dimensions: &FieldDimensions,
+    resting_ball_velocity_threshold: f32,
+    velocity_decay_factor: f32,
+    cycle_duration: Duration,
+) -> BallMotion {
+    let speed = velocity.norm();
+    let is_rolling = speed >= resting_ball_velocity_threshold;
+    if !is_rolling {
+        return BallMotion {
+            velocity,
+            speed,
+            rest_position: position,
+            ..Default::default()
+        };
+    }
+    let direction = velocity / speed;
+    // continuous equivalent of the per cycle velocity decay of the ball filter, which predicts
+    // once per control cycle
+    let decay_rate = (-velocity_decay_factor.ln() / cycle_duration.as_secs_f32().max(f32::EPSILON))
+        .max(f32::EPSILON);
+    let rolling_distance = speed / decay_rate;
+    let time_to_travel = |distance: f32| {
+        let remaining_velocity_fraction = 1.0 - distance / rolling_distance;
+        (remaining_velocity_fraction > 0.0)
+            .then(|| Duration::from_secs_f32(-remaining_velocity_fraction.ln() / decay_rate))
+    };
+
+    let half_field_length = field_dimensions.length / 2.0;
+    let half_field_width = field_dimensions.width / 2.0;
+    let distance_to_own_goal_line = (direction.x() < 0.0)
+        .then(|| (position.x() + half_field_length) / -direction.x())
+        .filter(|distance| *distance >= 0.0);
+    let distance_to_sideline = (direction.y() != 0.0)
+        .then(|| (direction.y().signum() * half_field_width - position.y()) / direction.y())
+        .filter(|distance| *distance >= 0.0);
+    let time_to_own_goal_line = distance_to_own_goal_line.and_then(time_to_travel);
+    let time_to_sideline = distance_to_sideline.and_then(time_to_travel);
+
+    BallMotion {
+        velocity,
+        speed,
+        direction,
+        is_rolling,
+        rest_position: position + direction * rolling_distance,
+        time_to_own_goal_line,
+        own_goal_line_crossing: distance_to_own_goal_line
+            .filter(|_| time_to_own_goal_line.is_some())
+            .map(|distance| position + direction * distance),
+        time_to_sideline,
+        sideline_crossing: distance_to_sideline
+            .filter(|_| time_to_sideline.is_some())
+            .map(|distance| position + direction * distance),
+    }
+}
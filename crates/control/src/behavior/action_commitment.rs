@@ -0,0 +1,190 @@
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use types::{action::Action, parameters::ActionCommitmentParameters};
+
+/// Keeps a selected committable action in front of the other committable actions for a minimum
+/// duration to stop the priority list from flapping between actions on small world state noise.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct ActionCommitment {
+    committed: Option<(Action, SystemTime)>,
+    /// The committed action is only given up once it is not applicable for the release margin
+    failing_since: Option<SystemTime>,
+}
+
+impl ActionCommitment {
+    /// Moves the committed action in front of the committable actions directly preceding it as
+    /// long as the minimum duration has not passed. Actions without commitment, e.g. resting or
+    /// returning from a penalty, keep their precedence. If the preconditions of the
+    /// committed action no longer hold, it does not produce a motion command and the next action
+    /// is taken anyway.
+    pub fn prioritize(
+        &self,
+        actions: &mut Vec<Action>,
+        now: SystemTime,
+        parameters: &ActionCommitmentParameters,
+    ) {
+        let Some((committed_action, committed_since)) = self.committed else {
+            return;
+        };
+        if !is_within(now, committed_since, parameters.minimum_duration) {
+            return;
+        }
+        let Some(committed_index) = actions
+            .iter()
+            .position(|&action| action == committed_action)
+        else {
+            return;
+        };
+        let number_of_preceding_committable_actions = actions[..committed_index]
+            .iter()
+            .rev()
+            .take_while(|action| parameters.actions.contains(action))
+            .count();
+        let action = actions.remove(committed_index);
+        actions.insert(
+            committed_index - number_of_preceding_committable_actions,
+            action,
+        );
+    }
+
+    pub fn update(
+        &mut self,
+        selected_action: Action,
+        now: SystemTime,
+        parameters: &ActionCommitmentParameters,
+    ) {
+        if let Some((committed_action, committed_since)) = self.committed {
+            if committed_action == selected_action {
+                self.failing_since = None;
+                return;
+            }
+            // short dropouts of the preconditions, e.g. by a noisy ball percept, do not end the
+            // commitment
+            let failing_since = *self.failing_since.get_or_insert(now);
+            if is_within(now, committed_since, parameters.minimum_duration)
+                && is_within(now, failing_since, parameters.release_margin)
+            {
+                return;
+            }
+        }
+        self.failing_since = None;
+        self.committed = parameters
+            .actions
+            .contains(&selected_action)
+            .then_some((selected_action, now));
+    }
+}
+
+fn is_within(now: SystemTime, since: SystemTime, duration: Duration) -> bool {
+    now.duration_since(since)
+        .is_ok_and(|elapsed| elapsed < duration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters() -> ActionCommitmentParameters {
+        ActionCommitmentParameters {
+            actions: vec![Action::InterceptBall, Action::Dribble, Action::SupportLeft],
+            minimum_duration: Duration::from_secs(1),
+            release_margin: Duration::from_millis(300),
+        }
+    }
+
+    fn at(milliseconds: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_millis(milliseconds)
+    }
+
+    fn committed_to(action: Action) -> ActionCommitment {
+        let mut commitment = ActionCommitment::default();
+        commitment.update(action, at(0), &parameters());
+        commitment
+    }
+
+    #[test]
+    fn committed_action_moves_in_front_of_committable_actions_only() {
+        let commitment = committed_to(Action::SupportLeft);
+        let mut actions = vec![
+            Action::FallSafely,
+            Action::InterceptBall,
+            Action::LookAround,
+            Action::Dribble,
+            Action::SupportLeft,
+        ];
+
+        commitment.prioritize(&mut actions, at(500), &parameters());
+
+        assert_eq!(
+            actions,
+            [
+                Action::FallSafely,
+                Action::InterceptBall,
+                Action::LookAround,
+                Action::SupportLeft,
+                Action::Dribble,
+            ]
+        );
+    }
+
+    #[test]
+    fn commitment_ends_after_minimum_duration() {
+        let commitment = committed_to(Action::SupportLeft);
+        let mut actions = vec![Action::Dribble, Action::SupportLeft];
+
+        commitment.prioritize(&mut actions, at(1000), &parameters());
+
+        assert_eq!(actions, [Action::Dribble, Action::SupportLeft]);
+    }
+
+    #[test]
+    fn short_dropouts_keep_the_commitment() {
+        let mut commitment = committed_to(Action::SupportLeft);
+
+        commitment.update(Action::Dribble, at(100), &parameters());
+        commitment.update(Action::Dribble, at(300), &parameters());
+        commitment.update(Action::SupportLeft, at(350), &parameters());
+        commitment.update(Action::Dribble, at(600), &parameters());
+        commitment.update(Action::Dribble, at(800), &parameters());
+
+        let mut actions = vec![Action::Dribble, Action::SupportLeft];
+        commitment.prioritize(&mut actions, at(800), &parameters());
+        assert_eq!(actions, [Action::SupportLeft, Action::Dribble]);
+    }
+
+    #[test]
+    fn lasting_dropout_releases_the_commitment() {
+        let mut commitment = committed_to(Action::SupportLeft);
+
+        commitment.update(Action::Dribble, at(100), &parameters());
+        commitment.update(Action::Dribble, at(400), &parameters());
+
+        let mut actions = vec![Action::SupportLeft, Action::Dribble];
+        commitment.prioritize(&mut actions, at(500), &parameters());
+        assert_eq!(actions, [Action::Dribble, Action::SupportLeft]);
+    }
+
+    #[test]
+    fn committed_action_does_not_overtake_return_from_penalty() {
+        let commitment = committed_to(Action::SupportLeft);
+        let mut actions = vec![
+            Action::InterceptBall,
+            Action::ReturnFromPenalty,
+            Action::Dribble,
+            Action::SupportLeft,
+        ];
+
+        commitment.prioritize(&mut actions, at(500), &parameters());
+
+        assert_eq!(
+            actions,
+            [
+                Action::InterceptBall,
+                Action::ReturnFromPenalty,
+                Action::SupportLeft,
+                Action::Dribble,
+            ]
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use types::{
+    motion_command::{ArmMotion, MotionCommand},
+    obstacles::{Obstacle, ObstacleKind},
+    parameters::ArmTuckingParameters,
+    support_foot::Side,
+};
+
+/// Pulls the arms tight on every side with a robot or goal post close by, arms swinging into
+/// other robots are the most common cause of pushing fouls in tight situations.
+///
+/// An arm stays tucked until the obstacles on its side are further away than the release radius
+/// to avoid flickering arms at the border of the tuck radius.
+pub fn tuck_near_obstacles(
+    motion_command: &mut MotionCommand,
+    last_motion_command: &MotionCommand,
+    obstacles: &[Obstacle],
+    parameters: &ArmTuckingParameters,
+) {
+    if !parameters.enable {
+        return;
+    }
+    let (left_arm, right_arm) = match motion_command {
+        MotionCommand::Walk {
+            left_arm,
+            right_arm,
+            ..
+        }
+        | MotionCommand::InWalkKick {
+            left_arm,
+            right_arm,
+            ..
+        } => (left_arm, right_arm),
+        _ => return,
+    };
+    for (side, arm) in [(Side::Left, left_arm), (Side::Right, right_arm)] {
+        let radius = if is_tucked(last_motion_command, side) {
+            parameters.release_radius
+        } else {
+            parameters.tuck_radius
+        };
+        if has_obstacle_within(obstacles, side, radius) {
+            *arm = ArmMotion::PullTight;
+        }
+    }
+}
+
+fn is_tucked(motion_command: &MotionCommand, side: Side) -> bool {
+    match motion_command {
+        MotionCommand::Walk {
+            left_arm,
+            right_arm,
+            ..
+        }
+        | MotionCommand::InWalkKick {
+            left_arm,
+            right_arm,
+            ..
+        } => {
+            let arm = match side {
+                Side::Left => left_arm,
+                Side::Right => right_arm,
+            };
+            *arm == ArmMotion::PullTight
+        }
+        _ => false,
+    }
+}
+
+fn has_obstacle_within(obstacles: &[Obstacle], side: Side, radius: f32) -> bool {
+    obstacles
+        .iter()
+        .filter(|obstacle| !matches!(obstacle.kind, ObstacleKind::Ball))
+        .any(|obstacle| {
+            let is_on_side = match side {
+                Side::Left => obstacle.position.y() >= 0.0,
+                Side::Right => obstacle.position.y() <= 0.0,
+            };
+            is_on_side && obstacle.position.coords().norm() - obstacle.radius_at_hip_height < radius
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use linear_algebra::point;
+    use types::motion_command::{HeadMotion, OrientationMode, WalkSpeed};
+
+    use super::*;
+
+    fn walk(arm: ArmMotion) -> MotionCommand {
+        MotionCommand::Walk {
+            head: HeadMotion::Center,
+            path: Vec::new(),
+            left_arm: arm,
+            right_arm: arm,
+            orientation_mode: OrientationMode::AlignWithPath,
+            speed: WalkSpeed::Normal,
+        }
+    }
+
+    #[test]
+    fn tucked_arms_are_released_with_hysteresis() {
+        let parameters = ArmTuckingParameters {
+            enable: true,
+            tuck_radius: 0.4,
+            release_radius: 0.6,
+        };
+        let obstacles = [Obstacle::robot(point![0.1, 0.6], 0.2, 0.1)];
+
+        let mut swinging = walk(ArmMotion::Swing);
+        tuck_near_obstacles(
+            &mut swinging,
+            &walk(ArmMotion::Swing),
+            &obstacles,
+            &parameters,
+        );
+        assert!(!is_tucked(&swinging, Side::Left));
+
+        let mut tucked = walk(ArmMotion::Swing);
+        tuck_near_obstacles(
+            &mut tucked,
+            &walk(ArmMotion::PullTight),
+            &obstacles,
+            &parameters,
+        );
+        assert!(is_tucked(&tucked, Side::Left));
+        assert!(!is_tucked(&tucked, Side::Right));
+    }
+}
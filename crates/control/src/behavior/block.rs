@@ -0,0 +1,217 @@
+use coordinate_systems::Field;
+use framework::AdditionalOutput;
+use geometry::{line_segment::LineSegment, look_at::LookAt};
+use linear_algebra::{distance, point, vector, Point2, Pose2};
+use types::{
+    field_dimensions::FieldDimensions, filtered_game_state::FilteredGameState,
+    motion_command::MotionCommand, parameters::BlockParameters, path_obstacles::PathObstacle,
+    roles::Role, world_state::WorldState,
+};
+
+use super::{head::LookAction, walk_to_pose::WalkAndStand};
+
+/// Puts the defender on the shot line between the ball and the own goal while the ball is close
+/// to the own goal, and crouches into the wide stance once a shot is imminent.
+///
+/// The left and right defender stand slightly apart from the shot line to cover more of the goal
+/// together.
+pub fn execute(
+    world_state: &WorldState,
+    field_dimensions: &FieldDimensions,
+    parameters: &BlockParameters,
+    walk_and_stand: &WalkAndStand,
+    look_action: &LookAction,
+    path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
+) -> Option<MotionCommand> {
+    if !parameters.enable
+        || !matches!(
+            world_state.filtered_game_controller_state?.game_state,
+            FilteredGameState::Playing {
+                ball_is_free: true,
+                ..
+            }
+        )
+    {
+        return None;
+    }
+    let ground_to_field = world_state.robot.ground_to_field?;
+    let ball = world_state.ball?.ball_in_field;
+    let own_goal = point![-field_dimensions.length / 2.0, 0.0];
+    if distance(ball, own_goal) > parameters.maximum_ball_distance_to_own_goal {
+        return None;
+    }
+    let lateral_offset = match world_state.robot.role {
+        Role::DefenderLeft => parameters.lateral_offset,
+        Role::DefenderRight => -parameters.lateral_offset,
+        _ => 0.0,
+    };
+    let position = block_position(ball, own_goal, parameters.distance_to_ball, lateral_offset)?;
+
+    let is_in_position =
+        distance(ground_to_field.as_pose().position(), position) < parameters.position_tolerance;
+    let is_shot_imminent = world_state.opponents.iter().any(|opponent| {
+        is_shot_imminent(
+            ground_to_field * opponent.position,
+            ball,
+            own_goal,
+            parameters,
+        )
+    });
+    if is_in_position && is_shot_imminent {
+        return Some(MotionCommand::ArmsUpSquat);
+    }
+
+    let pose = Pose2::new(position.coords(), position.look_at(&ball).angle());
+    walk_and_stand.execute(
+        ground_to_field.inverse() * pose,
+        look_action.execute(),
+        path_obstacles_output,
+    )
+}
+
+/// Only one defender blocks, the other one keeps its regular defending position to cover
+/// rebounds
+pub fn is_nearest_defender_to_shot_line(
+    world_state: &WorldState,
+    field_dimensions: &FieldDimensions,
+) -> bool {
+    let (Some(ground_to_field), Some(ball)) = (world_state.robot.ground_to_field, world_state.ball)
+    else {
+        return false;
+    };
+    let own_goal = point![-field_dimensions.length / 2.0, 0.0];
+    is_nearest_to_shot_line(
+        ground_to_field.as_pose().position(),
+        &world_state.teammate_defender_positions,
+        ball.ball_in_field,
+        own_goal,
+    )
+}
+
+/// Ties go to the robot itself, both defenders blocking is better than none
+fn is_nearest_to_shot_line(
+    own_position: Point2<Field>,
+    teammate_defender_positions: &[Point2<Field>],
+    ball: Point2<Field>,
+    own_goal: Point2<Field>,
+) -> bool {
+    let shot_line = LineSegment::new(ball, own_goal);
+    let own_distance = shot_line.shortest_distance_to_point(own_position);
+    teammate_defender_positions
+        .iter()
+        .all(|teammate| shot_line.shortest_distance_to_point(*teammate) >= own_distance)
+}
+
+/// At the distance to the ball on the line from the ball to the own goal, shifted sideways
+fn block_position(
+    ball: Point2<Field>,
+    own_goal: Point2<Field>,
+    distance_to_ball: f32,
+    lateral_offset: f32,
+) -> Option<Point2<Field>> {
+    let ball_to_goal = own_goal - ball;
+    if ball_to_goal.norm() <= distance_to_ball {
+        return None;
+    }
+    let direction = ball_to_goal.normalize();
+    // positive offsets are towards the left as seen from the own goal
+    let sideways = vector![direction.y(), -direction.x()];
+    Some(ball + direction * distance_to_ball + sideways * lateral_offset)
+}
+
+/// Obstacles have no orientation, an opponent right behind the ball as seen from the own goal is
+/// assumed to face the goal
+fn is_shot_imminent(
+    opponent: Point2<Field>,
+    ball: Point2<Field>,
+    own_goal: Point2<Field>,
+    parameters: &BlockParameters,
+) -> bool {
+    let opponent_to_ball = ball - opponent;
+    let ball_to_goal = own_goal - ball;
+    if opponent_to_ball.norm() > parameters.shot_imminent_distance
+        || opponent_to_ball.norm() == 0.0
+        || ball_to_goal.norm() == 0.0
+    {
+        return false;
+    }
+    let cosine = opponent_to_ball.normalize().dot(ball_to_goal.normalize());
+    cosine.clamp(-1.0, 1.0).acos() < parameters.maximum_shot_angle
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    fn parameters() -> BlockParameters {
+        BlockParameters {
+            enable: true,
+            distance_to_ball: 1.0,
+            lateral_offset: 0.2,
+            maximum_ball_distance_to_own_goal: 4.0,
+            position_tolerance: 0.15,
+            shot_imminent_distance: 0.4,
+            maximum_shot_angle: 0.5,
+        }
+    }
+
+    #[test]
+    fn block_position_is_on_the_shot_line() {
+        let ball = point![-2.0, 0.0];
+        let own_goal = point![-4.5, 0.0];
+
+        let position = block_position(ball, own_goal, 1.0, 0.0).unwrap();
+        assert_relative_eq!(position.x(), -3.0, epsilon = 0.001);
+        assert_relative_eq!(position.y(), 0.0, epsilon = 0.001);
+        let left = block_position(ball, own_goal, 1.0, 0.2).unwrap();
+        let right = block_position(ball, own_goal, 1.0, -0.2).unwrap();
+        assert!(left.y() > right.y());
+        assert!(block_position(point![-4.0, 0.0], own_goal, 1.0, 0.0).is_none());
+    }
+
+    #[test]
+    fn shot_is_imminent_with_an_opponent_behind_the_ball() {
+        let ball = point![-2.0, 0.0];
+        let own_goal = point![-4.5, 0.0];
+        let parameters = parameters();
+
+        assert!(is_shot_imminent(
+            point![-1.7, 0.0],
+            ball,
+            own_goal,
+            &parameters
+        ));
+        assert!(!is_shot_imminent(
+            point![-1.0, 0.0],
+            ball,
+            own_goal,
+            &parameters
+        ));
+        assert!(!is_shot_imminent(
+            point![-2.0, 0.3],
+            ball,
+            own_goal,
+            &parameters
+        ));
+        assert!(!is_shot_imminent(
+            point![-2.3, 0.0],
+            ball,
+            own_goal,
+            &parameters
+        ));
+    }
+
+    #[test]
+    fn only_the_defender_nearest_to_the_shot_line_blocks() {
+        let ball = point![-2.0, 1.0];
+        let own_goal = point![-4.5, 0.0];
+        let near = point![-3.0, 0.8];
+        let far = point![-3.5, -1.0];
+
+        assert!(is_nearest_to_shot_line(near, &[far], ball, own_goal));
+        assert!(!is_nearest_to_shot_line(far, &[near], ball, own_goal));
+        assert!(is_nearest_to_shot_line(far, &[], ball, own_goal));
+    }
+}
@@ -0,0 +1,55 @@
+use linear_algebra::distance;
+use types::{
+    field_dimensions::FieldDimensions,
+    kick_decision::KickDecision,
+    motion_command::MotionCommand,
+    parameters::{DribblingParameters, InWalkKicksParameters, KeeperClearanceParameters},
+    planned_path::PathSegment,
+    world_state::WorldState,
+};
+
+use super::{dribble, walk_to_pose::WalkPathPlanner};
+
+/// Leaves the goal line to kick a slow ball out of the own penalty area as long as no opponent
+/// could reach it first. The kick selector aims the kick towards the sideline, once the ball is
+/// out of the penalty area the keeper returns to defending the goal.
+#[allow(clippy::too_many_arguments)]
+pub fn execute(
+    world_state: &WorldState,
+    field_dimensions: &FieldDimensions,
+    parameters: &KeeperClearanceParameters,
+    walk_path_planner: &WalkPathPlanner,
+    in_walk_kicks: &InWalkKicksParameters,
+    dribbling_parameters: &DribblingParameters,
+    kick_decisions: Option<&[KickDecision]>,
+    dribble_path: Option<Vec<PathSegment>>,
+) -> Option<MotionCommand> {
+    if !parameters.enable {
+        return None;
+    }
+    let ball = world_state.ball?;
+    if !field_dimensions.is_inside_own_penalty_area(ball.ball_in_field)
+        || ball.ball_in_ground_velocity.norm() > parameters.maximum_ball_velocity
+    {
+        return None;
+    }
+    let is_opponent_close_to_ball = world_state
+        .opponents
+        .iter()
+        .any(|obstacle| {
+            distance(obstacle.position, ball.ball_in_ground)
+                < parameters.minimum_opponent_distance_to_ball
+        });
+    if is_opponent_close_to_ball {
+        return None;
+    }
+    dribble::execute(
+        world_state,
+        walk_path_planner,
+        in_walk_kicks,
+        field_dimensions,
+        dribbling_parameters,
+        kick_decisions,
+        dribble_path,
+    )
+}
@@ -9,18 +9,22 @@ use types::{
     field_dimensions::FieldDimensions,
     filtered_game_controller_state::FilteredGameControllerState,
     motion_command::MotionCommand,
-    parameters::RolePositionsParameters,
+    parameters::{IllegalDefenderParameters, RolePositionsParameters},
     path_obstacles::PathObstacle,
     support_foot::Side,
     world_state::{BallState, WorldState},
 };
 
-use super::{head::LookAction, walk_to_pose::WalkAndStand};
+use super::{
+    head::LookAction, illegal_defender::project_outside_own_penalty_area,
+    walk_to_pose::WalkAndStand,
+};
 
 pub struct Defend<'cycle> {
     world_state: &'cycle WorldState,
     field_dimensions: &'cycle FieldDimensions,
     role_positions: &'cycle RolePositionsParameters,
+    illegal_defender: &'cycle IllegalDefenderParameters,
     walk_and_stand: &'cycle WalkAndStand<'cycle>,
     look_action: &'cycle LookAction<'cycle>,
 }
@@ -30,6 +34,7 @@ impl<'cycle> Defend<'cycle> {
         world_state: &'cycle WorldState,
         field_dimensions: &'cycle FieldDimensions,
         role_positions: &'cycle RolePositionsParameters,
+        illegal_defender: &'cycle IllegalDefenderParameters,
         walk_and_stand: &'cycle WalkAndStand,
         look_action: &'cycle LookAction,
     ) -> Self {
@@ -37,6 +42,7 @@ impl<'cycle> Defend<'cycle> {
             world_state,
             field_dimensions,
             role_positions,
+            illegal_defender,
             walk_and_stand,
             look_action,
         }
@@ -55,7 +61,12 @@ impl<'cycle> Defend<'cycle> {
         &self,
         path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
     ) -> Option<MotionCommand> {
-        let pose = defend_left_pose(self.world_state, self.field_dimensions, self.role_positions)?;
+        let pose = defend_left_pose(
+            self.world_state,
+            self.field_dimensions,
+            self.role_positions,
+            self.illegal_defender,
+        )?;
         self.with_pose(pose, path_obstacles_output)
     }
 
@@ -63,7 +74,12 @@ impl<'cycle> Defend<'cycle> {
         &self,
         path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
     ) -> Option<MotionCommand> {
-        let pose = defend_right_pose(self.world_state, self.field_dimensions, self.role_positions)?;
+        let pose = defend_right_pose(
+            self.world_state,
+            self.field_dimensions,
+            self.role_positions,
+            self.illegal_defender,
+        )?;
         self.with_pose(pose, path_obstacles_output)
     }
 
@@ -71,8 +87,12 @@ impl<'cycle> Defend<'cycle> {
         &self,
         path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
     ) -> Option<MotionCommand> {
-        let pose =
-            defend_penalty_kick(self.world_state, self.field_dimensions, self.role_positions)?;
+        let pose = defend_penalty_kick(
+            self.world_state,
+            self.field_dimensions,
+            self.role_positions,
+            self.illegal_defender,
+        )?;
         self.with_pose(pose, path_obstacles_output)
     }
 
@@ -98,6 +118,7 @@ fn defend_left_pose(
     world_state: &WorldState,
     field_dimensions: &FieldDimensions,
     role_positions: &RolePositionsParameters,
+    illegal_defender: &IllegalDefenderParameters,
 ) -> Option<Pose2<Ground>> {
     let ground_to_field = world_state.robot.ground_to_field?;
     let ball = world_state
@@ -119,7 +140,11 @@ fn defend_left_pose(
         world_state.filtered_game_controller_state,
         field_dimensions,
     );
-    let defend_pose = block_on_circle(ball.ball_in_field, position_to_defend, distance_to_target);
+    let defend_pose = project_outside_own_penalty_area(
+        block_on_circle(ball.ball_in_field, position_to_defend, distance_to_target),
+        field_dimensions,
+        illegal_defender,
+    );
     let field_to_ground = ground_to_field.inverse();
     Some(field_to_ground * defend_pose)
 }
@@ -128,6 +153,7 @@ fn defend_right_pose(
     world_state: &WorldState,
     field_dimensions: &FieldDimensions,
     role_positions: &RolePositionsParameters,
+    illegal_defender: &IllegalDefenderParameters,
 ) -> Option<Pose2<Ground>> {
     let ground_to_field = world_state.robot.ground_to_field?;
     let ball = world_state
@@ -149,7 +175,11 @@ fn defend_right_pose(
         world_state.filtered_game_controller_state,
         field_dimensions,
     );
-    let defend_pose = block_on_circle(ball.ball_in_field, position_to_defend, distance_to_target);
+    let defend_pose = project_outside_own_penalty_area(
+        block_on_circle(ball.ball_in_field, position_to_defend, distance_to_target),
+        field_dimensions,
+        illegal_defender,
+    );
     Some(ground_to_field.inverse() * defend_pose)
 }
 
@@ -157,6 +187,7 @@ fn defend_penalty_kick(
     world_state: &WorldState,
     field_dimensions: &FieldDimensions,
     role_positions: &RolePositionsParameters,
+    illegal_defender: &IllegalDefenderParameters,
 ) -> Option<Pose2<Ground>> {
     let ground_to_field = world_state.robot.ground_to_field?;
     let ball = world_state
@@ -179,7 +210,11 @@ fn defend_penalty_kick(
         field_dimensions,
     );
 
-    let defend_pose = block_on_circle(ball.ball_in_field, position_to_defend, distance_to_target);
+    let defend_pose = project_outside_own_penalty_area(
+        block_on_circle(ball.ball_in_field, position_to_defend, distance_to_target),
+        field_dimensions,
+        illegal_defender,
+    );
     Some(ground_to_field.inverse() * defend_pose)
 }
 
@@ -212,7 +247,12 @@ fn defend_goal_pose(
         _ => role_positions.keeper_x_offset,
     };
 
-    let position_to_defend = point![-field_dimensions.length / 2.0 - 1.0, 0.0];
+    // a ball rolling into the goal is blocked on its trajectory instead of towards the goal center
+    let position_to_defend = world_state
+        .ball_motion
+        .and_then(|ball_motion| ball_motion.own_goal_line_crossing)
+        .filter(|crossing| crossing.y().abs() < field_dimensions.goal_inner_width / 2.0)
+        .unwrap_or(point![-field_dimensions.length / 2.0 - 1.0, 0.0]);
     let defend_pose = block_on_line(
         ball.ball_in_field,
         position_to_defend,
@@ -0,0 +1,77 @@
+use coordinate_systems::Field;
+use framework::AdditionalOutput;
+use geometry::{look_at::LookAt, rectangle::Rectangle};
+use linear_algebra::{point, Point2, Pose2};
+use types::{
+    motion_command::{HeadMotion, MotionCommand},
+    parameters::DemoParameters,
+    path_obstacles::PathObstacle,
+    primary_state::PrimaryState,
+    world_state::WorldState,
+};
+
+use super::walk_to_pose::WalkAndStand;
+
+/// Keeps the robot and the ball it plays with inside the demo area. While both are inside, the
+/// dribbling takes over.
+pub fn execute(
+    world_state: &WorldState,
+    parameters: &DemoParameters,
+    walk_and_stand: &WalkAndStand,
+    path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
+) -> Option<MotionCommand> {
+    if world_state.robot.primary_state != PrimaryState::Playing {
+        return None;
+    }
+    let Some(ball) = world_state.ball else {
+        return Some(MotionCommand::Stand {
+            head: HeadMotion::LookAround,
+        });
+    };
+    let ground_to_field = world_state.robot.ground_to_field?;
+    let robot_position = ground_to_field.as_pose().position();
+    let is_ball_inside = contains(&parameters.area, ball.ball_in_field);
+    if is_ball_inside && contains(&parameters.area, robot_position) {
+        return None;
+    }
+
+    // wait at the border closest to the ball or walk back into the area
+    let target = if is_ball_inside {
+        clamp_to_area(robot_position, &parameters.area, parameters.border_margin)
+    } else {
+        clamp_to_area(
+            ball.ball_in_field,
+            &parameters.area,
+            parameters.border_margin,
+        )
+    };
+    let target_pose = Pose2::new(target.coords(), target.look_at(&ball.ball_in_field).angle());
+    walk_and_stand.execute(
+        ground_to_field.inverse() * target_pose,
+        HeadMotion::LookAt {
+            target: ball.ball_in_ground,
+            camera: None,
+        },
+        path_obstacles_output,
+    )
+}
+
+fn contains(area: &Rectangle<Field>, position: Point2<Field>) -> bool {
+    (area.min.x()..=area.max.x()).contains(&position.x())
+        && (area.min.y()..=area.max.y()).contains(&position.y())
+}
+
+fn clamp_to_area(position: Point2<Field>, area: &Rectangle<Field>, margin: f32) -> Point2<Field> {
+    let center = area.min + (area.max - area.min) / 2.0;
+    let clamp = |value: f32, min: f32, max: f32, center: f32| {
+        if min + margin <= max - margin {
+            value.clamp(min + margin, max - margin)
+        } else {
+            center
+        }
+    };
+    point![
+        clamp(position.x(), area.min.x(), area.max.x(), center.x()),
+        clamp(position.y(), area.min.y(), area.max.y(), center.y())
+    ]
+}
@@ -1,42 +1,131 @@
 use coordinate_systems::Ground;
 use geometry::look_at::LookAt;
-use linear_algebra::{Point, Pose2};
+use linear_algebra::{distance, Orientation2, Point, Point2, Pose2};
 use types::{
-    motion_command::{HeadMotion, MotionCommand, OrientationMode},
-    parameters::{DribblingParameters, InWalkKickInfoParameters, InWalkKicksParameters},
+    field_dimensions::FieldDimensions,
+    kick_decision::KickDecision,
+    motion_command::{
+        ArmMotion, HeadMotion, KickVariant, MotionCommand, OrientationMode, WalkSpeed,
+    },
+    parameters::{
+        DribblingParameters, DuelParameters, InWalkKickInfoParameters, InWalkKicksParameters,
+    },
     planned_path::PathSegment,
     world_state::WorldState,
 };
 
+use crate::dribble_path_planner::is_inside_corridor;
+
 use super::walk_to_pose::{hybrid_alignment, WalkPathPlanner};
 
+/// Kicks or walks to the kick pose without contesting opponents, e.g. for passes and clearances
+/// where the ball has to go towards the selected target instead of away from an opponent
 #[allow(clippy::too_many_arguments)]
 pub fn execute(
     world_state: &WorldState,
     walk_path_planner: &WalkPathPlanner,
     in_walk_kicks: &InWalkKicksParameters,
+    field_dimensions: &FieldDimensions,
     parameters: &DribblingParameters,
+    kick_decisions: Option<&[KickDecision]>,
+    dribble_path: Option<Vec<PathSegment>>,
+) -> Option<MotionCommand> {
+    dribble(
+        world_state,
+        walk_path_planner,
+        in_walk_kicks,
+        field_dimensions,
+        parameters,
+        kick_decisions,
+        dribble_path,
+        None,
+    )
+}
+
+/// Dribbles like [`execute`], but switches to the duel mode against an opponent contesting the
+/// ball
+#[allow(clippy::too_many_arguments)]
+pub fn execute_with_duel(
+    world_state: &WorldState,
+    walk_path_planner: &WalkPathPlanner,
+    in_walk_kicks: &InWalkKicksParameters,
+    field_dimensions: &FieldDimensions,
+    parameters: &DribblingParameters,
+    kick_decisions: Option<&[KickDecision]>,
     dribble_path: Option<Vec<PathSegment>>,
+) -> Option<MotionCommand> {
+    let ball_position = world_state.ball?.ball_in_ground;
+    dribble(
+        world_state,
+        walk_path_planner,
+        in_walk_kicks,
+        field_dimensions,
+        parameters,
+        kick_decisions,
+        dribble_path,
+        duel_opponent(world_state, ball_position, &parameters.duel),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn dribble(
+    world_state: &WorldState,
+    walk_path_planner: &WalkPathPlanner,
+    in_walk_kicks: &InWalkKicksParameters,
+    field_dimensions: &FieldDimensions,
+    parameters: &DribblingParameters,
+    kick_decisions: Option<&[KickDecision]>,
+    dribble_path: Option<Vec<PathSegment>>,
+    duel_opponent: Option<Point2<Ground>>,
 ) -> Option<MotionCommand> {
     let ball_position = world_state.ball?.ball_in_ground;
     let head = HeadMotion::LookLeftAndRightOf {
         target: ball_position,
     };
-    let kick_decisions = world_state.kick_decisions.as_ref()?;
-    let instant_kick_decisions = world_state.instant_kick_decisions.as_ref()?;
+    let kick_decisions = kick_decisions?;
+    // instant kicks are taken as they are, the ones leaving the corridor are skipped
+    let instant_kick_decisions: Vec<_> = world_state
+        .instant_kick_decisions
+        .as_ref()?
+        .iter()
+        .filter(|decision| {
+            is_inside_corridor(
+                decision,
+                world_state,
+                in_walk_kicks,
+                field_dimensions,
+                &parameters.corridor,
+            )
+        })
+        .collect();
 
-    let available_kick = kick_decisions
+    let available_kicks: Vec<_> = kick_decisions
         .iter()
-        .chain(instant_kick_decisions.iter())
-        .find(|decision| {
+        .chain(instant_kick_decisions)
+        .filter(|decision| {
             is_kick_pose_reached(decision.kick_pose, &in_walk_kicks[decision.variant])
-        });
+        })
+        .collect();
+    let available_kick = match duel_opponent {
+        // a quick sideways kick gets the ball away from the contesting opponent
+        Some(_) => available_kicks
+            .iter()
+            .find(|decision| decision.variant == KickVariant::Side)
+            .or(available_kicks.first()),
+        None => available_kicks.first(),
+    };
     if let Some(kick) = available_kick {
+        let arm = match duel_opponent {
+            Some(_) => ArmMotion::PullTight,
+            None => ArmMotion::Swing,
+        };
         let command = MotionCommand::InWalkKick {
             head,
             kick: kick.variant,
             kicking_side: kick.kicking_side,
             strength: kick.strength,
+            left_arm: arm,
+            right_arm: arm,
         };
         return Some(command);
     }
@@ -61,14 +150,55 @@ pub fn execute(
         }
         orientation_mode => orientation_mode,
     };
-    match dribble_path {
-        Some(path) => {
+    match (dribble_path, duel_opponent) {
+        (Some(path), Some(opponent)) => {
+            // keep the body between opponent and ball until the kick pose is close, short steps
+            // and tight arms avoid pushing the opponent
+            let orientation_mode = if best_pose.position().coords().norm()
+                > parameters.distance_to_be_aligned
+                && distance(opponent, ball_position) > f32::EPSILON
+            {
+                OrientationMode::Override(Orientation2::from_vector(ball_position - opponent))
+            } else {
+                orientation_mode
+            };
+            Some(MotionCommand::Walk {
+                head,
+                path,
+                left_arm: ArmMotion::PullTight,
+                right_arm: ArmMotion::PullTight,
+                orientation_mode,
+                speed: WalkSpeed::Slow,
+            })
+        }
+        (Some(path), None) => {
             Some(walk_path_planner.walk_with_obstacle_avoiding_arms(head, orientation_mode, path))
         }
-        None => Some(MotionCommand::Stand { head }),
+        (None, _) => Some(MotionCommand::Stand { head }),
     }
 }
 
+/// Returns the closest opponent contesting the ball, if any
+fn duel_opponent(
+    world_state: &WorldState,
+    ball_position: Point2<Ground>,
+    parameters: &DuelParameters,
+) -> Option<Point2<Ground>> {
+    if !parameters.enable {
+        return None;
+    }
+    world_state
+        .opponents
+        .iter()
+        .map(|obstacle| obstacle.position)
+        .filter(|position| {
+            distance(*position, ball_position) < parameters.opponent_distance_to_ball
+        })
+        .min_by(|left, right| {
+            distance(*left, ball_position).total_cmp(&distance(*right, ball_position))
+        })
+}
+
 fn is_kick_pose_reached(
     kick_pose_to_robot: Pose2<Ground>,
     kick_info: &InWalkKickInfoParameters,
@@ -0,0 +1,183 @@
+use coordinate_systems::Field;
+use linear_algebra::{distance, Point2};
+use types::{
+    field_dimensions::FieldDimensions,
+    filtered_game_controller_state::FilteredGameControllerState,
+    filtered_game_state::FilteredGameState,
+    kick_decision::KickDecision,
+    motion_command::MotionCommand,
+    parameters::{DribblingParameters, EmergencyClearanceParameters, InWalkKicksParameters},
+    planned_path::PathSegment,
+    world_state::WorldState,
+};
+
+use super::{dribble, walk_to_pose::WalkPathPlanner};
+
+/// Overrides the role behavior of the robot closest to the ball while the ball rolls across the
+/// own goal box towards the goal line. The robot walks straight to the ball and takes whatever
+/// in-walk kick is available first.
+#[allow(clippy::too_many_arguments)]
+pub fn execute(
+    world_state: &WorldState,
+    field_dimensions: &FieldDimensions,
+    parameters: &EmergencyClearanceParameters,
+    walk_path_planner: &WalkPathPlanner,
+    in_walk_kicks: &InWalkKicksParameters,
+    dribbling_parameters: &DribblingParameters,
+    kick_decisions: Option<&[KickDecision]>,
+    dribble_path: Option<Vec<PathSegment>>,
+) -> Option<MotionCommand> {
+    if !is_responsible(world_state, field_dimensions, parameters) {
+        return None;
+    }
+    dribble::execute(
+        world_state,
+        walk_path_planner,
+        in_walk_kicks,
+        field_dimensions,
+        dribbling_parameters,
+        kick_decisions,
+        dribble_path,
+    )
+}
+
+/// A teammate claiming the goal mouth already clears the ball, a second robot running into the
+/// goal box only gets in its way
+fn is_responsible(
+    world_state: &WorldState,
+    field_dimensions: &FieldDimensions,
+    parameters: &EmergencyClearanceParameters,
+) -> bool {
+    if !parameters.enable || world_state.teammate_goal_mouth_claim.is_some() {
+        return false;
+    }
+    if !matches!(
+        world_state.filtered_game_controller_state,
+        None | Some(FilteredGameControllerState {
+            game_state: FilteredGameState::Playing {
+                ball_is_free: true,
+                ..
+            },
+            sub_state: None,
+            ..
+        })
+    ) {
+        return false;
+    }
+    let (Some(ball), Some(ball_motion)) = (world_state.ball, world_state.ball_motion) else {
+        return false;
+    };
+    let is_rolling_towards_goal_line = ball_motion.is_rolling
+        && ball_motion.speed > parameters.minimum_ball_velocity
+        && ball_motion.own_goal_line_crossing.is_some();
+    if !is_rolling_towards_goal_line
+        || !is_inside_own_goal_box(
+            ball.ball_in_field,
+            field_dimensions,
+            parameters.goal_box_margin,
+        )
+    {
+        return false;
+    }
+    let own_distance_to_ball = ball.ball_in_ground.coords().norm();
+    world_state
+        .teammate_positions
+        .iter()
+        .all(|position| distance(*position, ball.ball_in_field) >= own_distance_to_ball)
+}
+
+fn is_inside_own_goal_box(
+    position: Point2<Field>,
+    field_dimensions: &FieldDimensions,
+    margin: f32,
+) -> bool {
+    position.x() < -field_dimensions.length / 2.0 + field_dimensions.goal_box_area_length + margin
+        && position.y().abs() < field_dimensions.goal_box_area_width / 2.0 + margin
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::UNIX_EPOCH;
+
+    use linear_algebra::{point, vector};
+    use spl_network_messages::GoalMouthClaim;
+    use types::{ball_motion::BallMotion, support_foot::Side, world_state::BallState};
+
+    use super::*;
+
+    fn field_dimensions() -> FieldDimensions {
+        FieldDimensions {
+            length: 9.0,
+            width: 6.0,
+            goal_box_area_length: 0.6,
+            goal_box_area_width: 2.2,
+            ..Default::default()
+        }
+    }
+
+    fn parameters() -> EmergencyClearanceParameters {
+        EmergencyClearanceParameters {
+            enable: true,
+            goal_box_margin: 0.2,
+            minimum_ball_velocity: 0.1,
+        }
+    }
+
+    /// The ball rolls through the goal box towards the goal line, one meter in front of the robot
+    fn ball_rolling_into_goal() -> WorldState {
+        WorldState {
+            ball: Some(BallState {
+                ball_in_ground: point![1.0, 0.0],
+                ball_in_field: point![-4.2, 0.0],
+                ball_in_ground_velocity: vector![0.5, 0.0],
+                last_seen_ball: UNIX_EPOCH,
+                penalty_shot_direction: None,
+                field_side: Side::Left,
+            }),
+            ball_motion: Some(BallMotion {
+                speed: 0.5,
+                is_rolling: true,
+                own_goal_line_crossing: Some(point![-4.5, 0.0]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn closest_robot_clears_the_ball() {
+        let world_state = ball_rolling_into_goal();
+
+        assert!(is_responsible(
+            &world_state,
+            &field_dimensions(),
+            &parameters()
+        ));
+
+        let world_state = WorldState {
+            teammate_positions: vec![point![-4.0, 0.0]],
+            ..ball_rolling_into_goal()
+        };
+        assert!(!is_responsible(
+            &world_state,
+            &field_dimensions(),
+            &parameters()
+        ));
+    }
+
+    #[test]
+    fn claimed_goal_mouth_is_left_to_the_teammate() {
+        let world_state = WorldState {
+            teammate_goal_mouth_claim: Some(GoalMouthClaim {
+                clearing_target: point![0.0, 3.0],
+            }),
+            ..ball_rolling_into_goal()
+        };
+
+        assert!(!is_responsible(
+            &world_state,
+            &field_dimensions(),
+            &parameters()
+        ));
+    }
+}
@@ -0,0 +1,228 @@
+use coordinate_systems::Field;
+use framework::AdditionalOutput;
+use geometry::look_at::LookAt;
+use linear_algebra::{point, Orientation2, Point, Point2, Pose2, Rotation2, Vector2};
+use spl_network_messages::SubState;
+use types::{
+    field_dimensions::FieldDimensions,
+    motion_command::{MotionCommand, OrientationMode},
+    parameters::FreeKickParameters,
+    path_obstacles::PathObstacle,
+    planned_path::direct_path,
+    roles::Role,
+    rule_set::RuleSet,
+    world_state::WorldState,
+};
+
+use super::{
+    head::LookAction,
+    walk_to_pose::{WalkAndStand, WalkPathPlanner},
+};
+
+pub fn support(
+    world_state: &WorldState,
+    field_dimensions: &FieldDimensions,
+    parameters: &FreeKickParameters,
+    walk_and_stand: &WalkAndStand,
+    look_action: &LookAction,
+    path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
+) -> Option<MotionCommand> {
+    let ground_to_field = world_state.robot.ground_to_field?;
+    let ball = world_state.ball.or(world_state.rule_ball)?.ball_in_field;
+    let sub_state = world_state.filtered_game_controller_state?.sub_state?;
+    let ball_side = if ball.y() < 0.0 { -1.0 } else { 1.0 };
+
+    let supporting_position = match sub_state {
+        // wait for the cross on the far side of the goal
+        SubState::CornerKick => point![
+            parameters.corner_kick_supporter_position.x(),
+            -ball_side * parameters.corner_kick_supporter_position.y()
+        ],
+        // offer a short option up the sideline the ball is on
+        SubState::GoalKick => point![
+            parameters.goal_kick_supporter_position.x(),
+            ball_side * parameters.goal_kick_supporter_position.y()
+        ],
+        // stay infield behind the ball to receive a short kick-in
+        SubState::KickIn => point![
+            ball.x() + parameters.kick_in_supporter_position.x(),
+            ball_side * (field_dimensions.width / 2.0 - parameters.kick_in_supporter_position.y())
+        ],
+        _ => return None,
+    };
+    let supporting_position = clamp_to_field(supporting_position, field_dimensions);
+    let supporting_pose = Pose2::new(
+        supporting_position.coords(),
+        supporting_position.look_at(&ball).angle(),
+    );
+    walk_and_stand.execute(
+        ground_to_field.inverse() * supporting_pose,
+        look_action.execute(),
+        path_obstacles_output,
+    )
+}
+
+pub fn defend(
+    world_state: &WorldState,
+    field_dimensions: &FieldDimensions,
+    parameters: &FreeKickParameters,
+    rule_set: RuleSet,
+    walk_and_stand: &WalkAndStand,
+    look_action: &LookAction,
+    path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
+) -> Option<MotionCommand> {
+    let ground_to_field = world_state.robot.ground_to_field?;
+    let ball = world_state.ball.or(world_state.rule_ball)?.ball_in_field;
+
+    // block the direct line to our goal while keeping the distance required by the rules
+    let own_goal = point![-field_dimensions.length / 2.0, 0.0];
+    let ball_to_goal = own_goal - ball;
+    let required_distance = rule_set.free_kick_distance() + parameters.distance_margin;
+    let blocking_position = if ball_to_goal.norm() > required_distance {
+        ball + line_up_direction(
+            world_state,
+            ball,
+            ball_to_goal.normalize(),
+            required_distance,
+            parameters,
+        ) * required_distance
+    } else {
+        own_goal
+    };
+    let blocking_position = clamp_to_field(blocking_position, field_dimensions);
+    let blocking_pose = Pose2::new(
+        blocking_position.coords(),
+        blocking_position.look_at(&ball).angle(),
+    );
+    walk_and_stand.execute(
+        ground_to_field.inverse() * blocking_pose,
+        look_action.execute(),
+        path_obstacles_output,
+    )
+}
+
+/// Leaves the circle around the ball the rules keep us out of during opponent free kicks.
+///
+/// The path planner cannot plan out of the rule obstacle, hence the robot walks directly to the
+/// closest point on the circle that is still inside the field, facing the ball.
+pub fn keep_distance(
+    world_state: &WorldState,
+    field_dimensions: &FieldDimensions,
+    parameters: &FreeKickParameters,
+    rule_set: RuleSet,
+    walk_path_planner: &WalkPathPlanner,
+    look_action: &LookAction,
+) -> Option<MotionCommand> {
+    let ground_to_field = world_state.robot.ground_to_field?;
+    let ball = world_state.ball.or(world_state.rule_ball)?;
+    let robot_position = ground_to_field.as_pose().position();
+    if (robot_position - ball.ball_in_field).norm() >= rule_set.free_kick_distance() {
+        return None;
+    }
+
+    let exit = exclusion_circle_exit(
+        ball.ball_in_field,
+        robot_position,
+        rule_set.free_kick_distance() + parameters.distance_margin,
+        field_dimensions,
+    );
+    let exit_in_ground = ground_to_field.inverse() * exit;
+    let ball_in_ground = ball.ball_in_ground;
+    Some(walk_path_planner.walk_with_obstacle_avoiding_arms(
+        look_action.execute(),
+        OrientationMode::Override(Orientation2::new(
+            ball_in_ground.y().atan2(ball_in_ground.x()),
+        )),
+        direct_path(Point::origin(), exit_in_ground),
+    ))
+}
+
+/// Returns the point on the circle closest to the robot which is inside the field.
+fn exclusion_circle_exit(
+    ball: Point2<Field>,
+    robot_position: Point2<Field>,
+    radius: f32,
+    field_dimensions: &FieldDimensions,
+) -> Point2<Field> {
+    let ball_to_robot = robot_position - ball;
+    let direction = if ball_to_robot.norm() > f32::EPSILON {
+        ball_to_robot.normalize()
+    } else {
+        (point![-field_dimensions.length / 2.0, 0.0] - ball).normalize()
+    };
+    let is_inside_field = |position: Point2<Field>| {
+        position.x().abs() <= field_dimensions.length / 2.0
+            && position.y().abs() <= field_dimensions.width / 2.0
+    };
+    let angle_step = 10.0_f32.to_radians();
+    (0..=18)
+        .flat_map(|step| [step as f32 * angle_step, -(step as f32) * angle_step])
+        .map(|angle| ball + Rotation2::new(angle) * direction * radius)
+        .find(|position| is_inside_field(*position))
+        .unwrap_or_else(|| clamp_to_field(ball + direction * radius, field_dimensions))
+}
+
+/// During opponent kick-ins the striker supporter lines up next to the striker on the legal
+/// distance circle, shifted infield to cover a pass into the field.
+fn line_up_direction(
+    world_state: &WorldState,
+    ball: Point2<Field>,
+    ball_to_goal_direction: Vector2<Field>,
+    required_distance: f32,
+    parameters: &FreeKickParameters,
+) -> Vector2<Field> {
+    let is_kick_in = matches!(
+        world_state
+            .filtered_game_controller_state
+            .and_then(|game_controller_state| game_controller_state.sub_state),
+        Some(SubState::KickIn)
+    );
+    if !is_kick_in || world_state.robot.role != Role::StrikerSupporter {
+        return ball_to_goal_direction;
+    }
+    // the direction to our goal points backwards, turning it towards the field center
+    let infield_rotation_sign = if ball.y() < 0.0 { -1.0 } else { 1.0 };
+    let angle = infield_rotation_sign * parameters.kick_in_line_up_spacing / required_distance;
+    Rotation2::new(angle) * ball_to_goal_direction
+}
+
+pub fn clamp_to_field(
+    position: Point2<Field>,
+    field_dimensions: &FieldDimensions,
+) -> Point2<Field> {
+    point![
+        position.x().clamp(
+            -field_dimensions.length / 2.0,
+            field_dimensions.length / 2.0
+        ),
+        position
+            .y()
+            .clamp(-field_dimensions.width / 2.0, field_dimensions.width / 2.0)
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn exits_exclusion_circle_into_the_field() {
+        let field_dimensions = FieldDimensions {
+            length: 9.0,
+            width: 6.0,
+            ..Default::default()
+        };
+
+        let exit =
+            exclusion_circle_exit(point![0.0, 0.0], point![-0.5, 0.0], 1.0, &field_dimensions);
+        assert_relative_eq!(exit, point![-1.0, 0.0], epsilon = 0.001);
+
+        // the robot stands between the ball on the sideline and the field border
+        let exit =
+            exclusion_circle_exit(point![0.0, 3.0], point![0.0, 3.2], 1.0, &field_dimensions);
+        assert!(exit.y() <= 3.0);
+        assert_relative_eq!((exit - point![0.0, 3.0]).norm(), 1.0, epsilon = 0.001);
+    }
+}
@@ -0,0 +1,17 @@
+use types::{
+    motion_command::{HeadMotion, MotionCommand},
+    world_state::WorldState,
+};
+
+/// Stands still and follows the ball with the head, e.g. for demonstrations, photos and data
+/// collection.
+pub fn execute(world_state: &WorldState) -> Option<MotionCommand> {
+    let head = match world_state.ball {
+        Some(ball) => HeadMotion::LookAt {
+            target: ball.ball_in_ground,
+            camera: None,
+        },
+        None => HeadMotion::LookAround,
+    };
+    Some(MotionCommand::Stand { head })
+}
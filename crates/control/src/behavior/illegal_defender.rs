@@ -0,0 +1,126 @@
+use coordinate_systems::Field;
+use geometry::rectangle::Rectangle;
+use linear_algebra::{point, Point2, Pose2};
+use types::{
+    field_dimensions::FieldDimensions, parameters::IllegalDefenderParameters, roles::Role,
+    rule_obstacles::RuleObstacle, world_state::WorldState,
+};
+
+fn own_penalty_area(field_dimensions: &FieldDimensions, margin: f32) -> Rectangle<Field> {
+    let goal_line_x = -field_dimensions.length / 2.0;
+    Rectangle {
+        min: point![
+            goal_line_x - field_dimensions.border_strip_width,
+            -field_dimensions.penalty_area_width / 2.0 - margin
+        ],
+        max: point![
+            goal_line_x + field_dimensions.penalty_area_length + margin,
+            field_dimensions.penalty_area_width / 2.0 + margin
+        ],
+    }
+}
+
+fn contains(rectangle: &Rectangle<Field>, position: Point2<Field>) -> bool {
+    (rectangle.min.x()..=rectangle.max.x()).contains(&position.x())
+        && (rectangle.min.y()..=rectangle.max.y()).contains(&position.y())
+}
+
+/// Returns the own penalty area as obstacle for field players once it holds as many robots as
+/// allowed. Robots already inside the obstacle are not trapped by it and may leave on their own.
+///
+/// Teammates standing on the penalty area line would toggle the obstacle every cycle, once it is
+/// occupied teammates count as inside until they left the area by the occupancy hysteresis.
+pub fn own_penalty_area_obstacle(
+    world_state: &WorldState,
+    field_dimensions: &FieldDimensions,
+    parameters: &IllegalDefenderParameters,
+    was_occupied: bool,
+) -> Option<RuleObstacle> {
+    if matches!(
+        world_state.robot.role,
+        Role::Keeper | Role::ReplacementKeeper
+    ) {
+        return None;
+    }
+    let own_position = world_state.robot.ground_to_field?.as_pose().position();
+    let obstacle = own_penalty_area(field_dimensions, parameters.penalty_area_margin);
+    if contains(&obstacle, own_position) {
+        return None;
+    }
+    let penalty_area = own_penalty_area(
+        field_dimensions,
+        if was_occupied {
+            parameters.occupancy_hysteresis
+        } else {
+            0.0
+        },
+    );
+    let number_of_robots_in_penalty_area = world_state
+        .teammate_positions
+        .iter()
+        .filter(|position| contains(&penalty_area, **position))
+        .count();
+    let is_occupied =
+        number_of_robots_in_penalty_area >= parameters.maximum_number_of_robots_in_penalty_area;
+    is_occupied.then_some(RuleObstacle::Rectangle(obstacle))
+}
+
+/// Moves a pose inside the own penalty area to the closest border, keeping its orientation.
+pub fn project_outside_own_penalty_area(
+    pose: Pose2<Field>,
+    field_dimensions: &FieldDimensions,
+    parameters: &IllegalDefenderParameters,
+) -> Pose2<Field> {
+    let penalty_area = own_penalty_area(field_dimensions, parameters.penalty_area_margin);
+    let position = pose.position();
+    if !contains(&penalty_area, position) {
+        return pose;
+    }
+    let to_front = penalty_area.max.x() - position.x();
+    let to_left = penalty_area.max.y() - position.y();
+    let to_right = position.y() - penalty_area.min.y();
+    let projected_position = if to_front <= to_left && to_front <= to_right {
+        point![penalty_area.max.x(), position.y()]
+    } else if to_left <= to_right {
+        point![position.x(), penalty_area.max.y()]
+    } else {
+        point![position.x(), penalty_area.min.y()]
+    };
+    Pose2::from_parts(projected_position, pose.orientation())
+}
+
+#[cfg(test)]
+mod tests {
+    use linear_algebra::{vector, Isometry2};
+
+    use super::*;
+
+    #[test]
+    fn occupancy_is_kept_while_teammates_stand_on_the_line() {
+        let field_dimensions = FieldDimensions {
+            length: 9.0,
+            width: 6.0,
+            penalty_area_length: 1.65,
+            penalty_area_width: 4.0,
+            border_strip_width: 0.7,
+            ..Default::default()
+        };
+        let parameters = IllegalDefenderParameters {
+            maximum_number_of_robots_in_penalty_area: 2,
+            penalty_area_margin: 0.2,
+            occupancy_hysteresis: 0.3,
+        };
+        let mut world_state = WorldState::default();
+        world_state.robot.role = Role::DefenderLeft;
+        world_state.robot.ground_to_field = Some(Isometry2::from_parts(vector![0.0, 0.0], 0.0));
+        // the keeper inside and a defender just outside of the penalty area line
+        world_state.teammate_positions = vec![point![-4.2, 0.0], point![-2.75, 1.0]];
+
+        let is_occupied = |was_occupied| {
+            own_penalty_area_obstacle(&world_state, &field_dimensions, &parameters, was_occupied)
+                .is_some()
+        };
+        assert!(!is_occupied(false));
+        assert!(is_occupied(true));
+    }
+}
@@ -1,12 +1,13 @@
-use coordinate_systems::{Field, Ground};
+use coordinate_systems::Ground;
 use geometry::line::Line;
 use geometry::line_segment::LineSegment;
-use linear_algebra::{Isometry2, Orientation2, Point};
+use linear_algebra::{Orientation2, Point, Point2, Vector2};
 use spl_network_messages::{GamePhase, SubState};
 use types::{
+    ball_motion::BallMotion,
     filtered_game_controller_state::FilteredGameControllerState,
     filtered_game_state::FilteredGameState,
-    motion_command::{HeadMotion, MotionCommand, OrientationMode},
+    motion_command::{HeadMotion, MotionCommand, OrientationMode, WalkSpeed},
     parameters::InterceptBallParameters,
     planned_path::PathSegment,
     step_plan::Step,
@@ -38,7 +39,7 @@ pub fn execute(
     match (
         filtered_game_state,
         world_state.ball,
-        world_state.robot.ground_to_field,
+        world_state.ball_motion,
     ) {
         (
             Some(FilteredGameState::Playing {
@@ -46,9 +47,9 @@ pub fn execute(
             })
             | None,
             Some(ball),
-            Some(ground_to_field),
+            Some(ball_motion),
         ) => {
-            if !ball_is_interception_candidate(ball, ground_to_field, &parameters) {
+            if !ball_is_interception_candidate(ball, ball_motion, &parameters) {
                 return None;
             }
 
@@ -62,15 +63,12 @@ pub fn execute(
                 return None;
             }
 
-            let ball_line = Line(
+            let engagement_range = parameters.engagement_ranges.range(world_state.robot.role);
+            let interception_point = interception_point(
                 ball.ball_in_ground,
-                ball.ball_in_ground + ball.ball_in_ground_velocity,
-            );
-            let interception_point = ball_line.project_point(Point::origin());
-
-            if interception_point.coords().norm() > parameters.maximum_intercept_distance {
-                return None;
-            }
+                ball.ball_in_ground_velocity,
+                engagement_range,
+            )?;
 
             let path = vec![PathSegment::LineSegment(LineSegment(
                 Point::origin(),
@@ -86,6 +84,7 @@ pub fn execute(
                 left_arm: types::motion_command::ArmMotion::Swing,
                 right_arm: types::motion_command::ArmMotion::Swing,
                 orientation_mode: OrientationMode::Override(Orientation2::identity()),
+                speed: WalkSpeed::Normal,
             })
         }
         _ => None,
@@ -94,22 +93,98 @@ pub fn execute(
 
 fn ball_is_interception_candidate(
     ball: BallState,
-    ground_to_field: Isometry2<Ground, Field>,
+    ball_motion: BallMotion,
     parameters: &InterceptBallParameters,
 ) -> bool {
     let ball_is_in_front_of_robot = ball.ball_in_ground.coords().norm()
         < parameters.maximum_ball_distance
         && ball.ball_in_ground.x() > 0.0;
-    let ball_is_moving_towards_robot =
-        ball.ball_in_ground_velocity.x() < -parameters.minimum_ball_velocity_towards_robot;
+    let ball_is_moving_towards_robot = is_moving_towards_robot(
+        ball.ball_in_ground,
+        ball.ball_in_ground_velocity,
+        parameters.minimum_ball_velocity_towards_robot,
+    );
 
-    let ball_in_field_velocity = ground_to_field * ball.ball_in_ground_velocity;
-    let ball_is_moving = ball_in_field_velocity.norm() > parameters.minimum_ball_velocity;
+    let ball_is_moving = ball_motion.speed > parameters.minimum_ball_velocity;
     let ball_is_moving_towards_own_half =
-        ball_in_field_velocity.x() < -parameters.minimum_ball_velocity_towards_own_half;
+        ball_motion.velocity.x() < -parameters.minimum_ball_velocity_towards_own_half;
 
     ball_is_in_front_of_robot
         && ball_is_moving
         && ball_is_moving_towards_robot
         && ball_is_moving_towards_own_half
 }
+
+/// Balls passing by sideways count as well, not only those coming straight at the robot.
+fn is_moving_towards_robot(
+    ball_position: Point2<Ground>,
+    ball_velocity: Vector2<Ground>,
+    minimum_velocity_towards_robot: f32,
+) -> bool {
+    ball_position.coords().norm() > 0.0
+        && ball_velocity.dot(ball_position.coords().normalize()) < -minimum_velocity_towards_robot
+}
+
+/// The point closest to the robot on the ball's line of motion, if the ball has not passed it
+/// yet and it is within the engagement range.
+fn interception_point(
+    ball_position: Point2<Ground>,
+    ball_velocity: Vector2<Ground>,
+    engagement_range: f32,
+) -> Option<Point2<Ground>> {
+    let ball_line = Line(ball_position, ball_position + ball_velocity);
+    let interception_point = ball_line.project_point(Point::origin());
+    let ball_has_passed_interception_point =
+        (interception_point - ball_position).dot(ball_velocity) <= 0.0;
+    if ball_has_passed_interception_point || interception_point.coords().norm() > engagement_range {
+        return None;
+    }
+    Some(interception_point)
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use linear_algebra::{point, vector};
+
+    use super::*;
+
+    #[test]
+    fn sideways_passing_balls_move_towards_robot() {
+        assert!(is_moving_towards_robot(
+            point![2.0, 0.0],
+            vector![-1.0, 0.0],
+            0.1
+        ));
+        assert!(is_moving_towards_robot(
+            point![2.0, 1.0],
+            vector![-1.0, -0.2],
+            0.1
+        ));
+        assert!(!is_moving_towards_robot(
+            point![2.0, 1.0],
+            vector![0.0, 1.0],
+            0.1
+        ));
+        assert!(!is_moving_towards_robot(
+            point![0.0, 0.0],
+            vector![-1.0, 0.0],
+            0.1
+        ));
+    }
+
+    #[test]
+    fn interception_point_lies_ahead_of_ball_within_range() {
+        let point = interception_point(point![2.0, 0.5], vector![-1.0, 0.0], 0.6).unwrap();
+        assert_relative_eq!(point, point![0.0, 0.5], epsilon = 0.001);
+
+        assert_eq!(
+            interception_point(point![2.0, 0.5], vector![-1.0, 0.0], 0.4),
+            None
+        );
+        assert_eq!(
+            interception_point(point![-0.5, 0.5], vector![-1.0, 0.0], 0.6),
+            None
+        );
+    }
+}
@@ -1,19 +1,301 @@
+use std::time::Duration;
+
+use coordinate_systems::Field;
+use framework::AdditionalOutput;
+use linear_algebra::Point2;
+use spl_network_messages::{GamePhase, SubState, Team};
 use types::{
+    fall_state::FallState,
+    field_dimensions::FieldDimensions,
+    filtered_game_controller_state::FilteredGameControllerState,
+    filtered_game_state::FilteredGameState,
     motion_command::{JumpDirection, MotionCommand},
+    parameters::{KeeperDiveParameters, RobotCapabilitiesParameters},
     penalty_shot_direction::PenaltyShotDirection,
     world_state::WorldState,
 };
 
-pub fn execute(world_state: &WorldState) -> Option<MotionCommand> {
-    world_state
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dive {
+    Stay,
+    Squat,
+    WideStance,
+    Left,
+    Right,
+    Front,
+}
+
+pub fn execute(
+    world_state: &WorldState,
+    field_dimensions: &FieldDimensions,
+    parameters: &KeeperDiveParameters,
+    capabilities: &RobotCapabilitiesParameters,
+    predicted_goal_line_crossing_output: &mut AdditionalOutput<Option<Point2<Field>>>,
+) -> Option<MotionCommand> {
+    let crossing = predict_goal_line_crossing(world_state, field_dimensions, parameters);
+    predicted_goal_line_crossing_output
+        .fill_if_subscribed(|| crossing.map(|(position, _)| position));
+
+    let dive = match (crossing, world_state.robot.ground_to_field) {
+        (Some((position, time_to_goal_line)), Some(ground_to_field)) => {
+            let keeper_position = ground_to_field.as_pose().position();
+            decide_dive(
+                position.y() - keeper_position.y(),
+                time_to_goal_line,
+                parameters,
+            )
+        }
+        _ => dive_from_penalty_shot_direction(world_state),
+    };
+
+    let dive = conform_to_situation(
+        dive,
+        is_penalty_situation(world_state),
+        world_state.robot.fall_state,
+        parameters,
+    );
+    match restrict_to_capabilities(dive, capabilities) {
+        Dive::Stay => None,
+        // there is no dedicated wide stance motion yet, the squat covers the goal best
+        Dive::Squat | Dive::WideStance => Some(MotionCommand::ArmsUpSquat),
+        Dive::Left => Some(MotionCommand::Jump {
+            direction: JumpDirection::Left,
+        }),
+        Dive::Right => Some(MotionCommand::Jump {
+            direction: JumpDirection::Right,
+        }),
+        Dive::Front => Some(MotionCommand::Jump {
+            direction: JumpDirection::Front,
+        }),
+    }
+}
+
+/// Whole-body dives leave the keeper lying on the ground for several seconds, which only pays
+/// off against an opponent penalty kick. Everywhere else the keeper stays on its feet.
+fn conform_to_situation(
+    dive: Dive,
+    is_penalty_situation: bool,
+    fall_state: FallState,
+    parameters: &KeeperDiveParameters,
+) -> Dive {
+    if fall_state != FallState::Upright {
+        return Dive::Stay;
+    }
+    match dive {
+        Dive::Left | Dive::Right | Dive::Front
+            if !is_penalty_situation || !parameters.enable_dives =>
+        {
+            Dive::Stay
+        }
+        Dive::Squat
+            if is_penalty_situation && parameters.enable_dives && parameters.use_front_dive =>
+        {
+            Dive::Front
+        }
+        dive => dive,
+    }
+}
+
+fn is_penalty_situation(world_state: &WorldState) -> bool {
+    matches!(
+        world_state.filtered_game_controller_state,
+        Some(
+            FilteredGameControllerState {
+                game_state: FilteredGameState::Playing { .. },
+                game_phase: GamePhase::PenaltyShootout {
+                    kicking_team: Team::Opponent,
+                },
+                ..
+            } | FilteredGameControllerState {
+                game_state: FilteredGameState::Playing { .. },
+                kicking_team: Team::Opponent,
+                sub_state: Some(SubState::PenaltyKick),
+                ..
+            }
+        )
+    )
+}
+
+/// Returns where and when the ball is expected to cross the own goal line between the posts.
+fn predict_goal_line_crossing(
+    world_state: &WorldState,
+    field_dimensions: &FieldDimensions,
+    parameters: &KeeperDiveParameters,
+) -> Option<(Point2<Field>, Duration)> {
+    let ball_motion = world_state.ball_motion?;
+    if !ball_motion.is_rolling {
+        return None;
+    }
+    let position = ball_motion.own_goal_line_crossing?;
+    let time_to_goal_line = ball_motion.time_to_own_goal_line?;
+    let is_on_goal =
+        position.y().abs() <= field_dimensions.goal_inner_width / 2.0 + parameters.goal_post_margin;
+    is_on_goal.then_some((position, time_to_goal_line))
+}
+
+/// Decides on a dive given the lateral offset of the crossing point relative to the keeper.
+///
+/// The keeper only commits once the ball arrives within the commitment window after the
+/// reaction time. If the ball arrives before a dive could be completed, only the squat is fast
+/// enough to still make a difference.
+fn decide_dive(
+    lateral_offset: f32,
+    time_to_goal_line: Duration,
+    parameters: &KeeperDiveParameters,
+) -> Dive {
+    if time_to_goal_line > parameters.reaction_time + parameters.commitment_window {
+        return Dive::Stay;
+    }
+    let distance = lateral_offset.abs();
+    if distance <= parameters.squat_reach {
+        return Dive::Squat;
+    }
+    if time_to_goal_line < parameters.reaction_time {
+        return Dive::Stay;
+    }
+    match distance {
+        distance if distance <= parameters.wide_stance_reach => Dive::WideStance,
+        distance if distance <= parameters.dive_reach && lateral_offset > 0.0 => Dive::Left,
+        distance if distance <= parameters.dive_reach => Dive::Right,
+        _ => Dive::Stay,
+    }
+}
+
+/// Motions the robot cannot execute safely are replaced by staying upright.
+fn restrict_to_capabilities(dive: Dive, capabilities: &RobotCapabilitiesParameters) -> Dive {
+    match dive {
+        Dive::Left if !capabilities.can_dive_left => Dive::Stay,
+        Dive::Right if !capabilities.can_dive_right => Dive::Stay,
+        Dive::Squat | Dive::WideStance | Dive::Front if capabilities.has_weak_knee => Dive::Stay,
+        dive => dive,
+    }
+}
+
+fn dive_from_penalty_shot_direction(world_state: &WorldState) -> Dive {
+    match world_state
         .ball
-        .and_then(|ball| match ball.penalty_shot_direction {
-            Some(PenaltyShotDirection::Left) => Some(MotionCommand::Jump {
-                direction: JumpDirection::Left,
-            }),
-            Some(PenaltyShotDirection::Right) => Some(MotionCommand::Jump {
-                direction: JumpDirection::Right,
-            }),
-            Some(PenaltyShotDirection::NotMoving) | None => None,
-        })
+        .and_then(|ball| ball.penalty_shot_direction)
+    {
+        Some(PenaltyShotDirection::Left) => Dive::Left,
+        Some(PenaltyShotDirection::Right) => Dive::Right,
+        Some(PenaltyShotDirection::NotMoving) | None => Dive::Stay,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters() -> KeeperDiveParameters {
+        KeeperDiveParameters {
+            enable_dives: true,
+            reaction_time: Duration::from_millis(300),
+            commitment_window: Duration::from_millis(400),
+            goal_post_margin: 0.1,
+            squat_reach: 0.15,
+            use_front_dive: true,
+            wide_stance_reach: 0.3,
+            dive_reach: 0.8,
+        }
+    }
+
+    #[test]
+    fn dive_is_selected_by_lateral_offset() {
+        let time = Duration::from_millis(500);
+        assert_eq!(decide_dive(0.05, time, &parameters()), Dive::Squat);
+        assert_eq!(decide_dive(-0.25, time, &parameters()), Dive::WideStance);
+        assert_eq!(decide_dive(0.5, time, &parameters()), Dive::Left);
+        assert_eq!(decide_dive(-0.5, time, &parameters()), Dive::Right);
+        assert_eq!(decide_dive(1.0, time, &parameters()), Dive::Stay);
+    }
+
+    #[test]
+    fn keeper_only_commits_within_window() {
+        assert_eq!(
+            decide_dive(0.5, Duration::from_secs(2), &parameters()),
+            Dive::Stay
+        );
+        assert_eq!(
+            decide_dive(0.5, Duration::from_millis(100), &parameters()),
+            Dive::Stay
+        );
+        assert_eq!(
+            decide_dive(0.1, Duration::from_millis(100), &parameters()),
+            Dive::Squat
+        );
+    }
+
+    #[test]
+    fn limited_robots_do_not_dive_to_their_weak_side() {
+        let capabilities = RobotCapabilitiesParameters {
+            can_dive_left: false,
+            can_dive_right: true,
+            has_weak_knee: true,
+        };
+        assert_eq!(
+            restrict_to_capabilities(Dive::Left, &capabilities),
+            Dive::Stay
+        );
+        assert_eq!(
+            restrict_to_capabilities(Dive::Right, &capabilities),
+            Dive::Right
+        );
+        assert_eq!(
+            restrict_to_capabilities(Dive::Squat, &capabilities),
+            Dive::Stay
+        );
+    }
+
+    #[test]
+    fn keeper_only_falls_in_penalty_situations() {
+        let upright = FallState::Upright;
+        assert_eq!(
+            conform_to_situation(Dive::Left, false, upright, &parameters()),
+            Dive::Stay
+        );
+        assert_eq!(
+            conform_to_situation(Dive::Squat, false, upright, &parameters()),
+            Dive::Squat
+        );
+        assert_eq!(
+            conform_to_situation(Dive::Left, true, upright, &parameters()),
+            Dive::Left
+        );
+        assert_eq!(
+            conform_to_situation(Dive::Squat, true, upright, &parameters()),
+            Dive::Front
+        );
+        assert_eq!(
+            conform_to_situation(
+                Dive::Right,
+                true,
+                FallState::Fallen {
+                    facing: types::motion_command::Facing::Up
+                },
+                &parameters()
+            ),
+            Dive::Stay
+        );
+    }
+
+    #[test]
+    fn disabled_dives_fall_back_to_squatting() {
+        let parameters = KeeperDiveParameters {
+            enable_dives: false,
+            ..parameters()
+        };
+        let upright = FallState::Upright;
+        assert_eq!(
+            conform_to_situation(Dive::Left, true, upright, &parameters),
+            Dive::Stay
+        );
+        assert_eq!(
+            conform_to_situation(Dive::Front, true, upright, &parameters),
+            Dive::Stay
+        );
+        assert_eq!(
+            conform_to_situation(Dive::Squat, true, upright, &parameters),
+            Dive::Squat
+        );
+    }
 }
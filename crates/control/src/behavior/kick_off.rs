@@ -0,0 +1,398 @@
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use coordinate_systems::Field;
+use framework::AdditionalOutput;
+use linear_algebra::{point, Point2, Pose2};
+use spl_network_messages::{GamePhase, Team};
+use types::{
+    filtered_game_controller_state::FilteredGameControllerState,
+    filtered_game_state::FilteredGameState,
+    kick_off_play::KickOffPlay,
+    motion_command::{HeadMotion, MotionCommand},
+    parameters::{InWalkKicksParameters, KickOffParameters},
+    path_obstacles::PathObstacle,
+    world_state::WorldState,
+};
+
+use super::{
+    penalty_shot::{align_and_kick, KickAlignment},
+    walk_to_pose::WalkAndStand,
+};
+
+/// Set play of the striker at own kick-offs.
+///
+/// A play is chosen when the own kick-off is announced in ready, the striker lines up for it
+/// until playing starts and executes it during the first seconds of playing.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct KickOffSetPlay {
+    /// Index into [`KickOffPlay::ALL`] of the next play when rotating
+    next_play: usize,
+    current: Option<KickOff>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+struct KickOff {
+    play: KickOffPlay,
+    /// 1.0 for the left and -1.0 for the right side of the field
+    side: f32,
+    phase: Phase,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+enum Phase {
+    LiningUp,
+    Executing { since: SystemTime },
+    Completed,
+}
+
+impl KickOffSetPlay {
+    pub fn update(
+        self,
+        world_state: &WorldState,
+        parameters: &KickOffParameters,
+        now: SystemTime,
+    ) -> Self {
+        let mut next_play = self.next_play;
+        let current = match (world_state.filtered_game_controller_state, self.current) {
+            (Some(game_controller_state), current) if is_own_kick_off(game_controller_state) => {
+                let play = match (parameters.play, current) {
+                    (Some(play), _) => play,
+                    (None, Some(current)) => current.play,
+                    (None, None) => {
+                        next_play = (self.next_play + 1) % KickOffPlay::ALL.len();
+                        KickOffPlay::ALL[self.next_play % KickOffPlay::ALL.len()]
+                    }
+                };
+                // the side is chosen once, the striker would otherwise turn back and forth while
+                // the opponents walk to their kick-off positions
+                let side = current.map_or_else(|| open_side(world_state), |current| current.side);
+                Some(KickOff {
+                    play,
+                    side,
+                    phase: Phase::LiningUp,
+                })
+            }
+            (
+                Some(FilteredGameControllerState {
+                    game_state: FilteredGameState::Playing { .. },
+                    ..
+                }),
+                Some(current),
+            ) => {
+                let phase = match current.phase {
+                    Phase::LiningUp => Phase::Executing { since: now },
+                    Phase::Executing { since }
+                        if now
+                            .duration_since(since)
+                            .is_ok_and(|duration| duration > parameters.duration)
+                            || is_ball_away_from_center(
+                                world_state,
+                                parameters.completion_distance,
+                            ) =>
+                    {
+                        Phase::Completed
+                    }
+                    phase => phase,
+                };
+                Some(KickOff { phase, ..current })
+            }
+            _ => None,
+        };
+        Self { next_play, current }
+    }
+
+    /// The pose the striker lines up in behind the center mark, facing the target of the play
+    pub fn set_pose(
+        &self,
+        parameters: &KickOffParameters,
+        distance_to_center: f32,
+    ) -> Option<Pose2<Field>> {
+        if !parameters.enable {
+            return None;
+        }
+        let current = self.current?;
+        let (target, _) = target(current.play, current.side, parameters);
+        let direction = target.coords().normalize();
+        Some(Pose2::new(
+            -direction * distance_to_center,
+            direction.y().atan2(direction.x()),
+        ))
+    }
+}
+
+pub fn execute(
+    world_state: &WorldState,
+    kick_off: &KickOffSetPlay,
+    parameters: &KickOffParameters,
+    in_walk_kicks: &InWalkKicksParameters,
+    walk_and_stand: &WalkAndStand,
+    path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
+) -> Option<MotionCommand> {
+    if !parameters.enable {
+        return None;
+    }
+    let current = kick_off.current?;
+    if !matches!(current.phase, Phase::Executing { .. }) {
+        return None;
+    }
+    let ground_to_field = world_state.robot.ground_to_field?;
+    let ball_position = world_state.ball?.ball_in_ground;
+    let (target, strength) = target(current.play, current.side, parameters);
+
+    align_and_kick(
+        ball_position,
+        ground_to_field.inverse() * target,
+        strength,
+        false,
+        HeadMotion::LookAt {
+            target: ball_position,
+            camera: None,
+        },
+        in_walk_kicks,
+        &KickAlignment {
+            alignment_distance: parameters.alignment_distance,
+            maximum_lateral_alignment_error: parameters.maximum_lateral_alignment_error,
+            maximum_angular_alignment_error: parameters.maximum_angular_alignment_error,
+        },
+        walk_and_stand,
+        path_obstacles_output,
+    )
+}
+
+fn is_own_kick_off(game_controller_state: FilteredGameControllerState) -> bool {
+    matches!(
+        game_controller_state,
+        FilteredGameControllerState {
+            game_state: FilteredGameState::Ready {
+                kicking_team: Team::Hulks,
+            } | FilteredGameState::Set,
+            kicking_team: Team::Hulks,
+            sub_state: None,
+            game_phase: GamePhase::Normal | GamePhase::Overtime,
+            ..
+        }
+    )
+}
+
+/// Returns the target of the play in field coordinates and the kick strength
+fn target(play: KickOffPlay, side: f32, parameters: &KickOffParameters) -> (Point2<Field>, f32) {
+    let mirrored = |target: Point2<Field>, side: f32| point![target.x(), side * target.y().abs()];
+    match play {
+        KickOffPlay::ShortDiagonalPass => (
+            mirrored(parameters.pass_target, side),
+            parameters.pass_strength,
+        ),
+        KickOffPlay::LongKickIntoCorner => (
+            mirrored(parameters.corner_target, side),
+            parameters.corner_strength,
+        ),
+        KickOffPlay::DribbleLeft => (
+            mirrored(parameters.dribble_target, 1.0),
+            parameters.dribble_strength,
+        ),
+        KickOffPlay::DribbleRight => (
+            mirrored(parameters.dribble_target, -1.0),
+            parameters.dribble_strength,
+        ),
+    }
+}
+
+/// Returns the side of the field with fewer opponents, the left side on ties
+fn open_side(world_state: &WorldState) -> f32 {
+    let Some(ground_to_field) = world_state.robot.ground_to_field else {
+        return 1.0;
+    };
+    let balance: i32 = world_state
+        .opponents
+        .iter()
+        .map(|opponent| ground_to_field * opponent.position)
+        .map(|position| if position.y() > 0.0 { 1 } else { -1 })
+        .sum();
+    if balance > 0 {
+        -1.0
+    } else {
+        1.0
+    }
+}
+
+fn is_ball_away_from_center(world_state: &WorldState, completion_distance: f32) -> bool {
+    world_state
+        .ball
+        .is_some_and(|ball| ball.ball_in_field.coords().norm() > completion_distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use linear_algebra::{vector, Isometry2};
+    use types::obstacles::Obstacle;
+
+    use super::*;
+
+    fn parameters() -> KickOffParameters {
+        KickOffParameters {
+            enable: true,
+            pass_target: point![0.5, 1.5],
+            corner_target: point![3.5, 2.5],
+            dribble_target: point![1.0, 1.0],
+            duration: Duration::from_secs(8),
+            completion_distance: 1.0,
+            ..Default::default()
+        }
+    }
+
+    fn world_state(game_state: FilteredGameState, robots: &[Point2<Field>]) -> WorldState {
+        let mut world_state = WorldState::default();
+        world_state.filtered_game_controller_state = Some(FilteredGameControllerState {
+            game_state,
+            kicking_team: Team::Hulks,
+            game_phase: GamePhase::Normal,
+            ..Default::default()
+        });
+        world_state.robot.ground_to_field = Some(Isometry2::from_parts(vector![0.0, 0.0], 0.0));
+        world_state.opponents = robots
+            .iter()
+            .map(|robot| Obstacle::robot(point![robot.x(), robot.y()], 0.2, 0.3))
+            .collect();
+        world_state
+    }
+
+    fn ready() -> FilteredGameState {
+        FilteredGameState::Ready {
+            kicking_team: Team::Hulks,
+        }
+    }
+
+    fn playing() -> FilteredGameState {
+        FilteredGameState::Playing {
+            ball_is_free: true,
+            kick_off: true,
+        }
+    }
+
+    fn at(seconds: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn play_lines_up_executes_and_completes() {
+        let parameters = KickOffParameters {
+            play: Some(KickOffPlay::ShortDiagonalPass),
+            ..parameters()
+        };
+        let kick_off = KickOffSetPlay::default();
+
+        let kick_off = kick_off.update(&world_state(ready(), &[]), &parameters, at(0));
+        assert!(matches!(kick_off.current.unwrap().phase, Phase::LiningUp));
+
+        let kick_off = kick_off.update(
+            &world_state(FilteredGameState::Set, &[]),
+            &parameters,
+            at(1),
+        );
+        assert!(matches!(kick_off.current.unwrap().phase, Phase::LiningUp));
+
+        let kick_off = kick_off.update(&world_state(playing(), &[]), &parameters, at(2));
+        assert!(matches!(
+            kick_off.current.unwrap().phase,
+            Phase::Executing { since } if since == at(2)
+        ));
+
+        let kick_off = kick_off.update(&world_state(playing(), &[]), &parameters, at(5));
+        assert!(matches!(
+            kick_off.current.unwrap().phase,
+            Phase::Executing { .. }
+        ));
+
+        let kick_off = kick_off.update(&world_state(playing(), &[]), &parameters, at(11));
+        assert!(matches!(kick_off.current.unwrap().phase, Phase::Completed));
+
+        let kick_off = kick_off.update(
+            &world_state(FilteredGameState::Finished, &[]),
+            &parameters,
+            at(12),
+        );
+        assert!(kick_off.current.is_none());
+    }
+
+    #[test]
+    fn plays_rotate_between_kick_offs() {
+        let parameters = parameters();
+        let kick_off = KickOffSetPlay::default();
+
+        let first = kick_off.update(&world_state(ready(), &[]), &parameters, at(0));
+        let first = first.update(
+            &world_state(FilteredGameState::Set, &[]),
+            &parameters,
+            at(1),
+        );
+        let between = first.update(
+            &world_state(FilteredGameState::Initial, &[]),
+            &parameters,
+            at(2),
+        );
+        let second = between.update(&world_state(ready(), &[]), &parameters, at(3));
+
+        assert_eq!(first.current.unwrap().play, KickOffPlay::ALL[0]);
+        assert_eq!(second.current.unwrap().play, KickOffPlay::ALL[1]);
+    }
+
+    #[test]
+    fn side_is_kept_while_opponents_walk_to_their_positions() {
+        let parameters = parameters();
+        let kick_off = KickOffSetPlay::default();
+
+        let kick_off = kick_off.update(
+            &world_state(ready(), &[point![1.0, 1.0]]),
+            &parameters,
+            at(0),
+        );
+        assert_eq!(kick_off.current.unwrap().side, -1.0);
+
+        let kick_off = kick_off.update(
+            &world_state(
+                FilteredGameState::Set,
+                &[point![1.0, -1.0], point![2.0, -1.0]],
+            ),
+            &parameters,
+            at(1),
+        );
+        assert_eq!(kick_off.current.unwrap().side, -1.0);
+    }
+
+    #[test]
+    fn dribbles_ignore_the_open_side() {
+        assert_eq!(
+            target(KickOffPlay::DribbleLeft, -1.0, &parameters()).0,
+            point![1.0, 1.0]
+        );
+        assert_eq!(
+            target(KickOffPlay::DribbleRight, 1.0, &parameters()).0,
+            point![1.0, -1.0]
+        );
+        assert_eq!(
+            target(KickOffPlay::LongKickIntoCorner, -1.0, &parameters()).0,
+            point![3.5, -2.5]
+        );
+    }
+
+    #[test]
+    fn striker_lines_up_facing_the_target() {
+        let kick_off = KickOffSetPlay {
+            next_play: 0,
+            current: Some(KickOff {
+                play: KickOffPlay::DribbleLeft,
+                side: 1.0,
+                phase: Phase::LiningUp,
+            }),
+        };
+        let pose = kick_off.set_pose(&parameters(), 0.3).unwrap();
+
+        assert!(pose.position().x() < 0.0);
+        assert!(pose.position().y() < 0.0);
+        assert!((pose.orientation().angle() - std::f32::consts::FRAC_PI_4).abs() < 0.001);
+    }
+}
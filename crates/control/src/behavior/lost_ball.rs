@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use coordinate_systems::Field;
 use framework::AdditionalOutput;
 use geometry::look_at::LookAt;
@@ -12,13 +14,25 @@ use types::{
 
 use super::walk_to_pose::WalkPathPlanner;
 
+/// Walks towards the last known ball position while looking for the ball, looking right at the
+/// position while it is still likely to be there.
+///
+/// Gives way to the structured search once the robot has been the loser for too long or the last
+/// known ball position is too old to be trusted, instead of circling a stale position forever.
 pub fn execute(
     world_state: &WorldState,
     absolute_last_known_ball_position: Point2<Field>,
+    last_known_ball_confidence: f32,
+    time_as_loser: Duration,
     walk_path_planner: &WalkPathPlanner,
     lost_ball_parameters: &LostBallParameters,
     path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
 ) -> Option<MotionCommand> {
+    if time_as_loser >= lost_ball_parameters.timeout
+        || last_known_ball_confidence < lost_ball_parameters.minimum_confidence
+    {
+        return None;
+    }
     let ground_to_field = world_state.robot.ground_to_field?;
     let walk_target = ground_to_field.inverse()
         * (absolute_last_known_ball_position - lost_ball_parameters.offset_to_last_ball_location);
@@ -26,7 +40,15 @@ pub fn execute(
         ground_to_field.inverse() * absolute_last_known_ball_position;
 
     let orientation = Point2::origin().look_at(&relative_last_known_ball_position);
-    let path = walk_path_planner.plan(
+    let head = if last_known_ball_confidence >= lost_ball_parameters.look_at_confidence {
+        HeadMotion::LookAt {
+            target: relative_last_known_ball_position,
+            camera: None,
+        }
+    } else {
+        HeadMotion::SearchForLostBall
+    };
+    let planned_path = walk_path_planner.plan(
         walk_target,
         ground_to_field,
         None,
@@ -35,9 +57,63 @@ pub fn execute(
         &world_state.rule_obstacles,
         path_obstacles_output,
     );
-    Some(walk_path_planner.walk_with_obstacle_avoiding_arms(
-        HeadMotion::SearchForLostBall,
+    Some(walk_path_planner.walk_along_planned_path(
+        head,
         OrientationMode::Override(orientation),
-        path,
+        planned_path,
     ))
 }
+
+/// Halves with every `half_life` since the ball was last seen, without a half life the position
+/// is only trusted in the cycle the ball was seen.
+pub fn last_known_ball_confidence(age: Duration, half_life: Duration) -> f32 {
+    if half_life.is_zero() {
+        return if age.is_zero() { 1.0 } else { 0.0 };
+    }
+    0.5_f32.powf(age.as_secs_f32() / half_life.as_secs_f32())
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use node_testing::TestParameters;
+
+    use super::*;
+
+    #[test]
+    fn confidence_halves_with_every_half_life() {
+        let half_life = Duration::from_secs(5);
+
+        assert_relative_eq!(last_known_ball_confidence(Duration::ZERO, half_life), 1.0);
+        assert_relative_eq!(
+            last_known_ball_confidence(Duration::from_secs(10), half_life),
+            0.25
+        );
+    }
+
+    #[test]
+    fn confidence_without_half_life_is_a_number() {
+        assert_eq!(
+            last_known_ball_confidence(Duration::ZERO, Duration::ZERO),
+            1.0
+        );
+        assert_eq!(
+            last_known_ball_confidence(Duration::from_millis(12), Duration::ZERO),
+            0.0
+        );
+    }
+
+    #[test]
+    fn default_confidence_outlasts_the_timeout() {
+        let parameters: LostBallParameters = TestParameters::load_default()
+            .unwrap()
+            .get("behavior.lost_ball")
+            .unwrap();
+
+        // the loser gives up after the timeout, the confidence must not stop it earlier
+        let confidence_at_timeout =
+            last_known_ball_confidence(parameters.timeout, parameters.confidence_half_life);
+        assert!(confidence_at_timeout >= parameters.minimum_confidence);
+        assert!(parameters.look_at_confidence > parameters.minimum_confidence);
+    }
+}
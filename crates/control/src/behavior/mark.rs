@@ -0,0 +1,301 @@
+use coordinate_systems::Field;
+use framework::AdditionalOutput;
+use geometry::look_at::LookAt;
+use linear_algebra::{distance, point, Point2, Pose2};
+use types::{
+    field_dimensions::FieldDimensions,
+    filtered_game_state::FilteredGameState,
+    motion_command::MotionCommand,
+    parameters::{IllegalDefenderParameters, MarkingParameters},
+    path_obstacles::PathObstacle,
+    world_state::WorldState,
+};
+
+use super::{
+    head::LookAction, illegal_defender::project_outside_own_penalty_area,
+    walk_to_pose::WalkAndStand,
+};
+
+/// Picks the opponent to shadow while an opponent controls the ball: the unmarked one closest to
+/// the own goal, the ball carrier itself is left to the striker.
+///
+/// Opponents are followed by their obstacle filter identity, a marked opponent is kept as long as
+/// it is tracked and no teammate marks it from a better position.
+pub fn update(
+    marked_opponent: Option<usize>,
+    world_state: &WorldState,
+    field_dimensions: &FieldDimensions,
+    parameters: &MarkingParameters,
+    illegal_defender: &IllegalDefenderParameters,
+) -> Option<usize> {
+    if !parameters.enable
+        || !matches!(
+            world_state.filtered_game_controller_state?.game_state,
+            FilteredGameState::Playing {
+                ball_is_free: true,
+                ..
+            }
+        )
+    {
+        return None;
+    }
+    let ground_to_field = world_state.robot.ground_to_field?;
+    let ball = world_state.ball?.ball_in_field;
+    let own_position = ground_to_field.as_pose().position();
+    let opponents = tracked_opponents(world_state);
+    let ball_carrier = ball_carrier(
+        &opponents,
+        ball,
+        own_position,
+        &world_state.teammate_positions,
+        parameters.possession_distance,
+    )?;
+    select_opponent(
+        &opponents,
+        ball_carrier,
+        own_position,
+        &world_state.teammate_positions,
+        own_goal(field_dimensions),
+        marked_opponent,
+        parameters,
+        |opponent| marking_position(opponent, field_dimensions, parameters, illegal_defender),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute(
+    world_state: &WorldState,
+    marked_opponent: Option<usize>,
+    field_dimensions: &FieldDimensions,
+    parameters: &MarkingParameters,
+    illegal_defender: &IllegalDefenderParameters,
+    walk_and_stand: &WalkAndStand,
+    look_action: &LookAction,
+    path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
+) -> Option<MotionCommand> {
+    let marked_opponent = marked_opponent?;
+    let ground_to_field = world_state.robot.ground_to_field?;
+    let ball = world_state.ball?.ball_in_field;
+    let (_, opponent) = tracked_opponents(world_state)
+        .into_iter()
+        .find(|(id, _)| *id == marked_opponent)?;
+    let position = marking_position(opponent, field_dimensions, parameters, illegal_defender);
+    let pose = Pose2::new(position.coords(), position.look_at(&ball).angle());
+    walk_and_stand.execute(
+        ground_to_field.inverse() * pose,
+        look_action.execute(),
+        path_obstacles_output,
+    )
+}
+
+/// Opponents tracked by the obstacle filter with their identity
+fn tracked_opponents(world_state: &WorldState) -> Vec<(usize, Point2<Field>)> {
+    let Some(ground_to_field) = world_state.robot.ground_to_field else {
+        return Vec::new();
+    };
+    world_state
+        .opponents
+        .iter()
+        .filter_map(|opponent| Some((opponent.id?, ground_to_field * opponent.position)))
+        .collect()
+}
+
+/// The opponent close to the ball and closer to it than any robot of the own team
+fn ball_carrier(
+    opponents: &[(usize, Point2<Field>)],
+    ball: Point2<Field>,
+    own_position: Point2<Field>,
+    teammates: &[Point2<Field>],
+    possession_distance: f32,
+) -> Option<usize> {
+    let (id, opponent) = opponents
+        .iter()
+        .min_by(|(_, left), (_, right)| distance(*left, ball).total_cmp(&distance(*right, ball)))?;
+    let opponent_distance = distance(*opponent, ball);
+    let is_closest = teammates
+        .iter()
+        .chain([&own_position])
+        .all(|robot| distance(*robot, ball) > opponent_distance);
+    (opponent_distance < possession_distance && is_closest).then_some(*id)
+}
+
+/// Teammates claim the opponents from the most dangerous one on, each teammate the first one whose
+/// marking position it is close to and closer to than this robot. Every robot resolves the claims
+/// the same way from the communicated positions, such that no opponent is marked twice.
+#[allow(clippy::too_many_arguments)]
+fn select_opponent(
+    opponents: &[(usize, Point2<Field>)],
+    ball_carrier: usize,
+    own_position: Point2<Field>,
+    teammates: &[Point2<Field>],
+    own_goal: Point2<Field>,
+    marked_opponent: Option<usize>,
+    parameters: &MarkingParameters,
+    marking_position: impl Fn(Point2<Field>) -> Point2<Field>,
+) -> Option<usize> {
+    let mut threats: Vec<_> = opponents
+        .iter()
+        .filter(|(id, opponent)| {
+            *id != ball_carrier
+                && distance(*opponent, own_goal) < parameters.maximum_distance_to_own_goal
+        })
+        .collect();
+    threats.sort_by(|(_, left), (_, right)| {
+        distance(*left, own_goal).total_cmp(&distance(*right, own_goal))
+    });
+    let mut unclaiming_teammates = teammates.to_vec();
+    let unclaimed: Vec<_> = threats
+        .into_iter()
+        .filter(|(_, opponent)| {
+            let position = marking_position(*opponent);
+            let own_distance = distance(own_position, position);
+            let claiming_teammate = unclaiming_teammates.iter().position(|teammate| {
+                let teammate_distance = distance(*teammate, position);
+                teammate_distance <= parameters.marked_distance && teammate_distance < own_distance
+            });
+            match claiming_teammate {
+                Some(index) => {
+                    unclaiming_teammates.swap_remove(index);
+                    false
+                }
+                None => true,
+            }
+        })
+        .collect();
+    unclaimed
+        .iter()
+        .find(|(id, _)| Some(*id) == marked_opponent)
+        .or(unclaimed.first())
+        .map(|(id, _)| *id)
+}
+
+/// On the line from the opponent to the own goal, blocking both passes into its feet and its shot.
+/// Only the keeper may defend from inside the own penalty area, the position is kept outside.
+fn marking_position(
+    opponent: Point2<Field>,
+    field_dimensions: &FieldDimensions,
+    parameters: &MarkingParameters,
+    illegal_defender: &IllegalDefenderParameters,
+) -> Point2<Field> {
+    let own_goal = own_goal(field_dimensions);
+    let opponent_to_goal = own_goal - opponent;
+    let position = if opponent_to_goal.norm() <= parameters.marking_distance {
+        own_goal
+    } else {
+        opponent + opponent_to_goal.normalize() * parameters.marking_distance
+    };
+    project_outside_own_penalty_area(
+        Pose2::new(position.coords(), 0.0),
+        field_dimensions,
+        illegal_defender,
+    )
+    .position()
+}
+
+fn own_goal(field_dimensions: &FieldDimensions) -> Point2<Field> {
+    point![-field_dimensions.length / 2.0, 0.0]
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    fn parameters() -> MarkingParameters {
+        MarkingParameters {
+            enable: true,
+            possession_distance: 0.5,
+            marking_distance: 1.0,
+            marked_distance: 1.0,
+            maximum_distance_to_own_goal: 6.0,
+        }
+    }
+
+    fn field_dimensions() -> FieldDimensions {
+        FieldDimensions {
+            length: 9.0,
+            width: 6.0,
+            penalty_area_length: 1.65,
+            penalty_area_width: 4.0,
+            border_strip_width: 0.7,
+            ..Default::default()
+        }
+    }
+
+    fn illegal_defender() -> IllegalDefenderParameters {
+        IllegalDefenderParameters {
+            maximum_number_of_robots_in_penalty_area: 3,
+            penalty_area_margin: 0.2,
+            occupancy_hysteresis: 0.3,
+        }
+    }
+
+    fn select(
+        opponents: &[(usize, Point2<Field>)],
+        teammates: &[Point2<Field>],
+        marked_opponent: Option<usize>,
+    ) -> Option<usize> {
+        select_opponent(
+            opponents,
+            0,
+            point![-1.0, 0.0],
+            teammates,
+            own_goal(&field_dimensions()),
+            marked_opponent,
+            &parameters(),
+            |opponent| {
+                marking_position(
+                    opponent,
+                    &field_dimensions(),
+                    &parameters(),
+                    &illegal_defender(),
+                )
+            },
+        )
+    }
+
+    #[test]
+    fn most_dangerous_unmarked_opponent_is_selected_and_kept() {
+        let opponents = [
+            (0, point![0.0, 0.0]),
+            (1, point![-1.0, 2.5]),
+            (2, point![-2.0, -1.0]),
+        ];
+
+        assert_eq!(select(&opponents, &[], None), Some(2));
+
+        // a teammate right at the marking position of the closest opponent covers it
+        let teammate = marking_position(
+            point![-2.0, -1.0],
+            &field_dimensions(),
+            &parameters(),
+            &illegal_defender(),
+        );
+        assert_eq!(select(&opponents, &[teammate], None), Some(1));
+
+        assert_eq!(select(&opponents, &[], Some(1)), Some(1));
+    }
+
+    #[test]
+    fn teammate_claims_only_one_opponent() {
+        // both marking positions are close to the teammate, it claims the more dangerous one
+        let opponents = [(1, point![-1.5, 0.4]), (2, point![-1.5, -0.5])];
+        let teammate = point![-2.4, 0.0];
+
+        assert_eq!(select(&opponents, &[teammate], None), Some(2));
+    }
+
+    #[test]
+    fn marking_position_stays_outside_own_penalty_area() {
+        let position = marking_position(
+            point![-3.0, 0.0],
+            &field_dimensions(),
+            &parameters(),
+            &illegal_defender(),
+        );
+
+        // the penalty area ends 1.65 m in front of the goal line, extended by the margin
+        assert_relative_eq!(position, point![-2.65, 0.0], epsilon = 0.001);
+    }
+}
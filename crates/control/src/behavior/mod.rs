@@ -1,22 +1,44 @@
+mod action_commitment;
+mod arm_tucking;
+mod block;
 mod calibrate;
+mod clear_ball;
 mod defend;
+mod demo;
 mod dribble;
+mod emergency_clearance;
 mod fall_safely;
+mod free_kick;
 mod head;
+mod hold_position;
+mod illegal_defender;
 mod initial;
 mod intercept_ball;
 mod jump;
+mod kick_off;
 mod look_around;
 mod lost_ball;
+mod mark;
 pub mod node;
+mod orbit_ball;
+mod pass;
 mod penalize;
+mod penalty_rebound;
+mod penalty_shot;
 mod prepare_jump;
+mod receive_pass;
+mod rest;
+mod return_from_penalty;
 mod search;
 mod sit_down;
 mod stand;
 mod stand_up;
+mod strategy;
 mod support;
 mod unstiff;
+mod walk_in;
 mod walk_to_kick_off;
 mod walk_to_penalty_kick;
 pub mod walk_to_pose;
+mod wall;
+mod yield_goal_mouth;
@@ -1,64 +1,106 @@
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
-use color_eyre::Result;
+use color_eyre::{eyre::bail, Result};
 use serde::{Deserialize, Serialize};
 
 use context_attribute::context;
 use coordinate_systems::Field;
-use framework::{AdditionalOutput, MainOutput};
+use framework::{AdditionalOutput, MainOutput, PersistentState};
 use linear_algebra::{point, Point2};
+use log::warn;
 use spl_network_messages::{GamePhase, SubState, Team};
 use types::{
-    action::Action,
+    action::{Action, ActionEvaluation, ActionOutcome, ActionResolutionError, RejectionReason},
     cycle_time::CycleTime,
+    fall_state::FallState,
     field_dimensions::FieldDimensions,
     filtered_game_controller_state::FilteredGameControllerState,
     filtered_game_state::FilteredGameState,
-    motion_command::MotionCommand,
+    motion_command::{HeadMotion, MotionCommand, WalkSpeed},
     parameters::{
-        BehaviorParameters, InWalkKicksParameters, InterceptBallParameters, LostBallParameters,
+        ActionPriorityParameters, BehaviorParameters, InWalkKicksParameters,
+        InterceptBallParameters, LostBallParameters, RobotCapabilitiesParameters,
+        RoleActionsParameters,
     },
     path_obstacles::PathObstacle,
     planned_path::PathSegment,
     primary_state::PrimaryState,
     roles::Role,
+    rule_set::RuleSet,
     step_plan::Step,
     support_foot::Side,
+    walk_in_lane::WalkInLane,
     world_state::WorldState,
 };
 
 use crate::dribble_path_planner;
 
 use super::{
-    calibrate,
+    action_commitment::ActionCommitment,
+    arm_tucking, block, calibrate, clear_ball,
     defend::Defend,
-    dribble, fall_safely,
+    demo, dribble, emergency_clearance, fall_safely, free_kick,
     head::LookAction,
-    initial, intercept_ball, jump, look_around, lost_ball, penalize, prepare_jump, search,
-    sit_down, stand, stand_up, support, unstiff, walk_to_kick_off, walk_to_penalty_kick,
+    hold_position, illegal_defender, initial, intercept_ball, jump,
+    kick_off::{self, KickOffSetPlay},
+    look_around, lost_ball, mark,
+    orbit_ball::{self, BallBlockade},
+    pass, penalize, penalty_rebound,
+    penalty_shot::{self, PenaltyShot},
+    prepare_jump, receive_pass, rest, return_from_penalty, search, sit_down, stand, stand_up,
+    strategy::{self, Tempo},
+    support, unstiff,
+    walk_in::{self, WalkIn},
+    walk_to_kick_off, walk_to_penalty_kick,
     walk_to_pose::{WalkAndStand, WalkPathPlanner},
+    wall, yield_goal_mouth,
 };
 
 #[derive(Deserialize, Serialize)]
 pub struct Behavior {
     last_motion_command: MotionCommand,
     last_known_ball_position: Point2<Field>,
+    last_known_ball_time: Option<SystemTime>,
+    loser_since: Option<SystemTime>,
     active_since: Option<SystemTime>,
+    ready_since: Option<SystemTime>,
+    action_commitment: ActionCommitment,
+    penalty_shot: Option<PenaltyShot>,
+    kick_off: KickOffSetPlay,
+    ball_blockade: BallBlockade,
+    marked_opponent: Option<usize>,
+    support_position: Option<Point2<Field>>,
+    search_cell: Option<usize>,
+    is_own_penalty_area_occupied: bool,
+    /// Latest valid priority list, an invalid one set at runtime is ignored
+    action_priority: ActionPriorityParameters,
+    rejected_action_priority: Option<ActionPriorityParameters>,
 }
 
 #[context]
-pub struct CreationContext {}
+pub struct CreationContext {
+    action_priority: Parameter<ActionPriorityParameters, "behavior.action_priority">,
+}
 
 #[context]
 pub struct CycleContext {
     path_obstacles_output: AdditionalOutput<Vec<PathObstacle>, "path_obstacles">,
     dribble_path_obstacles_output: AdditionalOutput<Vec<PathObstacle>, "dribble_path_obstacles">,
     active_action_output: AdditionalOutput<Action, "active_action">,
+    action_evaluations_output: AdditionalOutput<Vec<ActionEvaluation>, "action_evaluations">,
+    action_resolution_error_output:
+        AdditionalOutput<Option<ActionResolutionError>, "action_resolution_error">,
+    walk_in_lane_output: AdditionalOutput<Option<WalkInLane>, "walk_in_lane">,
+    predicted_goal_line_crossing_output:
+        AdditionalOutput<Option<Point2<Field>>, "predicted_goal_line_crossing">,
 
     has_ground_contact: Input<bool, "has_ground_contact">,
+    is_shutdown_requested: Input<bool, "is_shutdown_requested">,
     world_state: Input<WorldState, "world_state">,
     cycle_time: Input<CycleTime, "cycle_time">,
 
+    yields_blocked_ball: CyclerState<bool, "yields_blocked_ball">,
+
     parameters: Parameter<BehaviorParameters, "behavior">,
     in_walk_kicks: Parameter<InWalkKicksParameters, "in_walk_kicks">,
     field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
@@ -66,6 +108,10 @@ pub struct CycleContext {
     intercept_ball_parameters: Parameter<InterceptBallParameters, "behavior.intercept_ball">,
     maximum_step_size: Parameter<Step, "step_planner.max_step_size">,
     striker_set_position: Parameter<Point2<Field>, "behavior.role_positions.striker_set_position">,
+    rule_set: Parameter<RuleSet, "rule_set">,
+    robot_capabilities: Parameter<RobotCapabilitiesParameters, "robot_capabilities">,
+    kick_pose_obstacle_radius: Parameter<f32, "kick_selector.kick_pose_obstacle_radius">,
+    angle_distance_weight: Parameter<f32, "kick_selector.angle_distance_weight">,
 }
 
 #[context]
@@ -76,11 +122,25 @@ pub struct MainOutputs {
 }
 
 impl Behavior {
-    pub fn new(_context: CreationContext) -> Result<Self> {
+    pub fn new(context: CreationContext) -> Result<Self> {
+        validate_action_priority(context.action_priority)?;
         Ok(Self {
             last_motion_command: MotionCommand::Unstiff,
             last_known_ball_position: point![0.0, 0.0],
+            last_known_ball_time: None,
+            loser_since: None,
             active_since: None,
+            ready_since: None,
+            action_commitment: Default::default(),
+            penalty_shot: None,
+            kick_off: Default::default(),
+            ball_blockade: Default::default(),
+            marked_opponent: None,
+            support_position: None,
+            search_cell: None,
+            is_own_penalty_area_occupied: false,
+            action_priority: context.action_priority.clone(),
+            rejected_action_priority: None,
         })
     }
 
@@ -92,9 +152,25 @@ impl Behavior {
                 dribble_path: None.into(),
             });
         }
+        if *context.is_shutdown_requested {
+            // the shutdown sequencer removes the stiffness once the robot is sitting, an unstiff
+            // robot is not stiffened again only to sit down
+            let command = match self.last_motion_command {
+                MotionCommand::Unstiff => MotionCommand::Unstiff,
+                _ => MotionCommand::SitDown {
+                    head: HeadMotion::Unstiff,
+                },
+            };
+            self.last_motion_command = command.clone();
+            return Ok(MainOutputs {
+                motion_command: command.into(),
+                dribble_path: None.into(),
+            });
+        }
 
         if let Some(ball_state) = &world_state.ball {
             self.last_known_ball_position = ball_state.ball_in_field;
+            self.last_known_ball_time = Some(context.cycle_time.start_time);
         }
 
         let now = context.cycle_time.start_time;
@@ -110,18 +186,64 @@ impl Behavior {
             ) => {}
             (Some(_), _) => self.active_since = None,
         }
+        match world_state.robot.primary_state {
+            PrimaryState::Ready => {
+                self.ready_since.get_or_insert(now);
+            }
+            _ => self.ready_since = None,
+        }
+        match world_state.robot.role {
+            Role::Loser => {
+                self.loser_since.get_or_insert(now);
+            }
+            _ => self.loser_since = None,
+        }
+        self.penalty_shot = penalty_shot::update(
+            self.penalty_shot,
+            world_state,
+            &context.parameters.penalty_shot,
+            now,
+        );
+        self.kick_off = self
+            .kick_off
+            .update(world_state, &context.parameters.kick_off, now);
+        self.ball_blockade = orbit_ball::update(
+            self.ball_blockade,
+            world_state,
+            &context.parameters.orbit_ball,
+            now,
+        );
+        *context.yields_blocked_ball = self.ball_blockade.yields_ball();
+        self.marked_opponent = mark::update(
+            self.marked_opponent,
+            world_state,
+            context.field_dimensions,
+            &context.parameters.marking,
+            &context.parameters.illegal_defender,
+        );
 
-        let mut actions = vec![
-            Action::Unstiff,
-            Action::SitDown,
-            Action::Penalize,
-            Action::Initial,
-            Action::FallSafely,
-            Action::StandUp,
-            Action::Stand,
-            Action::InterceptBall,
-            Action::Calibrate,
-        ];
+        self.update_action_priority(&context.parameters.action_priority);
+        let mut actions = self.action_priority.base_actions.clone();
+        // leaving the area around the ball during opponent free kicks comes before everything else
+        if matches!(
+            free_kick_kicking_team(world_state.filtered_game_controller_state),
+            Some(Team::Opponent | Team::Uncertain)
+        ) && !matches!(
+            world_state.robot.role,
+            Role::Keeper | Role::ReplacementKeeper
+        ) {
+            actions.push(Action::KeepFreeKickDistance);
+        }
+        // a returning robot only knows its sideline after seeing enough lines, playing the ball
+        // or taking a position before that risks an own goal
+        if world_state.robot.is_converging_after_reentry {
+            actions.push(Action::ReturnFromPenalty);
+        }
+        // the keeper tries to catch the ball with a jump before it runs after it
+        if world_state.robot.role != Role::Keeper {
+            actions.push(Action::EmergencyClearance);
+        }
+        actions.extend([Action::InterceptBall, Action::Calibrate]);
 
         if let Some(active_since) = self.active_since {
             if now.duration_since(active_since)? < context.parameters.initial_lookaround_duration {
@@ -133,9 +255,33 @@ impl Behavior {
             .filtered_game_controller_state
             .map(|filtered_game_controller_state| filtered_game_controller_state.game_state);
 
+        match (
+            world_state.robot.role,
+            free_kick_kicking_team(world_state.filtered_game_controller_state),
+        ) {
+            (Role::Striker, Some(Team::Hulks)) => actions.push(Action::TakeFreeKick),
+            (Role::StrikerSupporter, Some(Team::Hulks)) => actions.push(Action::SupportFreeKick),
+            (Role::Striker | Role::StrikerSupporter, Some(Team::Opponent | Team::Uncertain)) => {
+                actions.push(Action::DefendFreeKick)
+            }
+            _ => {}
+        }
+        actions.push(Action::FormWall);
+        actions.push(Action::AwaitPenaltyRebound);
+
         match world_state.robot.role {
-            Role::DefenderLeft => actions.push(Action::DefendLeft),
-            Role::DefenderRight => actions.push(Action::DefendRight),
+            Role::DefenderLeft => {
+                if block::is_nearest_defender_to_shot_line(world_state, context.field_dimensions) {
+                    actions.push(Action::Block);
+                }
+                actions.push(Action::DefendLeft);
+            }
+            Role::DefenderRight => {
+                if block::is_nearest_defender_to_shot_line(world_state, context.field_dimensions) {
+                    actions.push(Action::Block);
+                }
+                actions.push(Action::DefendRight);
+            }
             Role::Keeper => match world_state.filtered_game_controller_state {
                 Some(FilteredGameControllerState {
                     game_phase: GamePhase::PenaltyShootout { .. },
@@ -150,18 +296,50 @@ impl Behavior {
                     actions.push(Action::Jump);
                     actions.push(Action::PrepareJump);
                 }
-                _ => actions.push(Action::DefendGoal),
+                _ => {
+                    actions.push(Action::Jump);
+                    actions.push(Action::EmergencyClearance);
+                    actions.push(Action::YieldGoalMouth);
+                    actions.push(Action::ClearBall);
+                    actions.push(Action::DefendGoal);
+                }
             },
-            Role::Loser => actions.push(Action::SearchForLostBall),
-            Role::MidfielderLeft => actions.push(Action::SupportLeft),
-            Role::MidfielderRight => actions.push(Action::SupportRight),
-            Role::ReplacementKeeper => actions.push(Action::DefendGoal),
+            Role::Loser => {
+                actions.push(Action::SearchForLostBall);
+                actions.push(Action::Search);
+            }
+            Role::MidfielderLeft => {
+                actions.push(Action::ReceivePass);
+                actions.push(Action::MarkOpponent);
+                actions.push(Action::SupportLeft);
+            }
+            Role::MidfielderRight => {
+                actions.push(Action::ReceivePass);
+                actions.push(Action::MarkOpponent);
+                actions.push(Action::SupportRight);
+            }
+            Role::ReplacementKeeper => {
+                actions.push(Action::YieldGoalMouth);
+                actions.push(Action::ClearBall);
+                actions.push(Action::DefendGoal);
+            }
             Role::Searcher => actions.push(Action::Search),
             Role::Striker => match filtered_game_state {
                 None
                 | Some(FilteredGameState::Playing {
                     ball_is_free: true, ..
                 }) => {
+                    actions.push(Action::TakeKickOff);
+                    actions.push(Action::TakePenaltyShot);
+                    // a faster teammate also claims the ball, do not chase it together
+                    if world_state
+                        .teammate_striker
+                        .is_some_and(|teammate_striker| teammate_striker.is_faster)
+                    {
+                        actions.push(Action::SupportStriker);
+                    }
+                    actions.push(Action::OrbitBall);
+                    actions.push(Action::Pass);
                     actions.push(Action::Dribble);
                 }
                 Some(FilteredGameState::Ready {
@@ -183,26 +361,91 @@ impl Behavior {
                     _ => actions.push(Action::DefendKickOff),
                 },
             },
-            Role::StrikerSupporter => actions.push(Action::SupportStriker),
+            Role::StrikerSupporter => {
+                actions.push(Action::ReceivePass);
+                actions.push(Action::SupportStriker);
+            }
         };
+        actions.extend_from_slice(
+            self.action_priority
+                .role_additions
+                .actions(world_state.robot.role),
+        );
 
+        let strategy = strategy::select(
+            world_state.filtered_game_controller_state,
+            &context.parameters.strategy,
+        );
+        let mut role_positions = strategy::modulate_role_positions(
+            &context.parameters.role_positions,
+            strategy,
+            &context.parameters.strategy,
+        );
+        let tempo = strategy::select_tempo(&world_state.ball_possession, &context.parameters.tempo);
+        role_positions = strategy::modulate_role_positions_for_tempo(
+            &role_positions,
+            tempo,
+            &context.parameters.tempo,
+        );
+        let is_holding_defense = context.parameters.endgame.enable
+            && world_state
+                .filtered_game_controller_state
+                .is_some_and(|game_controller_state| {
+                    game_controller_state
+                        .is_half_ending_within(context.parameters.endgame.hold_defense_duration)
+                });
+        let is_game_state_stale = context.parameters.stale_game_state.enable
+            && world_state
+                .filtered_game_controller_state
+                .is_some_and(|game_controller_state| {
+                    game_controller_state.staleness
+                        > context.parameters.stale_game_state.minimum_staleness
+                });
+        if is_holding_defense || is_game_state_stale {
+            role_positions.defender_aggressive_ring_radius =
+                role_positions.defender_passive_ring_radius;
+        }
+
+        let own_penalty_area_obstacle = illegal_defender::own_penalty_area_obstacle(
+            world_state,
+            context.field_dimensions,
+            &context.parameters.illegal_defender,
+            self.is_own_penalty_area_occupied,
+        );
+        self.is_own_penalty_area_occupied = own_penalty_area_obstacle.is_some();
         let walk_path_planner = WalkPathPlanner::new(
             context.field_dimensions,
             &world_state.obstacles,
             &context.parameters.path_planning,
             &self.last_motion_command,
+            own_penalty_area_obstacle,
+        );
+        let walk_in_lane = walk_in::assign_lane(
+            world_state,
+            context.field_dimensions,
+            &context.parameters.ready_walk_in,
         );
+        context
+            .walk_in_lane_output
+            .fill_if_subscribed(|| walk_in_lane);
+        let time_in_ready = self
+            .ready_since
+            .and_then(|ready_since| now.duration_since(ready_since).ok())
+            .unwrap_or_default();
         let walk_and_stand = WalkAndStand::new(
             world_state,
             &context.parameters.walk_and_stand,
             &walk_path_planner,
             &self.last_motion_command,
+            walk_in_lane
+                .map(|lane| WalkIn::new(lane, time_in_ready, &context.parameters.ready_walk_in)),
         );
         let look_action = LookAction::new(world_state);
         let defend = Defend::new(
             world_state,
             context.field_dimensions,
-            &context.parameters.role_positions,
+            &role_positions,
+            &context.parameters.illegal_defender,
             &walk_and_stand,
             &look_action,
         );
@@ -214,148 +457,427 @@ impl Behavior {
             &mut dribble_path_obstacles,
         );
 
-        let dribble_path = dribble_path_planner::plan(
-            &walk_path_planner,
-            world_state,
-            &context.parameters.dribbling,
-            &mut dribble_path_obstacles_output,
-        );
+        let dribble_kick_decisions = world_state.kick_decisions.as_ref().map(|kick_decisions| {
+            dribble_path_planner::constrain_to_corridor(
+                kick_decisions,
+                world_state,
+                context.in_walk_kicks,
+                context.field_dimensions,
+                &context.parameters.dribbling.corridor,
+                *context.kick_pose_obstacle_radius,
+                *context.angle_distance_weight,
+            )
+        });
+        let dribble_path = dribble_kick_decisions.as_ref().and_then(|kick_decisions| {
+            dribble_path_planner::plan(
+                &walk_path_planner,
+                world_state,
+                kick_decisions,
+                &context.parameters.dribbling,
+                &mut dribble_path_obstacles_output,
+            )
+        });
         context
             .dribble_path_obstacles_output
             .fill_if_subscribed(|| dribble_path_obstacles.clone().unwrap_or_default());
 
-        let (action, motion_command) = actions
-            .iter()
-            .find_map(|action| {
-                let motion_command = match action {
-                    Action::Unstiff => unstiff::execute(world_state),
-                    Action::SitDown => sit_down::execute(world_state),
-                    Action::Penalize => penalize::execute(world_state),
-                    Action::Initial => initial::execute(world_state),
-                    Action::FallSafely => {
-                        fall_safely::execute(world_state, *context.has_ground_contact)
-                    }
-                    Action::StandUp => stand_up::execute(world_state),
-                    Action::LookAround => look_around::execute(world_state),
-                    Action::InterceptBall => intercept_ball::execute(
-                        world_state,
-                        *context.intercept_ball_parameters,
-                        *context.maximum_step_size,
-                    ),
-                    Action::Calibrate => calibrate::execute(world_state),
-                    Action::DefendGoal => defend.goal(&mut context.path_obstacles_output),
-                    Action::DefendKickOff => defend.kick_off(&mut context.path_obstacles_output),
-                    Action::DefendLeft => defend.left(&mut context.path_obstacles_output),
-                    Action::DefendRight => defend.right(&mut context.path_obstacles_output),
-                    Action::DefendPenaltyKick => {
-                        defend.penalty_kick(&mut context.path_obstacles_output)
-                    }
-                    Action::Stand => stand::execute(world_state, context.field_dimensions),
-                    Action::Dribble => dribble::execute(
-                        world_state,
-                        &walk_path_planner,
-                        context.in_walk_kicks,
-                        &context.parameters.dribbling,
-                        dribble_path.clone(),
-                    ),
-                    Action::Jump => jump::execute(world_state),
-                    Action::PrepareJump => prepare_jump::execute(world_state),
-                    Action::Search => search::execute(
-                        world_state,
-                        &walk_path_planner,
-                        &walk_and_stand,
-                        context.field_dimensions,
-                        &context.parameters.search,
-                        &mut context.path_obstacles_output,
-                    ),
-                    Action::SearchForLostBall => lost_ball::execute(
-                        world_state,
-                        self.last_known_ball_position,
-                        &walk_path_planner,
-                        context.lost_ball_parameters,
-                        &mut context.path_obstacles_output,
-                    ),
-                    Action::SupportLeft => support::execute(
-                        world_state,
-                        context.field_dimensions,
-                        Some(Side::Left),
-                        context
-                            .parameters
-                            .role_positions
-                            .left_midfielder_distance_to_ball,
-                        context
-                            .parameters
-                            .role_positions
-                            .left_midfielder_maximum_x_in_ready_and_when_ball_is_not_free,
-                        context.parameters.role_positions.left_midfielder_minimum_x,
-                        &walk_and_stand,
-                        &look_action,
-                        &mut context.path_obstacles_output,
-                    ),
-                    Action::SupportRight => support::execute(
-                        world_state,
-                        context.field_dimensions,
-                        Some(Side::Right),
-                        context
-                            .parameters
-                            .role_positions
-                            .right_midfielder_distance_to_ball,
-                        context
-                            .parameters
-                            .role_positions
-                            .right_midfielder_maximum_x_in_ready_and_when_ball_is_not_free,
-                        context.parameters.role_positions.right_midfielder_minimum_x,
-                        &walk_and_stand,
-                        &look_action,
-                        &mut context.path_obstacles_output,
-                    ),
-                    Action::SupportStriker => support::execute(
-                        world_state,
-                        context.field_dimensions,
-                        None,
-                        context
-                            .parameters
-                            .role_positions
-                            .striker_supporter_distance_to_ball,
-                        context
-                            .parameters
-                            .role_positions
-                            .striker_supporter_maximum_x_in_ready_and_when_ball_is_not_free,
-                        context
-                            .parameters
-                            .role_positions
-                            .striker_supporter_minimum_x,
-                        &walk_and_stand,
-                        &look_action,
-                        &mut context.path_obstacles_output,
-                    ),
-                    Action::WalkToKickOff => walk_to_kick_off::execute(
-                        world_state,
-                        &walk_and_stand,
-                        &look_action,
-                        &mut context.path_obstacles_output,
-                        *context.striker_set_position,
-                    ),
-                    Action::WalkToPenaltyKick => walk_to_penalty_kick::execute(
-                        world_state,
-                        &walk_and_stand,
-                        &look_action,
-                        &mut context.path_obstacles_output,
-                        context.field_dimensions,
-                    ),
-                }?;
-                Some((action, motion_command))
-            })
-            .unwrap_or_else(|| {
-                panic!(
-                    "there has to be at least one action available, world_state: {world_state:#?}",
-                )
+        if context.parameters.demo.enable {
+            actions = vec![
+                Action::Unstiff,
+                Action::Initial,
+                Action::FallSafely,
+                Action::StandUp,
+                Action::Demo,
+                Action::Dribble,
+            ];
+        }
+        if context.parameters.hold_position {
+            actions = vec![
+                Action::Unstiff,
+                Action::FallSafely,
+                Action::StandUp,
+                Action::HoldPosition,
+            ];
+        }
+
+        self.action_commitment
+            .prioritize(&mut actions, now, &context.parameters.action_commitment);
+
+        // a degraded robot stands instead of doing anything the team can do without it
+        if world_state.robot.robot_condition.is_degraded() {
+            rest::insert_before_non_critical_actions(
+                &mut actions,
+                &context.parameters.rest.non_critical_actions,
+            );
+        }
+
+        let mut action_evaluations = Vec::with_capacity(actions.len());
+        let selected_action = actions.iter().find_map(|action| {
+            if let Err(reason) = gate(*action, world_state, *context.has_ground_contact) {
+                action_evaluations.push(ActionEvaluation {
+                    action: *action,
+                    outcome: ActionOutcome::Rejected { reason },
+                });
+                return None;
+            }
+            let motion_command = match action {
+                Action::Unstiff => unstiff::execute(world_state),
+                Action::SitDown => sit_down::execute(world_state),
+                Action::Penalize => penalize::execute(world_state, &context.parameters.reentry),
+                Action::Initial => initial::execute(world_state),
+                Action::FallSafely => {
+                    fall_safely::execute(world_state, *context.has_ground_contact)
+                }
+                Action::StandUp => stand_up::execute(world_state),
+                Action::HoldPosition => hold_position::execute(world_state),
+                Action::Demo => demo::execute(
+                    world_state,
+                    &context.parameters.demo,
+                    &walk_and_stand,
+                    &mut context.path_obstacles_output,
+                ),
+                Action::LookAround => look_around::execute(world_state),
+                Action::ReturnFromPenalty => return_from_penalty::execute(
+                    world_state,
+                    context.field_dimensions,
+                    &context.parameters.reentry,
+                    &walk_and_stand,
+                    &mut context.path_obstacles_output,
+                ),
+                Action::EmergencyClearance => emergency_clearance::execute(
+                    world_state,
+                    context.field_dimensions,
+                    &context.parameters.emergency_clearance,
+                    &walk_path_planner,
+                    context.in_walk_kicks,
+                    &context.parameters.dribbling,
+                    dribble_kick_decisions.as_deref(),
+                    dribble_path.clone(),
+                ),
+                Action::InterceptBall => intercept_ball::execute(
+                    world_state,
+                    *context.intercept_ball_parameters,
+                    *context.maximum_step_size,
+                ),
+                Action::Calibrate => calibrate::execute(world_state),
+                Action::Rest => rest::execute(world_state, &look_action),
+                Action::DefendGoal => defend.goal(&mut context.path_obstacles_output),
+                Action::DefendKickOff => defend.kick_off(&mut context.path_obstacles_output),
+                Action::DefendLeft => defend.left(&mut context.path_obstacles_output),
+                Action::DefendRight => defend.right(&mut context.path_obstacles_output),
+                Action::DefendPenaltyKick => {
+                    defend.penalty_kick(&mut context.path_obstacles_output)
+                }
+                Action::Stand => stand::execute(world_state, context.field_dimensions),
+                Action::TakeKickOff => kick_off::execute(
+                    world_state,
+                    &self.kick_off,
+                    &context.parameters.kick_off,
+                    context.in_walk_kicks,
+                    &walk_and_stand,
+                    &mut context.path_obstacles_output,
+                ),
+                Action::TakePenaltyShot => penalty_shot::execute(
+                    world_state,
+                    self.penalty_shot,
+                    now,
+                    context.field_dimensions,
+                    &context.parameters.penalty_shot,
+                    context.in_walk_kicks,
+                    &walk_and_stand,
+                    &mut context.path_obstacles_output,
+                ),
+                Action::OrbitBall => orbit_ball::execute(
+                    world_state,
+                    &context.parameters.orbit_ball,
+                    &walk_path_planner,
+                    &mut context.path_obstacles_output,
+                ),
+                Action::Dribble => dribble::execute_with_duel(
+                    world_state,
+                    &walk_path_planner,
+                    context.in_walk_kicks,
+                    context.field_dimensions,
+                    &context.parameters.dribbling,
+                    dribble_kick_decisions.as_deref(),
+                    dribble_path.clone(),
+                ),
+                Action::Pass => pass::execute(
+                    world_state,
+                    &walk_path_planner,
+                    context.in_walk_kicks,
+                    context.field_dimensions,
+                    &context.parameters.dribbling,
+                    dribble_kick_decisions.as_deref(),
+                    dribble_path.clone(),
+                ),
+                Action::ReceivePass => receive_pass::execute(
+                    world_state,
+                    &context.parameters.passing.receiving,
+                    &walk_and_stand,
+                    &look_action,
+                    &mut context.path_obstacles_output,
+                ),
+                // the set-play specific kick targets are chosen by the kick selector, taking the
+                // free kick is dribbling towards them
+                Action::TakeFreeKick => dribble::execute(
+                    world_state,
+                    &walk_path_planner,
+                    context.in_walk_kicks,
+                    context.field_dimensions,
+                    &context.parameters.dribbling,
+                    dribble_kick_decisions.as_deref(),
+                    dribble_path.clone(),
+                ),
+                Action::SupportFreeKick => free_kick::support(
+                    world_state,
+                    context.field_dimensions,
+                    &context.parameters.free_kick,
+                    &walk_and_stand,
+                    &look_action,
+                    &mut context.path_obstacles_output,
+                ),
+                Action::DefendFreeKick => free_kick::defend(
+                    world_state,
+                    context.field_dimensions,
+                    &context.parameters.free_kick,
+                    *context.rule_set,
+                    &walk_and_stand,
+                    &look_action,
+                    &mut context.path_obstacles_output,
+                ),
+                Action::KeepFreeKickDistance => free_kick::keep_distance(
+                    world_state,
+                    context.field_dimensions,
+                    &context.parameters.free_kick,
+                    *context.rule_set,
+                    &walk_path_planner,
+                    &look_action,
+                ),
+                Action::FormWall => wall::execute(
+                    world_state,
+                    context.field_dimensions,
+                    &context.parameters.wall,
+                    *context.rule_set,
+                    &walk_and_stand,
+                    &look_action,
+                    &mut context.path_obstacles_output,
+                ),
+                Action::AwaitPenaltyRebound => penalty_rebound::execute(
+                    world_state,
+                    context.field_dimensions,
+                    &context.parameters.penalty_rebound,
+                    &context.parameters.illegal_defender,
+                    &walk_and_stand,
+                    &look_action,
+                    &mut context.path_obstacles_output,
+                ),
+                Action::Jump => jump::execute(
+                    world_state,
+                    context.field_dimensions,
+                    &context.parameters.keeper_dive,
+                    context.robot_capabilities,
+                    &mut context.predicted_goal_line_crossing_output,
+                ),
+                Action::PrepareJump => prepare_jump::execute(world_state),
+                Action::ClearBall => clear_ball::execute(
+                    world_state,
+                    context.field_dimensions,
+                    &context.parameters.keeper_clearance,
+                    &walk_path_planner,
+                    context.in_walk_kicks,
+                    &context.parameters.dribbling,
+                    dribble_kick_decisions.as_deref(),
+                    dribble_path.clone(),
+                ),
+                Action::YieldGoalMouth => yield_goal_mouth::execute(
+                    world_state,
+                    context.field_dimensions,
+                    &context.parameters.goal_mouth,
+                    &walk_and_stand,
+                    &look_action,
+                    &mut context.path_obstacles_output,
+                ),
+                Action::Search => search::execute(
+                    world_state,
+                    &walk_path_planner,
+                    &walk_and_stand,
+                    context.field_dimensions,
+                    &context.parameters.search,
+                    now,
+                    &mut self.search_cell,
+                    &mut context.path_obstacles_output,
+                ),
+                Action::SearchForLostBall => lost_ball::execute(
+                    world_state,
+                    self.last_known_ball_position,
+                    self.last_known_ball_time
+                        .map_or(0.0, |last_known_ball_time| {
+                            lost_ball::last_known_ball_confidence(
+                                now.duration_since(last_known_ball_time).unwrap_or_default(),
+                                context.lost_ball_parameters.confidence_half_life,
+                            )
+                        }),
+                    self.loser_since.map_or(Duration::ZERO, |loser_since| {
+                        now.duration_since(loser_since).unwrap_or_default()
+                    }),
+                    &walk_path_planner,
+                    context.lost_ball_parameters,
+                    &mut context.path_obstacles_output,
+                ),
+                Action::SupportLeft => support::execute(
+                    world_state,
+                    context.field_dimensions,
+                    Some(Side::Left),
+                    role_positions.left_midfielder_distance_to_ball,
+                    role_positions.left_midfielder_maximum_x_in_ready_and_when_ball_is_not_free,
+                    role_positions.left_midfielder_minimum_x,
+                    &context.parameters.support_positioning,
+                    &walk_and_stand,
+                    &look_action,
+                    &mut self.support_position,
+                    &mut context.path_obstacles_output,
+                ),
+                Action::SupportRight => support::execute(
+                    world_state,
+                    context.field_dimensions,
+                    Some(Side::Right),
+                    role_positions.right_midfielder_distance_to_ball,
+                    role_positions.right_midfielder_maximum_x_in_ready_and_when_ball_is_not_free,
+                    role_positions.right_midfielder_minimum_x,
+                    &context.parameters.support_positioning,
+                    &walk_and_stand,
+                    &look_action,
+                    &mut self.support_position,
+                    &mut context.path_obstacles_output,
+                ),
+                Action::SupportStriker => support::execute(
+                    world_state,
+                    context.field_dimensions,
+                    None,
+                    role_positions.striker_supporter_distance_to_ball,
+                    role_positions.striker_supporter_maximum_x_in_ready_and_when_ball_is_not_free,
+                    role_positions.striker_supporter_minimum_x,
+                    &context.parameters.support_positioning,
+                    &walk_and_stand,
+                    &look_action,
+                    &mut self.support_position,
+                    &mut context.path_obstacles_output,
+                ),
+                Action::Block => block::execute(
+                    world_state,
+                    context.field_dimensions,
+                    &context.parameters.block,
+                    &walk_and_stand,
+                    &look_action,
+                    &mut context.path_obstacles_output,
+                ),
+                Action::MarkOpponent => mark::execute(
+                    world_state,
+                    self.marked_opponent,
+                    context.field_dimensions,
+                    &context.parameters.marking,
+                    &context.parameters.illegal_defender,
+                    &walk_and_stand,
+                    &look_action,
+                    &mut context.path_obstacles_output,
+                ),
+                Action::WalkToKickOff => walk_to_kick_off::execute(
+                    world_state,
+                    &walk_and_stand,
+                    &look_action,
+                    &mut context.path_obstacles_output,
+                    *context.striker_set_position,
+                    &self.kick_off,
+                    &context.parameters.kick_off,
+                ),
+                Action::SafeStand => Some(safe_stand()),
+                Action::WalkToPenaltyKick => walk_to_penalty_kick::execute(
+                    world_state,
+                    &walk_and_stand,
+                    &look_action,
+                    &mut context.path_obstacles_output,
+                    context.field_dimensions,
+                ),
+            };
+            if motion_command.is_none() {
+                action_evaluations.push(ActionEvaluation {
+                    action: *action,
+                    outcome: ActionOutcome::Rejected {
+                        reason: RejectionReason::PreconditionsNotMet,
+                    },
+                });
+            }
+            Some((*action, motion_command?))
+        });
+        context
+            .action_resolution_error_output
+            .fill_if_subscribed(|| {
+                selected_action.is_none().then(|| ActionResolutionError {
+                    primary_state: world_state.robot.primary_state,
+                    role: world_state.robot.role,
+                    rejected_actions: action_evaluations.clone(),
+                })
             });
-        context.active_action_output.fill_if_subscribed(|| *action);
+        // a malformed world state must not take down the control cycler in the middle of a game
+        let (action, mut motion_command) =
+            selected_action.unwrap_or_else(|| (Action::SafeStand, safe_stand()));
+        if !matches!(
+            action,
+            Action::SupportLeft | Action::SupportRight | Action::SupportStriker
+        ) {
+            self.support_position = None;
+        }
+        if action != Action::Search {
+            self.search_cell = None;
+        }
+        context.active_action_output.fill_if_subscribed(|| action);
+        context.action_evaluations_output.fill_if_subscribed(|| {
+            let selected = ActionEvaluation {
+                action,
+                outcome: ActionOutcome::Selected,
+            };
+            let not_evaluated = actions
+                .iter()
+                .skip(action_evaluations.len() + 1)
+                .map(|&action| ActionEvaluation {
+                    action,
+                    outcome: ActionOutcome::NotEvaluated,
+                });
+            action_evaluations
+                .iter()
+                .copied()
+                .chain([selected])
+                .chain(not_evaluated)
+                .collect()
+        });
+
+        // defenders and the keeper keep their full speed to react to counters
+        let is_circulating = tempo == Tempo::Circulating
+            && matches!(
+                world_state.robot.role,
+                Role::MidfielderLeft | Role::MidfielderRight | Role::StrikerSupporter
+            );
+        if world_state.robot.robot_condition.is_degraded() || is_circulating {
+            if let MotionCommand::Walk { speed, .. } = &mut motion_command {
+                *speed = WalkSpeed::Slow;
+            }
+        }
+        arm_tucking::tuck_near_obstacles(
+            &mut motion_command,
+            &self.last_motion_command,
+            &world_state.obstacles,
+            &context.parameters.arm_tucking,
+        );
 
         self.last_motion_command = motion_command.clone();
+        self.action_commitment
+            .update(action, now, &context.parameters.action_commitment);
 
-        if matches!(action, Action::Dribble) {
+        if matches!(
+            action,
+            Action::Dribble | Action::Pass | Action::TakeFreeKick
+        ) {
             context
                 .path_obstacles_output
                 .fill_if_subscribed(|| dribble_path_obstacles.unwrap_or_default())
@@ -367,3 +889,344 @@ impl Behavior {
         })
     }
 }
+
+/// Set plays in progress, the action priority stays the configured one
+/// Where the ball was last seen and the running set plays, commitments and planned positions
+/// start over
+#[derive(Deserialize, Serialize)]
+pub struct PersistentBehavior {
+    last_known_ball_position: Point2<Field>,
+    last_known_ball_time: Option<SystemTime>,
+    active_since: Option<SystemTime>,
+    kick_off: KickOffSetPlay,
+    penalty_shot: Option<PenaltyShot>,
+}
+
+impl PersistentState for Behavior {
+    type State = PersistentBehavior;
+
+    fn persistent_state(&self) -> Self::State {
+        PersistentBehavior {
+            last_known_ball_position: self.last_known_ball_position,
+            last_known_ball_time: self.last_known_ball_time,
+            active_since: self.active_since,
+            kick_off: self.kick_off,
+            penalty_shot: self.penalty_shot,
+        }
+    }
+
+    fn restore_persistent_state(&mut self, state: Self::State) {
+        self.last_known_ball_position = state.last_known_ball_position;
+        self.last_known_ball_time = state.last_known_ball_time;
+        self.active_since = state.active_since;
+        self.kick_off = state.kick_off;
+        self.penalty_shot = state.penalty_shot;
+    }
+}
+
+impl Behavior {
+    /// Parameters change at runtime as well, a falling robot must not keep walking because of a
+    /// broken priority list
+    fn update_action_priority(&mut self, parameters: &ActionPriorityParameters) {
+        if *parameters == self.action_priority
+            || Some(parameters) == self.rejected_action_priority.as_ref()
+        {
+            return;
+        }
+        match validate_action_priority(parameters) {
+            Ok(()) => {
+                self.action_priority = parameters.clone();
+                self.rejected_action_priority = None;
+            }
+            Err(error) => {
+                warn!("ignoring action priority: {error}");
+                self.rejected_action_priority = Some(parameters.clone());
+            }
+        }
+    }
+}
+
+/// Safety actions the base actions have to contain before any gameplay action, otherwise e.g. a
+/// falling robot would keep walking
+const MANDATORY_ACTIONS: [Action; 3] = [Action::Unstiff, Action::FallSafely, Action::Penalize];
+
+fn validate_action_priority(parameters: &ActionPriorityParameters) -> Result<()> {
+    let base_actions = &parameters.base_actions;
+    let first_gameplay_action = base_actions
+        .iter()
+        .position(|&action| is_gameplay_action(action));
+    for mandatory_action in MANDATORY_ACTIONS {
+        let Some(index) = base_actions
+            .iter()
+            .position(|&action| action == mandatory_action)
+        else {
+            bail!("mandatory action {mandatory_action:?} is missing in the base actions");
+        };
+        if let Some(first_gameplay_action) = first_gameplay_action.filter(|&first| first < index) {
+            bail!(
+                "mandatory action {mandatory_action:?} has to come before gameplay action {:?}",
+                base_actions[first_gameplay_action]
+            );
+        }
+    }
+    // the fallback always applies and would hide every action behind it
+    let RoleActionsParameters {
+        defender_left,
+        defender_right,
+        keeper,
+        loser,
+        midfielder_left,
+        midfielder_right,
+        replacement_keeper,
+        searcher,
+        striker,
+        striker_supporter,
+    } = &parameters.role_additions;
+    let contains_safe_stand = [
+        base_actions,
+        defender_left,
+        defender_right,
+        keeper,
+        loser,
+        midfielder_left,
+        midfielder_right,
+        replacement_keeper,
+        searcher,
+        striker,
+        striker_supporter,
+    ]
+    .into_iter()
+    .any(|actions| actions.contains(&Action::SafeStand));
+    if contains_safe_stand {
+        bail!(
+            "{:?} is only the fallback and must not be configured",
+            Action::SafeStand
+        );
+    }
+    Ok(())
+}
+
+/// Actions only depending on the primary state or the fall state may precede the mandatory ones
+fn is_gameplay_action(action: Action) -> bool {
+    !matches!(
+        action,
+        Action::Unstiff
+            | Action::SitDown
+            | Action::Penalize
+            | Action::Initial
+            | Action::FallSafely
+            | Action::StandUp
+            | Action::Stand
+    )
+}
+
+/// Keeps the robot upright and searching for the ball and its position without depending on
+/// anything in the world state
+fn safe_stand() -> MotionCommand {
+    MotionCommand::Stand {
+        head: HeadMotion::LookAround,
+    }
+}
+
+/// Checks the preconditions an action cannot do without before it is executed, a rejected action
+/// reports the check it failed. Every check mirrors an early return of the action itself, actions
+/// passing the gate but not producing a motion command report their remaining preconditions.
+fn gate(
+    action: Action,
+    world_state: &WorldState,
+    has_ground_contact: bool,
+) -> Result<(), RejectionReason> {
+    let primary_state = world_state.robot.primary_state;
+    let required_primary_states: &[PrimaryState] = match action {
+        Action::Unstiff => &[PrimaryState::Unstiff],
+        Action::SitDown => &[PrimaryState::Finished],
+        Action::Penalize => &[PrimaryState::Penalized],
+        Action::Initial => &[PrimaryState::Initial],
+        Action::Calibrate => &[PrimaryState::Calibration],
+        Action::Rest => &[PrimaryState::Playing],
+        Action::LookAround => &[PrimaryState::Ready, PrimaryState::Playing],
+        _ => &[],
+    };
+    if !required_primary_states.is_empty() && !required_primary_states.contains(&primary_state) {
+        return Err(RejectionReason::WrongPrimaryState);
+    }
+    let is_penalty_shootout =
+        world_state
+            .filtered_game_controller_state
+            .is_some_and(|game_controller_state| {
+                matches!(
+                    game_controller_state.game_phase,
+                    GamePhase::PenaltyShootout { .. }
+                )
+            });
+    let is_localized = world_state.robot.ground_to_field.is_some();
+    let has_ball = world_state.ball.is_some();
+    match action {
+        Action::FallSafely
+            if !(matches!(world_state.robot.fall_state, FallState::Falling { .. })
+                && has_ground_contact) =>
+        {
+            Err(RejectionReason::NotFalling)
+        }
+        Action::StandUp if !matches!(world_state.robot.fall_state, FallState::Fallen { .. }) => {
+            Err(RejectionReason::NotFallen)
+        }
+        Action::LookAround if is_penalty_shootout => Err(RejectionReason::WrongGameState),
+        Action::TakeKickOff
+        | Action::TakePenaltyShot
+        | Action::OrbitBall
+        | Action::SupportLeft
+        | Action::SupportRight
+        | Action::SupportStriker
+            if !is_localized =>
+        {
+            Err(RejectionReason::NotLocalized)
+        }
+        Action::InterceptBall
+        | Action::TakeKickOff
+        | Action::TakePenaltyShot
+        | Action::OrbitBall
+        | Action::Dribble
+        | Action::Pass
+        | Action::TakeFreeKick
+            if !has_ball =>
+        {
+            Err(RejectionReason::NoBall)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn free_kick_kicking_team(
+    filtered_game_controller_state: Option<FilteredGameControllerState>,
+) -> Option<Team> {
+    match filtered_game_controller_state? {
+        FilteredGameControllerState {
+            game_state: FilteredGameState::Playing { .. },
+            sub_state:
+                Some(
+                    SubState::CornerKick
+                    | SubState::GoalKick
+                    | SubState::KickIn
+                    | SubState::PushingFreeKick,
+                ),
+            kicking_team,
+            ..
+        } => Some(kicking_team),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use node_testing::TestParameters;
+
+    use super::*;
+
+    fn default_action_priority() -> ActionPriorityParameters {
+        TestParameters::load_default()
+            .unwrap()
+            .get("behavior.action_priority")
+            .unwrap()
+    }
+
+    #[test]
+    fn restored_behavior_keeps_the_lost_ball_target() {
+        let action_priority = default_action_priority();
+        let mut behavior = Behavior::new(CreationContext {
+            action_priority: &action_priority,
+        })
+        .unwrap();
+        let ball_time = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+        let active_since = SystemTime::UNIX_EPOCH + Duration::from_secs(10);
+        behavior.last_known_ball_position = point![2.5, -1.0];
+        behavior.last_known_ball_time = Some(ball_time);
+        behavior.active_since = Some(active_since);
+
+        let snapshot = bincode::serialize(&behavior.persistent_state()).unwrap();
+        let mut restored = Behavior::new(CreationContext {
+            action_priority: &action_priority,
+        })
+        .unwrap();
+        restored.restore_persistent_state(bincode::deserialize(&snapshot).unwrap());
+
+        assert_eq!(restored.last_known_ball_position, point![2.5, -1.0]);
+        assert_eq!(restored.last_known_ball_time, Some(ball_time));
+        assert_eq!(restored.active_since, Some(active_since));
+    }
+
+    #[test]
+    fn default_action_priority_is_valid() {
+        assert!(validate_action_priority(&default_action_priority()).is_ok());
+    }
+
+    #[test]
+    fn missing_or_late_safety_actions_are_rejected() {
+        let mut without_fall_safely = default_action_priority();
+        without_fall_safely
+            .base_actions
+            .retain(|&action| action != Action::FallSafely);
+        assert!(validate_action_priority(&without_fall_safely).is_err());
+
+        let mut late_penalize = default_action_priority();
+        late_penalize
+            .base_actions
+            .retain(|&action| action != Action::Penalize);
+        late_penalize.base_actions.insert(0, Action::Dribble);
+        late_penalize.base_actions.push(Action::Penalize);
+        assert!(validate_action_priority(&late_penalize).is_err());
+    }
+
+    #[test]
+    fn configured_safe_stand_is_rejected() {
+        let mut with_safe_stand = default_action_priority();
+        with_safe_stand
+            .role_additions
+            .striker
+            .push(Action::SafeStand);
+        assert!(validate_action_priority(&with_safe_stand).is_err());
+    }
+
+    #[test]
+    fn invalid_action_priority_at_runtime_is_ignored() {
+        let valid = default_action_priority();
+        let mut behavior = Behavior::new(CreationContext::new(&valid)).unwrap();
+        let mut invalid = valid.clone();
+        invalid.base_actions.clear();
+
+        behavior.update_action_priority(&invalid);
+        assert_eq!(behavior.action_priority, valid);
+
+        let mut changed = valid.clone();
+        changed.role_additions.striker.push(Action::Search);
+        behavior.update_action_priority(&changed);
+        assert_eq!(behavior.action_priority, changed);
+    }
+
+    #[test]
+    fn gate_reports_the_precondition_the_action_misses() {
+        let mut world_state = WorldState::default();
+        world_state.robot.primary_state = PrimaryState::Ready;
+
+        assert_eq!(
+            gate(Action::Rest, &world_state, true),
+            Err(RejectionReason::WrongPrimaryState)
+        );
+        assert_eq!(
+            gate(Action::StandUp, &world_state, true),
+            Err(RejectionReason::NotFallen)
+        );
+        assert_eq!(
+            gate(Action::TakeKickOff, &world_state, true),
+            Err(RejectionReason::NotLocalized)
+        );
+        assert_eq!(
+            gate(Action::InterceptBall, &world_state, true),
+            Err(RejectionReason::NoBall)
+        );
+        assert_eq!(gate(Action::DefendGoal, &world_state, true), Ok(()));
+
+        world_state.robot.primary_state = PrimaryState::Playing;
+        assert_eq!(gate(Action::Rest, &world_state, true), Ok(()));
+    }
+}
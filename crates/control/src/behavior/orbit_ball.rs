@@ -0,0 +1,241 @@
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use coordinate_systems::Ground;
+use framework::AdditionalOutput;
+use geometry::look_at::LookAt;
+use linear_algebra::{distance, Point, Point2, Pose2, Rotation2};
+use types::{
+    motion_command::{HeadMotion, MotionCommand, OrientationMode},
+    parameters::OrbitBallParameters,
+    path_obstacles::PathObstacle,
+    roles::Role,
+    world_state::WorldState,
+};
+
+use super::walk_to_pose::WalkPathPlanner;
+
+/// How long an opponent has been keeping the striker from the ball
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct BallBlockade {
+    blocked_since: Option<SystemTime>,
+    yielded_since: Option<SystemTime>,
+}
+
+impl BallBlockade {
+    /// After orbiting without success, the striker leaves the ball to a teammate for a while
+    pub fn yields_ball(&self) -> bool {
+        self.yielded_since.is_some()
+    }
+}
+
+pub fn update(
+    blockade: BallBlockade,
+    world_state: &WorldState,
+    parameters: &OrbitBallParameters,
+    now: SystemTime,
+) -> BallBlockade {
+    let yielded_since = blockade.yielded_since.filter(|yielded_since| {
+        now.duration_since(*yielded_since)
+            .is_ok_and(|duration| duration < parameters.yield_duration)
+    });
+    let is_blocked = parameters.enable
+        && world_state.robot.role == Role::Striker
+        && shielding_opponent(world_state, parameters).is_some();
+    let blocked_since = is_blocked.then(|| blockade.blocked_since.unwrap_or(now));
+    let has_timed_out = blocked_since.is_some_and(|blocked_since| {
+        now.duration_since(blocked_since)
+            .is_ok_and(|duration| duration >= parameters.timeout)
+    });
+    BallBlockade {
+        blocked_since,
+        yielded_since: yielded_since.or(has_timed_out.then_some(now)),
+    }
+}
+
+/// Circles around the ball towards the side the shielding opponent does not cover, while the
+/// path planner keeps the robot clear of the opponent to not commit pushing.
+pub fn execute(
+    world_state: &WorldState,
+    parameters: &OrbitBallParameters,
+    walk_path_planner: &WalkPathPlanner,
+    path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
+) -> Option<MotionCommand> {
+    if !parameters.enable {
+        return None;
+    }
+    let ground_to_field = world_state.robot.ground_to_field?;
+    let ball = world_state.ball?.ball_in_ground;
+    let opponent = shielding_opponent(world_state, parameters)?;
+    let target = orbit_target(ball, opponent, parameters)?;
+
+    let planned_path = walk_path_planner.plan(
+        target.position(),
+        ground_to_field,
+        Some(ball),
+        1.0,
+        &world_state.obstacles,
+        &world_state.rule_obstacles,
+        path_obstacles_output,
+    );
+    Some(walk_path_planner.walk_along_planned_path(
+        HeadMotion::LookLeftAndRightOf { target: ball },
+        OrientationMode::Override(Point::origin().look_at(&ball)),
+        planned_path,
+    ))
+}
+
+/// Returns the opponent next to the ball if reaching the best kick pose would mean running into
+/// it
+fn shielding_opponent(
+    world_state: &WorldState,
+    parameters: &OrbitBallParameters,
+) -> Option<Point2<Ground>> {
+    let ball = world_state.ball?.ball_in_ground;
+    let kick_pose = world_state.kick_decisions.as_ref()?.first()?.kick_pose;
+    world_state
+        .opponents
+        .iter()
+        .map(|obstacle| obstacle.position)
+        .filter(|position| {
+            distance(*position, ball) < parameters.opponent_distance_to_ball
+                && distance(*position, kick_pose.position())
+                    < parameters.minimum_distance_to_opponent
+        })
+        .min_by(|left, right| distance(*left, ball).total_cmp(&distance(*right, ball)))
+}
+
+/// Next waypoint on the circle around the ball, continuing in the direction away from the
+/// opponent. The resulting pose faces the ball.
+fn orbit_target(
+    ball: Point2<Ground>,
+    opponent: Point2<Ground>,
+    parameters: &OrbitBallParameters,
+) -> Option<Pose2<Ground>> {
+    let ball_to_robot = Point2::origin() - ball;
+    let ball_to_opponent = opponent - ball;
+    if ball_to_robot.norm() <= f32::EPSILON {
+        return None;
+    }
+    let is_counterclockwise_of_opponent =
+        ball_to_opponent.x() * ball_to_robot.y() - ball_to_opponent.y() * ball_to_robot.x() >= 0.0;
+    let step_angle = if is_counterclockwise_of_opponent {
+        parameters.orbit_step_angle
+    } else {
+        -parameters.orbit_step_angle
+    };
+    let target =
+        ball + Rotation2::new(step_angle) * ball_to_robot.normalize() * parameters.orbit_radius;
+    Some(Pose2::new(target.coords(), target.look_at(&ball).angle()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use approx::assert_relative_eq;
+    use linear_algebra::{point, Isometry2};
+    use types::{
+        kick_decision::KickDecision, motion_command::KickVariant, obstacles::Obstacle,
+        support_foot::Side, world_state::BallState,
+    };
+
+    use super::*;
+
+    fn parameters() -> OrbitBallParameters {
+        OrbitBallParameters {
+            enable: true,
+            opponent_distance_to_ball: 0.4,
+            minimum_distance_to_opponent: 0.35,
+            orbit_radius: 0.5,
+            orbit_step_angle: 0.5,
+            timeout: Duration::from_secs(6),
+            yield_duration: Duration::from_secs(10),
+        }
+    }
+
+    fn at(seconds: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(seconds)
+    }
+
+    fn striker_behind_ball(opponents: &[Point2<Ground>]) -> WorldState {
+        let mut world_state = WorldState::default();
+        world_state.robot.role = Role::Striker;
+        let mut ball = BallState::new_at_center(Isometry2::identity());
+        ball.ball_in_ground = point![0.5, 0.0];
+        world_state.ball = Some(ball);
+        world_state.kick_decisions = Some(vec![KickDecision {
+            variant: KickVariant::Forward,
+            kicking_side: Side::Left,
+            kick_pose: Pose2::new(point![0.3, 0.0].coords(), 0.0),
+            strength: 1.0,
+        }]);
+        world_state.opponents = opponents
+            .iter()
+            .map(|&position| Obstacle::robot(position, 0.2, 0.3))
+            .collect();
+        world_state
+    }
+
+    #[test]
+    fn striker_yields_after_being_blocked_for_the_timeout() {
+        let world_state = striker_behind_ball(&[point![0.6, 0.0]]);
+
+        let blockade = update(BallBlockade::default(), &world_state, &parameters(), at(0));
+        assert!(!blockade.yields_ball());
+        let blockade = update(blockade, &world_state, &parameters(), at(5));
+        assert!(!blockade.yields_ball());
+        let blockade = update(blockade, &world_state, &parameters(), at(6));
+        assert!(blockade.yields_ball());
+    }
+
+    #[test]
+    fn yield_ends_after_its_duration() {
+        let world_state = striker_behind_ball(&[]);
+        let blockade = BallBlockade {
+            blocked_since: None,
+            yielded_since: Some(at(0)),
+        };
+
+        let blockade = update(blockade, &world_state, &parameters(), at(9));
+        assert!(blockade.yields_ball());
+        let blockade = update(blockade, &world_state, &parameters(), at(10));
+        assert!(!blockade.yields_ball());
+    }
+
+    #[test]
+    fn losing_the_opponent_resets_the_blockade() {
+        let blocked = striker_behind_ball(&[point![0.6, 0.0]]);
+        let free = striker_behind_ball(&[]);
+
+        let blockade = update(BallBlockade::default(), &blocked, &parameters(), at(0));
+        let blockade = update(blockade, &free, &parameters(), at(3));
+        let blockade = update(blockade, &blocked, &parameters(), at(4));
+        let blockade = update(blockade, &blocked, &parameters(), at(9));
+        assert!(!blockade.yields_ball());
+    }
+
+    #[test]
+    fn only_the_striker_is_blocked() {
+        let mut world_state = striker_behind_ball(&[point![0.6, 0.0]]);
+        world_state.robot.role = Role::StrikerSupporter;
+
+        let blockade = update(BallBlockade::default(), &world_state, &parameters(), at(0));
+        let blockade = update(blockade, &world_state, &parameters(), at(6));
+        assert!(!blockade.yields_ball());
+    }
+
+    #[test]
+    fn orbit_continues_away_from_the_opponent() {
+        let ball = point![0.5, 0.0];
+        let opponent_on_the_right = point![0.8, -0.1];
+        let target = orbit_target(ball, opponent_on_the_right, &parameters()).unwrap();
+        assert_relative_eq!(distance(target.position(), ball), 0.5, epsilon = 0.001);
+        assert!(target.position().y() > 0.0);
+
+        let opponent_on_the_left = point![0.8, 0.1];
+        let target = orbit_target(ball, opponent_on_the_left, &parameters()).unwrap();
+        assert!(target.position().y() < 0.0);
+    }
+}
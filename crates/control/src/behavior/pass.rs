@@ -0,0 +1,32 @@
+use types::{
+    field_dimensions::FieldDimensions,
+    kick_decision::KickDecision,
+    motion_command::MotionCommand,
+    parameters::{DribblingParameters, InWalkKicksParameters},
+    planned_path::PathSegment,
+    world_state::WorldState,
+};
+
+use super::{dribble, walk_to_pose::WalkPathPlanner};
+
+/// Kicks towards the announced receiver, the kick selector already aims at the pass target.
+pub fn execute(
+    world_state: &WorldState,
+    walk_path_planner: &WalkPathPlanner,
+    in_walk_kicks: &InWalkKicksParameters,
+    field_dimensions: &FieldDimensions,
+    parameters: &DribblingParameters,
+    kick_decisions: Option<&[KickDecision]>,
+    dribble_path: Option<Vec<PathSegment>>,
+) -> Option<MotionCommand> {
+    world_state.pass_intent?;
+    dribble::execute(
+        world_state,
+        walk_path_planner,
+        in_walk_kicks,
+        field_dimensions,
+        parameters,
+        kick_decisions,
+        dribble_path,
+    )
+}
@@ -1,8 +1,31 @@
-use types::{motion_command::MotionCommand, primary_state::PrimaryState, world_state::WorldState};
+use types::{
+    motion_command::{HeadMotion, MotionCommand},
+    parameters::ReentryParameters,
+    primary_state::PrimaryState,
+    world_state::WorldState,
+};
 
-pub fn execute(world_state: &WorldState) -> Option<MotionCommand> {
+pub fn execute(world_state: &WorldState, parameters: &ReentryParameters) -> Option<MotionCommand> {
     match world_state.robot.primary_state {
-        PrimaryState::Penalized => Some(MotionCommand::Penalized),
+        PrimaryState::Penalized => {
+            let remaining_penalty_duration = world_state.filtered_game_controller_state.and_then(
+                |filtered_game_controller_state| {
+                    filtered_game_controller_state.remaining_penalty_durations
+                        [world_state.robot.player_number]
+                },
+            );
+            if remaining_penalty_duration.is_some_and(|remaining_penalty_duration| {
+                remaining_penalty_duration <= parameters.stand_up_before_release
+            }) {
+                // penalized robots are placed facing the field, looking straight ahead already
+                // observes it before walking in
+                Some(MotionCommand::Stand {
+                    head: HeadMotion::ZeroAngles,
+                })
+            } else {
+                Some(MotionCommand::Penalized)
+            }
+        }
         _ => None,
     }
 }
@@ -0,0 +1,126 @@
+use coordinate_systems::Field;
+use framework::AdditionalOutput;
+use geometry::look_at::LookAt;
+use linear_algebra::{point, vector, Point2, Pose2, Rotation2};
+use spl_network_messages::{SubState, Team};
+use types::{
+    field_dimensions::FieldDimensions,
+    filtered_game_controller_state::FilteredGameControllerState,
+    filtered_game_state::FilteredGameState,
+    motion_command::MotionCommand,
+    parameters::{IllegalDefenderParameters, PenaltyReboundParameters},
+    path_obstacles::PathObstacle,
+    world_state::WorldState,
+};
+
+use super::{
+    free_kick::clamp_to_field, head::LookAction,
+    illegal_defender::project_outside_own_penalty_area, walk_to_pose::WalkAndStand,
+};
+
+/// Places the field players on an arc around the penalty mark during opponent penalty kicks, ready
+/// to sprint to a ball bouncing off the keeper or a post.
+///
+/// Every role owns one slot like in the wall, the slots of the first roles cover the lateral
+/// corridors rebounds usually roll into.
+#[allow(clippy::too_many_arguments)]
+pub fn execute(
+    world_state: &WorldState,
+    field_dimensions: &FieldDimensions,
+    parameters: &PenaltyReboundParameters,
+    illegal_defender: &IllegalDefenderParameters,
+    walk_and_stand: &WalkAndStand,
+    look_action: &LookAction,
+    path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
+) -> Option<MotionCommand> {
+    if !parameters.enable {
+        return None;
+    }
+    let ground_to_field = world_state.robot.ground_to_field?;
+    let is_opponent_penalty_kick = matches!(
+        world_state.filtered_game_controller_state?,
+        FilteredGameControllerState {
+            game_state: FilteredGameState::Ready { .. }
+                | FilteredGameState::Set
+                | FilteredGameState::Playing {
+                    ball_is_free: false,
+                    ..
+                },
+            sub_state: Some(SubState::PenaltyKick),
+            kicking_team: Team::Opponent,
+            ..
+        }
+    );
+    if !is_opponent_penalty_kick {
+        return None;
+    }
+    let slot_index = parameters
+        .slot_roles
+        .iter()
+        .position(|role| *role == world_state.robot.role)?;
+
+    let penalty_mark = point![
+        -field_dimensions.length / 2.0 + field_dimensions.penalty_marker_distance,
+        0.0
+    ];
+    let slot = slot_position(
+        penalty_mark,
+        slot_index,
+        parameters.distance_to_penalty_mark,
+        parameters.corridor_angle,
+    )?;
+    let slot = clamp_to_field(slot, field_dimensions);
+    let slot_pose = project_outside_own_penalty_area(
+        Pose2::new(slot.coords(), slot.look_at(&penalty_mark).angle()),
+        field_dimensions,
+        illegal_defender,
+    );
+    walk_and_stand.execute(
+        ground_to_field.inverse() * slot_pose,
+        look_action.execute(),
+        path_obstacles_output,
+    )
+}
+
+/// The first two slots cover the left and right corridor, the following ones fill the center of
+/// the arc, beginning with the line from the penalty mark to the field center.
+fn slot_position(
+    penalty_mark: Point2<Field>,
+    slot_index: usize,
+    distance_to_penalty_mark: f32,
+    corridor_angle: f32,
+) -> Option<Point2<Field>> {
+    let angle = match slot_index {
+        0 => corridor_angle,
+        1 => -corridor_angle,
+        2 => 0.0,
+        3 => corridor_angle / 2.0,
+        4 => -corridor_angle / 2.0,
+        _ => return None,
+    };
+    Some(penalty_mark + Rotation2::new(angle) * vector![distance_to_penalty_mark, 0.0])
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use linear_algebra::distance;
+
+    use super::*;
+
+    #[test]
+    fn corridors_are_covered_first() {
+        let penalty_mark = point![-3.2, 0.0];
+        let left = slot_position(penalty_mark, 0, 1.5, 1.2).unwrap();
+        let right = slot_position(penalty_mark, 1, 1.5, 1.2).unwrap();
+        let center = slot_position(penalty_mark, 2, 1.5, 1.2).unwrap();
+
+        for slot in [left, right, center] {
+            assert_relative_eq!(distance(slot, penalty_mark), 1.5, epsilon = 0.001);
+        }
+        assert!(left.y() > 0.0);
+        assert_relative_eq!(left.y(), -right.y(), epsilon = 0.001);
+        assert_relative_eq!(center.y(), 0.0, epsilon = 0.001);
+        assert!(slot_position(penalty_mark, 5, 1.5, 1.2).is_none());
+    }
+}
@@ -0,0 +1,306 @@
+use std::time::SystemTime;
+
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use coordinate_systems::{Field, Ground};
+use framework::AdditionalOutput;
+use linear_algebra::{distance, point, vector, Point, Point2, Pose2};
+use spl_network_messages::{GamePhase, Team};
+use types::{
+    field_dimensions::FieldDimensions,
+    filtered_game_controller_state::FilteredGameControllerState,
+    filtered_game_state::FilteredGameState,
+    motion_command::{
+        ArmMotion, HeadMotion, KickVariant, MotionCommand, OrientationMode, WalkSpeed,
+    },
+    obstacles::ObstacleKind,
+    parameters::{InWalkKicksParameters, PenaltyShotParameters},
+    path_obstacles::PathObstacle,
+    penalty_shot_variant::PenaltyShotVariant,
+    planned_path::direct_path,
+    support_foot::Side,
+    world_state::WorldState,
+};
+
+use crate::kick_selector::compute_kick_pose;
+
+use super::walk_to_pose::WalkAndStand;
+
+/// The current attempt of the own team in the penalty shootout
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct PenaltyShot {
+    variant: PenaltyShotVariant,
+    playing_since: Option<SystemTime>,
+}
+
+/// Keeps the variant for the whole attempt, a random one is only drawn when an attempt begins.
+pub fn update(
+    penalty_shot: Option<PenaltyShot>,
+    world_state: &WorldState,
+    parameters: &PenaltyShotParameters,
+    now: SystemTime,
+) -> Option<PenaltyShot> {
+    let game_state = match world_state.filtered_game_controller_state? {
+        FilteredGameControllerState {
+            game_phase:
+                GamePhase::PenaltyShootout {
+                    kicking_team: Team::Hulks,
+                },
+            game_state,
+            ..
+        } => game_state,
+        _ => return None,
+    };
+    let variant = match (parameters.variant, penalty_shot) {
+        (Some(variant), _) => variant,
+        (None, Some(penalty_shot)) => penalty_shot.variant,
+        (None, None) => *PenaltyShotVariant::ALL
+            .choose(&mut thread_rng())
+            .expect("there is at least one variant"),
+    };
+    let playing_since = match game_state {
+        FilteredGameState::Playing { .. } => Some(
+            penalty_shot
+                .and_then(|penalty_shot| penalty_shot.playing_since)
+                .unwrap_or(now),
+        ),
+        _ => None,
+    };
+    Some(PenaltyShot {
+        variant,
+        playing_since,
+    })
+}
+
+/// Aligns on the line from the ball to the target before approaching the kick pose in a straight
+/// line. The ball lies on the penalty mark at the beginning of the attempt, hence aligning to the
+/// ball also aligns to the mark.
+#[allow(clippy::too_many_arguments)]
+pub fn execute(
+    world_state: &WorldState,
+    penalty_shot: Option<PenaltyShot>,
+    now: SystemTime,
+    field_dimensions: &FieldDimensions,
+    parameters: &PenaltyShotParameters,
+    in_walk_kicks: &InWalkKicksParameters,
+    walk_and_stand: &WalkAndStand,
+    path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
+) -> Option<MotionCommand> {
+    if !parameters.enable {
+        return None;
+    }
+    let penalty_shot = penalty_shot?;
+    let playing_since = penalty_shot.playing_since?;
+    let ground_to_field = world_state.robot.ground_to_field?;
+    let ball_position = world_state.ball?.ball_in_ground;
+    let head = HeadMotion::LookLeftAndRightOf {
+        target: ball_position,
+    };
+
+    let is_feinting = penalty_shot.variant == PenaltyShotVariant::DelayedFeint
+        && now
+            .duration_since(playing_since)
+            .is_ok_and(|duration| duration < parameters.feint_delay);
+    let (target_y, strength) = shot_target(
+        penalty_shot.variant,
+        is_feinting,
+        side_away_from_keeper(world_state, field_dimensions),
+        parameters,
+    );
+    let target = ground_to_field.inverse() * point![field_dimensions.length / 2.0, target_y];
+
+    align_and_kick(
+        ball_position,
+        target,
+        strength,
+        is_feinting,
+        head,
+        in_walk_kicks,
+        &KickAlignment {
+            alignment_distance: parameters.alignment_distance,
+            maximum_lateral_alignment_error: parameters.maximum_lateral_alignment_error,
+            maximum_angular_alignment_error: parameters.maximum_angular_alignment_error,
+        },
+        walk_and_stand,
+        path_obstacles_output,
+    )
+}
+
+/// Tolerances of the straight approach towards a kick pose
+pub struct KickAlignment {
+    /// The robot aligns on the kick line this far behind the kick pose before approaching
+    pub alignment_distance: f32,
+    pub maximum_lateral_alignment_error: f32,
+    pub maximum_angular_alignment_error: f32,
+}
+
+/// Aligns on the line from the ball to the target and approaches the kick pose of the closer foot
+/// in a straight line. While holding, the robot waits aligned instead of kicking.
+#[allow(clippy::too_many_arguments)]
+pub fn align_and_kick(
+    ball_position: Point2<Ground>,
+    target: Point2<Ground>,
+    strength: f32,
+    is_holding: bool,
+    head: HeadMotion,
+    in_walk_kicks: &InWalkKicksParameters,
+    alignment: &KickAlignment,
+    walk_and_stand: &WalkAndStand,
+    path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
+) -> Option<MotionCommand> {
+    let kick_info = &in_walk_kicks[KickVariant::Forward];
+    let (kicking_side, kick_pose) = [Side::Left, Side::Right]
+        .into_iter()
+        .map(|side| {
+            (
+                side,
+                compute_kick_pose(ball_position, target, kick_info, side),
+            )
+        })
+        .min_by(|(_, left), (_, right)| {
+            left.position()
+                .coords()
+                .norm()
+                .total_cmp(&right.position().coords().norm())
+        })?;
+
+    struct KickPose;
+    let kick_pose_to_ground = kick_pose.as_transform::<KickPose>();
+    let robot_in_kick_pose: Point2<KickPose> = kick_pose_to_ground.inverse() * Point2::origin();
+    let angular_error = kick_pose.orientation().angle().abs();
+
+    let is_kick_pose_reached = robot_in_kick_pose.x().abs() < kick_info.reached_thresholds.x
+        && robot_in_kick_pose.y().abs() < kick_info.reached_thresholds.y
+        && angular_error < kick_info.reached_thresholds.z;
+    let is_aligned = robot_in_kick_pose.x() < kick_info.reached_thresholds.x
+        && robot_in_kick_pose.x()
+            > -alignment.alignment_distance - alignment.maximum_lateral_alignment_error
+        && robot_in_kick_pose.y().abs() < alignment.maximum_lateral_alignment_error
+        && angular_error < alignment.maximum_angular_alignment_error;
+
+    if is_holding && (is_aligned || is_kick_pose_reached) {
+        return Some(MotionCommand::Stand { head });
+    }
+    if is_kick_pose_reached {
+        return Some(MotionCommand::InWalkKick {
+            head,
+            kick: KickVariant::Forward,
+            kicking_side,
+            strength,
+            left_arm: ArmMotion::Swing,
+            right_arm: ArmMotion::Swing,
+        });
+    }
+    if is_aligned {
+        return Some(walk_precisely(head, kick_pose));
+    }
+
+    let alignment_pose =
+        kick_pose_to_ground * Pose2::new(vector![-alignment.alignment_distance, 0.0], 0.0);
+    if alignment_pose.position().coords().norm() > alignment.alignment_distance {
+        walk_and_stand.execute(alignment_pose, head, path_obstacles_output)
+    } else {
+        Some(walk_precisely(head, alignment_pose))
+    }
+}
+
+/// Walks straight with short steps, the path planner's tolerances are too coarse for the kick
+fn walk_precisely(head: HeadMotion, target_pose: Pose2<Ground>) -> MotionCommand {
+    MotionCommand::Walk {
+        head,
+        path: direct_path(Point::origin(), target_pose.position()),
+        left_arm: ArmMotion::Swing,
+        right_arm: ArmMotion::Swing,
+        orientation_mode: OrientationMode::Override(target_pose.orientation()),
+        speed: WalkSpeed::Slow,
+    }
+}
+
+/// Returns the lateral position of the target on the goal line and the kick strength
+fn shot_target(
+    variant: PenaltyShotVariant,
+    is_feinting: bool,
+    side_away_from_keeper: f32,
+    parameters: &PenaltyShotParameters,
+) -> (f32, f32) {
+    match variant {
+        PenaltyShotVariant::PlacedLeft => (
+            parameters.placed_shot_target_y,
+            parameters.placed_shot_strength,
+        ),
+        PenaltyShotVariant::PlacedRight => (
+            -parameters.placed_shot_target_y,
+            parameters.placed_shot_strength,
+        ),
+        PenaltyShotVariant::StrongCenter => (0.0, parameters.strong_shot_strength),
+        PenaltyShotVariant::DelayedFeint if is_feinting => (0.0, parameters.strong_shot_strength),
+        PenaltyShotVariant::DelayedFeint => (
+            side_away_from_keeper * parameters.placed_shot_target_y,
+            parameters.placed_shot_strength,
+        ),
+    }
+}
+
+/// Returns 1.0 for the left and -1.0 for the right side of the opponent goal, the keeper is
+/// assumed to be the robot closest to the goal center.
+fn side_away_from_keeper(world_state: &WorldState, field_dimensions: &FieldDimensions) -> f32 {
+    let Some(ground_to_field) = world_state.robot.ground_to_field else {
+        return 1.0;
+    };
+    let goal_center: Point2<Field> = point![field_dimensions.length / 2.0, 0.0];
+    let keeper = world_state
+        .obstacles
+        .iter()
+        .filter(|obstacle| matches!(obstacle.kind, ObstacleKind::Robot))
+        .map(|obstacle| ground_to_field * obstacle.position)
+        .min_by(|left, right| {
+            distance(*left, goal_center).total_cmp(&distance(*right, goal_center))
+        });
+    match keeper {
+        Some(keeper) if keeper.y() > 0.0 => -1.0,
+        _ => 1.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters() -> PenaltyShotParameters {
+        PenaltyShotParameters {
+            placed_shot_target_y: 0.5,
+            placed_shot_strength: 0.8,
+            strong_shot_strength: 1.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn placed_shots_aim_at_the_corners() {
+        assert_eq!(
+            shot_target(PenaltyShotVariant::PlacedLeft, false, 1.0, &parameters()),
+            (0.5, 0.8)
+        );
+        assert_eq!(
+            shot_target(PenaltyShotVariant::PlacedRight, false, 1.0, &parameters()),
+            (-0.5, 0.8)
+        );
+        assert_eq!(
+            shot_target(PenaltyShotVariant::StrongCenter, false, 1.0, &parameters()),
+            (0.0, 1.0)
+        );
+    }
+
+    #[test]
+    fn feint_switches_to_the_side_away_from_the_keeper() {
+        assert_eq!(
+            shot_target(PenaltyShotVariant::DelayedFeint, true, -1.0, &parameters()),
+            (0.0, 1.0)
+        );
+        assert_eq!(
+            shot_target(PenaltyShotVariant::DelayedFeint, false, -1.0, &parameters()),
+            (-0.5, 0.8)
+        );
+    }
+}
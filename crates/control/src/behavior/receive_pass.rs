@@ -0,0 +1,78 @@
+use coordinate_systems::Ground;
+use framework::AdditionalOutput;
+use geometry::{line_segment::LineSegment, look_at::LookAt};
+use linear_algebra::{distance, Point2, Pose2};
+use types::{
+    motion_command::MotionCommand, parameters::PassReceivingParameters,
+    path_obstacles::PathObstacle, world_state::WorldState,
+};
+
+use super::{head::LookAction, walk_to_pose::WalkAndStand};
+
+pub fn execute(
+    world_state: &WorldState,
+    parameters: &PassReceivingParameters,
+    walk_and_stand: &WalkAndStand,
+    look_action: &LookAction,
+    path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
+) -> Option<MotionCommand> {
+    let receiving_pose = receiving_pose(world_state, parameters)?;
+    walk_and_stand.execute(receiving_pose, look_action.execute(), path_obstacles_output)
+}
+
+/// While the ball rests, the robot waits at the spot on the pass line closest to it which is not
+/// covered by an opponent. Once the ball rolls, it intercepts the ball on its trajectory.
+fn receiving_pose(
+    world_state: &WorldState,
+    parameters: &PassReceivingParameters,
+) -> Option<Pose2<Ground>> {
+    let pass = world_state.announced_pass?;
+    let ground_to_field = world_state.robot.ground_to_field?;
+    let ball = world_state.ball.or(world_state.rule_ball)?;
+    let ball_position = ball.ball_in_ground;
+
+    let velocity = ball.ball_in_ground_velocity;
+    let receiving_position = if velocity.norm() > parameters.rolling_ball_velocity {
+        let time_to_closest_point =
+            (-ball_position.coords().dot(velocity) / velocity.norm_squared()).max(0.0);
+        ball_position + velocity * time_to_closest_point
+    } else {
+        open_position_on_pass_line(
+            world_state,
+            LineSegment::new(ball_position, ground_to_field.inverse() * pass.target),
+            parameters,
+        )
+    };
+    Some(Pose2::new(
+        receiving_position.coords(),
+        receiving_position.look_at(&ball_position).angle(),
+    ))
+}
+
+/// Candidates start at the point of the pass line closest to the robot and step towards the pass
+/// target, the first one without an opponent around it is taken.
+fn open_position_on_pass_line(
+    world_state: &WorldState,
+    pass_line: LineSegment<Ground>,
+    parameters: &PassReceivingParameters,
+) -> Point2<Ground> {
+    let closest_point = pass_line.closest_point(Point2::origin());
+    let LineSegment(_, pass_target) = pass_line;
+    let remaining_distance = distance(closest_point, pass_target);
+    let number_of_steps = (remaining_distance / parameters.candidate_spacing.max(0.01)) as usize;
+    let is_open = |candidate: Point2<Ground>| {
+        world_state
+            .opponents
+            .iter()
+            .all(|obstacle| distance(obstacle.position, candidate) > parameters.open_radius)
+    };
+    (0..=number_of_steps)
+        .map(|step| {
+            let progress = step as f32 * parameters.candidate_spacing;
+            closest_point
+                + (pass_target - closest_point) * (progress / remaining_distance.max(f32::EPSILON))
+        })
+        .chain([pass_target])
+        .find(|candidate| is_open(*candidate))
+        .unwrap_or(closest_point)
+}
@@ -0,0 +1,74 @@
+use types::{
+    action::Action, motion_command::MotionCommand, primary_state::PrimaryState,
+    world_state::WorldState,
+};
+
+use super::head::LookAction;
+
+/// Stands still while tracking the point of interest to let the battery and joints recover
+pub fn execute(world_state: &WorldState, look_action: &LookAction) -> Option<MotionCommand> {
+    let is_degraded = world_state.robot.robot_condition.is_degraded();
+    match world_state.robot.primary_state {
+        PrimaryState::Playing if is_degraded => Some(MotionCommand::Stand {
+            head: look_action.execute(),
+        }),
+        _ => None,
+    }
+}
+
+/// Puts resting in front of the first non-critical action.
+///
+/// Resting is inserted after the action commitment reordered the list, a committed non-critical
+/// action can never overtake it.
+pub fn insert_before_non_critical_actions(
+    actions: &mut Vec<Action>,
+    non_critical_actions: &[Action],
+) {
+    if let Some(index) = actions
+        .iter()
+        .position(|action| non_critical_actions.contains(action))
+    {
+        actions.insert(index, Action::Rest);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use types::parameters::ActionCommitmentParameters;
+
+    use crate::behavior::action_commitment::ActionCommitment;
+
+    use super::*;
+
+    #[test]
+    fn committed_non_critical_action_does_not_overtake_resting() {
+        let parameters = ActionCommitmentParameters {
+            actions: vec![Action::InterceptBall, Action::SupportLeft],
+            minimum_duration: Duration::from_secs(1),
+            release_margin: Duration::from_millis(300),
+        };
+        let mut commitment = ActionCommitment::default();
+        commitment.update(Action::SupportLeft, UNIX_EPOCH, &parameters);
+        let mut actions = vec![
+            Action::FallSafely,
+            Action::InterceptBall,
+            Action::SupportLeft,
+        ];
+
+        let now = UNIX_EPOCH + Duration::from_millis(500);
+        commitment.prioritize(&mut actions, now, &parameters);
+        insert_before_non_critical_actions(&mut actions, &[Action::SupportLeft, Action::Search]);
+
+        assert_eq!(
+            actions,
+            [
+                Action::FallSafely,
+                Action::Rest,
+                Action::SupportLeft,
+                Action::InterceptBall
+            ]
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use std::f32::consts::FRAC_PI_2;
+
+use coordinate_systems::Field;
+use framework::AdditionalOutput;
+use linear_algebra::{point, Point2, Pose2};
+use types::{
+    field_dimensions::FieldDimensions,
+    motion_command::{HeadMotion, MotionCommand},
+    parameters::ReentryParameters,
+    path_obstacles::PathObstacle,
+    primary_state::PrimaryState,
+    world_state::WorldState,
+};
+
+use super::walk_to_pose::WalkAndStand;
+
+/// Walks a returning robot straight into the field from its sideline entry point while looking
+/// around, until its localization converged it neither plays the ball nor takes a position. The
+/// hypotheses on both sidelines are mirrored at the long field axis, the walk target lies
+/// straight ahead of the robot under each of them such that the motion is the same no matter
+/// which one is right.
+pub fn execute(
+    world_state: &WorldState,
+    field_dimensions: &FieldDimensions,
+    parameters: &ReentryParameters,
+    walk_and_stand: &WalkAndStand,
+    path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
+) -> Option<MotionCommand> {
+    if !world_state.robot.is_converging_after_reentry
+        || world_state.robot.primary_state != PrimaryState::Playing
+    {
+        return None;
+    }
+    let look_around = MotionCommand::Stand {
+        head: HeadMotion::LookAround,
+    };
+    let Some(ground_to_field) = world_state.robot.ground_to_field else {
+        return Some(look_around);
+    };
+    if !parameters.enable_walk_in {
+        return Some(look_around);
+    }
+    let position = ground_to_field.as_pose().position();
+    let side = position.y().signum();
+    let target = walk_in_target(
+        position,
+        side,
+        field_dimensions,
+        parameters.walk_in_distance,
+    );
+    let pose = Pose2::new(target.coords(), -side * FRAC_PI_2);
+    walk_and_stand
+        .execute(
+            ground_to_field.inverse() * pose,
+            HeadMotion::LookAround,
+            path_obstacles_output,
+        )
+        .or(Some(look_around))
+}
+
+/// Keeps the position along the sideline, only the distance to it is independent of the sideline
+fn walk_in_target(
+    position: Point2<Field>,
+    side: f32,
+    field_dimensions: &FieldDimensions,
+    walk_in_distance: f32,
+) -> Point2<Field> {
+    let distance_to_axis = (field_dimensions.width / 2.0 - walk_in_distance).max(0.0);
+    point![position.x(), side * distance_to_axis]
+}
@@ -1,6 +1,8 @@
-use coordinate_systems::{Field, Ground};
+use std::time::SystemTime;
+
+use coordinate_systems::Field;
 use framework::AdditionalOutput;
-use linear_algebra::{point, Isometry2, Orientation2, Point2, Pose2};
+use linear_algebra::{point, Orientation2, Point2, Pose2};
 use types::{
     field_dimensions::FieldDimensions,
     motion_command::{HeadMotion, MotionCommand, OrientationMode},
@@ -21,11 +23,7 @@ enum SearchRole {
 }
 
 impl SearchRole {
-    fn to_position(
-        self,
-        ground_to_field: Isometry2<Ground, Field>,
-        field_dimensions: &FieldDimensions,
-    ) -> Point2<Ground> {
+    fn to_position(self, field_dimensions: &FieldDimensions) -> Point2<Field> {
         let goal = point![-field_dimensions.length / 2.0, 0.0];
         let defending_left = point![
             -field_dimensions.length / 2.0 + field_dimensions.goal_box_area_length + 0.2,
@@ -41,36 +39,61 @@ impl SearchRole {
             0.0
         ];
 
-        ground_to_field.inverse()
-            * match self {
-                SearchRole::Goal => goal,
-                SearchRole::Defend { side: Side::Left } => defending_left,
-                SearchRole::Defend { side: Side::Right } => defending_right,
-                SearchRole::Center => center,
-                SearchRole::Aggressive => aggressive,
-            }
+        match self {
+            SearchRole::Goal => goal,
+            SearchRole::Defend { side: Side::Left } => defending_left,
+            SearchRole::Defend { side: Side::Right } => defending_right,
+            SearchRole::Center => center,
+            SearchRole::Aggressive => aggressive,
+        }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn execute(
     world_state: &WorldState,
     walk_path_planner: &WalkPathPlanner,
     walk_and_stand: &WalkAndStand,
     field_dimensions: &FieldDimensions,
     parameters: &SearchParameters,
+    now: SystemTime,
+    search_cell: &mut Option<usize>,
     path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
 ) -> Option<MotionCommand> {
     let ground_to_field = world_state.robot.ground_to_field?;
     let search_role = assign_search_role(world_state);
-    let search_position = search_role
-        .map(|role| role.to_position(ground_to_field, field_dimensions))
-        .unwrap_or(point![0.0, 0.0]);
+    let search_position = match (search_role, world_state.search_sector_position) {
+        (Some(SearchRole::Goal), _) => SearchRole::Goal.to_position(field_dimensions),
+        // the ball search already picked the stalest part of the claimed sector
+        (_, Some(search_sector_position)) => search_sector_position,
+        (_, None) => {
+            // without a claimed sector, e.g. as Loser, the role position only anchors the
+            // search, robots sweep the regions nobody looked at for the longest time
+            let anchor = search_role
+                .map(|role| role.to_position(field_dimensions))
+                .unwrap_or(point![0.0, 0.0]);
+            let coverage = &world_state.field_coverage;
+            *search_cell = coverage.stalest_cell_near(
+                anchor,
+                parameters.coverage_distance_weight,
+                *search_cell,
+                parameters.coverage_cell_hysteresis,
+                now,
+                parameters.maximum_coverage_age,
+                field_dimensions,
+            );
+            search_cell
+                .map(|cell| coverage.cell_center(cell, field_dimensions))
+                .unwrap_or(anchor)
+        }
+    };
+    let search_position = ground_to_field.inverse() * search_position;
     let head = HeadMotion::SearchForLostBall;
     if let Some(SearchRole::Goal) = search_role {
         let goal_pose = Pose2::from(search_position);
         walk_and_stand.execute(goal_pose, head, path_obstacles_output)
     } else {
-        let path = walk_path_planner.plan(
+        let planned_path = walk_path_planner.plan(
             search_position,
             ground_to_field,
             None,
@@ -79,14 +102,18 @@ pub fn execute(
             &world_state.rule_obstacles,
             path_obstacles_output,
         );
-        let path_length: f32 = path.iter().map(|segment| segment.length()).sum();
+        let path_length: f32 = planned_path
+            .path
+            .iter()
+            .map(|segment| segment.length())
+            .sum();
         let is_reached = path_length < parameters.position_reached_distance;
         let orientation_mode = if is_reached {
             OrientationMode::Override(Orientation2::new(parameters.rotation_per_step))
         } else {
             OrientationMode::AlignWithPath
         };
-        Some(walk_path_planner.walk_with_obstacle_avoiding_arms(head, orientation_mode, path))
+        Some(walk_path_planner.walk_along_planned_path(head, orientation_mode, planned_path))
     }
 }
 
@@ -17,6 +17,10 @@ pub fn execute(
         PrimaryState::Initial => Some(MotionCommand::Stand {
             head: HeadMotion::ZeroAngles,
         }),
+        // looking across the field where the referee gives the ready signal
+        PrimaryState::Standby => Some(MotionCommand::Stand {
+            head: HeadMotion::Center,
+        }),
         PrimaryState::Set => {
             let ground_to_field = world_state.robot.ground_to_field?;
             let fallback_target = match world_state.filtered_game_controller_state {
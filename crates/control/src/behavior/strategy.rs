@@ -0,0 +1,150 @@
+use spl_network_messages::{GamePhase, Half, Team};
+use types::{
+    ball_possession::BallPossession,
+    filtered_game_controller_state::FilteredGameControllerState,
+    filtered_game_state::FilteredGameState,
+    parameters::{
+        RolePositionsModulation, RolePositionsParameters, StrategyParameters, TempoParameters,
+    },
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strategy {
+    Balanced,
+    /// Losing late in the game, everyone moves up to force an equalizer
+    Attacking,
+    /// Leading by a single goal late in the game, everyone drops back to keep the lead
+    ParkTheBus,
+}
+
+pub fn select(
+    filtered_game_controller_state: Option<FilteredGameControllerState>,
+    parameters: &StrategyParameters,
+) -> Strategy {
+    let Some(game_controller_state) = filtered_game_controller_state else {
+        return Strategy::Balanced;
+    };
+    let is_late_in_game = matches!(game_controller_state.game_phase, GamePhase::Normal)
+        && game_controller_state.half == Half::Second
+        && matches!(
+            game_controller_state.game_state,
+            FilteredGameState::Playing { .. }
+        )
+        && game_controller_state.remaining_time_in_half <= parameters.late_game_duration;
+    if !parameters.enable || !is_late_in_game {
+        return Strategy::Balanced;
+    }
+    let score_difference = i16::from(game_controller_state.own_score)
+        - i16::from(game_controller_state.opponent_score);
+    match score_difference {
+        difference if difference < 0 => Strategy::Attacking,
+        1 => Strategy::ParkTheBus,
+        _ => Strategy::Balanced,
+    }
+}
+
+pub fn modulate_role_positions(
+    role_positions: &RolePositionsParameters,
+    strategy: Strategy,
+    parameters: &StrategyParameters,
+) -> RolePositionsParameters {
+    let modulation = match strategy {
+        Strategy::Balanced => return role_positions.clone(),
+        Strategy::Attacking => &parameters.attacking,
+        Strategy::ParkTheBus => &parameters.park_the_bus,
+    };
+    apply_modulation(role_positions, modulation)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tempo {
+    Neutral,
+    /// The own team comfortably controls the ball, supporters spread out and walk slowly
+    Circulating,
+    /// The opponent controls the ball, everyone closes down space to win it back
+    Pressing,
+}
+
+pub fn select_tempo(ball_possession: &BallPossession, parameters: &TempoParameters) -> Tempo {
+    if !parameters.enable {
+        return Tempo::Neutral;
+    }
+    match ball_possession.team {
+        Team::Hulks if ball_possession.duration >= parameters.comfortable_possession_duration => {
+            Tempo::Circulating
+        }
+        Team::Opponent => Tempo::Pressing,
+        _ => Tempo::Neutral,
+    }
+}
+
+pub fn modulate_role_positions_for_tempo(
+    role_positions: &RolePositionsParameters,
+    tempo: Tempo,
+    parameters: &TempoParameters,
+) -> RolePositionsParameters {
+    let modulation = match tempo {
+        Tempo::Neutral => return role_positions.clone(),
+        Tempo::Circulating => &parameters.circulation,
+        Tempo::Pressing => &parameters.pressing,
+    };
+    apply_modulation(role_positions, modulation)
+}
+
+fn apply_modulation(
+    role_positions: &RolePositionsParameters,
+    modulation: &RolePositionsModulation,
+) -> RolePositionsParameters {
+    let RolePositionsModulation {
+        defender_ring_radius_factor,
+        supporter_distance_to_ball_factor,
+        supporter_minimum_x_factor,
+    } = *modulation;
+    RolePositionsParameters {
+        defender_aggressive_ring_radius: role_positions.defender_aggressive_ring_radius
+            * defender_ring_radius_factor,
+        defender_passive_ring_radius: role_positions.defender_passive_ring_radius
+            * defender_ring_radius_factor,
+        left_midfielder_distance_to_ball: role_positions.left_midfielder_distance_to_ball
+            * supporter_distance_to_ball_factor,
+        left_midfielder_minimum_x: scale_x(
+            role_positions.left_midfielder_minimum_x,
+            supporter_minimum_x_factor,
+        ),
+        right_midfielder_distance_to_ball: role_positions.right_midfielder_distance_to_ball
+            * supporter_distance_to_ball_factor,
+        right_midfielder_minimum_x: scale_x(
+            role_positions.right_midfielder_minimum_x,
+            supporter_minimum_x_factor,
+        ),
+        striker_supporter_distance_to_ball: role_positions.striker_supporter_distance_to_ball
+            * supporter_distance_to_ball_factor,
+        striker_supporter_minimum_x: scale_x(
+            role_positions.striker_supporter_minimum_x,
+            supporter_minimum_x_factor,
+        ),
+        ..role_positions.clone()
+    }
+}
+
+/// Applies the factor to the magnitude of x, such that a factor moves the position in the same
+/// direction in both halves
+fn scale_x(x: f32, factor: f32) -> f32 {
+    x + (factor - 1.0) * x.abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn scaling_x_moves_in_the_same_direction_in_both_halves() {
+        assert_relative_eq!(scale_x(2.0, 1.5), 3.0);
+        assert_relative_eq!(scale_x(-2.0, 1.5), -1.0);
+        assert_relative_eq!(scale_x(2.0, 0.5), 1.0);
+        assert_relative_eq!(scale_x(-2.0, 0.5), -3.0);
+        assert_relative_eq!(scale_x(2.25, -1.0), -2.25);
+    }
+}
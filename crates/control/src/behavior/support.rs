@@ -2,12 +2,14 @@ use std::f32::consts::FRAC_PI_4;
 
 use coordinate_systems::{Field, Ground};
 use framework::AdditionalOutput;
-use geometry::look_at::LookAt;
-use linear_algebra::{point, Pose2, Rotation2, Vector2};
+use geometry::{line_segment::LineSegment, look_at::LookAt};
+use linear_algebra::{distance, point, vector, Point2, Pose2, Rotation2, Vector2};
+use spl_network_messages::StrikerIntent;
 use types::{
     field_dimensions::FieldDimensions,
     filtered_game_state::FilteredGameState,
     motion_command::MotionCommand,
+    parameters::SupportPositioningParameters,
     path_obstacles::PathObstacle,
     support_foot::Side,
     world_state::{BallState, WorldState},
@@ -23,8 +25,10 @@ pub fn execute(
     distance_to_ball: f32,
     maximum_x_in_ready_and_when_ball_is_not_free: f32,
     minimum_x: f32,
+    positioning: &SupportPositioningParameters,
     walk_and_stand: &WalkAndStand,
     look_action: &LookAction,
+    previous_position: &mut Option<Point2<Field>>,
     path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
 ) -> Option<MotionCommand> {
     let pose = support_pose(
@@ -34,10 +38,13 @@ pub fn execute(
         distance_to_ball,
         maximum_x_in_ready_and_when_ball_is_not_free,
         minimum_x,
+        positioning,
+        previous_position,
     )?;
     walk_and_stand.execute(pose, look_action.execute(), path_obstacles_output)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn support_pose(
     world_state: &WorldState,
     field_dimensions: &FieldDimensions,
@@ -45,6 +52,8 @@ fn support_pose(
     distance_to_ball: f32,
     maximum_x_in_ready_and_when_ball_is_not_free: f32,
     minimum_x: f32,
+    positioning: &SupportPositioningParameters,
+    previous_position: &mut Option<Point2<Field>>,
 ) -> Option<Pose2<Ground>> {
     let ground_to_field = world_state.robot.ground_to_field?;
     let ball = world_state
@@ -52,35 +61,283 @@ fn support_pose(
         .or(world_state.ball)
         .unwrap_or_else(|| BallState::new_at_center(ground_to_field));
     let side = field_side.unwrap_or_else(|| ball.field_side.opposite());
-    let offset_vector = Rotation2::new(match side {
-        Side::Left => -FRAC_PI_4,
-        Side::Right => FRAC_PI_4,
-    }) * -(Vector2::<Field>::x_axis() * distance_to_ball);
-    let supporting_position = ball.ball_in_field + offset_vector;
+    let striker_intent = world_state
+        .teammate_striker
+        .and_then(|teammate_striker| teammate_striker.intent);
+    let supporting_position =
+        ball.ball_in_field + supporting_offset(side, distance_to_ball, striker_intent);
 
     let filtered_game_state = world_state
         .filtered_game_controller_state
         .map(|filtered_game_controller_state| filtered_game_controller_state.game_state);
-    let clamped_x = match filtered_game_state {
+    let (lower_x, upper_x) = match filtered_game_state {
         Some(FilteredGameState::Ready { .. })
         | Some(FilteredGameState::Playing {
             ball_is_free: false,
             ..
-        }) => supporting_position.x().clamp(
+        }) => (
             minimum_x.min(maximum_x_in_ready_and_when_ball_is_not_free),
             minimum_x.max(maximum_x_in_ready_and_when_ball_is_not_free),
         ),
-        _ => supporting_position
-            .x()
-            .clamp(minimum_x, field_dimensions.length / 2.0),
+        _ => (minimum_x, field_dimensions.length / 2.0),
+    };
+    let region = Region {
+        lower: point![lower_x, -field_dimensions.width / 2.0],
+        upper: point![upper_x, field_dimensions.width / 2.0],
     };
-    let clamped_y = supporting_position
-        .y()
-        .clamp(-field_dimensions.width / 2.0, field_dimensions.width / 2.0);
-    let clamped_position = point![clamped_x, clamped_y];
+    let clamped_position = region.clamp(supporting_position);
+    let position = if positioning.enable {
+        let opponents: Vec<_> = world_state
+            .opponents
+            .iter()
+            .map(|opponent| ground_to_field * opponent.position)
+            .collect();
+        open_space_position(
+            clamped_position,
+            &region,
+            ball.ball_in_field,
+            &opponents,
+            &world_state.teammate_positions,
+            *previous_position,
+            positioning,
+        )
+    } else {
+        clamped_position
+    };
+    *previous_position = Some(position);
     let support_pose = Pose2::new(
-        clamped_position.coords(),
-        clamped_position.look_at(&ball.ball_in_field).angle(),
+        position.coords(),
+        position.look_at(&ball.ball_in_field).angle(),
     );
     Some(ground_to_field.inverse() * support_pose)
 }
+
+/// Diagonally behind the ball to take it over from the striker. A shot rebounds from the goal or
+/// the keeper, the supporter waits diagonally in front of the ball instead.
+fn supporting_offset(
+    side: Side,
+    distance_to_ball: f32,
+    striker_intent: Option<StrikerIntent>,
+) -> Vector2<Field> {
+    let is_shot = striker_intent == Some(StrikerIntent::Shot);
+    let (direction, angle) = match (side, is_shot) {
+        (Side::Left, false) => (-Vector2::<Field>::x_axis(), -FRAC_PI_4),
+        (Side::Right, false) => (-Vector2::<Field>::x_axis(), FRAC_PI_4),
+        (Side::Left, true) => (Vector2::<Field>::x_axis(), FRAC_PI_4),
+        (Side::Right, true) => (Vector2::<Field>::x_axis(), -FRAC_PI_4),
+    };
+    Rotation2::new(angle) * (direction * distance_to_ball)
+}
+
+/// Rectangle of the field a supporter is allowed to position in
+struct Region {
+    lower: Point2<Field>,
+    upper: Point2<Field>,
+}
+
+impl Region {
+    fn clamp(&self, position: Point2<Field>) -> Point2<Field> {
+        point![
+            position.x().clamp(self.lower.x(), self.upper.x()),
+            position.y().clamp(self.lower.y(), self.upper.y())
+        ]
+    }
+
+    fn contains(&self, position: Point2<Field>) -> bool {
+        self.clamp(position) == position
+    }
+}
+
+/// Searches a grid of candidates around the nominal position for the one with the most open space,
+/// penalized by its distance to the nominal position and by opponents blocking the pass from the
+/// ball.
+///
+/// The previous position stays a candidate and is preferred by the hysteresis, the grid moves
+/// with the ball and the supporter would otherwise step between neighboring candidates.
+fn open_space_position(
+    nominal_position: Point2<Field>,
+    region: &Region,
+    ball: Point2<Field>,
+    opponents: &[Point2<Field>],
+    teammates: &[Point2<Field>],
+    previous_position: Option<Point2<Field>>,
+    parameters: &SupportPositioningParameters,
+) -> Point2<Field> {
+    let spacing = parameters.candidate_spacing.max(0.01);
+    let steps = (parameters.search_radius / spacing) as i32;
+    let robots: Vec<_> = opponents.iter().chain(teammates).copied().collect();
+    let score = |candidate: Point2<Field>| {
+        let pass_lane = LineSegment::new(ball, candidate);
+        let is_pass_lane_blocked = opponents.iter().any(|opponent| {
+            pass_lane.shortest_distance_to_point(*opponent) < parameters.pass_lane_width
+        });
+        open_space(candidate, ball, &robots, region, parameters)
+            - parameters.distance_penalty * distance(candidate, nominal_position)
+            - if is_pass_lane_blocked {
+                parameters.blocked_pass_lane_penalty
+            } else {
+                0.0
+            }
+    };
+    (-steps..=steps)
+        .flat_map(|column| {
+            (-steps..=steps).map(move |row| {
+                nominal_position + vector![column as f32 * spacing, row as f32 * spacing]
+            })
+        })
+        .map(|candidate| (candidate, 0.0))
+        .chain(previous_position.map(|position| (position, parameters.target_hysteresis)))
+        .filter(|(candidate, _)| {
+            region.contains(*candidate)
+                && distance(*candidate, nominal_position) <= parameters.search_radius
+        })
+        .map(|(candidate, bonus)| (candidate, score(candidate) + bonus))
+        .max_by(|(_, left), (_, right)| left.total_cmp(right))
+        .map_or(nominal_position, |(candidate, _)| candidate)
+}
+
+/// Area of the Voronoi cell of the candidate among the ball and the other robots, sampled within
+/// the evaluation radius and the region.
+fn open_space(
+    candidate: Point2<Field>,
+    ball: Point2<Field>,
+    robots: &[Point2<Field>],
+    region: &Region,
+    parameters: &SupportPositioningParameters,
+) -> f32 {
+    let spacing = parameters.sample_spacing.max(0.01);
+    let steps = (parameters.evaluation_radius / spacing) as i32;
+    // sites further away than twice the evaluation radius are never closer to a sample
+    let sites: Vec<_> = robots
+        .iter()
+        .chain([&ball])
+        .filter(|site| distance(**site, candidate) <= 2.0 * parameters.evaluation_radius)
+        .collect();
+    let number_of_samples = (-steps..=steps)
+        .flat_map(|column| {
+            (-steps..=steps)
+                .map(move |row| candidate + vector![column as f32 * spacing, row as f32 * spacing])
+        })
+        .filter(|sample| {
+            region.contains(*sample) && distance(*sample, candidate) <= parameters.evaluation_radius
+        })
+        .filter(|sample| {
+            let distance_to_candidate = distance(*sample, candidate);
+            sites
+                .iter()
+                .all(|site| distance(*sample, **site) > distance_to_candidate)
+        })
+        .count();
+    number_of_samples as f32 * spacing * spacing
+}
+
+/// Where to receive a pass the striker announced to this robot.
+///
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters() -> SupportPositioningParameters {
+        SupportPositioningParameters {
+            enable: true,
+            search_radius: 1.0,
+            candidate_spacing: 0.25,
+            evaluation_radius: 1.5,
+            sample_spacing: 0.1,
+            distance_penalty: 0.2,
+            pass_lane_width: 0.3,
+            blocked_pass_lane_penalty: 1.0,
+            target_hysteresis: 0.3,
+        }
+    }
+
+    fn region() -> Region {
+        Region {
+            lower: point![-4.5, -3.0],
+            upper: point![4.5, 3.0],
+        }
+    }
+
+    #[test]
+    fn supporter_moves_away_from_crowded_space() {
+        let region = region();
+        let nominal_position = point![0.0, 0.0];
+        let robots = [point![0.0, 0.6], point![0.4, 0.5], point![-0.4, 0.5]];
+
+        let position = open_space_position(
+            nominal_position,
+            &region,
+            point![-3.0, 0.0],
+            &robots,
+            &[],
+            None,
+            &parameters(),
+        );
+
+        assert!(position.y() < nominal_position.y());
+        assert!(region.contains(position));
+    }
+
+    #[test]
+    fn only_opponents_block_the_pass_lane() {
+        let parameters = parameters();
+        let ball = point![-3.0, 0.0];
+        let robot_on_pass_lane = [point![-1.5, 0.0]];
+
+        let behind_opponent = open_space_position(
+            point![0.0, 0.0],
+            &region(),
+            ball,
+            &robot_on_pass_lane,
+            &[],
+            None,
+            &parameters,
+        );
+        let behind_teammate = open_space_position(
+            point![0.0, 0.0],
+            &region(),
+            ball,
+            &[],
+            &robot_on_pass_lane,
+            None,
+            &parameters,
+        );
+
+        let pass_lane_clearance = |position| {
+            LineSegment::new(ball, position).shortest_distance_to_point(robot_on_pass_lane[0])
+        };
+        assert!(pass_lane_clearance(behind_opponent) >= parameters.pass_lane_width);
+        assert!(pass_lane_clearance(behind_teammate) < parameters.pass_lane_width);
+    }
+
+    #[test]
+    fn supporter_keeps_its_previous_position_within_the_hysteresis() {
+        let nominal_position = point![0.0, 0.0];
+        let previous_position = point![0.1, -0.1];
+
+        let position = open_space_position(
+            nominal_position,
+            &region(),
+            point![-3.0, 0.0],
+            &[],
+            &[],
+            Some(previous_position),
+            &parameters(),
+        );
+
+        assert_eq!(position, previous_position);
+    }
+
+    #[test]
+    fn supporter_waits_for_rebounds_of_shots_on_its_side() {
+        for side in [Side::Left, Side::Right] {
+            let behind = supporting_offset(side, 1.0, Some(StrikerIntent::Dribble));
+            let in_front = supporting_offset(side, 1.0, Some(StrikerIntent::Shot));
+
+            assert!(behind.x() < 0.0);
+            assert!(in_front.x() > 0.0);
+            assert_eq!(behind.y().signum(), in_front.y().signum());
+            assert_eq!(supporting_offset(side, 1.0, None), behind);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use coordinate_systems::Field;
+use linear_algebra::{point, Point2};
+use types::{
+    field_dimensions::FieldDimensions, parameters::ReadyWalkInParameters,
+    primary_state::PrimaryState, walk_in_lane::WalkInLane, world_state::WorldState,
+};
+
+/// Assigns the lane by player number on the side of the field the robot is on.
+pub fn assign_lane(
+    world_state: &WorldState,
+    field_dimensions: &FieldDimensions,
+    parameters: &ReadyWalkInParameters,
+) -> Option<WalkInLane> {
+    if !parameters.enable || !matches!(world_state.robot.primary_state, PrimaryState::Ready) {
+        return None;
+    }
+    let robot_position = world_state.robot.ground_to_field?.as_pose().position();
+    let side = if robot_position.y() < 0.0 { -1.0 } else { 1.0 };
+    let index = world_state.robot.player_number as usize;
+    let distance_to_sideline =
+        parameters.first_lane_distance_to_sideline + index as f32 * parameters.lane_spacing;
+    Some(WalkInLane {
+        index,
+        y: side * (field_dimensions.width / 2.0 - distance_to_sideline).max(0.0),
+    })
+}
+
+pub enum WalkInStep {
+    Wait,
+    Via(Point2<Field>),
+    Direct,
+}
+
+pub struct WalkIn<'cycle> {
+    lane: WalkInLane,
+    time_in_ready: Duration,
+    parameters: &'cycle ReadyWalkInParameters,
+}
+
+impl<'cycle> WalkIn<'cycle> {
+    pub fn new(
+        lane: WalkInLane,
+        time_in_ready: Duration,
+        parameters: &'cycle ReadyWalkInParameters,
+    ) -> Self {
+        Self {
+            lane,
+            time_in_ready,
+            parameters,
+        }
+    }
+
+    /// Robots with a short way to go let the others pass first, the higher the lane index the
+    /// longer they wait. Robots between the sideline and their lane walk along the lane until
+    /// they are level with their target.
+    pub fn step(&self, robot_position: Point2<Field>, target: Point2<Field>) -> WalkInStep {
+        let distance_to_target = (target - robot_position).norm();
+        let closeness =
+            1.0 - (distance_to_target / self.parameters.maximum_delayed_distance).clamp(0.0, 1.0);
+        let start_delay = self
+            .parameters
+            .start_delay_per_lane
+            .mul_f32(self.lane.index as f32 * closeness);
+        if self.time_in_ready < start_delay {
+            return WalkInStep::Wait;
+        }
+
+        let side = self.lane.y.signum();
+        let lane_distance_to_center = self.lane.y.abs();
+        let is_target_outside_lane = target.y() * side > lane_distance_to_center;
+        let is_robot_inside_lane = robot_position.y() * side
+            < lane_distance_to_center - self.parameters.lane_spacing / 2.0;
+        let remaining_x = target.x() - robot_position.x();
+        if remaining_x.abs() <= self.parameters.lane_exit_distance
+            || is_target_outside_lane
+            || is_robot_inside_lane
+        {
+            return WalkInStep::Direct;
+        }
+        let lookahead = self.parameters.lookahead_distance;
+        WalkInStep::Via(point![
+            robot_position.x() + remaining_x.clamp(-lookahead, lookahead),
+            self.lane.y
+        ])
+    }
+}
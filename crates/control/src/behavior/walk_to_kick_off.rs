@@ -1,20 +1,29 @@
 use coordinate_systems::Field;
 use framework::AdditionalOutput;
 use linear_algebra::{Point2, Pose2};
-use types::{motion_command::MotionCommand, path_obstacles::PathObstacle, world_state::WorldState};
+use types::{
+    motion_command::MotionCommand, parameters::KickOffParameters, path_obstacles::PathObstacle,
+    world_state::WorldState,
+};
 
-use super::{head::LookAction, walk_to_pose::WalkAndStand};
+use super::{head::LookAction, kick_off::KickOffSetPlay, walk_to_pose::WalkAndStand};
 
+/// Walks to the set position, lined up for the kick-off set play if there is one
 pub fn execute(
     world_state: &WorldState,
     walk_and_stand: &WalkAndStand,
     look_action: &LookAction,
     path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
     striker_set_position: Point2<Field>,
+    kick_off: &KickOffSetPlay,
+    kick_off_parameters: &KickOffParameters,
 ) -> Option<MotionCommand> {
     let ground_to_field = world_state.robot.ground_to_field?;
+    let set_pose = kick_off
+        .set_pose(kick_off_parameters, striker_set_position.coords().norm())
+        .unwrap_or_else(|| Pose2::from(striker_set_position));
     walk_and_stand.execute(
-        ground_to_field.inverse() * Pose2::from(striker_set_position),
+        ground_to_field.inverse() * set_pose,
         look_action.execute(),
         path_obstacles_output,
     )
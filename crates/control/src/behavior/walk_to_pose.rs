@@ -8,11 +8,11 @@ use types::{
     field_dimensions::FieldDimensions,
     motion_command::ArmMotion,
     motion_command::MotionCommand,
-    motion_command::{HeadMotion, OrientationMode},
+    motion_command::{HeadMotion, OrientationMode, WalkSpeed},
     obstacles::Obstacle,
     parameters::{PathPlanningParameters, WalkAndStandParameters},
     path_obstacles::PathObstacle,
-    planned_path::{direct_path, PathSegment},
+    planned_path::{direct_path, GapPassage, PathSegment, PlannedPath},
     rule_obstacles::RuleObstacle,
     support_foot::Side,
     world_state::WorldState,
@@ -20,11 +20,14 @@ use types::{
 
 use crate::path_planner::PathPlanner;
 
+use super::walk_in::{WalkIn, WalkInStep};
+
 pub struct WalkPathPlanner<'cycle> {
     field_dimensions: &'cycle FieldDimensions,
     obstacles: &'cycle [Obstacle],
     parameters: &'cycle PathPlanningParameters,
     last_motion_command: &'cycle MotionCommand,
+    own_penalty_area_obstacle: Option<RuleObstacle>,
 }
 
 impl<'cycle> WalkPathPlanner<'cycle> {
@@ -33,14 +36,19 @@ impl<'cycle> WalkPathPlanner<'cycle> {
         obstacles: &'cycle [Obstacle],
         parameters: &'cycle PathPlanningParameters,
         last_motion_command: &'cycle MotionCommand,
+        own_penalty_area_obstacle: Option<RuleObstacle>,
     ) -> Self {
         Self {
             field_dimensions,
             obstacles,
             parameters,
             last_motion_command,
+            own_penalty_area_obstacle,
         }
     }
+    /// Plans with the full footprint including the swinging arms first. Only if that path is
+    /// missing or detours further than any narrow path could justify, a second plan with the arms
+    /// pulled tight decides whether squeezing through a narrow gap is worth it.
     #[allow(clippy::too_many_arguments)]
     pub fn plan(
         &self,
@@ -51,18 +59,78 @@ impl<'cycle> WalkPathPlanner<'cycle> {
         obstacles: &[Obstacle],
         rule_obstacles: &[RuleObstacle],
         path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
-    ) -> Vec<PathSegment> {
+    ) -> PlannedPath {
+        let target_in_ground = self.clamp_to_field(target_in_ground, ground_to_field);
+        let plan_with = |robot_radius| {
+            self.plan_with_robot_radius(
+                target_in_ground,
+                ground_to_field,
+                ball_obstacle,
+                ball_obstacle_radius_factor,
+                obstacles,
+                rule_obstacles,
+                robot_radius,
+            )
+        };
+        let (wide_path, wide_planner) = plan_with(self.parameters.robot_radius_at_hip_height);
+        // no narrow path is shorter than the straight line, so a wide path within the detour
+        // factor of it can be taken without planning again
+        let may_prefer_narrow = wide_path.as_ref().map_or(true, |wide_path| {
+            path_length(wide_path)
+                > self.parameters.narrow_gap_detour_factor * target_in_ground.coords().norm()
+        });
+        let narrow_plan =
+            may_prefer_narrow.then(|| plan_with(self.parameters.robot_radius_with_tucked_arms));
+
+        let (path, gap_passage, planner) = match (wide_path, narrow_plan) {
+            (wide_path, Some((Some(narrow_path), narrow_planner)))
+                if wide_path.as_ref().map_or(true, |wide_path| {
+                    path_length(wide_path)
+                        > self.parameters.narrow_gap_detour_factor * path_length(&narrow_path)
+                }) =>
+            {
+                (narrow_path, GapPassage::Narrow, narrow_planner)
+            }
+            (Some(wide_path), _) => (wide_path, GapPassage::Wide, wide_planner),
+            (None, _) => (
+                direct_path(Point::origin(), Point::origin()),
+                GapPassage::Wide,
+                wide_planner,
+            ),
+        };
+        path_obstacles_output.fill_if_subscribed(|| planner.obstacles.clone());
+        PlannedPath { path, gap_passage }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn plan_with_robot_radius(
+        &self,
+        target_in_ground: Point2<Ground>,
+        ground_to_field: Isometry2<Ground, Field>,
+        ball_obstacle: Option<Point2<Ground>>,
+        ball_obstacle_radius_factor: f32,
+        obstacles: &[Obstacle],
+        rule_obstacles: &[RuleObstacle],
+        robot_radius: f32,
+    ) -> (Option<Vec<PathSegment>>, PathPlanner) {
         let mut planner = PathPlanner::default();
         planner.with_last_motion(
             self.last_motion_command,
             self.parameters.rotation_penalty_factor,
         );
-        planner.with_obstacles(obstacles, self.parameters.robot_radius_at_hip_height);
+        planner.with_obstacles(obstacles, robot_radius);
         planner.with_rule_obstacles(
             ground_to_field.inverse(),
             rule_obstacles,
             self.parameters.robot_radius_at_hip_height,
         );
+        if let Some(own_penalty_area_obstacle) = &self.own_penalty_area_obstacle {
+            planner.with_rule_obstacles(
+                ground_to_field.inverse(),
+                std::slice::from_ref(own_penalty_area_obstacle),
+                self.parameters.robot_radius_at_hip_height,
+            );
+        }
         planner.with_field_borders(
             ground_to_field,
             self.field_dimensions.length,
@@ -71,6 +139,11 @@ impl<'cycle> WalkPathPlanner<'cycle> {
             self.parameters.field_border_weight,
         );
         planner.with_goal_support_structures(ground_to_field.inverse(), self.field_dimensions);
+        planner.with_goal_posts(
+            ground_to_field.inverse(),
+            self.field_dimensions,
+            robot_radius + self.parameters.goal_post_inflation,
+        );
         if let Some(ball_position) = ball_obstacle {
             let foot_proportion = self.parameters.minimum_robot_radius_at_foot_height
                 / self.parameters.robot_radius_at_foot_height;
@@ -84,20 +157,44 @@ impl<'cycle> WalkPathPlanner<'cycle> {
             );
         }
 
+        let path = planner.plan(Point::origin(), target_in_ground).unwrap();
+        (path, planner)
+    }
+
+    fn clamp_to_field(
+        &self,
+        target_in_ground: Point2<Ground>,
+        ground_to_field: Isometry2<Ground, Field>,
+    ) -> Point2<Ground> {
         let target_in_field = ground_to_field * target_in_ground;
         let x_max = self.field_dimensions.length / 2.0 + self.field_dimensions.border_strip_width;
         let y_max = self.field_dimensions.width / 2.0 + self.field_dimensions.border_strip_width;
-        let clamped_target_in_robot = ground_to_field.inverse()
+        ground_to_field.inverse()
             * point![
                 target_in_field.x().clamp(-x_max, x_max),
                 target_in_field.y().clamp(-y_max, y_max)
-            ];
+            ]
+    }
 
-        let path = planner
-            .plan(Point::origin(), clamped_target_in_robot)
-            .unwrap();
-        path_obstacles_output.fill_if_subscribed(|| planner.obstacles.clone());
-        path.unwrap_or_else(|| direct_path(Point::origin(), Point::origin()))
+    pub fn walk_along_planned_path(
+        &self,
+        head: HeadMotion,
+        orientation_mode: OrientationMode,
+        planned_path: PlannedPath,
+    ) -> MotionCommand {
+        match planned_path.gap_passage {
+            GapPassage::Wide => {
+                self.walk_with_obstacle_avoiding_arms(head, orientation_mode, planned_path.path)
+            }
+            GapPassage::Narrow => MotionCommand::Walk {
+                head,
+                orientation_mode,
+                path: planned_path.path,
+                left_arm: ArmMotion::PullTight,
+                right_arm: ArmMotion::PullTight,
+                speed: WalkSpeed::Slow,
+            },
+        }
     }
 
     pub fn walk_with_obstacle_avoiding_arms(
@@ -112,6 +209,7 @@ impl<'cycle> WalkPathPlanner<'cycle> {
             path,
             left_arm: self.arm_motion_with_obstacles(Side::Left),
             right_arm: self.arm_motion_with_obstacles(Side::Right),
+            speed: WalkSpeed::Normal,
         }
     }
 
@@ -137,6 +235,7 @@ pub struct WalkAndStand<'cycle> {
     parameters: &'cycle WalkAndStandParameters,
     walk_path_planner: &'cycle WalkPathPlanner<'cycle>,
     last_motion_command: &'cycle MotionCommand,
+    walk_in: Option<WalkIn<'cycle>>,
 }
 
 impl<'cycle> WalkAndStand<'cycle> {
@@ -145,12 +244,14 @@ impl<'cycle> WalkAndStand<'cycle> {
         parameters: &'cycle WalkAndStandParameters,
         walk_path_planner: &'cycle WalkPathPlanner,
         last_motion_command: &'cycle MotionCommand,
+        walk_in: Option<WalkIn<'cycle>>,
     ) -> Self {
         Self {
             world_state,
             parameters,
             walk_path_planner,
             last_motion_command,
+            walk_in,
         }
     }
 
@@ -161,6 +262,19 @@ impl<'cycle> WalkAndStand<'cycle> {
         path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
     ) -> Option<MotionCommand> {
         let ground_to_field = self.world_state.robot.ground_to_field?;
+        let target_pose = match self.walk_in.as_ref().map(|walk_in| {
+            walk_in.step(
+                ground_to_field.as_pose().position(),
+                ground_to_field * target_pose.position(),
+            )
+        }) {
+            Some(WalkInStep::Wait) => return Some(MotionCommand::Stand { head }),
+            Some(WalkInStep::Via(waypoint)) => {
+                let waypoint = ground_to_field.inverse() * waypoint;
+                Pose2::new(waypoint.coords(), waypoint.y().atan2(waypoint.x()))
+            }
+            Some(WalkInStep::Direct) | None => target_pose,
+        };
         let distance_to_walk = target_pose.position().coords().norm();
         let angle_to_walk = target_pose.orientation().angle();
         let was_standing_last_cycle =
@@ -185,7 +299,7 @@ impl<'cycle> WalkAndStand<'cycle> {
         if is_reached {
             Some(MotionCommand::Stand { head })
         } else {
-            let path = self.walk_path_planner.plan(
+            let planned_path = self.walk_path_planner.plan(
                 target_pose.position(),
                 ground_to_field,
                 self.world_state.ball.map(|ball| ball.ball_in_ground),
@@ -194,15 +308,19 @@ impl<'cycle> WalkAndStand<'cycle> {
                 &self.world_state.rule_obstacles,
                 path_obstacles_output,
             );
-            Some(self.walk_path_planner.walk_with_obstacle_avoiding_arms(
+            Some(self.walk_path_planner.walk_along_planned_path(
                 head,
                 orientation_mode,
-                path,
+                planned_path,
             ))
         }
     }
 }
 
+fn path_length(path: &[PathSegment]) -> f32 {
+    path.iter().map(|segment| segment.length()).sum()
+}
+
 pub fn hybrid_alignment(
     target_pose: Pose2<Ground>,
     hybrid_align_distance: f32,
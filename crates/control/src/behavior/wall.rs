@@ -0,0 +1,154 @@
+use coordinate_systems::Field;
+use framework::AdditionalOutput;
+use geometry::look_at::LookAt;
+use linear_algebra::{distance, point, Point2, Pose2, Rotation2};
+use spl_network_messages::{SubState, Team};
+use types::{
+    field_dimensions::FieldDimensions, filtered_game_controller_state::FilteredGameControllerState,
+    filtered_game_state::FilteredGameState, motion_command::MotionCommand,
+    parameters::WallParameters, path_obstacles::PathObstacle, rule_set::RuleSet,
+    world_state::WorldState,
+};
+
+use super::{free_kick::clamp_to_field, head::LookAction, walk_to_pose::WalkAndStand};
+
+/// Lines up the defenders and midfielders on an arc between the ball and the own goal during
+/// opponent kick-offs and free kicks close to the own goal.
+///
+/// Every role owns one slot of the arc, since roles are unique within the team no two robots
+/// walk to the same spot.
+pub fn execute(
+    world_state: &WorldState,
+    field_dimensions: &FieldDimensions,
+    parameters: &WallParameters,
+    rule_set: RuleSet,
+    walk_and_stand: &WalkAndStand,
+    look_action: &LookAction,
+    path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
+) -> Option<MotionCommand> {
+    if !parameters.enable {
+        return None;
+    }
+    let ground_to_field = world_state.robot.ground_to_field?;
+    let slot_index = parameters
+        .slot_roles
+        .iter()
+        .position(|role| *role == world_state.robot.role)?;
+    let ball = world_state
+        .ball
+        .or(world_state.rule_ball)
+        .map(|ball| ball.ball_in_field);
+    let own_goal = point![-field_dimensions.length / 2.0, 0.0];
+
+    let (ball, distance_to_ball) = match world_state.filtered_game_controller_state? {
+        FilteredGameControllerState {
+            game_state:
+                FilteredGameState::Ready {
+                    kicking_team: Team::Opponent,
+                },
+            sub_state: None,
+            ..
+        }
+        | FilteredGameControllerState {
+            game_state:
+                FilteredGameState::Playing {
+                    ball_is_free: false,
+                    kick_off: true,
+                },
+            kicking_team: Team::Opponent,
+            sub_state: None,
+            ..
+        } => (
+            // the ball is not necessarily seen while walking in during Ready
+            ball.unwrap_or_else(Point2::origin),
+            field_dimensions.center_circle_diameter / 2.0 + parameters.distance_margin,
+        ),
+        FilteredGameControllerState {
+            game_state: FilteredGameState::Playing { .. },
+            sub_state:
+                Some(
+                    SubState::CornerKick
+                    | SubState::GoalKick
+                    | SubState::KickIn
+                    | SubState::PushingFreeKick,
+                ),
+            kicking_team: Team::Opponent,
+            ..
+        } => {
+            let ball = ball?;
+            if distance(ball, own_goal) > parameters.maximum_ball_distance_to_own_goal {
+                return None;
+            }
+            (
+                ball,
+                rule_set.free_kick_distance() + parameters.distance_margin,
+            )
+        }
+        _ => return None,
+    };
+
+    let slot = slot_position(
+        ball,
+        own_goal,
+        distance_to_ball,
+        slot_index,
+        parameters.slot_roles.len(),
+        parameters.slot_spacing,
+    )?;
+    let slot = clamp_to_field(slot, field_dimensions);
+    let slot_pose = Pose2::new(slot.coords(), slot.look_at(&ball).angle());
+    walk_and_stand.execute(
+        ground_to_field.inverse() * slot_pose,
+        look_action.execute(),
+        path_obstacles_output,
+    )
+}
+
+/// Slots are spread on the arc around the ball centered on the line to the own goal, the first
+/// slot is the leftmost one as seen from the own goal.
+fn slot_position(
+    ball: Point2<Field>,
+    own_goal: Point2<Field>,
+    distance_to_ball: f32,
+    slot_index: usize,
+    number_of_slots: usize,
+    slot_spacing: f32,
+) -> Option<Point2<Field>> {
+    let ball_to_goal = own_goal - ball;
+    if ball_to_goal.norm() <= distance_to_ball {
+        return None;
+    }
+    let lateral_offset = (slot_index as f32 - (number_of_slots - 1) as f32 / 2.0) * slot_spacing;
+    let angle = lateral_offset / distance_to_ball;
+    Some(ball + Rotation2::new(angle) * ball_to_goal.normalize() * distance_to_ball)
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn slots_are_spread_on_the_arc_between_ball_and_goal() {
+        let ball = point![0.0, 0.0];
+        let own_goal = point![-4.5, 0.0];
+        let slots: Vec<_> = (0..4)
+            .map(|index| slot_position(ball, own_goal, 1.0, index, 4, 0.4).unwrap())
+            .collect();
+
+        for slot in &slots {
+            assert_relative_eq!(distance(*slot, ball), 1.0, epsilon = 0.001);
+            assert!(slot.x() < ball.x());
+        }
+        for (left, right) in slots.iter().zip(slots.iter().skip(1)) {
+            assert!(left.y() > right.y());
+        }
+        assert_relative_eq!(slots[1].y(), -slots[2].y(), epsilon = 0.001);
+    }
+
+    #[test]
+    fn no_wall_if_ball_is_closer_to_goal_than_the_arc() {
+        assert!(slot_position(point![-4.0, 0.0], point![-4.5, 0.0], 1.0, 0, 4, 0.4).is_none());
+    }
+}
@@ -0,0 +1,41 @@
+use framework::AdditionalOutput;
+use geometry::look_at::LookAt;
+use linear_algebra::{point, Point2, Pose2};
+use types::{
+    field_dimensions::FieldDimensions, motion_command::MotionCommand,
+    parameters::GoalMouthParameters, path_obstacles::PathObstacle, world_state::WorldState,
+};
+
+use super::{head::LookAction, walk_to_pose::WalkAndStand};
+
+/// Leaves the corridor between the ball and the clearing target to the teammate that claimed the
+/// goal mouth and covers the far side of the goal from the opposite post in the meantime.
+pub fn execute(
+    world_state: &WorldState,
+    field_dimensions: &FieldDimensions,
+    parameters: &GoalMouthParameters,
+    walk_and_stand: &WalkAndStand,
+    look_action: &LookAction,
+    path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
+) -> Option<MotionCommand> {
+    let claim = world_state.teammate_goal_mouth_claim?;
+    let ground_to_field = world_state.robot.ground_to_field?;
+    let side = if claim.clearing_target.y() < 0.0 {
+        1.0
+    } else {
+        -1.0
+    };
+    let post_position = point![
+        -field_dimensions.length / 2.0 + parameters.post_distance_to_goal_line,
+        side * field_dimensions.goal_inner_width / 2.0
+    ];
+    let ball = world_state
+        .ball
+        .map_or(Point2::origin(), |ball| ball.ball_in_field);
+    let pose = Pose2::new(post_position.coords(), post_position.look_at(&ball).angle());
+    walk_and_stand.execute(
+        ground_to_field.inverse() * pose,
+        look_action.execute(),
+        path_obstacles_output,
+    )
+}
@@ -10,6 +10,7 @@ use types::{buttons::Buttons, cycle_time::CycleTime, sensor_data::SensorData};
 #[derive(Deserialize, Serialize)]
 pub struct ButtonFilter {
     chest_button_tap_detector: TapDetector,
+    head_middle_button_tap_detector: TapDetector,
     head_buttons_touched: SystemTime,
     last_head_buttons_touched: bool,
     calibration_buttons_touched: SystemTime,
@@ -38,6 +39,7 @@ impl ButtonFilter {
     pub fn new(_context: CreationContext) -> Result<Self> {
         Ok(Self {
             chest_button_tap_detector: TapDetector::default(),
+            head_middle_button_tap_detector: TapDetector::default(),
             head_buttons_touched: UNIX_EPOCH,
             last_head_buttons_touched: false,
             calibration_buttons_touched: UNIX_EPOCH,
@@ -52,6 +54,8 @@ impl ButtonFilter {
 
         self.chest_button_tap_detector
             .update(touch_sensors.chest_button);
+        self.head_middle_button_tap_detector
+            .update(touch_sensors.head_middle);
 
         let head_buttons_touched =
             touch_sensors.head_front && touch_sensors.head_middle && touch_sensors.head_rear;
@@ -93,6 +97,9 @@ impl ButtonFilter {
                 is_chest_button_pressed: self.chest_button_tap_detector.is_single_tapped(),
                 head_buttons_touched: debounced_head_buttons_touched,
                 calibration_buttons_touched: debounced_calibration_buttons_touched,
+                is_head_middle_button_tapped: self
+                    .head_middle_button_tap_detector
+                    .is_single_tapped(),
             }
             .into(),
         })
@@ -0,0 +1,83 @@
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use context_attribute::context;
+use framework::MainOutput;
+use types::{
+    cycle_time::CycleTime, cycler_rates::CyclerRateLimits,
+    game_controller_state::MessageBudgetStatus, parameters::CyclerRatesParameters,
+};
+
+/// Slows down the non-critical perception cyclers while the control cycler misses its deadlines
+#[derive(Deserialize, Serialize)]
+pub struct CyclerRateGovernor {
+    average_cycle_duration: f32,
+    is_under_cpu_pressure: bool,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    cycle_time: Input<CycleTime, "cycle_time">,
+    message_budget_status: Input<Option<MessageBudgetStatus>, "message_budget_status?">,
+
+    parameters: Parameter<CyclerRatesParameters, "cycler_rates">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub cycler_rate_limits: MainOutput<CyclerRateLimits>,
+}
+
+impl CyclerRateGovernor {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            average_cycle_duration: 0.0,
+            is_under_cpu_pressure: false,
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        let parameters = context.parameters;
+
+        self.average_cycle_duration = (1.0 - parameters.smoothing_factor)
+            * self.average_cycle_duration
+            + parameters.smoothing_factor * context.cycle_time.last_cycle_duration.as_secs_f32();
+        let maximum_cycle_duration = parameters.maximum_control_cycle_duration.as_secs_f32();
+        let pressure_threshold = if self.is_under_cpu_pressure {
+            parameters.release_ratio * maximum_cycle_duration
+        } else {
+            maximum_cycle_duration
+        };
+        self.is_under_cpu_pressure =
+            parameters.enable_downscaling && self.average_cycle_duration > pressure_threshold;
+
+        // teammates already send as little as possible, none of their scarce messages is delayed
+        let is_message_budget_low = context
+            .message_budget_status
+            .is_some_and(|status| status.warning_level > 0);
+
+        let audio = if self.is_under_cpu_pressure {
+            parameters.audio.downscaled_frequency
+        } else {
+            parameters.audio.maximum_frequency
+        };
+        let spl_network = if self.is_under_cpu_pressure && !is_message_budget_low {
+            parameters.spl_network.downscaled_frequency
+        } else {
+            parameters.spl_network.maximum_frequency
+        };
+
+        Ok(MainOutputs {
+            cycler_rate_limits: CyclerRateLimits {
+                audio,
+                spl_network,
+                is_under_cpu_pressure: self.is_under_cpu_pressure,
+            }
+            .into(),
+        })
+    }
+}
@@ -1,20 +1,31 @@
+use coordinate_systems::{Field, Ground};
 use framework::AdditionalOutput;
+use linear_algebra::{vector, Point2, Vector2};
 use spl_network_messages::Team;
 use std::f32::consts::PI;
 use types::{
-    filtered_game_controller_state::FilteredGameControllerState, parameters::DribblingParameters,
-    path_obstacles::PathObstacle, planned_path::PathSegment, world_state::WorldState,
+    field_dimensions::FieldDimensions,
+    filtered_game_controller_state::FilteredGameControllerState,
+    kick_decision::KickDecision,
+    parameters::{DribbleCorridorParameters, DribblingParameters, InWalkKicksParameters},
+    path_obstacles::PathObstacle,
+    planned_path::PathSegment,
+    support_foot::Side,
+    world_state::WorldState,
 };
 
-use crate::behavior::walk_to_pose::WalkPathPlanner;
+use crate::{
+    behavior::walk_to_pose::WalkPathPlanner,
+    kick_selector::{compute_kick_pose, sort_kick_decisions},
+};
 
 pub fn plan(
     walk_path_planner: &WalkPathPlanner,
     world_state: &WorldState,
+    kick_decisions: &[KickDecision],
     dribbling_parameters: &DribblingParameters,
     path_obstacles_output: &mut AdditionalOutput<Vec<PathObstacle>>,
 ) -> Option<Vec<PathSegment>> {
-    let kick_decisions = world_state.kick_decisions.as_ref()?;
     let best_kick_decision = kick_decisions.first()?;
     let ball = world_state.ball?;
     let ground_to_field = world_state.robot.ground_to_field?;
@@ -60,13 +71,148 @@ pub fn plan(
         world_state.rule_obstacles.as_slice()
     };
 
-    Some(walk_path_planner.plan(
-        best_pose.position(),
-        ground_to_field,
-        ball_obstacle,
-        ball_obstacle_radius_factor,
-        obstacles,
-        rule_obstacles,
-        path_obstacles_output,
-    ))
+    Some(
+        walk_path_planner
+            .plan(
+                best_pose.position(),
+                ground_to_field,
+                ball_obstacle,
+                ball_obstacle_radius_factor,
+                obstacles,
+                rule_obstacles,
+                path_obstacles_output,
+            )
+            .path,
+    )
+}
+
+/// Turns kicks which would roll the ball over a sideline or the opponent ground line next to the
+/// goal into kicks along that boundary, the kick pose is moved accordingly.
+///
+/// Moved kick poses change the ranking of the kick selector, the decisions are sorted again.
+pub fn constrain_to_corridor(
+    kick_decisions: &[KickDecision],
+    world_state: &WorldState,
+    in_walk_kicks: &InWalkKicksParameters,
+    field_dimensions: &FieldDimensions,
+    parameters: &DribbleCorridorParameters,
+    kick_pose_obstacle_radius: f32,
+    angle_distance_weight: f32,
+) -> Vec<KickDecision> {
+    let (Some(ball), Some(ground_to_field)) = (world_state.ball, world_state.robot.ground_to_field)
+    else {
+        return kick_decisions.to_vec();
+    };
+    if !parameters.enable {
+        return kick_decisions.to_vec();
+    }
+    let mut constrained_decisions: Vec<_> = kick_decisions
+        .iter()
+        .map(|decision| {
+            let kick_info = &in_walk_kicks[decision.variant];
+            let direction = shot_direction(decision, kick_info.orientation);
+            match constrain_direction(
+                ball.ball_in_field,
+                ground_to_field * direction,
+                field_dimensions,
+                parameters,
+            ) {
+                Some(constrained_direction) => {
+                    let target = ball.ball_in_ground
+                        + (ground_to_field.inverse() * constrained_direction)
+                            * kick_info.shot_distance;
+                    KickDecision {
+                        kick_pose: compute_kick_pose(
+                            ball.ball_in_ground,
+                            target,
+                            kick_info,
+                            decision.kicking_side,
+                        ),
+                        ..*decision
+                    }
+                }
+                None => *decision,
+            }
+        })
+        .collect();
+    sort_kick_decisions(
+        &mut constrained_decisions,
+        ball.ball_in_ground,
+        &world_state.obstacles,
+        in_walk_kicks,
+        kick_pose_obstacle_radius,
+        angle_distance_weight,
+    );
+    constrained_decisions
+}
+
+/// Whether the kick keeps the ball inside the dribble corridor without constraining it
+pub fn is_inside_corridor(
+    decision: &KickDecision,
+    world_state: &WorldState,
+    in_walk_kicks: &InWalkKicksParameters,
+    field_dimensions: &FieldDimensions,
+    parameters: &DribbleCorridorParameters,
+) -> bool {
+    let (Some(ball), Some(ground_to_field)) = (world_state.ball, world_state.robot.ground_to_field)
+    else {
+        return true;
+    };
+    let direction = shot_direction(decision, in_walk_kicks[decision.variant].orientation);
+    !parameters.enable
+        || constrain_direction(
+            ball.ball_in_field,
+            ground_to_field * direction,
+            field_dimensions,
+            parameters,
+        )
+        .is_none()
+}
+
+fn shot_direction(decision: &KickDecision, kick_orientation: f32) -> Vector2<Ground> {
+    let angle = match decision.kicking_side {
+        Side::Left => decision.kick_pose.orientation().angle() - kick_orientation,
+        Side::Right => decision.kick_pose.orientation().angle() + kick_orientation,
+    };
+    vector![angle.cos(), angle.sin()]
+}
+
+/// Returns the direction with the components pointing out of the corridor removed, `None` if the
+/// direction already keeps the ball inside.
+fn constrain_direction(
+    ball: Point2<Field>,
+    direction: Vector2<Field>,
+    field_dimensions: &FieldDimensions,
+    parameters: &DribbleCorridorParameters,
+) -> Option<Vector2<Field>> {
+    let rolled_ball = ball + direction * parameters.roll_distance;
+    let sideline = field_dimensions.width / 2.0 - parameters.boundary_margin;
+    let ground_line = field_dimensions.length / 2.0 - parameters.boundary_margin;
+    // rolling over the ground line between the posts is a shot on goal
+    let is_towards_goal = rolled_ball.y().abs() < field_dimensions.goal_inner_width / 2.0;
+
+    let leaves_over_sideline =
+        rolled_ball.y().abs() > sideline && rolled_ball.y() * direction.y() > 0.0;
+    let leaves_over_ground_line =
+        rolled_ball.x() > ground_line && direction.x() > 0.0 && !is_towards_goal;
+    if !leaves_over_sideline && !leaves_over_ground_line {
+        return None;
+    }
+
+    let constrained_x = if leaves_over_ground_line {
+        0.0
+    } else {
+        direction.x()
+    };
+    let constrained_y = if leaves_over_sideline {
+        0.0
+    } else {
+        direction.y()
+    };
+    let constrained_direction = vector![constrained_x, constrained_y];
+    Some(
+        constrained_direction
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(|| (Point2::origin() - ball).normalize()),
+    )
 }
@@ -7,18 +7,21 @@ use serde::{Deserialize, Serialize};
 use context_attribute::context;
 use coordinate_systems::{Field, Ground};
 use framework::MainOutput;
-use spl_network_messages::HulkMessage;
+use spl_network_messages::{GameControllerStateDigest, HulkMessage};
 use types::{
+    acoustic_signals::HeardAcousticSignals,
     ball_position::BallPosition,
     cycle_time::CycleTime,
     fall_state::FallState,
     filtered_whistle::FilteredWhistle,
-    game_controller_state::GameControllerState,
+    game_controller_state::{GameControllerState, MessageBudgetStatus},
     joints::head::HeadJoints,
     obstacles::Obstacle,
     parameters::{BallFilterParameters, CameraMatrixParameters, LookAtParameters},
     penalty_shot_direction::PenaltyShotDirection,
     primary_state::PrimaryState,
+    ready_signal::FilteredReadySignal,
+    robot_condition::RobotCondition,
     sensor_data::SensorData,
 };
 
@@ -46,14 +49,21 @@ pub struct MainOutputs {
     pub ball_position: MainOutput<Option<BallPosition<Ground>>>,
     pub cycle_time: MainOutput<CycleTime>,
     pub fall_state: MainOutput<FallState>,
+    pub filtered_ready_signal: MainOutput<FilteredReadySignal>,
     pub filtered_whistle: MainOutput<FilteredWhistle>,
     pub game_controller_state: MainOutput<Option<GameControllerState>>,
+    pub game_controller_state_digest: MainOutput<Option<GameControllerStateDigest>>,
     pub has_ground_contact: MainOutput<bool>,
+    pub heard_acoustic_signals: MainOutput<HeardAcousticSignals>,
     pub hulk_messages: MainOutput<Vec<HulkMessage>>,
+    pub is_converging_after_reentry: MainOutput<bool>,
+    pub localization_uncertainty: MainOutput<Option<f32>>,
+    pub message_budget_status: MainOutput<Option<MessageBudgetStatus>>,
     pub obstacles: MainOutput<Vec<Obstacle>>,
     pub penalty_shot_direction: MainOutput<Option<PenaltyShotDirection>>,
     pub primary_state: MainOutput<PrimaryState>,
     pub ground_to_field: MainOutput<Option<Isometry2<Ground, Field>>>,
+    pub robot_condition: MainOutput<RobotCondition>,
     pub sensor_data: MainOutput<SensorData>,
     pub stand_up_front_estimated_remaining_duration: MainOutput<Option<Duration>>,
     pub stand_up_back_estimated_remaining_duration: MainOutput<Option<Duration>>,
@@ -0,0 +1,102 @@
+use color_eyre::Result;
+use context_attribute::context;
+use coordinate_systems::{Field, Ground};
+use framework::{MainOutput, PerceptionInput};
+use linear_algebra::{Isometry2, Pose2};
+use serde::{Deserialize, Serialize};
+use spl_network_messages::PlayerNumber;
+use types::{
+    cycle_time::CycleTime, field_coverage::FieldCoverage, field_dimensions::FieldDimensions,
+    field_grid::FieldGrid, messages::IncomingMessage, parameters::FieldCoverageParameters,
+    sensor_data::SensorData,
+};
+
+#[derive(Deserialize, Serialize)]
+pub struct FieldCoverageMapper {
+    coverage: FieldCoverage,
+}
+
+#[context]
+pub struct CreationContext {
+    parameters: Parameter<FieldCoverageParameters, "field_coverage">,
+}
+
+#[context]
+pub struct CycleContext {
+    ground_to_field: Input<Option<Isometry2<Ground, Field>>, "ground_to_field?">,
+    cycle_time: Input<CycleTime, "cycle_time">,
+    has_ground_contact: Input<bool, "has_ground_contact">,
+    sensor_data: Input<SensorData, "sensor_data">,
+    network_message: PerceptionInput<Option<IncomingMessage>, "SplNetwork", "filtered_message?">,
+
+    field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
+    parameters: Parameter<FieldCoverageParameters, "field_coverage">,
+    player_number: Parameter<PlayerNumber, "player_number">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub field_coverage: MainOutput<FieldCoverage>,
+}
+
+impl FieldCoverageMapper {
+    pub fn new(context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            coverage: FieldCoverage::new(FieldGrid::new(
+                context.parameters.number_of_columns,
+                context.parameters.number_of_rows,
+            )),
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        let parameters = context.parameters;
+        let field_dimensions = context.field_dimensions;
+
+        for (receive_time, message) in context
+            .network_message
+            .persistent
+            .iter()
+            .flat_map(|(receive_time, messages)| {
+                messages.iter().map(move |message| (*receive_time, message))
+            })
+            .filter_map(|(receive_time, message)| match message {
+                Some(IncomingMessage::Spl(message)) => Some((receive_time, message)),
+                _ => None,
+            })
+            .filter(|(_, message)| message.player_number != *context.player_number)
+            .filter(|(_, message)| !message.fallen)
+        {
+            self.coverage.observe(
+                message.pose,
+                parameters.teammate_field_of_view,
+                parameters.observation_distance,
+                receive_time,
+                field_dimensions,
+            );
+        }
+
+        if let Some(ground_to_field) = context
+            .ground_to_field
+            .filter(|_| *context.has_ground_contact)
+        {
+            let robot_pose = ground_to_field.as_pose();
+            let camera = Pose2::new(
+                robot_pose.position().coords(),
+                robot_pose.angle() + context.sensor_data.positions.head.yaw,
+            );
+            self.coverage.observe(
+                camera,
+                parameters.field_of_view,
+                parameters.observation_distance,
+                context.cycle_time.start_time,
+                field_dimensions,
+            );
+        }
+
+        Ok(MainOutputs {
+            field_coverage: self.coverage.clone().into(),
+        })
+    }
+}
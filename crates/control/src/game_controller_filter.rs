@@ -1,17 +1,68 @@
-use std::time::SystemTime;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    net::IpAddr,
+    time::{Duration, SystemTime},
+};
 
 use color_eyre::Result;
 use context_attribute::context;
 use framework::{MainOutput, PerceptionInput};
+use hardware::SpeakerInterface;
+use log::{info, warn};
 use serde::{Deserialize, Serialize};
+use spl_network_messages::{
+    GameControllerStateDigest, GameState, Half, Penalty, PlayerNumber, Team,
+};
 use types::{
-    cycle_time::CycleTime, game_controller_state::GameControllerState, messages::IncomingMessage,
+    audio::SpeakerRequest,
+    cycle_time::CycleTime,
+    filtered_whistle::{FilteredWhistle, WhistlePattern},
+    game_controller_state::{
+        ActiveGameController, GameControllerSelectionReason, GameControllerState,
+        MessageBudgetStatus,
+    },
+    messages::{GameControllerDestination, IncomingMessage},
+    parameters::{
+        GameControllerFallbackParameters, GameControllerRebroadcastParameters,
+        KickingTeamFilterParameters, MessageBudgetParameters, SplNetworkParameters,
+    },
+    players::Players,
+    rule_set::RuleSet,
 };
 
+const PENALTY_TIMER_RESOLUTION: Duration = Duration::from_secs(1);
+
 #[derive(Deserialize, Serialize)]
 pub struct GameControllerFilter {
     game_controller_state: Option<GameControllerState>,
     last_game_state_change: Option<SystemTime>,
+    last_received_state: Option<SystemTime>,
+    last_contacts: BTreeMap<IpAddr, SystemTime>,
+    number_of_consistent_messages: BTreeMap<IpAddr, usize>,
+    /// Controllers whose latest message was sent to the own team port
+    team_port_sources: BTreeSet<IpAddr>,
+    /// Opponent team number sent by the active controller
+    opponent_team_number: Option<u8>,
+    rejected_sources: BTreeSet<IpAddr>,
+    active_game_controller: Option<ActiveGameController>,
+    announced_message_budget_warning_level: usize,
+    last_message_budget_warning: Option<SystemTime>,
+    filtered_kicking_team: Option<Team>,
+    kicking_team_candidate: Option<KickingTeamCandidate>,
+    latest_rebroadcast: Option<(SystemTime, GameControllerStateDigest)>,
+    /// The state was received from a teammate instead of the GameController
+    is_rebroadcast_state: bool,
+    /// Latest transition of the locally advanced state following the own whistle, it is relayed
+    /// to teammates which may have missed the whistle
+    last_whistle_transition: Option<SystemTime>,
+    penalty_release_times: Players<Option<SystemTime>>,
+}
+
+#[derive(Clone, Copy, Deserialize, Serialize)]
+struct KickingTeamCandidate {
+    team: Team,
+    first_received: SystemTime,
+    number_of_messages: usize,
 }
 
 #[context]
@@ -20,13 +71,23 @@ pub struct CreationContext {}
 #[context]
 pub struct CycleContext {
     cycle_time: Input<CycleTime, "cycle_time">,
+    filtered_whistle: Input<FilteredWhistle, "filtered_whistle">,
     network_message: PerceptionInput<Option<IncomingMessage>, "SplNetwork", "filtered_message?">,
+
+    spl_network: Parameter<SplNetworkParameters, "spl_network">,
+    rule_set: Parameter<RuleSet, "rule_set">,
+
+    hardware_interface: HardwareInterface,
 }
 
 #[context]
 #[derive(Default)]
 pub struct MainOutputs {
     pub game_controller_state: MainOutput<Option<GameControllerState>>,
+    pub active_game_controller: MainOutput<Option<ActiveGameController>>,
+    pub message_budget_status: MainOutput<Option<MessageBudgetStatus>>,
+    /// Only present while the GameController is received directly
+    pub game_controller_state_digest: MainOutput<Option<GameControllerStateDigest>>,
 }
 
 impl GameControllerFilter {
@@ -34,21 +95,104 @@ impl GameControllerFilter {
         Ok(Self {
             game_controller_state: None,
             last_game_state_change: None,
+            last_received_state: None,
+            last_contacts: BTreeMap::new(),
+            number_of_consistent_messages: BTreeMap::new(),
+            team_port_sources: BTreeSet::new(),
+            opponent_team_number: None,
+            rejected_sources: BTreeSet::new(),
+            active_game_controller: None,
+            announced_message_budget_warning_level: 0,
+            last_message_budget_warning: None,
+            filtered_kicking_team: None,
+            kicking_team_candidate: None,
+            latest_rebroadcast: None,
+            is_rebroadcast_state: false,
+            last_whistle_transition: None,
+            penalty_release_times: Players::default(),
         })
     }
 
-    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
-        for game_controller_state_message in context
+    pub fn cycle(&mut self, context: CycleContext<impl SpeakerInterface>) -> Result<MainOutputs> {
+        for (receive_time, address, destination, game_controller_state_message) in context
             .network_message
             .persistent
-            .values()
-            .flatten()
-            .flatten()
-            .filter_map(|message| match message {
-                IncomingMessage::GameController(message) => Some(message),
-                IncomingMessage::Spl(_) => None,
+            .iter()
+            .flat_map(|(receive_time, messages)| {
+                messages
+                    .iter()
+                    .flatten()
+                    .map(move |message| (*receive_time, message))
+            })
+            .filter_map(|(receive_time, message)| match message {
+                IncomingMessage::GameController(address, destination, message) => {
+                    Some((receive_time, address.ip(), *destination, message))
+                }
+                IncomingMessage::Spl(message) => {
+                    if let Some(digest) = message.game_controller_state {
+                        if self
+                            .latest_rebroadcast
+                            .map_or(true, |(latest, _)| latest <= receive_time)
+                        {
+                            self.latest_rebroadcast = Some((receive_time, digest));
+                        }
+                    }
+                    None
+                }
             })
         {
+            let is_active = self
+                .active_game_controller
+                .is_some_and(|active| active.address == address);
+            // only parsable messages naming our team arrive here, a controller announcing another
+            // opponent than the active one is set up for a different game
+            let opponent_team_number = game_controller_state_message.opponent_team.team_number;
+            if !is_active
+                && self
+                    .opponent_team_number
+                    .is_some_and(|active_opponent| active_opponent != opponent_team_number)
+            {
+                if self.rejected_sources.insert(address) {
+                    warn!(
+                        "ignoring GameController at {address} advertising a game against team {opponent_team_number}"
+                    );
+                }
+                self.number_of_consistent_messages.remove(&address);
+                continue;
+            }
+            let is_interrupted = self
+                .last_contacts
+                .get(&address)
+                .is_some_and(|last_contact| {
+                    receive_time
+                        .duration_since(*last_contact)
+                        .unwrap_or_default()
+                        > context
+                            .spl_network
+                            .game_controller_handover_maximum_message_gap
+                });
+            if is_interrupted {
+                self.number_of_consistent_messages.remove(&address);
+            }
+            self.last_contacts.insert(address, receive_time);
+            *self
+                .number_of_consistent_messages
+                .entry(address)
+                .or_default() += 1;
+            if destination == GameControllerDestination::TeamPort {
+                self.team_port_sources.insert(address);
+            } else {
+                self.team_port_sources.remove(&address);
+            }
+            self.select_game_controller(receive_time, context.spl_network);
+            if self
+                .active_game_controller
+                .map_or(true, |active| active.address != address)
+            {
+                continue;
+            }
+            self.opponent_team_number = Some(opponent_team_number);
+
             let game_state_changed = match &self.game_controller_state {
                 Some(game_controller_state) => {
                     game_controller_state.game_state != game_controller_state_message.game_state
@@ -58,22 +202,1100 @@ impl GameControllerFilter {
             if game_state_changed {
                 self.last_game_state_change = Some(context.cycle_time.start_time);
             }
+            self.last_received_state = Some(receive_time);
+            self.is_rebroadcast_state = false;
+            let kicking_team = self.filter_kicking_team(
+                game_controller_state_message.kicking_team,
+                receive_time,
+                &context.spl_network.kicking_team_filter,
+            );
+            let penalties: Players<Option<Penalty>> =
+                game_controller_state_message.hulks_team.clone().into();
+            self.track_penalty_release_times(&penalties, receive_time);
             self.game_controller_state = Some(GameControllerState {
                 game_state: game_controller_state_message.game_state,
                 game_phase: game_controller_state_message.game_phase,
-                kicking_team: game_controller_state_message.kicking_team,
+                kicking_team,
+                raw_kicking_team: game_controller_state_message.kicking_team,
                 last_game_state_change: self.last_game_state_change.unwrap(),
-                penalties: game_controller_state_message.hulks_team.clone().into(),
+                penalties,
+                remaining_penalty_durations: Players::default(),
                 remaining_amount_of_messages: game_controller_state_message
                     .hulks_team
                     .remaining_amount_of_messages,
                 sub_state: game_controller_state_message.sub_state,
                 hulks_team_is_home_after_coin_toss: game_controller_state_message
                     .hulks_team_is_home_after_coin_toss,
+                half: game_controller_state_message.half,
+                remaining_time_in_half: game_controller_state_message.remaining_time_in_half,
+                hulks_score: game_controller_state_message.hulks_team.score,
+                opponent_score: game_controller_state_message.opponent_team.score,
+                staleness: Duration::ZERO,
+                is_fallback: false,
             });
         }
+        self.select_game_controller(context.cycle_time.start_time, context.spl_network);
+        if self.active_game_controller.is_none() {
+            self.apply_rebroadcast(
+                context.cycle_time.start_time,
+                &context.spl_network.game_controller_rebroadcast,
+            );
+        }
+        self.advance_stale_state(
+            context.cycle_time,
+            context.filtered_whistle,
+            &context.spl_network.game_controller_fallback,
+            context.spl_network.game_controller_rebroadcast.timeout,
+        );
+        if let Some(state) = self.game_controller_state.as_mut() {
+            state.remaining_penalty_durations = remaining_penalty_durations(
+                &self.penalty_release_times,
+                context.cycle_time.start_time,
+            );
+        }
+        let relays_whistle_transition = self.game_controller_state.is_some_and(|state| {
+            state.is_fallback
+                && self.last_whistle_transition.is_some_and(|transition| {
+                    context
+                        .cycle_time
+                        .start_time
+                        .duration_since(transition)
+                        .unwrap_or_default()
+                        < context.spl_network.game_controller_rebroadcast.timeout
+                })
+        });
+        let game_controller_state_digest = self
+            .game_controller_state
+            .filter(|_| {
+                (self.active_game_controller.is_some() || relays_whistle_transition)
+                    && context
+                        .spl_network
+                        .game_controller_rebroadcast
+                        .enable_sending
+            })
+            .map(|game_controller_state| digest(&game_controller_state));
+
+        let message_budget_status = self.game_controller_state.map(|game_controller_state| {
+            message_budget_status(
+                game_controller_state.remaining_amount_of_messages,
+                context.rule_set.message_budget(),
+                &context.spl_network.message_budget,
+            )
+        });
+        if let Some(status) = message_budget_status {
+            self.warn_about_message_budget(
+                status,
+                context.cycle_time.start_time,
+                &context.spl_network.message_budget,
+                context.hardware_interface,
+            );
+        }
+
         Ok(MainOutputs {
             game_controller_state: self.game_controller_state.into(),
+            active_game_controller: self.active_game_controller.into(),
+            message_budget_status: message_budget_status.into(),
+            game_controller_state_digest: game_controller_state_digest.into(),
         })
     }
+
+    /// Follows the game with the state rebroadcast by teammates, direct GameController messages
+    /// always take precedence.
+    fn apply_rebroadcast(
+        &mut self,
+        now: SystemTime,
+        parameters: &GameControllerRebroadcastParameters,
+    ) {
+        if !parameters.enable_receiving {
+            return;
+        }
+        let Some((receive_time, digest)) = self.latest_rebroadcast.filter(|(receive_time, _)| {
+            now.duration_since(*receive_time).unwrap_or_default() < parameters.timeout
+        }) else {
+            return;
+        };
+        let previous = self.game_controller_state;
+        let game_state_changed =
+            previous.map_or(true, |previous| previous.game_state != digest.game_state);
+        if game_state_changed {
+            self.last_game_state_change = Some(receive_time);
+        }
+        self.last_received_state = Some(receive_time);
+        self.is_rebroadcast_state = true;
+        self.filtered_kicking_team = Some(digest.kicking_team);
+        self.kicking_team_candidate = None;
+        let penalties = penalties_from_bits(digest.penalized_players);
+        self.track_penalty_release_times(&penalties, receive_time);
+        self.game_controller_state = Some(GameControllerState {
+            game_state: digest.game_state,
+            game_phase: digest.game_phase,
+            kicking_team: digest.kicking_team,
+            raw_kicking_team: digest.kicking_team,
+            last_game_state_change: self.last_game_state_change.unwrap_or(receive_time),
+            penalties,
+            remaining_penalty_durations: Players::default(),
+            remaining_amount_of_messages: digest.remaining_amount_of_messages,
+            sub_state: digest.sub_state,
+            hulks_team_is_home_after_coin_toss: digest.hulks_team_is_home_after_coin_toss,
+            // the half is not rebroadcast, it does not change while reception is broken
+            half: previous.map_or(Half::First, |previous| previous.half),
+            remaining_time_in_half: Duration::from_secs(digest.remaining_seconds_in_half.into()),
+            hulks_score: digest.hulks_score,
+            opponent_score: digest.opponent_score,
+            staleness: Duration::ZERO,
+            is_fallback: false,
+        });
+    }
+
+    /// Without any state received for the timeout, the game most likely goes on without the
+    /// GameController. The play starts with the whistle in Set, Ready ends after the usual
+    /// duration and a double whistle in Playing signals a goal. A state relayed by teammates is
+    /// stale as soon as the rebroadcast times out, since the relaying robots stopped following
+    /// the game. Transitions following the own whistle are relayed to the teammates, robots
+    /// which missed the whistle follow them instead of waiting in Set.
+    fn advance_stale_state(
+        &mut self,
+        cycle_time: &CycleTime,
+        filtered_whistle: &FilteredWhistle,
+        parameters: &GameControllerFallbackParameters,
+        rebroadcast_timeout: Duration,
+    ) {
+        let Some(state) = self.game_controller_state.as_mut() else {
+            return;
+        };
+        let now = cycle_time.start_time;
+        state.staleness = self
+            .last_received_state
+            .map_or(Duration::ZERO, |last_received| {
+                now.duration_since(last_received).unwrap_or_default()
+            });
+        let timeout = if self.is_rebroadcast_state {
+            parameters.timeout.min(rebroadcast_timeout)
+        } else {
+            parameters.timeout
+        };
+        let was_fallback = state.is_fallback;
+        state.is_fallback = parameters.enable && state.staleness > timeout;
+        if state.is_fallback != was_fallback {
+            if state.is_fallback {
+                warn!("no GameController state received, advancing the game locally");
+            } else {
+                info!("GameController state received again");
+            }
+        }
+        if !state.is_fallback {
+            return;
+        }
+
+        let is_goal = state.game_state == GameState::Playing
+            && filtered_whistle.pattern_this_cycle == Some(WhistlePattern::Double);
+        if is_goal {
+            // who scored is unknown without the GameController
+            state.kicking_team = Team::Uncertain;
+            state.raw_kicking_team = Team::Uncertain;
+        }
+        let next_game_state = match state.game_state {
+            GameState::Set if filtered_whistle.started_this_cycle => Some(GameState::Playing),
+            GameState::Playing if is_goal => Some(GameState::Ready),
+            GameState::Ready
+                if now
+                    .duration_since(state.last_game_state_change)
+                    .unwrap_or_default()
+                    > parameters.ready_duration =>
+            {
+                Some(GameState::Set)
+            }
+            _ => None,
+        };
+        if let Some(game_state) = next_game_state {
+            if game_state != GameState::Set {
+                self.last_whistle_transition = Some(now);
+            }
+            state.game_state = game_state;
+            state.last_game_state_change = now;
+            self.last_game_state_change = Some(now);
+        }
+        if state.game_state == GameState::Playing {
+            state.remaining_time_in_half = state
+                .remaining_time_in_half
+                .saturating_sub(cycle_time.last_cycle_duration);
+        }
+    }
+
+    /// The GameController sends the remaining penalty time in whole seconds. Each message bounds
+    /// the release from below, the bound is kept until a later message raises it (e.g. the
+    /// countdown paused) or contradicts it by more than the resolution (the penalty was reissued).
+    /// Penalties without countdown, like the ones rebroadcast by teammates, keep the previous
+    /// prediction.
+    fn track_penalty_release_times(
+        &mut self,
+        penalties: &Players<Option<Penalty>>,
+        receive_time: SystemTime,
+    ) {
+        for (player_number, penalty) in penalties.iter() {
+            let release_time = &mut self.penalty_release_times[player_number];
+            *release_time = match penalty.map(|penalty| penalty.remaining()) {
+                None => None,
+                Some(remaining) if remaining.is_zero() => *release_time,
+                Some(remaining) => {
+                    let earliest_release = receive_time + remaining;
+                    match *release_time {
+                        Some(previous)
+                            if (earliest_release..earliest_release + PENALTY_TIMER_RESOLUTION)
+                                .contains(&previous) =>
+                        {
+                            Some(previous)
+                        }
+                        _ => Some(earliest_release),
+                    }
+                }
+            };
+        }
+    }
+
+    /// Keeps the previous kicking team until the new one was received in enough consecutive
+    /// messages or for long enough.
+    fn filter_kicking_team(
+        &mut self,
+        kicking_team: Team,
+        receive_time: SystemTime,
+        parameters: &KickingTeamFilterParameters,
+    ) -> Team {
+        let filtered_kicking_team = *self.filtered_kicking_team.get_or_insert(kicking_team);
+        if kicking_team == filtered_kicking_team {
+            self.kicking_team_candidate = None;
+            return filtered_kicking_team;
+        }
+        let candidate = match self.kicking_team_candidate {
+            Some(candidate) if candidate.team == kicking_team => KickingTeamCandidate {
+                number_of_messages: candidate.number_of_messages + 1,
+                ..candidate
+            },
+            _ => KickingTeamCandidate {
+                team: kicking_team,
+                first_received: receive_time,
+                number_of_messages: 1,
+            },
+        };
+        let has_dwelled = receive_time
+            .duration_since(candidate.first_received)
+            .unwrap_or_default()
+            >= parameters.minimum_dwell_time;
+        if candidate.number_of_messages >= parameters.minimum_number_of_consistent_messages
+            || has_dwelled
+        {
+            self.filtered_kicking_team = Some(kicking_team);
+            self.kicking_team_candidate = None;
+            kicking_team
+        } else {
+            self.kicking_team_candidate = Some(candidate);
+            filtered_kicking_team
+        }
+    }
+
+    /// Announces every newly passed threshold once, but not more often than the minimum interval.
+    fn warn_about_message_budget(
+        &mut self,
+        status: MessageBudgetStatus,
+        now: SystemTime,
+        parameters: &MessageBudgetParameters,
+        hardware_interface: &impl SpeakerInterface,
+    ) {
+        if status.warning_level < self.announced_message_budget_warning_level {
+            // the budget was refilled, e.g. for the next game
+            self.announced_message_budget_warning_level = status.warning_level;
+        }
+        let may_warn = self
+            .last_message_budget_warning
+            .map_or(true, |last_warning| {
+                now.duration_since(last_warning).unwrap_or_default()
+                    >= parameters.minimum_warning_interval
+            });
+        if status.warning_level <= self.announced_message_budget_warning_level || !may_warn {
+            return;
+        }
+        warn!(
+            "message budget at {:.0} percent",
+            status.remaining_fraction * 100.0
+        );
+        hardware_interface.write_to_speakers(SpeakerRequest::PlayTones {
+            frequencies: [
+                parameters.warning_tone.low_frequency,
+                parameters.warning_tone.high_frequency,
+            ],
+            duration: parameters.warning_tone_duration,
+        });
+        self.announced_message_budget_warning_level = status.warning_level;
+        self.last_message_budget_warning = Some(now);
+    }
+
+    fn select_game_controller(&mut self, now: SystemTime, parameters: &SplNetworkParameters) {
+        let number_of_alive_controllers_before = self.last_contacts.len();
+        self.last_contacts.retain(|_, last_contact| {
+            now.duration_since(*last_contact).unwrap_or_default()
+                < parameters.game_controller_timeout
+        });
+        let last_contacts = &self.last_contacts;
+        self.number_of_consistent_messages
+            .retain(|address, _| last_contacts.contains_key(address));
+        self.team_port_sources
+            .retain(|address| last_contacts.contains_key(address));
+        let number_of_alive_controllers = self.last_contacts.len();
+        if number_of_alive_controllers > 1
+            && number_of_alive_controllers != number_of_alive_controllers_before
+        {
+            warn!("receiving from {number_of_alive_controllers} GameControllers");
+        }
+
+        // while a controller is active, others have to prove themselves with consistent messages
+        // before they may take over, a controller sending to the own team port is preferred over
+        // ones only broadcasting to the common state port
+        let active_address = self.active_game_controller.map(|active| active.address);
+        let team_port_sources = &self.team_port_sources;
+        let Some((&newest_address, &newest_contact)) = self
+            .last_contacts
+            .iter()
+            .filter(|(address, _)| {
+                active_address.map_or(true, |active_address| {
+                    **address == active_address
+                        || self.number_of_consistent_messages.get(address).is_some_and(
+                            |number_of_messages| {
+                                *number_of_messages
+                                    >= parameters
+                                        .game_controller_handover_minimum_number_of_messages
+                            },
+                        )
+                })
+            })
+            .max_by_key(|(address, last_contact)| {
+                (team_port_sources.contains(address), **last_contact)
+            })
+        else {
+            if let Some(active) = self.active_game_controller.take() {
+                warn!("lost contact to GameController at {}", active.address);
+            }
+            self.opponent_team_number = None;
+            self.rejected_sources.clear();
+            return;
+        };
+        let number_of_rejected_controllers = self.rejected_sources.len();
+
+        let reason = match self.active_game_controller {
+            None => Some(GameControllerSelectionReason::FirstContact),
+            Some(active) => match self.last_contacts.get(&active.address) {
+                None => Some(GameControllerSelectionReason::ActiveExpired),
+                Some(_)
+                    if self.team_port_sources.contains(&newest_address)
+                        && !self.team_port_sources.contains(&active.address) =>
+                {
+                    Some(GameControllerSelectionReason::AddressedToTeam)
+                }
+                Some(active_contact)
+                    if newest_contact
+                        .duration_since(*active_contact)
+                        .unwrap_or_default()
+                        > parameters.game_controller_handover_timeout =>
+                {
+                    Some(GameControllerSelectionReason::NewerStream)
+                }
+                Some(_) => None,
+            },
+        };
+
+        match (reason, self.active_game_controller.as_mut()) {
+            (Some(reason), _) => {
+                info!("switching to GameController at {newest_address} ({reason:?})");
+                self.active_game_controller = Some(ActiveGameController {
+                    address: newest_address,
+                    reason,
+                    since: now,
+                    number_of_alive_controllers,
+                    number_of_rejected_controllers,
+                });
+            }
+            (None, Some(active)) => {
+                active.number_of_alive_controllers = number_of_alive_controllers;
+                active.number_of_rejected_controllers = number_of_rejected_controllers;
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+fn digest(game_controller_state: &GameControllerState) -> GameControllerStateDigest {
+    let penalized_players = game_controller_state
+        .penalties
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, penalty))| penalty.is_some())
+        .fold(0u8, |bits, (index, _)| bits | 1 << index);
+    GameControllerStateDigest {
+        game_state: game_controller_state.game_state,
+        game_phase: game_controller_state.game_phase,
+        sub_state: game_controller_state.sub_state,
+        kicking_team: game_controller_state.kicking_team,
+        remaining_seconds_in_half: game_controller_state
+            .remaining_time_in_half
+            .as_secs()
+            .try_into()
+            .unwrap_or(u16::MAX),
+        penalized_players,
+        remaining_amount_of_messages: game_controller_state.remaining_amount_of_messages,
+        hulks_score: game_controller_state.hulks_score,
+        opponent_score: game_controller_state.opponent_score,
+        hulks_team_is_home_after_coin_toss: game_controller_state
+            .hulks_team_is_home_after_coin_toss,
+    }
+}
+
+/// The kind and remaining time of penalties are not rebroadcast
+fn penalties_from_bits(penalized_players: u8) -> Players<Option<Penalty>> {
+    let mut penalties = Players::<Option<Penalty>>::default();
+    for (index, player_number) in [
+        PlayerNumber::One,
+        PlayerNumber::Two,
+        PlayerNumber::Three,
+        PlayerNumber::Four,
+        PlayerNumber::Five,
+        PlayerNumber::Six,
+        PlayerNumber::Seven,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        if penalized_players & 1 << index != 0 {
+            penalties[player_number] = Some(Penalty::Manual {
+                remaining: Duration::ZERO,
+            });
+        }
+    }
+    penalties
+}
+
+fn remaining_penalty_durations(
+    penalty_release_times: &Players<Option<SystemTime>>,
+    now: SystemTime,
+) -> Players<Option<Duration>> {
+    let mut remaining_penalty_durations = Players::<Option<Duration>>::default();
+    for (player_number, release_time) in penalty_release_times.iter() {
+        remaining_penalty_durations[player_number] =
+            release_time.map(|release_time| release_time.duration_since(now).unwrap_or_default());
+    }
+    remaining_penalty_durations
+}
+
+fn message_budget_status(
+    remaining_amount_of_messages: u16,
+    message_budget: u16,
+    parameters: &MessageBudgetParameters,
+) -> MessageBudgetStatus {
+    let remaining_fraction = f32::from(remaining_amount_of_messages) / f32::from(message_budget);
+    let warning_level = parameters
+        .warning_thresholds
+        .iter()
+        .filter(|&&threshold| remaining_fraction < threshold)
+        .count();
+    MessageBudgetStatus {
+        remaining_fraction,
+        warning_level,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        net::{Ipv4Addr, SocketAddr},
+        sync::Arc,
+        time::Duration,
+    };
+
+    use node_testing::{
+        cycle_time, time, FakeHardwareInterface, PerceptionInputBuilder, TestParameters,
+    };
+    use spl_network_messages::{
+        CompetitionPhase, CompetitionType, GameControllerStateMessage, GamePhase, GameState, Half,
+        HulkMessage, Player, PlayerNumber, Team, TeamColor, TeamState,
+    };
+
+    use super::*;
+
+    fn team_state() -> TeamState {
+        TeamState {
+            team_number: 24,
+            field_player_color: TeamColor::Blue,
+            goal_keeper_color: TeamColor::Red,
+            goal_keeper_player_number: PlayerNumber::One,
+            score: 0,
+            penalty_shoot_index: 0,
+            penalty_shoots: Vec::new(),
+            remaining_amount_of_messages: 1200,
+            players: vec![Player { penalty: None }; 7],
+        }
+    }
+
+    fn message_from(last_octet: u8) -> Option<IncomingMessage> {
+        message_with_budget(last_octet, 1200)
+    }
+
+    fn message_with_budget(
+        last_octet: u8,
+        remaining_amount_of_messages: u16,
+    ) -> Option<IncomingMessage> {
+        Some(IncomingMessage::GameController(
+            address(last_octet),
+            GameControllerDestination::StatePort,
+            GameControllerStateMessage {
+                competition_phase: CompetitionPhase::RoundRobin,
+                competition_type: CompetitionType::Normal,
+                game_phase: GamePhase::Normal,
+                game_state: GameState::Playing,
+                sub_state: None,
+                half: Half::First,
+                remaining_time_in_half: Duration::from_secs(600),
+                secondary_time: Duration::ZERO,
+                hulks_team: TeamState {
+                    remaining_amount_of_messages,
+                    ..team_state()
+                },
+                opponent_team: team_state(),
+                kicking_team: Team::Hulks,
+                hulks_team_is_home_after_coin_toss: true,
+            },
+        ))
+    }
+
+    fn message_to_team_port(last_octet: u8) -> Option<IncomingMessage> {
+        let mut message = message_from(last_octet);
+        if let Some(IncomingMessage::GameController(_, destination, _)) = &mut message {
+            *destination = GameControllerDestination::TeamPort;
+        }
+        message
+    }
+
+    fn message_against(last_octet: u8, opponent_team_number: u8) -> Option<IncomingMessage> {
+        let mut message = message_from(last_octet);
+        if let Some(IncomingMessage::GameController(_, _, message)) = &mut message {
+            message.opponent_team.team_number = opponent_team_number;
+        }
+        message
+    }
+
+    fn address(last_octet: u8) -> SocketAddr {
+        SocketAddr::from((Ipv4Addr::new(10, 0, 0, last_octet), 3838))
+    }
+
+    #[test]
+    fn selects_game_controller() {
+        let cases = [
+            (
+                "first contact is selected",
+                vec![(0, 10)],
+                100,
+                Some((10, GameControllerSelectionReason::FirstContact)),
+            ),
+            (
+                "parallel stream does not take over",
+                vec![(0, 10), (100, 11), (200, 10)],
+                300,
+                Some((10, GameControllerSelectionReason::FirstContact)),
+            ),
+            (
+                "single message of newer stream does not take over",
+                vec![(0, 10), (1000, 11)],
+                1100,
+                Some((10, GameControllerSelectionReason::FirstContact)),
+            ),
+            (
+                "newer stream takes over after handover timeout",
+                vec![(0, 10), (1000, 11), (1100, 11), (1200, 11)],
+                1300,
+                Some((11, GameControllerSelectionReason::NewerStream)),
+            ),
+            (
+                "interrupted stream has to send consistent messages again",
+                vec![(0, 10), (1000, 11), (1100, 11), (3000, 11)],
+                3100,
+                Some((10, GameControllerSelectionReason::FirstContact)),
+            ),
+            ("silent controller expires", vec![(0, 10)], 6000, None),
+        ];
+        let spl_network: SplNetworkParameters = TestParameters::load_default()
+            .unwrap()
+            .get("spl_network")
+            .unwrap();
+        let hardware_interface = Arc::new(FakeHardwareInterface::default());
+
+        for (description, messages, now, expected) in cases {
+            let network_message = messages.into_iter().fold(
+                PerceptionInputBuilder::default(),
+                |builder, (at, sender)| {
+                    builder.persistent(time(Duration::from_millis(at)), message_from(sender))
+                },
+            );
+            let cycle_time = cycle_time(Duration::from_millis(now), Duration::from_millis(12));
+            let mut node = GameControllerFilter::new(CreationContext::new()).unwrap();
+
+            let outputs = node
+                .cycle(CycleContext::new(
+                    &cycle_time,
+                    &FilteredWhistle::default(),
+                    network_message.build_optional(),
+                    &spl_network,
+                    &RuleSet::Spl2024,
+                    &hardware_interface,
+                ))
+                .unwrap();
+
+            let active = outputs
+                .active_game_controller
+                .value
+                .map(|active| (active.address, active.reason));
+            let expected = expected.map(|(last_octet, reason)| (address(last_octet).ip(), reason));
+            assert_eq!(active, expected, "{description}");
+        }
+    }
+
+    #[test]
+    fn prefers_game_controller_sending_to_the_team_port() {
+        let spl_network: SplNetworkParameters = TestParameters::load_default()
+            .unwrap()
+            .get("spl_network")
+            .unwrap();
+        let hardware_interface = Arc::new(FakeHardwareInterface::default());
+        let mut node = GameControllerFilter::new(CreationContext::new()).unwrap();
+        let mut active_game_controller = |messages: Vec<(u64, Option<IncomingMessage>)>, now| {
+            let network_message = messages.into_iter().fold(
+                PerceptionInputBuilder::default(),
+                |builder, (at, message)| {
+                    builder.persistent(time(Duration::from_millis(at)), message)
+                },
+            );
+            let cycle_time = cycle_time(Duration::from_millis(now), Duration::from_millis(12));
+            node.cycle(CycleContext::new(
+                &cycle_time,
+                &FilteredWhistle::default(),
+                network_message.build_optional(),
+                &spl_network,
+                &RuleSet::Spl2024,
+                &hardware_interface,
+            ))
+            .unwrap()
+            .active_game_controller
+            .value
+            .map(|active| (active.address, active.reason))
+        };
+
+        assert_eq!(
+            active_game_controller(
+                vec![
+                    (0, message_from(10)),
+                    (100, message_to_team_port(11)),
+                    (200, message_to_team_port(11)),
+                ],
+                250,
+            ),
+            Some((
+                address(10).ip(),
+                GameControllerSelectionReason::FirstContact
+            ))
+        );
+        assert_eq!(
+            active_game_controller(
+                vec![(300, message_from(10)), (300, message_to_team_port(11))],
+                350,
+            ),
+            Some((
+                address(11).ip(),
+                GameControllerSelectionReason::AddressedToTeam
+            ))
+        );
+        assert_eq!(
+            active_game_controller(
+                vec![(800, message_from(10)), (800, message_to_team_port(11))],
+                850,
+            ),
+            Some((
+                address(11).ip(),
+                GameControllerSelectionReason::AddressedToTeam
+            ))
+        );
+    }
+
+    #[test]
+    fn ignores_game_controller_with_different_team_pairing() {
+        let spl_network: SplNetworkParameters = TestParameters::load_default()
+            .unwrap()
+            .get("spl_network")
+            .unwrap();
+        let hardware_interface = Arc::new(FakeHardwareInterface::default());
+        let network_message = [
+            (0, message_from(10)),
+            (1000, message_against(11, 5)),
+            (1100, message_against(11, 5)),
+            (1200, message_against(11, 5)),
+        ]
+        .into_iter()
+        .fold(
+            PerceptionInputBuilder::default(),
+            |builder, (at, message)| builder.persistent(time(Duration::from_millis(at)), message),
+        );
+        let cycle_time = cycle_time(Duration::from_millis(1300), Duration::from_millis(12));
+        let mut node = GameControllerFilter::new(CreationContext::new()).unwrap();
+
+        let outputs = node
+            .cycle(CycleContext::new(
+                &cycle_time,
+                &FilteredWhistle::default(),
+                network_message.build_optional(),
+                &spl_network,
+                &RuleSet::Spl2024,
+                &hardware_interface,
+            ))
+            .unwrap();
+
+        let active = outputs.active_game_controller.value.unwrap();
+        assert_eq!(active.address, address(10).ip());
+        assert_eq!(active.number_of_alive_controllers, 1);
+        assert_eq!(active.number_of_rejected_controllers, 1);
+    }
+
+    #[test]
+    fn stale_state_is_advanced_locally() {
+        let spl_network: SplNetworkParameters = TestParameters::load_default()
+            .unwrap()
+            .get("spl_network")
+            .unwrap();
+        let hardware_interface = Arc::new(FakeHardwareInterface::default());
+        let mut message = message_from(10);
+        if let Some(IncomingMessage::GameController(_, _, message)) = &mut message {
+            message.game_state = GameState::Set;
+        }
+        let mut node = GameControllerFilter::new(CreationContext::new()).unwrap();
+        let no_whistle = FilteredWhistle::default();
+        let whistle = FilteredWhistle {
+            started_this_cycle: true,
+            ..Default::default()
+        };
+        let double_whistle = FilteredWhistle {
+            pattern_this_cycle: Some(WhistlePattern::Double),
+            ..Default::default()
+        };
+        let cases = [
+            (0, message, &whistle, false, GameState::Set),
+            (1, None, &whistle, false, GameState::Set),
+            (10, None, &no_whistle, true, GameState::Set),
+            (11, None, &whistle, true, GameState::Playing),
+            (100, None, &no_whistle, true, GameState::Playing),
+            (101, None, &double_whistle, true, GameState::Ready),
+            (147, None, &no_whistle, true, GameState::Set),
+        ];
+
+        for (at, message, whistle, expected_is_fallback, expected_game_state) in cases {
+            let network_message = PerceptionInputBuilder::default()
+                .persistent(time(Duration::from_secs(at)), message);
+            let cycle_time = cycle_time(Duration::from_secs(at), Duration::from_millis(12));
+
+            let outputs = node
+                .cycle(CycleContext::new(
+                    &cycle_time,
+                    whistle,
+                    network_message.build_optional(),
+                    &spl_network,
+                    &RuleSet::Spl2024,
+                    &hardware_interface,
+                ))
+                .unwrap();
+
+            let game_controller_state = outputs.game_controller_state.value.unwrap();
+            assert_eq!(
+                game_controller_state.is_fallback, expected_is_fallback,
+                "at {at}s"
+            );
+            assert_eq!(
+                game_controller_state.game_state, expected_game_state,
+                "at {at}s"
+            );
+            if expected_game_state == GameState::Ready {
+                assert_eq!(game_controller_state.kicking_team, Team::Uncertain);
+            }
+            // besides the state of an active GameController, only the transitions following the
+            // whistle are relayed to the teammates
+            let relayed_game_state = outputs
+                .game_controller_state_digest
+                .value
+                .map(|digest| digest.game_state);
+            let expected_relay = match at {
+                0 | 1 | 11 | 101 => Some(expected_game_state),
+                _ => None,
+            };
+            assert_eq!(relayed_game_state, expected_relay, "at {at}s");
+        }
+    }
+
+    #[test]
+    fn penalty_release_is_predicted_between_messages() {
+        let spl_network: SplNetworkParameters = TestParameters::load_default()
+            .unwrap()
+            .get("spl_network")
+            .unwrap();
+        let hardware_interface = Arc::new(FakeHardwareInterface::default());
+        let message_with_penalty = |remaining_seconds: Option<u64>| {
+            let mut message = message_from(10);
+            if let Some(IncomingMessage::GameController(_, _, message)) = &mut message {
+                message.hulks_team.players[1].penalty =
+                    remaining_seconds.map(|seconds| Penalty::PlayerPushing {
+                        remaining: Duration::from_secs(seconds),
+                    });
+            }
+            message
+        };
+        let mut node = GameControllerFilter::new(CreationContext::new()).unwrap();
+        // the second message at 1.5s is consistent with the release at 30s
+        let cases = [
+            (0, message_with_penalty(Some(30)), Some(30000)),
+            (1500, message_with_penalty(Some(28)), Some(28500)),
+            (10000, None, Some(20000)),
+            (35000, None, Some(0)),
+            (35500, message_with_penalty(None), None),
+        ];
+
+        for (at, message, expected_remaining) in cases {
+            let network_message = PerceptionInputBuilder::default()
+                .persistent(time(Duration::from_millis(at)), message);
+            let cycle_time = cycle_time(Duration::from_millis(at), Duration::from_millis(12));
+
+            let outputs = node
+                .cycle(CycleContext::new(
+                    &cycle_time,
+                    &FilteredWhistle::default(),
+                    network_message.build_optional(),
+                    &spl_network,
+                    &RuleSet::Spl2024,
+                    &hardware_interface,
+                ))
+                .unwrap();
+
+            let remaining_penalty_durations = outputs
+                .game_controller_state
+                .value
+                .unwrap()
+                .remaining_penalty_durations;
+            assert_eq!(
+                remaining_penalty_durations[PlayerNumber::Two],
+                expected_remaining.map(Duration::from_millis),
+                "at {at}ms"
+            );
+            assert_eq!(remaining_penalty_durations[PlayerNumber::One], None);
+        }
+    }
+
+    #[test]
+    fn rebroadcast_is_only_used_without_direct_game_controller() {
+        let spl_network: SplNetworkParameters = TestParameters::load_default()
+            .unwrap()
+            .get("spl_network")
+            .unwrap();
+        let hardware_interface = Arc::new(FakeHardwareInterface::default());
+        let rebroadcast = Some(IncomingMessage::Spl(HulkMessage {
+            game_controller_state: Some(GameControllerStateDigest {
+                game_state: GameState::Ready,
+                game_phase: GamePhase::Normal,
+                sub_state: None,
+                kicking_team: Team::Opponent,
+                remaining_seconds_in_half: 300,
+                penalized_players: 0b100,
+                remaining_amount_of_messages: 1000,
+                hulks_score: 1,
+                opponent_score: 0,
+                hulks_team_is_home_after_coin_toss: true,
+            }),
+            ..Default::default()
+        }));
+        let cases = [
+            (
+                "direct game controller is preferred",
+                vec![(0, message_from(10)), (100, rebroadcast.clone())],
+                GameState::Playing,
+            ),
+            (
+                "rebroadcast fills in without game controller",
+                vec![(100, rebroadcast.clone())],
+                GameState::Ready,
+            ),
+        ];
+
+        for (description, messages, expected) in cases {
+            let network_message = messages.into_iter().fold(
+                PerceptionInputBuilder::default(),
+                |builder, (at, message)| {
+                    builder.persistent(time(Duration::from_millis(at)), message)
+                },
+            );
+            let cycle_time = cycle_time(Duration::from_millis(200), Duration::from_millis(12));
+            let mut node = GameControllerFilter::new(CreationContext::new()).unwrap();
+
+            let outputs = node
+                .cycle(CycleContext::new(
+                    &cycle_time,
+                    &FilteredWhistle::default(),
+                    network_message.build_optional(),
+                    &spl_network,
+                    &RuleSet::Spl2024,
+                    &hardware_interface,
+                ))
+                .unwrap();
+
+            let game_controller_state = outputs.game_controller_state.value.unwrap();
+            assert_eq!(game_controller_state.game_state, expected, "{description}");
+            assert_eq!(
+                outputs.game_controller_state_digest.value.is_some(),
+                expected == GameState::Playing,
+                "{description}"
+            );
+        }
+    }
+
+    fn rebroadcast(game_state: GameState, hulks_score: u8) -> Option<IncomingMessage> {
+        Some(IncomingMessage::Spl(HulkMessage {
+            game_controller_state: Some(GameControllerStateDigest {
+                game_state,
+                game_phase: GamePhase::Normal,
+                sub_state: None,
+                kicking_team: Team::Opponent,
+                remaining_seconds_in_half: 300,
+                penalized_players: 0,
+                remaining_amount_of_messages: 1000,
+                hulks_score,
+                opponent_score: 0,
+                hulks_team_is_home_after_coin_toss: true,
+            }),
+            ..Default::default()
+        }))
+    }
+
+    #[test]
+    fn goal_leads_to_ready_through_rebroadcast_only() {
+        let spl_network: SplNetworkParameters = TestParameters::load_default()
+            .unwrap()
+            .get("spl_network")
+            .unwrap();
+        let hardware_interface = Arc::new(FakeHardwareInterface::default());
+        let mut node = GameControllerFilter::new(CreationContext::new()).unwrap();
+        // the rebroadcast times out after 6 seconds
+        let cases = [
+            (
+                0,
+                rebroadcast(GameState::Playing, 0),
+                GameState::Playing,
+                false,
+            ),
+            (
+                1000,
+                rebroadcast(GameState::Ready, 1),
+                GameState::Ready,
+                false,
+            ),
+            (
+                5000,
+                rebroadcast(GameState::Ready, 1),
+                GameState::Ready,
+                false,
+            ),
+            (11500, None, GameState::Ready, true),
+        ];
+
+        for (at, message, expected_game_state, expected_is_fallback) in cases {
+            let network_message = PerceptionInputBuilder::default()
+                .persistent(time(Duration::from_millis(at)), message);
+            let cycle_time = cycle_time(Duration::from_millis(at), Duration::from_millis(12));
+
+            let outputs = node
+                .cycle(CycleContext::new(
+                    &cycle_time,
+                    &FilteredWhistle::default(),
+                    network_message.build_optional(),
+                    &spl_network,
+                    &RuleSet::Spl2024,
+                    &hardware_interface,
+                ))
+                .unwrap();
+
+            let game_controller_state = outputs.game_controller_state.value.unwrap();
+            assert_eq!(
+                game_controller_state.game_state, expected_game_state,
+                "at {at}ms"
+            );
+            assert_eq!(
+                game_controller_state.is_fallback, expected_is_fallback,
+                "at {at}ms"
+            );
+        }
+    }
+
+    #[test]
+    fn kicking_team_changes_only_after_consistent_messages() {
+        let parameters = KickingTeamFilterParameters {
+            minimum_number_of_consistent_messages: 2,
+            minimum_dwell_time: Duration::from_secs(1),
+        };
+        let mut node = GameControllerFilter::new(CreationContext::new()).unwrap();
+        let cases = [
+            (0, Team::Hulks, Team::Hulks),
+            (500, Team::Opponent, Team::Hulks),
+            (1000, Team::Hulks, Team::Hulks),
+            (1500, Team::Opponent, Team::Hulks),
+            (2000, Team::Opponent, Team::Opponent),
+        ];
+
+        for (at, kicking_team, expected) in cases {
+            let filtered = node.filter_kicking_team(
+                kicking_team,
+                time(Duration::from_millis(at)),
+                &parameters,
+            );
+            assert_eq!(filtered, expected, "{kicking_team:?} at {at}ms");
+        }
+    }
+
+    #[test]
+    fn warns_once_per_passed_message_budget_threshold() {
+        let spl_network: SplNetworkParameters = TestParameters::load_default()
+            .unwrap()
+            .get("spl_network")
+            .unwrap();
+        let hardware_interface = Arc::new(FakeHardwareInterface::default());
+        let mut node = GameControllerFilter::new(CreationContext::new()).unwrap();
+        // minimum warning interval is 30 seconds
+        let cases = [
+            (0, 1200, 0),
+            (1, 500, 1),
+            (2, 400, 0),
+            (3, 200, 0),
+            (40, 200, 1),
+        ];
+
+        for (at, remaining_amount_of_messages, expected_number_of_warnings) in cases {
+            let network_message = PerceptionInputBuilder::default().persistent(
+                time(Duration::from_secs(at)),
+                message_with_budget(10, remaining_amount_of_messages),
+            );
+            let cycle_time = cycle_time(Duration::from_secs(at), Duration::from_millis(12));
+
+            node.cycle(CycleContext::new(
+                &cycle_time,
+                &FilteredWhistle::default(),
+                network_message.build_optional(),
+                &spl_network,
+                &RuleSet::Spl2024,
+                &hardware_interface,
+            ))
+            .unwrap();
+
+            assert_eq!(
+                hardware_interface.take_speaker_requests().len(),
+                expected_number_of_warnings,
+                "{remaining_amount_of_messages} messages at {at}s"
+            );
+        }
+    }
 }
@@ -0,0 +1,104 @@
+use std::time::SystemTime;
+
+use color_eyre::{eyre::WrapErr, Result};
+use context_attribute::context;
+use coordinate_systems::{Field, Ground};
+use hardware::NetworkInterface;
+use linear_algebra::Isometry2;
+use serde::{Deserialize, Serialize};
+use spl_network_messages::{GameControllerReturnMessage, PlayerNumber};
+use types::{
+    ball_position::BallPosition, cycle_time::CycleTime, fall_state::FallState,
+    field_dimensions::FieldDimensions, initial_pose::InitialPose, messages::OutgoingMessage,
+    parameters::SplNetworkParameters, players::Players, primary_state::PrimaryState,
+};
+
+use crate::localization::generate_initial_pose;
+
+/// Sends the standard GameController return message with the player number, fallen state, pose
+/// and ball observation, the GameController shows the robot as connected as long as these arrive.
+#[derive(Deserialize, Serialize)]
+pub struct GameControllerReturnMessageSender {
+    last_transmitted: Option<SystemTime>,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    ball_position: Input<Option<BallPosition<Ground>>, "ball_position?">,
+    cycle_time: Input<CycleTime, "cycle_time">,
+    fall_state: Input<FallState, "fall_state">,
+    ground_to_field: Input<Option<Isometry2<Ground, Field>>, "ground_to_field?">,
+    primary_state: Input<PrimaryState, "primary_state">,
+
+    field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
+    initial_poses: Parameter<Players<InitialPose>, "localization.initial_poses">,
+    player_number: Parameter<PlayerNumber, "player_number">,
+    spl_network: Parameter<SplNetworkParameters, "spl_network">,
+
+    hardware_interface: HardwareInterface,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {}
+
+impl GameControllerReturnMessageSender {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            last_transmitted: None,
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext<impl NetworkInterface>) -> Result<MainOutputs> {
+        let cycle_start_time = context.cycle_time.start_time;
+        let interval_has_passed = self.last_transmitted.map_or(true, |last_transmitted| {
+            cycle_start_time
+                .duration_since(last_transmitted)
+                .is_ok_and(|duration| {
+                    duration > context.spl_network.game_controller_return_message_interval
+                })
+        });
+        if !interval_has_passed {
+            return Ok(MainOutputs {});
+        }
+
+        let ground_to_field =
+            context
+                .ground_to_field
+                .copied()
+                .unwrap_or_else(|| match context.primary_state {
+                    PrimaryState::Initial | PrimaryState::Standby => generate_initial_pose(
+                        &context.initial_poses[*context.player_number],
+                        context.field_dimensions,
+                    )
+                    .as_transform(),
+                    _ => Default::default(),
+                });
+        let ball = context
+            .ball_position
+            .map(|ball| spl_network_messages::BallPosition {
+                age: cycle_start_time
+                    .duration_since(ball.last_seen)
+                    .unwrap_or_default(),
+                position: ball.position,
+            });
+
+        self.last_transmitted = Some(cycle_start_time);
+        context
+            .hardware_interface
+            .write_to_network(OutgoingMessage::GameController(
+                GameControllerReturnMessage {
+                    player_number: *context.player_number,
+                    fallen: matches!(context.fall_state, FallState::Fallen { .. }),
+                    pose: ground_to_field.as_pose(),
+                    ball,
+                },
+            ))
+            .wrap_err("failed to write GameControllerReturnMessage to hardware")?;
+
+        Ok(MainOutputs {})
+    }
+}
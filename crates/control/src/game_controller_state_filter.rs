@@ -3,7 +3,7 @@ use std::time::{Duration, SystemTime};
 use color_eyre::Result;
 use context_attribute::context;
 use coordinate_systems::{Field, Ground};
-use framework::MainOutput;
+use framework::{MainOutput, PersistentState};
 use linear_algebra::{distance, Isometry2, Point2, Vector2};
 use serde::{Deserialize, Serialize};
 use spl_network_messages::{GamePhase, GameState, Team};
@@ -12,6 +12,7 @@ use types::{
     filtered_game_controller_state::FilteredGameControllerState,
     filtered_game_state::FilteredGameState, filtered_whistle::FilteredWhistle,
     game_controller_state::GameControllerState, parameters::GameStateFilterParameters,
+    ready_signal::FilteredReadySignal,
 };
 #[derive(Deserialize, Serialize)]
 pub struct GameControllerStateFilter {
@@ -27,6 +28,7 @@ pub struct CycleContext {
     ball_position: Input<Option<BallPosition<Ground>>, "ball_position?">,
     cycle_time: Input<CycleTime, "cycle_time">,
     filtered_whistle: Input<FilteredWhistle, "filtered_whistle">,
+    filtered_ready_signal: Input<FilteredReadySignal, "filtered_ready_signal">,
     game_controller_state: RequiredInput<Option<GameControllerState>, "game_controller_state?">,
 
     config: Parameter<GameStateFilterParameters, "game_state_filter">,
@@ -56,6 +58,7 @@ impl GameControllerStateFilter {
             context.config,
             context.game_controller_state,
             context.filtered_whistle,
+            context.filtered_ready_signal,
             context.cycle_time,
             &mut self.state,
             &mut self.opponent_state,
@@ -66,6 +69,7 @@ impl GameControllerStateFilter {
             game_phase: context.game_controller_state.game_phase,
             kicking_team: context.game_controller_state.kicking_team,
             penalties: context.game_controller_state.penalties,
+            remaining_penalty_durations: context.game_controller_state.remaining_penalty_durations,
             remaining_number_of_messages: context
                 .game_controller_state
                 .remaining_amount_of_messages,
@@ -73,6 +77,12 @@ impl GameControllerStateFilter {
             own_team_is_home_after_coin_toss: context
                 .game_controller_state
                 .hulks_team_is_home_after_coin_toss,
+            half: context.game_controller_state.half,
+            remaining_time_in_half: context.game_controller_state.remaining_time_in_half,
+            own_score: context.game_controller_state.hulks_score,
+            opponent_score: context.game_controller_state.opponent_score,
+            staleness: context.game_controller_state.staleness,
+            is_fallback: context.game_controller_state.is_fallback,
         };
         Ok(MainOutputs {
             filtered_game_controller_state: Some(filtered_game_controller_state).into(),
@@ -80,6 +90,19 @@ impl GameControllerStateFilter {
     }
 }
 
+impl PersistentState for GameControllerStateFilter {
+    type State = (State, State);
+
+    fn persistent_state(&self) -> Self::State {
+        (self.state, self.opponent_state)
+    }
+
+    fn restore_persistent_state(&mut self, (state, opponent_state): Self::State) {
+        self.state = state;
+        self.opponent_state = opponent_state;
+    }
+}
+
 struct FilteredGameStates {
     own: FilteredGameState,
     opponent: FilteredGameState,
@@ -93,6 +116,7 @@ fn filter_game_states(
     config: &GameStateFilterParameters,
     game_controller_state: &GameControllerState,
     filtered_whistle: &FilteredWhistle,
+    filtered_ready_signal: &FilteredReadySignal,
     cycle_time: &CycleTime,
     state: &mut State,
     opponent_state: &mut State,
@@ -107,6 +131,7 @@ fn filter_game_states(
         *state,
         game_controller_state,
         filtered_whistle.is_detected,
+        filtered_ready_signal.is_detected,
         cycle_time.start_time,
         config,
         ball_detected_far_from_any_goal,
@@ -115,6 +140,7 @@ fn filter_game_states(
         *opponent_state,
         game_controller_state,
         filtered_whistle.is_detected,
+        filtered_ready_signal.is_detected,
         cycle_time.start_time,
         config,
         ball_detected_far_from_any_goal,
@@ -152,6 +178,7 @@ fn next_filtered_state(
     current_state: State,
     game_controller_state: &GameControllerState,
     is_whistle_detected: bool,
+    is_ready_signal_detected: bool,
     cycle_start_time: SystemTime,
     config: &GameStateFilterParameters,
     ball_detected_far_from_any_goal: bool,
@@ -186,17 +213,81 @@ fn next_filtered_state(
         (_, GameState::Finished) => State::TentativeFinished {
             time_when_finished_clicked: cycle_start_time,
         },
-        (State::Initial | State::Ready, _)
-        | (State::Set, GameState::Initial | GameState::Ready | GameState::Playing)
+        // the team walks in as soon as it agreed on the referee's signal
+        (State::Standby, GameState::Standby) if is_ready_signal_detected => {
+            State::ReadySignalInStandby {
+                time_when_ready_signal_was_detected: cycle_start_time,
+            }
+        }
+        (
+            State::ReadySignalInStandby {
+                time_when_ready_signal_was_detected,
+            },
+            GameState::Standby,
+        ) => {
+            if cycle_start_time
+                .duration_since(time_when_ready_signal_was_detected)
+                .unwrap()
+                < config.standby_message_delay + config.game_controller_controller_delay
+            {
+                State::ReadySignalInStandby {
+                    time_when_ready_signal_was_detected,
+                }
+            } else {
+                State::Standby
+            }
+        }
+        (
+            State::Initial | State::Standby | State::ReadySignalInStandby { .. } | State::Ready,
+            _,
+        )
+        | (State::Set, GameState::Initial | GameState::Standby | GameState::Ready)
         | (
             State::WhistleInSet { .. },
-            GameState::Initial | GameState::Ready | GameState::Playing,
+            GameState::Initial | GameState::Standby | GameState::Ready,
+        )
+        | (
+            State::KickOff { .. },
+            GameState::Initial | GameState::Standby | GameState::Ready | GameState::Set,
+        )
+        | (
+            State::Playing,
+            GameState::Initial | GameState::Standby | GameState::Ready | GameState::Set,
         )
-        | (State::Playing, GameState::Initial | GameState::Ready | GameState::Set)
         | (
             State::WhistleInPlaying { .. },
-            GameState::Initial | GameState::Ready | GameState::Set,
+            GameState::Initial | GameState::Standby | GameState::Ready | GameState::Set,
         ) => State::from_game_state(game_controller_state.game_state),
+        // the kick-off starts with the whistle if it was heard before the GameController confirmed
+        (State::Set, GameState::Playing) => State::KickOff {
+            time_when_play_started: cycle_start_time,
+        },
+        (
+            State::WhistleInSet {
+                time_when_whistle_was_detected,
+            },
+            GameState::Playing,
+        ) => State::KickOff {
+            time_when_play_started: time_when_whistle_was_detected,
+        },
+        (
+            State::KickOff {
+                time_when_play_started,
+            },
+            GameState::Playing,
+        ) => {
+            if in_kick_off_grace_period(
+                cycle_start_time,
+                time_when_play_started,
+                config.kick_off_grace_period + config.game_controller_controller_delay,
+            ) {
+                State::KickOff {
+                    time_when_play_started,
+                }
+            } else {
+                State::Playing
+            }
+        }
         (State::Set, GameState::Set) => {
             if is_whistle_detected {
                 State::WhistleInSet {
@@ -284,13 +375,23 @@ fn in_kick_off_grace_period(
 }
 
 #[derive(Clone, Copy, Deserialize, Serialize)]
-enum State {
+pub enum State {
     Initial,
+    Standby,
+    /// Ready locally before the GameController confirmed it, reverts to Standby if it never does
+    ReadySignalInStandby {
+        time_when_ready_signal_was_detected: SystemTime,
+    },
     Ready,
     Set,
+    /// Playing locally before the GameController confirmed it, reverts to Set if it never does
     WhistleInSet {
         time_when_whistle_was_detected: SystemTime,
     },
+    /// Playing within the grace period of the kick-off, confirmed by the GameController
+    KickOff {
+        time_when_play_started: SystemTime,
+    },
     Playing,
     WhistleInPlaying {
         time_when_whistle_was_detected: SystemTime,
@@ -305,6 +406,7 @@ impl State {
     fn from_game_state(game_state: GameState) -> Self {
         match game_state {
             GameState::Initial => State::Initial,
+            GameState::Standby => State::Standby,
             GameState::Ready => State::Ready,
             GameState::Set => State::Set,
             GameState::Playing => State::Playing,
@@ -361,16 +463,20 @@ impl State {
 
         match self {
             State::Initial => FilteredGameState::Initial,
-            State::Ready => FilteredGameState::Ready {
+            State::Standby => FilteredGameState::Standby,
+            State::ReadySignalInStandby { .. } | State::Ready => FilteredGameState::Ready {
                 kicking_team: game_controller_state.kicking_team,
             },
             State::Set => FilteredGameState::Set,
             State::WhistleInSet {
-                time_when_whistle_was_detected,
+                time_when_whistle_was_detected: time_when_play_started,
+            }
+            | State::KickOff {
+                time_when_play_started,
             } => {
                 let kick_off_grace_period = in_kick_off_grace_period(
                     cycle_start_time,
-                    *time_when_whistle_was_detected,
+                    *time_when_play_started,
                     config.kick_off_grace_period + config.game_controller_controller_delay,
                 );
                 let opponent_kick_off = opponent_is_kicking_team
@@ -398,3 +504,137 @@ impl State {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use node_testing::time;
+    use spl_network_messages::Half;
+    use types::players::Players;
+
+    use super::*;
+
+    const GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+    fn parameters() -> GameStateFilterParameters {
+        GameStateFilterParameters {
+            kick_off_grace_period: GRACE_PERIOD,
+            ..Default::default()
+        }
+    }
+
+    fn game_controller_state(game_state: GameState) -> GameControllerState {
+        GameControllerState {
+            game_state,
+            game_phase: GamePhase::Normal,
+            kicking_team: Team::Opponent,
+            raw_kicking_team: Team::Opponent,
+            last_game_state_change: time(Duration::ZERO),
+            penalties: Players::default(),
+            remaining_penalty_durations: Players::default(),
+            remaining_amount_of_messages: 1200,
+            sub_state: None,
+            hulks_team_is_home_after_coin_toss: true,
+            half: Half::First,
+            remaining_time_in_half: Duration::from_secs(600),
+            hulks_score: 0,
+            opponent_score: 0,
+            staleness: Duration::ZERO,
+            is_fallback: false,
+        }
+    }
+
+    fn next_state(state: State, game_state: GameState, seconds: u64) -> State {
+        next_filtered_state(
+            state,
+            &game_controller_state(game_state),
+            false,
+            false,
+            time(Duration::from_secs(seconds)),
+            &parameters(),
+            false,
+        )
+    }
+
+    fn filtered_game_state(state: State, seconds: u64) -> FilteredGameState {
+        state.construct_hulks_filtered_game_state(
+            &game_controller_state(GameState::Playing),
+            time(Duration::from_secs(seconds)),
+            false,
+            &parameters(),
+        )
+    }
+
+    #[test]
+    fn playing_without_whistle_starts_the_kick_off() {
+        let state = next_state(State::Set, GameState::Playing, 100);
+
+        assert!(matches!(
+            state,
+            State::KickOff { time_when_play_started } if time_when_play_started == time(Duration::from_secs(100))
+        ));
+        assert_eq!(
+            filtered_game_state(state, 100),
+            FilteredGameState::Playing {
+                ball_is_free: false,
+                kick_off: true,
+            }
+        );
+    }
+
+    #[test]
+    fn confirmed_whistle_keeps_the_whistle_time_for_the_kick_off() {
+        let whistle = State::WhistleInSet {
+            time_when_whistle_was_detected: time(Duration::from_secs(100)),
+        };
+
+        let state = next_state(whistle, GameState::Playing, 103);
+
+        assert!(matches!(
+            state,
+            State::KickOff { time_when_play_started } if time_when_play_started == time(Duration::from_secs(100))
+        ));
+        assert!(matches!(
+            next_state(state, GameState::Playing, 109),
+            State::KickOff { .. }
+        ));
+        assert!(matches!(
+            next_state(state, GameState::Playing, 110),
+            State::Playing
+        ));
+    }
+
+    #[test]
+    fn ball_is_free_once_the_grace_period_is_over() {
+        let kick_off = State::KickOff {
+            time_when_play_started: time(Duration::from_secs(100)),
+        };
+
+        assert_eq!(
+            filtered_game_state(kick_off, 109),
+            FilteredGameState::Playing {
+                ball_is_free: false,
+                kick_off: true,
+            }
+        );
+        let state = next_state(kick_off, GameState::Playing, 110);
+        assert_eq!(
+            filtered_game_state(state, 110),
+            FilteredGameState::Playing {
+                ball_is_free: true,
+                kick_off: false,
+            }
+        );
+    }
+
+    #[test]
+    fn kick_off_follows_the_game_controller_back_to_set() {
+        let kick_off = State::KickOff {
+            time_when_play_started: time(Duration::from_secs(100)),
+        };
+
+        assert!(matches!(
+            next_state(kick_off, GameState::Set, 101),
+            State::Set
+        ));
+    }
+}
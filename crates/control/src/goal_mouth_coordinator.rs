@@ -0,0 +1,169 @@
+use std::time::SystemTime;
+
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use context_attribute::context;
+use coordinate_systems::Field;
+use framework::{MainOutput, PerceptionInput};
+use linear_algebra::{point, Point2};
+use spl_network_messages::{GoalMouthClaim, PlayerNumber};
+use types::{
+    cycle_time::CycleTime, field_dimensions::FieldDimensions,
+    filtered_game_controller_state::FilteredGameControllerState,
+    filtered_game_state::FilteredGameState, messages::IncomingMessage,
+    parameters::GoalMouthParameters, roles::Role, world_state::BallState,
+};
+
+/// Resolves the scrum of striker and keeper when the striker follows the ball into the own
+/// penalty area: the striker claims the goal mouth and commits to clearing towards one sideline,
+/// the keeper yields the corridor and holds the other post.
+#[derive(Deserialize, Serialize)]
+pub struct GoalMouthCoordinator {
+    own_claim: Option<GoalMouthClaim>,
+    teammate_claim: Option<ReceivedClaim>,
+}
+
+#[derive(Clone, Copy, Deserialize, Serialize)]
+struct ReceivedClaim {
+    claimant: PlayerNumber,
+    claim: GoalMouthClaim,
+    received_at: SystemTime,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    ball_state: Input<Option<BallState>, "ball_state?">,
+    cycle_time: Input<CycleTime, "cycle_time">,
+    filtered_game_controller_state:
+        Input<Option<FilteredGameControllerState>, "filtered_game_controller_state?">,
+    role: Input<Role, "role">,
+    network_message: PerceptionInput<Option<IncomingMessage>, "SplNetwork", "filtered_message?">,
+
+    field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
+    parameters: Parameter<GoalMouthParameters, "behavior.goal_mouth">,
+    player_number: Parameter<PlayerNumber, "player_number">,
+
+    goal_mouth_claim: CyclerState<Option<GoalMouthClaim>, "goal_mouth_claim">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub own_goal_mouth_claim: MainOutput<Option<GoalMouthClaim>>,
+    pub teammate_goal_mouth_claim: MainOutput<Option<GoalMouthClaim>>,
+}
+
+impl GoalMouthCoordinator {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            own_claim: None,
+            teammate_claim: None,
+        })
+    }
+
+    pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
+        let parameters = context.parameters;
+        let now = context.cycle_time.start_time;
+
+        for (receive_time, message) in context
+            .network_message
+            .persistent
+            .iter()
+            .flat_map(|(receive_time, messages)| {
+                messages.iter().map(move |message| (*receive_time, message))
+            })
+            .filter_map(|(receive_time, message)| match message {
+                Some(IncomingMessage::Spl(message)) => Some((receive_time, message)),
+                _ => None,
+            })
+            .filter(|(_, message)| message.player_number != *context.player_number)
+        {
+            match message.goal_mouth_claim {
+                Some(claim) => {
+                    self.teammate_claim = Some(ReceivedClaim {
+                        claimant: message.player_number,
+                        claim,
+                        received_at: receive_time,
+                    });
+                }
+                None if self.teammate_claim.is_some_and(|received_claim| {
+                    received_claim.claimant == message.player_number
+                }) =>
+                {
+                    self.teammate_claim = None;
+                }
+                None => {}
+            }
+        }
+        if self.teammate_claim.is_some_and(|received_claim| {
+            now.duration_since(received_claim.received_at)
+                .unwrap_or_default()
+                > parameters.claim_timeout
+        }) {
+            self.teammate_claim = None;
+        }
+
+        let is_ball_free = matches!(
+            context.filtered_game_controller_state,
+            None | Some(FilteredGameControllerState {
+                game_state: FilteredGameState::Playing {
+                    ball_is_free: true,
+                    ..
+                },
+                ..
+            })
+        );
+        self.own_claim = if parameters.enable && is_ball_free && *context.role == Role::Striker {
+            context
+                .ball_state
+                .map(|ball| ball.ball_in_field)
+                .filter(|ball| {
+                    is_inside_own_penalty_area(
+                        *ball,
+                        context.field_dimensions,
+                        parameters.penalty_area_margin,
+                    )
+                })
+                .map(|ball| {
+                    // the clearing direction must not flip while the ball crosses the goal center
+                    let side = match self.own_claim {
+                        Some(claim) if claim.clearing_target.y() < 0.0 => -1.0,
+                        Some(_) => 1.0,
+                        None if ball.y() < 0.0 => -1.0,
+                        None => 1.0,
+                    };
+                    GoalMouthClaim {
+                        clearing_target: point![
+                            parameters.clearing_target.x(),
+                            side * parameters.clearing_target.y().abs()
+                        ],
+                    }
+                })
+        } else {
+            None
+        };
+        *context.goal_mouth_claim = self.own_claim;
+
+        Ok(MainOutputs {
+            own_goal_mouth_claim: self.own_claim.into(),
+            teammate_goal_mouth_claim: self
+                .teammate_claim
+                .filter(|_| parameters.enable)
+                .map(|received_claim| received_claim.claim)
+                .into(),
+        })
+    }
+}
+
+fn is_inside_own_penalty_area(
+    position: Point2<Field>,
+    field_dimensions: &FieldDimensions,
+    margin: f32,
+) -> bool {
+    position.x() < -field_dimensions.length / 2.0 + field_dimensions.penalty_area_length + margin
+        && position.y().abs() < field_dimensions.penalty_area_width / 2.0 + margin
+}
@@ -0,0 +1,92 @@
+/// Solves the linear assignment problem for a square cost matrix with the Hungarian method.
+///
+/// Returns the column assigned to each row such that the sum of costs is minimal.
+pub fn solve(costs: &[Vec<f32>]) -> Vec<usize> {
+    let size = costs.len();
+    assert!(
+        costs.iter().all(|row| row.len() == size),
+        "cost matrix has to be square"
+    );
+
+    // potentials and matching are 1-indexed, index 0 is a virtual column used as starting point
+    let mut row_potentials = vec![0.0; size + 1];
+    let mut column_potentials = vec![0.0; size + 1];
+    let mut row_of_column = vec![0; size + 1];
+    let mut previous_column = vec![0; size + 1];
+
+    for row in 1..=size {
+        row_of_column[0] = row;
+        let mut current_column = 0;
+        let mut minimal_slack = vec![f32::INFINITY; size + 1];
+        let mut is_used = vec![false; size + 1];
+        loop {
+            is_used[current_column] = true;
+            let current_row = row_of_column[current_column];
+            let mut delta = f32::INFINITY;
+            let mut next_column = 0;
+            for column in 1..=size {
+                if is_used[column] {
+                    continue;
+                }
+                let slack = costs[current_row - 1][column - 1]
+                    - row_potentials[current_row]
+                    - column_potentials[column];
+                if slack < minimal_slack[column] {
+                    minimal_slack[column] = slack;
+                    previous_column[column] = current_column;
+                }
+                if minimal_slack[column] < delta {
+                    delta = minimal_slack[column];
+                    next_column = column;
+                }
+            }
+            for column in 0..=size {
+                if is_used[column] {
+                    row_potentials[row_of_column[column]] += delta;
+                    column_potentials[column] -= delta;
+                } else {
+                    minimal_slack[column] -= delta;
+                }
+            }
+            current_column = next_column;
+            if row_of_column[current_column] == 0 {
+                break;
+            }
+        }
+        // augment along the alternating path
+        while current_column != 0 {
+            let column = previous_column[current_column];
+            row_of_column[current_column] = row_of_column[column];
+            current_column = column;
+        }
+    }
+
+    let mut column_of_row = vec![0; size];
+    for column in 1..=size {
+        if row_of_column[column] != 0 {
+            column_of_row[row_of_column[column] - 1] = column - 1;
+        }
+    }
+    column_of_row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_minimal_assignment() {
+        let costs = vec![
+            vec![4.0, 1.0, 3.0],
+            vec![2.0, 0.0, 5.0],
+            vec![3.0, 2.0, 2.0],
+        ];
+
+        assert_eq!(solve(&costs), vec![1, 0, 2]);
+    }
+
+    #[test]
+    fn empty_matrix_yields_empty_assignment() {
+        assert!(solve(&[]).is_empty());
+    }
+}
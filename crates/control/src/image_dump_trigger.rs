@@ -0,0 +1,111 @@
+use std::time::SystemTime;
+
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use context_attribute::context;
+use coordinate_systems::Ground;
+use framework::MainOutput;
+use types::{
+    ball_position::BallPosition,
+    cycle_time::CycleTime,
+    fall_state::FallState,
+    image_dump::{ImageDumpReason, ImageDumpRequest},
+    parameters::ImageDumpTriggerParameters,
+    primary_state::PrimaryState,
+    walk_command::WalkCommand,
+};
+
+#[derive(Deserialize, Serialize)]
+pub struct ImageDumpTrigger {
+    was_falling: bool,
+    was_localization_lost: bool,
+    was_kicking: bool,
+    last_kick: Option<SystemTime>,
+    last_request: Option<ImageDumpRequest>,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    ball_position: Input<Option<BallPosition<Ground>>, "ball_position?">,
+    cycle_time: Input<CycleTime, "cycle_time">,
+    fall_state: Input<FallState, "fall_state">,
+    localization_uncertainty: Input<Option<f32>, "localization_uncertainty?">,
+    primary_state: Input<PrimaryState, "primary_state">,
+    walk_command: Input<WalkCommand, "walk_command">,
+
+    parameters: Parameter<ImageDumpTriggerParameters, "image_dump_trigger">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    /// The vision cyclers dump their image ring buffers once they see a new request
+    pub last_image_dump_request: MainOutput<Option<ImageDumpRequest>>,
+}
+
+impl ImageDumpTrigger {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            was_falling: false,
+            was_localization_lost: false,
+            was_kicking: false,
+            last_kick: None,
+            last_request: None,
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        let now = context.cycle_time.start_time;
+        let is_playing = *context.primary_state == PrimaryState::Playing;
+
+        let is_falling = matches!(context.fall_state, FallState::Falling { .. });
+        let has_started_falling = is_falling && !self.was_falling;
+        self.was_falling = is_falling;
+
+        let is_localization_lost = is_playing
+            && context.localization_uncertainty.is_some_and(|uncertainty| {
+                *uncertainty > context.parameters.maximum_localization_uncertainty
+            });
+        let has_lost_localization = is_localization_lost && !self.was_localization_lost;
+        self.was_localization_lost = is_localization_lost;
+
+        let is_kicking = matches!(context.walk_command, WalkCommand::Kick(..));
+        if is_kicking && !self.was_kicking && is_playing {
+            self.last_kick = Some(now);
+        }
+        self.was_kicking = is_kicking;
+        let has_missed_kick = match self.last_kick {
+            Some(last_kick)
+                if now.duration_since(last_kick).unwrap_or_default()
+                    >= context.parameters.missed_kick_delay =>
+            {
+                self.last_kick = None;
+                context.ball_position.is_some_and(|ball| {
+                    ball.position.coords().norm() < context.parameters.missed_kick_ball_distance
+                })
+            }
+            _ => false,
+        };
+
+        let reason = if has_started_falling {
+            Some(ImageDumpReason::Fall)
+        } else if has_lost_localization {
+            Some(ImageDumpReason::LostLocalization)
+        } else if has_missed_kick {
+            Some(ImageDumpReason::MissedKick)
+        } else {
+            None
+        };
+        if let Some(reason) = reason {
+            self.last_request = Some(ImageDumpRequest { reason, time: now });
+        }
+
+        Ok(MainOutputs {
+            last_image_dump_request: self.last_request.into(),
+        })
+    }
+}
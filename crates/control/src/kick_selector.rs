@@ -15,13 +15,20 @@ use linear_algebra::{
     distance, point, vector, IntoFramed, Isometry2, Orientation2, Point, Point2, Pose2, Rotation2,
     Vector2,
 };
+use spl_network_messages::{GoalMouthClaim, PassAnnouncement, SubState, Team};
 use types::{
     field_dimensions::FieldDimensions,
+    filtered_game_controller_state::FilteredGameControllerState,
     kick_decision::KickDecision,
-    kick_target::KickTarget,
+    kick_target::{EvaluatedKickTarget, KickTarget},
     motion_command::KickVariant,
-    obstacles::Obstacle,
-    parameters::{FindKickTargetsParameters, InWalkKickInfoParameters, InWalkKicksParameters},
+    obstacles::{opponents, Obstacle},
+    parameters::{
+        FindKickTargetsParameters, InWalkKickInfoParameters, InWalkKicksParameters,
+        KeeperClearanceParameters,
+    },
+    roles::Role,
+    shot_selection::{ShotSelection, StrikerDecision},
     support_foot::Side,
     world_state::BallState,
 };
@@ -37,8 +44,16 @@ pub struct CycleContext {
     ground_to_field: RequiredInput<Option<Isometry2<Ground, Field>>, "ground_to_field?">,
     ball_state: RequiredInput<Option<BallState>, "ball_state?">,
     obstacles: Input<Vec<Obstacle>, "obstacles">,
+    teammate_positions: Input<Vec<Point2<Field>>, "teammate_positions">,
+    shot_selection: Input<Option<ShotSelection>, "shot_selection?">,
+    pass_intent: Input<Option<PassAnnouncement>, "pass_intent?">,
+    own_goal_mouth_claim: Input<Option<GoalMouthClaim>, "own_goal_mouth_claim?">,
+    filtered_game_controller_state:
+        Input<Option<FilteredGameControllerState>, "filtered_game_controller_state?">,
+    role: Input<Role, "role">,
 
     field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
+    teammate_matching_distance: Parameter<f32, "obstacle_filter.teammate_matching_distance">,
 
     in_walk_kicks: Parameter<InWalkKicksParameters, "in_walk_kicks">,
     angle_distance_weight: Parameter<f32, "kick_selector.angle_distance_weight">,
@@ -52,8 +67,14 @@ pub struct CycleContext {
 
     default_kick_strength: Parameter<f32, "kick_selector.default_kick_strength">,
     corner_kick_strength: Parameter<f32, "kick_selector.corner_kick_strength">,
+    dribble_kick_strength: Parameter<f32, "behavior.shot_selection.dribble_kick_strength">,
+    pass_kick_strength: Parameter<f32, "behavior.passing.kick_strength">,
+    goal_kick_target: Parameter<Point2<Field>, "behavior.free_kick.goal_kick_target">,
+    kick_in_target: Parameter<Point2<Field>, "behavior.free_kick.kick_in_target">,
+    keeper_clearance: Parameter<KeeperClearanceParameters, "behavior.keeper_clearance">,
 
     kick_targets: AdditionalOutput<Vec<KickTarget>, "kick_targets">,
+    evaluated_kick_targets: AdditionalOutput<Vec<EvaluatedKickTarget>, "evaluated_kick_targets">,
     instant_kick_targets: AdditionalOutput<Vec<Point2<Ground>>, "instant_kick_targets">,
 }
 
@@ -88,29 +109,112 @@ impl KickSelector {
             *context.ball_radius_for_kick_target_selection,
         );
 
-        let instant_kick_decisions = generate_decisions_for_instant_kicks(
-            &sides,
-            &kick_variants,
-            context.in_walk_kicks,
-            ball_position,
-            &obstacle_circles,
-            context.field_dimensions,
-            *context.ground_to_field,
-            *context.closer_threshold,
-            &mut context.instant_kick_targets,
-            *context.default_kick_strength,
-            *context.goal_accuracy_margin,
-        );
+        // an announced pass overrides the shot selection, the receiver is already on its way
+        let decision = match context.pass_intent {
+            Some(pass_intent) => StrikerDecision::Pass {
+                target: context.ground_to_field.inverse() * pass_intent.target,
+            },
+            None => context
+                .shot_selection
+                .map(|shot_selection| shot_selection.decision)
+                .unwrap_or_default(),
+        };
+        let default_kick_strength = match decision {
+            StrikerDecision::Dribble => *context.dribble_kick_strength,
+            StrikerDecision::Pass { .. } => *context.pass_kick_strength,
+            StrikerDecision::Shoot => *context.default_kick_strength,
+        };
 
-        let kick_targets = collect_kick_targets(
-            *context.ground_to_field,
-            context.field_dimensions,
-            &obstacle_circles,
-            ball_position,
-            *context.max_kick_around_obstacle_angle,
-            context.find_kick_targets,
-            *context.corner_kick_strength,
-        );
+        let ball_in_field = *context.ground_to_field * ball_position;
+        let side = if ball_in_field.y() < 0.0 { -1.0 } else { 1.0 };
+        let set_play_target = context
+            .filtered_game_controller_state
+            .filter(|game_controller_state| game_controller_state.kicking_team == Team::Hulks)
+            .and_then(
+                |game_controller_state| match game_controller_state.sub_state {
+                    // a goal kick clears the ball up the sideline instead of across our penalty area
+                    Some(SubState::GoalKick) => Some(point![
+                        context.goal_kick_target.x(),
+                        side * context.goal_kick_target.y()
+                    ]),
+                    // a kick-in is played infield, never along or over the sideline it just crossed
+                    Some(SubState::KickIn) => Some(point![
+                        (ball_in_field.x() + context.kick_in_target.x()).clamp(
+                            -context.field_dimensions.length / 2.0,
+                            context.field_dimensions.length / 2.0
+                        ),
+                        side * (context.field_dimensions.width / 2.0 - context.kick_in_target.y())
+                    ]),
+                    _ => None,
+                },
+            )
+            .map(|target| KickTarget {
+                position: context.ground_to_field.inverse() * target,
+                strength: Some(*context.default_kick_strength),
+            })
+            .or_else(|| {
+                // the striker sticks to the announced direction, the keeper left that corridor free
+                context.own_goal_mouth_claim.map(|claim| KickTarget {
+                    position: context.ground_to_field.inverse() * claim.clearing_target,
+                    strength: Some(context.keeper_clearance.kick_strength),
+                })
+            })
+            .or_else(|| {
+                clearance_target(
+                    *context.role,
+                    ball_in_field,
+                    context.field_dimensions,
+                    context.keeper_clearance,
+                )
+                .map(|target| KickTarget {
+                    position: context.ground_to_field.inverse() * target,
+                    strength: Some(context.keeper_clearance.kick_strength),
+                })
+            });
+
+        // instant kicks aim at the goal, they are not used while passing or clearing
+        let instant_kick_decisions = match decision {
+            _ if set_play_target.is_some() => Vec::new(),
+            StrikerDecision::Pass { .. } => Vec::new(),
+            StrikerDecision::Shoot | StrikerDecision::Dribble => {
+                generate_decisions_for_instant_kicks(
+                    &sides,
+                    &kick_variants,
+                    context.in_walk_kicks,
+                    ball_position,
+                    &obstacle_circles,
+                    context.field_dimensions,
+                    *context.ground_to_field,
+                    *context.closer_threshold,
+                    &mut context.instant_kick_targets,
+                    default_kick_strength,
+                    *context.goal_accuracy_margin,
+                )
+            }
+        };
+
+        let kick_targets = match (set_play_target, decision) {
+            (Some(target), _) => vec![target],
+            (None, StrikerDecision::Pass { target }) => vec![KickTarget::new(target)],
+            (None, StrikerDecision::Shoot | StrikerDecision::Dribble) => collect_kick_targets(
+                *context.ground_to_field,
+                context.field_dimensions,
+                &opponents(
+                    context.obstacles,
+                    context.teammate_positions,
+                    *context.ground_to_field,
+                    *context.teammate_matching_distance,
+                )
+                .cloned()
+                .collect::<Vec<_>>(),
+                &obstacle_circles,
+                ball_position,
+                *context.max_kick_around_obstacle_angle,
+                context.find_kick_targets,
+                *context.corner_kick_strength,
+                &mut context.evaluated_kick_targets,
+            ),
+        };
 
         context
             .kick_targets
@@ -124,33 +228,20 @@ impl KickSelector {
                     kick_variant,
                     side,
                     ball_position,
-                    *context.default_kick_strength,
+                    default_kick_strength,
                 )
             })
             .flatten()
             .collect();
 
-        kick_decisions.sort_by(|left, right| {
-            let left_in_obstacle = is_inside_any_obstacle(
-                left.kick_pose,
-                context.obstacles,
-                *context.kick_pose_obstacle_radius,
-            );
-            let right_in_obstacle = is_inside_any_obstacle(
-                right.kick_pose,
-                context.obstacles,
-                *context.kick_pose_obstacle_radius,
-            );
-            let distance_to_left =
-                distance_to_kick_pose(left.kick_pose, *context.angle_distance_weight);
-            let distance_to_right =
-                distance_to_kick_pose(right.kick_pose, *context.angle_distance_weight);
-            match (left_in_obstacle, right_in_obstacle) {
-                (true, false) => Ordering::Less,
-                (false, true) => Ordering::Greater,
-                _ => distance_to_left.total_cmp(&distance_to_right),
-            }
-        });
+        sort_kick_decisions(
+            &mut kick_decisions,
+            ball_position,
+            context.obstacles,
+            context.in_walk_kicks,
+            *context.kick_pose_obstacle_radius,
+            *context.angle_distance_weight,
+        );
 
         Ok(MainOutputs {
             kick_decisions: Some(kick_decisions).into(),
@@ -159,6 +250,55 @@ impl KickSelector {
     }
 }
 
+/// Orders the kick decisions from best to worst, decisions changed after the selection are
+/// re-ranked with this as well.
+pub fn sort_kick_decisions(
+    kick_decisions: &mut [KickDecision],
+    ball_position: Point2<Ground>,
+    obstacles: &[Obstacle],
+    in_walk_kicks: &InWalkKicksParameters,
+    kick_pose_obstacle_radius: f32,
+    angle_distance_weight: f32,
+) {
+    kick_decisions.sort_by(|left, right| {
+        let left_in_obstacle =
+            is_inside_any_obstacle(left.kick_pose, obstacles, kick_pose_obstacle_radius);
+        let right_in_obstacle =
+            is_inside_any_obstacle(right.kick_pose, obstacles, kick_pose_obstacle_radius);
+        let left_is_approachable =
+            is_approachable(left.kick_pose, ball_position, &in_walk_kicks[left.variant]);
+        let right_is_approachable = is_approachable(
+            right.kick_pose,
+            ball_position,
+            &in_walk_kicks[right.variant],
+        );
+        let distance_to_left = distance_to_kick_pose(left.kick_pose, angle_distance_weight);
+        let distance_to_right = distance_to_kick_pose(right.kick_pose, angle_distance_weight);
+        match (left_in_obstacle, right_in_obstacle) {
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            _ => right_is_approachable
+                .cmp(&left_is_approachable)
+                .then(distance_to_left.total_cmp(&distance_to_right)),
+        }
+    });
+}
+
+/// The keeper clears the ball out of the own penalty area towards the closer sideline instead of
+/// playing it across the goal.
+fn clearance_target(
+    role: Role,
+    ball_in_field: Point2<Field>,
+    field_dimensions: &FieldDimensions,
+    parameters: &KeeperClearanceParameters,
+) -> Option<Point2<Field>> {
+    let is_clearing = parameters.enable
+        && matches!(role, Role::Keeper | Role::ReplacementKeeper)
+        && field_dimensions.is_inside_own_penalty_area(ball_in_field);
+    let side = if ball_in_field.y() < 0.0 { -1.0 } else { 1.0 };
+    is_clearing.then_some(point![parameters.target.x(), side * parameters.target.y()])
+}
+
 fn generate_obstacle_circles(
     obstacles: &[Obstacle],
     ball_radius_for_kick_target_selection: f32,
@@ -273,14 +413,17 @@ fn is_scoring_goal(
     ball_to_target.intersects_line_segment(opponent_goal_line)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn collect_kick_targets(
     ground_to_field: Isometry2<Ground, Field>,
     field_dimensions: &FieldDimensions,
+    opponents: &[Obstacle],
     obstacle_circles: &[Circle<Ground>],
     ball_position: Point2<Ground>,
     max_kick_around_obstacle_angle: f32,
     parameters: &FindKickTargetsParameters,
     corner_kick_strength: f32,
+    evaluated_kick_targets_output: &mut AdditionalOutput<Vec<EvaluatedKickTarget>>,
 ) -> Vec<KickTarget> {
     let field_to_ground = ground_to_field.inverse();
     let mut kick_targets = Vec::new();
@@ -293,10 +436,18 @@ fn collect_kick_targets(
             corner_kick_strength,
         ));
     } else {
-        kick_targets.extend(generate_goal_line_kick_targets(
+        let evaluated_kick_targets = evaluate_goal_line_kick_targets(
             field_dimensions,
-            field_to_ground,
+            ground_to_field,
+            opponents,
+            ball_position,
+            parameters,
+        );
+        kick_targets.extend(best_kick_targets(
+            &evaluated_kick_targets,
+            parameters.target_score_tolerance,
         ));
+        evaluated_kick_targets_output.fill_if_subscribed(|| evaluated_kick_targets);
     }
 
     let obstacle_circles: Vec<_> = obstacle_circles
@@ -364,24 +515,91 @@ fn generate_corner_kick_targets(
     }]
 }
 
-fn generate_goal_line_kick_targets(
+/// Scores targets spread over the goal by the clearance a shot towards them keeps to the
+/// opponents and the goal posts. The keeper is inflated by its reach.
+fn evaluate_goal_line_kick_targets(
     field_dimensions: &FieldDimensions,
-    field_to_ground: Isometry2<Field, Ground>,
+    ground_to_field: Isometry2<Ground, Field>,
+    opponents: &[Obstacle],
+    ball_position: Point2<Ground>,
+    parameters: &FindKickTargetsParameters,
+) -> Vec<EvaluatedKickTarget> {
+    let field_to_ground = ground_to_field.inverse();
+    let goal_center = point![field_dimensions.length / 2.0, 0.0];
+    let opponents: Vec<_> = opponents
+        .iter()
+        .map(|obstacle| {
+            (
+                ground_to_field * obstacle.position,
+                obstacle.radius_at_foot_height,
+            )
+        })
+        .collect();
+    let keeper = opponents
+        .iter()
+        .map(|(position, _)| *position)
+        .filter(|position| distance(*position, goal_center) < parameters.keeper_search_radius)
+        .min_by(|left, right| {
+            distance(*left, goal_center).total_cmp(&distance(*right, goal_center))
+        });
+    let post_radius = field_dimensions.goal_post_diameter / 2.0;
+    let posts = [
+        point![
+            goal_center.x(),
+            field_dimensions.goal_inner_width / 2.0 + post_radius
+        ],
+        point![
+            goal_center.x(),
+            -field_dimensions.goal_inner_width / 2.0 - post_radius
+        ],
+    ];
+    let blockers: Vec<(Point2<Field>, f32)> = opponents
+        .iter()
+        .map(|&(position, radius)| {
+            if Some(position) == keeper {
+                (position, radius + parameters.keeper_reach)
+            } else {
+                (position, radius)
+            }
+        })
+        .chain(posts.into_iter().map(|post| (post, post_radius)))
+        .collect();
+
+    let ball = ground_to_field * ball_position;
+    let usable_half_width = field_dimensions.goal_inner_width / 2.0 - post_radius;
+    let number_of_targets = parameters.number_of_goal_targets.max(2);
+    (0..number_of_targets)
+        .map(|index| {
+            let y = -usable_half_width
+                + 2.0 * usable_half_width * index as f32 / (number_of_targets - 1) as f32;
+            let target = point![goal_center.x(), y];
+            let shot = LineSegment::new(ball, target);
+            let clearance = blockers
+                .iter()
+                .map(|(position, radius)| shot.shortest_distance_to_point(*position) - radius)
+                .fold(parameters.maximum_target_clearance, f32::min);
+            EvaluatedKickTarget {
+                position: field_to_ground * target,
+                score: clearance,
+            }
+        })
+        .collect()
+}
+
+/// All targets almost as good as the best one, the kick pose decides between them.
+fn best_kick_targets(
+    evaluated_kick_targets: &[EvaluatedKickTarget],
+    score_tolerance: f32,
 ) -> Vec<KickTarget> {
-    let left_goal_half = field_to_ground
-        * point![
-            field_dimensions.length / 2.0,
-            field_dimensions.goal_inner_width / 4.0
-        ];
-    let right_goal_half = field_to_ground
-        * point![
-            field_dimensions.length / 2.0,
-            -field_dimensions.goal_inner_width / 4.0
-        ];
-    vec![
-        KickTarget::new(left_goal_half),
-        KickTarget::new(right_goal_half),
-    ]
+    let best_score = evaluated_kick_targets
+        .iter()
+        .map(|target| target.score)
+        .fold(f32::NEG_INFINITY, f32::max);
+    evaluated_kick_targets
+        .iter()
+        .filter(|target| target.score >= best_score - score_tolerance)
+        .map(|target| KickTarget::new(target.position))
+        .collect()
 }
 
 fn kick_decisions_from_targets(
@@ -436,7 +654,22 @@ fn mirror_kick_pose<Frame>(kick_pose: Pose2<Frame>) -> Pose2<Frame> {
     )
 }
 
-fn compute_kick_pose(
+/// Whether the kick pose can be reached by walking straight towards the ball, i.e. without
+/// circling around it to realign.
+fn is_approachable(
+    kick_pose: Pose2<Ground>,
+    ball_position: Point2<Ground>,
+    kick_info: &InWalkKickInfoParameters,
+) -> bool {
+    let required_approach = ball_position - kick_pose.position();
+    let current_approach = ball_position.coords();
+    if required_approach.norm() <= 0.0 || current_approach.norm() <= 0.0 {
+        return true;
+    }
+    required_approach.angle(current_approach) < kick_info.approach_cone
+}
+
+pub fn compute_kick_pose(
     ball_position: Point2<Ground>,
     target_to_kick_to: Point2<Ground>,
     kick_info: &InWalkKickInfoParameters,
@@ -12,6 +12,7 @@ use types::{
     led::{Ear, Eye, Leds},
     messages::IncomingMessage,
     primary_state::PrimaryState,
+    robot_condition::RobotCondition,
     roles::Role,
     sensor_data::SensorData,
 };
@@ -34,6 +35,7 @@ pub struct CycleContext {
     cycle_time: Input<CycleTime, "cycle_time">,
     filtered_whistle: Input<FilteredWhistle, "filtered_whistle">,
     role: Input<Role, "role">,
+    robot_condition: Input<RobotCondition, "robot_condition">,
 
     balls_bottom: PerceptionInput<Option<Vec<Ball>>, "VisionBottom", "balls?">,
     balls_top: PerceptionInput<Option<Vec<Ball>>, "VisionTop", "balls?">,
@@ -76,6 +78,7 @@ impl LedStatus {
                 false => Rgb::BLACK,
             },
             PrimaryState::Initial => Rgb::BLACK,
+            PrimaryState::Standby => Rgb::TURQUOISE,
             PrimaryState::Ready => Rgb::BLUE,
             PrimaryState::Set => Rgb::YELLOW,
             PrimaryState::Playing => Rgb::GREEN,
@@ -176,7 +179,7 @@ impl LedStatus {
                 messages
                     .iter()
                     .flatten()
-                    .any(|&message| matches!(message, IncomingMessage::GameController(_)))
+                    .any(|&message| matches!(message, IncomingMessage::GameController(..)))
                     .then_some(timestamp)
             })
         {
@@ -195,12 +198,24 @@ impl LedStatus {
                 .fold(0.0, f32::max),
         );
 
+        let foot = match context.robot_condition {
+            RobotCondition {
+                are_joints_hot: true,
+                ..
+            } => Rgb::RED,
+            RobotCondition {
+                is_battery_low: true,
+                ..
+            } => Rgb::YELLOW,
+            _ => Rgb::GREEN,
+        };
+
         let leds = Leds {
             left_ear: ears,
             right_ear: ears,
             chest,
-            left_foot: Rgb::GREEN,
-            right_foot: Rgb::GREEN,
+            left_foot: foot,
+            right_foot: foot,
             left_eye,
             right_eye,
         };
@@ -1,37 +1,56 @@
 pub mod a_star;
+pub mod acoustic_signaling;
+pub mod action_latency_monitor;
 pub mod active_vision;
 pub mod ball_filter;
+pub mod ball_possession_estimator;
+pub mod ball_search;
 pub mod ball_state_composer;
 pub mod behavior;
 pub mod button_filter;
 pub mod camera_matrix_calculator;
 pub mod center_of_mass_provider;
+pub mod cycler_rate_governor;
 pub mod dribble_path_planner;
 pub mod fake_data;
 pub mod fall_state_estimation;
+pub mod field_coverage_mapper;
 pub mod foot_bumper_filter;
 pub mod game_controller_filter;
+pub mod game_controller_return_message_sender;
 pub mod game_controller_state_filter;
+pub mod goal_mouth_coordinator;
 pub mod ground_contact_detector;
 pub mod ground_provider;
+pub mod hungarian;
+pub mod image_dump_trigger;
 pub mod kick_selector;
 pub mod kinematics_provider;
 pub mod led_status;
 pub mod localization;
+pub mod location_announcer;
+pub mod manual_placement;
 pub mod motion;
 pub mod obstacle_filter;
 pub mod odometry;
 pub mod orientation_filter;
+pub mod pass_target_selector;
 pub mod path_planner;
 pub mod penalty_shot_direction_estimation;
 pub mod primary_state_filter;
+pub mod ready_signal_filter;
+pub mod robot_condition_filter;
 pub mod role_assignment;
 pub mod rule_obstacle_composer;
+pub mod self_test;
 pub mod sensor_data_receiver;
+pub mod shot_selector;
 pub mod sole_pressure_filter;
 pub mod sonar_filter;
 pub mod support_foot_estimation;
+pub mod teammate_striker_filter;
 pub mod time_to_reach_kick_position;
+pub mod transform_sanity_checker;
 pub mod visual_referee_filter;
 pub mod whistle_filter;
 pub mod world_state_composer;
@@ -1,6 +1,7 @@
 use std::{
     f32::consts::{FRAC_PI_2, PI},
     mem::take,
+    time::{Duration, SystemTime},
 };
 
 use approx::assert_relative_eq;
@@ -12,11 +13,12 @@ use serde::{Deserialize, Serialize};
 use context_attribute::context;
 use coordinate_systems::{Field, Ground};
 use filtering::pose_filter::PoseFilter;
-use framework::{AdditionalOutput, HistoricInput, MainOutput, PerceptionInput};
+use framework::{AdditionalOutput, HistoricInput, MainOutput, PerceptionInput, PersistentState};
 use geometry::line::{Line, Line2};
 use linear_algebra::{distance, point, vector, IntoTransform, Isometry2, Pose2};
 use spl_network_messages::{GamePhase, Penalty, PlayerNumber, Team};
 use types::{
+    cycle_time::CycleTime,
     field_dimensions::FieldDimensions,
     field_marks::{field_marks_from_field_dimensions, CorrespondencePoints, Direction, FieldMark},
     filtered_game_controller_state::FilteredGameControllerState,
@@ -37,6 +39,8 @@ pub struct Localization {
     hypotheses_when_entered_playing: Vec<ScoredPose>,
     is_penalized_with_motion_in_set_or_initial: bool,
     was_picked_up_while_penalized_with_motion_in_set_or_initial: bool,
+    /// Set when the robot re-entered the field after a penalty until the re-entry prior converged
+    reentered_at: Option<SystemTime>,
 }
 
 #[context]
@@ -60,6 +64,7 @@ pub struct CycleContext {
         Input<Option<FilteredGameControllerState>, "filtered_game_controller_state?">,
     has_ground_contact: Input<bool, "has_ground_contact">,
     primary_state: Input<PrimaryState, "primary_state">,
+    cycle_time: Input<CycleTime, "cycle_time">,
 
     circle_measurement_noise: Parameter<Vector2<f32>, "localization.circle_measurement_noise">,
     field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
@@ -86,6 +91,11 @@ pub struct CycleContext {
     penalized_distance: Parameter<f32, "localization.penalized_distance">,
     penalized_hypothesis_covariance:
         Parameter<Matrix3<f32>, "localization.penalized_hypothesis_covariance">,
+    reentry_hypothesis_covariance:
+        Parameter<Matrix3<f32>, "localization.reentry_hypothesis_covariance">,
+    reentry_hypothesis_score: Parameter<f32, "localization.reentry_hypothesis_score">,
+    reentry_convergence_uncertainty: Parameter<f32, "localization.reentry_convergence_uncertainty">,
+    reentry_convergence_timeout: Parameter<Duration, "localization.reentry_convergence_timeout">,
     score_per_good_match: Parameter<f32, "localization.score_per_good_match">,
     use_line_measurements: Parameter<bool, "localization.use_line_measurements">,
     injected_ground_to_field_of_home_after_coin_toss_before_second_half: Parameter<
@@ -105,6 +115,11 @@ pub struct MainOutputs {
     pub ground_to_field: MainOutput<Option<Isometry2<Ground, Field>>>,
     pub ground_to_field_of_home_after_coin_toss_before_second_half:
         MainOutput<Option<Isometry2<Ground, Field>>>,
+    /// Standard deviation of the best hypothesis' position in meters
+    pub localization_uncertainty: MainOutput<Option<f32>>,
+    /// The robot returned from a penalty and the localization has not yet settled on one side of
+    /// the field
+    pub is_converging_after_reentry: MainOutput<bool>,
 }
 
 impl Localization {
@@ -121,6 +136,7 @@ impl Localization {
             hypotheses_when_entered_playing: vec![],
             is_penalized_with_motion_in_set_or_initial: false,
             was_picked_up_while_penalized_with_motion_in_set_or_initial: false,
+            reentered_at: None,
         })
     }
 
@@ -132,7 +148,7 @@ impl Localization {
         penalty: &Option<Penalty>,
     ) {
         match (self.last_primary_state, primary_state, game_phase) {
-            (PrimaryState::Initial, PrimaryState::Ready, _) => {
+            (PrimaryState::Initial | PrimaryState::Standby, PrimaryState::Ready, _) => {
                 let initial_pose = generate_initial_pose(
                     &context.initial_poses[*context.player_number],
                     context.field_dimensions,
@@ -215,21 +231,24 @@ impl Localization {
                     self.is_penalized_with_motion_in_set_or_initial = false;
                     self.was_picked_up_while_penalized_with_motion_in_set_or_initial = false;
                 } else {
-                    let penalized_poses = generate_penalized_poses(
+                    // the rules place returning robots on a sideline at the own penalty mark,
+                    // facing the field, only the side is unknown
+                    let reentry_poses = generate_penalized_poses(
                         context.field_dimensions,
                         *context.penalized_distance,
                     );
-                    self.hypotheses = penalized_poses
+                    self.hypotheses = reentry_poses
                         .into_iter()
                         .map(|pose| {
                             ScoredPose::from_isometry(
                                 pose,
-                                *context.penalized_hypothesis_covariance,
-                                *context.initial_hypothesis_score,
+                                *context.reentry_hypothesis_covariance,
+                                *context.reentry_hypothesis_score,
                             )
                         })
                         .collect();
                     self.hypotheses_when_entered_playing = self.hypotheses.clone();
+                    self.reentered_at = Some(context.cycle_time.start_time);
                 }
             }
             (PrimaryState::Unstiff, _, _) => {
@@ -504,13 +523,28 @@ impl Localization {
             self.was_picked_up_while_penalized_with_motion_in_set_or_initial = true;
         }
 
-        let ground_to_field = match primary_state {
+        let (ground_to_field, localization_uncertainty) = match primary_state {
             PrimaryState::Ready | PrimaryState::Set | PrimaryState::Playing => {
                 self.update_state(&mut context)?;
-                Some(*context.ground_to_field)
+                let localization_uncertainty = self.get_best_hypothesis().map(|hypothesis| {
+                    let covariance = hypothesis.state.covariance;
+                    (covariance[(0, 0)] + covariance[(1, 1)]).sqrt()
+                });
+                (Some(*context.ground_to_field), localization_uncertainty)
             }
-            _ => None,
+            _ => (None, None),
         };
+        self.reentered_at = self.reentered_at.filter(|&reentered_at| {
+            is_converging_after_reentry(
+                reentered_at,
+                context.cycle_time.start_time,
+                primary_state,
+                self.hypotheses.len(),
+                localization_uncertainty,
+                *context.reentry_convergence_uncertainty,
+                *context.reentry_convergence_timeout,
+            )
+        });
         let ground_to_field_of_home_after_coin_toss_before_second_half = context
             .injected_ground_to_field_of_home_after_coin_toss_before_second_half
             .copied()
@@ -535,6 +569,8 @@ impl Localization {
             ground_to_field: ground_to_field.into(),
             ground_to_field_of_home_after_coin_toss_before_second_half:
                 ground_to_field_of_home_after_coin_toss_before_second_half.into(),
+            localization_uncertainty: localization_uncertainty.into(),
+            is_converging_after_reentry: self.reentered_at.is_some().into(),
         })
     }
 
@@ -545,6 +581,26 @@ impl Localization {
     }
 }
 
+/// Field marks are derived from the field dimensions and the primary state is taken anew
+impl PersistentState for Localization {
+    type State = (Vec<ScoredPose>, Vec<ScoredPose>);
+
+    fn persistent_state(&self) -> Self::State {
+        (
+            self.hypotheses.clone(),
+            self.hypotheses_when_entered_playing.clone(),
+        )
+    }
+
+    fn restore_persistent_state(
+        &mut self,
+        (hypotheses, hypotheses_when_entered_playing): Self::State,
+    ) {
+        self.hypotheses = hypotheses;
+        self.hypotheses_when_entered_playing = hypotheses_when_entered_playing;
+    }
+}
+
 pub fn goal_support_structure_line_marks_from_field_dimensions(
     field_dimensions: &FieldDimensions,
 ) -> Vec<FieldMark> {
@@ -1016,6 +1072,30 @@ fn generate_penalized_poses(
     ]
 }
 
+/// A reentered robot converges until a single confident hypothesis is left, it gives up after the
+/// timeout or once it is out of play again
+fn is_converging_after_reentry(
+    reentered_at: SystemTime,
+    now: SystemTime,
+    primary_state: PrimaryState,
+    number_of_hypotheses: usize,
+    localization_uncertainty: Option<f32>,
+    convergence_uncertainty: f32,
+    convergence_timeout: Duration,
+) -> bool {
+    let has_converged = number_of_hypotheses == 1
+        && localization_uncertainty
+            .is_some_and(|uncertainty| uncertainty < convergence_uncertainty);
+    let has_timed_out = now
+        .duration_since(reentered_at)
+        .is_ok_and(|duration| duration > convergence_timeout);
+    matches!(
+        primary_state,
+        PrimaryState::Ready | PrimaryState::Set | PrimaryState::Playing
+    ) && !has_converged
+        && !has_timed_out
+}
+
 #[cfg(test)]
 mod tests {
     use std::f32::consts::FRAC_PI_4;
@@ -1701,4 +1781,73 @@ mod tests {
         let update = get_2d_translation_measurement(ground_to_field, field_mark_correspondence);
         assert_relative_eq!(update, nalgebra::vector![0.0, -2.0], epsilon = 0.0001);
     }
+
+    #[test]
+    fn reentered_robot_converges_until_a_single_confident_hypothesis_is_left() {
+        let reentered_at = SystemTime::UNIX_EPOCH;
+        let now = reentered_at + Duration::from_secs(2);
+        let timeout = Duration::from_secs(10);
+
+        assert!(is_converging_after_reentry(
+            reentered_at,
+            now,
+            PrimaryState::Playing,
+            2,
+            Some(0.1),
+            0.3,
+            timeout
+        ));
+        assert!(is_converging_after_reentry(
+            reentered_at,
+            now,
+            PrimaryState::Playing,
+            1,
+            Some(0.5),
+            0.3,
+            timeout
+        ));
+        assert!(is_converging_after_reentry(
+            reentered_at,
+            now,
+            PrimaryState::Playing,
+            1,
+            None,
+            0.3,
+            timeout
+        ));
+        assert!(!is_converging_after_reentry(
+            reentered_at,
+            now,
+            PrimaryState::Playing,
+            1,
+            Some(0.1),
+            0.3,
+            timeout
+        ));
+    }
+
+    #[test]
+    fn reentered_robot_stops_converging_after_timeout_or_out_of_play() {
+        let reentered_at = SystemTime::UNIX_EPOCH;
+        let timeout = Duration::from_secs(10);
+
+        assert!(!is_converging_after_reentry(
+            reentered_at,
+            reentered_at + Duration::from_secs(11),
+            PrimaryState::Playing,
+            2,
+            None,
+            0.3,
+            timeout
+        ));
+        assert!(!is_converging_after_reentry(
+            reentered_at,
+            reentered_at + Duration::from_secs(2),
+            PrimaryState::Penalized,
+            2,
+            None,
+            0.3,
+            timeout
+        ));
+    }
 }
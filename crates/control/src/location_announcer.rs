@@ -0,0 +1,60 @@
+use color_eyre::Result;
+use context_attribute::context;
+use hardware::SpeakerInterface;
+use serde::{Deserialize, Serialize};
+use types::{
+    audio::SpeakerRequest,
+    parameters::{LocationAnnouncementParameters, LocationParameters},
+};
+
+/// Confirms the active location acoustically once after startup, a fallback to `nao_location`
+/// sounds different than a location selected from the connected network.
+#[derive(Deserialize, Serialize)]
+pub struct LocationAnnouncer {
+    has_announced: bool,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    location: Parameter<Option<LocationParameters>, "location?">,
+    parameters: Parameter<LocationAnnouncementParameters, "location_announcement">,
+
+    hardware_interface: HardwareInterface,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {}
+
+impl LocationAnnouncer {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            has_announced: false,
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext<impl SpeakerInterface>) -> Result<MainOutputs> {
+        if self.has_announced {
+            return Ok(MainOutputs {});
+        }
+        let Some(location) = context.location else {
+            return Ok(MainOutputs {});
+        };
+        let tone = if location.is_detected_from_network {
+            &context.parameters.detected_tone
+        } else {
+            &context.parameters.fallback_tone
+        };
+        context
+            .hardware_interface
+            .write_to_speakers(SpeakerRequest::PlayTones {
+                frequencies: [tone.low_frequency, tone.high_frequency],
+                duration: context.parameters.tone_duration,
+            });
+        self.has_announced = true;
+        Ok(MainOutputs {})
+    }
+}
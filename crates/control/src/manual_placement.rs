@@ -0,0 +1,145 @@
+use std::time::{Duration, SystemTime};
+
+use color_eyre::Result;
+use context_attribute::context;
+use coordinate_systems::{Field, Ground};
+use framework::MainOutput;
+use hardware::SpeakerInterface;
+use linear_algebra::{distance, point, Isometry2, Point2, Pose2};
+use serde::{Deserialize, Serialize};
+use spl_network_messages::{GamePhase, PlayerNumber, Team};
+use types::{
+    audio::{Sound, SpeakerRequest},
+    cycle_time::CycleTime,
+    field_dimensions::FieldDimensions,
+    filtered_game_controller_state::FilteredGameControllerState,
+    filtered_game_state::FilteredGameState,
+    parameters::ManualPlacementParameters,
+};
+
+#[derive(Deserialize, Serialize)]
+pub struct ManualPlacement {
+    last_hint: Option<SystemTime>,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    cycle_time: Input<CycleTime, "cycle_time">,
+    filtered_game_controller_state:
+        Input<Option<FilteredGameControllerState>, "filtered_game_controller_state?">,
+    ground_to_field: Input<Option<Isometry2<Ground, Field>>, "ground_to_field?">,
+
+    field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
+    parameters: Parameter<ManualPlacementParameters, "manual_placement">,
+    player_number: Parameter<PlayerNumber, "player_number">,
+
+    hardware_interface: HardwareInterface,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub manual_placement_pose: MainOutput<Option<Pose2<Field>>>,
+}
+
+impl ManualPlacement {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self { last_hint: None })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext<impl SpeakerInterface>) -> Result<MainOutputs> {
+        let parameters = context.parameters;
+        let Some(game_controller_state) = context.filtered_game_controller_state else {
+            return Ok(MainOutputs::default());
+        };
+        let is_before_kick_off = matches!(
+            game_controller_state.game_state,
+            FilteredGameState::Ready { .. } | FilteredGameState::Set
+        );
+        if !is_before_kick_off
+            || !matches!(game_controller_state.game_phase, GamePhase::Normal)
+            || game_controller_state.sub_state.is_some()
+        {
+            self.last_hint = None;
+            return Ok(MainOutputs::default());
+        }
+
+        let positions = match game_controller_state.kicking_team {
+            Team::Hulks => &parameters.kicking_team,
+            Team::Opponent | Team::Uncertain => &parameters.defending_team,
+        };
+        // manually placed robots always face the opponent goal
+        let manual_placement_pose = Pose2::from(positions[*context.player_number]);
+
+        let now = context.cycle_time.start_time;
+        let is_misplaced = context.ground_to_field.map_or(true, |ground_to_field| {
+            distance(
+                ground_to_field.as_pose().position(),
+                manual_placement_pose.position(),
+            ) > parameters.placement_tolerance
+        });
+        let may_hint = self.last_hint.map_or(true, |last_hint| {
+            now.duration_since(last_hint).unwrap_or(Duration::ZERO) > parameters.hint_interval
+        });
+        if parameters.enable_speaker_hints
+            && game_controller_state.game_state == FilteredGameState::Set
+            && is_misplaced
+            && may_hint
+        {
+            for sound in placement_hint(
+                manual_placement_pose.position(),
+                context.field_dimensions,
+                parameters.penalty_area_corner_radius,
+            ) {
+                context
+                    .hardware_interface
+                    .write_to_speakers(SpeakerRequest::PlaySound { sound });
+            }
+            self.last_hint = Some(now);
+        }
+
+        Ok(MainOutputs {
+            manual_placement_pose: Some(manual_placement_pose).into(),
+        })
+    }
+}
+
+/// Describes a placement position by the nearest field feature and the side of the field it is on.
+fn placement_hint(
+    position: Point2<Field>,
+    field_dimensions: &FieldDimensions,
+    penalty_area_corner_radius: f32,
+) -> Vec<Sound> {
+    let side = if position.y() > 0.0 {
+        Sound::Left
+    } else {
+        Sound::Right
+    };
+    let goal_line_x = -field_dimensions.length / 2.0;
+    let penalty_area_corner = point![
+        goal_line_x + field_dimensions.penalty_area_length,
+        position.y().signum() * field_dimensions.penalty_area_width / 2.0
+    ];
+    let penalty_spot = point![goal_line_x + field_dimensions.penalty_marker_distance, 0.0];
+
+    if position.x() < goal_line_x + field_dimensions.goal_box_area_length
+        && position.y().abs() < field_dimensions.goal_box_area_width / 2.0
+    {
+        vec![Sound::Keeper]
+    } else if distance(position, penalty_area_corner) < penalty_area_corner_radius {
+        vec![Sound::PenaltyArea, Sound::Corner, side]
+    } else if distance(position, penalty_spot) < penalty_area_corner_radius {
+        vec![Sound::PenaltySpot]
+    } else if position.coords().norm() < field_dimensions.center_circle_diameter {
+        vec![Sound::CenterCircle]
+    } else if position.x() < goal_line_x + field_dimensions.penalty_area_length
+        && position.y().abs() < field_dimensions.penalty_area_width / 2.0
+    {
+        vec![Sound::PenaltyArea, side]
+    } else {
+        vec![side]
+    }
+}
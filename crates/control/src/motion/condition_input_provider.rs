@@ -48,6 +48,7 @@ impl ConditionInputProvider {
         Ok(MainOutputs {
             condition_input: ConditionInput {
                 filtered_angular_velocity: self.angular_velocity_filter.state(),
+                roll_pitch: context.sensor_data.inertial_measurement_unit.roll_pitch,
                 fall_state: *context.fall_state,
             }
             .into(),
@@ -26,6 +26,7 @@ pub struct CreationContext {}
 #[context]
 pub struct CycleContext {
     arms_up_squat_joints_command: Input<MotorCommands<Joints<f32>>, "arms_up_squat_joints_command">,
+    jump_front_joints_command: Input<MotorCommands<Joints<f32>>, "jump_front_joints_command">,
     jump_left_joints_command: Input<MotorCommands<Joints<f32>>, "jump_left_joints_command">,
     jump_right_joints_command: Input<MotorCommands<Joints<f32>>, "jump_right_joints_command">,
     motion_selection: Input<MotionSelection, "motion_selection">,
@@ -86,6 +87,7 @@ impl DispatchingInterpolator {
                 MotionType::Dispatching => panic!("Dispatching cannot dispatch itself"),
                 MotionType::FallProtection => panic!("Is executed immediately"),
                 MotionType::Initial => *context.initial_pose,
+                MotionType::JumpFront => context.jump_front_joints_command.positions,
                 MotionType::JumpLeft => context.jump_left_joints_command.positions,
                 MotionType::JumpRight => context.jump_right_joints_command.positions,
                 MotionType::Penalized => *context.penalized_pose,
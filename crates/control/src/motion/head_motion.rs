@@ -2,19 +2,24 @@ use std::f32::consts::PI;
 
 use color_eyre::Result;
 use context_attribute::context;
-use framework::MainOutput;
+use framework::{AdditionalOutput, MainOutput};
 use serde::{Deserialize, Serialize};
 use types::{
     cycle_time::CycleTime,
+    head_stabilization::HeadStabilization,
     joints::head::HeadJoints,
     motion_command::{HeadMotion as HeadMotionCommand, MotionCommand},
     motor_commands::MotorCommands,
+    parameters::HeadStabilizationParameters,
     sensor_data::SensorData,
+    step_plan::Step,
+    walk_command::WalkCommand,
 };
 
 #[derive(Default, Deserialize, Serialize)]
 pub struct HeadMotion {
     last_positions: HeadJoints<f32>,
+    torso_oscillation: HeadJoints<f32>,
 }
 
 #[context]
@@ -27,6 +32,8 @@ pub struct CycleContext {
     maximum_velocity: Parameter<HeadJoints<f32>, "head_motion.maximum_velocity">,
     outer_maximum_pitch: Parameter<f32, "head_motion.outer_maximum_pitch">,
     outer_yaw: Parameter<f32, "head_motion.outer_yaw">,
+    stabilization: Parameter<HeadStabilizationParameters, "head_motion.stabilization">,
+    max_step_size: Parameter<Step, "step_planner.max_step_size">,
 
     look_around: Input<HeadJoints<f32>, "look_around">,
     look_at: Input<HeadJoints<f32>, "look_at">,
@@ -34,6 +41,9 @@ pub struct CycleContext {
     sensor_data: Input<SensorData, "sensor_data">,
     cycle_time: Input<CycleTime, "cycle_time">,
     has_ground_contact: Input<bool, "has_ground_contact">,
+    walk_command: Input<WalkCommand, "walk_command">,
+
+    head_stabilization: AdditionalOutput<HeadStabilization, "head_stabilization">,
 }
 
 #[context]
@@ -46,10 +56,11 @@ impl HeadMotion {
     pub fn new(_context: CreationContext) -> Result<Self> {
         Ok(Self {
             last_positions: Default::default(),
+            torso_oscillation: Default::default(),
         })
     }
 
-    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+    pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
         let MotorCommands {
             positions: raw_positions,
             stiffnesses,
@@ -60,6 +71,7 @@ impl HeadMotion {
                 positions: Default::default(),
                 stiffnesses: HeadJoints::fill(0.8),
             });
+        let raw_positions = raw_positions + self.stabilize(&mut context);
 
         let maximum_movement =
             *context.maximum_velocity * context.cycle_time.last_cycle_duration.as_secs_f32();
@@ -99,6 +111,46 @@ impl HeadMotion {
         })
     }
 
+    /// Counter-rotates the neck against the torso swaying while walking such that the cameras
+    /// keep looking at the same spot.
+    fn stabilize(&mut self, context: &mut CycleContext) -> HeadJoints<f32> {
+        let parameters = context.stabilization;
+        let cycle_duration = context.cycle_time.last_cycle_duration.as_secs_f32();
+        let angular_velocity = context
+            .sensor_data
+            .inertial_measurement_unit
+            .angular_velocity;
+
+        // a leaky integrator only keeps the oscillation, not the mean orientation or drift
+        let decay = (1.0 - parameters.leak_rate * cycle_duration).clamp(0.0, 1.0);
+        self.torso_oscillation = HeadJoints {
+            yaw: (self.torso_oscillation.yaw + angular_velocity.z * cycle_duration) * decay,
+            pitch: (self.torso_oscillation.pitch + angular_velocity.y * cycle_duration) * decay,
+        };
+
+        let speed_factor = match context.walk_command {
+            WalkCommand::Walk(step) => walk_speed_factor(step, context.max_step_size),
+            WalkCommand::Stand | WalkCommand::Kick(..) => 0.0,
+        };
+        let is_stabilizing = parameters.enable
+            && *context.has_ground_contact
+            && context.motion_command.head_motion() != Some(HeadMotionCommand::Unstiff);
+        let compensation = if is_stabilizing {
+            compensate(self.torso_oscillation, speed_factor, parameters)
+        } else {
+            HeadJoints::default()
+        };
+
+        context
+            .head_stabilization
+            .fill_if_subscribed(|| HeadStabilization {
+                speed_factor,
+                torso_oscillation: self.torso_oscillation,
+                compensation,
+            });
+        compensation
+    }
+
     pub fn joints_from_motion(context: &CycleContext) -> MotorCommands<HeadJoints<f32>> {
         let stiffnesses = HeadJoints::fill(0.8);
         match context.motion_command.head_motion() {
@@ -128,3 +180,113 @@ impl HeadMotion {
         }
     }
 }
+
+fn compensate(
+    torso_oscillation: HeadJoints<f32>,
+    speed_factor: f32,
+    parameters: &HeadStabilizationParameters,
+) -> HeadJoints<f32> {
+    HeadJoints {
+        yaw: (-parameters.gains.yaw * speed_factor * torso_oscillation.yaw).clamp(
+            -parameters.maximum_compensation.yaw,
+            parameters.maximum_compensation.yaw,
+        ),
+        pitch: (-parameters.gains.pitch * speed_factor * torso_oscillation.pitch).clamp(
+            -parameters.maximum_compensation.pitch,
+            parameters.maximum_compensation.pitch,
+        ),
+    }
+}
+
+fn walk_speed_factor(step: &Step, max_step_size: &Step) -> f32 {
+    let relative = |value: f32, maximum: f32| {
+        if maximum > f32::EPSILON {
+            value / maximum
+        } else {
+            0.0
+        }
+    };
+    nalgebra::vector![
+        relative(step.forward, max_step_size.forward),
+        relative(step.left, max_step_size.left),
+        relative(step.turn, max_step_size.turn)
+    ]
+    .norm()
+    .min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    fn parameters() -> HeadStabilizationParameters {
+        HeadStabilizationParameters {
+            enable: true,
+            gains: HeadJoints {
+                yaw: 0.5,
+                pitch: 1.0,
+            },
+            leak_rate: 2.0,
+            maximum_compensation: HeadJoints {
+                yaw: 0.1,
+                pitch: 0.1,
+            },
+        }
+    }
+
+    #[test]
+    fn walk_speed_is_relative_to_maximum_step() {
+        let max_step_size = Step {
+            forward: 0.1,
+            left: 0.05,
+            turn: 0.5,
+        };
+        let step = |forward, left, turn| Step {
+            forward,
+            left,
+            turn,
+        };
+        assert_relative_eq!(walk_speed_factor(&step(0.0, 0.0, 0.0), &max_step_size), 0.0);
+        assert_relative_eq!(
+            walk_speed_factor(&step(0.05, 0.0, 0.0), &max_step_size),
+            0.5
+        );
+        assert_relative_eq!(
+            walk_speed_factor(&step(0.0, -0.05, 0.0), &max_step_size),
+            1.0
+        );
+        assert_relative_eq!(
+            walk_speed_factor(&step(0.1, 0.05, 0.5), &max_step_size),
+            1.0
+        );
+        assert_relative_eq!(
+            walk_speed_factor(&step(0.05, 0.0, 0.0), &Step::default()),
+            0.0
+        );
+    }
+
+    #[test]
+    fn compensation_counters_oscillation_within_limits() {
+        let oscillation = HeadJoints {
+            yaw: 0.04,
+            pitch: -0.06,
+        };
+        let compensation = compensate(oscillation, 1.0, &parameters());
+        assert_relative_eq!(compensation.yaw, -0.02);
+        assert_relative_eq!(compensation.pitch, 0.06);
+
+        let half_speed = compensate(oscillation, 0.5, &parameters());
+        assert_relative_eq!(half_speed.yaw, -0.01);
+        assert_relative_eq!(half_speed.pitch, 0.03);
+
+        let large_oscillation = HeadJoints {
+            yaw: -1.0,
+            pitch: 1.0,
+        };
+        let clamped = compensate(large_oscillation, 1.0, &parameters());
+        assert_relative_eq!(clamped.yaw, 0.1);
+        assert_relative_eq!(clamped.pitch, -0.1);
+    }
+}
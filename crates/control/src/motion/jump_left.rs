@@ -10,6 +10,7 @@ use types::{
     joints::Joints,
     motion_selection::{MotionSafeExits, MotionSelection, MotionType},
     motor_commands::MotorCommands,
+    support_foot::Side,
 };
 
 #[derive(Deserialize, Serialize)]
@@ -58,7 +59,7 @@ impl JumpLeft {
         context.motion_safe_exits[MotionType::JumpLeft] = self.interpolator.is_finished();
 
         Ok(MainOutputs {
-            jump_left_joints_command: self.interpolator.value().into(),
+            jump_left_joints_command: self.interpolator.value_on(Side::Left).into(),
         })
     }
 }
@@ -7,9 +7,10 @@ use serde::{Deserialize, Serialize};
 use types::{
     condition_input::ConditionInput,
     cycle_time::CycleTime,
-    joints::{mirror::Mirror, Joints},
+    joints::Joints,
     motion_selection::{MotionSafeExits, MotionSelection, MotionType},
     motor_commands::MotorCommands,
+    support_foot::Side,
 };
 
 #[derive(Deserialize, Serialize)]
@@ -58,7 +59,7 @@ impl JumpRight {
         context.motion_safe_exits[MotionType::JumpRight] = self.interpolator.is_finished();
 
         Ok(MainOutputs {
-            jump_right_joints_command: self.interpolator.value().mirrored().into(),
+            jump_right_joints_command: self.interpolator.value_on(Side::Right).into(),
         })
     }
 }
@@ -4,6 +4,7 @@ pub mod condition_input_provider;
 pub mod dispatching_interpolator;
 pub mod fall_protector;
 pub mod head_motion;
+pub mod jump_front;
 pub mod jump_left;
 pub mod jump_right;
 pub mod look_around;
@@ -11,6 +12,7 @@ pub mod look_at;
 pub mod motion_selector;
 pub mod motor_commands_collector;
 pub mod motor_commands_optimizer;
+pub mod shutdown_sequencer;
 pub mod sit_down;
 pub mod stand_up_back;
 pub mod stand_up_front;
@@ -77,6 +77,7 @@ fn motion_type_from_command(command: &MotionCommand) -> MotionType {
         MotionCommand::Jump { direction } => match direction {
             JumpDirection::Left => MotionType::JumpLeft,
             JumpDirection::Right => MotionType::JumpRight,
+            JumpDirection::Front => MotionType::JumpFront,
         },
         MotionCommand::Penalized => MotionType::Penalized,
         MotionCommand::SitDown { .. } => MotionType::SitDown,
@@ -103,6 +104,8 @@ fn transition_motion(
         (MotionType::Dispatching, true, MotionType::Unstiff, true) => MotionType::SitDown,
         (MotionType::StandUpFront, _, MotionType::FallProtection, _) => MotionType::StandUpFront,
         (MotionType::StandUpBack, _, MotionType::FallProtection, _) => MotionType::StandUpBack,
+        // dives fall on purpose and protect the robot themselves
+        (MotionType::JumpFront | MotionType::JumpLeft | MotionType::JumpRight, false, _, _) => from,
         (MotionType::StandUpFront, true, MotionType::StandUpFront, _) => MotionType::Dispatching,
         (MotionType::StandUpBack, true, MotionType::StandUpBack, _) => MotionType::Dispatching,
         (_, _, MotionType::FallProtection, _) => MotionType::FallProtection,
@@ -113,3 +116,84 @@ fn transition_motion(
         _ => from,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use types::motion_command::{FallDirection, HeadMotion};
+
+    use super::*;
+
+    fn cycle(
+        motion_selector: &mut MotionSelector,
+        motion_command: MotionCommand,
+        motion_safe_to_exit: bool,
+    ) -> MotionSelection {
+        let mut motion_safe_exits = MotionSafeExits::default();
+        motion_safe_exits[motion_selector.current_motion] = motion_safe_to_exit;
+        motion_selector
+            .cycle(CycleContext::new(
+                &motion_command,
+                &true,
+                &mut motion_safe_exits,
+            ))
+            .unwrap()
+            .motion_selection
+            .value
+    }
+
+    fn diving_selector() -> MotionSelector {
+        MotionSelector {
+            current_motion: MotionType::JumpLeft,
+            dispatching_motion: None,
+        }
+    }
+
+    #[test]
+    fn dive_is_not_interrupted_before_its_end() {
+        let mut motion_selector = diving_selector();
+
+        let selection = cycle(
+            &mut motion_selector,
+            MotionCommand::FallProtection {
+                direction: FallDirection::Backward,
+            },
+            false,
+        );
+
+        assert_eq!(selection.current_motion, MotionType::JumpLeft);
+    }
+
+    #[test]
+    fn stand_up_after_dive_follows_the_fall_state() {
+        for (facing, stand_up) in [
+            (Facing::Down, MotionType::StandUpFront),
+            (Facing::Up, MotionType::StandUpBack),
+        ] {
+            let mut motion_selector = diving_selector();
+
+            let selection = cycle(
+                &mut motion_selector,
+                MotionCommand::StandUp { facing },
+                true,
+            );
+
+            assert_eq!(selection.current_motion, MotionType::Dispatching);
+            assert_eq!(selection.dispatching_motion, Some(stand_up));
+        }
+    }
+
+    #[test]
+    fn finished_dive_does_not_force_a_stand_up() {
+        let mut motion_selector = diving_selector();
+
+        let selection = cycle(
+            &mut motion_selector,
+            MotionCommand::Stand {
+                head: HeadMotion::ZeroAngles,
+            },
+            true,
+        );
+
+        assert_eq!(selection.dispatching_motion, Some(MotionType::Stand));
+    }
+}
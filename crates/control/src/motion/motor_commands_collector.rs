@@ -25,14 +25,17 @@ pub struct CycleContext {
     dispatching_command: Input<MotorCommands<Joints<f32>>, "dispatching_command">,
     fall_protection_command: Input<MotorCommands<Joints<f32>>, "fall_protection_command">,
     head_joints_command: Input<MotorCommands<HeadJoints<f32>>, "head_joints_command">,
+    jump_front_joints_command: Input<MotorCommands<Joints<f32>>, "jump_front_joints_command">,
     jump_left_joints_command: Input<MotorCommands<Joints<f32>>, "jump_left_joints_command">,
     jump_right_joints_command: Input<MotorCommands<Joints<f32>>, "jump_right_joints_command">,
     motion_selection: Input<MotionSelection, "motion_selection">,
     sensor_data: Input<SensorData, "sensor_data">,
+    shutdown_stiffness_scale: Input<f32, "shutdown_stiffness_scale">,
     sit_down_joints_command: Input<MotorCommands<Joints<f32>>, "sit_down_joints_command">,
     stand_up_back_positions: Input<Joints<f32>, "stand_up_back_positions">,
     stand_up_front_positions: Input<Joints<f32>, "stand_up_front_positions">,
     walk_motor_commands: Input<MotorCommands<BodyJoints<f32>>, "walk_motor_commands">,
+    self_test_joint_offsets: Input<Joints<f32>, "self_test_joint_offsets">,
     cycle_time: Input<CycleTime, "cycle_time">,
 
     joint_calibration_offsets: Parameter<Joints<f32>, "joint_calibration_offsets">,
@@ -66,6 +69,7 @@ impl MotorCommandCollector {
         let head_joints_command = context.head_joints_command;
         let motion_selection = context.motion_selection;
         let arms_up_squat = context.arms_up_squat_joints_command;
+        let jump_front = context.jump_front_joints_command;
         let jump_left = context.jump_left_joints_command;
         let jump_right = context.jump_right_joints_command;
         let sit_down = context.sit_down_joints_command;
@@ -86,12 +90,13 @@ impl MotorCommandCollector {
             MotionType::Initial => (
                 self.current_minimizer.optimize(
                     context.sensor_data.currents,
-                    *context.initial_pose,
+                    *context.initial_pose + *context.self_test_joint_offsets,
                     *context.cycle_time,
                     *context.current_minimizer_parameters,
                 ),
                 Joints::fill(0.6),
             ),
+            MotionType::JumpFront => (jump_front.positions, jump_front.stiffnesses),
             MotionType::JumpLeft => (jump_left.positions, jump_left.stiffnesses),
             MotionType::JumpRight => (jump_right.positions, jump_right.stiffnesses),
             MotionType::Penalized => (
@@ -127,7 +132,7 @@ impl MotorCommandCollector {
         let compensated_positions = positions + *context.joint_calibration_offsets;
         let motor_commands = MotorCommands {
             positions: compensated_positions,
-            stiffnesses,
+            stiffnesses: stiffnesses * *context.shutdown_stiffness_scale,
         };
 
         context
@@ -0,0 +1,166 @@
+use std::time::SystemTime;
+
+use color_eyre::Result;
+use context_attribute::context;
+use framework::MainOutput;
+use hardware::ShutdownInterface;
+use serde::{Deserialize, Serialize};
+use types::{
+    cycle_time::CycleTime,
+    motion_selection::{MotionSafeExits, MotionSelection, MotionType},
+    parameters::ShutdownParameters,
+};
+
+/// Brings the robot into a safe posture before the process exits: the behavior sits down, then
+/// the stiffness is ramped to zero and the framework is told to finish the shutdown.
+#[derive(Deserialize, Serialize)]
+pub struct ShutdownSequencer {
+    requested_at: Option<SystemTime>,
+    ramp_started_at: Option<SystemTime>,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    cycle_time: Input<CycleTime, "cycle_time">,
+    is_shutdown_requested: Input<bool, "is_shutdown_requested">,
+    motion_selection: Input<MotionSelection, "motion_selection">,
+
+    parameters: Parameter<ShutdownParameters, "shutdown">,
+
+    motion_safe_exits: CyclerState<MotionSafeExits, "motion_safe_exits">,
+
+    hardware_interface: HardwareInterface,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub shutdown_stiffness_scale: MainOutput<f32>,
+}
+
+impl ShutdownSequencer {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            requested_at: None,
+            ramp_started_at: None,
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext<impl ShutdownInterface>) -> Result<MainOutputs> {
+        if !*context.is_shutdown_requested {
+            return Ok(MainOutputs {
+                shutdown_stiffness_scale: 1.0.into(),
+            });
+        }
+
+        let is_sitting = match context.motion_selection.current_motion {
+            MotionType::SitDown => context.motion_safe_exits[MotionType::SitDown],
+            MotionType::Unstiff => true,
+            _ => false,
+        };
+        let stiffness_scale = self.stiffness_scale(
+            context.cycle_time.start_time,
+            is_sitting,
+            context.parameters,
+        );
+        if stiffness_scale <= 0.0 {
+            context.hardware_interface.finish_shutdown();
+        }
+
+        Ok(MainOutputs {
+            shutdown_stiffness_scale: stiffness_scale.into(),
+        })
+    }
+
+    fn stiffness_scale(
+        &mut self,
+        now: SystemTime,
+        is_sitting: bool,
+        parameters: &ShutdownParameters,
+    ) -> f32 {
+        let requested_at = *self.requested_at.get_or_insert(now);
+        let sit_down_timed_out = now
+            .duration_since(requested_at)
+            .is_ok_and(|duration| duration >= parameters.sit_down_timeout);
+        if is_sitting || sit_down_timed_out {
+            self.ramp_started_at.get_or_insert(now);
+        }
+
+        match self.ramp_started_at {
+            Some(ramp_started_at) => {
+                let ramp_duration = parameters.stiffness_ramp_duration.as_secs_f32();
+                let elapsed = now
+                    .duration_since(ramp_started_at)
+                    .unwrap_or_default()
+                    .as_secs_f32();
+                if elapsed >= ramp_duration {
+                    0.0
+                } else {
+                    1.0 - elapsed / ramp_duration
+                }
+            }
+            None => 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    const PARAMETERS: ShutdownParameters = ShutdownParameters {
+        sit_down_timeout: Duration::from_secs(5),
+        stiffness_ramp_duration: Duration::from_secs(2),
+    };
+
+    fn at(seconds: f32) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs_f32(seconds)
+    }
+
+    #[test]
+    fn stiffness_ramps_out_once_sitting() {
+        let mut sequencer = ShutdownSequencer {
+            requested_at: None,
+            ramp_started_at: None,
+        };
+        assert_relative_eq!(sequencer.stiffness_scale(at(0.0), false, &PARAMETERS), 1.0);
+        assert_relative_eq!(sequencer.stiffness_scale(at(1.0), false, &PARAMETERS), 1.0);
+        assert_relative_eq!(sequencer.stiffness_scale(at(2.0), true, &PARAMETERS), 1.0);
+        assert_relative_eq!(
+            sequencer.stiffness_scale(at(3.0), true, &PARAMETERS),
+            0.5,
+            epsilon = 1e-4
+        );
+        // leaving the sitting posture does not restart the ramp
+        assert_relative_eq!(
+            sequencer.stiffness_scale(at(3.5), false, &PARAMETERS),
+            0.25,
+            epsilon = 1e-4
+        );
+        assert_relative_eq!(sequencer.stiffness_scale(at(4.0), false, &PARAMETERS), 0.0);
+    }
+
+    #[test]
+    fn stiffness_ramps_out_after_sit_down_timeout() {
+        let mut sequencer = ShutdownSequencer {
+            requested_at: None,
+            ramp_started_at: None,
+        };
+        assert_relative_eq!(sequencer.stiffness_scale(at(10.0), false, &PARAMETERS), 1.0);
+        assert_relative_eq!(sequencer.stiffness_scale(at(14.9), false, &PARAMETERS), 1.0);
+        assert_relative_eq!(sequencer.stiffness_scale(at(15.0), false, &PARAMETERS), 1.0);
+        assert_relative_eq!(
+            sequencer.stiffness_scale(at(16.0), false, &PARAMETERS),
+            0.5,
+            epsilon = 1e-4
+        );
+        assert_relative_eq!(sequencer.stiffness_scale(at(17.0), false, &PARAMETERS), 0.0);
+    }
+}
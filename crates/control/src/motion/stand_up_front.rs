@@ -16,6 +16,8 @@ use types::{
 #[derive(Deserialize, Serialize)]
 pub struct StandUpFront {
     interpolator: MotionInterpolator<Joints<f32>>,
+    fast_interpolator: MotionInterpolator<Joints<f32>>,
+    is_fast: bool,
 }
 
 #[context]
@@ -29,6 +31,8 @@ pub struct CycleContext {
     cycle_time: Input<CycleTime, "cycle_time">,
     motion_selection: Input<MotionSelection, "motion_selection">,
 
+    enable_fast_motions: Parameter<bool, "stand_up.enable_fast_motions">,
+
     motion_safe_exits: CyclerState<MotionSafeExits, "motion_safe_exits">,
 }
 
@@ -45,6 +49,11 @@ impl StandUpFront {
         Ok(Self {
             interpolator: MotionFile::from_path(paths.motions.join("stand_up_front.json"))?
                 .try_into()?,
+            fast_interpolator: MotionFile::from_path(
+                paths.motions.join("stand_up_front_fast.json"),
+            )?
+            .try_into()?,
+            is_fast: false,
         })
     }
 
@@ -54,10 +63,10 @@ impl StandUpFront {
 
         context.motion_safe_exits[MotionType::StandUpFront] = false;
 
-        self.interpolator
-            .advance_by(last_cycle_duration, condition_input);
+        let interpolator = self.active_interpolator();
+        interpolator.advance_by(last_cycle_duration, condition_input);
 
-        if self.interpolator.is_finished() {
+        if interpolator.is_finished() {
             context.motion_safe_exits[MotionType::StandUpFront] = true;
         }
     }
@@ -66,15 +75,27 @@ impl StandUpFront {
         let stand_up_front_estimated_remaining_duration =
             if let MotionType::StandUpFront = context.motion_selection.current_motion {
                 self.advance_interpolator(context);
-                Some(self.interpolator.estimated_remaining_duration())
+                Some(self.active_interpolator().estimated_remaining_duration())
             } else {
+                // the motion set is only switched between stand ups
+                self.is_fast = *context.enable_fast_motions;
                 self.interpolator.reset();
+                self.fast_interpolator.reset();
                 None
             };
         Ok(MainOutputs {
-            stand_up_front_positions: self.interpolator.value().into(),
+            stand_up_front_positions: self.active_interpolator().value().into(),
             stand_up_front_estimated_remaining_duration:
                 stand_up_front_estimated_remaining_duration.into(),
         })
     }
+
+    /// The fast motion set skips the stabilization frames once the torso is already upright
+    fn active_interpolator(&mut self) -> &mut MotionInterpolator<Joints<f32>> {
+        if self.is_fast {
+            &mut self.fast_interpolator
+        } else {
+            &mut self.interpolator
+        }
+    }
 }
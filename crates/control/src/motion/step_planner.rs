@@ -5,7 +5,7 @@ use context_attribute::context;
 use framework::MainOutput;
 use linear_algebra::{Orientation2, Pose2};
 use types::{
-    motion_command::{MotionCommand, OrientationMode},
+    motion_command::{MotionCommand, OrientationMode, WalkSpeed},
     planned_path::PathSegment,
     step_plan::Step,
 };
@@ -25,6 +25,7 @@ pub struct CycleContext {
     max_step_size_backwards: Parameter<f32, "step_planner.max_step_size_backwards">,
     rotation_exponent: Parameter<f32, "step_planner.rotation_exponent">,
     translation_exponent: Parameter<f32, "step_planner.translation_exponent">,
+    slow_walk_step_size_factor: Parameter<f32, "step_planner.slow_walk_step_size_factor">,
 
     walk_return_offset: CyclerState<Step, "walk_return_offset">,
 }
@@ -41,12 +42,13 @@ impl StepPlanner {
     }
 
     pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
-        let (path, orientation_mode) = match context.motion_command {
+        let (path, orientation_mode, speed) = match context.motion_command {
             MotionCommand::Walk {
                 path,
                 orientation_mode,
+                speed,
                 ..
-            } => (path, orientation_mode),
+            } => (path, orientation_mode, speed),
             _ => {
                 return Ok(MainOutputs {
                     step_plan: Step {
@@ -109,11 +111,23 @@ impl StepPlanner {
             step = *injected_step;
         }
 
+        let (max_step_size, max_step_size_backwards) = match speed {
+            WalkSpeed::Normal => (*context.max_step_size, *context.max_step_size_backwards),
+            WalkSpeed::Slow => (
+                Step {
+                    forward: context.max_step_size.forward * context.slow_walk_step_size_factor,
+                    left: context.max_step_size.left * context.slow_walk_step_size_factor,
+                    turn: context.max_step_size.turn,
+                },
+                context.max_step_size_backwards * context.slow_walk_step_size_factor,
+            ),
+        };
+
         let step = compensate_with_return_offset(step, *context.walk_return_offset);
         let step = clamp_step_to_walk_volume(
             step,
-            context.max_step_size,
-            *context.max_step_size_backwards,
+            &max_step_size,
+            max_step_size_backwards,
             *context.translation_exponent,
             *context.rotation_exponent,
         );
@@ -12,6 +12,7 @@ use serde::{Deserialize, Serialize};
 use types::{
     cycle_time::CycleTime,
     joints::{arm::ArmJoints, body::BodyJoints, leg::LegJoints},
+    kick_step::FinishedKick,
     motion_command::{KickVariant, MotionCommand},
     motion_selection::{MotionSafeExits, MotionType},
     motor_commands::MotorCommands,
@@ -28,6 +29,7 @@ use self::{
     arms::SwingingArm,
     balancing::{step_adjustment, support_leg_gyro_balancing, swing_leg_foot_leveling},
     engine::{calculate_foot_to_robot, parabolic_return, parabolic_step},
+    foot_collision::avoid_foot_collision,
     foot_offsets::FootOffsets,
     kicking::apply_joint_overrides,
     walk_state::WalkState,
@@ -36,6 +38,7 @@ use self::{
 mod arms;
 mod balancing;
 mod engine;
+mod foot_collision;
 mod foot_offsets;
 mod kicking;
 mod walk_state;
@@ -139,6 +142,7 @@ pub struct CycleContext {
 
     motion_safe_exits: CyclerState<MotionSafeExits, "motion_safe_exits">,
     walk_return_offset: CyclerState<Step, "walk_return_offset">,
+    finished_kick: CyclerState<Option<FinishedKick>, "finished_kick">,
 
     motion_command: Input<MotionCommand, "motion_command">,
     robot_kinematics: Input<RobotKinematics, "robot_kinematics">,
@@ -245,6 +249,22 @@ impl WalkingEngine {
             self.end_step_phase();
         }
 
+        let is_step_finished_this_cycle = self.t.is_zero();
+        if let WalkState::Kicking(variant, kicking_side, kick_step_i, strength) = self.walk_state {
+            let number_of_kick_steps = match variant {
+                KickVariant::Forward => context.kick_steps.forward.len(),
+                KickVariant::Turn => context.kick_steps.turn.len(),
+                KickVariant::Side => context.kick_steps.side.len(),
+            };
+            if is_step_finished_this_cycle && kick_step_i + 1 == number_of_kick_steps {
+                *context.finished_kick = Some(FinishedKick {
+                    variant,
+                    kicking_side,
+                    strength,
+                });
+            }
+        }
+
         let left_arm = self.left_arm.next(
             self.left_foot,
             context.motion_command,
@@ -471,11 +491,15 @@ impl WalkingEngine {
                     config.inside_turn_ratio,
                 );
                 let forward_acceleration = requested_step.forward - last_step.forward;
-                self.current_step = Step {
-                    forward: last_step.forward
-                        + forward_acceleration.min(config.max_forward_acceleration),
-                    ..requested_step
-                };
+                self.current_step = avoid_foot_collision(
+                    Step {
+                        forward: last_step.forward
+                            + forward_acceleration.min(config.max_forward_acceleration),
+                        ..requested_step
+                    },
+                    next_swing_side,
+                    &config.foot_collision,
+                );
                 let (swing_foot_t0, support_foot_t0) = if next_swing_side == Side::Left {
                     (
                         Step {
@@ -529,10 +553,12 @@ impl WalkingEngine {
                     KickVariant::Side => &kick_steps.side,
                 };
                 let base_step = kick_steps[kick_step_i].base_step;
-                self.current_step = match kick_side {
+                let kick_step = match kick_side {
                     Side::Left => base_step,
                     Side::Right => base_step.mirrored(),
                 };
+                self.current_step =
+                    avoid_foot_collision(kick_step, swing_side.opposite(), &config.foot_collision);
                 self.planned_step_duration = config.base_step_duration;
                 self.swing_side = swing_side.opposite();
                 self.max_swing_foot_lift = config.base_foot_lift + config.additional_kick_foot_lift;
@@ -239,6 +239,11 @@ impl SwingingArm {
                 left_arm,
                 right_arm,
                 ..
+            }
+            | MotionCommand::InWalkKick {
+                left_arm,
+                right_arm,
+                ..
             } => match self.side {
                 Side::Left => *left_arm,
                 Side::Right => *right_arm,
@@ -0,0 +1,185 @@
+use nalgebra::{vector, Rotation2, Vector2};
+use types::{
+    parameters::FootCollisionParameters, robot_dimensions::RobotDimensions, step_plan::Step,
+    support_foot::Side,
+};
+
+const NUMBER_OF_BISECTION_STEPS: usize = 8;
+
+/// Adjusts a step such that the swing foot does not collide with the support foot at the end of
+/// the step.
+///
+/// The step is widened first, only if that is not sufficient the turn is reduced. The forward
+/// component is kept, otherwise in-walk-kick steps would lose their follow-through.
+pub fn avoid_foot_collision(
+    step: Step,
+    swing_side: Side,
+    parameters: &FootCollisionParameters,
+) -> Step {
+    if !parameters.enable || !are_feet_colliding(step, swing_side, parameters) {
+        return step;
+    }
+    let outwards = match swing_side {
+        Side::Left => 1.0,
+        Side::Right => -1.0,
+    };
+    let widened_step = |widening: f32| Step {
+        left: step.left + outwards * widening,
+        ..step
+    };
+    let maximally_widened_step = widened_step(parameters.maximum_widening);
+    if !are_feet_colliding(maximally_widened_step, swing_side, parameters) {
+        let widening =
+            bisect(|widening| are_feet_colliding(widened_step(widening), swing_side, parameters));
+        return widened_step(widening * parameters.maximum_widening);
+    }
+
+    let turned_step = |scale: f32| Step {
+        turn: step.turn * scale,
+        ..maximally_widened_step
+    };
+    let scale =
+        bisect(|scale| are_feet_colliding(turned_step(1.0 - scale), swing_side, parameters));
+    turned_step(1.0 - scale)
+}
+
+/// Finds the smallest value in [0, 1] for which `is_colliding` is false, assuming it is false at 1.
+fn bisect(is_colliding: impl Fn(f32) -> bool) -> f32 {
+    let mut colliding = 0.0;
+    let mut free = 1.0;
+    for _ in 0..NUMBER_OF_BISECTION_STEPS {
+        let value = (colliding + free) / 2.0;
+        if is_colliding(value) {
+            colliding = value;
+        } else {
+            free = value;
+        }
+    }
+    free
+}
+
+/// Compares the sole outlines of both feet at the end of the step, i.e. when the swing foot is
+/// placed at half the step ahead and the support foot at half the step behind.
+fn are_feet_colliding(step: Step, swing_side: Side, parameters: &FootCollisionParameters) -> bool {
+    let hip_offset = RobotDimensions::ROBOT_TO_LEFT_PELVIS.y();
+    let swing_side_sign = match swing_side {
+        Side::Left => 1.0,
+        Side::Right => -1.0,
+    };
+    let swing_foot = sole_corners(
+        vector![
+            step.forward / 2.0,
+            swing_side_sign * hip_offset + step.left / 2.0
+        ],
+        step.turn / 2.0,
+        parameters,
+    );
+    let support_foot = sole_corners(
+        vector![
+            -step.forward / 2.0,
+            -swing_side_sign * hip_offset - step.left / 2.0
+        ],
+        -step.turn / 2.0,
+        parameters,
+    );
+    are_polygons_overlapping(&swing_foot, &support_foot)
+}
+
+fn sole_corners(
+    ankle: Vector2<f32>,
+    orientation: f32,
+    parameters: &FootCollisionParameters,
+) -> [Vector2<f32>; 4] {
+    let rotation = Rotation2::new(orientation);
+    let center = ankle + rotation * vector![parameters.sole_center_offset, 0.0];
+    let half_length = parameters.foot_length / 2.0 + parameters.safety_margin;
+    let half_width = parameters.foot_width / 2.0 + parameters.safety_margin;
+    [
+        vector![half_length, half_width],
+        vector![-half_length, half_width],
+        vector![-half_length, -half_width],
+        vector![half_length, -half_width],
+    ]
+    .map(|corner| center + rotation * corner)
+}
+
+/// Separating axis test for convex polygons
+fn are_polygons_overlapping(first: &[Vector2<f32>; 4], second: &[Vector2<f32>; 4]) -> bool {
+    let edge_normals = |polygon: &[Vector2<f32>; 4]| {
+        (0..polygon.len()).map(|index| {
+            let edge = polygon[(index + 1) % polygon.len()] - polygon[index];
+            vector![-edge.y, edge.x]
+        })
+    };
+    edge_normals(first).chain(edge_normals(second)).all(|axis| {
+        let project = |polygon: &[Vector2<f32>; 4]| {
+            polygon.iter().map(|corner| corner.dot(&axis)).fold(
+                (f32::INFINITY, f32::NEG_INFINITY),
+                |(minimum, maximum), value| (minimum.min(value), maximum.max(value)),
+            )
+        };
+        let (first_minimum, first_maximum) = project(first);
+        let (second_minimum, second_maximum) = project(second);
+        first_minimum < second_maximum && second_minimum < first_maximum
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters() -> FootCollisionParameters {
+        FootCollisionParameters {
+            enable: true,
+            foot_length: 0.16,
+            foot_width: 0.08,
+            maximum_widening: 0.05,
+            safety_margin: 0.002,
+            sole_center_offset: 0.03,
+        }
+    }
+
+    #[test]
+    fn standing_feet_do_not_collide() {
+        assert!(!are_feet_colliding(Step::zero(), Side::Left, &parameters()));
+    }
+
+    #[test]
+    fn tight_outward_turn_is_widened() {
+        let step = Step {
+            forward: 0.0,
+            left: 0.0,
+            turn: 1.0,
+        };
+        assert!(are_feet_colliding(step, Side::Left, &parameters()));
+
+        let adjusted_step = avoid_foot_collision(step, Side::Left, &parameters());
+
+        assert!(!are_feet_colliding(
+            adjusted_step,
+            Side::Left,
+            &parameters()
+        ));
+        assert!(adjusted_step.left > 0.0);
+        assert_eq!(adjusted_step.turn, step.turn);
+    }
+
+    #[test]
+    fn inward_turn_is_reduced_if_widening_is_not_sufficient() {
+        let step = Step {
+            forward: 0.05,
+            left: 0.0,
+            turn: -1.0,
+        };
+
+        let adjusted_step = avoid_foot_collision(step, Side::Left, &parameters());
+
+        assert!(!are_feet_colliding(
+            adjusted_step,
+            Side::Left,
+            &parameters()
+        ));
+        assert!(adjusted_step.turn.abs() < step.turn.abs());
+        assert_eq!(adjusted_step.forward, step.forward);
+    }
+}
@@ -25,6 +25,7 @@ use types::{
 #[derive(Deserialize, Serialize)]
 pub struct ObstacleFilter {
     hypotheses: Vec<Hypothesis>,
+    next_hypothesis_id: usize,
     last_primary_state: PrimaryState,
 }
 
@@ -68,6 +69,7 @@ impl ObstacleFilter {
     pub fn new(_context: CreationContext) -> Result<Self> {
         Ok(Self {
             hypotheses: Vec::new(),
+            next_hypothesis_id: 0,
             last_primary_state: PrimaryState::Unstiff,
         })
     }
@@ -221,6 +223,7 @@ impl ObstacleFilter {
                     _ => panic!("Unexpected obstacle radius"),
                 };
                 Obstacle {
+                    id: Some(hypothesis.id),
                     position: hypothesis.state.mean.framed().as_point(),
                     kind: hypothesis.obstacle_kind,
                     radius_at_hip_height,
@@ -313,6 +316,7 @@ impl ObstacleFilter {
     ) {
         let initial_state = detected_position.inner.coords;
         let new_hypothesis = Hypothesis {
+            id: self.next_hypothesis_id,
             state: MultivariateNormalDistribution {
                 mean: initial_state,
                 covariance: initial_covariance,
@@ -321,6 +325,7 @@ impl ObstacleFilter {
             measurement_count: 1,
             last_update: detection_time,
         };
+        self.next_hypothesis_id += 1;
         self.hypotheses.push(new_hypothesis);
     }
 
@@ -364,7 +369,7 @@ impl ObstacleFilter {
     }
 }
 
-fn calculate_goal_post_positions(
+pub fn calculate_goal_post_positions(
     ground_to_field: Option<Isometry2<Ground, Field>>,
     field_dimensions: &FieldDimensions,
 ) -> Vec<Point2<Ground>> {
@@ -2,10 +2,12 @@ use color_eyre::Result;
 use context_attribute::context;
 use coordinate_systems::{Field, Robot};
 use framework::{AdditionalOutput, MainOutput};
-use linear_algebra::{Orientation2, Vector2};
+use linear_algebra::{vector, Orientation2, Vector2};
 use nalgebra::{Isometry2, Translation2};
 use serde::{Deserialize, Serialize};
 use types::{
+    kick_step::FinishedKick,
+    parameters::InWalkKicksParameters,
     robot_kinematics::RobotKinematics,
     support_foot::{Side, SupportFoot},
 };
@@ -23,12 +25,16 @@ pub struct CreationContext {}
 #[context]
 pub struct CycleContext {
     accumulated_odometry: AdditionalOutput<Isometry2<f32>, "accumulated_odometry">,
+    kick_odometry_correction: AdditionalOutput<Vector2<Robot>, "kick_odometry_correction">,
 
     robot_kinematics: Input<RobotKinematics, "robot_kinematics">,
     robot_orientation: Input<Orientation2<Field>, "robot_orientation">,
     support_foot: Input<SupportFoot, "support_foot">,
 
     odometry_scale_factor: Parameter<Vector2<Robot>, "odometry.odometry_scale_factor">,
+    in_walk_kicks: Parameter<InWalkKicksParameters, "in_walk_kicks">,
+
+    finished_kick: CyclerState<Option<FinishedKick>, "finished_kick">,
 }
 
 #[context]
@@ -56,8 +62,19 @@ impl Odometry {
             self.last_left_sole_to_right_sole,
         );
         self.last_left_sole_to_right_sole = left_sole_to_right_sole;
+        // the feet do not see the robot being pushed forward or slipping when kicking, the
+        // rotation is already covered by the orientation
+        let kick_correction = context
+            .finished_kick
+            .take()
+            .map_or(Vector2::zeros(), |kick| {
+                kick_odometry_correction(&kick, context.in_walk_kicks)
+            });
+        context
+            .kick_odometry_correction
+            .fill_if_subscribed(|| kick_correction);
         let corrected_offset_to_last_position =
-            offset_to_last_position.component_mul(*context.odometry_scale_factor);
+            offset_to_last_position.component_mul(*context.odometry_scale_factor) + kick_correction;
 
         let orientation_offset = self
             .last_orientation
@@ -90,3 +107,14 @@ fn calculate_offset_to_last_position(
         None => Vector2::zeros(),
     }
 }
+
+fn kick_odometry_correction(
+    kick: &FinishedKick,
+    in_walk_kicks: &InWalkKicksParameters,
+) -> Vector2<Robot> {
+    let offset = in_walk_kicks[kick.variant].odometry_offset * kick.strength;
+    match kick.kicking_side {
+        Side::Left => vector![offset.x, offset.y],
+        Side::Right => vector![offset.x, -offset.y],
+    }
+}
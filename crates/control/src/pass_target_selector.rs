@@ -0,0 +1,276 @@
+use std::{
+    collections::BTreeMap,
+    time::{Duration, SystemTime},
+};
+
+use color_eyre::Result;
+use context_attribute::context;
+use coordinate_systems::{Field, Ground};
+use framework::{MainOutput, PerceptionInput};
+use geometry::line_segment::LineSegment;
+use linear_algebra::{distance, Isometry2, Point2};
+use serde::{Deserialize, Serialize};
+use spl_network_messages::{PassAnnouncement, PlayerNumber};
+use types::{
+    cycle_time::CycleTime,
+    filtered_game_controller_state::FilteredGameControllerState,
+    filtered_game_state::FilteredGameState,
+    messages::IncomingMessage,
+    obstacles::{opponents, Obstacle},
+    parameters::{EndgameParameters, PassingParameters, ReducedFormationParameters},
+    players::Players,
+    roles::Role,
+    shot_selection::ShotSelection,
+    world_state::BallState,
+};
+
+use crate::role_assignment::{pick_role_with_penalties, Formation};
+
+#[derive(Deserialize, Serialize)]
+pub struct PassTargetSelector {
+    teammates: BTreeMap<PlayerNumber, Teammate>,
+    received_pass: Option<ReceivedPass>,
+}
+
+#[derive(Clone, Copy, Deserialize, Serialize)]
+struct Teammate {
+    position: Point2<Field>,
+    is_degraded: bool,
+    last_seen: SystemTime,
+}
+
+#[derive(Clone, Copy, Deserialize, Serialize)]
+struct ReceivedPass {
+    passer: PlayerNumber,
+    announcement: PassAnnouncement,
+    received_at: SystemTime,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    announced_roles: Input<Players<Option<Role>>, "announced_roles">,
+    announced_times_to_reach_kick_position:
+        Input<Players<Option<Duration>>, "announced_times_to_reach_kick_position">,
+    ball_state: Input<Option<BallState>, "ball_state?">,
+    cycle_time: Input<CycleTime, "cycle_time">,
+    filtered_game_controller_state:
+        Input<Option<FilteredGameControllerState>, "filtered_game_controller_state?">,
+    ground_to_field: Input<Option<Isometry2<Ground, Field>>, "ground_to_field?">,
+    keeper_incapable_players: Input<Players<Option<bool>>, "keeper_incapable_players">,
+    obstacles: Input<Vec<Obstacle>, "obstacles">,
+    role: Input<Role, "role">,
+    shot_selection: Input<Option<ShotSelection>, "shot_selection?">,
+    teammate_positions: Input<Vec<Point2<Field>>, "teammate_positions">,
+    unresponsive_players: Input<Players<bool>, "unresponsive_players">,
+    network_message: PerceptionInput<Option<IncomingMessage>, "SplNetwork", "filtered_message?">,
+
+    optional_roles: Parameter<Vec<Role>, "behavior.optional_roles">,
+    reduced_formations: Parameter<ReducedFormationParameters, "role_assignment.reduced_formations">,
+    striker_claim_hysteresis: Parameter<Duration, "role_assignment.striker_claim_hysteresis">,
+    parameters: Parameter<PassingParameters, "behavior.passing">,
+    endgame: Parameter<EndgameParameters, "behavior.endgame">,
+    teammate_matching_distance: Parameter<f32, "obstacle_filter.teammate_matching_distance">,
+    player_number: Parameter<PlayerNumber, "player_number">,
+
+    pass_announcement: CyclerState<Option<PassAnnouncement>, "pass_announcement">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub pass_intent: MainOutput<Option<PassAnnouncement>>,
+    pub announced_pass: MainOutput<Option<PassAnnouncement>>,
+}
+
+impl PassTargetSelector {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            teammates: BTreeMap::new(),
+            received_pass: None,
+        })
+    }
+
+    pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
+        let parameters = context.parameters;
+        let now = context.cycle_time.start_time;
+
+        for (receive_time, message) in context
+            .network_message
+            .persistent
+            .iter()
+            .flat_map(|(receive_time, messages)| {
+                messages.iter().map(move |message| (*receive_time, message))
+            })
+            .filter_map(|(receive_time, message)| match message {
+                Some(IncomingMessage::Spl(message)) => Some((receive_time, message)),
+                _ => None,
+            })
+            .filter(|(_, message)| message.player_number != *context.player_number)
+        {
+            self.teammates.insert(
+                message.player_number,
+                Teammate {
+                    position: message.pose.position(),
+                    is_degraded: message.is_degraded,
+                    last_seen: receive_time,
+                },
+            );
+            match message.pass {
+                Some(announcement) if announcement.receiver == *context.player_number => {
+                    self.received_pass = Some(ReceivedPass {
+                        passer: message.player_number,
+                        announcement,
+                        received_at: receive_time,
+                    });
+                }
+                _ if self
+                    .received_pass
+                    .is_some_and(|received_pass| received_pass.passer == message.player_number) =>
+                {
+                    self.received_pass = None;
+                }
+                _ => {}
+            }
+        }
+        self.teammates.retain(|_, teammate| {
+            now.duration_since(teammate.last_seen).unwrap_or_default() < parameters.teammate_timeout
+        });
+        if self.received_pass.is_some_and(|received_pass| {
+            now.duration_since(received_pass.received_at)
+                .unwrap_or_default()
+                > parameters.announcement_timeout
+        }) {
+            self.received_pass = None;
+        }
+
+        let pass_intent = if parameters.enable && *context.role == Role::Striker {
+            self.select_pass(&context)
+        } else {
+            None
+        };
+        *context.pass_announcement = pass_intent;
+
+        Ok(MainOutputs {
+            pass_intent: pass_intent.into(),
+            announced_pass: self
+                .received_pass
+                .map(|received_pass| received_pass.announcement)
+                .into(),
+        })
+    }
+
+    fn select_pass(&self, context: &CycleContext) -> Option<PassAnnouncement> {
+        let parameters = context.parameters;
+        let ground_to_field = context.ground_to_field?;
+        let ball = context.ball_state?.ball_in_field;
+        let game_controller_state = context.filtered_game_controller_state?;
+        if !matches!(
+            game_controller_state.game_state,
+            FilteredGameState::Playing {
+                ball_is_free: true,
+                ..
+            }
+        ) {
+            return None;
+        }
+        // a pass takes too long to set up at the end of a half, the striker shoots instead
+        if context.endgame.enable
+            && game_controller_state.is_half_ending_within(context.endgame.immediate_shot_duration)
+        {
+            return None;
+        }
+        let is_shot_blocked = context.shot_selection.is_some_and(|shot_selection| {
+            shot_selection.features.keeper_coverage >= parameters.blocked_shot_coverage
+        });
+        if !is_shot_blocked {
+            return None;
+        }
+
+        let opponents: Vec<Point2<Field>> = opponents(
+            context.obstacles,
+            context.teammate_positions,
+            *ground_to_field,
+            *context.teammate_matching_distance,
+        )
+        .map(|obstacle| *ground_to_field * obstacle.position)
+        .collect();
+
+        self.teammates
+            .iter()
+            // a degraded teammate stands still and would not get to the ball in time
+            .filter(|(_, teammate)| !teammate.is_degraded)
+            .filter(|(player_number, _)| {
+                pick_role_with_penalties(
+                    **player_number,
+                    &game_controller_state.penalties,
+                    *context.player_number,
+                    Formation {
+                        optional_roles: context.optional_roles,
+                        reduced_formations: if context.reduced_formations.enable {
+                            &context.reduced_formations.formations
+                        } else {
+                            &[]
+                        },
+                        keeper_incapable_players: context.keeper_incapable_players,
+                        unresponsive_players: context.unresponsive_players,
+                        times_to_reach_kick_position: context
+                            .announced_times_to_reach_kick_position,
+                        current_roles: context.announced_roles,
+                        claim_hysteresis: *context.striker_claim_hysteresis,
+                    },
+                ) == Role::StrikerSupporter
+            })
+            .map(|(player_number, teammate)| PassAnnouncement {
+                receiver: *player_number,
+                target: teammate.position,
+            })
+            .filter(|pass| {
+                let pass_distance = distance(ball, pass.target);
+                (parameters.minimum_pass_distance..=parameters.maximum_pass_distance)
+                    .contains(&pass_distance)
+                    && is_lane_free(ball, pass.target, &opponents, parameters.lane_clearance)
+            })
+            .max_by(|left, right| pass_score(ball, left).total_cmp(&pass_score(ball, right)))
+    }
+}
+
+/// Among the passes with a free lane, the one gaining the most ground towards the opponent goal is
+/// the best
+fn pass_score(ball: Point2<Field>, pass: &PassAnnouncement) -> f32 {
+    pass.target.x() - ball.x()
+}
+
+/// An opponent marking the receiver blocks the lane as well, it would intercept the ball right
+/// before the receiver gets it.
+fn is_lane_free(
+    ball: Point2<Field>,
+    target: Point2<Field>,
+    opponents: &[Point2<Field>],
+    lane_clearance: f32,
+) -> bool {
+    let lane = LineSegment::new(ball, target);
+    opponents
+        .iter()
+        .all(|opponent| lane.shortest_distance_to_point(*opponent) > lane_clearance)
+}
+
+#[cfg(test)]
+mod tests {
+    use linear_algebra::point;
+
+    use super::*;
+
+    #[test]
+    fn opponents_block_the_lane_up_to_the_receiver() {
+        let ball = point![0.0, 0.0];
+        let receiver = point![3.0, 0.0];
+
+        assert!(is_lane_free(ball, receiver, &[point![1.5, 1.0]], 0.4));
+        assert!(!is_lane_free(ball, receiver, &[point![1.5, 0.2]], 0.4));
+        // an opponent marking the receiver intercepts the pass
+        assert!(!is_lane_free(ball, receiver, &[point![3.2, 0.1]], 0.4));
+    }
+}
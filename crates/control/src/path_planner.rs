@@ -14,7 +14,10 @@ use types::{
     rule_obstacles::RuleObstacle,
 };
 
-use crate::a_star::{a_star_search, DynamicMap};
+use crate::{
+    a_star::{a_star_search, DynamicMap},
+    obstacle_filter::calculate_goal_post_positions,
+};
 
 #[derive(Debug, Clone)]
 pub struct PathNode {
@@ -224,6 +227,24 @@ impl PathPlanner {
         );
     }
 
+    /// Adds the four goal posts at their positions known from the field dimensions, independent of
+    /// whether they are currently perceived.
+    pub fn with_goal_posts(
+        &mut self,
+        field_to_ground: Isometry2<Field, Ground>,
+        field_dimensions: &FieldDimensions,
+        own_robot_radius: f32,
+    ) {
+        let goal_post_radius = field_dimensions.goal_post_diameter / 2.0;
+        let goal_posts =
+            calculate_goal_post_positions(Some(field_to_ground.inverse()), field_dimensions);
+
+        self.obstacles
+            .extend(goal_posts.into_iter().map(|goal_post| {
+                PathObstacle::from(Circle::new(goal_post, goal_post_radius + own_robot_radius))
+            }));
+    }
+
     fn generate_start_destination_tangents(&mut self) {
         let direct_path = LineSegment(self.nodes[0].position, self.nodes[1].position);
         let direct_path_blocked = self
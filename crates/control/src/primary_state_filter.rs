@@ -27,6 +27,7 @@ pub struct CycleContext {
         Input<Option<FilteredGameControllerState>, "filtered_game_controller_state?">,
 
     player_number: Parameter<PlayerNumber, "player_number">,
+    demo_mode: Parameter<bool, "behavior.demo.enable">,
 
     hardware_interface: HardwareInterface,
 }
@@ -76,6 +77,36 @@ impl PrimaryStateFilter {
 
             (PrimaryState::Initial, _, _, true, _) => PrimaryState::Calibration,
 
+            // demo transitions ignore the GameController and penalties
+            (last_primary_state, _, is_chest_button_pressed, _, _) if *context.demo_mode => {
+                let is_toggled =
+                    is_chest_button_pressed || context.buttons.is_head_middle_button_tapped;
+                match (last_primary_state, is_toggled) {
+                    // releasing the head buttons after unstiffing must not start the robot
+                    (PrimaryState::Unstiff, _) if is_chest_button_pressed => PrimaryState::Initial,
+                    (PrimaryState::Unstiff, _) => PrimaryState::Unstiff,
+                    (PrimaryState::Playing, true) => PrimaryState::Initial,
+                    (_, true) | (PrimaryState::Playing, false) => PrimaryState::Playing,
+                    (_, false) => PrimaryState::Initial,
+                }
+            }
+
+            // without GameController, the assistant referee penalizes and unpenalizes with the
+            // chest button
+            (
+                last_primary_state @ (PrimaryState::Playing | PrimaryState::Penalized),
+                _,
+                is_chest_button_pressed,
+                _,
+                Some(FilteredGameControllerState {
+                    is_fallback: true, ..
+                }),
+            ) => match (last_primary_state, is_chest_button_pressed) {
+                (PrimaryState::Playing, true) => PrimaryState::Penalized,
+                (PrimaryState::Penalized, true) => PrimaryState::Playing,
+                (last_primary_state, _) => last_primary_state,
+            },
+
             // GameController transitions (entering listening mode and staying within)
             (PrimaryState::Unstiff, _, true, _, Some(filtered_game_controller_state))
             | (PrimaryState::Finished, _, true, _, Some(filtered_game_controller_state)) => {
@@ -128,6 +159,7 @@ impl PrimaryStateFilter {
         match game_state {
             FilteredGameState::Ready { .. } => PrimaryState::Ready,
             FilteredGameState::Initial => PrimaryState::Initial,
+            FilteredGameState::Standby => PrimaryState::Standby,
             FilteredGameState::Set => PrimaryState::Set,
             FilteredGameState::Playing { .. } => PrimaryState::Playing,
             FilteredGameState::Finished => PrimaryState::Finished,
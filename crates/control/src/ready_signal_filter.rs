@@ -0,0 +1,296 @@
+use std::time::{Duration, SystemTime};
+
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use context_attribute::context;
+use framework::{MainOutput, PerceptionInput};
+use spl_network_messages::{GameState, PlayerNumber};
+use types::{
+    cycle_time::CycleTime,
+    game_controller_state::GameControllerState,
+    messages::IncomingMessage,
+    parameters::ReadySignalFilterParameters,
+    players::Players,
+    ready_signal::{
+        FilteredReadySignal, ReadySignalDecision, ReadySignalDecisionSource, ReadySignalVote,
+    },
+};
+
+#[derive(Deserialize, Serialize)]
+pub struct ReadySignalFilter {
+    votes: Players<Option<ReadySignalVote>>,
+    majority_since: Option<SystemTime>,
+    last_decision: Option<ReadySignalDecision>,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    cycle_time: Input<CycleTime, "cycle_time">,
+    game_controller_state: Input<Option<GameControllerState>, "game_controller_state?">,
+    network_message: PerceptionInput<Option<IncomingMessage>, "SplNetwork", "filtered_message?">,
+
+    parameters: Parameter<ReadySignalFilterParameters, "ready_signal_filter">,
+    player_number: Parameter<PlayerNumber, "player_number">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub filtered_ready_signal: MainOutput<FilteredReadySignal>,
+}
+
+impl ReadySignalFilter {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            votes: Default::default(),
+            majority_since: None,
+            last_decision: None,
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        let now = context.cycle_time.start_time;
+        let parameters = context.parameters;
+
+        let is_in_standby = context
+            .game_controller_state
+            .is_some_and(|state| state.game_state == GameState::Standby);
+        if !is_in_standby {
+            self.votes = Default::default();
+            self.majority_since = None;
+            return Ok(MainOutputs {
+                filtered_ready_signal: FilteredReadySignal {
+                    last_decision: self.last_decision,
+                    ..Default::default()
+                }
+                .into(),
+            });
+        }
+
+        let own_confidence = parameters.injected_detection_confidence;
+        if let Some(confidence) = own_confidence {
+            self.votes[*context.player_number] = Some(ReadySignalVote {
+                confidence,
+                time: now,
+            });
+        }
+        for (time, message) in context
+            .network_message
+            .persistent
+            .iter()
+            .flat_map(|(time, messages)| messages.iter().map(move |message| (*time, message)))
+            .filter_map(|(time, message)| match message {
+                Some(IncomingMessage::Spl(message)) => Some((time, message)),
+                _ => None,
+            })
+        {
+            if let Some(confidence) = message.ready_signal_confidence {
+                self.votes[message.player_number] = Some(ReadySignalVote { confidence, time });
+            }
+        }
+        let expired_voters: Vec<_> = self
+            .votes
+            .iter()
+            .filter(|(_, vote)| {
+                vote.is_some_and(|vote| elapsed_since(now, vote.time) > parameters.vote_timeout)
+            })
+            .map(|(player, _)| player)
+            .collect();
+        for player in expired_voters {
+            self.votes[player] = None;
+        }
+
+        let number_of_votes = self.votes.iter().filter(|(_, vote)| vote.is_some()).count();
+        let number_of_positive_votes = self
+            .votes
+            .iter()
+            .filter_map(|(_, vote)| *vote)
+            .filter(|vote| vote.confidence >= parameters.confidence_threshold)
+            .count();
+        let has_majority =
+            number_of_positive_votes > 0 && 2 * number_of_positive_votes > number_of_votes;
+        self.majority_since = if has_majority {
+            Some(self.majority_since.unwrap_or(now))
+        } else {
+            None
+        };
+
+        // walking into Ready without the signal is penalized, a majority of only few votes has
+        // to hold for a while before the team trusts it
+        let source = if has_majority
+            && number_of_positive_votes >= parameters.minimum_number_of_positive_votes
+        {
+            Some(ReadySignalDecisionSource::Majority)
+        } else if self.majority_since.is_some_and(|majority_since| {
+            elapsed_since(now, majority_since) >= parameters.fallback_duration
+        }) {
+            Some(ReadySignalDecisionSource::FallbackTimer)
+        } else {
+            None
+        };
+        let decision = source.map(|source| ReadySignalDecision {
+            source,
+            time: now,
+            number_of_positive_votes,
+            number_of_votes,
+        });
+        let filtered_ready_signal = FilteredReadySignal {
+            own_confidence,
+            votes: self.votes,
+            number_of_positive_votes,
+            majority_since: self.majority_since,
+            is_detected: decision.is_some(),
+            last_decision: decision.or(self.last_decision),
+        };
+        if decision.is_some() {
+            // a new decision has to be built from fresh votes
+            self.last_decision = decision;
+            self.votes = Default::default();
+            self.majority_since = None;
+        }
+
+        Ok(MainOutputs {
+            filtered_ready_signal: filtered_ready_signal.into(),
+        })
+    }
+}
+
+fn elapsed_since(now: SystemTime, time: SystemTime) -> Duration {
+    now.duration_since(time).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use node_testing::{cycle_time, time, PerceptionInputBuilder, TestParameters};
+    use spl_network_messages::{GamePhase, Half, HulkMessage, Team};
+
+    use super::*;
+
+    fn standby() -> GameControllerState {
+        GameControllerState {
+            game_state: GameState::Standby,
+            game_phase: GamePhase::Normal,
+            kicking_team: Team::Hulks,
+            raw_kicking_team: Team::Hulks,
+            last_game_state_change: time(Duration::ZERO),
+            penalties: Players::default(),
+            remaining_penalty_durations: Players::default(),
+            remaining_amount_of_messages: 1200,
+            sub_state: None,
+            hulks_team_is_home_after_coin_toss: true,
+            half: Half::First,
+            remaining_time_in_half: Duration::from_secs(600),
+            hulks_score: 0,
+            opponent_score: 0,
+            staleness: Duration::ZERO,
+            is_fallback: false,
+        }
+    }
+
+    fn vote(player_number: PlayerNumber, confidence: f32) -> Option<IncomingMessage> {
+        Some(IncomingMessage::Spl(HulkMessage {
+            player_number,
+            ready_signal_confidence: Some(confidence),
+            ..Default::default()
+        }))
+    }
+
+    fn decisions(
+        votes: &[(u64, Option<IncomingMessage>)],
+        cycles: &[u64],
+    ) -> Vec<Option<ReadySignalDecisionSource>> {
+        let parameters: ReadySignalFilterParameters = TestParameters::load_default()
+            .unwrap()
+            .get("ready_signal_filter")
+            .unwrap();
+        let game_controller_state = standby();
+        let mut node = ReadySignalFilter::new(CreationContext::new()).unwrap();
+
+        cycles
+            .iter()
+            .map(|&now| {
+                let network_message = votes.iter().filter(|(at, _)| *at <= now).fold(
+                    PerceptionInputBuilder::default(),
+                    |builder, (at, message)| {
+                        builder.persistent(time(Duration::from_millis(*at)), message.clone())
+                    },
+                );
+                let cycle_time = cycle_time(Duration::from_millis(now), Duration::from_millis(12));
+                let outputs = node
+                    .cycle(CycleContext::new(
+                        &cycle_time,
+                        Some(&game_controller_state),
+                        network_message.build_optional(),
+                        &parameters,
+                        &PlayerNumber::One,
+                    ))
+                    .unwrap();
+                let filtered_ready_signal = outputs.filtered_ready_signal.value;
+                filtered_ready_signal
+                    .is_detected
+                    .then(|| filtered_ready_signal.last_decision.unwrap().source)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn majority_of_positive_votes_decides() {
+        let votes = [
+            (0, vote(PlayerNumber::Two, 0.9)),
+            (0, vote(PlayerNumber::Three, 0.2)),
+            (100, vote(PlayerNumber::Four, 0.8)),
+        ];
+
+        let decisions = decisions(&votes, &[0, 100]);
+
+        assert_eq!(decisions, [None, Some(ReadySignalDecisionSource::Majority)]);
+    }
+
+    #[test]
+    fn explicit_negative_votes_outweigh_a_single_detection() {
+        // the negative votes are repeated such that they do not time out
+        let votes: Vec<_> = (0..8)
+            .flat_map(|second| {
+                let at = second * 1000;
+                [
+                    (at, vote(PlayerNumber::Two, 0.9)),
+                    (at, vote(PlayerNumber::Three, 0.1)),
+                    (at, vote(PlayerNumber::Four, 0.1)),
+                ]
+            })
+            .collect();
+
+        let decisions = decisions(&votes, &[0, 3000, 7000]);
+
+        assert_eq!(decisions, [None, None, None]);
+    }
+
+    #[test]
+    fn single_vote_decides_only_after_fallback_duration() {
+        let votes: Vec<_> = (0..7)
+            .map(|second| (second * 1000, vote(PlayerNumber::Two, 0.9)))
+            .collect();
+
+        let decisions = decisions(&votes, &[0, 3000, 6000]);
+
+        assert_eq!(
+            decisions,
+            [None, None, Some(ReadySignalDecisionSource::FallbackTimer)]
+        );
+    }
+
+    #[test]
+    fn votes_time_out_without_refresh() {
+        let votes = [(0, vote(PlayerNumber::Two, 0.9))];
+
+        let decisions = decisions(&votes, &[0, 4000, 6000]);
+
+        assert_eq!(decisions, [None, None, None]);
+    }
+}
@@ -0,0 +1,161 @@
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use context_attribute::context;
+use framework::MainOutput;
+use types::{
+    parameters::RobotConditionParameters, robot_condition::RobotCondition, sensor_data::SensorData,
+};
+
+#[derive(Deserialize, Serialize)]
+pub struct RobotConditionFilter {
+    robot_condition: RobotCondition,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    sensor_data: Input<SensorData, "sensor_data">,
+
+    parameters: Parameter<RobotConditionParameters, "robot_condition">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub robot_condition: MainOutput<RobotCondition>,
+}
+
+impl RobotConditionFilter {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            robot_condition: RobotCondition::default(),
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        let parameters = context.parameters;
+        if !parameters.enable {
+            self.robot_condition = RobotCondition::default();
+            return Ok(MainOutputs {
+                robot_condition: self.robot_condition.into(),
+            });
+        }
+
+        // both conditions only recover with some margin, otherwise the behavior would toggle
+        // between walking and standing around the threshold
+        let is_battery_low = context.sensor_data.battery.is_some_and(|battery| {
+            let minimum_charge = if self.robot_condition.is_battery_low {
+                parameters.minimum_battery_charge + parameters.battery_charge_hysteresis
+            } else {
+                parameters.minimum_battery_charge
+            };
+            battery.charge < minimum_charge
+        });
+        let maximum_temperature = context
+            .sensor_data
+            .temperature_sensors
+            .into_iter()
+            .fold(0.0, f32::max);
+        let maximum_joint_temperature = if self.robot_condition.are_joints_hot {
+            parameters.maximum_joint_temperature - parameters.joint_temperature_hysteresis
+        } else {
+            parameters.maximum_joint_temperature
+        };
+        let are_joints_hot = maximum_temperature > maximum_joint_temperature;
+
+        self.robot_condition = RobotCondition {
+            is_battery_low,
+            are_joints_hot,
+        };
+        Ok(MainOutputs {
+            robot_condition: self.robot_condition.into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use types::sensor_data::Battery;
+
+    use super::*;
+
+    fn parameters() -> RobotConditionParameters {
+        RobotConditionParameters {
+            enable: true,
+            minimum_battery_charge: 0.2,
+            battery_charge_hysteresis: 0.05,
+            maximum_joint_temperature: 70.0,
+            joint_temperature_hysteresis: 5.0,
+        }
+    }
+
+    fn conditions(sensor_data: impl IntoIterator<Item = SensorData>) -> Vec<RobotCondition> {
+        let parameters = parameters();
+        let mut filter = RobotConditionFilter::new(CreationContext::new()).unwrap();
+        sensor_data
+            .into_iter()
+            .map(|sensor_data| {
+                filter
+                    .cycle(CycleContext::new(&sensor_data, &parameters))
+                    .unwrap()
+                    .robot_condition
+                    .value
+            })
+            .collect()
+    }
+
+    fn with_battery_charge(charge: f32) -> SensorData {
+        SensorData {
+            battery: Some(Battery {
+                charge,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn with_joint_temperature(temperature: f32) -> SensorData {
+        let mut sensor_data = SensorData::default();
+        sensor_data.temperature_sensors.left_leg.knee_pitch = temperature;
+        sensor_data
+    }
+
+    #[test]
+    fn low_battery_recovers_only_above_the_hysteresis() {
+        let is_battery_low: Vec<_> = conditions(
+            [0.25, 0.19, 0.22, 0.26]
+                .into_iter()
+                .map(with_battery_charge),
+        )
+        .into_iter()
+        .map(|condition| condition.is_battery_low)
+        .collect();
+
+        assert_eq!(is_battery_low, [false, true, true, false]);
+    }
+
+    #[test]
+    fn hot_joints_recover_only_below_the_hysteresis() {
+        let are_joints_hot: Vec<_> = conditions(
+            [65.0, 71.0, 67.0, 64.0]
+                .into_iter()
+                .map(with_joint_temperature),
+        )
+        .into_iter()
+        .map(|condition| condition.are_joints_hot)
+        .collect();
+
+        assert_eq!(are_joints_hot, [false, true, true, false]);
+    }
+
+    #[test]
+    fn missing_battery_state_is_not_degraded() {
+        assert_eq!(
+            conditions([SensorData::default()]),
+            [RobotCondition::default()]
+        );
+    }
+}
@@ -1,41 +1,86 @@
 use std::time::{Duration, SystemTime};
 
-use color_eyre::{eyre::WrapErr, Result};
+use color_eyre::Result;
 use serde::{Deserialize, Serialize};
 
 use context_attribute::context;
 use coordinate_systems::{Field, Ground};
-use framework::{MainOutput, PerceptionInput};
+use framework::{MainOutput, PerceptionInput, PersistentState};
 use hardware::NetworkInterface;
-use linear_algebra::{Isometry2, Point2, Vector};
+use linear_algebra::{distance, Isometry2, Point2, Pose2, Vector};
 use spl_network_messages::{
-    GameControllerReturnMessage, GamePhase, HulkMessage, Penalty, PlayerNumber, SubState, Team,
+    GameControllerStateDigest, GamePhase, GoalMouthClaim, HulkMessage, PassAnnouncement, Penalty,
+    PlayerNumber, StrikerIntent, SubState, Team,
 };
 use types::{
+    acoustic_signals::HeardAcousticSignals,
     ball_position::BallPosition,
     cycle_time::CycleTime,
     fall_state::FallState,
     field_dimensions::FieldDimensions,
     filtered_game_controller_state::FilteredGameControllerState,
+    game_controller_state::MessageBudgetStatus,
     initial_pose::InitialPose,
     messages::{IncomingMessage, OutgoingMessage},
-    parameters::SplNetworkParameters,
+    parameters::{
+        ReadyRoleAssignmentParameters, ReadySignalFilterParameters, ReducedFormationParameters,
+        RobotCapabilitiesParameters, SplNetworkParameters,
+    },
     players::Players,
     primary_state::PrimaryState,
+    ready_signal::FilteredReadySignal,
+    robot_condition::RobotCondition,
     roles::Role,
 };
 
-use crate::localization::generate_initial_pose;
+use crate::{hungarian, localization::generate_initial_pose};
 
 #[derive(Deserialize, Serialize)]
 pub struct RoleAssignment {
     last_received_spl_striker_message: Option<SystemTime>,
-    last_system_time_transmitted_game_controller_return_message: Option<SystemTime>,
     last_transmitted_spl_striker_message: Option<SystemTime>,
+    last_transmitted_search_sector: Option<u8>,
+    last_transmitted_pass_announcement: Option<PassAnnouncement>,
+    last_transmitted_yields_blocked_ball: bool,
+    last_transmitted_is_degraded: bool,
+    last_transmitted_ready_signal_vote: bool,
+    last_transmitted_goal_mouth_claim: Option<GoalMouthClaim>,
+    last_transmitted_striker_intent: Option<StrikerIntent>,
+    last_transmitted_releases_striker: bool,
+    last_transmitted_game_controller_state: Option<(SystemTime, GameControllerStateDigest)>,
+    /// Latest time a teammate relayed the GameController state
+    last_received_game_controller_state: Option<SystemTime>,
     role: Role,
     role_initialized: bool,
     team_ball: Option<BallPosition<Field>>,
     last_time_keeper_penalized: Option<SystemTime>,
+    last_known_poses: Players<Option<Pose2<Field>>>,
+    ready_player_roles: Option<Players<Role>>,
+    ready_since: Option<SystemTime>,
+    /// Poses the robots sent during the current Ready state, the input of the Ready assignment
+    ready_poses: Players<Option<Pose2<Field>>>,
+    keeper_incapable_players: Players<Option<bool>>,
+    last_message_times: Players<Option<SystemTime>>,
+    last_known_roles: Players<Option<Role>>,
+    last_known_times_to_reach_kick_position: Players<Option<Duration>>,
+    last_primary_state: PrimaryState,
+}
+
+/// The roles distributed besides keeper and striker and who is available to take them
+#[derive(Clone, Copy)]
+pub struct Formation<'a> {
+    pub optional_roles: &'a [Role],
+    /// Replace the optional roles if present for the number of available robots
+    pub reduced_formations: &'a [Vec<Role>],
+    pub keeper_incapable_players: &'a Players<Option<bool>>,
+    pub unresponsive_players: &'a Players<bool>,
+    /// Latest announced time of each robot to reach the ball, the fastest one besides the striker
+    /// supports it
+    pub times_to_reach_kick_position: &'a Players<Option<Duration>>,
+    pub current_roles: &'a Players<Option<Role>>,
+    /// Time to the ball another robot has to be faster by to take over the striker or the
+    /// supporter role
+    pub claim_hysteresis: Duration,
 }
 
 #[context]
@@ -50,8 +95,19 @@ pub struct CycleContext {
     primary_state: Input<PrimaryState, "primary_state">,
     ground_to_field: Input<Option<Isometry2<Ground, Field>>, "ground_to_field?">,
     cycle_time: Input<CycleTime, "cycle_time">,
+    heard_acoustic_signals: Input<HeardAcousticSignals, "heard_acoustic_signals">,
+    message_budget_status: Input<Option<MessageBudgetStatus>, "message_budget_status?">,
+    game_controller_state_digest:
+        Input<Option<GameControllerStateDigest>, "game_controller_state_digest?">,
+    robot_condition: Input<RobotCondition, "robot_condition">,
+    filtered_ready_signal: Input<FilteredReadySignal, "filtered_ready_signal">,
     network_message: PerceptionInput<Option<IncomingMessage>, "SplNetwork", "filtered_message?">,
     time_to_reach_kick_position: CyclerState<Duration, "time_to_reach_kick_position">,
+    search_sector: CyclerState<Option<u8>, "search_sector">,
+    pass_announcement: CyclerState<Option<PassAnnouncement>, "pass_announcement">,
+    yields_blocked_ball: CyclerState<bool, "yields_blocked_ball">,
+    goal_mouth_claim: CyclerState<Option<GoalMouthClaim>, "goal_mouth_claim">,
+    striker_intent: CyclerState<Option<StrikerIntent>, "striker_intent">,
 
     field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
     forced_role: Parameter<Option<Role>, "role_assignment.forced_role?">,
@@ -61,6 +117,16 @@ pub struct CycleContext {
     optional_roles: Parameter<Vec<Role>, "behavior.optional_roles">,
     player_number: Parameter<PlayerNumber, "player_number">,
     spl_network: Parameter<SplNetworkParameters, "spl_network">,
+    striker_claim_hysteresis: Parameter<Duration, "role_assignment.striker_claim_hysteresis">,
+    release_striker_when_fallen: Parameter<bool, "role_assignment.release_striker_when_fallen">,
+    fallen_time_to_reach_kick_position:
+        Parameter<Duration, "role_assignment.fallen_time_to_reach_kick_position">,
+    teammate_pose_timeout: Parameter<Duration, "role_assignment.teammate_pose_timeout">,
+    ready_role_assignment:
+        Parameter<ReadyRoleAssignmentParameters, "role_assignment.ready_role_assignment">,
+    reduced_formations: Parameter<ReducedFormationParameters, "role_assignment.reduced_formations">,
+    robot_capabilities: Parameter<RobotCapabilitiesParameters, "robot_capabilities">,
+    ready_signal_filter: Parameter<ReadySignalFilterParameters, "ready_signal_filter">,
 
     hardware: HardwareInterface,
 }
@@ -71,18 +137,44 @@ pub struct MainOutputs {
     pub team_ball: MainOutput<Option<BallPosition<Field>>>,
     pub network_robot_obstacles: MainOutput<Vec<Point2<Ground>>>,
     pub role: MainOutput<Role>,
+    pub teammate_positions: MainOutput<Vec<Point2<Field>>>,
+    /// Positions of the teammates announcing a defender role
+    pub teammate_defender_positions: MainOutput<Vec<Point2<Field>>>,
+    pub keeper_incapable_players: MainOutput<Players<Option<bool>>>,
+    /// Teammates silent for longer than the unresponsive timeout
+    pub unresponsive_players: MainOutput<Players<bool>>,
+    pub announced_roles: MainOutput<Players<Option<Role>>>,
+    pub announced_times_to_reach_kick_position: MainOutput<Players<Option<Duration>>>,
 }
 
 impl RoleAssignment {
     pub fn new(_context: CreationContext) -> Result<Self> {
         Ok(Self {
             last_received_spl_striker_message: None,
-            last_system_time_transmitted_game_controller_return_message: None,
             last_transmitted_spl_striker_message: None,
+            last_transmitted_search_sector: None,
+            last_transmitted_pass_announcement: None,
+            last_transmitted_yields_blocked_ball: false,
+            last_transmitted_is_degraded: false,
+            last_transmitted_ready_signal_vote: false,
+            last_transmitted_goal_mouth_claim: None,
+            last_transmitted_striker_intent: None,
+            last_transmitted_releases_striker: false,
+            last_transmitted_game_controller_state: None,
+            last_received_game_controller_state: None,
             role: Role::Striker,
             role_initialized: false,
             team_ball: None,
             last_time_keeper_penalized: None,
+            last_known_poses: Default::default(),
+            ready_player_roles: None,
+            ready_since: None,
+            ready_poses: Default::default(),
+            keeper_incapable_players: Default::default(),
+            last_message_times: Default::default(),
+            last_known_roles: Default::default(),
+            last_known_times_to_reach_kick_position: Default::default(),
+            last_primary_state: PrimaryState::Unstiff,
         })
     }
 
@@ -90,13 +182,15 @@ impl RoleAssignment {
         let cycle_start_time = context.cycle_time.start_time;
         let primary_state = *context.primary_state;
         let mut role = self.role;
+        self.keeper_incapable_players[*context.player_number] =
+            Some(!context.robot_capabilities.is_keeper_capable());
 
         let ground_to_field =
             context
                 .ground_to_field
                 .copied()
                 .unwrap_or_else(|| match context.primary_state {
-                    PrimaryState::Initial => generate_initial_pose(
+                    PrimaryState::Initial | PrimaryState::Standby => generate_initial_pose(
                         &context.initial_poses[*context.player_number],
                         context.field_dimensions,
                     )
@@ -104,6 +198,15 @@ impl RoleAssignment {
                     _ => Default::default(),
                 });
 
+        if !matches!(primary_state, PrimaryState::Ready | PrimaryState::Set) {
+            self.ready_player_roles = None;
+            self.ready_since = None;
+        }
+        if primary_state == PrimaryState::Ready && self.last_primary_state != PrimaryState::Ready {
+            self.ready_since = Some(cycle_start_time);
+            self.ready_poses = Default::default();
+        }
+
         if !self.role_initialized
             || primary_state == PrimaryState::Ready
             || primary_state == PrimaryState::Set
@@ -132,26 +235,73 @@ impl RoleAssignment {
                     player_roles[striker] = Role::Striker;
                 }
             }
+
+            // every robot sends its pose at the beginning of Ready, once the poses are exchanged
+            // all robots solve the same assignment from them. It is kept for the whole Ready and
+            // Set state such that no one changes their mind.
+            let are_ready_poses_exchanged = self.ready_since.map_or(true, |ready_since| {
+                cycle_start_time
+                    .duration_since(ready_since)
+                    .is_ok_and(|duration| {
+                        duration >= context.ready_role_assignment.pose_exchange_duration
+                    })
+            });
+            if context.ready_role_assignment.enable
+                && matches!(primary_state, PrimaryState::Ready | PrimaryState::Set)
+                && are_ready_poses_exchanged
+            {
+                // robots whose pose did not arrive during Ready are assumed where they were last
+                let mut poses = self.last_known_poses;
+                for (player, pose) in self.ready_poses.iter() {
+                    if pose.is_some() {
+                        poses[player] = *pose;
+                    }
+                }
+                player_roles = *self.ready_player_roles.get_or_insert_with(|| {
+                    assign_ready_roles(
+                        player_roles,
+                        context
+                            .filtered_game_controller_state
+                            .map(|state| &state.penalties),
+                        &poses,
+                        context.initial_poses,
+                        context.field_dimensions,
+                        context.ready_role_assignment,
+                    )
+                });
+            }
             role = player_roles[*context.player_number];
 
             self.role_initialized = true;
             self.last_received_spl_striker_message = Some(cycle_start_time);
+            self.last_message_times = Players {
+                one: Some(cycle_start_time),
+                two: Some(cycle_start_time),
+                three: Some(cycle_start_time),
+                four: Some(cycle_start_time),
+                five: Some(cycle_start_time),
+                six: Some(cycle_start_time),
+                seven: Some(cycle_start_time),
+            };
             self.team_ball = None;
         }
 
-        let send_game_controller_return_message = self
-            .last_system_time_transmitted_game_controller_return_message
-            .is_none()
-            || cycle_start_time.duration_since(
-                self.last_system_time_transmitted_game_controller_return_message
-                    .unwrap(),
-            )? > context.spl_network.game_controller_return_message_interval;
-
         let mut send_spl_striker_message = self.last_transmitted_spl_striker_message.is_none()
             || cycle_start_time
                 .duration_since(self.last_transmitted_spl_striker_message.unwrap())?
                 > context.spl_network.spl_striker_message_send_interval;
 
+        // a striker chirp keeps the team from timing out while the network is down
+        if let Some(last_striker_chirp) = context.heard_acoustic_signals.last_striker {
+            if role != Role::Striker
+                && self
+                    .last_received_spl_striker_message
+                    .map_or(true, |last_received| last_received < last_striker_chirp)
+            {
+                self.last_received_spl_striker_message = Some(last_striker_chirp);
+            }
+        }
+
         let spl_striker_message_timeout = match self.last_received_spl_striker_message {
             None => false,
             Some(last_received_spl_striker_message) => {
@@ -160,32 +310,44 @@ impl RoleAssignment {
             }
         };
 
+        // the lower the remaining message budget, the longer we stay silent between messages
+        let silence_interval_factor = context
+            .message_budget_status
+            .and_then(|status| status.warning_level.checked_sub(1))
+            .and_then(|index| {
+                context
+                    .spl_network
+                    .message_budget
+                    .silence_interval_factors
+                    .get(index)
+            })
+            .copied()
+            .unwrap_or(1.0);
         let silence_interval_has_passed = match self.last_transmitted_spl_striker_message {
             Some(last_transmitted_spl_striker_message) => {
                 cycle_start_time.duration_since(last_transmitted_spl_striker_message)?
-                    > context.spl_network.silence_interval_between_messages
+                    > context
+                        .spl_network
+                        .silence_interval_between_messages
+                        .mul_f32(silence_interval_factor)
             }
             None => true,
         };
 
-        if send_game_controller_return_message {
-            self.last_system_time_transmitted_game_controller_return_message =
-                Some(cycle_start_time);
-            context
-                .hardware
-                .write_to_network(OutgoingMessage::GameController(
-                    GameControllerReturnMessage {
-                        player_number: *context.player_number,
-                        fallen: matches!(context.fall_state, FallState::Fallen { .. }),
-                        pose: ground_to_field.as_pose(),
-                        ball: seen_ball_to_game_controller_ball_position(
-                            context.ball_position,
-                            cycle_start_time,
-                        ),
-                    },
-                ))
-                .wrap_err("failed to write GameControllerReturnMessage to hardware")?;
-        }
+        let is_fallen = *context.release_striker_when_fallen
+            && matches!(
+                context.fall_state,
+                FallState::Falling { .. } | FallState::Fallen { .. }
+            );
+        let time_to_reach_kick_position = reported_time_to_reach_kick_position(
+            is_fallen,
+            *context.time_to_reach_kick_position,
+            *context.fallen_time_to_reach_kick_position,
+        );
+
+        self.last_known_roles[*context.player_number] = Some(role);
+        self.last_known_times_to_reach_kick_position[*context.player_number] =
+            Some(time_to_reach_kick_position);
 
         let mut team_ball = self.team_ball;
 
@@ -210,6 +372,64 @@ impl RoleAssignment {
             }
         }
 
+        // the heartbeats slow down with the message budget, teammates wait correspondingly longer
+        let reduced_formations = context.reduced_formations;
+        let heartbeat_interval = reduced_formations
+            .heartbeat_interval
+            .mul_f32(silence_interval_factor);
+        let unresponsive_players = unresponsive_players(
+            &self.last_message_times,
+            *context.player_number,
+            cycle_start_time,
+            reduced_formations,
+            silence_interval_factor,
+        );
+        // robots that missed different messages disagree about who is unresponsive and may pick
+        // the same role of a reduced formation, the one with the higher player number falls back
+        // to the full formation until the conflict is resolved
+        let unresponsive_players = if has_formation_role_conflict(
+            self.role,
+            *context.player_number,
+            &self.last_known_roles,
+            &self.last_message_times,
+            cycle_start_time,
+            heartbeat_interval,
+        ) {
+            Players::default()
+        } else {
+            unresponsive_players
+        };
+        let formations: &[Vec<Role>] = if reduced_formations.enable {
+            &reduced_formations.formations
+        } else {
+            &[]
+        };
+
+        // the team reorganized while the robot was penalized, instead of its old role it takes the
+        // one the team currently misses
+        if self.last_primary_state == PrimaryState::Penalized
+            && primary_state == PrimaryState::Playing
+        {
+            if let Some(game_controller_state) = context.filtered_game_controller_state {
+                role = pick_role_after_penalty(
+                    *context.player_number,
+                    &game_controller_state.penalties,
+                    &self.last_message_times,
+                    cycle_start_time,
+                    context.spl_network.spl_striker_message_receive_timeout,
+                    Formation {
+                        optional_roles: context.optional_roles,
+                        reduced_formations: formations,
+                        keeper_incapable_players: &self.keeper_incapable_players,
+                        unresponsive_players: &unresponsive_players,
+                        times_to_reach_kick_position: &self.last_known_times_to_reach_kick_position,
+                        current_roles: &self.last_known_roles,
+                        claim_hysteresis: *context.striker_claim_hysteresis,
+                    },
+                );
+            }
+        }
+
         let mut network_robot_obstacles = vec![];
         let mut spl_messages = context
             .network_message
@@ -217,7 +437,7 @@ impl RoleAssignment {
             .into_values()
             .flatten()
             .filter_map(|message| match message {
-                Some(IncomingMessage::GameController(_)) | None => None,
+                Some(IncomingMessage::GameController(..)) | None => None,
                 Some(IncomingMessage::Spl(message)) => Some(message),
             })
             .peekable();
@@ -228,18 +448,43 @@ impl RoleAssignment {
                 context.ball_position,
                 primary_state,
                 None,
-                Some(*context.time_to_reach_kick_position),
+                Some(time_to_reach_kick_position),
                 send_spl_striker_message,
                 team_ball,
                 cycle_start_time,
                 context.filtered_game_controller_state,
                 *context.player_number,
                 context.spl_network.striker_trusts_team_ball,
-                context.optional_roles,
+                Formation {
+                    optional_roles: context.optional_roles,
+                    reduced_formations: formations,
+                    keeper_incapable_players: &self.keeper_incapable_players,
+                    unresponsive_players: &unresponsive_players,
+                    times_to_reach_kick_position: &self.last_known_times_to_reach_kick_position,
+                    current_roles: &self.last_known_roles,
+                    claim_hysteresis: *context.striker_claim_hysteresis,
+                },
             );
         } else {
             for spl_message in spl_messages {
                 self.last_received_spl_striker_message = Some(cycle_start_time);
+                self.last_known_poses[spl_message.player_number] = Some(spl_message.pose);
+                if primary_state == PrimaryState::Ready {
+                    self.ready_poses[spl_message.player_number] = Some(spl_message.pose);
+                }
+                self.last_message_times[spl_message.player_number] = Some(cycle_start_time);
+                if spl_message.player_number != *context.player_number {
+                    self.last_known_roles[spl_message.player_number] = Some(spl_message.role);
+                    self.last_known_times_to_reach_kick_position[spl_message.player_number] =
+                        spl_message.time_to_reach_kick_position;
+                }
+                self.keeper_incapable_players[spl_message.player_number] =
+                    Some(!spl_message.is_keeper_capable);
+                if spl_message.game_controller_state.is_some()
+                    && spl_message.player_number != *context.player_number
+                {
+                    self.last_received_game_controller_state = Some(cycle_start_time);
+                }
                 let sender_position = ground_to_field.inverse() * spl_message.pose.position();
                 if spl_message.player_number != *context.player_number {
                     network_robot_obstacles.push(sender_position);
@@ -250,14 +495,22 @@ impl RoleAssignment {
                     context.ball_position,
                     primary_state,
                     Some(spl_message),
-                    Some(*context.time_to_reach_kick_position),
+                    Some(time_to_reach_kick_position),
                     send_spl_striker_message,
                     team_ball,
                     cycle_start_time,
                     context.filtered_game_controller_state,
                     *context.player_number,
                     context.spl_network.striker_trusts_team_ball,
-                    context.optional_roles,
+                    Formation {
+                        optional_roles: context.optional_roles,
+                        reduced_formations: formations,
+                        keeper_incapable_players: &self.keeper_incapable_players,
+                        unresponsive_players: &unresponsive_players,
+                        times_to_reach_kick_position: &self.last_known_times_to_reach_kick_position,
+                        current_roles: &self.last_known_roles,
+                        claim_hysteresis: *context.striker_claim_hysteresis,
+                    },
                 );
             }
         }
@@ -275,12 +528,142 @@ impl RoleAssignment {
             }
         }
 
-        if send_spl_striker_message
-            && primary_state == PrimaryState::Playing
+        let search_sector = *context.search_sector;
+        let search_sector_claim_needs_update = search_sector != self.last_transmitted_search_sector
+            || (search_sector.is_some()
+                && match self.last_transmitted_spl_striker_message {
+                    Some(last_transmitted_spl_striker_message) => {
+                        cycle_start_time.duration_since(last_transmitted_spl_striker_message)?
+                            > context.spl_network.search_sector_claim_refresh_interval
+                    }
+                    None => true,
+                });
+
+        // the receiver has to know about a pass (or its cancellation) before the ball is on its way
+        let pass_announcement = *context.pass_announcement;
+        let pass_announcement_changed = pass_announcement.map(|pass| pass.receiver)
+            != self
+                .last_transmitted_pass_announcement
+                .map(|pass| pass.receiver);
+
+        // teammates only take over a blocked ball once they know about the worse time to reach it
+        let yields_blocked_ball = *context.yields_blocked_ball;
+        let yields_blocked_ball_changed =
+            yields_blocked_ball != self.last_transmitted_yields_blocked_ball;
+
+        let is_degraded = context.robot_condition.is_degraded();
+        let is_degraded_changed = is_degraded != self.last_transmitted_is_degraded;
+
+        let ready_signal_confidence = context.filtered_ready_signal.own_confidence;
+        let ready_signal_vote = ready_signal_confidence.is_some_and(|confidence| {
+            confidence >= context.ready_signal_filter.confidence_threshold
+        });
+        // teammates forget votes after a timeout, the current vote is repeated as long as the
+        // robot watches for the signal, negative ones included
+        let ready_signal_vote_is_due = ready_signal_confidence.is_some()
+            && (ready_signal_vote != self.last_transmitted_ready_signal_vote
+                || self
+                    .last_transmitted_spl_striker_message
+                    .map_or(true, |last_transmitted| {
+                        cycle_start_time
+                            .duration_since(last_transmitted)
+                            .is_ok_and(|duration| {
+                                duration > context.ready_signal_filter.vote_refresh_interval
+                            })
+                    }));
+
+        // the keeper has to step aside before the striker walks into it
+        let goal_mouth_claim = *context.goal_mouth_claim;
+        let goal_mouth_claim_changed =
+            goal_mouth_claim.is_some() != self.last_transmitted_goal_mouth_claim.is_some();
+
+        // teammates coordinate with the striker, they have to know about a changed plan early
+        let transmitted_role = context.forced_role.copied().unwrap_or(role);
+        let striker_intent = if transmitted_role == Role::Striker {
+            *context.striker_intent
+        } else {
+            None
+        };
+        let striker_intent_changed = striker_intent != self.last_transmitted_striker_intent;
+
+        let releases_striker = is_fallen && transmitted_role == Role::Striker;
+        let striker_release_is_due = releases_striker && !self.last_transmitted_releases_striker;
+
+        // silent teammates are considered missing after a while, everyone has to show a sign of life
+        let heartbeat_is_due = reduced_formations.enable
+            && self
+                .last_transmitted_spl_striker_message
+                .map_or(true, |last_transmitted| {
+                    cycle_start_time
+                        .duration_since(last_transmitted)
+                        .is_ok_and(|duration| duration > heartbeat_interval)
+                });
+
+        // teammates without GameController reception follow the game with the relayed state, it
+        // is sent in every game state once it changes and refreshed before it times out unless a
+        // teammate already relays it
+        let game_controller_state_digest = context.game_controller_state_digest.copied();
+        let game_controller_state_rebroadcast_is_due =
+            game_controller_state_digest.is_some_and(|digest| {
+                is_game_controller_state_rebroadcast_due(
+                    digest,
+                    self.last_transmitted_game_controller_state,
+                    self.last_received_game_controller_state,
+                    cycle_start_time,
+                    context
+                        .spl_network
+                        .game_controller_rebroadcast
+                        .refresh_interval,
+                )
+            });
+
+        // teammates assign the Ready roles from the poses sent at the beginning of Ready
+        let ready_pose_is_due = context.ready_role_assignment.enable
+            && primary_state == PrimaryState::Ready
+            && self.ready_poses[*context.player_number].is_none();
+
+        let is_allowed_to_transmit = match primary_state {
+            PrimaryState::Playing => true,
+            PrimaryState::Ready => ready_pose_is_due || game_controller_state_rebroadcast_is_due,
+            // teammates count the votes on the referee's signal while waiting in Standby
+            PrimaryState::Standby => {
+                ready_signal_vote_is_due || game_controller_state_rebroadcast_is_due
+            }
+            _ => game_controller_state_rebroadcast_is_due,
+        };
+
+        if (send_spl_striker_message
+            || search_sector_claim_needs_update
+            || pass_announcement_changed
+            || yields_blocked_ball_changed
+            || is_degraded_changed
+            || ready_signal_vote_is_due
+            || goal_mouth_claim_changed
+            || striker_intent_changed
+            || striker_release_is_due
+            || heartbeat_is_due
+            || ready_pose_is_due
+            || game_controller_state_rebroadcast_is_due)
+            && is_allowed_to_transmit
             && silence_interval_has_passed
         {
             self.last_transmitted_spl_striker_message = Some(cycle_start_time);
             self.last_received_spl_striker_message = Some(cycle_start_time);
+            self.last_transmitted_search_sector = search_sector;
+            self.last_transmitted_pass_announcement = pass_announcement;
+            self.last_transmitted_yields_blocked_ball = yields_blocked_ball;
+            self.last_transmitted_is_degraded = is_degraded;
+            self.last_transmitted_ready_signal_vote = ready_signal_vote;
+            self.last_transmitted_goal_mouth_claim = goal_mouth_claim;
+            self.last_transmitted_striker_intent = striker_intent;
+            self.last_transmitted_releases_striker = releases_striker;
+            if let Some(digest) = game_controller_state_digest {
+                self.last_transmitted_game_controller_state = Some((cycle_start_time, digest));
+            }
+            self.last_known_poses[*context.player_number] = Some(ground_to_field.as_pose());
+            if primary_state == PrimaryState::Ready {
+                self.ready_poses[*context.player_number] = Some(ground_to_field.as_pose());
+            }
             if let Some(game_controller_state) = context.filtered_game_controller_state {
                 if game_controller_state.remaining_number_of_messages
                     > context
@@ -301,9 +684,18 @@ impl RoleAssignment {
                         .write_to_network(OutgoingMessage::Spl(HulkMessage {
                             player_number: *context.player_number,
                             fallen: matches!(context.fall_state, FallState::Fallen { .. }),
+                            is_keeper_capable: context.robot_capabilities.is_keeper_capable(),
+                            is_degraded,
                             pose: ground_to_field.as_pose(),
                             ball_position,
-                            time_to_reach_kick_position: Some(*context.time_to_reach_kick_position),
+                            time_to_reach_kick_position: Some(time_to_reach_kick_position),
+                            search_sector,
+                            pass: pass_announcement,
+                            ready_signal_confidence,
+                            goal_mouth_claim,
+                            role: transmitted_role,
+                            striker_intent,
+                            game_controller_state: game_controller_state_digest,
                         }))?;
                 }
             }
@@ -315,6 +707,7 @@ impl RoleAssignment {
             self.role = role;
         }
         self.team_ball = team_ball;
+        self.last_primary_state = primary_state;
 
         if let Some(game_controller_state) = context.filtered_game_controller_state {
             if game_controller_state.penalties.one.is_some() {
@@ -322,14 +715,133 @@ impl RoleAssignment {
             }
         }
 
+        let teammates: Vec<_> = self
+            .last_known_poses
+            .iter()
+            .filter(|(player, _)| *player != *context.player_number)
+            .filter(|(player, _)| {
+                context
+                    .filtered_game_controller_state
+                    .map_or(true, |state| state.penalties[*player].is_none())
+            })
+            // teammates only send their pose on changes, an old one may be far off by now
+            .filter(|(player, _)| {
+                self.last_message_times[*player].is_some_and(|last_message_time| {
+                    cycle_start_time
+                        .duration_since(last_message_time)
+                        .is_ok_and(|duration| duration <= *context.teammate_pose_timeout)
+                })
+            })
+            .filter_map(|(player, pose)| pose.map(|pose| (player, pose.position())))
+            .collect();
+        let teammate_positions = teammates.iter().map(|(_, position)| *position).collect();
+        let teammate_defender_positions = teammates
+            .iter()
+            .filter(|(player, _)| {
+                matches!(
+                    self.last_known_roles[*player],
+                    Some(Role::DefenderLeft | Role::DefenderRight)
+                )
+            })
+            .map(|(_, position)| *position)
+            .collect();
+
         Ok(MainOutputs {
             role: self.role.into(),
             team_ball: self.team_ball.into(),
             network_robot_obstacles: network_robot_obstacles.into(),
+            teammate_positions: teammate_positions.into(),
+            teammate_defender_positions: teammate_defender_positions.into(),
+            keeper_incapable_players: self.keeper_incapable_players.into(),
+            unresponsive_players: unresponsive_players.into(),
+            announced_roles: self.last_known_roles.into(),
+            announced_times_to_reach_kick_position: self
+                .last_known_times_to_reach_kick_position
+                .into(),
         })
     }
 }
 
+/// The role and what the robot knew about its teammates, transmission bookkeeping and timings
+/// start over
+#[derive(Deserialize, Serialize)]
+pub struct PersistentRoleAssignment {
+    role: Role,
+    role_initialized: bool,
+    team_ball: Option<BallPosition<Field>>,
+    last_known_poses: Players<Option<Pose2<Field>>>,
+    keeper_incapable_players: Players<Option<bool>>,
+    last_known_roles: Players<Option<Role>>,
+}
+
+impl PersistentState for RoleAssignment {
+    type State = PersistentRoleAssignment;
+
+    fn persistent_state(&self) -> Self::State {
+        PersistentRoleAssignment {
+            role: self.role,
+            role_initialized: self.role_initialized,
+            team_ball: self.team_ball,
+            last_known_poses: self.last_known_poses,
+            keeper_incapable_players: self.keeper_incapable_players,
+            last_known_roles: self.last_known_roles,
+        }
+    }
+
+    fn restore_persistent_state(&mut self, state: Self::State) {
+        self.role = state.role;
+        self.role_initialized = state.role_initialized;
+        self.team_ball = state.team_ball;
+        self.last_known_poses = state.last_known_poses;
+        self.keeper_incapable_players = state.keeper_incapable_players;
+        self.last_known_roles = state.last_known_roles;
+    }
+}
+
+/// Exchanges the roles of all unpenalized field players such that the total distance to the
+/// nominal Ready positions is minimal, the keeper always keeps its role. Robots without a known
+/// pose are assumed at their initial pose.
+fn assign_ready_roles(
+    player_roles: Players<Role>,
+    penalties: Option<&Players<Option<Penalty>>>,
+    last_known_poses: &Players<Option<Pose2<Field>>>,
+    initial_poses: &Players<InitialPose>,
+    field_dimensions: &FieldDimensions,
+    parameters: &ReadyRoleAssignmentParameters,
+) -> Players<Role> {
+    let players: Vec<PlayerNumber> = player_roles
+        .iter()
+        .filter(|(player, role)| {
+            **role != Role::Keeper
+                && penalties.map_or(true, |penalties| penalties[*player].is_none())
+        })
+        .map(|(player, _)| player)
+        .collect();
+    let roles: Vec<Role> = players.iter().map(|player| player_roles[*player]).collect();
+    let costs: Vec<Vec<f32>> = players
+        .iter()
+        .map(|player| {
+            let position = last_known_poses[*player]
+                .unwrap_or_else(|| generate_initial_pose(&initial_poses[*player], field_dimensions))
+                .position();
+            roles
+                .iter()
+                .map(|role| {
+                    parameters
+                        .position(*role)
+                        .map_or(0.0, |target| distance(position, target))
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut assigned_roles = player_roles;
+    for (player, role_index) in players.iter().zip(hungarian::solve(&costs)) {
+        assigned_roles[*player] = roles[role_index];
+    }
+    assigned_roles
+}
+
 #[allow(clippy::too_many_arguments)]
 fn process_role_state_machine(
     current_role: Role,
@@ -344,8 +856,16 @@ fn process_role_state_machine(
     filtered_game_controller_state: Option<&FilteredGameControllerState>,
     player_number: PlayerNumber,
     striker_trusts_team_ball: Duration,
-    optional_roles: &[Role],
+    formation: Formation,
 ) -> (Role, bool, Option<BallPosition<Field>>) {
+    let striker_claim = StrikerClaim {
+        current_role,
+        time_to_reach_kick_position,
+        player_number,
+        cycle_start_time,
+        filtered_game_controller_state,
+        formation,
+    };
     if let Some(game_controller_state) = filtered_game_controller_state {
         match game_controller_state.game_phase {
             GamePhase::PenaltyShootout {
@@ -416,14 +936,7 @@ fn process_role_state_machine(
                     }
                 }
             }
-            _ => decide_if_claiming_striker_or_other_role(
-                spl_message,
-                time_to_reach_kick_position,
-                player_number,
-                cycle_start_time,
-                filtered_game_controller_state,
-                optional_roles,
-            ),
+            _ => decide_if_claiming_striker_or_other_role(spl_message, striker_claim),
         },
 
         //Striker remains Striker, sends message after timeout
@@ -439,14 +952,7 @@ fn process_role_state_machine(
                     team_ball_from_seen_ball(detected_own_ball, current_pose, cycle_start_time),
                 )
             }
-            _ => decide_if_claiming_striker_or_other_role(
-                spl_message,
-                time_to_reach_kick_position,
-                player_number,
-                cycle_start_time,
-                filtered_game_controller_state,
-                optional_roles,
-            ),
+            _ => decide_if_claiming_striker_or_other_role(spl_message, striker_claim),
         },
 
         //Loser remains Loser
@@ -454,14 +960,7 @@ fn process_role_state_machine(
 
         (Role::Loser, None, Some(spl_message)) => match &spl_message.ball_position {
             None => (Role::Loser, false, None), //edge-case, a striker (which should not exist) lost the ball
-            _ => decide_if_claiming_striker_or_other_role(
-                spl_message,
-                time_to_reach_kick_position,
-                player_number,
-                cycle_start_time,
-                filtered_game_controller_state,
-                optional_roles,
-            ),
+            _ => decide_if_claiming_striker_or_other_role(spl_message, striker_claim),
         },
 
         //Loser found ball and becomes Striker
@@ -481,14 +980,7 @@ fn process_role_state_machine(
                     team_ball_from_seen_ball(detected_own_ball, current_pose, cycle_start_time),
                 )
             }
-            _ => decide_if_claiming_striker_or_other_role(
-                spl_message,
-                time_to_reach_kick_position,
-                player_number,
-                cycle_start_time,
-                filtered_game_controller_state,
-                optional_roles,
-            ),
+            _ => decide_if_claiming_striker_or_other_role(spl_message, striker_claim),
         },
 
         //Searcher remains Searcher
@@ -496,14 +988,7 @@ fn process_role_state_machine(
 
         (Role::Searcher, None, Some(spl_message)) => match &spl_message.ball_position {
             None => (Role::Searcher, false, team_ball), //edge-case, a striker (which should not exist) lost the ball
-            _ => decide_if_claiming_striker_or_other_role(
-                spl_message,
-                time_to_reach_kick_position,
-                player_number,
-                cycle_start_time,
-                filtered_game_controller_state,
-                optional_roles,
-            ),
+            _ => decide_if_claiming_striker_or_other_role(spl_message, striker_claim),
         },
 
         //Searcher found ball and becomes Striker
@@ -520,14 +1005,7 @@ fn process_role_state_machine(
                 true,
                 team_ball_from_seen_ball(detected_own_ball, current_pose, cycle_start_time),
             ),
-            _ => decide_if_claiming_striker_or_other_role(
-                spl_message,
-                time_to_reach_kick_position,
-                player_number,
-                cycle_start_time,
-                filtered_game_controller_state,
-                optional_roles,
-            ),
+            _ => decide_if_claiming_striker_or_other_role(spl_message, striker_claim),
         },
 
         // remain in other_role
@@ -542,14 +1020,7 @@ fn process_role_state_machine(
                     (other_role, false, None)
                 }
             }
-            _ => decide_if_claiming_striker_or_other_role(
-                spl_message,
-                time_to_reach_kick_position,
-                player_number,
-                cycle_start_time,
-                filtered_game_controller_state,
-                optional_roles,
-            ),
+            _ => decide_if_claiming_striker_or_other_role(spl_message, striker_claim),
         },
 
         // Claim Striker if team-ball position is None
@@ -573,27 +1044,50 @@ fn process_role_state_machine(
                 true,
                 team_ball_from_seen_ball(detected_own_ball, current_pose, cycle_start_time),
             ),
-            _ => decide_if_claiming_striker_or_other_role(
-                spl_message,
-                time_to_reach_kick_position,
-                player_number,
-                cycle_start_time,
-                filtered_game_controller_state,
-                optional_roles,
-            ),
+            _ => decide_if_claiming_striker_or_other_role(spl_message, striker_claim),
         },
     }
 }
 
-fn decide_if_claiming_striker_or_other_role(
-    spl_message: &HulkMessage,
+/// What the own robot compares with a teammate's message to decide whether it claims the striker
+/// role
+#[derive(Clone, Copy)]
+struct StrikerClaim<'a> {
+    current_role: Role,
     time_to_reach_kick_position: Option<Duration>,
     player_number: PlayerNumber,
     cycle_start_time: SystemTime,
-    filtered_game_controller_state: Option<&FilteredGameControllerState>,
-    optional_roles: &[Role],
+    filtered_game_controller_state: Option<&'a FilteredGameControllerState>,
+    formation: Formation<'a>,
+}
+
+fn decide_if_claiming_striker_or_other_role(
+    spl_message: &HulkMessage,
+    claim: StrikerClaim,
 ) -> (Role, bool, Option<BallPosition<Field>>) {
-    if time_to_reach_kick_position < spl_message.time_to_reach_kick_position {
+    let StrikerClaim {
+        current_role,
+        time_to_reach_kick_position,
+        player_number,
+        cycle_start_time,
+        filtered_game_controller_state,
+        formation,
+    } = claim;
+    // Only claiming the striker role requires a clear advantage, a striker yields to any claim.
+    // Otherwise two robots with similar times to the ball would keep taking the role from each other.
+    let claim_margin = if current_role == Role::Striker {
+        Duration::ZERO
+    } else {
+        formation.claim_hysteresis
+    };
+    let is_clearly_faster = match (
+        time_to_reach_kick_position,
+        spl_message.time_to_reach_kick_position,
+    ) {
+        (Some(own_time), Some(sender_time)) => own_time + claim_margin < sender_time,
+        (own_time, sender_time) => own_time < sender_time,
+    };
+    if is_clearly_faster {
         (
             Role::Striker,
             true,
@@ -605,7 +1099,7 @@ fn decide_if_claiming_striker_or_other_role(
                 player_number,
                 filtered_game_controller_state,
                 spl_message.player_number,
-                optional_roles,
+                formation,
             ),
             false,
             team_ball_from_spl_message(cycle_start_time, spl_message),
@@ -613,16 +1107,6 @@ fn decide_if_claiming_striker_or_other_role(
     }
 }
 
-fn seen_ball_to_game_controller_ball_position(
-    ball: Option<&BallPosition<Ground>>,
-    cycle_start_time: SystemTime,
-) -> Option<spl_network_messages::BallPosition<Ground>> {
-    ball.map(|ball| spl_network_messages::BallPosition {
-        age: cycle_start_time.duration_since(ball.last_seen).unwrap(),
-        position: ball.position,
-    })
-}
-
 fn seen_ball_to_hulks_network_ball_position(
     ball: Option<&BallPosition<Ground>>,
     ground_to_field: Isometry2<Ground, Field>,
@@ -676,25 +1160,25 @@ fn generate_role(
     own_player_number: PlayerNumber,
     game_controller_state: Option<&FilteredGameControllerState>,
     striker_player_number: PlayerNumber,
-    optional_roles: &[Role],
+    formation: Formation,
 ) -> Role {
     if let Some(state) = game_controller_state {
         pick_role_with_penalties(
             own_player_number,
             &state.penalties,
             striker_player_number,
-            optional_roles,
+            formation,
         )
     } else {
         Role::Striker // This case only happens if we don't have a game controller state
     }
 }
 
-fn pick_role_with_penalties(
+pub fn pick_role_with_penalties(
     own_player_number: PlayerNumber,
     penalties: &Players<Option<Penalty>>,
     striker_player_number: PlayerNumber,
-    optional_roles: &[Role],
+    formation: Formation,
 ) -> Role {
     let mut role_assignment: Players<Option<Role>> = Players {
         one: None,
@@ -705,32 +1189,63 @@ fn pick_role_with_penalties(
         six: None,
         seven: None,
     };
+    let mut unavailable_players = Players::<bool>::default();
+    for (player, penalty) in penalties.iter() {
+        unavailable_players[player] = player != own_player_number
+            && player != striker_player_number
+            && (penalty.is_some() || formation.unresponsive_players[player]);
+    }
 
     role_assignment[striker_player_number] = Some(Role::Striker);
     let mut unassigned_robots = 6;
 
-    unassigned_robots -= penalties
+    unassigned_robots -= unavailable_players
         .iter()
-        .filter(|(_player, &penalty)| penalty.is_some())
+        .filter(|(_player, &is_unavailable)| is_unavailable)
         .count();
 
     if unassigned_robots > 0 {
-        unassigned_robots =
-            assign_keeper_or_replacement_keeper(unassigned_robots, penalties, &mut role_assignment);
+        unassigned_robots = assign_keeper_or_replacement_keeper(
+            unassigned_robots,
+            &unavailable_players,
+            formation.keeper_incapable_players,
+            &mut role_assignment,
+        );
     }
 
-    for &optional_role in optional_roles.iter().take(unassigned_robots) {
-        if needs_assignment(PlayerNumber::Two, penalties, &role_assignment) {
+    let optional_roles = formation
+        .reduced_formations
+        .get(unassigned_robots)
+        .map_or(formation.optional_roles, Vec::as_slice);
+    let optional_roles = &optional_roles[..unassigned_robots.min(optional_roles.len())];
+    let striker_supporter = optional_roles
+        .contains(&Role::StrikerSupporter)
+        .then(|| pick_striker_supporter(&unavailable_players, &role_assignment, formation))
+        .flatten();
+    if let Some(striker_supporter) = striker_supporter {
+        role_assignment[striker_supporter] = Some(Role::StrikerSupporter);
+    }
+    let mut remaining_roles = optional_roles.to_vec();
+    if striker_supporter.is_some() {
+        if let Some(index) = remaining_roles
+            .iter()
+            .position(|&role| role == Role::StrikerSupporter)
+        {
+            remaining_roles.remove(index);
+        }
+    }
+    for optional_role in remaining_roles {
+        if needs_assignment(PlayerNumber::Two, &unavailable_players, &role_assignment) {
             role_assignment[PlayerNumber::Two] = Some(optional_role);
-        } else if needs_assignment(PlayerNumber::Three, penalties, &role_assignment) {
+        } else if needs_assignment(PlayerNumber::Three, &unavailable_players, &role_assignment) {
             role_assignment[PlayerNumber::Three] = Some(optional_role);
-        } else if needs_assignment(PlayerNumber::Four, penalties, &role_assignment) {
+        } else if needs_assignment(PlayerNumber::Four, &unavailable_players, &role_assignment) {
             role_assignment[PlayerNumber::Four] = Some(optional_role);
-        } else if needs_assignment(PlayerNumber::Five, penalties, &role_assignment) {
+        } else if needs_assignment(PlayerNumber::Five, &unavailable_players, &role_assignment) {
             role_assignment[PlayerNumber::Five] = Some(optional_role);
-        } else if needs_assignment(PlayerNumber::Six, penalties, &role_assignment) {
+        } else if needs_assignment(PlayerNumber::Six, &unavailable_players, &role_assignment) {
             role_assignment[PlayerNumber::Six] = Some(optional_role);
-        } else if needs_assignment(PlayerNumber::Seven, penalties, &role_assignment) {
+        } else if needs_assignment(PlayerNumber::Seven, &unavailable_players, &role_assignment) {
             role_assignment[PlayerNumber::Seven] = Some(optional_role);
         }
     }
@@ -738,43 +1253,720 @@ fn pick_role_with_penalties(
     role_assignment[own_player_number].unwrap_or_default()
 }
 
+/// The robot reaching the ball fastest after the striker supports it, the current supporter keeps
+/// its role unless another robot is faster by the claim hysteresis. As long as no candidate
+/// announced its time, the supporter is chosen by player number like the other roles.
+fn pick_striker_supporter(
+    unavailable_players: &Players<bool>,
+    role_assignment: &Players<Option<Role>>,
+    formation: Formation,
+) -> Option<PlayerNumber> {
+    let candidates = [
+        PlayerNumber::Two,
+        PlayerNumber::Three,
+        PlayerNumber::Four,
+        PlayerNumber::Five,
+        PlayerNumber::Six,
+        PlayerNumber::Seven,
+    ]
+    .into_iter()
+    .filter(|player| needs_assignment(*player, unavailable_players, role_assignment));
+    let is_any_time_known = candidates
+        .clone()
+        .any(|player| formation.times_to_reach_kick_position[player].is_some());
+    if !is_any_time_known {
+        return None;
+    }
+    candidates.min_by_key(|player| {
+        let time_to_reach_kick_position =
+            formation.times_to_reach_kick_position[*player].unwrap_or(Duration::MAX);
+        if formation.current_roles[*player] == Some(Role::StrikerSupporter) {
+            time_to_reach_kick_position.saturating_sub(formation.claim_hysteresis)
+        } else {
+            time_to_reach_kick_position
+        }
+    })
+}
+
+/// Keeps the current striker and fills the formation around it, without a striker the team lost
+/// the ball and the returning robot helps searching. The striker is the latest robot announcing
+/// the role, older announcements than the striker message timeout are outdated.
+fn pick_role_after_penalty(
+    own_player_number: PlayerNumber,
+    penalties: &Players<Option<Penalty>>,
+    last_message_times: &Players<Option<SystemTime>>,
+    now: SystemTime,
+    striker_message_timeout: Duration,
+    formation: Formation,
+) -> Role {
+    let striker = formation
+        .current_roles
+        .iter()
+        .filter(|(player, role)| {
+            *player != own_player_number
+                && **role == Some(Role::Striker)
+                && penalties[*player].is_none()
+                && !formation.unresponsive_players[*player]
+        })
+        .filter_map(|(player, _)| Some((player, last_message_times[player]?)))
+        .filter(|(_, last_message_time)| {
+            now.duration_since(*last_message_time)
+                .is_ok_and(|duration| duration <= striker_message_timeout)
+        })
+        .max_by_key(|(_, last_message_time)| *last_message_time)
+        .map(|(player, _)| player);
+    match striker {
+        Some(striker) => pick_role_with_penalties(own_player_number, penalties, striker, formation),
+        None => Role::Searcher,
+    }
+}
+
 fn needs_assignment(
     player_number: PlayerNumber,
-    penalties: &Players<Option<Penalty>>,
+    unavailable_players: &Players<bool>,
     role_assignment: &Players<Option<Role>>,
 ) -> bool {
-    role_assignment[player_number].is_none() && penalties[player_number].is_none()
+    role_assignment[player_number].is_none() && !unavailable_players[player_number]
 }
 
+/// The keeper is always player one, robots that cannot perform all keeper motions only
+/// replace it if nobody else is available. Without the capabilities of all candidates the robots
+/// could disagree on the replacement keeper, the candidates are then taken in their order.
 fn assign_keeper_or_replacement_keeper(
     unassigned_robots: usize,
-    penalties: &Players<Option<Penalty>>,
+    unavailable_players: &Players<bool>,
+    keeper_incapable_players: &Players<Option<bool>>,
     role_assignment: &mut Players<Option<Role>>,
 ) -> usize {
-    if needs_assignment(PlayerNumber::One, penalties, role_assignment) {
+    if needs_assignment(PlayerNumber::One, unavailable_players, role_assignment) {
         role_assignment[PlayerNumber::One] = Some(Role::Keeper);
         return unassigned_robots - 1;
     }
 
-    if needs_assignment(PlayerNumber::Two, penalties, role_assignment) {
-        role_assignment[PlayerNumber::Two] = Some(Role::ReplacementKeeper);
-        return unassigned_robots - 1;
-    } else if needs_assignment(PlayerNumber::Three, penalties, role_assignment) {
-        role_assignment[PlayerNumber::Three] = Some(Role::ReplacementKeeper);
-        return unassigned_robots - 1;
-    } else if needs_assignment(PlayerNumber::Four, penalties, role_assignment) {
-        role_assignment[PlayerNumber::Four] = Some(Role::ReplacementKeeper);
-        return unassigned_robots - 1;
-    } else if needs_assignment(PlayerNumber::Five, penalties, role_assignment) {
-        role_assignment[PlayerNumber::Five] = Some(Role::ReplacementKeeper);
-        return unassigned_robots - 1;
-    } else if needs_assignment(PlayerNumber::Six, penalties, role_assignment) {
-        role_assignment[PlayerNumber::Six] = Some(Role::ReplacementKeeper);
-        return unassigned_robots - 1;
-    } else if needs_assignment(PlayerNumber::Seven, penalties, role_assignment) {
-        role_assignment[PlayerNumber::Seven] = Some(Role::ReplacementKeeper);
+    let candidates = [
+        PlayerNumber::Two,
+        PlayerNumber::Three,
+        PlayerNumber::Four,
+        PlayerNumber::Five,
+        PlayerNumber::Six,
+        PlayerNumber::Seven,
+    ];
+    let available_candidates: Vec<_> = candidates
+        .into_iter()
+        .filter(|player| needs_assignment(*player, unavailable_players, role_assignment))
+        .collect();
+    let are_capabilities_known = available_candidates
+        .iter()
+        .all(|player| keeper_incapable_players[*player].is_some());
+    let replacement_keeper = available_candidates
+        .iter()
+        .find(|player| !are_capabilities_known || keeper_incapable_players[**player] == Some(false))
+        .or(available_candidates.first())
+        .copied();
+    if let Some(player) = replacement_keeper {
+        role_assignment[player] = Some(Role::ReplacementKeeper);
         return unassigned_robots - 1;
     }
 
     unassigned_robots
 }
+
+/// Due once the situation changed or if neither the own robot nor a teammate relayed the state
+/// for the refresh interval
+fn is_game_controller_state_rebroadcast_due(
+    digest: GameControllerStateDigest,
+    last_transmitted: Option<(SystemTime, GameControllerStateDigest)>,
+    last_received: Option<SystemTime>,
+    now: SystemTime,
+    refresh_interval: Duration,
+) -> bool {
+    let Some((last_transmitted_time, last_transmitted_digest)) = last_transmitted else {
+        return true;
+    };
+    let refresh_is_due = [Some(last_transmitted_time), last_received]
+        .into_iter()
+        .flatten()
+        .all(|last_relayed| {
+            now.duration_since(last_relayed)
+                .is_ok_and(|duration| duration > refresh_interval)
+        });
+    !digest.has_same_situation(&last_transmitted_digest) || refresh_is_due
+}
+
+/// Teammates count as unresponsive once they have been silent for the timeout, the own robot
+/// never does. The timeout is stretched by the same factor as the heartbeats.
+fn unresponsive_players(
+    last_message_times: &Players<Option<SystemTime>>,
+    own_player_number: PlayerNumber,
+    now: SystemTime,
+    parameters: &ReducedFormationParameters,
+    silence_interval_factor: f32,
+) -> Players<bool> {
+    let mut unresponsive_players = Players::<bool>::default();
+    if !parameters.enable {
+        return unresponsive_players;
+    }
+    let unresponsive_timeout = parameters
+        .unresponsive_timeout
+        .mul_f32(silence_interval_factor);
+    for (player, last_message_time) in last_message_times.iter() {
+        unresponsive_players[player] = player != own_player_number
+            && last_message_time.is_some_and(|last_message_time| {
+                now.duration_since(last_message_time)
+                    .is_ok_and(|duration| duration > unresponsive_timeout)
+            });
+    }
+    unresponsive_players
+}
+
+/// Whether a teammate with a lower player number announced the own formation role within the
+/// last heartbeat interval
+fn has_formation_role_conflict(
+    own_role: Role,
+    own_player_number: PlayerNumber,
+    last_known_roles: &Players<Option<Role>>,
+    last_message_times: &Players<Option<SystemTime>>,
+    now: SystemTime,
+    heartbeat_interval: Duration,
+) -> bool {
+    if matches!(
+        own_role,
+        Role::Keeper | Role::Striker | Role::Loser | Role::Searcher
+    ) {
+        return false;
+    }
+    last_known_roles.iter().any(|(player, role)| {
+        player < own_player_number
+            && *role == Some(own_role)
+            && last_message_times[player].is_some_and(|last_message_time| {
+                now.duration_since(last_message_time)
+                    .is_ok_and(|duration| duration <= heartbeat_interval)
+            })
+    })
+}
+
+/// A fallen robot reports a time worse than any teammate's such that the best placed teammate
+/// takes over right away instead of once the stand up time exceeds its own. After standing up it
+/// has to win the striker claim with the usual hysteresis again.
+fn reported_time_to_reach_kick_position(
+    is_fallen: bool,
+    time_to_reach_kick_position: Duration,
+    fallen_time_to_reach_kick_position: Duration,
+) -> Duration {
+    if is_fallen {
+        fallen_time_to_reach_kick_position.max(time_to_reach_kick_position)
+    } else {
+        time_to_reach_kick_position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use node_testing::TestParameters;
+
+    use super::*;
+
+    const OPTIONAL_ROLES: [Role; 5] = [
+        Role::DefenderLeft,
+        Role::DefenderRight,
+        Role::StrikerSupporter,
+        Role::MidfielderRight,
+        Role::MidfielderLeft,
+    ];
+
+    fn seconds(seconds: f32) -> Option<Duration> {
+        Some(Duration::from_secs_f32(seconds))
+    }
+
+    fn formation<'a>(
+        times_to_reach_kick_position: &'a Players<Option<Duration>>,
+        current_roles: &'a Players<Option<Role>>,
+    ) -> Formation<'a> {
+        const NOBODY: Players<bool> = Players {
+            one: false,
+            two: false,
+            three: false,
+            four: false,
+            five: false,
+            six: false,
+            seven: false,
+        };
+        const ALL_KEEPER_CAPABLE: Players<Option<bool>> = Players {
+            one: Some(false),
+            two: Some(false),
+            three: Some(false),
+            four: Some(false),
+            five: Some(false),
+            six: Some(false),
+            seven: Some(false),
+        };
+        Formation {
+            optional_roles: &OPTIONAL_ROLES,
+            reduced_formations: &[],
+            keeper_incapable_players: &ALL_KEEPER_CAPABLE,
+            unresponsive_players: &NOBODY,
+            times_to_reach_kick_position,
+            current_roles,
+            claim_hysteresis: Duration::from_millis(500),
+        }
+    }
+
+    fn roles_with_striker(
+        striker: PlayerNumber,
+        times_to_reach_kick_position: &Players<Option<Duration>>,
+        current_roles: &Players<Option<Role>>,
+    ) -> Players<Role> {
+        let mut roles = Players::<Role>::default();
+        for (player, _) in Players::<()>::default().iter() {
+            roles[player] = pick_role_with_penalties(
+                player,
+                &Players::default(),
+                striker,
+                formation(times_to_reach_kick_position, current_roles),
+            );
+        }
+        roles
+    }
+
+    #[test]
+    fn fastest_robot_besides_the_striker_supports_it() {
+        let times_to_reach_kick_position = Players {
+            two: seconds(10.0),
+            three: seconds(6.0),
+            four: seconds(3.0),
+            five: seconds(8.0),
+            six: None,
+            ..Default::default()
+        };
+
+        let roles = roles_with_striker(
+            PlayerNumber::Seven,
+            &times_to_reach_kick_position,
+            &Players::default(),
+        );
+
+        assert_eq!(roles.one, Role::Keeper);
+        assert_eq!(roles.two, Role::DefenderLeft);
+        assert_eq!(roles.three, Role::DefenderRight);
+        assert_eq!(roles.four, Role::StrikerSupporter);
+        assert_eq!(roles.five, Role::MidfielderRight);
+        assert_eq!(roles.six, Role::MidfielderLeft);
+        assert_eq!(roles.seven, Role::Striker);
+    }
+
+    #[test]
+    fn supporter_is_only_taken_over_by_a_clearly_faster_robot() {
+        let current_roles = Players {
+            four: Some(Role::StrikerSupporter),
+            ..Default::default()
+        };
+        let slightly_faster = Players {
+            three: seconds(3.0),
+            four: seconds(3.3),
+            ..Default::default()
+        };
+        let clearly_faster = Players {
+            three: seconds(2.5),
+            four: seconds(3.3),
+            ..Default::default()
+        };
+
+        let roles = roles_with_striker(PlayerNumber::Seven, &slightly_faster, &current_roles);
+        assert_eq!(roles.four, Role::StrikerSupporter);
+        assert_eq!(roles.three, Role::DefenderRight);
+
+        let roles = roles_with_striker(PlayerNumber::Seven, &clearly_faster, &current_roles);
+        assert_eq!(roles.three, Role::StrikerSupporter);
+        assert_eq!(roles.four, Role::DefenderRight);
+    }
+
+    #[test]
+    fn striker_is_only_claimed_with_a_clear_advantage() {
+        let times_to_reach_kick_position = Players::default();
+        let current_roles = Players::default();
+        let message = HulkMessage {
+            player_number: PlayerNumber::Three,
+            time_to_reach_kick_position: seconds(2.0),
+            ..Default::default()
+        };
+        // the claiming robot announces its claim
+        let claims = |current_role, own_time| {
+            decide_if_claiming_striker_or_other_role(
+                &message,
+                StrikerClaim {
+                    current_role,
+                    time_to_reach_kick_position: seconds(own_time),
+                    player_number: PlayerNumber::Four,
+                    cycle_start_time: SystemTime::UNIX_EPOCH,
+                    filtered_game_controller_state: None,
+                    formation: formation(&times_to_reach_kick_position, &current_roles),
+                },
+            )
+            .1
+        };
+
+        assert!(!claims(Role::DefenderRight, 1.8));
+        assert!(claims(Role::Searcher, 1.4));
+        assert!(claims(Role::Striker, 1.9));
+        assert!(!claims(Role::Striker, 2.1));
+    }
+
+    fn assign_ready_roles_at(
+        positions: [(PlayerNumber, Point2<Field>); 6],
+        penalties: &Players<Option<Penalty>>,
+    ) -> Players<Role> {
+        let parameters = TestParameters::load_default().unwrap();
+        let ready_role_assignment: ReadyRoleAssignmentParameters = parameters
+            .get("role_assignment.ready_role_assignment")
+            .unwrap();
+        let mut last_known_poses = Players::default();
+        for (player, position) in positions {
+            last_known_poses[player] = Some(Pose2::from(position));
+        }
+        assign_ready_roles(
+            Players {
+                one: Role::Keeper,
+                two: Role::DefenderLeft,
+                three: Role::DefenderRight,
+                four: Role::StrikerSupporter,
+                five: Role::MidfielderRight,
+                six: Role::MidfielderLeft,
+                seven: Role::Striker,
+            },
+            Some(penalties),
+            &last_known_poses,
+            &parameters.get("localization.initial_poses").unwrap(),
+            &parameters.get("field_dimensions").unwrap(),
+            &ready_role_assignment,
+        )
+    }
+
+    fn positions_with_swapped_defenders() -> [(PlayerNumber, Point2<Field>); 6] {
+        let parameters: ReadyRoleAssignmentParameters = TestParameters::load_default()
+            .unwrap()
+            .get("role_assignment.ready_role_assignment")
+            .unwrap();
+        [
+            (PlayerNumber::Two, parameters.defender_right),
+            (PlayerNumber::Three, parameters.defender_left),
+            (PlayerNumber::Four, parameters.striker_supporter),
+            (PlayerNumber::Five, parameters.midfielder_right),
+            (PlayerNumber::Six, parameters.midfielder_left),
+            (PlayerNumber::Seven, parameters.striker),
+        ]
+    }
+
+    #[test]
+    fn ready_roles_follow_the_robot_positions() {
+        let roles = assign_ready_roles_at(positions_with_swapped_defenders(), &Players::default());
+
+        assert_eq!(roles.one, Role::Keeper);
+        assert_eq!(roles.two, Role::DefenderRight);
+        assert_eq!(roles.three, Role::DefenderLeft);
+        assert_eq!(roles.four, Role::StrikerSupporter);
+        assert_eq!(roles.seven, Role::Striker);
+    }
+
+    #[test]
+    fn penalized_robots_keep_their_ready_role() {
+        let penalties = Players {
+            two: Some(Penalty::PlayerPushing {
+                remaining: Duration::from_secs(30),
+            }),
+            ..Default::default()
+        };
+
+        let roles = assign_ready_roles_at(positions_with_swapped_defenders(), &penalties);
+
+        assert_eq!(roles.two, Role::DefenderLeft);
+        assert_eq!(roles.three, Role::DefenderRight);
+    }
+
+    fn reduced_formations() -> ReducedFormationParameters {
+        TestParameters::load_default()
+            .unwrap()
+            .get("role_assignment.reduced_formations")
+            .unwrap()
+    }
+
+    fn seconds_ago(now: SystemTime, seconds: u64) -> Option<SystemTime> {
+        Some(now - Duration::from_secs(seconds))
+    }
+
+    #[test]
+    fn silent_teammates_become_unresponsive() {
+        let parameters = reduced_formations();
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let silent_for = parameters.unresponsive_timeout.as_secs() + 10;
+        let last_message_times = Players {
+            two: seconds_ago(now, silent_for),
+            three: seconds_ago(now, 10),
+            five: seconds_ago(now, 2 * silent_for),
+            ..Default::default()
+        };
+
+        let unresponsive = unresponsive_players(
+            &last_message_times,
+            PlayerNumber::Five,
+            now,
+            &parameters,
+            1.0,
+        );
+        assert!(unresponsive.two);
+        assert!(!unresponsive.three);
+        assert!(
+            !unresponsive.four,
+            "never heard of teammates are not missed"
+        );
+        assert!(!unresponsive.five, "the own robot is never unresponsive");
+
+        let unresponsive = unresponsive_players(
+            &last_message_times,
+            PlayerNumber::Five,
+            now,
+            &parameters,
+            2.0,
+        );
+        assert!(
+            !unresponsive.two,
+            "the timeout stretches with the heartbeats"
+        );
+    }
+
+    #[test]
+    fn penalized_keeper_is_replaced_and_roles_move_up() {
+        let penalties = Players {
+            one: Some(Penalty::PlayerPushing {
+                remaining: Duration::from_secs(30),
+            }),
+            ..Default::default()
+        };
+        let times_to_reach_kick_position = Players::default();
+        let current_roles = Players::default();
+        let role = |player| {
+            pick_role_with_penalties(
+                player,
+                &penalties,
+                PlayerNumber::Seven,
+                formation(&times_to_reach_kick_position, &current_roles),
+            )
+        };
+
+        assert_eq!(role(PlayerNumber::Two), Role::ReplacementKeeper);
+        assert_eq!(role(PlayerNumber::Three), Role::DefenderLeft);
+        assert_eq!(role(PlayerNumber::Four), Role::DefenderRight);
+        assert_eq!(role(PlayerNumber::Five), Role::StrikerSupporter);
+        assert_eq!(role(PlayerNumber::Six), Role::MidfielderRight);
+    }
+
+    #[test]
+    fn replacement_keeper_follows_the_player_order_without_all_capabilities() {
+        let penalties = Players {
+            one: Some(Penalty::PlayerPushing {
+                remaining: Duration::from_secs(30),
+            }),
+            ..Default::default()
+        };
+        let times_to_reach_kick_position = Players::default();
+        let current_roles = Players::default();
+        let role = |player, keeper_incapable_players| {
+            pick_role_with_penalties(
+                player,
+                &penalties,
+                PlayerNumber::Seven,
+                Formation {
+                    keeper_incapable_players,
+                    ..formation(&times_to_reach_kick_position, &current_roles)
+                },
+            )
+        };
+
+        let all_known = Players {
+            one: Some(false),
+            two: Some(true),
+            three: Some(false),
+            four: Some(false),
+            five: Some(false),
+            six: Some(false),
+            seven: Some(false),
+        };
+        assert_eq!(
+            role(PlayerNumber::Three, &all_known),
+            Role::ReplacementKeeper
+        );
+
+        let three_unknown = Players {
+            three: None,
+            ..all_known
+        };
+        assert_eq!(
+            role(PlayerNumber::Two, &three_unknown),
+            Role::ReplacementKeeper
+        );
+        assert_ne!(
+            role(PlayerNumber::Three, &three_unknown),
+            Role::ReplacementKeeper
+        );
+    }
+
+    #[test]
+    fn fallen_striker_hands_over_to_teammate_and_reclaims_after_standing_up() {
+        let fallen_time = Duration::from_secs(1800);
+        let own_time = Duration::from_secs(3);
+        let times_to_reach_kick_position = Players::default();
+        let current_roles = Players::default();
+        let message = HulkMessage {
+            player_number: PlayerNumber::Three,
+            time_to_reach_kick_position: seconds(5.0),
+            ..Default::default()
+        };
+        let claims = |current_role, is_fallen| {
+            decide_if_claiming_striker_or_other_role(
+                &message,
+                StrikerClaim {
+                    current_role,
+                    time_to_reach_kick_position: Some(reported_time_to_reach_kick_position(
+                        is_fallen,
+                        own_time,
+                        fallen_time,
+                    )),
+                    player_number: PlayerNumber::Four,
+                    cycle_start_time: SystemTime::UNIX_EPOCH,
+                    filtered_game_controller_state: None,
+                    formation: formation(&times_to_reach_kick_position, &current_roles),
+                },
+            )
+            .1
+        };
+
+        assert_eq!(
+            reported_time_to_reach_kick_position(true, own_time, fallen_time),
+            fallen_time
+        );
+        assert!(!claims(Role::Striker, true));
+        assert!(claims(Role::DefenderRight, false));
+    }
+
+    #[test]
+    fn unresponsive_teammates_lead_to_a_reduced_formation() {
+        let parameters = reduced_formations();
+        let unresponsive_players = Players {
+            three: true,
+            four: true,
+            five: true,
+            ..Default::default()
+        };
+        let times_to_reach_kick_position = Players::default();
+        let current_roles = Players::default();
+        let role = |player| {
+            pick_role_with_penalties(
+                player,
+                &Players::default(),
+                PlayerNumber::Seven,
+                Formation {
+                    reduced_formations: &parameters.formations,
+                    unresponsive_players: &unresponsive_players,
+                    ..formation(&times_to_reach_kick_position, &current_roles)
+                },
+            )
+        };
+
+        assert_eq!(role(PlayerNumber::One), Role::Keeper);
+        assert_eq!(role(PlayerNumber::Two), Role::DefenderLeft);
+        assert_eq!(role(PlayerNumber::Six), Role::StrikerSupporter);
+    }
+
+    #[test]
+    fn robot_with_higher_number_yields_a_formation_role_announced_twice() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let heartbeat_interval = Duration::from_secs(25);
+        let last_known_roles = Players {
+            two: Some(Role::DefenderLeft),
+            six: Some(Role::DefenderLeft),
+            ..Default::default()
+        };
+        let last_message_times = Players {
+            two: seconds_ago(now, 5),
+            six: seconds_ago(now, 5),
+            ..Default::default()
+        };
+        let has_conflict = |own_role, own_player_number| {
+            has_formation_role_conflict(
+                own_role,
+                own_player_number,
+                &last_known_roles,
+                &last_message_times,
+                now,
+                heartbeat_interval,
+            )
+        };
+
+        assert!(has_conflict(Role::DefenderLeft, PlayerNumber::Six));
+        assert!(!has_conflict(Role::DefenderLeft, PlayerNumber::Two));
+        assert!(!has_conflict(Role::DefenderRight, PlayerNumber::Six));
+        assert!(!has_conflict(Role::Striker, PlayerNumber::Six));
+    }
+
+    #[test]
+    fn returning_robot_follows_the_latest_striker() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let striker_message_timeout = Duration::from_secs(5);
+        let times_to_reach_kick_position = Players::default();
+        let current_roles = Players {
+            two: Some(Role::Striker),
+            four: Some(Role::Striker),
+            six: Some(Role::Striker),
+            ..Default::default()
+        };
+        let last_message_times = Players {
+            two: seconds_ago(now, 3),
+            four: seconds_ago(now, 1),
+            six: seconds_ago(now, 30),
+            ..Default::default()
+        };
+        let role_after_penalty = |own_player_number, last_message_times| {
+            pick_role_after_penalty(
+                own_player_number,
+                &Players::default(),
+                last_message_times,
+                now,
+                striker_message_timeout,
+                formation(&times_to_reach_kick_position, &current_roles),
+            )
+        };
+
+        assert_eq!(
+            role_after_penalty(PlayerNumber::Five, &last_message_times),
+            roles_with_striker(
+                PlayerNumber::Four,
+                &times_to_reach_kick_position,
+                &current_roles
+            )
+            .five
+        );
+
+        let without_recent_striker = Players {
+            four: None,
+            ..last_message_times
+        };
+        assert_eq!(
+            role_after_penalty(PlayerNumber::Five, &without_recent_striker),
+            roles_with_striker(
+                PlayerNumber::Two,
+                &times_to_reach_kick_position,
+                &current_roles
+            )
+            .five
+        );
+
+        let outdated = Players {
+            two: seconds_ago(now, 10),
+            four: seconds_ago(now, 10),
+            ..last_message_times
+        };
+        assert_eq!(
+            role_after_penalty(PlayerNumber::Five, &outdated),
+            Role::Searcher
+        );
+    }
+}
@@ -8,7 +8,8 @@ use linear_algebra::{point, vector, Point};
 use spl_network_messages::{SubState, Team};
 use types::{
     field_dimensions::FieldDimensions, filtered_game_controller_state::FilteredGameControllerState,
-    filtered_game_state::FilteredGameState, rule_obstacles::RuleObstacle, world_state::BallState,
+    filtered_game_state::FilteredGameState, rule_obstacles::RuleObstacle, rule_set::RuleSet,
+    world_state::BallState,
 };
 
 #[derive(Deserialize, Serialize)]
@@ -26,8 +27,8 @@ pub struct CycleContext {
     center_circle_obstacle_increase:
         Parameter<f32, "rule_obstacles.center_circle_obstacle_increase">,
     field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
-    free_kick_obstacle_radius: Parameter<f32, "rule_obstacles.free_kick_obstacle_radius">,
     penaltykick_box_extension: Parameter<f32, "rule_obstacles.penaltykick_box_extension">,
+    rule_set: Parameter<RuleSet, "rule_set">,
 }
 
 #[context]
@@ -61,7 +62,7 @@ impl RuleObstacleComposer {
             ) => {
                 let free_kick_obstacle = RuleObstacle::Circle(Circle::new(
                     ball.ball_in_field,
-                    *context.free_kick_obstacle_radius,
+                    context.rule_set.free_kick_distance(),
                 ));
                 rule_obstacles.push(free_kick_obstacle);
             }
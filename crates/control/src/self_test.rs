@@ -0,0 +1,259 @@
+use std::{f32::consts::TAU, fs::File, time::SystemTime};
+
+use color_eyre::{eyre::WrapErr, Result};
+use context_attribute::context;
+use framework::{MainOutput, PerceptionInput};
+use hardware::SpeakerInterface;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use spl_network_messages::IncomingMessage;
+use types::{
+    audio::{Sound, SpeakerRequest},
+    cycle_time::CycleTime,
+    horizon_crop::HorizonCrop,
+    joints::Joints,
+    motor_commands::MotorCommands,
+    parameters::SelfTestParameters,
+    primary_state::PrimaryState,
+    self_test::{CheckResult, SelfTestReport},
+    sensor_data::SensorData,
+};
+
+#[derive(Deserialize, Serialize)]
+pub struct SelfTest {
+    state: State,
+    maximum_joint_errors: Joints<f32>,
+    number_of_top_camera_updates: usize,
+    number_of_bottom_camera_updates: usize,
+    number_of_network_messages: usize,
+    sonar_readings_valid: bool,
+    maximum_foot_pressure: f32,
+}
+
+#[derive(Clone, Copy, Deserialize, Serialize)]
+enum State {
+    Pending,
+    Running { start_time: SystemTime },
+    Finished,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    cycle_time: Input<CycleTime, "cycle_time">,
+    primary_state: Input<PrimaryState, "primary_state">,
+    sensor_data: Input<SensorData, "sensor_data">,
+
+    horizon_crop_top: PerceptionInput<HorizonCrop, "VisionTop", "horizon_crop">,
+    horizon_crop_bottom: PerceptionInput<HorizonCrop, "VisionBottom", "horizon_crop">,
+    network_message: PerceptionInput<Option<IncomingMessage>, "SplNetwork", "filtered_message?">,
+
+    last_actuated_motor_commands:
+        CyclerState<MotorCommands<Joints<f32>>, "last_actuated_motor_commands">,
+
+    joint_calibration_offsets: Parameter<Joints<f32>, "joint_calibration_offsets">,
+    parameters: Parameter<SelfTestParameters, "self_test">,
+
+    hardware_interface: HardwareInterface,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub self_test_joint_offsets: MainOutput<Joints<f32>>,
+    pub self_test_report: MainOutput<Option<SelfTestReport>>,
+}
+
+impl SelfTest {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            state: State::Pending,
+            maximum_joint_errors: Joints::fill(0.0),
+            number_of_top_camera_updates: 0,
+            number_of_bottom_camera_updates: 0,
+            number_of_network_messages: 0,
+            sonar_readings_valid: false,
+            maximum_foot_pressure: 0.0,
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext<impl SpeakerInterface>) -> Result<MainOutputs> {
+        let parameters = context.parameters;
+        let now = context.cycle_time.start_time;
+
+        self.state = match (self.state, *context.primary_state) {
+            (State::Pending, PrimaryState::Initial) if parameters.enable => {
+                info!("starting self-test");
+                self.reset();
+                State::Running { start_time: now }
+            }
+            (State::Running { .. }, primary_state) if primary_state != PrimaryState::Initial => {
+                warn!("self-test aborted, will restart in next initial");
+                State::Pending
+            }
+            (state, _) => state,
+        };
+
+        let State::Running { start_time } = self.state else {
+            return Ok(MainOutputs {
+                self_test_joint_offsets: Joints::fill(0.0).into(),
+                self_test_report: None.into(),
+            });
+        };
+
+        let elapsed = now.duration_since(start_time).unwrap_or_default();
+        if elapsed >= parameters.duration {
+            let report = self.generate_report(parameters);
+            self.finish(&report, &context);
+            self.state = State::Finished;
+            return Ok(MainOutputs {
+                self_test_joint_offsets: Joints::fill(0.0).into(),
+                self_test_report: Some(report).into(),
+            });
+        }
+
+        self.collect_measurements(&context);
+
+        let phase = TAU * elapsed.as_secs_f32() / parameters.duration.as_secs_f32();
+        let self_test_joint_offsets = parameters.joint_amplitudes * phase.sin();
+
+        Ok(MainOutputs {
+            self_test_joint_offsets: self_test_joint_offsets.into(),
+            self_test_report: None.into(),
+        })
+    }
+
+    fn reset(&mut self) {
+        self.maximum_joint_errors = Joints::fill(0.0);
+        self.number_of_top_camera_updates = 0;
+        self.number_of_bottom_camera_updates = 0;
+        self.number_of_network_messages = 0;
+        self.sonar_readings_valid = false;
+        self.maximum_foot_pressure = 0.0;
+    }
+
+    fn collect_measurements(&mut self, context: &CycleContext<impl SpeakerInterface>) {
+        // the actuated commands contain the calibration offsets, the measured positions do not
+        let requested_positions =
+            context.last_actuated_motor_commands.positions - *context.joint_calibration_offsets;
+        let joint_errors = context.sensor_data.positions - requested_positions;
+        for (joint, error) in joint_errors.enumerate() {
+            self.maximum_joint_errors[joint] = self.maximum_joint_errors[joint].max(error.abs());
+        }
+
+        self.number_of_top_camera_updates += context
+            .horizon_crop_top
+            .persistent
+            .values()
+            .flatten()
+            .count();
+        self.number_of_bottom_camera_updates += context
+            .horizon_crop_bottom
+            .persistent
+            .values()
+            .flatten()
+            .count();
+        self.number_of_network_messages += context
+            .network_message
+            .persistent
+            .values()
+            .flatten()
+            .filter(|message| message.is_some())
+            .count();
+
+        let sonar = &context.sensor_data.sonar_sensors;
+        let valid_sonar_range = &context.parameters.valid_sonar_range;
+        self.sonar_readings_valid |=
+            valid_sonar_range.contains(&sonar.left) && valid_sonar_range.contains(&sonar.right);
+
+        let force_sensitive_resistors = &context.sensor_data.force_sensitive_resistors;
+        let foot_pressure =
+            force_sensitive_resistors.left.sum() + force_sensitive_resistors.right.sum();
+        self.maximum_foot_pressure = self.maximum_foot_pressure.max(foot_pressure);
+    }
+
+    fn generate_report(&self, parameters: &SelfTestParameters) -> SelfTestReport {
+        let failed_joints: Vec<_> = self
+            .maximum_joint_errors
+            .enumerate()
+            .filter(|(_, error)| *error > parameters.maximum_joint_error)
+            .map(|(joint, error)| format!("{joint:?} deviated by {error:.3} rad"))
+            .collect();
+        let joints = if failed_joints.is_empty() {
+            CheckResult::Passed
+        } else {
+            CheckResult::Failed {
+                reason: failed_joints.join(", "),
+            }
+        };
+        let top_camera = check_updates(self.number_of_top_camera_updates, "top camera images");
+        let bottom_camera =
+            check_updates(self.number_of_bottom_camera_updates, "bottom camera images");
+        let network = check_updates(self.number_of_network_messages, "network messages");
+        let sonar = if self.sonar_readings_valid {
+            CheckResult::Passed
+        } else {
+            CheckResult::Failed {
+                reason: format!(
+                    "no sonar readings within {:?}",
+                    parameters.valid_sonar_range
+                ),
+            }
+        };
+        let force_sensitive_resistors =
+            if self.maximum_foot_pressure >= parameters.minimum_foot_pressure {
+                CheckResult::Passed
+            } else {
+                CheckResult::Failed {
+                    reason: format!(
+                        "maximum foot pressure {:.3} below {:.3}",
+                        self.maximum_foot_pressure, parameters.minimum_foot_pressure
+                    ),
+                }
+            };
+
+        SelfTestReport {
+            joints,
+            top_camera,
+            bottom_camera,
+            sonar,
+            force_sensitive_resistors,
+            network,
+        }
+    }
+
+    fn finish(&self, report: &SelfTestReport, context: &CycleContext<impl SpeakerInterface>) {
+        let sound = if report.is_passed() {
+            info!("self-test passed");
+            Sound::Weeeee
+        } else {
+            warn!("self-test failed: {report:?}");
+            Sound::Sigh
+        };
+        context
+            .hardware_interface
+            .write_to_speakers(SpeakerRequest::PlaySound { sound });
+
+        if let Err(error) = write_report(report, context.parameters) {
+            warn!("failed to write self-test report: {error:#}");
+        }
+    }
+}
+
+fn check_updates(number_of_updates: usize, description: &str) -> CheckResult {
+    if number_of_updates > 0 {
+        CheckResult::Passed
+    } else {
+        CheckResult::Failed {
+            reason: format!("received no {description}"),
+        }
+    }
+}
+
+fn write_report(report: &SelfTestReport, parameters: &SelfTestParameters) -> Result<()> {
+    let file = File::create(&parameters.report_path)
+        .wrap_err_with(|| format!("failed to create {}", parameters.report_path.display()))?;
+    serde_json::to_writer_pretty(file, report).wrap_err("failed to serialize self-test report")
+}
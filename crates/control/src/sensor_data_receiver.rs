@@ -3,7 +3,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use color_eyre::{eyre::WrapErr, Result};
 use context_attribute::context;
 use framework::{AdditionalOutput, MainOutput};
-use hardware::{SensorInterface, TimeInterface};
+use hardware::{SensorInterface, ShutdownInterface, TimeInterface};
 use serde::{Deserialize, Serialize};
 use types::{cycle_time::CycleTime, joints::Joints, sensor_data::SensorData};
 
@@ -28,6 +28,7 @@ pub struct CycleContext {
 pub struct MainOutputs {
     pub sensor_data: MainOutput<SensorData>,
     pub cycle_time: MainOutput<CycleTime>,
+    pub is_shutdown_requested: MainOutput<bool>,
 }
 
 impl SensorDataReceiver {
@@ -39,7 +40,7 @@ impl SensorDataReceiver {
 
     pub fn cycle(
         &mut self,
-        mut context: CycleContext<impl SensorInterface + TimeInterface>,
+        mut context: CycleContext<impl SensorInterface + ShutdownInterface + TimeInterface>,
     ) -> Result<MainOutputs> {
         let mut sensor_data = context
             .hardware_interface
@@ -71,6 +72,7 @@ impl SensorDataReceiver {
         Ok(MainOutputs {
             sensor_data: sensor_data.into(),
             cycle_time: cycle_time.into(),
+            is_shutdown_requested: context.hardware_interface.is_shutdown_requested().into(),
         })
     }
 }
@@ -0,0 +1,426 @@
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use context_attribute::context;
+use coordinate_systems::{Field, Ground};
+use filtering::hysteresis::greater_than_with_hysteresis;
+use framework::MainOutput;
+use geometry::line_segment::LineSegment;
+use linear_algebra::{distance, point, Isometry2, Point2};
+use spl_network_messages::StrikerIntent;
+use types::{
+    field_dimensions::FieldDimensions,
+    filtered_game_controller_state::FilteredGameControllerState,
+    obstacles::{opponents, Obstacle},
+    parameters::{EndgameParameters, OpportunisticShotParameters, ShotSelectionParameters},
+    shot_selection::{ShotFeatures, ShotSelection, StrikerDecision},
+    world_state::BallState,
+};
+
+#[derive(Deserialize, Serialize)]
+pub struct ShotSelector {
+    is_goal_open: bool,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    ground_to_field: RequiredInput<Option<Isometry2<Ground, Field>>, "ground_to_field?">,
+    ball_state: RequiredInput<Option<BallState>, "ball_state?">,
+    obstacles: Input<Vec<Obstacle>, "obstacles">,
+    network_robot_obstacles: Input<Vec<Point2<Ground>>, "network_robot_obstacles">,
+    filtered_game_controller_state:
+        Input<Option<FilteredGameControllerState>, "filtered_game_controller_state?">,
+
+    field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
+    parameters: Parameter<ShotSelectionParameters, "behavior.shot_selection">,
+    endgame: Parameter<EndgameParameters, "behavior.endgame">,
+    teammate_matching_distance: Parameter<f32, "obstacle_filter.teammate_matching_distance">,
+
+    striker_intent: CyclerState<Option<StrikerIntent>, "striker_intent">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub shot_selection: MainOutput<Option<ShotSelection>>,
+}
+
+impl ShotSelector {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            is_goal_open: false,
+        })
+    }
+
+    pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
+        let ground_to_field = *context.ground_to_field;
+        let parameters = context.parameters;
+        let field_dimensions = context.field_dimensions;
+
+        let teammates: Vec<Point2<Field>> = context
+            .network_robot_obstacles
+            .iter()
+            .map(|position| ground_to_field * *position)
+            .collect();
+        let opponents: Vec<Opponent> = opponents(
+            context.obstacles,
+            &teammates,
+            ground_to_field,
+            *context.teammate_matching_distance,
+        )
+        .map(|obstacle| Opponent {
+            position: ground_to_field * obstacle.position,
+            radius: obstacle.radius_at_foot_height,
+        })
+        .collect();
+
+        let ball = context.ball_state.ball_in_field;
+        let features = shot_features(ball, &opponents, field_dimensions, parameters);
+        let expected_goals_shot = expected_goals(&features, parameters);
+
+        let goal_center = point![field_dimensions.length / 2.0, 0.0];
+        let dribble_length = parameters.dribble_distance.min(distance(ball, goal_center));
+        let dribble_position = if dribble_length > f32::EPSILON {
+            ball + (goal_center - ball).normalize() * dribble_length
+        } else {
+            ball
+        };
+        let dribble_features =
+            shot_features(dribble_position, &opponents, field_dimensions, parameters);
+        let expected_goals_dribble = expected_goals(&dribble_features, parameters)
+            * (1.0 - parameters.dribble_loss_per_pressure * features.opponent_pressure).max(0.0);
+
+        let best_pass = teammates
+            .iter()
+            .filter(|teammate| distance(ball, **teammate) >= parameters.minimum_pass_distance)
+            .filter(|teammate| is_lane_free(ball, **teammate, &opponents))
+            .map(|teammate| {
+                let receiver_features =
+                    shot_features(*teammate, &opponents, field_dimensions, parameters);
+                let pass_success =
+                    (-distance(ball, *teammate) / parameters.pass_distance_scale).exp();
+                (
+                    *teammate,
+                    pass_success * expected_goals(&receiver_features, parameters),
+                )
+            })
+            .max_by(|(_, left), (_, right)| left.total_cmp(right));
+
+        let is_keeper_displaced = opponents.iter().all(|opponent| {
+            distance(opponent.position, goal_center)
+                > parameters.opportunistic_shot.keeper_displacement_distance
+        });
+        self.is_goal_open = is_goal_open(
+            self.is_goal_open,
+            &features,
+            is_keeper_displaced,
+            &parameters.opportunistic_shot,
+        );
+
+        let is_half_ending = context.endgame.enable
+            && context
+                .filtered_game_controller_state
+                .is_some_and(|game_controller_state| {
+                    game_controller_state
+                        .is_half_ending_within(context.endgame.immediate_shot_duration)
+                });
+
+        let decision = decide(
+            expected_goals_shot,
+            expected_goals_dribble,
+            best_pass.map(|(target, expected_goals_pass)| {
+                (ground_to_field.inverse() * target, expected_goals_pass)
+            }),
+            self.is_goal_open,
+            is_half_ending,
+        );
+        // teammates learn about the decision with the next team message
+        *context.striker_intent = Some(match decision {
+            StrikerDecision::Shoot => StrikerIntent::Shot,
+            StrikerDecision::Dribble => StrikerIntent::Dribble,
+            StrikerDecision::Pass { .. } => StrikerIntent::Pass,
+        });
+
+        Ok(MainOutputs {
+            shot_selection: Some(ShotSelection {
+                features,
+                expected_goals_shot,
+                expected_goals_dribble,
+                expected_goals_pass: best_pass.map(|(_, expected_goals_pass)| expected_goals_pass),
+                is_goal_open: self.is_goal_open,
+                decision,
+            })
+            .into(),
+        })
+    }
+}
+
+/// Picks the option most likely to end in a goal, unless there is a reason to shoot right away.
+fn decide(
+    expected_goals_shot: f32,
+    expected_goals_dribble: f32,
+    best_pass: Option<(Point2<Ground>, f32)>,
+    is_goal_open: bool,
+    is_half_ending: bool,
+) -> StrikerDecision {
+    match best_pass {
+        // the goal opened up, shoot right away instead of continuing the planned dribbling
+        _ if is_goal_open => StrikerDecision::Shoot,
+        // there is no time left to set up a pass or dribble closer
+        _ if is_half_ending => StrikerDecision::Shoot,
+        Some((target, expected_goals_pass))
+            if expected_goals_pass > expected_goals_shot.max(expected_goals_dribble) =>
+        {
+            StrikerDecision::Pass { target }
+        }
+        _ if expected_goals_dribble > expected_goals_shot => StrikerDecision::Dribble,
+        _ => StrikerDecision::Shoot,
+    }
+}
+
+struct Opponent {
+    position: Point2<Field>,
+    radius: f32,
+}
+
+fn shot_features(
+    position: Point2<Field>,
+    opponents: &[Opponent],
+    field_dimensions: &FieldDimensions,
+    parameters: &ShotSelectionParameters,
+) -> ShotFeatures {
+    let goal_x = field_dimensions.length / 2.0;
+    let left_post = point![goal_x, field_dimensions.goal_inner_width / 2.0];
+    let right_post = point![goal_x, -field_dimensions.goal_inner_width / 2.0];
+    let direction_to = |target: Point2<Field>| {
+        let offset = target - position;
+        offset.y().atan2(offset.x())
+    };
+    let right_angle = direction_to(right_post);
+    let left_angle = direction_to(left_post);
+    let opening_angle = if position.x() < goal_x {
+        (left_angle - right_angle).max(0.0)
+    } else {
+        0.0
+    };
+
+    let mut shadows: Vec<(f32, f32)> = opponents
+        .iter()
+        .filter(|opponent| opponent.position.x() > position.x())
+        .filter_map(|opponent| {
+            let distance = distance(position, opponent.position);
+            if distance <= opponent.radius {
+                return Some((right_angle, left_angle));
+            }
+            let center = direction_to(opponent.position);
+            let half_width = (opponent.radius / distance).asin();
+            let start = (center - half_width).max(right_angle);
+            let end = (center + half_width).min(left_angle);
+            (start < end).then_some((start, end))
+        })
+        .collect();
+    shadows.sort_by(|left, right| left.0.total_cmp(&right.0));
+    let mut covered_angle = 0.0;
+    let mut covered_until = right_angle;
+    for (start, end) in shadows {
+        let start = start.max(covered_until);
+        if end > start {
+            covered_angle += end - start;
+            covered_until = end;
+        }
+    }
+    let keeper_coverage = if opening_angle > 0.0 {
+        (covered_angle / opening_angle).clamp(0.0, 1.0)
+    } else {
+        1.0
+    };
+
+    let opponent_pressure = opponents
+        .iter()
+        .map(|opponent| {
+            (1.0 - distance(position, opponent.position) / parameters.pressure_radius).max(0.0)
+        })
+        .sum();
+
+    ShotFeatures {
+        distance_to_goal: distance(position, point![goal_x, 0.0]),
+        opening_angle,
+        keeper_coverage,
+        opponent_pressure,
+    }
+}
+
+fn is_goal_open(
+    was_goal_open: bool,
+    features: &ShotFeatures,
+    is_keeper_displaced: bool,
+    parameters: &OpportunisticShotParameters,
+) -> bool {
+    let free_angle = features.opening_angle * (1.0 - features.keeper_coverage);
+    parameters.enable
+        && is_keeper_displaced
+        && features.distance_to_goal < parameters.maximum_distance_to_goal
+        && greater_than_with_hysteresis(
+            was_goal_open,
+            free_angle,
+            parameters.minimum_free_angle,
+            parameters.free_angle_hysteresis,
+        )
+}
+
+/// Logistic model mapping shot features to the probability of scoring.
+fn expected_goals(features: &ShotFeatures, parameters: &ShotSelectionParameters) -> f32 {
+    let logit = parameters.intercept
+        + parameters.distance_weight * features.distance_to_goal
+        + parameters.opening_angle_weight * features.opening_angle
+        + parameters.keeper_coverage_weight * features.keeper_coverage
+        + parameters.opponent_pressure_weight * features.opponent_pressure;
+    1.0 / (1.0 + (-logit).exp())
+}
+
+fn is_lane_free(start: Point2<Field>, end: Point2<Field>, opponents: &[Opponent]) -> bool {
+    let lane = LineSegment::new(start, end);
+    opponents
+        .iter()
+        .all(|opponent| lane.shortest_distance_to_point(opponent.position) > opponent.radius)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameters() -> ShotSelectionParameters {
+        ShotSelectionParameters {
+            intercept: 1.0,
+            distance_weight: -0.8,
+            opening_angle_weight: 2.0,
+            keeper_coverage_weight: -3.0,
+            opponent_pressure_weight: -0.5,
+            pressure_radius: 1.0,
+            dribble_distance: 1.0,
+            dribble_loss_per_pressure: 0.3,
+            minimum_pass_distance: 1.0,
+            pass_distance_scale: 4.0,
+            dribble_kick_strength: 0.5,
+            opportunistic_shot: OpportunisticShotParameters {
+                enable: true,
+                minimum_free_angle: 0.25,
+                free_angle_hysteresis: 0.05,
+                keeper_displacement_distance: 0.8,
+                maximum_distance_to_goal: 3.5,
+            },
+        }
+    }
+
+    fn field_dimensions() -> FieldDimensions {
+        FieldDimensions {
+            length: 9.0,
+            width: 6.0,
+            goal_inner_width: 1.5,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn expected_goals_decrease_with_distance() {
+        let field_dimensions = field_dimensions();
+        let parameters = parameters();
+        let close = shot_features(point![3.0, 0.0], &[], &field_dimensions, &parameters);
+        let far = shot_features(point![-1.0, 0.0], &[], &field_dimensions, &parameters);
+
+        assert!(close.opening_angle > far.opening_angle);
+        assert!(expected_goals(&close, &parameters) > expected_goals(&far, &parameters));
+    }
+
+    #[test]
+    fn keeper_in_front_of_goal_covers_opening() {
+        let field_dimensions = field_dimensions();
+        let parameters = parameters();
+        let keeper = Opponent {
+            position: point![4.3, 0.0],
+            radius: 0.3,
+        };
+        let open = shot_features(point![3.0, 0.0], &[], &field_dimensions, &parameters);
+        let covered = shot_features(point![3.0, 0.0], &[keeper], &field_dimensions, &parameters);
+
+        assert_eq!(open.keeper_coverage, 0.0);
+        assert!(covered.keeper_coverage > 0.2);
+        assert!(expected_goals(&covered, &parameters) < expected_goals(&open, &parameters));
+    }
+
+    #[test]
+    fn decision_follows_the_highest_expected_goals() {
+        let target = point![1.0, 2.0];
+
+        assert!(matches!(
+            decide(0.3, 0.2, None, false, false),
+            StrikerDecision::Shoot
+        ));
+        assert!(matches!(
+            decide(0.2, 0.3, None, false, false),
+            StrikerDecision::Dribble
+        ));
+        assert!(matches!(
+            decide(0.2, 0.3, Some((target, 0.4)), false, false),
+            StrikerDecision::Pass { target: pass_target } if pass_target == target
+        ));
+        assert!(matches!(
+            decide(0.2, 0.4, Some((target, 0.3)), false, false),
+            StrikerDecision::Dribble
+        ));
+    }
+
+    #[test]
+    fn open_goal_and_ending_half_shoot_right_away() {
+        let target = point![1.0, 2.0];
+
+        assert!(matches!(
+            decide(0.1, 0.3, Some((target, 0.4)), true, false),
+            StrikerDecision::Shoot
+        ));
+        assert!(matches!(
+            decide(0.1, 0.3, Some((target, 0.4)), false, true),
+            StrikerDecision::Shoot
+        ));
+    }
+
+    #[test]
+    fn open_goal_is_kept_within_hysteresis() {
+        let field_dimensions = field_dimensions();
+        let parameters = parameters();
+        let features = shot_features(point![3.0, 0.0], &[], &field_dimensions, &parameters);
+        let barely_open = ShotFeatures {
+            keeper_coverage: 1.0
+                - parameters.opportunistic_shot.minimum_free_angle / features.opening_angle,
+            ..features
+        };
+
+        assert!(is_goal_open(
+            false,
+            &features,
+            true,
+            &parameters.opportunistic_shot
+        ));
+        assert!(!is_goal_open(
+            false,
+            &features,
+            false,
+            &parameters.opportunistic_shot
+        ));
+        assert!(!is_goal_open(
+            false,
+            &barely_open,
+            true,
+            &parameters.opportunistic_shot
+        ));
+        assert!(is_goal_open(
+            true,
+            &barely_open,
+            true,
+            &parameters.opportunistic_shot
+        ));
+    }
+}
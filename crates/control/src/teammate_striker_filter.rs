@@ -0,0 +1,212 @@
+use std::{
+    collections::BTreeMap,
+    time::{Duration, SystemTime},
+};
+
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use context_attribute::context;
+use framework::{MainOutput, PerceptionInput};
+use spl_network_messages::{PlayerNumber, StrikerIntent};
+use types::{
+    cycle_time::CycleTime, messages::IncomingMessage, parameters::TeammateStrikerParameters,
+    roles::Role, world_state::TeammateStriker,
+};
+
+/// Tracks which teammates announce the striker role, together with their time to reach the ball
+/// and what they intend to do with it, such that two strikers do not chase the same ball while
+/// the role assignment is still resolving the conflict.
+#[derive(Deserialize, Serialize)]
+pub struct TeammateStrikerFilter {
+    strikers: BTreeMap<PlayerNumber, AnnouncedStriker>,
+}
+
+#[derive(Clone, Copy, Deserialize, Serialize)]
+struct AnnouncedStriker {
+    intent: Option<StrikerIntent>,
+    time_to_reach_kick_position: Option<Duration>,
+    received_at: SystemTime,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    cycle_time: Input<CycleTime, "cycle_time">,
+    network_message: PerceptionInput<Option<IncomingMessage>, "SplNetwork", "filtered_message?">,
+
+    parameters: Parameter<TeammateStrikerParameters, "behavior.teammate_striker">,
+    player_number: Parameter<PlayerNumber, "player_number">,
+
+    time_to_reach_kick_position: CyclerState<Duration, "time_to_reach_kick_position">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub teammate_striker: MainOutput<Option<TeammateStriker>>,
+}
+
+impl TeammateStrikerFilter {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            strikers: BTreeMap::new(),
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        let parameters = context.parameters;
+        let now = context.cycle_time.start_time;
+
+        for (receive_time, message) in context
+            .network_message
+            .persistent
+            .iter()
+            .flat_map(|(receive_time, messages)| {
+                messages.iter().map(move |message| (*receive_time, message))
+            })
+            .filter_map(|(receive_time, message)| match message {
+                Some(IncomingMessage::Spl(message)) => Some((receive_time, message)),
+                _ => None,
+            })
+            .filter(|(_, message)| message.player_number != *context.player_number)
+        {
+            if message.role == Role::Striker {
+                self.strikers.insert(
+                    message.player_number,
+                    AnnouncedStriker {
+                        intent: message.striker_intent,
+                        time_to_reach_kick_position: message.time_to_reach_kick_position,
+                        received_at: receive_time,
+                    },
+                );
+            } else {
+                self.strikers.remove(&message.player_number);
+            }
+        }
+        self.strikers.retain(|_, striker| {
+            now.duration_since(striker.received_at).unwrap_or_default() < parameters.timeout
+        });
+
+        if !parameters.enable {
+            return Ok(MainOutputs {
+                teammate_striker: None.into(),
+            });
+        }
+        let own_time_to_reach_kick_position = *context.time_to_reach_kick_position;
+        let teammate_striker = self
+            .strikers
+            .iter()
+            .min_by_key(|(_, striker)| striker.time_to_reach_kick_position.unwrap_or(Duration::MAX))
+            .map(|(player_number, striker)| TeammateStriker {
+                player_number: *player_number,
+                intent: striker.intent,
+                is_faster: striker.time_to_reach_kick_position.is_some_and(
+                    |time_to_reach_kick_position| {
+                        time_to_reach_kick_position + parameters.yield_margin
+                            < own_time_to_reach_kick_position
+                    },
+                ),
+            });
+
+        Ok(MainOutputs {
+            teammate_striker: teammate_striker.into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use node_testing::{cycle_time, time, PerceptionInputBuilder, TestParameters};
+    use spl_network_messages::HulkMessage;
+
+    use super::*;
+
+    fn announcement(
+        player_number: PlayerNumber,
+        role: Role,
+        time_to_reach_kick_position: u64,
+    ) -> Option<IncomingMessage> {
+        Some(IncomingMessage::Spl(HulkMessage {
+            player_number,
+            role,
+            time_to_reach_kick_position: Some(Duration::from_secs(time_to_reach_kick_position)),
+            striker_intent: Some(StrikerIntent::Shot),
+            ..Default::default()
+        }))
+    }
+
+    fn teammate_strikers(
+        messages: &[(u64, Option<IncomingMessage>)],
+        cycles: &[u64],
+    ) -> Vec<Option<TeammateStriker>> {
+        let parameters: TeammateStrikerParameters = TestParameters::load_default()
+            .unwrap()
+            .get("behavior.teammate_striker")
+            .unwrap();
+        let mut node = TeammateStrikerFilter::new(CreationContext::new()).unwrap();
+        let mut own_time_to_reach_kick_position = Duration::from_secs(5);
+
+        cycles
+            .iter()
+            .map(|&now| {
+                // messages are only handed to the first cycle after their reception
+                let network_message = messages
+                    .iter()
+                    .filter(|(at, _)| *at <= now && now < at + 100)
+                    .fold(
+                        PerceptionInputBuilder::default(),
+                        |builder, (at, message)| {
+                            builder.persistent(time(Duration::from_millis(*at)), message.clone())
+                        },
+                    );
+                let cycle_time = cycle_time(Duration::from_millis(now), Duration::from_millis(12));
+                node.cycle(CycleContext::new(
+                    &cycle_time,
+                    network_message.build_optional(),
+                    &parameters,
+                    &PlayerNumber::One,
+                    &mut own_time_to_reach_kick_position,
+                ))
+                .unwrap()
+                .teammate_striker
+                .value
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fastest_announced_striker_is_tracked_with_its_intent() {
+        let messages = [
+            (0, announcement(PlayerNumber::Two, Role::Striker, 8)),
+            (0, announcement(PlayerNumber::Three, Role::Striker, 2)),
+        ];
+
+        let teammate_strikers = teammate_strikers(&messages, &[0]);
+
+        let teammate_striker = teammate_strikers[0].unwrap();
+        assert_eq!(teammate_striker.player_number, PlayerNumber::Three);
+        assert_eq!(teammate_striker.intent, Some(StrikerIntent::Shot));
+        assert!(teammate_striker.is_faster);
+    }
+
+    #[test]
+    fn striker_is_forgotten_after_another_role_or_silence() {
+        let messages = [
+            (0, announcement(PlayerNumber::Two, Role::Striker, 8)),
+            (0, announcement(PlayerNumber::Three, Role::Striker, 2)),
+            (1000, announcement(PlayerNumber::Three, Role::Searcher, 2)),
+        ];
+
+        let teammate_strikers = teammate_strikers(&messages, &[0, 1000, 4000]);
+
+        assert_eq!(
+            teammate_strikers[1].map(|striker| striker.player_number),
+            Some(PlayerNumber::Two)
+        );
+        assert!(!teammate_strikers[1].unwrap().is_faster);
+        assert!(teammate_strikers[2].is_none());
+    }
+}
@@ -24,6 +24,7 @@ pub struct CycleContext {
         AdditionalOutput<Option<Duration>, "time_to_reach_kick_position_output">,
 
     time_to_reach_kick_position: CyclerState<Duration, "time_to_reach_kick_position">,
+    yields_blocked_ball: CyclerState<bool, "yields_blocked_ball">,
 
     configuration: Parameter<BehaviorParameters, "behavior">,
 
@@ -89,6 +90,9 @@ impl TimeToReachKickPosition {
                 .half_rotation
                 .mul_f32(angle * FRAC_1_PI)
         });
+        // a striker giving up on a blocked ball reports the worst time such that any teammate wins
+        // the striker claim
+        let walk_time = walk_time.filter(|_| !*context.yields_blocked_ball);
         let time_to_reach_kick_position = walk_time.map(|walk_time| {
             [
                 walk_time,
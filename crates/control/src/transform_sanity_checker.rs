@@ -0,0 +1,231 @@
+use color_eyre::Result;
+use context_attribute::context;
+use coordinate_systems::{Field, Ground, Robot};
+use framework::{MainOutput, PerceptionInput};
+use linear_algebra::{Isometry2, Isometry3, Orientation2};
+use log::warn;
+use projection::{camera_matrices::CameraMatrices, camera_matrix::CameraMatrix};
+use serde::{Deserialize, Serialize};
+use types::{
+    field_border::FieldBorder,
+    field_dimensions::FieldDimensions,
+    parameters::TransformSanityCheckerParameters,
+    transform_sanity::{TransformCheck, TransformInconsistency},
+};
+
+/// Debug node cross-validating the key transforms every cycle, frame convention bugs otherwise
+/// only surface as strange behavior.
+#[derive(Deserialize, Serialize)]
+pub struct TransformSanityChecker {
+    last_ground_to_field: Option<Isometry2<Ground, Field>>,
+    last_robot_orientation: Orientation2<Field>,
+    failing_checks: Vec<TransformCheck>,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    camera_matrices: Input<Option<CameraMatrices>, "camera_matrices?">,
+    ground_to_field: Input<Option<Isometry2<Ground, Field>>, "ground_to_field?">,
+    ground_to_robot: Input<Option<Isometry3<Ground, Robot>>, "ground_to_robot?">,
+    robot_orientation: Input<Orientation2<Field>, "robot_orientation">,
+
+    field_border_top: PerceptionInput<Option<FieldBorder>, "VisionTop", "field_border?">,
+
+    field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
+    parameters: Parameter<TransformSanityCheckerParameters, "transform_sanity_checker">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub transform_inconsistencies: MainOutput<Vec<TransformInconsistency>>,
+}
+
+impl TransformSanityChecker {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {
+            last_ground_to_field: None,
+            last_robot_orientation: Orientation2::identity(),
+            failing_checks: Vec::new(),
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        let parameters = context.parameters;
+        if !parameters.enable {
+            return Ok(MainOutputs::default());
+        }
+
+        let mut inconsistencies = Vec::new();
+        if let Some(ground_to_field) = context.ground_to_field {
+            let position = ground_to_field.as_pose().position();
+            let distance_off_carpet = (position.x().abs()
+                - context.field_dimensions.length / 2.0
+                - context.field_dimensions.border_strip_width)
+                .max(
+                    position.y().abs()
+                        - context.field_dimensions.width / 2.0
+                        - context.field_dimensions.border_strip_width,
+                );
+            if distance_off_carpet > parameters.carpet_margin {
+                inconsistencies.push(TransformInconsistency {
+                    check: TransformCheck::GroundToFieldOffCarpet,
+                    error: distance_off_carpet,
+                });
+            }
+            if let Some(last_ground_to_field) = self.last_ground_to_field {
+                let localized_rotation = last_ground_to_field
+                    .orientation()
+                    .rotation_to(ground_to_field.orientation())
+                    .angle();
+                let measured_rotation = self
+                    .last_robot_orientation
+                    .rotation_to(*context.robot_orientation)
+                    .angle();
+                let discrepancy =
+                    Orientation2::<Field>::new(localized_rotation - measured_rotation)
+                        .angle()
+                        .abs();
+                if discrepancy > parameters.maximum_rotation_discrepancy {
+                    inconsistencies.push(TransformInconsistency {
+                        check: TransformCheck::GroundToFieldRotation,
+                        error: discrepancy,
+                    });
+                }
+            }
+        }
+        self.last_ground_to_field = context.ground_to_field.copied();
+        self.last_robot_orientation = *context.robot_orientation;
+
+        if let Some(camera_matrices) = context.camera_matrices {
+            if let Some(ground_to_robot) = context.ground_to_robot {
+                let error =
+                    transform_error(camera_matrices.top.ground_to_robot, *ground_to_robot).max(
+                        transform_error(camera_matrices.bottom.ground_to_robot, *ground_to_robot),
+                    );
+                if error > parameters.maximum_transform_error {
+                    inconsistencies.push(TransformInconsistency {
+                        check: TransformCheck::CameraGroundToRobot,
+                        error,
+                    });
+                }
+            }
+            let error = chain_error(&camera_matrices.top).max(chain_error(&camera_matrices.bottom));
+            if error > parameters.maximum_transform_error {
+                inconsistencies.push(TransformInconsistency {
+                    check: TransformCheck::CameraChain,
+                    error,
+                });
+            }
+            let field_border = context
+                .field_border_top
+                .persistent
+                .values()
+                .chain(context.field_border_top.temporary.values())
+                .flatten()
+                .filter_map(|field_border| *field_border)
+                .last();
+            if let (Some(field_border), Some(horizon)) = (field_border, camera_matrices.top.horizon)
+            {
+                // image y grows downwards, the border on the ground has to be below the horizon
+                let height_above_horizon = field_border
+                    .border_lines
+                    .iter()
+                    .flat_map(|line| [line.0, line.1])
+                    .map(|point| horizon.y_at_x(point.x()) - point.y())
+                    .fold(f32::NEG_INFINITY, f32::max);
+                if height_above_horizon > parameters.horizon_margin {
+                    inconsistencies.push(TransformInconsistency {
+                        check: TransformCheck::FieldBorderAboveHorizon,
+                        error: height_above_horizon,
+                    });
+                }
+            }
+        }
+
+        for inconsistency in &inconsistencies {
+            if !self.failing_checks.contains(&inconsistency.check) {
+                warn!(
+                    "transform check {:?} failed with an error of {}",
+                    inconsistency.check, inconsistency.error
+                );
+            }
+        }
+        self.failing_checks = inconsistencies
+            .iter()
+            .map(|inconsistency| inconsistency.check)
+            .collect();
+
+        Ok(MainOutputs {
+            transform_inconsistencies: inconsistencies.into(),
+        })
+    }
+}
+
+/// Larger of the translation difference and the rotation difference
+fn transform_error(left: Isometry3<Ground, Robot>, right: Isometry3<Ground, Robot>) -> f32 {
+    let difference = left.inner.inverse() * right.inner;
+    difference
+        .translation
+        .vector
+        .norm()
+        .max(difference.rotation.angle())
+}
+
+fn chain_error(camera_matrix: &CameraMatrix) -> f32 {
+    let chained =
+        camera_matrix.head_to_camera * camera_matrix.robot_to_head * camera_matrix.ground_to_robot;
+    let difference = camera_matrix.ground_to_camera.inner.inverse() * chained.inner;
+    difference
+        .translation
+        .vector
+        .norm()
+        .max(difference.rotation.angle())
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use linear_algebra::{vector, Isometry3};
+    use nalgebra::{Point2, Vector2};
+
+    use super::*;
+
+    fn camera_matrix(ground_to_robot: Isometry3<Ground, Robot>) -> CameraMatrix {
+        CameraMatrix::from_normalized_focal_and_center(
+            Vector2::new(0.95, 1.27),
+            Point2::new(0.5, 0.5),
+            vector![640.0, 480.0],
+            ground_to_robot,
+            Isometry3::from_translation(0.0, 0.0, -0.2),
+            Isometry3::from_translation(-0.05, 0.0, -0.06),
+        )
+    }
+
+    #[test]
+    fn transform_error_is_larger_of_translation_and_rotation() {
+        let transform = Isometry3::<Ground, Robot>::from_translation(0.0, 0.0, -0.5);
+        assert_relative_eq!(transform_error(transform, transform), 0.0);
+
+        let shifted = Isometry3::from_translation(0.1, 0.0, 0.0) * transform;
+        assert_relative_eq!(transform_error(transform, shifted), 0.1, epsilon = 1e-5);
+
+        let rotated = Isometry3::from_rotation(vector![0.0, 0.3, 0.0]) * transform;
+        assert_relative_eq!(transform_error(transform, rotated), 0.3, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn stale_precomputed_camera_transform_is_detected() {
+        let mut camera_matrix = camera_matrix(Isometry3::from_translation(0.0, 0.0, -0.5));
+        assert_relative_eq!(chain_error(&camera_matrix), 0.0, epsilon = 1e-5);
+
+        camera_matrix.ground_to_robot = Isometry3::from_translation(0.0, 0.0, -0.4);
+        assert_relative_eq!(chain_error(&camera_matrix), 0.1, epsilon = 1e-5);
+
+        camera_matrix.compute_memoized();
+        assert_relative_eq!(chain_error(&camera_matrix), 0.0, epsilon = 1e-5);
+    }
+}
@@ -1,16 +1,25 @@
-use std::{collections::VecDeque, time::SystemTime};
+use std::{
+    collections::VecDeque,
+    time::{Duration, SystemTime},
+};
 
 use color_eyre::Result;
 use context_attribute::context;
 use framework::{MainOutput, PerceptionInput};
 use serde::{Deserialize, Serialize};
-use types::{cycle_time::CycleTime, filtered_whistle::FilteredWhistle, whistle::Whistle};
+use types::{
+    cycle_time::CycleTime,
+    filtered_whistle::{FilteredWhistle, WhistlePattern},
+    whistle::Whistle,
+};
 
 #[derive(Deserialize, Serialize)]
 pub struct WhistleFilter {
     detection_buffer: VecDeque<bool>,
     was_detected_last_cycle: bool,
     last_detection: Option<SystemTime>,
+    first_whistle_of_pattern: Option<SystemTime>,
+    last_pattern: Option<WhistlePattern>,
 }
 
 #[context]
@@ -22,6 +31,10 @@ pub struct CycleContext {
 
     buffer_length: Parameter<usize, "whistle_filter.buffer_length">,
     minimum_detections: Parameter<usize, "whistle_filter.minimum_detections">,
+    minimum_double_whistle_interval:
+        Parameter<Duration, "whistle_filter.minimum_double_whistle_interval">,
+    maximum_double_whistle_interval:
+        Parameter<Duration, "whistle_filter.maximum_double_whistle_interval">,
     detected_whistle: PerceptionInput<Whistle, "Audio", "detected_whistle">,
 }
 
@@ -37,6 +50,8 @@ impl WhistleFilter {
             detection_buffer: Default::default(),
             was_detected_last_cycle: false,
             last_detection: None,
+            first_whistle_of_pattern: None,
+            last_pattern: None,
         })
     }
 
@@ -64,14 +79,159 @@ impl WhistleFilter {
             self.last_detection = Some(cycle_start_time);
         }
         self.was_detected_last_cycle = is_detected;
+        let pattern_this_cycle = self.classify_pattern(
+            started_this_cycle,
+            is_detected,
+            cycle_start_time,
+            *context.minimum_double_whistle_interval,
+            *context.maximum_double_whistle_interval,
+        );
+        if pattern_this_cycle.is_some() {
+            self.last_pattern = pattern_this_cycle;
+        }
 
         Ok(MainOutputs {
             filtered_whistle: FilteredWhistle {
                 is_detected,
                 last_detection: self.last_detection,
                 started_this_cycle,
+                pattern_this_cycle,
+                last_pattern: self.last_pattern,
             }
             .into(),
         })
     }
+
+    /// Whistles starting within the double whistle interval after each other form a double
+    /// whistle. Detections starting earlier are considered interruptions of the same whistle.
+    fn classify_pattern(
+        &mut self,
+        started_this_cycle: bool,
+        is_detected: bool,
+        now: SystemTime,
+        minimum_interval: Duration,
+        maximum_interval: Duration,
+    ) -> Option<WhistlePattern> {
+        let time_since_first_whistle = self
+            .first_whistle_of_pattern
+            .map(|first_whistle| now.duration_since(first_whistle).unwrap_or_default());
+        match time_since_first_whistle {
+            Some(interval) if started_this_cycle && interval < minimum_interval => None,
+            Some(interval) if started_this_cycle && interval <= maximum_interval => {
+                self.first_whistle_of_pattern = None;
+                Some(WhistlePattern::Double)
+            }
+            Some(_) if started_this_cycle => {
+                self.first_whistle_of_pattern = Some(now);
+                Some(WhistlePattern::Single)
+            }
+            Some(interval) if interval > maximum_interval && !is_detected => {
+                self.first_whistle_of_pattern = None;
+                Some(WhistlePattern::Single)
+            }
+            None if started_this_cycle => {
+                self.first_whistle_of_pattern = Some(now);
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINIMUM_INTERVAL: Duration = Duration::from_millis(200);
+    const MAXIMUM_INTERVAL: Duration = Duration::from_millis(1500);
+
+    fn whistle_filter() -> WhistleFilter {
+        WhistleFilter {
+            detection_buffer: Default::default(),
+            was_detected_last_cycle: false,
+            last_detection: None,
+            first_whistle_of_pattern: None,
+            last_pattern: None,
+        }
+    }
+
+    fn classify_at(
+        filter: &mut WhistleFilter,
+        milliseconds: u64,
+        started_this_cycle: bool,
+        is_detected: bool,
+    ) -> Option<WhistlePattern> {
+        filter.classify_pattern(
+            started_this_cycle,
+            is_detected,
+            SystemTime::UNIX_EPOCH + Duration::from_millis(milliseconds),
+            MINIMUM_INTERVAL,
+            MAXIMUM_INTERVAL,
+        )
+    }
+
+    #[test]
+    fn two_whistles_within_the_interval_are_a_double_whistle() {
+        let mut filter = whistle_filter();
+
+        assert_eq!(classify_at(&mut filter, 0, true, true), None);
+        assert_eq!(classify_at(&mut filter, 500, false, false), None);
+        assert_eq!(
+            classify_at(&mut filter, 1000, true, true),
+            Some(WhistlePattern::Double)
+        );
+        assert_eq!(classify_at(&mut filter, 3000, false, false), None);
+    }
+
+    #[test]
+    fn whistle_without_second_one_is_single_once_the_interval_passed() {
+        let mut filter = whistle_filter();
+
+        assert_eq!(classify_at(&mut filter, 0, true, true), None);
+        assert_eq!(classify_at(&mut filter, 1000, false, false), None);
+        assert_eq!(
+            classify_at(&mut filter, 1600, false, false),
+            Some(WhistlePattern::Single)
+        );
+        assert_eq!(classify_at(&mut filter, 1700, false, false), None);
+    }
+
+    #[test]
+    fn single_whistle_waits_until_the_whistle_ended() {
+        let mut filter = whistle_filter();
+
+        assert_eq!(classify_at(&mut filter, 0, true, true), None);
+        assert_eq!(classify_at(&mut filter, 1600, false, true), None);
+        assert_eq!(
+            classify_at(&mut filter, 1700, false, false),
+            Some(WhistlePattern::Single)
+        );
+    }
+
+    #[test]
+    fn interrupted_detection_of_one_whistle_is_no_double_whistle() {
+        let mut filter = whistle_filter();
+
+        assert_eq!(classify_at(&mut filter, 0, true, true), None);
+        assert_eq!(classify_at(&mut filter, 100, true, true), None);
+        assert_eq!(
+            classify_at(&mut filter, 1600, false, false),
+            Some(WhistlePattern::Single)
+        );
+    }
+
+    #[test]
+    fn late_second_whistle_completes_the_first_as_single_and_starts_a_new_pattern() {
+        let mut filter = whistle_filter();
+
+        assert_eq!(classify_at(&mut filter, 0, true, true), None);
+        assert_eq!(
+            classify_at(&mut filter, 1550, true, true),
+            Some(WhistlePattern::Single)
+        );
+        assert_eq!(
+            classify_at(&mut filter, 2000, true, true),
+            Some(WhistlePattern::Double)
+        );
+    }
 }
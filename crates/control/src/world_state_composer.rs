@@ -4,16 +4,20 @@ use coordinate_systems::{Field, Ground};
 use framework::MainOutput;
 use linear_algebra::{Isometry2, Point2};
 use serde::{Deserialize, Serialize};
-use spl_network_messages::PlayerNumber;
+use spl_network_messages::{GoalMouthClaim, PassAnnouncement, PlayerNumber};
 use types::{
+    ball_motion::BallMotion,
+    ball_possession::BallPossession,
     fall_state::FallState,
+    field_coverage::FieldCoverage,
     filtered_game_controller_state::FilteredGameControllerState,
     kick_decision::KickDecision,
-    obstacles::Obstacle,
+    obstacles::{opponents, Obstacle},
     primary_state::PrimaryState,
+    robot_condition::RobotCondition,
     roles::Role,
     rule_obstacles::RuleObstacle,
-    world_state::{BallState, RobotState, WorldState},
+    world_state::{BallState, RobotState, TeammateStriker, WorldState},
 };
 
 #[derive(Deserialize, Serialize)]
@@ -25,22 +29,35 @@ pub struct CreationContext {}
 #[context]
 pub struct CycleContext {
     ball: Input<Option<BallState>, "ball_state?">,
+    ball_motion: Input<Option<BallMotion>, "ball_motion?">,
     rule_ball: Input<Option<BallState>, "rule_ball_state?">,
     filtered_game_controller_state:
         Input<Option<FilteredGameControllerState>, "filtered_game_controller_state?">,
     ground_to_field: Input<Option<Isometry2<Ground, Field>>, "ground_to_field?">,
     kick_decisions: Input<Option<Vec<KickDecision>>, "kick_decisions?">,
     instant_kick_decisions: Input<Option<Vec<KickDecision>>, "instant_kick_decisions?">,
+    search_sector_position: Input<Option<Point2<Field>>, "search_sector_position?">,
+    pass_intent: Input<Option<PassAnnouncement>, "pass_intent?">,
+    announced_pass: Input<Option<PassAnnouncement>, "announced_pass?">,
+    teammate_goal_mouth_claim: Input<Option<GoalMouthClaim>, "teammate_goal_mouth_claim?">,
+    teammate_striker: Input<Option<TeammateStriker>, "teammate_striker?">,
 
     player_number: Parameter<PlayerNumber, "player_number">,
+    teammate_matching_distance: Parameter<f32, "obstacle_filter.teammate_matching_distance">,
 
     fall_state: Input<FallState, "fall_state">,
     has_ground_contact: Input<bool, "has_ground_contact">,
     obstacles: Input<Vec<Obstacle>, "obstacles">,
     rule_obstacles: Input<Vec<RuleObstacle>, "rule_obstacles">,
+    teammate_positions: Input<Vec<Point2<Field>>, "teammate_positions">,
+    teammate_defender_positions: Input<Vec<Point2<Field>>, "teammate_defender_positions">,
     primary_state: Input<PrimaryState, "primary_state">,
     role: Input<Role, "role">,
     position_of_interest: Input<Point2<Ground>, "position_of_interest">,
+    field_coverage: Input<FieldCoverage, "field_coverage">,
+    ball_possession: Input<BallPossession, "ball_possession">,
+    robot_condition: Input<RobotCondition, "robot_condition">,
+    is_converging_after_reentry: Input<bool, "is_converging_after_reentry">,
 }
 
 #[context]
@@ -62,17 +79,43 @@ impl WorldStateComposer {
             fall_state: *context.fall_state,
             has_ground_contact: *context.has_ground_contact,
             player_number: *context.player_number,
+            robot_condition: *context.robot_condition,
+            is_converging_after_reentry: *context.is_converging_after_reentry,
         };
 
+        let opponents = context
+            .ground_to_field
+            .map(|ground_to_field| {
+                opponents(
+                    context.obstacles,
+                    context.teammate_positions,
+                    *ground_to_field,
+                    *context.teammate_matching_distance,
+                )
+                .copied()
+                .collect()
+            })
+            .unwrap_or_default();
         let world_state = WorldState {
             ball: context.ball.copied(),
+            ball_motion: context.ball_motion.copied(),
+            ball_possession: *context.ball_possession,
             rule_ball: context.rule_ball.copied(),
             obstacles: context.obstacles.clone(),
+            opponents,
             rule_obstacles: context.rule_obstacles.clone(),
+            teammate_positions: context.teammate_positions.clone(),
+            teammate_defender_positions: context.teammate_defender_positions.clone(),
             position_of_interest: *context.position_of_interest,
             robot,
             kick_decisions: context.kick_decisions.cloned(),
             instant_kick_decisions: context.instant_kick_decisions.cloned(),
+            search_sector_position: context.search_sector_position.copied(),
+            field_coverage: context.field_coverage.clone(),
+            pass_intent: context.pass_intent.copied(),
+            announced_pass: context.announced_pass.copied(),
+            teammate_goal_mouth_claim: context.teammate_goal_mouth_claim.copied(),
+            teammate_striker: context.teammate_striker.copied(),
             filtered_game_controller_state: context.filtered_game_controller_state.copied(),
         };
 
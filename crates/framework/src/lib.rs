@@ -9,6 +9,7 @@ mod parameters;
 mod perception_databases;
 mod perception_input;
 mod recording_index;
+mod state_snapshot;
 
 pub use additional_output::{should_be_filled, AdditionalOutput};
 pub use future_queue::{future_queue, Consumer, Item, Producer, Update, Updates};
@@ -21,3 +22,4 @@ pub use parameters::Parameters;
 pub use perception_databases::PerceptionDatabases;
 pub use perception_input::PerceptionInput;
 pub use recording_index::{RecordingFrame, RecordingIndex};
+pub use state_snapshot::{PersistentState, StateSnapshot};
@@ -0,0 +1,219 @@
+use std::{
+    collections::BTreeMap,
+    fs::{create_dir_all, read, rename, write},
+    io::ErrorKind,
+    mem::take,
+    path::{Path, PathBuf},
+    sync::mpsc::{sync_channel, SyncSender},
+    thread::{self, JoinHandle},
+    time::{Duration, SystemTime},
+};
+
+use bincode::{DefaultOptions, Options};
+use color_eyre::{eyre::WrapErr, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// Snapshots are written at most this often
+const WRITE_INTERVAL: Duration = Duration::from_secs(1);
+/// Older snapshots are from a previous game or a different deployment and are ignored
+const MAXIMUM_AGE: Duration = Duration::from_secs(120);
+
+#[derive(Deserialize, Serialize)]
+struct Snapshot {
+    timestamp: SystemTime,
+    node_states: BTreeMap<String, Vec<u8>>,
+}
+
+/// Runtime state of a node worth keeping across a restart. Everything else, e.g. configuration
+/// derived from parameters, is created anew by the node's constructor.
+pub trait PersistentState {
+    type State: Serialize + DeserializeOwned;
+
+    fn persistent_state(&self) -> Self::State;
+    fn restore_persistent_state(&mut self, state: Self::State);
+}
+
+/// Periodically persists the state of selected nodes to disk such that a process restarted after
+/// a crash or a redeploy continues where it stopped instead of starting from scratch.
+///
+/// Writing happens on a separate thread to not block the cycler.
+pub struct StateSnapshot {
+    restored_node_states: BTreeMap<String, Vec<u8>>,
+    node_states: BTreeMap<String, Vec<u8>>,
+    last_write: Option<SystemTime>,
+    sender: Option<SyncSender<Snapshot>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl StateSnapshot {
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let restored_node_states = match read_snapshot(&path) {
+            Ok(Some(snapshot))
+                if snapshot
+                    .timestamp
+                    .elapsed()
+                    .is_ok_and(|age| age < MAXIMUM_AGE) =>
+            {
+                snapshot.node_states
+            }
+            Ok(_) => Default::default(),
+            Err(error) => {
+                eprintln!("ignoring state snapshot: {error:?}");
+                Default::default()
+            }
+        };
+
+        let (sender, receiver) = sync_channel::<Snapshot>(1);
+        let writer = thread::spawn(move || {
+            for snapshot in receiver {
+                if let Err(error) = write_snapshot(&path, &snapshot) {
+                    eprintln!("failed to write state snapshot: {error:?}");
+                }
+            }
+        });
+
+        Self {
+            restored_node_states,
+            node_states: Default::default(),
+            last_write: None,
+            sender: Some(sender),
+            writer: Some(writer),
+        }
+    }
+
+    /// Restores the persistent state of the node if the snapshot contains a compatible one
+    pub fn restore<Node: PersistentState>(&mut self, name: &str, node: &mut Node) {
+        let Some(state) = self.restored_node_states.remove(name) else {
+            return;
+        };
+        match options().deserialize(&state) {
+            Ok(state) => node.restore_persistent_state(state),
+            Err(error) => eprintln!("failed to restore state of `{name}`: {error}"),
+        }
+    }
+
+    pub fn is_due(&self) -> bool {
+        self.last_write.map_or(true, |last_write| {
+            last_write
+                .elapsed()
+                .map_or(true, |elapsed| elapsed >= WRITE_INTERVAL)
+        })
+    }
+
+    pub fn add<Node: PersistentState>(&mut self, name: &str, node: &Node) -> Result<()> {
+        let state = options()
+            .serialize(&node.persistent_state())
+            .wrap_err_with(|| format!("failed to serialize state of `{name}`"))?;
+        self.node_states.insert(name.to_string(), state);
+        Ok(())
+    }
+
+    /// Hands the added node states over to the writing thread. If it is still busy with the
+    /// previous snapshot, this one is skipped.
+    pub fn write(&mut self) {
+        let timestamp = SystemTime::now();
+        if let Some(sender) = &self.sender {
+            let _ = sender.try_send(Snapshot {
+                timestamp,
+                node_states: take(&mut self.node_states),
+            });
+        }
+        self.last_write = Some(timestamp);
+    }
+
+    /// Hands the added node states over to the writing thread and waits until it wrote them,
+    /// used when shutting down since the thread is not awaited otherwise. Later writes are
+    /// ignored.
+    pub fn finish(&mut self) {
+        if let Some(sender) = self.sender.take() {
+            let _ = sender.send(Snapshot {
+                timestamp: SystemTime::now(),
+                node_states: take(&mut self.node_states),
+            });
+        }
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+/// Trailing bytes are rejected to detect states of nodes whose structure changed
+fn options() -> impl Options {
+    DefaultOptions::new()
+        .with_fixint_encoding()
+        .reject_trailing_bytes()
+}
+
+fn read_snapshot(path: &Path) -> Result<Option<Snapshot>> {
+    let buffer = match read(path) {
+        Ok(buffer) => buffer,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(error) => {
+            return Err(error).wrap_err_with(|| format!("failed to read {}", path.display()))
+        }
+    };
+    let snapshot = options()
+        .deserialize(&buffer)
+        .wrap_err_with(|| format!("failed to deserialize {}", path.display()))?;
+    Ok(Some(snapshot))
+}
+
+fn write_snapshot(path: &Path, snapshot: &Snapshot) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent)
+            .wrap_err_with(|| format!("failed to create {}", parent.display()))?;
+    }
+    let buffer = options()
+        .serialize(snapshot)
+        .wrap_err("failed to serialize snapshot")?;
+    // the previous snapshot is replaced at once, a crash while writing must not corrupt it
+    let temporary_path = path.with_extension("tmp");
+    write(&temporary_path, buffer)
+        .wrap_err_with(|| format!("failed to write {}", temporary_path.display()))?;
+    rename(&temporary_path, path)
+        .wrap_err_with(|| format!("failed to move snapshot to {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env::temp_dir, process};
+
+    use super::*;
+
+    struct Counter {
+        step: u32,
+        count: u32,
+    }
+
+    impl PersistentState for Counter {
+        type State = u32;
+
+        fn persistent_state(&self) -> u32 {
+            self.count
+        }
+
+        fn restore_persistent_state(&mut self, count: u32) {
+            self.count = count;
+        }
+    }
+
+    #[test]
+    fn only_persistent_state_is_restored() {
+        let path = temp_dir()
+            .join(format!("state_snapshot_test_{}", process::id()))
+            .join("control.bincode");
+        let mut snapshot = StateSnapshot::load(&path);
+        snapshot
+            .add("Counter", &Counter { step: 1, count: 42 })
+            .unwrap();
+        snapshot.finish();
+
+        let mut counter = Counter { step: 2, count: 0 };
+        StateSnapshot::load(&path).restore("Counter", &mut counter);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+
+        assert_eq!(counter.count, 42);
+        assert_eq!(counter.step, 2, "configuration is kept");
+    }
+}
@@ -52,6 +52,12 @@ pub trait SensorInterface {
     fn read_from_sensors(&self) -> Result<SensorData>;
 }
 
+pub trait ShutdownInterface {
+    /// A shutdown was requested, the robot should get into a safe posture before it is finished
+    fn is_shutdown_requested(&self) -> bool;
+    fn finish_shutdown(&self);
+}
+
 pub trait SpeakerInterface {
     fn write_to_speakers(&self, request: SpeakerRequest);
 }
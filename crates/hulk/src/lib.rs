@@ -2,7 +2,8 @@
 
 use hardware::{
     ActuatorInterface, CameraInterface, IdInterface, MicrophoneInterface, NetworkInterface,
-    PathsInterface, RecordingInterface, SensorInterface, SpeakerInterface, TimeInterface,
+    PathsInterface, RecordingInterface, SensorInterface, ShutdownInterface, SpeakerInterface,
+    TimeInterface,
 };
 
 pub trait HardwareInterface:
@@ -14,6 +15,7 @@ pub trait HardwareInterface:
     + PathsInterface
     + RecordingInterface
     + SensorInterface
+    + ShutdownInterface
     + SpeakerInterface
     + TimeInterface
 {
@@ -0,0 +1,215 @@
+//! Asynchronous client for talking to the communication server of a running robot or simulator.
+//!
+//! This wraps the channel based [`Communication`] of the `communication` crate into an API that
+//! is convenient for tools and scripts:
+//!
+//! ```no_run
+//! # async fn example() -> color_eyre::Result<()> {
+//! let client = hulk_client::Client::connect("10.1.24.42").await?;
+//! let mut subscription = client
+//!     .subscribe::<bool>("Control.main.has_ground_contact")
+//!     .await?;
+//! let has_ground_contact = subscription.next().await?;
+//! client.set_parameter("behavior.strategy.enable", false).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{collections::BTreeSet, marker::PhantomData};
+
+use color_eyre::{
+    eyre::{bail, eyre, WrapErr},
+    Result,
+};
+use communication::{
+    client::{Communication, ConnectionStatus, CyclerOutput, SubscriberMessage},
+    messages::{Fields, Format, Path},
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{from_value, to_value, Value};
+use tokio::{runtime::Handle, sync::mpsc::Receiver};
+use types::motion_command::MotionCommand;
+use uuid::Uuid;
+
+pub const COMMUNICATION_PORT: u16 = 1337;
+
+pub fn address_to_communication_url(address: &str) -> String {
+    format!("ws://{address}:{COMMUNICATION_PORT}")
+}
+
+#[derive(Clone)]
+pub struct Client {
+    communication: Communication,
+}
+
+impl Client {
+    /// Creates a client without waiting for the connection, must be called within a tokio runtime.
+    pub fn new(address: Option<&str>, connect: bool) -> Self {
+        let communication = Communication::new(address.map(address_to_communication_url), connect);
+        Self { communication }
+    }
+
+    /// Creates a client and waits until the connection to `address` is established.
+    pub async fn connect(address: &str) -> Result<Self> {
+        let client = Self::new(Some(address), true);
+        client.wait_until_connected().await?;
+        Ok(client)
+    }
+
+    /// Gives access to the underlying communication, e.g. to share it with other subscribers.
+    pub fn communication(&self) -> &Communication {
+        &self.communication
+    }
+
+    pub async fn set_connect(&self, connect: bool) {
+        self.communication.set_connect(connect).await
+    }
+
+    pub async fn set_address(&self, address: &str) {
+        self.communication
+            .set_address(address_to_communication_url(address))
+            .await
+    }
+
+    pub fn connection_status(&self) -> ConnectionStatus {
+        self.communication
+            .subscribe_connection_status_updates()
+            .borrow()
+            .clone()
+    }
+
+    pub async fn wait_until_connected(&self) -> Result<()> {
+        let mut receiver = self.communication.subscribe_connection_status_updates();
+        receiver
+            .wait_for(|status| matches!(status, ConnectionStatus::Connected { .. }))
+            .await
+            .wrap_err("connection status channel closed")?;
+        Ok(())
+    }
+
+    pub async fn output_fields(&self) -> Option<Fields> {
+        self.communication.get_output_fields().await
+    }
+
+    pub async fn parameter_fields(&self) -> Option<BTreeSet<Path>> {
+        self.communication.get_parameter_fields().await
+    }
+
+    /// Subscribes to an output given as path, e.g. `Control.main.world_state`.
+    pub async fn subscribe<T: DeserializeOwned>(&self, output: &str) -> Result<Subscription<T>> {
+        let output: CyclerOutput = output.parse()?;
+        let (uuid, receiver) = self
+            .communication
+            .subscribe_output(output, Format::Textual)
+            .await;
+        Ok(Subscription {
+            communication: self.communication.clone(),
+            kind: SubscriptionKind::Output,
+            uuid,
+            receiver,
+            _value: PhantomData,
+        })
+    }
+
+    pub async fn subscribe_parameter<T: DeserializeOwned>(
+        &self,
+        path: &str,
+    ) -> Result<Subscription<T>> {
+        let (uuid, receiver) = self
+            .communication
+            .subscribe_parameter(path.to_string())
+            .await;
+        Ok(Subscription {
+            communication: self.communication.clone(),
+            kind: SubscriptionKind::Parameter,
+            uuid,
+            receiver,
+            _value: PhantomData,
+        })
+    }
+
+    /// Fetches the current value of a parameter once.
+    pub async fn parameter<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let mut subscription = self.subscribe_parameter(path).await?;
+        subscription
+            .next()
+            .await
+            .wrap_err_with(|| format!("failed to fetch parameter `{path}`"))
+    }
+
+    pub async fn set_parameter(&self, path: &str, value: impl Serialize) -> Result<()> {
+        let value = to_value(value)
+            .wrap_err_with(|| format!("failed to serialize value for parameter `{path}`"))?;
+        self.communication.update_parameter_value(path, value).await;
+        Ok(())
+    }
+
+    /// Sets all parameters at once, the robot applies them in the same cycle or none of them if
+    /// any fails to apply.
+    pub async fn set_parameters(&self, updates: Vec<(String, Value)>) -> Result<()> {
+        self.communication.update_parameter_values(updates).await;
+        Ok(())
+    }
+
+    /// Overrides the motion command selected by the behavior, `None` returns control to it.
+    pub async fn inject_motion_command(&self, motion_command: Option<MotionCommand>) -> Result<()> {
+        self.set_parameter("behavior.injected_motion_command", motion_command)
+            .await
+    }
+}
+
+enum SubscriptionKind {
+    Output,
+    Parameter,
+}
+
+/// Stream of deserialized values, unsubscribes when dropped.
+pub struct Subscription<T> {
+    communication: Communication,
+    kind: SubscriptionKind,
+    uuid: Uuid,
+    receiver: Receiver<SubscriberMessage>,
+    _value: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> Subscription<T> {
+    /// Waits for the next value, fails if the subscription was rejected or the client is gone.
+    pub async fn next(&mut self) -> Result<T> {
+        let value = self.next_value().await?;
+        from_value(value).wrap_err("failed to deserialize subscribed value")
+    }
+
+    pub async fn next_value(&mut self) -> Result<Value> {
+        loop {
+            match self.receiver.recv().await {
+                Some(SubscriberMessage::Update { value }) => return Ok(value),
+                Some(SubscriberMessage::SubscriptionSuccess) => {}
+                Some(SubscriberMessage::SubscriptionFailure { info }) => {
+                    bail!("subscription failed: {info}")
+                }
+                Some(SubscriberMessage::UpdateBinary { .. }) => {
+                    bail!("received binary update for textual subscription")
+                }
+                None => return Err(eyre!("subscription closed")),
+            }
+        }
+    }
+}
+
+impl<T> Drop for Subscription<T> {
+    fn drop(&mut self) {
+        let Ok(handle) = Handle::try_current() else {
+            return;
+        };
+        let communication = self.communication.clone();
+        let uuid = self.uuid;
+        match self.kind {
+            SubscriptionKind::Output => {
+                handle.spawn(async move { communication.unsubscribe_output(uuid).await });
+            }
+            SubscriptionKind::Parameter => {
+                handle.spawn(async move { communication.unsubscribe_parameter(uuid).await });
+            }
+        }
+    }
+}
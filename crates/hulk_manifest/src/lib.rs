@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use source_analyzer::{
     cyclers::{CyclerKind, Cyclers},
     error::Error,
@@ -5,6 +7,10 @@ use source_analyzer::{
 };
 
 pub fn collect_hulk_cyclers() -> Result<Cyclers, Error> {
+    collect_hulk_cyclers_in("..")
+}
+
+pub fn collect_hulk_cyclers_in(root: impl AsRef<Path>) -> Result<Cyclers, Error> {
     let manifest = FrameworkManifest {
         cyclers: vec![
             CyclerManifest {
@@ -18,12 +24,15 @@ pub fn collect_hulk_cyclers() -> Result<Cyclers, Error> {
                     "vision::feet_detection",
                     "vision::field_border_detection",
                     "vision::field_color_detection",
+                    "vision::horizon_crop_provider",
+                    "vision::image_ring_buffer",
                     "vision::image_segmenter",
                     "vision::limb_projector",
                     "vision::line_detection",
                     "vision::perspective_grid_candidates_provider",
                     "vision::segment_filter",
                 ],
+                persisted_nodes: vec![],
             },
             CyclerManifest {
                 name: "Control",
@@ -31,23 +40,34 @@ pub fn collect_hulk_cyclers() -> Result<Cyclers, Error> {
                 instances: vec![""],
                 setup_nodes: vec!["control::sensor_data_receiver"],
                 nodes: vec![
+                    "control::acoustic_signaling",
+                    "control::action_latency_monitor",
                     "control::active_vision",
                     "control::ball_filter",
+                    "control::ball_possession_estimator",
+                    "control::ball_search",
                     "control::ball_state_composer",
                     "control::behavior::node",
                     "control::button_filter",
                     "control::camera_matrix_calculator",
                     "control::center_of_mass_provider",
+                    "control::cycler_rate_governor",
                     "control::fall_state_estimation",
+                    "control::field_coverage_mapper",
                     "control::foot_bumper_filter",
                     "control::game_controller_filter",
+                    "control::game_controller_return_message_sender",
                     "control::game_controller_state_filter",
+                    "control::goal_mouth_coordinator",
                     "control::ground_contact_detector",
                     "control::ground_provider",
+                    "control::image_dump_trigger",
                     "control::kick_selector",
                     "control::kinematics_provider",
                     "control::led_status",
                     "control::localization",
+                    "control::location_announcer",
+                    "control::manual_placement",
                     "control::motion::arms_up_squat",
                     "control::motion::condition_input_provider",
                     "control::motion::dispatching_interpolator",
@@ -56,11 +76,13 @@ pub fn collect_hulk_cyclers() -> Result<Cyclers, Error> {
                     "control::motion::motor_commands_collector",
                     "control::motion::motor_commands_optimizer",
                     "control::motion::command_sender",
+                    "control::motion::jump_front",
                     "control::motion::jump_left",
                     "control::motion::jump_right",
                     "control::motion::look_around",
                     "control::motion::look_at",
                     "control::motion::motion_selector",
+                    "control::motion::shutdown_sequencer",
                     "control::motion::sit_down",
                     "control::motion::stand_up_back",
                     "control::motion::stand_up_front",
@@ -70,36 +92,55 @@ pub fn collect_hulk_cyclers() -> Result<Cyclers, Error> {
                     "control::obstacle_filter",
                     "control::odometry",
                     "control::orientation_filter",
+                    "control::pass_target_selector",
                     "control::penalty_shot_direction_estimation",
                     "control::primary_state_filter",
+                    "control::ready_signal_filter",
+                    "control::robot_condition_filter",
                     "control::role_assignment",
                     "control::rule_obstacle_composer",
+                    "control::self_test",
+                    "control::shot_selector",
                     "control::sole_pressure_filter",
                     "control::sonar_filter",
                     "control::support_foot_estimation",
+                    "control::teammate_striker_filter",
                     "control::time_to_reach_kick_position",
+                    "control::transform_sanity_checker",
                     "control::visual_referee_filter",
                     "control::whistle_filter",
                     "control::world_state_composer",
                 ],
+                persisted_nodes: vec![
+                    "control::ball_filter",
+                    "control::behavior::node",
+                    "control::game_controller_state_filter",
+                    "control::localization",
+                    "control::role_assignment",
+                ],
             },
             CyclerManifest {
                 name: "SplNetwork",
                 kind: CyclerKind::Perception,
                 instances: vec![""],
                 setup_nodes: vec!["spl_network::message_receiver"],
-                nodes: vec!["spl_network::message_filter"],
+                nodes: vec!["spl_network::message_filter", "spl_network::rate_limiter"],
+                persisted_nodes: vec![],
             },
             CyclerManifest {
                 name: "Audio",
                 kind: CyclerKind::Perception,
                 instances: vec![""],
                 setup_nodes: vec!["audio::microphone_recorder"],
-                nodes: vec!["audio::whistle_detection"],
+                nodes: vec![
+                    "audio::acoustic_signal_detection",
+                    "audio::rate_limiter",
+                    "audio::whistle_detection",
+                ],
+                persisted_nodes: vec![],
             },
         ],
     };
 
-    let root = "..";
     Cyclers::try_from_manifest(manifest, root)
 }
@@ -14,7 +14,7 @@ use color_eyre::{
     eyre::{eyre, Error, WrapErr},
     Result,
 };
-use hardware::{PathsInterface, RecordingInterface, SpeakerInterface};
+use hardware::{PathsInterface, RecordingInterface, ShutdownInterface, SpeakerInterface};
 use parking_lot::Mutex;
 use serde::Deserialize;
 use spl_network::endpoint::{Endpoint, Ports};
@@ -63,10 +63,15 @@ pub struct HardwareInterface {
     camera_bottom: Camera,
     enable_recording: AtomicBool,
     keep_running: CancellationToken,
+    shutdown_requested: CancellationToken,
 }
 
 impl HardwareInterface {
-    pub fn new(keep_running: CancellationToken, parameters: Parameters) -> Result<Self> {
+    pub fn new(
+        keep_running: CancellationToken,
+        shutdown_requested: CancellationToken,
+        parameters: Parameters,
+    ) -> Result<Self> {
         let i2c_head_mutex = Arc::new(Mutex::new(()));
         let runtime = Builder::new_current_thread()
             .enable_all()
@@ -106,6 +111,7 @@ impl HardwareInterface {
 
             enable_recording: AtomicBool::new(false),
             keep_running,
+            shutdown_requested,
         })
     }
 }
@@ -187,6 +193,16 @@ impl SensorInterface for HardwareInterface {
     }
 }
 
+impl ShutdownInterface for HardwareInterface {
+    fn is_shutdown_requested(&self) -> bool {
+        self.shutdown_requested.is_cancelled()
+    }
+
+    fn finish_shutdown(&self) {
+        self.keep_running.cancel();
+    }
+}
+
 impl SpeakerInterface for HardwareInterface {
     fn write_to_speakers(&self, request: SpeakerRequest) {
         self.speakers.write_to_speakers(request);
@@ -27,6 +27,16 @@ pub struct Battery {
     pub temperature: f32,
 }
 
+impl From<Battery> for types::sensor_data::Battery {
+    fn from(from: Battery) -> Self {
+        types::sensor_data::Battery {
+            charge: from.charge,
+            current: from.current,
+            temperature: from.temperature,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 #[repr(C)]
 pub struct Vertex2 {
@@ -0,0 +1,65 @@
+use std::{
+    collections::HashMap,
+    fs::{read_to_string, remove_file, symlink_metadata},
+    os::unix::fs::symlink,
+    path::Path,
+    process::Command,
+};
+
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    Result,
+};
+use parameters::directory::{NETWORK_LOCATIONS_FILE, NETWORK_LOCATION_DIRECTORY};
+use serde_json::from_str;
+
+/// Points the network location symlink to the location mapped to the connected WiFi network.
+///
+/// Without a mapping for the current network, the symlink is removed and the parameters fall back
+/// to `nao_location`. Returns the selected location.
+pub fn select_location_from_network(parameters_directory: &Path) -> Result<Option<String>> {
+    let network_location = parameters_directory.join(NETWORK_LOCATION_DIRECTORY);
+    if symlink_metadata(&network_location).is_ok() {
+        remove_file(&network_location)
+            .wrap_err_with(|| format!("failed to remove {network_location:?}"))?;
+    }
+
+    let mapping_path = parameters_directory.join(NETWORK_LOCATIONS_FILE);
+    if !mapping_path.exists() {
+        return Ok(None);
+    }
+    let mapping: HashMap<String, String> = from_str(
+        &read_to_string(&mapping_path)
+            .wrap_err_with(|| format!("failed to read {mapping_path:?}"))?,
+    )
+    .wrap_err_with(|| format!("failed to parse {mapping_path:?}"))?;
+
+    let Some(location) = connected_network()?.and_then(|network| mapping.get(&network).cloned())
+    else {
+        return Ok(None);
+    };
+    if !parameters_directory.join(&location).is_dir() {
+        bail!("location {location} from {mapping_path:?} does not exist");
+    }
+    symlink(&location, &network_location)
+        .wrap_err_with(|| format!("failed to link {network_location:?} to {location}"))?;
+    Ok(Some(location))
+}
+
+fn connected_network() -> Result<Option<String>> {
+    let output = Command::new("iwctl")
+        .arg("station")
+        .arg("wlan0")
+        .arg("show")
+        .output()
+        .wrap_err("failed to execute iwctl command")?;
+
+    Ok(String::from_utf8(output.stdout)
+        .wrap_err("failed to decode UTF-8")?
+        .lines()
+        .find_map(|line| {
+            line.split("Connected network")
+                .nth(1)
+                .map(|string| string.trim().to_owned())
+        }))
+}
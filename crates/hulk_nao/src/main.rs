@@ -4,6 +4,8 @@ use std::{
     io::stdout,
     path::{Path, PathBuf},
     sync::Arc,
+    thread::{sleep, spawn},
+    time::Duration,
 };
 
 use clap::Parser;
@@ -16,6 +18,8 @@ use framework::Parameters as FrameworkParameters;
 use hardware::IdInterface;
 use hardware_interface::{HardwareInterface, Parameters as HardwareParameters};
 use hulk::execution::run;
+use location::select_location_from_network;
+use log::{info, warn};
 use serde_json::from_reader;
 use tokio_util::sync::CancellationToken;
 
@@ -25,9 +29,14 @@ mod double_buffered_reader;
 mod hardware_interface;
 mod hula;
 mod hula_wrapper;
+mod location;
 mod microphones;
 mod speakers;
 
+/// The control cycler sits down and removes stiffness within this time, otherwise the process is
+/// terminated without a safe posture
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
 pub fn setup_logger() -> Result<(), fern::InitError> {
     fern::Dispatch::new()
         .format(|out, message, record| {
@@ -58,10 +67,26 @@ fn main() -> Result<()> {
     setup_logger()?;
     install()?;
     let keep_running = CancellationToken::new();
+    let shutdown_requested = CancellationToken::new();
     set_handler({
         let keep_running = keep_running.clone();
+        let shutdown_requested = shutdown_requested.clone();
         move || {
-            keep_running.cancel();
+            if shutdown_requested.is_cancelled() {
+                warn!("shutdown requested again, terminating immediately");
+                keep_running.cancel();
+                return;
+            }
+            info!("shutdown requested, sitting down before terminating");
+            shutdown_requested.cancel();
+            let keep_running = keep_running.clone();
+            spawn(move || {
+                sleep(SHUTDOWN_TIMEOUT);
+                if !keep_running.is_cancelled() {
+                    warn!("shutdown did not finish in time, terminating");
+                    keep_running.cancel();
+                }
+            });
         }
     })?;
 
@@ -78,11 +103,22 @@ fn main() -> Result<()> {
     let hardware_parameters: HardwareParameters =
         from_reader(file).wrap_err("failed to parse hardware parameters")?;
 
-    let hardware_interface = HardwareInterface::new(keep_running.clone(), hardware_parameters)
-        .wrap_err("failed to create hardware interface")?;
+    let hardware_interface = HardwareInterface::new(
+        keep_running.clone(),
+        shutdown_requested,
+        hardware_parameters,
+    )
+    .wrap_err("failed to create hardware interface")?;
 
     let ids = hardware_interface.get_ids();
 
+    // the parameters are loaded by `run`, the location has to be selected before
+    match select_location_from_network(&framework_parameters.parameters_directory) {
+        Ok(Some(location)) => info!("selected location {location} from the connected network"),
+        Ok(None) => warn!("no location mapped to the connected network, using nao_location"),
+        Err(error) => warn!("failed to select location from the connected network: {error:#}"),
+    }
+
     run(
         Arc::new(hardware_interface),
         framework_parameters.communication_addresses,
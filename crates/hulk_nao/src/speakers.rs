@@ -1,5 +1,6 @@
 use std::{
     collections::HashMap,
+    f32::consts::TAU,
     sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError},
     thread::{spawn, JoinHandle},
     time::Duration,
@@ -33,7 +34,7 @@ impl Speakers {
         let sounds =
             Self::load_sounds(paths, parameters.volume).wrap_err("failed to loads sounds")?;
         let (sender, receiver) = sync_channel(5);
-        let worker = Some(spawn(move || worker(device, sounds, receiver)));
+        let worker = Some(spawn(move || worker(device, sounds, parameters, receiver)));
         Ok(Self {
             worker_sender: Some(sender),
             worker,
@@ -135,11 +136,26 @@ pub struct Parameters {
     format: Format,
 }
 
-fn worker(device: PCM, sounds: HashMap<Sound, Vec<f32>>, receiver: Receiver<SpeakerRequest>) {
-    while let Ok(SpeakerRequest::PlaySound { sound }) = receiver.recv() {
-        let samples = sounds
-            .get(&sound)
-            .expect("missing sound, recheck Sound::all()");
+fn worker(
+    device: PCM,
+    sounds: HashMap<Sound, Vec<f32>>,
+    parameters: Parameters,
+    receiver: Receiver<SpeakerRequest>,
+) {
+    while let Ok(request) = receiver.recv() {
+        let tones;
+        let samples = match request {
+            SpeakerRequest::PlaySound { sound } => sounds
+                .get(&sound)
+                .expect("missing sound, recheck Sound::all()"),
+            SpeakerRequest::PlayTones {
+                frequencies,
+                duration,
+            } => {
+                tones = synthesize_tones(frequencies, duration, &parameters);
+                &tones
+            }
+        };
         let io = device
             .io_f32()
             .expect("f32 device should always be available");
@@ -154,3 +170,22 @@ fn worker(device: PCM, sounds: HashMap<Sound, Vec<f32>>, receiver: Receiver<Spea
         }
     }
 }
+
+fn synthesize_tones(
+    frequencies: [f32; 2],
+    duration: Duration,
+    parameters: &Parameters,
+) -> Vec<f32> {
+    let number_of_frames = (duration.as_secs_f32() * parameters.sample_rate as f32) as usize;
+    (0..number_of_frames)
+        .flat_map(|frame| {
+            let time = frame as f32 / parameters.sample_rate as f32;
+            let sample = frequencies
+                .iter()
+                .map(|frequency| (TAU * frequency * time).sin())
+                .sum::<f32>()
+                / frequencies.len() as f32;
+            std::iter::repeat(sample * parameters.volume).take(parameters.number_of_channels)
+        })
+        .collect()
+}
@@ -13,7 +13,8 @@ use execution::{RecordingFilePaths, Replayer};
 use framework::Parameters as FrameworkParameters;
 use hardware::{
     ActuatorInterface, CameraInterface, IdInterface, MicrophoneInterface, NetworkInterface,
-    PathsInterface, RecordingInterface, SensorInterface, SpeakerInterface, TimeInterface,
+    PathsInterface, RecordingInterface, SensorInterface, ShutdownInterface, SpeakerInterface,
+    TimeInterface,
 };
 use serde_json::from_reader;
 use tokio_util::sync::CancellationToken;
@@ -40,6 +41,7 @@ pub trait HardwareInterface:
     + PathsInterface
     + RecordingInterface
     + SensorInterface
+    + ShutdownInterface
     + SpeakerInterface
     + TimeInterface
 {
@@ -114,6 +116,14 @@ impl SensorInterface for ReplayerHardwareInterface {
     }
 }
 
+impl ShutdownInterface for ReplayerHardwareInterface {
+    fn is_shutdown_requested(&self) -> bool {
+        false
+    }
+
+    fn finish_shutdown(&self) {}
+}
+
 impl SpeakerInterface for ReplayerHardwareInterface {
     fn write_to_speakers(&self, _request: SpeakerRequest) {}
 }
@@ -13,7 +13,8 @@ use color_eyre::{
 };
 use hardware::{
     ActuatorInterface, CameraInterface, IdInterface, MicrophoneInterface, NetworkInterface,
-    PathsInterface, RecordingInterface, SensorInterface, SpeakerInterface, TimeInterface,
+    PathsInterface, RecordingInterface, SensorInterface, ShutdownInterface, SpeakerInterface,
+    TimeInterface,
 };
 use serde::Deserialize;
 use spl_network::endpoint::{Endpoint, Ports};
@@ -400,10 +401,22 @@ impl SensorInterface for HardwareInterface {
             touch_sensors,
             temperature_sensors,
             currents,
+            battery: None,
         })
     }
 }
 
+impl ShutdownInterface for HardwareInterface {
+    fn is_shutdown_requested(&self) -> bool {
+        // the simulation terminates the controller right away, there is no time to sit down
+        false
+    }
+
+    fn finish_shutdown(&self) {
+        self.keep_running.cancel();
+    }
+}
+
 impl SpeakerInterface for HardwareInterface {
     fn write_to_speakers(&self, _request: SpeakerRequest) {
         // not implemented
@@ -1,6 +1,6 @@
 use std::{fmt::Debug, time::Duration};
 
-use crate::{FallenAbort, StabilizedCondition};
+use crate::{FallenAbort, StabilizedCondition, UprightCondition};
 
 use enum_dispatch::enum_dispatch;
 use serde::{Deserialize, Serialize};
@@ -36,6 +36,7 @@ pub trait TimeOut {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DiscreteConditionType {
     StabilizedCondition,
+    UprightCondition,
 }
 
 #[enum_dispatch(Condition)]
@@ -5,6 +5,7 @@ pub mod motion_interpolator;
 pub mod spline_interpolator;
 pub mod stabilized_condition;
 pub mod timed_spline;
+pub mod upright_condition;
 
 pub use condition::{Condition, ContinuousConditionType, DiscreteConditionType, Response, TimeOut};
 pub use fallen_abort_condition::FallenAbort;
@@ -13,3 +14,4 @@ pub use motion_interpolator::MotionInterpolator;
 pub use spline_interpolator::SplineInterpolator;
 pub use stabilized_condition::StabilizedCondition;
 pub use timed_spline::TimedSpline;
+pub use upright_condition::UprightCondition;
@@ -1,16 +1,20 @@
 use std::fmt::Debug;
 use std::{fs::File, path::Path, time::Duration};
 
-use color_eyre::eyre::{Result, WrapErr};
+use color_eyre::eyre::{bail, Result, WrapErr};
+use log::warn;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use serde_json::from_reader;
+use serde_json::{from_reader, from_value, json, Value};
 use splines::{Interpolate, Interpolation};
 
 use crate::condition::{ContinuousConditionType, DiscreteConditionType};
 
+/// Files without a version predate versioning and are treated as version 0
+pub const CURRENT_MOTION_FILE_VERSION: u64 = 1;
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct MotionFile<T> {
-    #[serde(default)]
+    pub version: u64,
     pub interpolation_mode: Interpolation<Duration, T>,
     pub initial_positions: T,
     pub motion: Vec<MotionFileFrame<T>>,
@@ -24,23 +28,131 @@ where
         let file = File::open(&motion_file_path).wrap_err_with(|| {
             format!("failed to open motion file {:?}", motion_file_path.as_ref())
         })?;
-        from_reader(file).wrap_err_with(|| {
+        let mut motion_file: Value = from_reader(file).wrap_err_with(|| {
             format!(
                 "failed to parse motion file {:?}",
                 motion_file_path.as_ref()
             )
+        })?;
+        let version = migrate(&mut motion_file).wrap_err_with(|| {
+            format!(
+                "failed to migrate motion file {:?}",
+                motion_file_path.as_ref()
+            )
+        })?;
+        if version < CURRENT_MOTION_FILE_VERSION {
+            warn!(
+                "migrated motion file {:?} from version {version} to {CURRENT_MOTION_FILE_VERSION}, consider updating the file",
+                motion_file_path.as_ref()
+            );
+        }
+        from_value(motion_file).wrap_err_with(|| {
+            format!(
+                "failed to deserialize motion file {:?}",
+                motion_file_path.as_ref()
+            )
         })
     }
 }
 
+/// Brings a motion file to the current version one version at a time and returns the version it
+/// was read with. Every migration only fills in what the newer version added, such that old files
+/// keep working unchanged on disk.
+fn migrate(motion_file: &mut Value) -> Result<u64> {
+    let version = motion_file
+        .get("version")
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+    if version > CURRENT_MOTION_FILE_VERSION {
+        bail!("version {version} is newer than the supported {CURRENT_MOTION_FILE_VERSION}");
+    }
+    for from_version in version..CURRENT_MOTION_FILE_VERSION {
+        match from_version {
+            0 => migrate_from_version_0(motion_file)?,
+            _ => unreachable!("there is a migration for every version before the current one"),
+        }
+    }
+    Ok(version)
+}
+
+/// Version 1 introduced the version field and made the interpolation mode and, for motions
+/// commanding motors, the stiffnesses explicit. Both used to be implicit: interpolation was
+/// linear and motors without stiffnesses were fully stiff.
+fn migrate_from_version_0(motion_file: &mut Value) -> Result<()> {
+    let Some(motion_file) = motion_file.as_object_mut() else {
+        bail!("expected an object");
+    };
+    motion_file
+        .entry("interpolation_mode")
+        .or_insert_with(|| json!("linear"));
+    if let Some(initial_positions) = motion_file.get_mut("initial_positions") {
+        fill_full_stiffnesses(initial_positions);
+    }
+    if let Some(frames) = motion_file.get_mut("motion").and_then(Value::as_array_mut) {
+        for keyframes in frames
+            .iter_mut()
+            .filter_map(|frame| frame.get_mut("keyframes"))
+            .filter_map(Value::as_array_mut)
+        {
+            for positions in keyframes
+                .iter_mut()
+                .filter_map(|keyframe| keyframe.get_mut("positions"))
+            {
+                fill_full_stiffnesses(positions);
+            }
+        }
+    }
+    motion_file.insert("version".to_string(), json!(1));
+    Ok(())
+}
+
+/// Motor commands without stiffnesses get full stiffness on every joint that has a position
+fn fill_full_stiffnesses(motor_commands: &mut Value) {
+    let Some(motor_commands) = motor_commands.as_object_mut() else {
+        return;
+    };
+    if motor_commands.contains_key("stiffnesses") {
+        return;
+    }
+    if let Some(positions) = motor_commands.get("positions") {
+        let stiffnesses = with_full_stiffness(positions);
+        motor_commands.insert("stiffnesses".to_string(), stiffnesses);
+    }
+}
+
+fn with_full_stiffness(positions: &Value) -> Value {
+    match positions {
+        Value::Object(joints) => Value::Object(
+            joints
+                .iter()
+                .map(|(name, joint)| (name.clone(), with_full_stiffness(joint)))
+                .collect(),
+        ),
+        _ => json!(1.0),
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct MotionFileFrame<T> {
     pub name: Option<String>,
+    pub label: Option<String>,
     pub entry_condition: Option<DiscreteConditionType>,
     #[serde(default)]
     pub interrupt_conditions: Vec<ContinuousConditionType>,
     pub keyframes: Vec<KeyFrame<T>>,
     pub exit_condition: Option<DiscreteConditionType>,
+    #[serde(default)]
+    pub jumps: Vec<MotionFileJump>,
+    /// The motion finishes after this frame instead of continuing with the next one, frames
+    /// behind it are only reached through jumps
+    #[serde(default)]
+    pub finishes_motion: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MotionFileJump {
+    pub condition: DiscreteConditionType,
+    pub target: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -66,3 +178,94 @@ where
 {
     Ok(Duration::from_secs_f32(f32::deserialize(deserializer)?))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unversioned_file_gets_linear_interpolation_and_full_stiffness() {
+        let mut motion_file = json!({
+            "initial_positions": {
+                "positions": { "head": { "yaw": 0.1, "pitch": 0.2 } }
+            },
+            "motion": [{
+                "keyframes": [{
+                    "duration": 1.0,
+                    "positions": {
+                        "positions": { "head": { "yaw": 0.3, "pitch": 0.4 } }
+                    }
+                }]
+            }]
+        });
+
+        let version = migrate(&mut motion_file).unwrap();
+
+        assert_eq!(version, 0);
+        assert_eq!(motion_file["version"], json!(CURRENT_MOTION_FILE_VERSION));
+        assert_eq!(motion_file["interpolation_mode"], json!("linear"));
+        let full_stiffness = json!({ "head": { "yaw": 1.0, "pitch": 1.0 } });
+        assert_eq!(
+            motion_file["initial_positions"]["stiffnesses"],
+            full_stiffness
+        );
+        assert_eq!(
+            motion_file["motion"][0]["keyframes"][0]["positions"]["stiffnesses"],
+            full_stiffness
+        );
+    }
+
+    #[test]
+    fn migration_keeps_what_the_file_specifies() {
+        let initial_positions = json!({
+            "positions": { "head": { "yaw": 0.1, "pitch": 0.2 } },
+            "stiffnesses": { "head": { "yaw": 0.5, "pitch": 0.5 } }
+        });
+        let mut motion_file = json!({
+            "interpolation_mode": "cosine",
+            "initial_positions": initial_positions,
+            "motion": []
+        });
+
+        migrate(&mut motion_file).unwrap();
+
+        assert_eq!(motion_file["interpolation_mode"], json!("cosine"));
+        assert_eq!(motion_file["initial_positions"], initial_positions);
+    }
+
+    #[test]
+    fn joint_positions_without_motor_commands_are_left_alone() {
+        let initial_positions = json!({ "head": { "yaw": 0.1, "pitch": 0.2 } });
+        let mut motion_file = json!({
+            "initial_positions": initial_positions,
+            "motion": []
+        });
+
+        migrate(&mut motion_file).unwrap();
+
+        assert_eq!(motion_file["initial_positions"], initial_positions);
+    }
+
+    #[test]
+    fn current_file_is_not_changed() {
+        let original = json!({
+            "version": CURRENT_MOTION_FILE_VERSION,
+            "interpolation_mode": "linear",
+            "initial_positions": { "positions": { "head": { "yaw": 0.1 } } },
+            "motion": []
+        });
+        let mut motion_file = original.clone();
+
+        let version = migrate(&mut motion_file).unwrap();
+
+        assert_eq!(version, CURRENT_MOTION_FILE_VERSION);
+        assert_eq!(motion_file, original);
+    }
+
+    #[test]
+    fn newer_file_is_rejected() {
+        let mut motion_file = json!({ "version": CURRENT_MOTION_FILE_VERSION + 1 });
+
+        assert!(migrate(&mut motion_file).is_err());
+    }
+}
@@ -1,16 +1,19 @@
 use std::fmt::Debug;
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
 use crate::{
     condition::{ContinuousConditionType, DiscreteConditionType, Response, TimeOut},
-    timed_spline::{InterpolatorError, TimedSpline},
-    Condition, MotionFile,
+    timed_spline::TimedSpline,
+    Condition, MotionFile, MotionFileJump,
+};
+use color_eyre::{
+    eyre::{bail, eyre},
+    Report, Result,
 };
-use color_eyre::{Report, Result};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use splines::Interpolate;
-use types::condition_input::ConditionInput;
+use types::{condition_input::ConditionInput, joints::mirror::Mirror, support_foot::Side};
 
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct ConditionedSpline<T> {
@@ -18,11 +21,20 @@ pub struct ConditionedSpline<T> {
     pub interrupt_conditions: Vec<ContinuousConditionType>,
     pub spline: TimedSpline<T>,
     pub exit_condition: Option<DiscreteConditionType>,
+    pub jumps: Vec<ConditionedJump>,
+    pub finishes_motion: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ConditionedJump {
+    pub condition: DiscreteConditionType,
+    pub target_frame_index: usize,
 }
 
 #[derive(Default, Debug, Deserialize, Serialize)]
 pub struct MotionInterpolator<T> {
     frames: Vec<ConditionedSpline<T>>,
+    labels: HashMap<String, usize>,
     current_state: State<T>,
 }
 
@@ -40,7 +52,9 @@ enum State<T> {
         current_frame_index: usize,
         time_since_start: Duration,
     },
-    Finished,
+    Finished {
+        last_frame_index: usize,
+    },
     Aborted {
         at_position: T,
     },
@@ -174,17 +188,53 @@ impl<T: Debug + Interpolate<f32>> MotionInterpolator<T> {
                         current_frame_index,
                         time_since_start: time_since_start + time_step,
                     },
-                    _ if current_frame_index < self.frames.len() - 1 => State::CheckEntry {
-                        current_frame_index: current_frame_index + 1,
-                        time_since_start: Duration::ZERO,
+                    _ => match self.fulfilled_jump_target(current_frame_index, condition_input) {
+                        Some(target_frame_index) => {
+                            self.enter_frame_from(current_frame_index, target_frame_index)
+                        }
+                        None if current_frame_index < self.frames.len() - 1
+                            && !current_frame.finishes_motion =>
+                        {
+                            self.enter_frame_from(current_frame_index, current_frame_index + 1)
+                        }
+                        None => State::Finished {
+                            last_frame_index: current_frame_index,
+                        },
                     },
-                    _ => State::Finished,
                 }
             }
             other_state => other_state,
         };
     }
 
+    fn fulfilled_jump_target(
+        &self,
+        current_frame_index: usize,
+        condition_input: &ConditionInput,
+    ) -> Option<usize> {
+        self.frames[current_frame_index]
+            .jumps
+            .iter()
+            .find(|jump| matches!(jump.condition.evaluate(condition_input), Response::Continue))
+            .map(|jump| jump.target_frame_index)
+    }
+
+    fn enter_frame_from(
+        &mut self,
+        current_frame_index: usize,
+        next_frame_index: usize,
+    ) -> State<T> {
+        // splines of jump targets may have been started from elsewhere before
+        let start_position = self.frames[current_frame_index].spline.end_position();
+        self.frames[next_frame_index]
+            .spline
+            .set_initial_positions(start_position);
+        State::CheckEntry {
+            current_frame_index: next_frame_index,
+            time_since_start: Duration::ZERO,
+        }
+    }
+
     pub fn advance_by(&mut self, time_step: Duration, condition_input: &ConditionInput) {
         if let ReturnState::Return = self.check_continuous_conditions(condition_input) {
             return;
@@ -194,7 +244,10 @@ impl<T: Debug + Interpolate<f32>> MotionInterpolator<T> {
     }
 
     pub fn is_finished(&self) -> bool {
-        matches!(self.current_state, State::Finished | State::Aborted { .. })
+        matches!(
+            self.current_state,
+            State::Finished { .. } | State::Aborted { .. }
+        )
     }
 
     pub fn value(&self) -> T {
@@ -213,7 +266,9 @@ impl<T: Debug + Interpolate<f32>> MotionInterpolator<T> {
                 current_frame_index,
                 ..
             } => self.frames[current_frame_index].spline.end_position(),
-            State::Finished => self.frames.last().unwrap().spline.end_position(),
+            State::Finished { last_frame_index } => {
+                self.frames[last_frame_index].spline.end_position()
+            }
             State::Aborted { at_position } => at_position,
         }
     }
@@ -225,19 +280,77 @@ impl<T: Debug + Interpolate<f32>> MotionInterpolator<T> {
         };
     }
 
+    pub fn reset_to_label(&mut self, label: &str) -> Result<()> {
+        let frame_index = self.frame_index_of_label(label)?;
+        if frame_index > 0 {
+            let start_position = self.frames[frame_index - 1].spline.end_position();
+            self.frames[frame_index]
+                .spline
+                .set_initial_positions(start_position);
+        }
+        self.current_state = State::CheckEntry {
+            current_frame_index: frame_index,
+            time_since_start: Duration::ZERO,
+        };
+        Ok(())
+    }
+
+    pub fn jump_to_label(&mut self, label: &str) -> Result<()> {
+        let target_frame_index = self.frame_index_of_label(label)?;
+        let current_position = self.value();
+        self.frames[target_frame_index]
+            .spline
+            .set_initial_positions(current_position);
+        self.current_state = State::CheckEntry {
+            current_frame_index: target_frame_index,
+            time_since_start: Duration::ZERO,
+        };
+        Ok(())
+    }
+
+    pub fn current_label(&self) -> Option<&str> {
+        let current_frame_index = self.current_state.current_frame_index()?;
+        self.labels
+            .iter()
+            .find(|(_, frame_index)| **frame_index == current_frame_index)
+            .map(|(label, _)| label.as_str())
+    }
+
+    fn frame_index_of_label(&self, label: &str) -> Result<usize> {
+        self.labels
+            .get(label)
+            .copied()
+            .ok_or_else(|| eyre!("motion has no frame labeled {label:?}"))
+    }
+
     pub fn set_initial_positions(&mut self, position: T) {
-        if let Some(keyframe) = self.frames.first_mut() {
+        let current_frame_index = self.current_state.current_frame_index().unwrap_or(0);
+        if let Some(keyframe) = self.frames.get_mut(current_frame_index) {
             keyframe.spline.set_initial_positions(position);
         }
     }
 
+    /// Frames executed after the given one if no jump is taken
+    fn following_frames(&self, index: usize) -> impl Iterator<Item = &ConditionedSpline<T>> {
+        let is_last = self.frames[index].finishes_motion;
+        self.frames
+            .iter()
+            .skip(index + 1)
+            .take_while(move |_| !is_last)
+            .scan(false, |has_finished, frame| {
+                if *has_finished {
+                    return None;
+                }
+                *has_finished = frame.finishes_motion;
+                Some(frame)
+            })
+    }
+
     pub fn estimated_remaining_duration(&self) -> Duration {
         match self.current_state.current_frame_index() {
             Some(index) => {
                 let mut remaining = self
-                    .frames
-                    .iter()
-                    .skip(index + 1)
+                    .following_frames(index)
                     .map(|frame| frame.spline.total_duration())
                     .sum::<Duration>();
                 remaining += match self.current_state {
@@ -249,7 +362,7 @@ impl<T: Debug + Interpolate<f32>> MotionInterpolator<T> {
                         time_since_start,
                     ),
                     State::CheckExit { .. } => Duration::ZERO,
-                    State::Finished => Duration::ZERO,
+                    State::Finished { .. } => Duration::ZERO,
                     State::Aborted { .. } => Duration::MAX,
                 };
                 remaining
@@ -265,12 +378,32 @@ impl<T: Debug + Interpolate<f32>> MotionInterpolator<T> {
     }
 }
 
+impl<T: Debug + Interpolate<f32> + Mirror> MotionInterpolator<T> {
+    /// Motion files of single-sided motions are designed for the left side, executing them on the
+    /// right side mirrors them at runtime.
+    pub fn value_on(&self, side: Side) -> T {
+        match side {
+            Side::Left => self.value(),
+            Side::Right => self.value().mirrored(),
+        }
+    }
+}
+
 impl<T: Debug + Interpolate<f32>> TryFrom<MotionFile<T>> for MotionInterpolator<T> {
     type Error = Report;
 
     fn try_from(motion_file: MotionFile<T>) -> Result<Self> {
         let interpolation_mode = motion_file.interpolation_mode;
 
+        let mut labels = HashMap::new();
+        for (frame_index, frame) in motion_file.motion.iter().enumerate() {
+            if let Some(label) = &frame.label {
+                if labels.insert(label.clone(), frame_index).is_some() {
+                    bail!("label {label:?} is used for more than one frame");
+                }
+            }
+        }
+
         let first_frame = motion_file.motion.first().unwrap();
 
         let mut motion_frames = vec![ConditionedSpline {
@@ -282,6 +415,8 @@ impl<T: Debug + Interpolate<f32>> TryFrom<MotionFile<T>> for MotionInterpolator<
                 interpolation_mode,
             )?,
             exit_condition: first_frame.exit_condition.clone(),
+            jumps: resolve_jumps(&first_frame.jumps, &labels)?,
+            finishes_motion: first_frame.finishes_motion,
         }];
 
         motion_frames.extend(
@@ -299,9 +434,11 @@ impl<T: Debug + Interpolate<f32>> TryFrom<MotionFile<T>> for MotionInterpolator<
                             interpolation_mode,
                         )?,
                         exit_condition: second_frame.exit_condition,
+                        jumps: resolve_jumps(&second_frame.jumps, &labels)?,
+                        finishes_motion: second_frame.finishes_motion,
                     })
                 })
-                .collect::<Result<Vec<_>, InterpolatorError>>()?,
+                .collect::<Result<Vec<_>>>()?,
         );
 
         Ok(Self {
@@ -310,6 +447,113 @@ impl<T: Debug + Interpolate<f32>> TryFrom<MotionFile<T>> for MotionInterpolator<
                 time_since_start: Duration::ZERO,
             },
             frames: motion_frames,
+            labels,
+        })
+    }
+}
+
+fn resolve_jumps(
+    jumps: &[MotionFileJump],
+    labels: &HashMap<String, usize>,
+) -> Result<Vec<ConditionedJump>> {
+    jumps
+        .iter()
+        .map(|jump| {
+            let target_frame_index = labels
+                .get(&jump.target)
+                .copied()
+                .ok_or_else(|| eyre!("jump target {:?} does not exist", jump.target))?;
+            Ok(ConditionedJump {
+                condition: jump.condition.clone(),
+                target_frame_index,
+            })
         })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use splines::Interpolation;
+
+    use crate::{KeyFrame, MotionFileFrame};
+
+    use super::*;
+
+    fn frame(label: &str, position: f32) -> MotionFileFrame<f32> {
+        MotionFileFrame {
+            name: None,
+            label: Some(label.to_string()),
+            entry_condition: None,
+            interrupt_conditions: Vec::new(),
+            keyframes: vec![KeyFrame {
+                duration: Duration::from_secs(1),
+                positions: position,
+            }],
+            exit_condition: None,
+            jumps: Vec::new(),
+            finishes_motion: false,
+        }
+    }
+
+    fn interpolator() -> MotionInterpolator<f32> {
+        MotionFile {
+            version: 1,
+            interpolation_mode: Interpolation::Linear,
+            initial_positions: 0.0,
+            motion: vec![frame("first", 1.0), frame("second", 2.0)],
+        }
+        .try_into()
+        .unwrap()
+    }
+
+    #[test]
+    fn initial_positions_start_the_first_frame_before_the_motion_started() {
+        let mut interpolator = interpolator();
+
+        interpolator.set_initial_positions(5.0);
+
+        assert_eq!(interpolator.value(), 5.0);
+    }
+
+    #[test]
+    fn initial_positions_start_the_current_frame_and_leave_others_untouched() {
+        let mut interpolator = interpolator();
+        interpolator.reset_to_label("second").unwrap();
+
+        interpolator.set_initial_positions(5.0);
+
+        assert_eq!(interpolator.value(), 5.0);
+        interpolator.reset();
+        assert_eq!(interpolator.value(), 0.0);
+    }
+
+    #[test]
+    fn frames_behind_a_finishing_frame_are_skipped() {
+        let mut interpolator: MotionInterpolator<f32> = MotionFile {
+            version: 1,
+            interpolation_mode: Interpolation::Linear,
+            initial_positions: 0.0,
+            motion: vec![
+                MotionFileFrame {
+                    finishes_motion: true,
+                    ..frame("first", 1.0)
+                },
+                frame("second", 2.0),
+            ],
+        }
+        .try_into()
+        .unwrap();
+        assert_eq!(
+            interpolator.estimated_remaining_duration(),
+            Duration::from_secs(1)
+        );
+
+        let condition_input = ConditionInput::default();
+        for _ in 0..20 {
+            interpolator.advance_by(Duration::from_millis(100), &condition_input);
+        }
+
+        assert!(interpolator.is_finished());
+        assert_eq!(interpolator.value(), 1.0);
     }
 }
@@ -0,0 +1,29 @@
+use std::{fmt::Debug, time::Duration};
+
+use crate::condition::{Condition, Response, TimeOut};
+
+use serde::{Deserialize, Serialize};
+use types::condition_input::ConditionInput;
+
+/// Continues as soon as the torso is upright, meant for jumps skipping stabilization frames
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UprightCondition {
+    maximum_tilt: f32,
+}
+
+impl Condition for UprightCondition {
+    fn evaluate(&self, condition_input: &ConditionInput) -> Response {
+        if condition_input.roll_pitch.x.abs() < self.maximum_tilt
+            && condition_input.roll_pitch.y.abs() < self.maximum_tilt
+        {
+            return Response::Continue;
+        }
+        Response::Wait
+    }
+}
+
+impl TimeOut for UprightCondition {
+    fn timeout(&self, _time_since_start: Duration) -> bool {
+        false
+    }
+}
@@ -225,6 +225,78 @@ impl Nao {
         String::from_utf8(output.stdout).wrap_err("failed to decode UTF-8")
     }
 
+    pub async fn retrieve_log_tail(&self, number_of_lines: usize) -> Result<String> {
+        let output = self
+            .ssh_to_nao()
+            .arg("tail")
+            .arg(format!("-n{number_of_lines}"))
+            .arg("hulk/logs/hulk.{out,err}")
+            .output()
+            .await
+            .wrap_err("failed to execute tail command")?;
+
+        if !output.status.success() {
+            bail!("tail ssh command exited with {}", output.status);
+        }
+
+        String::from_utf8(output.stdout).wrap_err("failed to decode UTF-8")
+    }
+
+    /// Lists the parameter locations uploaded to the NAO, the active one is copied to
+    /// `nao_location` during upload
+    pub async fn list_parameter_locations(&self) -> Result<Vec<String>> {
+        let output = self
+            .ssh_to_nao()
+            .arg("ls")
+            .arg("-1")
+            .arg("-d")
+            .arg("hulk/etc/parameters/*/")
+            .output()
+            .await
+            .wrap_err("failed to execute list command")?;
+
+        if !output.status.success() {
+            bail!("list ssh command exited with {}", output.status);
+        }
+
+        let stdout = String::from_utf8(output.stdout).wrap_err("failed to decode UTF-8")?;
+        Ok(stdout
+            .lines()
+            .filter_map(|line| line.trim_end_matches('/').rsplit('/').next())
+            .filter(|location| is_selectable_location(location))
+            .map(ToString::to_string)
+            .collect())
+    }
+
+    /// Replaces the parameters in `nao_location` by another uploaded location. The directory is
+    /// copied instead of linked since uploads follow links on the NAO. The copy is completed
+    /// before the previous parameters are removed, so a failing copy keeps them.
+    pub async fn activate_parameter_location(&self, location: &str) -> Result<()> {
+        if !is_selectable_location(location) {
+            bail!("invalid location name {location:?}");
+        }
+
+        let status = self
+            .ssh_to_nao()
+            .arg(format!(
+                "cd hulk/etc/parameters \
+                 && test -d '{location}' \
+                 && rm -rf -- upcoming_nao_location \
+                 && cp -r -- '{location}' upcoming_nao_location \
+                 && rm -rf -- nao_location \
+                 && mv -- upcoming_nao_location nao_location"
+            ))
+            .status()
+            .await
+            .wrap_err("failed to execute copy ssh command")?;
+
+        if !status.success() {
+            bail!("copy ssh command exited with {status}");
+        }
+
+        Ok(())
+    }
+
     pub async fn power_off(&self) -> Result<()> {
         let status = self
             .ssh_to_nao()
@@ -476,6 +548,16 @@ fn extract_version_number(input: &str) -> Option<String> {
     None
 }
 
+/// Locations ending in `_location` are managed by the upload and must never be replaced, the
+/// name is put into a shell command and must not contain anything besides a plain file name
+fn is_selectable_location(location: &str) -> bool {
+    !location.is_empty()
+        && !location.ends_with("_location")
+        && location
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || "_-".contains(character))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -492,4 +574,16 @@ mod test {
         let output = extract_version_number(input);
         assert_eq!(output, Some("5.1.3".to_string()));
     }
+
+    #[test]
+    fn only_plain_uploaded_locations_are_selectable() {
+        assert!(is_selectable_location("webots"));
+        assert!(is_selectable_location("robocup-2024_field_a"));
+        assert!(!is_selectable_location(""));
+        assert!(!is_selectable_location("nao_location"));
+        assert!(!is_selectable_location("upcoming_nao_location"));
+        assert!(!is_selectable_location(".."));
+        assert!(!is_selectable_location("../../../"));
+        assert!(!is_selectable_location("webots; rm -rf ~"));
+    }
 }
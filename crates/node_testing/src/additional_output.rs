@@ -0,0 +1,36 @@
+use framework::AdditionalOutput;
+
+/// Owns the data an [`AdditionalOutput`] writes to, so tests can inspect what a node filled.
+#[derive(Debug)]
+pub struct AdditionalOutputCapture<DataType> {
+    is_subscribed: bool,
+    data: Option<DataType>,
+}
+
+impl<DataType> AdditionalOutputCapture<DataType> {
+    pub fn subscribed() -> Self {
+        Self {
+            is_subscribed: true,
+            data: None,
+        }
+    }
+
+    pub fn unsubscribed() -> Self {
+        Self {
+            is_subscribed: false,
+            data: None,
+        }
+    }
+
+    pub fn output(&mut self) -> AdditionalOutput<'_, DataType> {
+        AdditionalOutput::new(self.is_subscribed, &mut self.data)
+    }
+
+    pub fn captured(&self) -> Option<&DataType> {
+        self.data.as_ref()
+    }
+
+    pub fn take(&mut self) -> Option<DataType> {
+        self.data.take()
+    }
+}
@@ -0,0 +1,22 @@
+use parking_lot::Mutex;
+
+use hardware::SpeakerInterface;
+use types::audio::SpeakerRequest;
+
+/// Hardware interface recording everything a node writes to it.
+#[derive(Debug, Default)]
+pub struct FakeHardwareInterface {
+    speaker_requests: Mutex<Vec<SpeakerRequest>>,
+}
+
+impl FakeHardwareInterface {
+    pub fn take_speaker_requests(&self) -> Vec<SpeakerRequest> {
+        std::mem::take(&mut *self.speaker_requests.lock())
+    }
+}
+
+impl SpeakerInterface for FakeHardwareInterface {
+    fn write_to_speakers(&self, request: SpeakerRequest) {
+        self.speaker_requests.lock().push(request);
+    }
+}
@@ -0,0 +1,32 @@
+use std::{collections::BTreeMap, time::SystemTime};
+
+use framework::HistoricInput;
+
+/// Collects owned values of a `HistoricInput` and lends them to a context.
+#[derive(Debug)]
+pub struct HistoricInputBuilder<DataType> {
+    historic: BTreeMap<SystemTime, DataType>,
+}
+
+impl<DataType> Default for HistoricInputBuilder<DataType> {
+    fn default() -> Self {
+        Self {
+            historic: BTreeMap::new(),
+        }
+    }
+}
+
+impl<DataType> HistoricInputBuilder<DataType> {
+    pub fn with(mut self, time: SystemTime, data: DataType) -> Self {
+        self.historic.insert(time, data);
+        self
+    }
+
+    pub fn build(&self) -> HistoricInput<&DataType> {
+        self.historic
+            .iter()
+            .map(|(time, data)| (*time, data))
+            .collect::<BTreeMap<_, _>>()
+            .into()
+    }
+}
@@ -0,0 +1,33 @@
+//! Helpers for unit testing nodes without running a cycler.
+//!
+//! Contexts generated by `#[context]` only hold references, so tests own the data in the builders
+//! of this crate and borrow from them when calling `CycleContext::new(...)`.
+
+mod additional_output;
+mod hardware_interface;
+mod historic_input;
+mod parameters;
+mod perception_input;
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use types::cycle_time::CycleTime;
+
+pub use additional_output::AdditionalOutputCapture;
+pub use hardware_interface::FakeHardwareInterface;
+pub use historic_input::HistoricInputBuilder;
+pub use parameters::TestParameters;
+pub use perception_input::PerceptionInputBuilder;
+
+/// Returns a point in time `offset` after an arbitrary but fixed epoch.
+pub fn time(offset: Duration) -> SystemTime {
+    UNIX_EPOCH + offset
+}
+
+/// Returns a cycle time starting `offset` after the epoch of [`time`].
+pub fn cycle_time(offset: Duration, last_cycle_duration: Duration) -> CycleTime {
+    CycleTime {
+        start_time: time(offset),
+        last_cycle_duration,
+    }
+}
@@ -0,0 +1,60 @@
+use std::{fs::File, path::Path};
+
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+const DEFAULT_PARAMETERS: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../etc/parameters/default.json"
+);
+
+/// Parameters of the repository defaults, optionally overridden per test case.
+#[derive(Clone, Debug)]
+pub struct TestParameters {
+    parameters: Value,
+}
+
+impl TestParameters {
+    pub fn load_default() -> Result<Self> {
+        Self::load(DEFAULT_PARAMETERS)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file =
+            File::open(path).wrap_err_with(|| format!("failed to open {}", path.display()))?;
+        let parameters = serde_json::from_reader(file)
+            .wrap_err_with(|| format!("failed to parse {}", path.display()))?;
+        Ok(Self { parameters })
+    }
+
+    /// Deserializes the parameter at a dot-separated `path`, e.g. `"spl_network.game_controller_timeout"`.
+    pub fn get<DataType: DeserializeOwned>(&self, path: &str) -> Result<DataType> {
+        let value = self
+            .parameters
+            .pointer(&to_pointer(path))
+            .ok_or_else(|| eyre!("no parameter at {path}"))?;
+        DataType::deserialize(value).wrap_err_with(|| format!("failed to deserialize {path}"))
+    }
+
+    pub fn set(mut self, path: &str, value: impl Serialize) -> Result<Self> {
+        let value = serde_json::to_value(value)
+            .wrap_err_with(|| format!("failed to serialize value for {path}"))?;
+        let slot = self
+            .parameters
+            .pointer_mut(&to_pointer(path))
+            .ok_or_else(|| eyre!("no parameter at {path}"))?;
+        *slot = value;
+        Ok(self)
+    }
+}
+
+fn to_pointer(path: &str) -> String {
+    path.split('.')
+        .map(|segment| format!("/{segment}"))
+        .collect()
+}
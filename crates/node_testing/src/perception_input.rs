@@ -0,0 +1,66 @@
+use std::{collections::BTreeMap, time::SystemTime};
+
+use framework::PerceptionInput;
+
+/// Collects owned values of a `PerceptionInput` and lends them to a context.
+#[derive(Debug)]
+pub struct PerceptionInputBuilder<DataType> {
+    persistent: BTreeMap<SystemTime, Vec<DataType>>,
+    temporary: BTreeMap<SystemTime, Vec<DataType>>,
+}
+
+impl<DataType> Default for PerceptionInputBuilder<DataType> {
+    fn default() -> Self {
+        Self {
+            persistent: BTreeMap::new(),
+            temporary: BTreeMap::new(),
+        }
+    }
+}
+
+impl<DataType> PerceptionInputBuilder<DataType> {
+    pub fn persistent(mut self, time: SystemTime, data: DataType) -> Self {
+        self.persistent.entry(time).or_default().push(data);
+        self
+    }
+
+    pub fn temporary(mut self, time: SystemTime, data: DataType) -> Self {
+        self.temporary.entry(time).or_default().push(data);
+        self
+    }
+
+    pub fn build(&self) -> PerceptionInput<Vec<&DataType>> {
+        PerceptionInput {
+            persistent: borrow_all(&self.persistent),
+            temporary: borrow_all(&self.temporary),
+        }
+    }
+}
+
+impl<DataType> PerceptionInputBuilder<Option<DataType>> {
+    /// Builds the input of optional paths like `"filtered_message?"`.
+    pub fn build_optional(&self) -> PerceptionInput<Vec<Option<&DataType>>> {
+        PerceptionInput {
+            persistent: borrow_all_optional(&self.persistent),
+            temporary: borrow_all_optional(&self.temporary),
+        }
+    }
+}
+
+fn borrow_all<DataType>(
+    databases: &BTreeMap<SystemTime, Vec<DataType>>,
+) -> BTreeMap<SystemTime, Vec<&DataType>> {
+    databases
+        .iter()
+        .map(|(time, data)| (*time, data.iter().collect()))
+        .collect()
+}
+
+fn borrow_all_optional<DataType>(
+    databases: &BTreeMap<SystemTime, Vec<Option<DataType>>>,
+) -> BTreeMap<SystemTime, Vec<Option<&DataType>>> {
+    databases
+        .iter()
+        .map(|(time, data)| (*time, data.iter().map(Option::as_ref).collect()))
+        .collect()
+}
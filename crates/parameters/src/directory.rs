@@ -1,14 +1,22 @@
 use std::{
+    fs::canonicalize,
     io,
     path::{Path, PathBuf},
 };
 
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
-use serde_json::{error, from_str, from_value, to_string_pretty, to_value, Value};
+use serde_json::{error, from_str, from_value, json, to_string_pretty, to_value, Value};
 use tokio::fs::{read_to_string, write};
 
 use super::json::{clone_nested_value, merge_json, prune_equal_branches};
 
+/// Symlink to the location selected from the connected WiFi network, takes precedence over
+/// `nao_location` on NAOs
+pub const NETWORK_LOCATION_DIRECTORY: &str = "network_location";
+
+/// Maps WiFi network names to locations
+pub const NETWORK_LOCATIONS_FILE: &str = "network_locations.json";
+
 #[derive(Debug, thiserror::Error)]
 pub enum DirectoryError {
     #[error("failed to get default parameters")]
@@ -57,9 +65,7 @@ where
         .await
         .map_err(DirectoryError::DefaultParametersNotGet)?;
 
-    let location_directory = parameters_root_path
-        .as_ref()
-        .join(location_directory_from_head_id(head_id));
+    let location_directory = location_directory(parameters_root_path.as_ref(), head_id);
 
     let location_default_file_path = location_directory.join("default.json");
     if location_default_file_path.exists() {
@@ -105,6 +111,19 @@ where
         merge_json(&mut parameters, &location_head_parameters);
     }
 
+    if let Some(name) = location_name(&location_directory) {
+        let is_detected_from_network = location_directory.ends_with(NETWORK_LOCATION_DIRECTORY);
+        merge_json(
+            &mut parameters,
+            &json!({
+                "location": {
+                    "name": name,
+                    "is_detected_from_network": is_detected_from_network,
+                },
+            }),
+        );
+    }
+
     from_value(parameters).map_err(DirectoryError::JsonValueNotConvertedToParameters)
 }
 
@@ -179,9 +198,7 @@ fn file_path_from_scope(
 ) -> PathBuf {
     let directory = match scope.location {
         Location::All => parameters_root_path.as_ref().to_path_buf(),
-        Location::Current => parameters_root_path
-            .as_ref()
-            .join(location_directory_from_head_id(head_id)),
+        Location::Current => location_directory(parameters_root_path.as_ref(), head_id),
     };
     match scope.id {
         Id::All => directory.join("default.json"),
@@ -190,6 +207,22 @@ fn file_path_from_scope(
     }
 }
 
+fn location_directory(parameters_root_path: &Path, head_id: &str) -> PathBuf {
+    let network_location_directory = parameters_root_path.join(NETWORK_LOCATION_DIRECTORY);
+    let is_nao = location_directory_from_head_id(head_id) == "nao_location";
+    if is_nao && network_location_directory.exists() {
+        network_location_directory
+    } else {
+        parameters_root_path.join(location_directory_from_head_id(head_id))
+    }
+}
+
+/// Name of the directory the location symlink points to
+fn location_name(location_directory: &Path) -> Option<String> {
+    let resolved_directory = canonicalize(location_directory).ok()?;
+    Some(resolved_directory.file_name()?.to_str()?.to_string())
+}
+
 fn location_directory_from_head_id(head_id: &str) -> &'static str {
     let webots_id_found = head_id.starts_with("webots");
     let behavior_simulator_id_found = head_id.starts_with("behavior_simulator");
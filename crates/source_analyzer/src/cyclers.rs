@@ -3,14 +3,14 @@ use std::{
     path::Path,
 };
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use toposort_scc::IndexGraph;
 
 use crate::{
     contexts::Field,
     error::Error,
     manifest::{CyclerManifest, FrameworkManifest},
-    node::Node,
+    node::{Node, NodeName},
 };
 
 pub type CyclerName = String;
@@ -77,7 +77,7 @@ impl Cyclers {
     }
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum CyclerKind {
     Perception,
     RealTime,
@@ -90,6 +90,7 @@ pub struct Cycler {
     pub instances: Vec<InstanceName>,
     pub setup_nodes: Vec<Node>,
     pub cycle_nodes: Vec<Node>,
+    pub persisted_nodes: BTreeSet<NodeName>,
 }
 
 impl Cycler {
@@ -109,6 +110,20 @@ impl Cycler {
             .iter()
             .map(|specification| Node::try_from_node_name(specification, root))
             .collect::<Result<Vec<_>, _>>()?;
+        let persisted_nodes = cycler_manifest
+            .persisted_nodes
+            .iter()
+            .map(|specification| {
+                cycler_manifest
+                    .nodes
+                    .iter()
+                    .position(|node| node == specification)
+                    .map(|index| cycle_nodes[index].name.clone())
+                    .ok_or_else(|| Error::UnknownPersistedNode {
+                        node: specification.to_string(),
+                    })
+            })
+            .collect::<Result<_, _>>()?;
 
         let mut cycler = Self {
             name: cycler_manifest.name.to_string(),
@@ -116,6 +131,7 @@ impl Cycler {
             instances,
             setup_nodes,
             cycle_nodes,
+            persisted_nodes,
         };
         cycler.sort_nodes()?;
 
@@ -169,6 +185,12 @@ impl Cycler {
     pub fn iter_nodes(&self) -> impl Iterator<Item = &Node> {
         self.setup_nodes.iter().chain(self.cycle_nodes.iter())
     }
+
+    pub fn iter_persisted_nodes(&self) -> impl Iterator<Item = &Node> {
+        self.cycle_nodes
+            .iter()
+            .filter(|node| self.persisted_nodes.contains(&node.name))
+    }
 }
 
 fn sort_nodes(
@@ -21,6 +21,8 @@ pub enum Error {
     InvalidModulePath,
     #[error("`{node}` requires output `{output}`, but it is never produced")]
     MissingOutput { node: String, output: String },
+    #[error("persisted node `{node}` is not a node of its cycler")]
+    UnknownPersistedNode { node: String },
     #[error("failed to sort nodes, circular dependency detected")]
     CircularDependency,
 }
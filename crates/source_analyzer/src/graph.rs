@@ -0,0 +1,285 @@
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    contexts::Field,
+    cyclers::{Cycler, CyclerKind, Cyclers},
+    node::Node,
+    path::Path,
+};
+
+/// Machine-readable view of all cyclers, their nodes, and the fields connecting them.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Graph {
+    pub cyclers: Vec<GraphCycler>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GraphCycler {
+    pub name: String,
+    pub kind: CyclerKind,
+    pub instances: Vec<String>,
+    pub nodes: Vec<GraphNode>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GraphNode {
+    pub name: String,
+    pub module: String,
+    pub is_setup_node: bool,
+    pub creation_context: Vec<GraphField>,
+    pub cycle_context: Vec<GraphField>,
+    pub main_outputs: Vec<GraphField>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GraphField {
+    pub name: String,
+    pub kind: FieldKind,
+    pub data_type: Option<String>,
+    pub path: Option<String>,
+    pub cycler_instance: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum FieldKind {
+    AdditionalOutput,
+    CyclerState,
+    HardwareInterface,
+    HistoricInput,
+    Input,
+    MainOutput,
+    Parameter,
+    PerceptionInput,
+    RequiredInput,
+}
+
+impl Graph {
+    pub fn cycler(&self, name: &str) -> Option<&GraphCycler> {
+        self.cyclers.iter().find(|cycler| cycler.name == name)
+    }
+
+    /// Finds the node writing the main output that is the first segment of `path`.
+    pub fn producer_of(&self, cycler: &str, path: &str) -> Option<&GraphNode> {
+        let output_name = path.split('.').next()?.trim_end_matches('?');
+        self.cycler(cycler)?.nodes.iter().find(|node| {
+            node.main_outputs
+                .iter()
+                .any(|field| field.name == output_name)
+        })
+    }
+
+    pub fn cycler_of_instance(&self, instance: &str) -> Option<&GraphCycler> {
+        self.cyclers
+            .iter()
+            .find(|cycler| cycler.instances.iter().any(|name| name == instance))
+    }
+
+    /// Finds the cycler an input field of a node in `consuming_cycler` reads from.
+    pub fn source_cycler_of<'graph>(
+        &'graph self,
+        consuming_cycler: &'graph GraphCycler,
+        field: &GraphField,
+    ) -> Option<&'graph GraphCycler> {
+        match field.kind {
+            FieldKind::HistoricInput
+            | FieldKind::Input
+            | FieldKind::PerceptionInput
+            | FieldKind::RequiredInput => match &field.cycler_instance {
+                Some(instance) => self.cycler_of_instance(instance),
+                None => Some(consuming_cycler),
+            },
+            _ => None,
+        }
+    }
+
+    /// Lists all nodes (with their cycler) reading the main output `output_name` of `cycler`.
+    pub fn consumers_of<'graph>(
+        &'graph self,
+        cycler: &'graph str,
+        output_name: &'graph str,
+    ) -> impl Iterator<Item = (&'graph GraphCycler, &'graph GraphNode)> + 'graph {
+        self.cyclers.iter().flat_map(move |consuming_cycler| {
+            consuming_cycler
+                .nodes
+                .iter()
+                .filter(move |node| {
+                    node.cycle_context.iter().any(|field| {
+                        self.source_cycler_of(consuming_cycler, field)
+                            .is_some_and(|source_cycler| source_cycler.name == cycler)
+                            && field.output_name() == Some(output_name)
+                    })
+                })
+                .map(move |node| (consuming_cycler, node))
+        })
+    }
+}
+
+impl GraphField {
+    /// Name of the main output an input field refers to, i.e. the first segment of its path.
+    pub fn output_name(&self) -> Option<&str> {
+        self.path
+            .as_deref()
+            .and_then(|path| path.split('.').next())
+            .map(|name| name.trim_end_matches('?'))
+    }
+}
+
+impl From<&Cyclers> for Graph {
+    fn from(cyclers: &Cyclers) -> Self {
+        Self {
+            cyclers: cyclers.cyclers.iter().map(GraphCycler::from).collect(),
+        }
+    }
+}
+
+impl From<&Cycler> for GraphCycler {
+    fn from(cycler: &Cycler) -> Self {
+        let setup_nodes = cycler
+            .setup_nodes
+            .iter()
+            .map(|node| GraphNode::from_node(node, true));
+        let cycle_nodes = cycler
+            .cycle_nodes
+            .iter()
+            .map(|node| GraphNode::from_node(node, false));
+        Self {
+            name: cycler.name.clone(),
+            kind: cycler.kind,
+            instances: cycler.instances.clone(),
+            nodes: setup_nodes.chain(cycle_nodes).collect(),
+        }
+    }
+}
+
+impl GraphNode {
+    fn from_node(node: &Node, is_setup_node: bool) -> Self {
+        let to_fields = |fields: &[Field]| fields.iter().map(GraphField::from).collect();
+        Self {
+            name: node.name.clone(),
+            module: node.module.to_token_stream().to_string().replace(' ', ""),
+            is_setup_node,
+            creation_context: to_fields(&node.contexts.creation_context),
+            cycle_context: to_fields(&node.contexts.cycle_context),
+            main_outputs: to_fields(&node.contexts.main_outputs),
+        }
+    }
+}
+
+impl From<&Field> for GraphField {
+    fn from(field: &Field) -> Self {
+        let to_string =
+            |data_type: &syn::Type| Some(data_type.to_token_stream().to_string().replace(' ', ""));
+        let (kind, name, data_type, path, cycler_instance) = match field {
+            Field::AdditionalOutput {
+                data_type,
+                name,
+                path,
+            } => (
+                FieldKind::AdditionalOutput,
+                name,
+                to_string(data_type),
+                Some(path),
+                None,
+            ),
+            Field::CyclerState {
+                data_type,
+                name,
+                path,
+            } => (
+                FieldKind::CyclerState,
+                name,
+                to_string(data_type),
+                Some(path),
+                None,
+            ),
+            Field::HardwareInterface { name } => {
+                (FieldKind::HardwareInterface, name, None, None, None)
+            }
+            Field::HistoricInput {
+                data_type,
+                name,
+                path,
+            } => (
+                FieldKind::HistoricInput,
+                name,
+                to_string(data_type),
+                Some(path),
+                None,
+            ),
+            Field::Input {
+                cycler_instance,
+                data_type,
+                name,
+                path,
+            } => (
+                FieldKind::Input,
+                name,
+                to_string(data_type),
+                Some(path),
+                cycler_instance.clone(),
+            ),
+            Field::MainOutput { data_type, name } => (
+                FieldKind::MainOutput,
+                name,
+                to_string(data_type),
+                None,
+                None,
+            ),
+            Field::Parameter {
+                data_type,
+                name,
+                path,
+            } => (
+                FieldKind::Parameter,
+                name,
+                to_string(data_type),
+                Some(path),
+                None,
+            ),
+            Field::PerceptionInput {
+                cycler_instance,
+                data_type,
+                name,
+                path,
+            } => (
+                FieldKind::PerceptionInput,
+                name,
+                to_string(data_type),
+                Some(path),
+                Some(cycler_instance.clone()),
+            ),
+            Field::RequiredInput {
+                cycler_instance,
+                data_type,
+                name,
+                path,
+            } => (
+                FieldKind::RequiredInput,
+                name,
+                to_string(data_type),
+                Some(path),
+                cycler_instance.clone(),
+            ),
+        };
+        Self {
+            name: name.to_string(),
+            kind,
+            data_type,
+            path: path.map(path_to_string),
+            cycler_instance,
+        }
+    }
+}
+
+fn path_to_string(path: &Path) -> String {
+    path.segments
+        .iter()
+        .map(|segment| {
+            let prefix = if segment.is_variable { "$" } else { "" };
+            let suffix = if segment.is_optional { "?" } else { "" };
+            format!("{prefix}{}{suffix}", segment.name)
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
@@ -1,6 +1,7 @@
 pub mod contexts;
 pub mod cyclers;
 pub mod error;
+pub mod graph;
 pub mod manifest;
 pub mod node;
 pub mod path;
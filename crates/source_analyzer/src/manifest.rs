@@ -14,4 +14,6 @@ pub struct CyclerManifest {
     pub instances: Vec<&'static str>,
     pub setup_nodes: Vec<&'static str>,
     pub nodes: Vec<&'static str>,
+    /// Nodes whose state is restored after the process restarted
+    pub persisted_nodes: Vec<&'static str>,
 }
@@ -5,9 +5,10 @@ use std::{
 
 use log::warn;
 use serde::Deserialize;
+use spl_network_messages::GameControllerStateMessage;
 use thiserror::Error;
 use tokio::{net::UdpSocket, select, sync::Mutex};
-use types::messages::{IncomingMessage, OutgoingMessage};
+use types::messages::{GameControllerDestination, IncomingMessage, OutgoingMessage};
 
 pub struct Endpoint {
     ports: Ports,
@@ -58,7 +59,11 @@ impl Endpoint {
                     match game_controller_state_buffer[0..received_bytes].try_into() {
                         Ok(parsed_message) => {
                             *self.last_game_controller_address.lock().await = Some(address);
-                            break Ok(IncomingMessage::GameController(parsed_message));
+                            break Ok(IncomingMessage::GameController(
+                                address,
+                                GameControllerDestination::StatePort,
+                                parsed_message,
+                            ));
                         }
                         Err(error) => {
                             warn!("Failed to parse GameController state message (will be discarded): {error:?}");
@@ -67,7 +72,17 @@ impl Endpoint {
                     }
                 },
                 result = self.spl_socket.recv_from(&mut spl_buffer) => {
-                    let (received_bytes, _address) = result.map_err(Error::ReadError)?;
+                    let (received_bytes, address) = result.map_err(Error::ReadError)?;
+                    if let Ok(parsed_message) =
+                        GameControllerStateMessage::try_from(&spl_buffer[0..received_bytes])
+                    {
+                        *self.last_game_controller_address.lock().await = Some(address);
+                        break Ok(IncomingMessage::GameController(
+                            address,
+                            GameControllerDestination::TeamPort,
+                            parsed_message,
+                        ));
+                    }
                     match bincode::deserialize(&spl_buffer[0..received_bytes]) {
                         Ok(parsed_message) => {
                             break Ok(IncomingMessage::Spl(parsed_message));
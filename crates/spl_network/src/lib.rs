@@ -1,3 +1,4 @@
 pub mod endpoint;
 pub mod message_filter;
 pub mod message_receiver;
+pub mod rate_limiter;
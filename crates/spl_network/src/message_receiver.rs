@@ -1,12 +1,19 @@
+use std::{
+    thread::sleep,
+    time::{Duration, SystemTime},
+};
+
 use color_eyre::{eyre::WrapErr, Result};
 use context_attribute::context;
 use framework::MainOutput;
-use hardware::NetworkInterface;
+use hardware::{NetworkInterface, TimeInterface};
 use serde::{Deserialize, Serialize};
 use types::messages::IncomingMessage;
 
 #[derive(Deserialize, Serialize)]
-pub struct MessageReceiver {}
+pub struct MessageReceiver {
+    last_cycle_start: Option<SystemTime>,
+}
 
 #[context]
 pub struct CreationContext {}
@@ -14,6 +21,7 @@ pub struct CreationContext {}
 #[context]
 pub struct CycleContext {
     hardware_interface: HardwareInterface,
+    minimum_cycle_interval: CyclerState<Duration, "minimum_cycle_interval">,
 }
 
 #[context]
@@ -23,14 +31,32 @@ pub struct MainOutputs {
 
 impl MessageReceiver {
     pub fn new(_context: CreationContext) -> Result<Self> {
-        Ok(Self {})
+        Ok(Self {
+            last_cycle_start: None,
+        })
     }
 
-    pub fn cycle(&mut self, context: CycleContext<impl NetworkInterface>) -> Result<MainOutputs> {
+    pub fn cycle(
+        &mut self,
+        context: CycleContext<impl NetworkInterface + TimeInterface>,
+    ) -> Result<MainOutputs> {
+        // while downscaled, messages wait in the socket buffer instead of being dropped, otherwise
+        // the rate is unlimited and every message is read as soon as it arrives
+        if let Some(last_cycle_start) = self.last_cycle_start {
+            let elapsed = context
+                .hardware_interface
+                .get_now()
+                .duration_since(last_cycle_start)
+                .unwrap_or_default();
+            if let Some(remaining) = context.minimum_cycle_interval.checked_sub(elapsed) {
+                sleep(remaining);
+            }
+        }
         let message = context
             .hardware_interface
             .read_from_network()
             .wrap_err("failed to read from network")?;
+        self.last_cycle_start = Some(context.hardware_interface.get_now());
         Ok(MainOutputs {
             message: message.into(),
         })
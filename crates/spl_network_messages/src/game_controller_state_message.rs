@@ -23,8 +23,8 @@ use crate::{
         PENALTY_SPL_PLAYER_STANCE, PENALTY_SPL_REQUEST_FOR_PICKUP, PENALTY_SUBSTITUTE,
         SET_PLAY_CORNER_KICK, SET_PLAY_GOAL_KICK, SET_PLAY_KICK_IN, SET_PLAY_NONE,
         SET_PLAY_PENALTY_KICK, SET_PLAY_PUSHING_FREE_KICK, STATE_FINISHED, STATE_INITIAL,
-        STATE_PLAYING, STATE_READY, STATE_SET, TEAM_BLACK, TEAM_BLUE, TEAM_BROWN, TEAM_GRAY,
-        TEAM_GREEN, TEAM_ORANGE, TEAM_PURPLE, TEAM_RED, TEAM_WHITE, TEAM_YELLOW,
+        STATE_PLAYING, STATE_READY, STATE_SET, STATE_STANDBY, TEAM_BLACK, TEAM_BLUE, TEAM_BROWN,
+        TEAM_GRAY, TEAM_GREEN, TEAM_ORANGE, TEAM_PURPLE, TEAM_RED, TEAM_WHITE, TEAM_YELLOW,
     },
     PlayerNumber, HULKS_TEAM_NUMBER,
 };
@@ -229,7 +229,9 @@ impl CompetitionType {
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy,
+)]
 pub enum GamePhase {
     #[default]
     Normal,
@@ -260,6 +262,7 @@ impl GamePhase {
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
 pub enum GameState {
     Initial,
+    Standby,
     Ready,
     Set,
     Playing,
@@ -270,6 +273,7 @@ impl GameState {
     fn try_from(game_state: u8) -> Result<Self> {
         match game_state {
             STATE_INITIAL => Ok(GameState::Initial),
+            STATE_STANDBY => Ok(GameState::Standby),
             STATE_READY => Ok(GameState::Ready),
             STATE_SET => Ok(GameState::Set),
             STATE_PLAYING => Ok(GameState::Playing),
@@ -314,7 +318,9 @@ impl SubState {
     }
 }
 
-#[derive(Default, Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
+#[derive(
+    Default, Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy,
+)]
 pub enum SubState {
     #[default]
     GoalKick,
@@ -324,8 +330,11 @@ pub enum SubState {
     PenaltyKick,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy,
+)]
 pub enum Half {
+    #[default]
     First,
     Second,
 }
@@ -454,4 +463,23 @@ impl Penalty {
             _ => bail!("unexpected penalty type"),
         }
     }
+
+    /// Time until the GameController releases the player, zero if it is not counting down
+    pub fn remaining(&self) -> Duration {
+        match self {
+            Penalty::IllegalBallContact { remaining }
+            | Penalty::PlayerPushing { remaining }
+            | Penalty::IllegalMotionInInitial { remaining }
+            | Penalty::IllegalMotionInSet { remaining }
+            | Penalty::InactivePlayer { remaining }
+            | Penalty::IllegalPosition { remaining }
+            | Penalty::LeavingTheField { remaining }
+            | Penalty::RequestForPickup { remaining }
+            | Penalty::LocalGameStuck { remaining }
+            | Penalty::IllegalPositionInSet { remaining }
+            | Penalty::PlayerStance { remaining }
+            | Penalty::Substitute { remaining }
+            | Penalty::Manual { remaining } => *remaining,
+        }
+    }
 }
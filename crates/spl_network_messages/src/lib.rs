@@ -24,9 +24,92 @@ pub use visual_referee_message::{VisualRefereeDecision, VisualRefereeMessage};
 pub struct HulkMessage {
     pub player_number: PlayerNumber,
     pub fallen: bool,
+    pub is_keeper_capable: bool,
+    /// Low battery or hot joints, the sender avoids walking
+    pub is_degraded: bool,
     pub pose: Pose2<Field>,
     pub ball_position: Option<BallPosition<Field>>,
     pub time_to_reach_kick_position: Option<Duration>,
+    pub search_sector: Option<u8>,
+    pub pass: Option<PassAnnouncement>,
+    /// Confidence of the sender's detection of the referee's ready signal during Standby
+    pub ready_signal_confidence: Option<f32>,
+    pub goal_mouth_claim: Option<GoalMouthClaim>,
+    pub role: Role,
+    /// What the sender is about to do with the ball, only sent by the striker
+    pub striker_intent: Option<StrikerIntent>,
+    pub game_controller_state: Option<GameControllerStateDigest>,
+}
+
+/// Compact copy of the latest GameController state of the sender, only sent while the sender
+/// receives the GameController directly. Teammates with broken GameController reception follow
+/// the game with it.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
+pub struct GameControllerStateDigest {
+    pub game_state: GameState,
+    pub game_phase: GamePhase,
+    pub sub_state: Option<SubState>,
+    pub kicking_team: Team,
+    pub remaining_seconds_in_half: u16,
+    /// Bit `n` is set if the player with number `n + 1` is penalized
+    pub penalized_players: u8,
+    pub remaining_amount_of_messages: u16,
+    pub hulks_score: u8,
+    pub opponent_score: u8,
+    pub hulks_team_is_home_after_coin_toss: bool,
+}
+
+impl GameControllerStateDigest {
+    /// Whether both describe the same situation of the game, the counters running down during
+    /// the game are ignored
+    pub fn has_same_situation(&self, other: &Self) -> bool {
+        self.game_state == other.game_state
+            && self.game_phase == other.game_phase
+            && self.sub_state == other.sub_state
+            && self.kicking_team == other.kicking_team
+            && self.penalized_players == other.penalized_players
+            && self.hulks_score == other.hulks_score
+            && self.opponent_score == other.opponent_score
+            && self.hulks_team_is_home_after_coin_toss == other.hulks_team_is_home_after_coin_toss
+    }
+}
+
+#[derive(
+    Default, Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy,
+)]
+pub enum Role {
+    DefenderLeft,
+    DefenderRight,
+    Keeper,
+    Loser,
+    MidfielderLeft,
+    MidfielderRight,
+    ReplacementKeeper,
+    Searcher,
+    #[default]
+    Striker,
+    StrikerSupporter,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+pub enum StrikerIntent {
+    Dribble,
+    Pass,
+    Shot,
+}
+
+/// Announces that the sender is about to pass the ball to `receiver` at `target`.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, SerializeHierarchy)]
+pub struct PassAnnouncement {
+    pub receiver: PlayerNumber,
+    pub target: Point2<Field>,
+}
+
+/// Claims the own penalty area for the sender while it clears the ball towards
+/// `clearing_target`, the keeper keeps out of its way.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize, SerializeHierarchy)]
+pub struct GoalMouthClaim {
+    pub clearing_target: Point2<Field>,
 }
 
 #[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -38,7 +121,18 @@ pub struct BallPosition<Frame> {
 pub const HULKS_TEAM_NUMBER: u8 = 24;
 
 #[derive(
-    Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize, SerializeHierarchy,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Serialize,
+    SerializeHierarchy,
 )]
 pub enum PlayerNumber {
     One,
@@ -73,19 +167,49 @@ mod tests {
 
     use linear_algebra::{Point, Pose2};
 
-    use crate::{BallPosition, HulkMessage, PlayerNumber};
+    use crate::{
+        BallPosition, GameControllerStateDigest, GamePhase, GameState, GoalMouthClaim, HulkMessage,
+        PassAnnouncement, PlayerNumber, Role, StrikerIntent, SubState, Team,
+    };
 
     #[test]
     fn maximum_hulk_message_size() {
         let test_message = HulkMessage {
             player_number: PlayerNumber::Seven,
             fallen: false,
+            is_keeper_capable: true,
+            is_degraded: true,
             pose: Pose2::default(),
             ball_position: Some(BallPosition {
                 position: Point::origin(),
                 age: Duration::MAX,
             }),
             time_to_reach_kick_position: Some(Duration::MAX),
+            search_sector: Some(u8::MAX),
+            pass: Some(PassAnnouncement {
+                receiver: PlayerNumber::Seven,
+                target: Point::origin(),
+            }),
+            ready_signal_confidence: Some(1.0),
+            goal_mouth_claim: Some(GoalMouthClaim {
+                clearing_target: Point::origin(),
+            }),
+            role: Role::StrikerSupporter,
+            striker_intent: Some(StrikerIntent::Shot),
+            game_controller_state: Some(GameControllerStateDigest {
+                game_state: GameState::Playing,
+                game_phase: GamePhase::PenaltyShootout {
+                    kicking_team: Team::Opponent,
+                },
+                sub_state: Some(SubState::PenaltyKick),
+                kicking_team: Team::Opponent,
+                remaining_seconds_in_half: u16::MAX,
+                penalized_players: u8::MAX,
+                remaining_amount_of_messages: u16::MAX,
+                hulks_score: u8::MAX,
+                opponent_score: u8::MAX,
+                hulks_team_is_home_after_coin_toss: true,
+            }),
         };
         assert!(bincode::serialize(&test_message).unwrap().len() <= 128)
     }
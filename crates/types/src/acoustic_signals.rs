@@ -0,0 +1,29 @@
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize, SerializeHierarchy)]
+pub enum AcousticEvent {
+    Striker,
+    BallNearOwnGoal,
+}
+
+/// A chirp is encoded by two simultaneously played tones.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct AcousticCode {
+    pub low_frequency: f32,
+    pub high_frequency: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct AcousticDetection {
+    pub striker: bool,
+    pub ball_near_own_goal: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct HeardAcousticSignals {
+    pub last_striker: Option<SystemTime>,
+    pub last_ball_near_own_goal: Option<SystemTime>,
+}
@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 
-#[derive(Debug, Clone, Copy, SerializeHierarchy, Serialize, Deserialize)]
+use crate::{primary_state::PrimaryState, roles::Role};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, SerializeHierarchy, Serialize, Deserialize)]
 pub enum Action {
     Unstiff,
     SitDown,
@@ -9,23 +11,82 @@ pub enum Action {
     Initial,
     FallSafely,
     StandUp,
+    HoldPosition,
+    Demo,
     Stand,
     LookAround,
+    ReturnFromPenalty,
+    EmergencyClearance,
     InterceptBall,
     Calibrate,
+    Rest,
+    TakeKickOff,
+    TakePenaltyShot,
+    OrbitBall,
     Dribble,
+    Pass,
+    ReceivePass,
+    TakeFreeKick,
+    SupportFreeKick,
+    DefendFreeKick,
+    KeepFreeKickDistance,
+    FormWall,
     DefendGoal,
     DefendKickOff,
     DefendLeft,
     DefendRight,
+    Block,
     DefendPenaltyKick,
+    AwaitPenaltyRebound,
     Jump,
     PrepareJump,
+    ClearBall,
+    YieldGoalMouth,
     SupportLeft,
     SupportRight,
     SupportStriker,
+    MarkOpponent,
     Search,
     SearchForLostBall,
     WalkToKickOff,
     WalkToPenaltyKick,
+    /// Never part of the priority list, taken if none of its actions is applicable
+    SafeStand,
+}
+
+/// Outcome of a candidate action during the behavior's priority based selection
+#[derive(Debug, Clone, Copy, SerializeHierarchy, Serialize, Deserialize)]
+pub struct ActionEvaluation {
+    pub action: Action,
+    pub outcome: ActionOutcome,
+}
+
+#[derive(Debug, Clone, Copy, SerializeHierarchy, Serialize, Deserialize)]
+pub enum ActionOutcome {
+    Selected,
+    Rejected {
+        reason: RejectionReason,
+    },
+    /// A higher priority action was selected before this one was considered
+    NotEvaluated,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, SerializeHierarchy, Serialize, Deserialize)]
+pub enum RejectionReason {
+    WrongPrimaryState,
+    NotFalling,
+    NotFallen,
+    NotLocalized,
+    NoBall,
+    WrongGameState,
+    PreconditionsNotMet,
+}
+
+/// Describes a cycle in which none of the prioritized actions was applicable and the behavior
+/// fell back to [`Action::SafeStand`]
+#[derive(Debug, Clone, SerializeHierarchy, Serialize, Deserialize)]
+pub struct ActionResolutionError {
+    pub primary_state: PrimaryState,
+    pub role: Role,
+    pub rejected_actions: Vec<ActionEvaluation>,
 }
@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+/// World state changes the behavior is expected to react to.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+pub enum LatencyTrigger {
+    BallAppeared,
+    GameStateChanged,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ActionLatency {
+    pub trigger: LatencyTrigger,
+    /// Time from the trigger to the first motion command change reacting to it
+    pub latency: Duration,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct LatencyHistogram {
+    pub bin_width: Duration,
+    pub counts: Vec<usize>,
+    /// Triggers not followed by a motion command change within the range of the histogram
+    pub number_of_unanswered_triggers: usize,
+}
+
+impl LatencyHistogram {
+    pub fn new(bin_width: Duration, number_of_bins: usize) -> Self {
+        Self {
+            bin_width,
+            counts: vec![0; number_of_bins],
+            number_of_unanswered_triggers: 0,
+        }
+    }
+
+    pub fn maximum_latency(&self) -> Duration {
+        self.bin_width * self.counts.len() as u32
+    }
+
+    /// Latencies beyond the last bin, or any latency if the bins have no width, count as
+    /// unanswered
+    pub fn add(&mut self, latency: Duration) {
+        let bin = latency
+            .as_nanos()
+            .checked_div(self.bin_width.as_nanos())
+            .and_then(|bin| usize::try_from(bin).ok());
+        match bin.and_then(|bin| self.counts.get_mut(bin)) {
+            Some(count) => *count += 1,
+            None => self.number_of_unanswered_triggers += 1,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ActionLatencyReport {
+    pub ball_appeared: LatencyHistogram,
+    pub game_state_changed: LatencyHistogram,
+}
+
+impl ActionLatencyReport {
+    pub fn new(bin_width: Duration, number_of_bins: usize) -> Self {
+        Self {
+            ball_appeared: LatencyHistogram::new(bin_width, number_of_bins),
+            game_state_changed: LatencyHistogram::new(bin_width, number_of_bins),
+        }
+    }
+
+    pub fn histogram_mut(&mut self, trigger: LatencyTrigger) -> &mut LatencyHistogram {
+        match trigger {
+            LatencyTrigger::BallAppeared => &mut self.ball_appeared,
+            LatencyTrigger::GameStateChanged => &mut self.game_state_changed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latency_is_counted_in_its_bin() {
+        let mut histogram = LatencyHistogram::new(Duration::from_millis(50), 4);
+
+        histogram.add(Duration::from_millis(0));
+        histogram.add(Duration::from_millis(49));
+        histogram.add(Duration::from_millis(120));
+
+        assert_eq!(histogram.counts, [2, 0, 1, 0]);
+        assert_eq!(histogram.number_of_unanswered_triggers, 0);
+    }
+
+    #[test]
+    fn latency_beyond_the_last_bin_is_unanswered() {
+        let mut histogram = LatencyHistogram::new(Duration::from_millis(50), 4);
+
+        histogram.add(Duration::from_millis(200));
+
+        assert_eq!(histogram.counts, [0, 0, 0, 0]);
+        assert_eq!(histogram.number_of_unanswered_triggers, 1);
+    }
+
+    #[test]
+    fn zero_bin_width_does_not_panic() {
+        let mut histogram = LatencyHistogram::new(Duration::ZERO, 4);
+
+        histogram.add(Duration::from_millis(10));
+
+        assert_eq!(histogram.counts, [0, 0, 0, 0]);
+        assert_eq!(histogram.number_of_unanswered_triggers, 1);
+    }
+}
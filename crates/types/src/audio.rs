@@ -1,10 +1,20 @@
-use std::fmt::{self, Display, Formatter};
+use std::{
+    fmt::{self, Display, Formatter},
+    time::Duration,
+};
 
 use enum_iterator::Sequence;
 
 #[derive(Copy, Clone, Debug)]
 pub enum SpeakerRequest {
-    PlaySound { sound: Sound },
+    PlaySound {
+        sound: Sound,
+    },
+    /// Plays sine tones of the given frequencies simultaneously
+    PlayTones {
+        frequencies: [f32; 2],
+        duration: Duration,
+    },
 }
 
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Sequence)]
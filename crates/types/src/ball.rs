@@ -23,3 +23,18 @@ pub struct Ball {
     pub position: Point2<Ground>,
     pub image_location: Circle<Pixel>,
 }
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+pub enum PlausibilityDecision {
+    /// Close enough to the robot to be trusted without checks
+    Unchecked,
+    Accepted,
+    OutsideCarpet,
+    ImpossibleJump,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
+pub struct GatedBall {
+    pub position: Point2<Ground>,
+    pub decision: PlausibilityDecision,
+}
@@ -4,13 +4,26 @@ use serde::{Deserialize, Serialize};
 
 use coordinate_systems::Ground;
 use linear_algebra::{vector, Point};
+use nalgebra::Matrix2;
 use serialize_hierarchy::SerializeHierarchy;
 
 use crate::{
-    ball_position::BallPosition, multivariate_normal_distribution::MultivariateNormalDistribution,
+    ball_position::BallPosition, camera_position::CameraPosition,
+    multivariate_normal_distribution::MultivariateNormalDistribution,
     parameters::BallFilterParameters,
 };
 
+/// Measurement covariances the filter applied for a single ball detection.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, SerializeHierarchy)]
+pub struct MeasurementCovariance {
+    pub camera: CameraPosition,
+    pub distance: f32,
+    #[serialize_hierarchy(leaf)]
+    pub moving: Matrix2<f32>,
+    #[serialize_hierarchy(leaf)]
+    pub resting: Matrix2<f32>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, SerializeHierarchy)]
 pub struct Hypothesis {
     pub moving_state: MultivariateNormalDistribution<4>,
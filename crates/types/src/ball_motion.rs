@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use coordinate_systems::Field;
+use linear_algebra::{Point2, Vector2};
+use serialize_hierarchy::SerializeHierarchy;
+
+/// Derived quantities of the filtered ball movement.
+///
+/// Predictions assume the ball decelerates the same way the ball filter predicts it, i.e. the
+/// velocity decays exponentially.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, SerializeHierarchy)]
+pub struct BallMotion {
+    pub velocity: Vector2<Field>,
+    pub speed: f32,
+    /// Unit vector in rolling direction, zero if the ball is resting
+    pub direction: Vector2<Field>,
+    pub is_rolling: bool,
+    /// Position the ball comes to rest at if nothing stops it earlier
+    pub rest_position: Point2<Field>,
+    pub time_to_own_goal_line: Option<Duration>,
+    pub own_goal_line_crossing: Option<Point2<Field>>,
+    pub time_to_sideline: Option<Duration>,
+    pub sideline_crossing: Option<Point2<Field>>,
+}
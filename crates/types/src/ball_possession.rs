@@ -0,0 +1,16 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+use spl_network_messages::Team;
+
+/// Which team effectively controls the ball. The team only changes after the new estimate
+/// persisted for a while, a loose or contested ball is `Team::Uncertain`.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, SerializeHierarchy)]
+pub struct BallPossession {
+    pub team: Team,
+    /// How long the current team has been controlling the ball
+    pub duration: Duration,
+    /// Fraction of the controlled playing time of this game the own team had the ball
+    pub own_share: Option<f32>,
+}
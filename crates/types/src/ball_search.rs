@@ -0,0 +1,190 @@
+use std::time::{Duration, SystemTime};
+
+use coordinate_systems::Field;
+use linear_algebra::{distance, Point2};
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+use spl_network_messages::PlayerNumber;
+
+use crate::{
+    field_coverage::FieldCoverage, field_dimensions::FieldDimensions, field_grid::FieldGrid,
+};
+
+/// Coarse estimate of where the ball might be, one likelihood per search sector.
+///
+/// Sectors are the cells of a grid over the field.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct BallSearchHeatmap {
+    pub grid: FieldGrid,
+    pub likelihoods: Vec<f32>,
+}
+
+impl BallSearchHeatmap {
+    pub fn new(grid: FieldGrid) -> Self {
+        let number_of_sectors = grid.number_of_cells();
+        Self {
+            grid,
+            likelihoods: vec![1.0 / number_of_sectors as f32; number_of_sectors],
+        }
+    }
+
+    pub fn number_of_sectors(&self) -> usize {
+        self.likelihoods.len()
+    }
+
+    pub fn sector_center(
+        &self,
+        sector: usize,
+        field_dimensions: &FieldDimensions,
+    ) -> Point2<Field> {
+        self.grid.cell_center(sector, field_dimensions)
+    }
+
+    /// Concentrates the likelihood around a known ball position.
+    pub fn reset_around(
+        &mut self,
+        ball_position: Point2<Field>,
+        spread: f32,
+        field_dimensions: &FieldDimensions,
+    ) {
+        for sector in 0..self.number_of_sectors() {
+            let distance = distance(self.sector_center(sector, field_dimensions), ball_position);
+            self.likelihoods[sector] = (-distance.powi(2) / (2.0 * spread.powi(2))).exp();
+        }
+        self.normalize();
+    }
+
+    /// Blends the likelihoods towards a uniform distribution, `factor` of 1.0 yields uniform.
+    pub fn diffuse(&mut self, factor: f32) {
+        let uniform = 1.0 / self.number_of_sectors() as f32;
+        for likelihood in &mut self.likelihoods {
+            *likelihood += (uniform - *likelihood) * factor.clamp(0.0, 1.0);
+        }
+    }
+
+    /// Reduces the likelihood of each sector by `decay`, weighted with the share of its coverage
+    /// cells any robot of the team observed within `observation_timeout`.
+    ///
+    /// Teammates report their observations only every few seconds, the timeout keeps them in
+    /// effect in between.
+    pub fn observe(
+        &mut self,
+        coverage: &FieldCoverage,
+        now: SystemTime,
+        observation_timeout: Duration,
+        decay: f32,
+        field_dimensions: &FieldDimensions,
+    ) {
+        let mut number_of_cells = vec![0; self.number_of_sectors()];
+        let mut number_of_observed_cells = vec![0; self.number_of_sectors()];
+        for cell in 0..coverage.number_of_cells() {
+            let sector = self.grid.cell_containing(
+                coverage.cell_center(cell, field_dimensions),
+                field_dimensions,
+            );
+            number_of_cells[sector] += 1;
+            if coverage.age(cell, now, observation_timeout) < observation_timeout {
+                number_of_observed_cells[sector] += 1;
+            }
+        }
+        for (likelihood, (observed, total)) in self
+            .likelihoods
+            .iter_mut()
+            .zip(number_of_observed_cells.into_iter().zip(number_of_cells))
+        {
+            if total > 0 {
+                let observed_share = observed as f32 / total as f32;
+                *likelihood *= (1.0 - decay * observed_share).max(0.0);
+            }
+        }
+    }
+
+    /// Whether the sector is closer to the own position than to any of the other searchers.
+    ///
+    /// Partitions the field into non-overlapping search regions, one per searching robot.
+    pub fn is_in_own_region(
+        &self,
+        sector: usize,
+        own_position: Point2<Field>,
+        other_searchers: impl IntoIterator<Item = Point2<Field>>,
+        field_dimensions: &FieldDimensions,
+    ) -> bool {
+        let sector_center = self.sector_center(sector, field_dimensions);
+        let own_distance = distance(own_position, sector_center);
+        other_searchers
+            .into_iter()
+            .all(|searcher| own_distance <= distance(searcher, sector_center))
+    }
+
+    pub fn normalize(&mut self) {
+        let sum: f32 = self.likelihoods.iter().sum();
+        if sum > f32::EPSILON {
+            for likelihood in &mut self.likelihoods {
+                *likelihood /= sum;
+            }
+        } else {
+            *self = Self::new(self.grid);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
+pub struct SearchSectorClaim {
+    pub player_number: PlayerNumber,
+    pub sector: usize,
+    pub searcher_position: Point2<Field>,
+    pub last_update: SystemTime,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::UNIX_EPOCH;
+
+    use linear_algebra::{point, Pose2};
+
+    use super::*;
+
+    fn field_dimensions() -> FieldDimensions {
+        FieldDimensions {
+            length: 9.0,
+            width: 6.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn teammate_observation_suppresses_its_sector_between_messages() {
+        let field_dimensions = field_dimensions();
+        let mut heatmap = BallSearchHeatmap::new(FieldGrid::new(2, 1));
+        let mut coverage = FieldCoverage::new(FieldGrid::new(4, 1));
+        let message_time = UNIX_EPOCH + Duration::from_secs(100);
+        // a single teammate message, looking at the opponent half
+        let teammate = Pose2::new(point![0.5, 0.0].coords(), 0.0);
+        coverage.observe(teammate, 1.0, 3.0, message_time, &field_dimensions);
+
+        let cycle_duration = Duration::from_millis(12);
+        let observation_timeout = Duration::from_secs(4);
+        for cycle in 0..250 {
+            heatmap.observe(
+                &coverage,
+                message_time + cycle_duration * cycle,
+                observation_timeout,
+                0.5 * cycle_duration.as_secs_f32(),
+                &field_dimensions,
+            );
+            heatmap.normalize();
+        }
+
+        assert!(heatmap.likelihoods[1] < 0.5 * heatmap.likelihoods[0]);
+
+        let suppressed = heatmap.likelihoods.clone();
+        heatmap.observe(
+            &coverage,
+            message_time + observation_timeout,
+            observation_timeout,
+            0.5,
+            &field_dimensions,
+        );
+        assert_eq!(heatmap.likelihoods, suppressed);
+    }
+}
@@ -6,4 +6,5 @@ pub struct Buttons {
     pub is_chest_button_pressed: bool,
     pub head_buttons_touched: bool,
     pub calibration_buttons_touched: bool,
+    pub is_head_middle_button_tapped: bool,
 }
@@ -1,4 +1,4 @@
-use nalgebra::Vector3;
+use nalgebra::{Vector2, Vector3};
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 
@@ -7,5 +7,6 @@ use crate::fall_state::FallState;
 #[derive(Default, Debug, Clone, Serialize, Deserialize, SerializeHierarchy)]
 pub struct ConditionInput {
     pub filtered_angular_velocity: Vector3<f32>,
+    pub roll_pitch: Vector2<f32>,
     pub fall_state: FallState,
 }
@@ -0,0 +1,66 @@
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+/// Frequencies in Hz the control cycler grants the non-critical perception cyclers
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct CyclerRateLimits {
+    pub audio: f32,
+    pub spl_network: f32,
+    pub is_under_cpu_pressure: bool,
+}
+
+/// Frequencies in Hz a perception cycler is allowed to run at and actually achieved
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct CyclerRate {
+    pub maximum_frequency: f32,
+    pub achieved_frequency: f32,
+}
+
+impl CyclerRate {
+    /// Folds the duration since the previous cycle into the achieved frequency
+    pub fn update(&mut self, last_cycle_duration: Duration, smoothing_factor: f32) {
+        let seconds = last_cycle_duration.as_secs_f32();
+        if seconds > 0.0 {
+            self.achieved_frequency = (1.0 - smoothing_factor) * self.achieved_frequency
+                + smoothing_factor * (1.0 / seconds);
+        }
+    }
+
+    /// Zero if the cycler may run as fast as its hardware allows
+    pub fn minimum_cycle_interval(&self) -> Duration {
+        if self.maximum_frequency > 0.0 {
+            Duration::from_secs_f32(1.0 / self.maximum_frequency)
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+/// Shared by the rate limiter nodes of the perception cyclers, measures the achieved rate and
+/// applies the granted one
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct CyclerRateLimiter {
+    last_cycle_start: Option<SystemTime>,
+    cycler_rate: CyclerRate,
+}
+
+impl CyclerRateLimiter {
+    pub fn update(
+        &mut self,
+        now: SystemTime,
+        maximum_frequency: f32,
+        smoothing_factor: f32,
+    ) -> CyclerRate {
+        if let Some(last_cycle_start) = self.last_cycle_start {
+            self.cycler_rate.update(
+                now.duration_since(last_cycle_start).unwrap_or_default(),
+                smoothing_factor,
+            );
+        }
+        self.last_cycle_start = Some(now);
+        self.cycler_rate.maximum_frequency = maximum_frequency;
+        self.cycler_rate
+    }
+}
@@ -0,0 +1,176 @@
+use std::time::{Duration, SystemTime};
+
+use coordinate_systems::{Field, Ground};
+use linear_algebra::{distance, Point2, Pose2};
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+use crate::{field_dimensions::FieldDimensions, field_grid::FieldGrid};
+
+/// When each cell of a grid over the field was last seen by any robot of the team.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct FieldCoverage {
+    pub grid: FieldGrid,
+    pub last_observed: Vec<Option<SystemTime>>,
+}
+
+impl FieldCoverage {
+    pub fn new(grid: FieldGrid) -> Self {
+        Self {
+            grid,
+            last_observed: vec![None; grid.number_of_cells()],
+        }
+    }
+
+    pub fn number_of_cells(&self) -> usize {
+        self.last_observed.len()
+    }
+
+    pub fn cell_center(&self, cell: usize, field_dimensions: &FieldDimensions) -> Point2<Field> {
+        self.grid.cell_center(cell, field_dimensions)
+    }
+
+    /// Marks all cells with their center inside the view cone of a camera as observed.
+    ///
+    /// `camera` is placed at the robot position and oriented along the viewing direction.
+    pub fn observe(
+        &mut self,
+        camera: Pose2<Field>,
+        field_of_view: f32,
+        observation_distance: f32,
+        time: SystemTime,
+        field_dimensions: &FieldDimensions,
+    ) {
+        let field_to_camera = camera.as_transform::<Ground>().inverse();
+        for cell in 0..self.number_of_cells() {
+            let cell_in_camera = field_to_camera * self.cell_center(cell, field_dimensions);
+            let is_visible = cell_in_camera.coords().norm() < observation_distance
+                && cell_in_camera.y().atan2(cell_in_camera.x()).abs() < field_of_view / 2.0;
+            if is_visible {
+                self.last_observed[cell] = Some(time);
+            }
+        }
+    }
+
+    /// Time since the cell was last observed, saturating at `maximum_age` which also applies to
+    /// cells never observed.
+    pub fn age(&self, cell: usize, now: SystemTime, maximum_age: Duration) -> Duration {
+        self.last_observed[cell]
+            .map_or(maximum_age, |last_observed| {
+                now.duration_since(last_observed).unwrap_or_default()
+            })
+            .min(maximum_age)
+    }
+
+    /// The cell with the highest age, discounted by `distance_weight` seconds per meter between
+    /// the cell center and `anchor`.
+    ///
+    /// The previous cell gets `hysteresis` seconds on top, similarly stale cells would otherwise
+    /// make the searcher turn back and forth.
+    #[allow(clippy::too_many_arguments)]
+    pub fn stalest_cell_near(
+        &self,
+        anchor: Point2<Field>,
+        distance_weight: f32,
+        previous_cell: Option<usize>,
+        hysteresis: Duration,
+        now: SystemTime,
+        maximum_age: Duration,
+        field_dimensions: &FieldDimensions,
+    ) -> Option<usize> {
+        self.stalest_cell_among(
+            0..self.number_of_cells(),
+            anchor,
+            distance_weight,
+            previous_cell,
+            hysteresis,
+            now,
+            maximum_age,
+            field_dimensions,
+        )
+    }
+
+    /// Like [`Self::stalest_cell_near`], restricted to the `candidates` cells.
+    #[allow(clippy::too_many_arguments)]
+    pub fn stalest_cell_among(
+        &self,
+        candidates: impl IntoIterator<Item = usize>,
+        anchor: Point2<Field>,
+        distance_weight: f32,
+        previous_cell: Option<usize>,
+        hysteresis: Duration,
+        now: SystemTime,
+        maximum_age: Duration,
+        field_dimensions: &FieldDimensions,
+    ) -> Option<usize> {
+        let score = |cell: usize| {
+            let bonus = if previous_cell == Some(cell) {
+                hysteresis.as_secs_f32()
+            } else {
+                0.0
+            };
+            self.age(cell, now, maximum_age).as_secs_f32() + bonus
+                - distance_weight * distance(anchor, self.cell_center(cell, field_dimensions))
+        };
+        candidates
+            .into_iter()
+            .max_by(|left, right| score(*left).total_cmp(&score(*right)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::UNIX_EPOCH;
+
+    use linear_algebra::point;
+
+    use super::*;
+
+    fn field_dimensions() -> FieldDimensions {
+        FieldDimensions {
+            length: 9.0,
+            width: 6.0,
+            ..Default::default()
+        }
+    }
+
+    fn at(seconds: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn only_cells_in_the_view_cone_are_observed() {
+        let mut coverage = FieldCoverage::new(FieldGrid::new(3, 1));
+        let camera = Pose2::new(point![-1.5, 0.0].coords(), 0.0);
+
+        coverage.observe(camera, 1.0, 4.0, at(10), &field_dimensions());
+
+        assert_eq!(coverage.last_observed, [None, Some(at(10)), None]);
+        let maximum_age = Duration::from_secs(60);
+        assert_eq!(coverage.age(1, at(15), maximum_age), Duration::from_secs(5));
+        assert_eq!(coverage.age(0, at(15), maximum_age), maximum_age);
+    }
+
+    #[test]
+    fn previous_cell_is_kept_while_similarly_stale() {
+        let mut coverage = FieldCoverage::new(FieldGrid::new(3, 1));
+        coverage.last_observed = vec![Some(at(0)), Some(at(100)), Some(at(2))];
+        let anchor = point![0.0, 0.0];
+        let maximum_age = Duration::from_secs(60);
+        let stalest = |previous_cell| {
+            coverage.stalest_cell_near(
+                anchor,
+                0.0,
+                previous_cell,
+                Duration::from_secs(5),
+                at(40),
+                maximum_age,
+                &field_dimensions(),
+            )
+        };
+
+        assert_eq!(stalest(None), Some(0));
+        assert_eq!(stalest(Some(2)), Some(2));
+        assert_eq!(stalest(Some(1)), Some(0));
+    }
+}
@@ -29,6 +29,11 @@ impl FieldDimensions {
         position.x().abs() < self.length / 2.0 && position.y().abs() < self.width / 2.0
     }
 
+    pub fn is_inside_own_penalty_area(&self, position: Point2<Field>) -> bool {
+        position.x() < -self.length / 2.0 + self.penalty_area_length
+            && position.y().abs() < self.penalty_area_width / 2.0
+    }
+
     pub fn is_inside_any_goal_box(&self, position: Point2<Field>) -> bool {
         position.x().abs() > self.length / 2.0 - self.goal_box_area_length
             && position.y().abs() < self.goal_box_area_width / 2.0
@@ -0,0 +1,54 @@
+use coordinate_systems::Field;
+use linear_algebra::{point, Point2};
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+use crate::field_dimensions::FieldDimensions;
+
+/// Equally sized cells covering the field.
+///
+/// Cells are indexed row-major starting at the own goal line on the right side of the field.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct FieldGrid {
+    pub number_of_columns: usize,
+    pub number_of_rows: usize,
+}
+
+impl FieldGrid {
+    pub fn new(number_of_columns: usize, number_of_rows: usize) -> Self {
+        Self {
+            number_of_columns,
+            number_of_rows,
+        }
+    }
+
+    pub fn number_of_cells(&self) -> usize {
+        self.number_of_columns * self.number_of_rows
+    }
+
+    pub fn cell_center(&self, cell: usize, field_dimensions: &FieldDimensions) -> Point2<Field> {
+        let column = cell % self.number_of_columns;
+        let row = cell / self.number_of_columns;
+        let cell_length = field_dimensions.length / self.number_of_columns as f32;
+        let cell_width = field_dimensions.width / self.number_of_rows as f32;
+        point![
+            -field_dimensions.length / 2.0 + (column as f32 + 0.5) * cell_length,
+            -field_dimensions.width / 2.0 + (row as f32 + 0.5) * cell_width
+        ]
+    }
+
+    /// The cell containing the point, points outside the field belong to the closest cell.
+    pub fn cell_containing(
+        &self,
+        point: Point2<Field>,
+        field_dimensions: &FieldDimensions,
+    ) -> usize {
+        let cell_length = field_dimensions.length / self.number_of_columns as f32;
+        let cell_width = field_dimensions.width / self.number_of_rows as f32;
+        let column = ((point.x() + field_dimensions.length / 2.0) / cell_length).floor();
+        let row = ((point.y() + field_dimensions.width / 2.0) / cell_width).floor();
+        let column = (column.max(0.0) as usize).min(self.number_of_columns - 1);
+        let row = (row.max(0.0) as usize).min(self.number_of_rows - 1);
+        row * self.number_of_columns + column
+    }
+}
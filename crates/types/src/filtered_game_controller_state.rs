@@ -1,6 +1,8 @@
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
-use spl_network_messages::{GamePhase, Penalty, SubState, Team};
+use spl_network_messages::{GamePhase, Half, Penalty, SubState, Team};
 
 use crate::{filtered_game_state::FilteredGameState, players::Players};
 
@@ -11,7 +13,26 @@ pub struct FilteredGameControllerState {
     pub game_phase: GamePhase,
     pub kicking_team: Team,
     pub penalties: Players<Option<Penalty>>,
+    /// Predicted time until penalized players are released, unknown without a countdown
+    pub remaining_penalty_durations: Players<Option<Duration>>,
     pub remaining_number_of_messages: u16,
     pub sub_state: Option<SubState>,
     pub own_team_is_home_after_coin_toss: bool,
+    pub half: Half,
+    pub remaining_time_in_half: Duration,
+    pub own_score: u8,
+    pub opponent_score: u8,
+    /// Time since the latest state was received from the GameController or a teammate
+    pub staleness: Duration,
+    /// Game state and remaining time are advanced locally, penalties of teammates are outdated
+    pub is_fallback: bool,
+}
+
+impl FilteredGameControllerState {
+    /// Whether the ball is in play during regular time and the half ends within the duration
+    pub fn is_half_ending_within(&self, duration: Duration) -> bool {
+        matches!(self.game_phase, GamePhase::Normal)
+            && matches!(self.game_state, FilteredGameState::Playing { .. })
+            && self.remaining_time_in_half <= duration
+    }
 }
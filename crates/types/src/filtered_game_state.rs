@@ -6,6 +6,8 @@ use spl_network_messages::Team;
 pub enum FilteredGameState {
     #[default]
     Initial,
+    /// Robots stand at the sideline waiting for the referee's ready signal
+    Standby,
     Ready {
         kicking_team: Team,
     },
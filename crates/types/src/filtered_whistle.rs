@@ -8,4 +8,16 @@ pub struct FilteredWhistle {
     pub is_detected: bool,
     pub started_this_cycle: bool,
     pub last_detection: Option<SystemTime>,
+    /// Only set in the cycle the pattern is classified, a single whistle is known once the time
+    /// for a second whistle has passed
+    pub pattern_this_cycle: Option<WhistlePattern>,
+    pub last_pattern: Option<WhistlePattern>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+pub enum WhistlePattern {
+    /// Starts the play
+    Single,
+    /// Signals a goal, the teams return for the kick-off
+    Double,
 }
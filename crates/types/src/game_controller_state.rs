@@ -1,8 +1,11 @@
-use std::time::SystemTime;
+use std::{
+    net::IpAddr,
+    time::{Duration, SystemTime},
+};
 
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
-use spl_network_messages::{GamePhase, GameState, Penalty, SubState, Team};
+use spl_network_messages::{GamePhase, GameState, Half, Penalty, SubState, Team};
 
 use crate::players::Players;
 
@@ -10,10 +13,54 @@ use crate::players::Players;
 pub struct GameControllerState {
     pub game_state: GameState,
     pub game_phase: GamePhase,
+    /// Only changes once the GameController sent the new kicking team consistently
     pub kicking_team: Team,
+    /// Kicking team as received in the latest message
+    pub raw_kicking_team: Team,
     pub last_game_state_change: SystemTime,
     pub penalties: Players<Option<Penalty>>,
+    /// Predicted time until penalized players are released, counted down between messages
+    pub remaining_penalty_durations: Players<Option<Duration>>,
     pub remaining_amount_of_messages: u16,
     pub sub_state: Option<SubState>,
     pub hulks_team_is_home_after_coin_toss: bool,
+    pub half: Half,
+    pub remaining_time_in_half: Duration,
+    pub hulks_score: u8,
+    pub opponent_score: u8,
+    /// Time since the latest state was received from the GameController or a teammate
+    pub staleness: Duration,
+    /// The state is advanced locally since neither the GameController nor a teammate sent one
+    pub is_fallback: bool,
+}
+
+/// How much of the team message budget is left, senders become more sparing the lower it gets
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct MessageBudgetStatus {
+    pub remaining_fraction: f32,
+    /// Number of warning thresholds the remaining fraction fell below
+    pub warning_level: usize,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+pub enum GameControllerSelectionReason {
+    /// No controller was active before
+    FirstContact,
+    /// The previously active controller stopped sending
+    ActiveExpired,
+    /// The active controller fell silent while another one kept sending
+    NewerStream,
+    /// Another controller sends to the own team port while the active one does not
+    AddressedToTeam,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ActiveGameController {
+    #[serialize_hierarchy(leaf)]
+    pub address: IpAddr,
+    pub reason: GameControllerSelectionReason,
+    pub since: SystemTime,
+    pub number_of_alive_controllers: usize,
+    /// Sources ignored for advertising a different team pairing than the active controller
+    pub number_of_rejected_controllers: usize,
 }
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+use crate::joints::head::HeadJoints;
+
+/// Terms of the head stabilization while walking, exposed for tuning
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct HeadStabilization {
+    /// Walk speed relative to the maximum step size, scales the compensation
+    pub speed_factor: f32,
+    /// Torso oscillation around its mean orientation, integrated from the gyroscope
+    pub torso_oscillation: HeadJoints<f32>,
+    /// Added to the requested head joint positions
+    pub compensation: HeadJoints<f32>,
+}
@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+use coordinate_systems::Pixel;
+use linear_algebra::Point2;
+use serialize_hierarchy::SerializeHierarchy;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct HorizonCrop {
+    /// Crop line at the image borders, may lie outside of the image
+    pub left_y: f32,
+    pub right_y: f32,
+    pub image_width: f32,
+    pub image_height: f32,
+}
+
+impl HorizonCrop {
+    /// The crop line is interpolated before it is clamped to the image, clamping the borders first
+    /// would bend the line wherever it leaves the image
+    pub fn y_at_x(&self, x: f32) -> f32 {
+        let y = if self.image_width <= 0.0 {
+            self.left_y
+        } else {
+            self.left_y + (self.right_y - self.left_y) * x / self.image_width
+        };
+        y.clamp(0.0, self.image_height.max(0.0))
+    }
+
+    pub fn y_minimum(&self) -> f32 {
+        self.y_at_x(0.0).min(self.y_at_x(self.image_width))
+    }
+
+    pub fn is_above(&self, point: Point2<Pixel>) -> bool {
+        point.y() < self.y_at_x(point.x())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+    use linear_algebra::point;
+
+    use super::*;
+
+    #[test]
+    fn crop_line_leaving_the_image_keeps_its_slope_inside() {
+        let horizon_crop = HorizonCrop {
+            left_y: -100.0,
+            right_y: 300.0,
+            image_width: 640.0,
+            image_height: 480.0,
+        };
+
+        assert_relative_eq!(horizon_crop.y_at_x(0.0), 0.0);
+        assert_relative_eq!(horizon_crop.y_at_x(320.0), 100.0);
+        assert_relative_eq!(horizon_crop.y_at_x(640.0), 300.0);
+        assert_relative_eq!(horizon_crop.y_minimum(), 0.0);
+    }
+
+    #[test]
+    fn crop_line_below_the_image_is_clamped_to_the_bottom() {
+        let horizon_crop = HorizonCrop {
+            left_y: 500.0,
+            right_y: 700.0,
+            image_width: 640.0,
+            image_height: 480.0,
+        };
+
+        assert_relative_eq!(horizon_crop.y_at_x(320.0), 480.0);
+        assert!(!horizon_crop.is_above(point![320.0, 479.0]));
+    }
+}
@@ -0,0 +1,19 @@
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+/// Failures the camera images are dumped for, such that the vision team can see what the robot
+/// saw at that moment
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+pub enum ImageDumpReason {
+    Fall,
+    LostLocalization,
+    MissedKick,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ImageDumpRequest {
+    pub reason: ImageDumpReason,
+    pub time: SystemTime,
+}
@@ -1,3 +1,26 @@
 pub trait Mirror {
     fn mirrored(self) -> Self;
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::joints::Joints;
+
+    use super::*;
+
+    #[test]
+    fn mirroring_twice_yields_original_joints() {
+        let mut joints = Joints::default();
+        for (index, (joint, _)) in Joints::<f32>::default().enumerate().enumerate() {
+            joints[joint] = index as f32 + 1.0;
+        }
+
+        assert_eq!(joints.mirrored().mirrored(), joints);
+        assert_eq!(joints.with_swapped_sides().with_swapped_sides(), joints);
+        assert_eq!(
+            joints.mirrored().left_leg.hip_roll,
+            -joints.right_leg.hip_roll
+        );
+        assert_eq!(joints.with_swapped_sides().left_leg, joints.right_leg);
+    }
+}
@@ -343,6 +343,19 @@ impl Mirror for Joints<f32> {
     }
 }
 
+impl<T> Joints<T> {
+    /// Exchanges the values of left and right joints without inverting them, e.g. for stiffnesses.
+    pub fn with_swapped_sides(self) -> Self {
+        Self {
+            head: self.head,
+            left_arm: self.right_arm,
+            right_arm: self.left_arm,
+            left_leg: self.right_leg,
+            right_leg: self.left_leg,
+        }
+    }
+}
+
 impl<T> From<Joints<T>> for HeadJoints<T> {
     fn from(joints: Joints<T>) -> Self {
         Self {
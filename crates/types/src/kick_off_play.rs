@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+pub enum KickOffPlay {
+    /// Passes forward to the side with fewer robots, where a midfielder picks the ball up
+    ShortDiagonalPass,
+    /// Kicks deep into the corner on the side with fewer robots
+    LongKickIntoCorner,
+    DribbleLeft,
+    DribbleRight,
+}
+
+impl KickOffPlay {
+    pub const ALL: [Self; 4] = [
+        Self::ShortDiagonalPass,
+        Self::LongKickIntoCorner,
+        Self::DribbleLeft,
+        Self::DribbleRight,
+    ];
+}
@@ -2,7 +2,7 @@ use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
-use crate::step_plan::Step;
+use crate::{motion_command::KickVariant, step_plan::Step, support_foot::Side};
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct JointOverride {
@@ -16,3 +16,11 @@ pub struct KickStep {
     pub hip_pitch_overrides: Option<Vec<JointOverride>>,
     pub ankle_pitch_overrides: Option<Vec<JointOverride>>,
 }
+
+/// An in-walk kick whose last step was just completed by the walking engine
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct FinishedKick {
+    pub variant: KickVariant,
+    pub kicking_side: Side,
+    pub strength: f32,
+}
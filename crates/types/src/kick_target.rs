@@ -25,3 +25,10 @@ impl KickTarget {
         }
     }
 }
+
+/// A goal target together with how hard it is for the opponents to block a shot towards it.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct EvaluatedKickTarget {
+    pub position: Point2<Ground>,
+    pub score: f32,
+}
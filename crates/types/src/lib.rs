@@ -1,19 +1,27 @@
 #![recursion_limit = "256"]
+pub mod acoustic_signals;
 pub mod action;
+pub mod action_latency;
 pub mod audio;
 pub mod ball;
 pub mod ball_filter;
+pub mod ball_motion;
 pub mod ball_position;
+pub mod ball_possession;
+pub mod ball_search;
 pub mod buttons;
 pub mod camera_position;
 pub mod color;
 pub mod condition_input;
 pub mod cycle_time;
+pub mod cycler_rates;
 pub mod detected_feet;
 pub mod fall_state;
 pub mod field_border;
 pub mod field_color;
+pub mod field_coverage;
 pub mod field_dimensions;
+pub mod field_grid;
 pub mod field_lines;
 pub mod field_marks;
 pub mod filtered_game_controller_state;
@@ -25,6 +33,9 @@ pub mod foot_bumper_values;
 pub mod game_controller_state;
 pub mod grayscale_image;
 pub mod hardware;
+pub mod head_stabilization;
+pub mod horizon_crop;
+pub mod image_dump;
 pub mod image_segments;
 pub mod initial_look_around;
 pub mod initial_pose;
@@ -32,6 +43,7 @@ pub mod interpolated;
 pub mod joints;
 pub mod joints_velocity;
 pub mod kick_decision;
+pub mod kick_off_play;
 pub mod kick_step;
 pub mod kick_target;
 pub mod led;
@@ -50,25 +62,33 @@ pub mod orientation_filter;
 pub mod parameters;
 pub mod path_obstacles;
 pub mod penalty_shot_direction;
+pub mod penalty_shot_variant;
 pub mod perspective_grid_candidates;
 pub mod planned_path;
 pub mod players;
 pub mod point_of_interest;
 pub mod primary_state;
+pub mod ready_signal;
+pub mod robot_condition;
 pub mod robot_dimensions;
 pub mod robot_kinematics;
 pub mod robot_masses;
 pub mod roles;
 pub mod rule_obstacles;
+pub mod rule_set;
 pub mod samples;
+pub mod self_test;
 pub mod sensor_data;
+pub mod shot_selection;
 pub mod sole_pressure;
 pub mod sonar_obstacle;
 pub mod sonar_values;
 pub mod step_adjustment;
 pub mod step_plan;
 pub mod support_foot;
+pub mod transform_sanity;
 pub mod walk_command;
+pub mod walk_in_lane;
 pub mod whistle;
 pub mod world_state;
 pub mod ycbcr422_image;
@@ -1,3 +1,5 @@
+use std::net::SocketAddr;
+
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 use spl_network_messages::{
@@ -6,10 +8,23 @@ use spl_network_messages::{
 
 #[derive(Clone, Debug, Deserialize, Serialize, SerializeHierarchy)]
 pub enum IncomingMessage {
-    GameController(GameControllerStateMessage),
+    GameController(
+        SocketAddr,
+        GameControllerDestination,
+        GameControllerStateMessage,
+    ),
     Spl(HulkMessage),
 }
 
+/// Port a GameController state message was received on
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+pub enum GameControllerDestination {
+    /// The port all GameControllers broadcast their state to
+    StatePort,
+    /// The port of the own team, only a GameController set up for our game sends there
+    TeamPort,
+}
+
 impl Default for IncomingMessage {
     fn default() -> Self {
         Self::Spl(Default::default())
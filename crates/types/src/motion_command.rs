@@ -40,12 +40,15 @@ pub enum MotionCommand {
         left_arm: ArmMotion,
         right_arm: ArmMotion,
         orientation_mode: OrientationMode,
+        speed: WalkSpeed,
     },
     InWalkKick {
         head: HeadMotion,
         kick: KickVariant,
         kicking_side: Side,
         strength: f32,
+        left_arm: ArmMotion,
+        right_arm: ArmMotion,
     },
 }
 
@@ -88,6 +91,16 @@ pub enum ArmMotion {
     PullTight,
 }
 
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy,
+)]
+pub enum WalkSpeed {
+    #[default]
+    Normal,
+    /// Shorter steps, e.g. while squeezing through a narrow gap between obstacles
+    Slow,
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
 pub enum KickDirection {
     Back,
@@ -127,6 +140,7 @@ pub enum FallDirection {
 pub enum JumpDirection {
     Left,
     Right,
+    Front,
 }
 
 #[derive(
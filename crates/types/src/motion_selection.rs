@@ -15,6 +15,7 @@ pub enum MotionType {
     Dispatching,
     FallProtection,
     Initial,
+    JumpFront,
     JumpLeft,
     JumpRight,
     Penalized,
@@ -38,6 +39,7 @@ pub struct MotionSafeExits {
     dispatching: bool,
     fall_protection: bool,
     initial: bool,
+    jump_front: bool,
     jump_left: bool,
     jump_right: bool,
     penalized: bool,
@@ -56,6 +58,7 @@ impl Default for MotionSafeExits {
             dispatching: false,
             fall_protection: true,
             initial: true,
+            jump_front: false,
             jump_left: false,
             jump_right: false,
             penalized: true,
@@ -77,6 +80,7 @@ impl Index<MotionType> for MotionSafeExits {
             MotionType::ArmsUpSquat => &self.arms_up_squat,
             MotionType::Dispatching => &self.dispatching,
             MotionType::Initial => &self.initial,
+            MotionType::JumpFront => &self.jump_front,
             MotionType::JumpLeft => &self.jump_left,
             MotionType::JumpRight => &self.jump_right,
             MotionType::FallProtection => &self.fall_protection,
@@ -97,6 +101,7 @@ impl IndexMut<MotionType> for MotionSafeExits {
             MotionType::ArmsUpSquat => &mut self.arms_up_squat,
             MotionType::Dispatching => &mut self.dispatching,
             MotionType::Initial => &mut self.initial,
+            MotionType::JumpFront => &mut self.jump_front,
             MotionType::JumpLeft => &mut self.jump_left,
             MotionType::JumpRight => &mut self.jump_right,
             MotionType::FallProtection => &mut self.fall_protection,
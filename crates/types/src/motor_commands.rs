@@ -25,14 +25,12 @@ pub struct MotorCommands<Joints> {
 
 impl_Interpolate!(f32, MotorCommands<Joints<f32>>, PI);
 
-impl<Joints> Mirror for MotorCommands<Joints>
-where
-    Joints: Mirror,
-{
+impl Mirror for MotorCommands<Joints<f32>> {
     fn mirrored(self) -> Self {
         Self {
-            positions: Joints::mirrored(self.positions),
-            stiffnesses: Joints::mirrored(self.stiffnesses),
+            positions: self.positions.mirrored(),
+            // stiffnesses are never negative, they only change sides
+            stiffnesses: self.stiffnesses.with_swapped_sides(),
         }
     }
 }
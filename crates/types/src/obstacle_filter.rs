@@ -8,6 +8,7 @@ use crate::{
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Hypothesis {
+    pub id: usize,
     pub state: MultivariateNormalDistribution<2>,
     pub measurement_count: usize,
     pub last_update: SystemTime,
@@ -1,9 +1,9 @@
 use serde::{Deserialize, Serialize};
 
-use linear_algebra::Point2;
+use linear_algebra::{distance, Isometry2, Point2};
 use serialize_hierarchy::SerializeHierarchy;
 
-use coordinate_systems::Ground;
+use coordinate_systems::{Field, Ground};
 
 #[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub enum ObstacleKind {
@@ -16,6 +16,8 @@ pub enum ObstacleKind {
 
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
 pub struct Obstacle {
+    /// Stays the same while the obstacle filter keeps tracking the obstacle
+    pub id: Option<usize>,
     pub kind: ObstacleKind,
     pub position: Point2<Ground>,
     pub radius_at_foot_height: f32,
@@ -25,6 +27,7 @@ pub struct Obstacle {
 impl Obstacle {
     pub fn ball(position: Point2<Ground>, radius: f32) -> Self {
         Self {
+            id: None,
             kind: ObstacleKind::Ball,
             position,
             radius_at_foot_height: radius,
@@ -38,6 +41,7 @@ impl Obstacle {
         radius_at_hip_height: f32,
     ) -> Self {
         Self {
+            id: None,
             kind: ObstacleKind::Robot,
             position,
             radius_at_foot_height,
@@ -47,6 +51,7 @@ impl Obstacle {
 
     pub fn goal_post(position: Point2<Ground>, radius: f32) -> Self {
         Self {
+            id: None,
             kind: ObstacleKind::GoalPost,
             position,
             radius_at_foot_height: radius,
@@ -54,3 +59,45 @@ impl Obstacle {
         }
     }
 }
+
+/// Robot obstacles not matching a teammate's communicated position
+pub fn opponents<'a>(
+    obstacles: &'a [Obstacle],
+    teammate_positions: &'a [Point2<Field>],
+    ground_to_field: Isometry2<Ground, Field>,
+    teammate_matching_distance: f32,
+) -> impl Iterator<Item = &'a Obstacle> {
+    obstacles
+        .iter()
+        .filter(|obstacle| matches!(obstacle.kind, ObstacleKind::Robot))
+        .filter(move |obstacle| {
+            let position = ground_to_field * obstacle.position;
+            teammate_positions
+                .iter()
+                .all(|teammate| distance(*teammate, position) > teammate_matching_distance)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use linear_algebra::{point, vector};
+
+    use super::*;
+
+    #[test]
+    fn teammates_are_not_opponents() {
+        let obstacles = [
+            Obstacle::robot(point![-1.0, 1.0], 0.2, 0.3),
+            Obstacle::robot(point![1.0, -1.0], 0.2, 0.3),
+            Obstacle::goal_post(point![-4.5, 0.8], 0.05),
+        ];
+        let teammate_positions = [point![-0.6, 1.1]];
+        let ground_to_field = Isometry2::from_parts(vector![0.5, 0.0], 0.0);
+
+        let opponents: Vec<_> =
+            opponents(&obstacles, &teammate_positions, ground_to_field, 0.5).collect();
+
+        assert_eq!(opponents.len(), 1);
+        assert_eq!(opponents[0].position, point![1.0, -1.0]);
+    }
+}
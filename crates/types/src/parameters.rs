@@ -2,15 +2,22 @@ use std::ops::{Index, Range};
 use std::{path::PathBuf, time::Duration};
 
 use coordinate_systems::{Field, Ground};
+use geometry::rectangle::Rectangle;
 use linear_algebra::{Point2, Vector2};
 use nalgebra::{Vector3, Vector4};
 use serde::{Deserialize, Serialize};
 use serialize_hierarchy::SerializeHierarchy;
 
 use crate::{
-    joints::{arm::ArmJoints, head::HeadJoints, leg::LegJoints},
+    acoustic_signals::{AcousticCode, AcousticEvent},
+    action::Action,
+    camera_position::CameraPosition,
+    joints::{arm::ArmJoints, head::HeadJoints, leg::LegJoints, Joints},
+    kick_off_play::KickOffPlay,
     kick_step::KickStep,
     motion_command::{KickVariant, MotionCommand},
+    penalty_shot_variant::PenaltyShotVariant,
+    players::Players,
     roles::Role,
     step_plan::Step,
 };
@@ -23,6 +30,29 @@ pub struct WhistleDetectionParameters {
     pub number_of_chunks: usize,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct AcousticSignalingParameters {
+    pub enable: bool,
+    pub network_silence_timeout: Duration,
+    pub chirp_duration: Duration,
+    pub chirp_interval: Duration,
+    pub own_chirp_suppression: Duration,
+    pub ball_near_own_goal_distance: f32,
+    pub tone_scaling: f32,
+    pub minimum_detections: usize,
+    pub striker: AcousticCode,
+    pub ball_near_own_goal: AcousticCode,
+}
+
+impl AcousticSignalingParameters {
+    pub fn code(&self, event: AcousticEvent) -> &AcousticCode {
+        match event {
+            AcousticEvent::Striker => &self.striker,
+            AcousticEvent::BallNearOwnGoal => &self.ball_near_own_goal,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct StepPlannerParameters {
     pub injected_step: Option<Step>,
@@ -30,21 +60,507 @@ pub struct StepPlannerParameters {
     pub max_step_size_backwards: f32,
     pub translation_exponent: f32,
     pub rotation_exponent: f32,
+    /// Scales the translational step size while walking slowly
+    pub slow_walk_step_size_factor: f32,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct BehaviorParameters {
     pub injected_motion_command: Option<MotionCommand>,
+    /// Keeps the robot standing and tracking the ball, only the safety actions remain active
+    pub hold_position: bool,
+    pub demo: DemoParameters,
     pub lost_ball: LostBallParameters,
     pub optional_roles: Vec<Role>,
     pub path_planning: PathPlanningParameters,
     pub role_positions: RolePositionsParameters,
+    pub support_positioning: SupportPositioningParameters,
     pub walk_and_stand: WalkAndStandParameters,
+    pub arm_tucking: ArmTuckingParameters,
     pub dribbling: DribblingParameters,
     pub search: SearchParameters,
     pub look_action: LookActionParameters,
     pub intercept_ball: InterceptBallParameters,
     pub initial_lookaround_duration: Duration,
+    pub shot_selection: ShotSelectionParameters,
+    pub passing: PassingParameters,
+    pub free_kick: FreeKickParameters,
+    pub strategy: StrategyParameters,
+    pub tempo: TempoParameters,
+    pub endgame: EndgameParameters,
+    pub stale_game_state: StaleGameStateParameters,
+    pub penalty_shot: PenaltyShotParameters,
+    pub kick_off: KickOffParameters,
+    pub orbit_ball: OrbitBallParameters,
+    pub keeper_dive: KeeperDiveParameters,
+    pub ready_walk_in: ReadyWalkInParameters,
+    pub action_commitment: ActionCommitmentParameters,
+    pub action_priority: ActionPriorityParameters,
+    pub illegal_defender: IllegalDefenderParameters,
+    pub wall: WallParameters,
+    pub marking: MarkingParameters,
+    pub block: BlockParameters,
+    pub penalty_rebound: PenaltyReboundParameters,
+    pub keeper_clearance: KeeperClearanceParameters,
+    pub emergency_clearance: EmergencyClearanceParameters,
+    pub goal_mouth: GoalMouthParameters,
+    pub teammate_striker: TeammateStrikerParameters,
+    pub rest: RestParameters,
+    pub reentry: ReentryParameters,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct TeammateStrikerParameters {
+    pub enable: bool,
+    /// Teammates not sending for this long no longer count as striker
+    pub timeout: Duration,
+    /// A teammate has to be this much faster to the ball before a striker yields to it
+    pub yield_margin: Duration,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ReentryParameters {
+    /// Walk into the field while converging instead of looking around at the entry point
+    pub enable_walk_in: bool,
+    /// Distance of the walk in target from the sideline
+    pub walk_in_distance: f32,
+    /// Stand up from the penalized pose once the own penalty is predicted to end within this
+    /// duration, such that the robot is ready to walk in when released
+    pub stand_up_before_release: Duration,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct RestParameters {
+    /// Actions the robot skips for standing while its battery is low or its joints are hot
+    pub non_critical_actions: Vec<Action>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct EmergencyClearanceParameters {
+    pub enable: bool,
+    /// Grows the own goal box the ball has to be in
+    pub goal_box_margin: f32,
+    pub minimum_ball_velocity: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct GoalMouthParameters {
+    pub enable: bool,
+    /// Grows the own penalty area the ball has to be in for the striker to claim it
+    pub penalty_area_margin: f32,
+    /// The keeper stops yielding if it has not heard of the claim for this long
+    pub claim_timeout: Duration,
+    /// Mirrored to the side of the field the ball is on
+    pub clearing_target: Point2<Field>,
+    /// The keeper holds the post opposite to the clearing direction this far in front of the line
+    pub post_distance_to_goal_line: f32,
+}
+
+/// Plays with a ball without GameController, e.g. for exhibitions. The chest button or a tap on
+/// the middle head button starts and stops playing.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct DemoParameters {
+    pub enable: bool,
+    /// The robot neither walks nor follows the ball out of this area
+    pub area: Rectangle<Field>,
+    pub border_margin: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct KeeperClearanceParameters {
+    pub enable: bool,
+    pub maximum_ball_velocity: f32,
+    /// The keeper stays on the line if an opponent is closer to the ball than this
+    pub minimum_opponent_distance_to_ball: f32,
+    /// Mirrored to the side of the field the ball is on
+    pub target: Point2<Field>,
+    pub kick_strength: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct WallParameters {
+    pub enable: bool,
+    /// Roles forming the wall, ordered from left to right as seen from the own goal
+    pub slot_roles: Vec<Role>,
+    pub slot_spacing: f32,
+    /// Added to the distance the rules require during kick-offs and free kicks
+    pub distance_margin: f32,
+    /// Free kicks further away from the own goal do not need a wall
+    pub maximum_ball_distance_to_own_goal: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct BlockParameters {
+    pub enable: bool,
+    /// Distance from the ball towards the own goal on the shot line
+    pub distance_to_ball: f32,
+    /// Sideways shift of the defenders from the shot line, left defender to the left
+    pub lateral_offset: f32,
+    /// Balls further away are left to the regular defending positions
+    pub maximum_ball_distance_to_own_goal: f32,
+    /// The defender only crouches if it is this close to its block position
+    pub position_tolerance: f32,
+    /// An opponent this close behind the ball is about to shoot
+    pub shot_imminent_distance: f32,
+    /// Maximum angle between the opponent's approach to the ball and the line to the own goal
+    pub maximum_shot_angle: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct MarkingParameters {
+    pub enable: bool,
+    /// An opponent this close to the ball and closer to it than the own team controls it
+    pub possession_distance: f32,
+    /// Distance from the marked opponent towards the own goal
+    pub marking_distance: f32,
+    /// A teammate this close to the marking position marks the opponent already
+    pub marked_distance: f32,
+    /// Opponents further away are no threat worth marking
+    pub maximum_distance_to_own_goal: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct IllegalDefenderParameters {
+    /// Number of own robots (including the keeper) allowed in the own penalty area at once
+    pub maximum_number_of_robots_in_penalty_area: usize,
+    pub penalty_area_margin: f32,
+    /// Once occupied, robots count as inside until they are this far outside the penalty area
+    pub occupancy_hysteresis: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ActionCommitmentParameters {
+    /// Once selected, these actions take precedence over each other for the minimum duration
+    pub actions: Vec<Action>,
+    pub minimum_duration: Duration,
+    /// A committed action has to be inapplicable for this long before another one takes over
+    pub release_margin: Duration,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize, SerializeHierarchy)]
+pub struct ActionPriorityParameters {
+    /// Checked before all situational actions, has to start with the safety actions
+    pub base_actions: Vec<Action>,
+    /// Checked after the situational actions of the robot's role
+    pub role_additions: RoleActionsParameters,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize, SerializeHierarchy)]
+pub struct RoleActionsParameters {
+    pub defender_left: Vec<Action>,
+    pub defender_right: Vec<Action>,
+    pub keeper: Vec<Action>,
+    pub loser: Vec<Action>,
+    pub midfielder_left: Vec<Action>,
+    pub midfielder_right: Vec<Action>,
+    pub replacement_keeper: Vec<Action>,
+    pub searcher: Vec<Action>,
+    pub striker: Vec<Action>,
+    pub striker_supporter: Vec<Action>,
+}
+
+impl RoleActionsParameters {
+    pub fn actions(&self, role: Role) -> &[Action] {
+        match role {
+            Role::DefenderLeft => &self.defender_left,
+            Role::DefenderRight => &self.defender_right,
+            Role::Keeper => &self.keeper,
+            Role::Loser => &self.loser,
+            Role::MidfielderLeft => &self.midfielder_left,
+            Role::MidfielderRight => &self.midfielder_right,
+            Role::ReplacementKeeper => &self.replacement_keeper,
+            Role::Searcher => &self.searcher,
+            Role::Striker => &self.striker,
+            Role::StrikerSupporter => &self.striker_supporter,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ReadyWalkInParameters {
+    pub enable: bool,
+    pub first_lane_distance_to_sideline: f32,
+    pub lane_spacing: f32,
+    /// How far ahead along the lane the intermediate walk target is placed
+    pub lookahead_distance: f32,
+    /// Robots leave their lane once they are this close to their target in x
+    pub lane_exit_distance: f32,
+    /// Delay per lane index for robots with a short way to go
+    pub start_delay_per_lane: Duration,
+    /// Robots with a longer way to go than this start immediately
+    pub maximum_delayed_distance: f32,
+}
+
+/// Nominal Ready positions used to exchange roles between field players such that the total
+/// walking distance is minimal
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ReadyRoleAssignmentParameters {
+    pub enable: bool,
+    /// Robots send their pose at the beginning of Ready, the roles are assigned after this duration
+    pub pose_exchange_duration: Duration,
+    pub defender_left: Point2<Field>,
+    pub defender_right: Point2<Field>,
+    pub midfielder_left: Point2<Field>,
+    pub midfielder_right: Point2<Field>,
+    pub striker: Point2<Field>,
+    pub striker_supporter: Point2<Field>,
+}
+
+/// Teammates without a team message for the timeout count as missing like penalized ones, the
+/// remaining robots take the roles of the formation for their number instead of the optional roles
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ReducedFormationParameters {
+    pub enable: bool,
+    /// Robots send a team message at least this often such that teammates know they are alive,
+    /// stretched by the silence interval factor of the message budget
+    pub heartbeat_interval: Duration,
+    pub unresponsive_timeout: Duration,
+    /// Optional roles by the number of robots available besides keeper and striker
+    pub formations: Vec<Vec<Role>>,
+}
+
+impl ReadyRoleAssignmentParameters {
+    pub fn position(&self, role: Role) -> Option<Point2<Field>> {
+        match role {
+            Role::DefenderLeft => Some(self.defender_left),
+            Role::DefenderRight => Some(self.defender_right),
+            Role::MidfielderLeft => Some(self.midfielder_left),
+            Role::MidfielderRight => Some(self.midfielder_right),
+            Role::Striker => Some(self.striker),
+            Role::StrikerSupporter => Some(self.striker_supporter),
+            Role::Keeper | Role::Loser | Role::ReplacementKeeper | Role::Searcher => None,
+        }
+    }
+}
+
+/// Thresholds at which the robot starts sparing its battery and joints
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct RobotConditionParameters {
+    pub enable: bool,
+    /// State of charge between 0.0 and 1.0
+    pub minimum_battery_charge: f32,
+    pub battery_charge_hysteresis: f32,
+    /// Degrees Celsius, below the levels at which the motors reduce their stiffness themselves
+    pub maximum_joint_temperature: f32,
+    pub joint_temperature_hysteresis: f32,
+}
+
+/// Team vote on the referee's hand signal ending Standby
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ReadySignalFilterParameters {
+    /// Only set for testing as long as there is no detection of the referee on the robot
+    pub injected_detection_confidence: Option<f32>,
+    pub confidence_threshold: f32,
+    pub minimum_number_of_positive_votes: usize,
+    /// Votes of robots that stopped sending are forgotten after this duration
+    pub vote_timeout: Duration,
+    /// The own vote is sent again after this interval, it has to be shorter than the timeout
+    pub vote_refresh_interval: Duration,
+    /// A majority with too few votes is only trusted after it lasted this long
+    pub fallback_duration: Duration,
+}
+
+/// Processing rates of the non-critical perception cyclers
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct CyclerRatesParameters {
+    pub enable_downscaling: bool,
+    pub audio: CyclerRateParameters,
+    pub spl_network: CyclerRateParameters,
+    /// The control cycler being slower than this on average indicates CPU pressure
+    pub maximum_control_cycle_duration: Duration,
+    /// Pressure is released once the average falls below this fraction of the maximum
+    pub release_ratio: f32,
+    /// Weight of the latest cycle in the averaged cycle durations and frequencies
+    pub smoothing_factor: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct CyclerRateParameters {
+    /// Hz, zero lets the cycler run as fast as its hardware allows
+    pub maximum_frequency: f32,
+    /// Hz, used while the CPU is under pressure
+    pub downscaled_frequency: f32,
+}
+
+/// Mechanical limitations of a single robot, overridden in the parameters of its body id
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct RobotCapabilitiesParameters {
+    pub can_dive_left: bool,
+    pub can_dive_right: bool,
+    /// Squatting motions are avoided to spare the knees
+    pub has_weak_knee: bool,
+}
+
+impl RobotCapabilitiesParameters {
+    pub fn is_keeper_capable(&self) -> bool {
+        self.can_dive_left && self.can_dive_right && !self.has_weak_knee
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct KeeperDiveParameters {
+    /// Whole-body dives stay disabled until their motions are tuned on the robots, the keeper
+    /// then only squats
+    pub enable_dives: bool,
+    /// Time the dive motions need to reach the blocking pose
+    pub reaction_time: Duration,
+    /// The keeper commits at most this long before the reaction time would be exceeded
+    pub commitment_window: Duration,
+    pub goal_post_margin: f32,
+    pub squat_reach: f32,
+    /// In penalty situations, balls within the squat reach are blocked by diving to the front
+    pub use_front_dive: bool,
+    pub wide_stance_reach: f32,
+    pub dive_reach: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct StrategyParameters {
+    pub enable: bool,
+    pub late_game_duration: Duration,
+    pub attacking: RolePositionsModulation,
+    pub park_the_bus: RolePositionsModulation,
+}
+
+/// Slow, safe circulation while the own team comfortably controls the ball, urgent pressing while
+/// the opponent does
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct TempoParameters {
+    pub enable: bool,
+    /// The own team has to control the ball this long before the play is slowed down
+    pub comfortable_possession_duration: Duration,
+    pub circulation: RolePositionsModulation,
+    pub pressing: RolePositionsModulation,
+}
+
+/// Risk taking in the final seconds of each half, regardless of the score
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct EndgameParameters {
+    pub enable: bool,
+    /// The striker shoots right away instead of passing or dribbling
+    pub immediate_shot_duration: Duration,
+    /// Defenders stay on their passive ring instead of pushing up
+    pub hold_defense_duration: Duration,
+}
+
+/// Without a fresh GameController state penalties and set plays of the opponent may be missed,
+/// the defenders stay on their passive ring meanwhile
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct StaleGameStateParameters {
+    pub enable: bool,
+    pub minimum_staleness: Duration,
+}
+
+/// Circling around the ball while an opponent shields it
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct OrbitBallParameters {
+    pub enable: bool,
+    pub opponent_distance_to_ball: f32,
+    /// The ball counts as shielded if the best kick pose is closer than this to the opponent
+    pub minimum_distance_to_opponent: f32,
+    pub orbit_radius: f32,
+    /// Angle around the ball between the robot and the next waypoint on the orbit
+    pub orbit_step_angle: f32,
+    /// After orbiting this long, the striker leaves the ball to a teammate
+    pub timeout: Duration,
+    pub yield_duration: Duration,
+}
+
+/// Set plays of the striker at own kick-offs
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct KickOffParameters {
+    pub enable: bool,
+    /// The plays are rotated between kick-offs if none is set
+    pub play: Option<KickOffPlay>,
+    /// Targets are given for the left side and mirrored for the right side, they have to lie in
+    /// the opponent half for the striker to line up in the own half
+    pub pass_target: Point2<Field>,
+    pub pass_strength: f32,
+    pub corner_target: Point2<Field>,
+    pub corner_strength: f32,
+    pub dribble_target: Point2<Field>,
+    pub dribble_strength: f32,
+    /// The regular behavior takes over after this long in playing
+    pub duration: Duration,
+    /// The play is complete once the ball is this far away from the center mark
+    pub completion_distance: f32,
+    pub alignment_distance: f32,
+    pub maximum_lateral_alignment_error: f32,
+    pub maximum_angular_alignment_error: f32,
+}
+
+/// Kicks of the striker during the penalty shootout
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct PenaltyShotParameters {
+    pub enable: bool,
+    /// A new variant is drawn randomly for every attempt if none is set
+    pub variant: Option<PenaltyShotVariant>,
+    /// Lateral distance of placed shots from the center of the goal
+    pub placed_shot_target_y: f32,
+    pub placed_shot_strength: f32,
+    pub strong_shot_strength: f32,
+    /// How long the feint waits aligned for a center shot after the attempt started
+    pub feint_delay: Duration,
+    /// The robot aligns on the kick line this far behind the kick pose before approaching
+    pub alignment_distance: f32,
+    pub maximum_lateral_alignment_error: f32,
+    pub maximum_angular_alignment_error: f32,
+}
+
+/// Factors the role position parameters are scaled with while a strategy or tempo is active
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct RolePositionsModulation {
+    pub defender_ring_radius_factor: f32,
+    pub supporter_distance_to_ball_factor: f32,
+    /// Scales how far the minimum x lies from the center line, factors above 1 move it towards
+    /// the opponent goal and below 1 towards the own goal, no matter on which half it lies
+    pub supporter_minimum_x_factor: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct PenaltyReboundParameters {
+    pub enable: bool,
+    /// Roles by priority, the first two cover the lateral rebound corridors
+    pub slot_roles: Vec<Role>,
+    /// Arc around the penalty mark, at least the distance the rules require
+    pub distance_to_penalty_mark: f32,
+    /// Angle of the corridor slots to the line from the penalty mark to the field center
+    pub corridor_angle: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct FreeKickParameters {
+    pub corner_kick_supporter_position: Point2<Field>,
+    pub goal_kick_supporter_position: Point2<Field>,
+    pub goal_kick_target: Point2<Field>,
+    /// Relative to the ball in x, distance from the sideline in y
+    pub kick_in_supporter_position: Point2<Field>,
+    /// Relative to the ball in x, distance from the sideline in y
+    pub kick_in_target: Point2<Field>,
+    pub kick_in_line_up_spacing: f32,
+    pub distance_margin: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct PassingParameters {
+    pub enable: bool,
+    pub blocked_shot_coverage: f32,
+    pub minimum_pass_distance: f32,
+    pub maximum_pass_distance: f32,
+    pub lane_clearance: f32,
+    pub kick_strength: f32,
+    pub teammate_timeout: Duration,
+    pub announcement_timeout: Duration,
+    pub receiving: PassReceivingParameters,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct PassReceivingParameters {
+    /// Faster balls are intercepted on their trajectory instead of awaited on the pass line
+    pub rolling_ball_velocity: f32,
+    /// Receiving positions with an opponent closer than this are considered covered
+    pub open_radius: f32,
+    pub candidate_spacing: f32,
 }
 
 #[derive(Copy, Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -53,6 +569,36 @@ pub struct LookActionParameters {
     pub distance_threshold: f32,
     pub look_forward_position: Point2<Ground>,
     pub position_of_interest_switch_interval: Duration,
+    pub ball_weight: f32,
+    pub localization_weight: f32,
+    pub obstacle_weight: f32,
+    /// Information gain of looking forward, any other target has to beat it
+    pub forward_information_gain: f32,
+    /// A kind of target not looked at for this long is as valuable as it gets
+    pub staleness_saturation: Duration,
+    pub ball_age_saturation: Duration,
+    /// Standard deviation of the position in meters at which the localization is considered lost
+    pub localization_uncertainty_saturation: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct SupportPositioningParameters {
+    /// Supporters search for open space around their role position instead of standing on it
+    pub enable: bool,
+    /// Candidates are searched within this distance around the role position
+    pub search_radius: f32,
+    pub candidate_spacing: f32,
+    /// Open space of a candidate is only measured within this distance
+    pub evaluation_radius: f32,
+    pub sample_spacing: f32,
+    /// Subtracted from the open space in square meters per meter away from the role position
+    pub distance_penalty: f32,
+    /// Robots closer than this to the line from the ball to a candidate block the pass
+    pub pass_lane_width: f32,
+    /// Subtracted from the open space in square meters for a blocked pass lane
+    pub blocked_pass_lane_penalty: f32,
+    /// Added to the score of the previous position in square meters
+    pub target_hysteresis: f32,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -78,6 +624,37 @@ pub struct RolePositionsParameters {
 pub struct SearchParameters {
     pub position_reached_distance: f32,
     pub rotation_per_step: f32,
+    /// seconds of staleness a field cell loses per meter of distance to the search anchor
+    pub coverage_distance_weight: f32,
+    /// staleness added to the previously chosen field cell
+    pub coverage_cell_hysteresis: Duration,
+    /// cells not observed for longer are considered equally stale
+    pub maximum_coverage_age: Duration,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ShotSelectionParameters {
+    pub intercept: f32,
+    pub distance_weight: f32,
+    pub opening_angle_weight: f32,
+    pub keeper_coverage_weight: f32,
+    pub opponent_pressure_weight: f32,
+    pub pressure_radius: f32,
+    pub dribble_distance: f32,
+    pub dribble_loss_per_pressure: f32,
+    pub minimum_pass_distance: f32,
+    pub pass_distance_scale: f32,
+    pub dribble_kick_strength: f32,
+    pub opportunistic_shot: OpportunisticShotParameters,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct OpportunisticShotParameters {
+    pub enable: bool,
+    pub minimum_free_angle: f32,
+    pub free_angle_hysteresis: f32,
+    pub keeper_displacement_distance: f32,
+    pub maximum_distance_to_goal: f32,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -94,6 +671,12 @@ pub struct FindKickTargetsParameters {
     pub emergency_kick_target_angles: Vec<f32>,
     pub max_kick_around_obstacle_angle: f32,
     pub ball_radius_for_kick_target_selection: f32,
+    pub number_of_goal_targets: usize,
+    pub keeper_search_radius: f32,
+    /// how far the keeper is able to reach by stepping or diving
+    pub keeper_reach: f32,
+    pub maximum_target_clearance: f32,
+    pub target_score_tolerance: f32,
 }
 
 impl Index<KickVariant> for InWalkKicksParameters {
@@ -115,6 +698,11 @@ pub struct InWalkKickInfoParameters {
     pub reached_thresholds: Vector3<f32>,
     pub shot_distance: f32,
     pub enabled: bool,
+    /// Maximum angle between the current and the required direction to approach the ball from
+    pub approach_cone: f32,
+    /// Displacement of the robot caused by momentum transfer and foot slip of a kick with the
+    /// left foot at full strength, mirrored for the right foot
+    pub odometry_offset: nalgebra::Vector2<f32>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -123,6 +711,34 @@ pub struct DribblingParameters {
     pub distance_to_be_aligned: f32,
     pub angle_to_approach_ball_from_threshold: f32,
     pub ignore_robot_when_near_ball_radius: f32,
+    pub duel: DuelParameters,
+    pub corridor: DribbleCorridorParameters,
+}
+
+/// Keeps dribbled balls from rolling out near the sidelines and the opponent ground line
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct DribbleCorridorParameters {
+    pub enable: bool,
+    /// distance to the field boundary the ball should stay away from
+    pub boundary_margin: f32,
+    /// how far a dribble kick is expected to push the ball
+    pub roll_distance: f32,
+}
+
+/// Dribbling against an opponent contesting the ball
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct DuelParameters {
+    pub enable: bool,
+    pub opponent_distance_to_ball: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ArmTuckingParameters {
+    pub enable: bool,
+    /// Arms are pulled tight on the side of obstacles with their hull closer than this
+    pub tuck_radius: f32,
+    /// Tucked arms are released once all obstacles on their side are further away than this
+    pub release_radius: f32,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -136,6 +752,14 @@ pub struct WalkAndStandParameters {
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct LostBallParameters {
     pub offset_to_last_ball_location: Vector2<Field>,
+    /// The loser gives up on the last known ball position and searches the field afterwards
+    pub timeout: Duration,
+    /// Age after which the confidence in the last known ball position has halved
+    pub confidence_half_life: Duration,
+    /// Below this confidence the loser searches the field, reached no earlier than the timeout
+    pub minimum_confidence: f32,
+    /// Above this confidence the loser looks right at the last known ball position
+    pub look_at_confidence: f32,
 }
 
 #[derive(Copy, Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -144,7 +768,31 @@ pub struct InterceptBallParameters {
     pub minimum_ball_velocity: f32,
     pub minimum_ball_velocity_towards_robot: f32,
     pub minimum_ball_velocity_towards_own_half: f32,
-    pub maximum_intercept_distance: f32,
+    pub engagement_ranges: InterceptEngagementRanges,
+}
+
+/// How far a robot of each role steps aside to get into the line of a passing ball
+#[derive(Copy, Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct InterceptEngagementRanges {
+    pub keeper: f32,
+    pub defender: f32,
+    pub midfielder: f32,
+    pub striker: f32,
+    pub striker_supporter: f32,
+    pub searcher: f32,
+}
+
+impl InterceptEngagementRanges {
+    pub fn range(&self, role: Role) -> f32 {
+        match role {
+            Role::Keeper | Role::ReplacementKeeper => self.keeper,
+            Role::DefenderLeft | Role::DefenderRight => self.defender,
+            Role::MidfielderLeft | Role::MidfielderRight => self.midfielder,
+            Role::Striker => self.striker,
+            Role::StrikerSupporter => self.striker_supporter,
+            Role::Loser | Role::Searcher => self.searcher,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -156,7 +804,15 @@ pub struct PathPlanningParameters {
     pub rotation_penalty_factor: f32,
     pub minimum_robot_radius_at_foot_height: f32,
     pub robot_radius_at_foot_height: f32,
+    /// Includes the swinging arms
     pub robot_radius_at_hip_height: f32,
+    /// Half the shoulder width, used to squeeze through gaps with the arms pulled tight
+    pub robot_radius_with_tucked_arms: f32,
+    /// A narrow gap is passed if avoiding it makes the path longer by more than this factor
+    pub narrow_gap_detour_factor: f32,
+    /// Enlarges the goal posts beyond the robot radius, they are added from the field dimensions
+    /// so a slightly off localization must not let the robot clip them
+    pub goal_post_inflation: f32,
     pub half_rotation: Duration,
 }
 
@@ -165,6 +821,8 @@ pub struct GameStateFilterParameters {
     pub game_controller_controller_delay: Duration,
     pub playing_message_delay: Duration,
     pub ready_message_delay: Duration,
+    /// How long the team waits in Ready for the GameController to confirm the referee's signal
+    pub standby_message_delay: Duration,
     pub kick_off_grace_period: Duration,
     pub tentative_finish_duration: Duration,
     pub distance_to_consider_ball_moved_in_kick_off: f32,
@@ -181,6 +839,7 @@ pub struct WalkingEngineParameters {
     pub emergency_foot_lift: f32,
     pub emergency_step: Step,
     pub emergency_step_duration: Duration,
+    pub foot_collision: FootCollisionParameters,
     pub foot_pressure_threshold: f32,
     pub forward_foot_support_offset: f32,
     pub gyro_balance_factors: LegJoints<f32>,
@@ -218,6 +877,17 @@ pub struct WalkingEngineParameters {
     pub walk_hip_height: f32,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct FootCollisionParameters {
+    pub enable: bool,
+    pub foot_length: f32,
+    pub foot_width: f32,
+    pub maximum_widening: f32,
+    pub safety_margin: f32,
+    /// forward offset of the sole center relative to the ankle
+    pub sole_center_offset: f32,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct SwingingArmsParameters {
     pub debug_pull_back: bool,
@@ -264,6 +934,59 @@ pub struct SplNetworkParameters {
     pub spl_striker_message_receive_timeout: Duration,
     pub spl_striker_message_send_interval: Duration,
     pub striker_trusts_team_ball: Duration,
+    pub search_sector_claim_refresh_interval: Duration,
+    pub game_controller_timeout: Duration,
+    pub game_controller_handover_timeout: Duration,
+    /// Consecutive messages another controller has to send before it may replace the active one
+    pub game_controller_handover_minimum_number_of_messages: usize,
+    /// Messages further apart than this are not consecutive, the count starts over
+    pub game_controller_handover_maximum_message_gap: Duration,
+    pub game_controller_fallback: GameControllerFallbackParameters,
+    pub message_budget: MessageBudgetParameters,
+    pub kicking_team_filter: KickingTeamFilterParameters,
+    pub game_controller_rebroadcast: GameControllerRebroadcastParameters,
+}
+
+/// Teammates share the GameController state they receive directly in their team messages, which
+/// is used while no GameController is received directly.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct GameControllerRebroadcastParameters {
+    pub enable_sending: bool,
+    pub enable_receiving: bool,
+    /// Rebroadcast states older than this are not used anymore
+    pub timeout: Duration,
+    /// A state that did not change is sent again after this interval unless a teammate already
+    /// did, it has to be shorter than the timeout
+    pub refresh_interval: Duration,
+}
+
+/// Once neither the GameController nor a teammate sent a state for the timeout, the last state is
+/// advanced locally instead of freezing the game.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct GameControllerFallbackParameters {
+    pub enable: bool,
+    pub timeout: Duration,
+    /// The GameController switches from Ready to Set after this duration
+    pub ready_duration: Duration,
+}
+
+/// A changed kicking team is accepted once either condition holds, such that quick corrections
+/// of the referee do not reach the behavior.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct KickingTeamFilterParameters {
+    pub minimum_number_of_consistent_messages: usize,
+    pub minimum_dwell_time: Duration,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct MessageBudgetParameters {
+    /// Remaining fractions of the message budget to warn at, in descending order
+    pub warning_thresholds: Vec<f32>,
+    pub minimum_warning_interval: Duration,
+    pub warning_tone: AcousticCode,
+    pub warning_tone_duration: Duration,
+    /// Scales the silence interval between team messages once the respective threshold is passed
+    pub silence_interval_factors: Vec<f32>,
 }
 
 #[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -295,6 +1018,60 @@ pub struct BallDetectionParameters {
     pub image_containment_merge_factor: f32,
     pub cluster_merge_radius_factor: f32,
     pub ball_radius_enlargement_factor: f32,
+    pub shadow_normalization: ShadowNormalizationParameters,
+    pub plausibility_gate: BallPlausibilityGateParameters,
+}
+
+/// Rejects far away detections which cannot be the ball according to the field model
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct BallPlausibilityGateParameters {
+    pub enable: bool,
+    /// Closer detections are reliable enough to pass unchecked
+    pub minimum_distance: f32,
+    /// Detections further outside of the carpet are e.g. spectators' shoes
+    pub carpet_margin: f32,
+    /// Fastest the ball can travel since the filtered ball was last seen
+    pub maximum_ball_speed: f32,
+    /// Jump allowed regardless of the elapsed time, covers projection and localization errors
+    pub jump_tolerance: f32,
+}
+
+/// Local contrast normalization of ball candidate samples, tuned per location lighting profile
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ShadowNormalizationParameters {
+    pub enable: bool,
+    /// Half size of the window (in sample pixels) the local mean and contrast are computed in
+    pub window_radius: usize,
+    /// Keeps flat regions from being amplified to noise
+    pub minimum_standard_deviation: f32,
+    pub target_mean: f32,
+    pub target_standard_deviation: f32,
+    /// Writes patches which are only classified as ball with or without normalization
+    pub dump_disagreeing_patches: bool,
+    pub dump_directory: PathBuf,
+    pub maximum_number_of_dumped_patches: usize,
+}
+
+/// Keeps the recent camera images in memory to dump them when a failure happens
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ImageRingBufferParameters {
+    pub enable: bool,
+    pub duration: Duration,
+    pub frames_per_second: f32,
+    /// Only every n-th pixel in both directions is kept
+    pub downsampling_factor: u32,
+    pub jpeg_quality: u8,
+    pub dump_directory: PathBuf,
+    /// Older dumps are removed from the dump directory
+    pub maximum_number_of_dumps: usize,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ImageDumpTriggerParameters {
+    pub maximum_localization_uncertainty: f32,
+    /// After this time the ball has to have left the kicking robot
+    pub missed_kick_delay: Duration,
+    pub missed_kick_ball_distance: f32,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -303,8 +1080,7 @@ pub struct BallFilterParameters {
     pub measurement_matching_distance: f32,
     pub hypothesis_merge_distance: f32,
     pub process_noise: Vector4<f32>,
-    pub measurement_noise_moving: nalgebra::Vector2<f32>,
-    pub measurement_noise_resting: nalgebra::Vector2<f32>,
+    pub measurement_noise: BallMeasurementNoiseParameters,
     pub initial_covariance: Vector4<f32>,
     pub visible_validity_exponential_decay_factor: f32,
     pub hidden_validity_exponential_decay_factor: f32,
@@ -313,6 +1089,41 @@ pub struct BallFilterParameters {
     pub resting_ball_velocity_threshold: f32,
 }
 
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct BallMeasurementNoiseParameters {
+    pub top: CameraBallMeasurementNoise,
+    pub bottom: CameraBallMeasurementNoise,
+}
+
+impl BallMeasurementNoiseParameters {
+    pub fn for_measurement(&self, camera: CameraPosition, distance: f32) -> &BallMeasurementNoise {
+        let camera_noise = match camera {
+            CameraPosition::Top => &self.top,
+            CameraPosition::Bottom => &self.bottom,
+        };
+        if distance < camera_noise.close_range {
+            &camera_noise.close
+        } else {
+            &camera_noise.far
+        }
+    }
+}
+
+/// Noise of a single camera, split into a close and a far distance band.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct CameraBallMeasurementNoise {
+    pub close_range: f32,
+    pub close: BallMeasurementNoise,
+    pub far: BallMeasurementNoise,
+}
+
+/// Diagonal measurement noise, scaled by the squared distance to the ball.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct BallMeasurementNoise {
+    pub moving: nalgebra::Vector2<f32>,
+    pub resting: nalgebra::Vector2<f32>,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct ObstacleFilterParameters {
     pub hypothesis_timeout: Duration,
@@ -335,6 +1146,8 @@ pub struct ObstacleFilterParameters {
     pub robot_obstacle_radius_at_foot_height: f32,
     pub unknown_obstacle_radius: f32,
     pub goal_post_obstacle_radius: f32,
+    /// Robot obstacles this close to a teammate's communicated position are the teammate
+    pub teammate_matching_distance: f32,
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
@@ -368,3 +1181,114 @@ pub struct FallProtectionParameters {
     pub arm_stiffness: f32,
     pub leg_stiffness: f32,
 }
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct SelfTestParameters {
+    pub enable: bool,
+    pub duration: Duration,
+    pub joint_amplitudes: Joints<f32>,
+    pub maximum_joint_error: f32,
+    pub valid_sonar_range: Range<f32>,
+    pub minimum_foot_pressure: f32,
+    pub report_path: PathBuf,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct BallSearchParameters {
+    pub number_of_columns: usize,
+    pub number_of_rows: usize,
+    pub claim_timeout: Duration,
+    pub claim_switch_margin: f32,
+    pub distance_weight: f32,
+    pub ball_spread: f32,
+    pub uniform_convergence_time: Duration,
+    /// Observations of the field coverage reduce the likelihood for this long, teammates send
+    /// their poses only every few seconds
+    pub observation_timeout: Duration,
+    pub observation_decay_rate: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct FieldCoverageParameters {
+    pub number_of_columns: usize,
+    pub number_of_rows: usize,
+    /// horizontal opening angle of the own camera
+    pub field_of_view: f32,
+    /// teammates do not communicate their head yaw, a wider cone covers their scanning
+    pub teammate_field_of_view: f32,
+    pub observation_distance: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ManualPlacementParameters {
+    pub kicking_team: Players<Point2<Field>>,
+    pub defending_team: Players<Point2<Field>>,
+    pub enable_speaker_hints: bool,
+    pub hint_interval: Duration,
+    pub placement_tolerance: f32,
+    pub penalty_area_corner_radius: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct HeadStabilizationParameters {
+    pub enable: bool,
+    /// Fraction of the torso oscillation the neck counter-rotates at full walk speed
+    pub gains: HeadJoints<f32>,
+    /// Decay of the integrated gyroscope in 1/s, removes drift and the mean orientation
+    pub leak_rate: f32,
+    pub maximum_compensation: HeadJoints<f32>,
+}
+
+/// Injected while loading the parameters, names the location layer in use
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct LocationParameters {
+    pub name: String,
+    /// The location was selected from the connected WiFi network instead of `nao_location`
+    pub is_detected_from_network: bool,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct LocationAnnouncementParameters {
+    pub detected_tone: AcousticCode,
+    /// Played when the network is not mapped to a location and `nao_location` is used
+    pub fallback_tone: AcousticCode,
+    pub tone_duration: Duration,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ActionLatencyParameters {
+    pub bin_width: Duration,
+    pub number_of_bins: usize,
+    /// Jumps of the walk target larger than this count as a changed motion command
+    pub walk_target_change_threshold: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ShutdownParameters {
+    /// Stiffness is removed after this time even if the robot did not manage to sit down
+    pub sit_down_timeout: Duration,
+    pub stiffness_ramp_duration: Duration,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct TransformSanityCheckerParameters {
+    pub enable: bool,
+    /// Localized positions further outside the carpet are inconsistent
+    pub carpet_margin: f32,
+    /// Per cycle difference between the localized and the measured rotation
+    pub maximum_rotation_discrepancy: f32,
+    /// Difference of transforms that should be equal, in meters and radians
+    pub maximum_transform_error: f32,
+    /// Pixels the field border may reach above the horizon
+    pub horizon_margin: f32,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct BallPossessionParameters {
+    /// A team needs a robot this close to the ball to control it
+    pub possession_distance: f32,
+    /// The closest robot has to be this much closer to the ball than the other team's
+    pub contest_margin: f32,
+    /// A new estimate has to persist this long before the possession changes
+    pub switch_duration: Duration,
+}
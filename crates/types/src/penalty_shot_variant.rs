@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+pub enum PenaltyShotVariant {
+    PlacedLeft,
+    PlacedRight,
+    StrongCenter,
+    /// Waits aligned for a center shot and then places the ball away from the keeper
+    DelayedFeint,
+}
+
+impl PenaltyShotVariant {
+    pub const ALL: [Self; 4] = [
+        Self::PlacedLeft,
+        Self::PlacedRight,
+        Self::StrongCenter,
+        Self::DelayedFeint,
+    ];
+}
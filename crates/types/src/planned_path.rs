@@ -77,5 +77,18 @@ impl PathSegment {
 
 #[derive(Clone, Debug, Default, Serialize, SerializeHierarchy, Deserialize)]
 pub struct PlannedPath {
-    pub path: Option<Vec<PathSegment>>,
+    pub path: Vec<PathSegment>,
+    pub gap_passage: GapPassage,
+}
+
+/// Whether the path keeps the full footprint including the swinging arms to all obstacles
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy,
+)]
+pub enum GapPassage {
+    #[default]
+    Wide,
+    /// The path leads through a gap only passable slowly with the arms pulled tight, since the
+    /// detour around it is too long
+    Narrow,
 }
@@ -17,3 +17,24 @@ pub enum PointOfInterest {
         absolute_position: Point2<Field>,
     },
 }
+
+/// Why the active vision decided to look at the current point of interest
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub enum LookReason {
+    /// The ball has not been seen for a while
+    BallAge,
+    /// The localization is uncertain and needs field marks
+    LocalizationUncertainty,
+    /// This kind of target has not been looked at for a while
+    Staleness,
+    /// No other target is worth looking at
+    #[default]
+    NothingToObserve,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct LookTarget {
+    pub point_of_interest: PointOfInterest,
+    pub reason: LookReason,
+    pub information_gain: f32,
+}
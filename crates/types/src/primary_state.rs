@@ -8,6 +8,7 @@ pub enum PrimaryState {
     #[default]
     Unstiff,
     Initial,
+    Standby,
     Ready,
     Set,
     Playing,
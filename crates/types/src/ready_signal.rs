@@ -0,0 +1,42 @@
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+use crate::players::Players;
+
+/// Result of the team vote on the referee's hand signal that ends Standby
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct FilteredReadySignal {
+    /// Confidence of the own detection, it is shared with the team
+    pub own_confidence: Option<f32>,
+    pub votes: Players<Option<ReadySignalVote>>,
+    pub number_of_positive_votes: usize,
+    pub majority_since: Option<SystemTime>,
+    /// Only set in the cycle the team decided on the ready signal
+    pub is_detected: bool,
+    /// Kept after Standby for post-game analysis
+    pub last_decision: Option<ReadySignalDecision>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ReadySignalVote {
+    pub confidence: f32,
+    pub time: SystemTime,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ReadySignalDecision {
+    pub source: ReadySignalDecisionSource,
+    pub time: SystemTime,
+    pub number_of_positive_votes: usize,
+    pub number_of_votes: usize,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+pub enum ReadySignalDecisionSource {
+    /// Enough teammates agreed on the detection
+    Majority,
+    /// Too few teammates voted, but the detections agreed for a long time
+    FallbackTimer,
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+/// Hardware conditions under which the robot spares itself instead of walking around
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy,
+)]
+pub struct RobotCondition {
+    pub is_battery_low: bool,
+    pub are_joints_hot: bool,
+}
+
+impl RobotCondition {
+    pub fn is_degraded(&self) -> bool {
+        self.is_battery_low || self.are_joints_hot
+    }
+}
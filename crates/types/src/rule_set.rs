@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+/// Selects the version of the SPL rules the robots play by.
+///
+/// Everything that changes between rule years is answered here so that behavior code does not
+/// need to know which rules are in effect, e.g. when playing test events with last year's rules.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy,
+)]
+pub enum RuleSet {
+    Spl2023,
+    #[default]
+    Spl2024,
+}
+
+impl RuleSet {
+    /// Distance the defending team has to keep to the ball during a free kick.
+    pub fn free_kick_distance(self) -> f32 {
+        match self {
+            RuleSet::Spl2023 => 0.75,
+            RuleSet::Spl2024 => 0.75,
+        }
+    }
+
+    /// Number of team messages each team may send during a game.
+    pub fn message_budget(self) -> u16 {
+        match self {
+            RuleSet::Spl2023 => 1200,
+            RuleSet::Spl2024 => 1200,
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub enum CheckResult {
+    #[default]
+    Passed,
+    Failed {
+        reason: String,
+    },
+}
+
+impl CheckResult {
+    pub fn is_passed(&self) -> bool {
+        matches!(self, CheckResult::Passed)
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct SelfTestReport {
+    pub joints: CheckResult,
+    pub top_camera: CheckResult,
+    pub bottom_camera: CheckResult,
+    pub sonar: CheckResult,
+    pub force_sensitive_resistors: CheckResult,
+    pub network: CheckResult,
+}
+
+impl SelfTestReport {
+    pub fn is_passed(&self) -> bool {
+        self.joints.is_passed()
+            && self.top_camera.is_passed()
+            && self.bottom_camera.is_passed()
+            && self.sonar.is_passed()
+            && self.force_sensitive_resistors.is_passed()
+            && self.network.is_passed()
+    }
+}
@@ -56,6 +56,14 @@ pub struct TouchSensors {
     pub right_hand_right: bool,
 }
 
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct Battery {
+    /// State of charge between 0.0 and 1.0
+    pub charge: f32,
+    pub current: f32,
+    pub temperature: f32,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
 pub struct SensorData {
     pub positions: Joints<f32>,
@@ -65,4 +73,6 @@ pub struct SensorData {
     pub touch_sensors: TouchSensors,
     pub temperature_sensors: Joints<f32>,
     pub currents: Joints<f32>,
+    /// Not available in simulation
+    pub battery: Option<Battery>,
 }
@@ -0,0 +1,36 @@
+use coordinate_systems::Ground;
+use linear_algebra::Point2;
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ShotFeatures {
+    pub distance_to_goal: f32,
+    /// Angle between the goal posts as seen from the ball
+    pub opening_angle: f32,
+    /// Fraction of the opening angle covered by opponents, the keeper as well as field players
+    pub keeper_coverage: f32,
+    pub opponent_pressure: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub enum StrikerDecision {
+    #[default]
+    Shoot,
+    Dribble,
+    Pass {
+        target: Point2<Ground>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, SerializeHierarchy)]
+pub struct ShotSelection {
+    pub features: ShotFeatures,
+    /// Probability of scoring, exported to evaluate the model against actual shot outcomes
+    pub expected_goals_shot: f32,
+    pub expected_goals_dribble: f32,
+    pub expected_goals_pass: Option<f32>,
+    /// The keeper left the goal and the free part of the goal is wide enough for a direct shot
+    pub is_goal_open: bool,
+    pub decision: StrikerDecision,
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, SerializeHierarchy)]
+pub enum TransformCheck {
+    /// The localized robot position is not on the carpet
+    GroundToFieldOffCarpet,
+    /// The localized orientation turned differently than the inertial measurement unit measured
+    GroundToFieldRotation,
+    /// The ground to robot transform of the camera matrix is not the one of the ground provider
+    CameraGroundToRobot,
+    /// The precomputed ground to camera transform is not the chain of its parts
+    CameraChain,
+    /// Parts of the detected field border lie above the horizon of the top camera
+    FieldBorderAboveHorizon,
+}
+
+/// A failed check with the size of the inconsistency, in meters, radians or pixels
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, SerializeHierarchy)]
+pub struct TransformInconsistency {
+    pub check: TransformCheck,
+    pub error: f32,
+}
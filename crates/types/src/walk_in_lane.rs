@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use serialize_hierarchy::SerializeHierarchy;
+
+/// Corridor parallel to the sideline a robot follows while walking in during Ready.
+///
+/// Every player number has its own lane, so robots entering from the same sideline do not all
+/// funnel through the same corridor.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, SerializeHierarchy)]
+pub struct WalkInLane {
+    pub index: usize,
+    /// Field coordinate of the lane
+    pub y: f32,
+}
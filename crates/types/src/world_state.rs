@@ -5,27 +5,53 @@ use serde::{Deserialize, Serialize};
 use coordinate_systems::{Field, Ground};
 use linear_algebra::{Isometry2, Point2, Vector2};
 use serialize_hierarchy::SerializeHierarchy;
-use spl_network_messages::PlayerNumber;
+use spl_network_messages::{GoalMouthClaim, PassAnnouncement, PlayerNumber, StrikerIntent};
 
 use crate::{
-    fall_state::FallState, filtered_game_controller_state::FilteredGameControllerState,
+    ball_motion::BallMotion, ball_possession::BallPossession, fall_state::FallState,
+    field_coverage::FieldCoverage, filtered_game_controller_state::FilteredGameControllerState,
     kick_decision::KickDecision, obstacles::Obstacle, penalty_shot_direction::PenaltyShotDirection,
-    primary_state::PrimaryState, roles::Role, rule_obstacles::RuleObstacle, support_foot::Side,
+    primary_state::PrimaryState, robot_condition::RobotCondition, roles::Role,
+    rule_obstacles::RuleObstacle, support_foot::Side,
 };
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, SerializeHierarchy)]
 pub struct WorldState {
     pub ball: Option<BallState>,
+    pub ball_motion: Option<BallMotion>,
+    pub ball_possession: BallPossession,
     pub rule_ball: Option<BallState>,
     pub filtered_game_controller_state: Option<FilteredGameControllerState>,
     pub obstacles: Vec<Obstacle>,
+    /// Robot obstacles not matching a teammate's communicated position
+    pub opponents: Vec<Obstacle>,
     pub rule_obstacles: Vec<RuleObstacle>,
+    pub teammate_positions: Vec<Point2<Field>>,
+    /// Positions of the teammates announcing a defender role
+    pub teammate_defender_positions: Vec<Point2<Field>>,
     pub position_of_interest: Point2<Ground>,
     pub kick_decisions: Option<Vec<KickDecision>>,
     pub instant_kick_decisions: Option<Vec<KickDecision>>,
+    pub search_sector_position: Option<Point2<Field>>,
+    pub field_coverage: FieldCoverage,
+    pub pass_intent: Option<PassAnnouncement>,
+    pub announced_pass: Option<PassAnnouncement>,
+    /// A teammate clears the ball out of the own penalty area, the keeper keeps out of its way
+    pub teammate_goal_mouth_claim: Option<GoalMouthClaim>,
+    pub teammate_striker: Option<TeammateStriker>,
     pub robot: RobotState,
 }
 
+/// The teammate currently announcing the striker role, the fastest one if several do.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, SerializeHierarchy)]
+pub struct TeammateStriker {
+    pub player_number: PlayerNumber,
+    pub intent: Option<StrikerIntent>,
+    /// The teammate reaches the ball sooner than this robot, a striker yields to it until the role
+    /// assignment resolves the conflict
+    pub is_faster: bool,
+}
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, SerializeHierarchy)]
 pub struct BallState {
     pub ball_in_ground: Point2<Ground>,
@@ -57,4 +83,7 @@ pub struct RobotState {
     pub fall_state: FallState,
     pub has_ground_contact: bool,
     pub player_number: PlayerNumber,
+    pub robot_condition: RobotCondition,
+    /// Returned from a penalty and not yet sure on which sideline
+    pub is_converging_after_reentry: bool,
 }
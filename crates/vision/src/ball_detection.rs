@@ -1,17 +1,32 @@
-use color_eyre::Result;
+use std::{
+    fs::{create_dir_all, File},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::mpsc::{sync_channel, SyncSender, TrySendError},
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::{eyre::WrapErr, Result};
 use compiled_nn::CompiledNN;
+use log::warn;
 use serde::{Deserialize, Serialize};
 
 use context_attribute::context;
-use coordinate_systems::Pixel;
+use coordinate_systems::{Field, Ground, Pixel};
 use framework::{deserialize_not_implemented, AdditionalOutput, MainOutput};
 use geometry::{circle::Circle, rectangle::Rectangle};
 use hardware::PathsInterface;
-use linear_algebra::{point, vector, Vector2};
+use linear_algebra::{distance, point, vector, Isometry2, Vector2};
 use projection::{camera_matrix::CameraMatrix, Projection};
 use types::{
-    ball::{Ball, CandidateEvaluation},
-    parameters::BallDetectionParameters,
+    ball::{Ball, CandidateEvaluation, GatedBall, PlausibilityDecision},
+    ball_position::BallPosition,
+    cycle_time::CycleTime,
+    field_dimensions::FieldDimensions,
+    parameters::{
+        BallDetectionParameters, BallPlausibilityGateParameters, ShadowNormalizationParameters,
+    },
     perspective_grid_candidates::PerspectiveGridCandidates,
     ycbcr422_image::YCbCr422Image,
 };
@@ -37,6 +52,10 @@ struct BallCluster<'a> {
 pub struct BallDetection {
     #[serde(skip, default = "deserialize_not_implemented")]
     neural_networks: NeuralNetworks,
+    number_of_dumped_patches: usize,
+    /// Writes dumped patches on a separate thread, file IO takes too long for the vision cycle
+    #[serde(skip)]
+    patch_dumper: Option<SyncSender<(Vec<Sample>, PathBuf)>>,
 }
 
 #[context]
@@ -48,14 +67,19 @@ pub struct CreationContext {
 #[context]
 pub struct CycleContext {
     ball_candidates: AdditionalOutput<Vec<CandidateEvaluation>, "ball_candidates">,
+    plausibility_decisions: AdditionalOutput<Vec<GatedBall>, "ball_plausibility_decisions">,
 
     camera_matrix: RequiredInput<Option<CameraMatrix>, "camera_matrix?">,
     perspective_grid_candidates:
         RequiredInput<Option<PerspectiveGridCandidates>, "perspective_grid_candidates?">,
     image: Input<YCbCr422Image, "image">,
+    cycle_time: Input<CycleTime, "Control", "cycle_time">,
+    filtered_ball: Input<Option<BallPosition<Ground>>, "Control", "ball_position?">,
+    ground_to_field: Input<Option<Isometry2<Ground, Field>>, "Control", "ground_to_field?">,
 
     parameters: Parameter<BallDetectionParameters, "ball_detection.$cycler_instance">,
     ball_radius: Parameter<f32, "field_dimensions.ball_radius">,
+    field_dimensions: Parameter<FieldDimensions, "field_dimensions">,
 }
 
 #[context]
@@ -94,12 +118,29 @@ impl BallDetection {
             classifier,
             positioner,
         };
-        Ok(Self { neural_networks })
+        let (patch_dumper, patches) = sync_channel::<(Vec<Sample>, PathBuf)>(1);
+        thread::Builder::new()
+            .name("shadow_patch_dumper".to_string())
+            .spawn(move || {
+                for (patches, directory) in patches {
+                    if let Err(error) = dump_patches(&patches, &directory) {
+                        warn!("failed to dump shadow patches: {error:#}");
+                    }
+                }
+            })
+            .wrap_err("failed to spawn shadow patch dumper")?;
+        Ok(Self {
+            neural_networks,
+            number_of_dumped_patches: 0,
+            patch_dumper: Some(patch_dumper),
+        })
     }
 
     pub fn cycle(&mut self, mut context: CycleContext) -> Result<MainOutputs> {
         let candidates = &context.perspective_grid_candidates.candidates;
+        let shadow_normalization = &context.parameters.shadow_normalization;
 
+        let mut disagreeing_patches = Vec::new();
         let evaluations = evaluate_candidates(
             candidates,
             context.image,
@@ -108,7 +149,29 @@ impl BallDetection {
             context.parameters.ball_radius_enlargement_factor,
             context.parameters.preclassifier_confidence_threshold,
             context.parameters.classifier_confidence_threshold,
+            shadow_normalization,
+            &mut disagreeing_patches,
         );
+        let remaining_dumps = shadow_normalization
+            .maximum_number_of_dumped_patches
+            .saturating_sub(self.number_of_dumped_patches);
+        disagreeing_patches.truncate(remaining_dumps);
+        match &self.patch_dumper {
+            Some(patch_dumper) if !disagreeing_patches.is_empty() => {
+                let number_of_patches = disagreeing_patches.len();
+                let dump = (
+                    disagreeing_patches,
+                    shadow_normalization.dump_directory.clone(),
+                );
+                match patch_dumper.try_send(dump) {
+                    Ok(()) => self.number_of_dumped_patches += number_of_patches,
+                    // the patches of a later cycle are dumped instead
+                    Err(TrySendError::Full(_)) => {}
+                    Err(TrySendError::Disconnected(_)) => warn!("shadow patch dumper stopped"),
+                }
+            }
+            _ => {}
+        }
         context
             .ball_candidates
             .fill_if_subscribed(|| evaluations.clone());
@@ -134,7 +197,40 @@ impl BallDetection {
             context.parameters.cluster_merge_radius_factor,
         );
 
-        let balls = project_balls_to_ground(&clusters, context.camera_matrix, *context.ball_radius);
+        let mut balls =
+            project_balls_to_ground(&clusters, context.camera_matrix, *context.ball_radius);
+
+        let plausibility_gate = &context.parameters.plausibility_gate;
+        if plausibility_gate.enable {
+            let decisions: Vec<_> = balls
+                .iter()
+                .map(|ball| GatedBall {
+                    position: ball.position,
+                    decision: decide_plausibility(
+                        ball,
+                        context.filtered_ball,
+                        context.ground_to_field,
+                        context.cycle_time,
+                        context.field_dimensions,
+                        plausibility_gate,
+                    ),
+                })
+                .collect();
+            balls = balls
+                .into_iter()
+                .zip(&decisions)
+                .filter(|(_, gated)| {
+                    matches!(
+                        gated.decision,
+                        PlausibilityDecision::Unchecked | PlausibilityDecision::Accepted
+                    )
+                })
+                .map(|(ball, _)| ball)
+                .collect();
+            context
+                .plausibility_decisions
+                .fill_if_subscribed(|| decisions);
+        }
 
         Ok(MainOutputs {
             balls: Some(balls).into(),
@@ -194,6 +290,80 @@ fn sample_grayscale(image: &YCbCr422Image, candidate: Circle<Pixel>) -> Sample {
     sample
 }
 
+/// Normalizes each pixel by the mean and standard deviation of its neighborhood.
+///
+/// Shadows scale the brightness of a region, which cancels out in the normalized sample as long
+/// as the local contrast stays above the minimum standard deviation.
+fn normalize_local_contrast(sample: &Sample, parameters: &ShadowNormalizationParameters) -> Sample {
+    // summed area tables with an additional leading row and column of zeros
+    let mut sums = [[0.0_f64; SAMPLE_SIZE + 1]; SAMPLE_SIZE + 1];
+    let mut squared_sums = [[0.0_f64; SAMPLE_SIZE + 1]; SAMPLE_SIZE + 1];
+    for (y, row) in sample.iter().enumerate() {
+        for (x, pixel) in row.iter().enumerate() {
+            let pixel = *pixel as f64;
+            sums[y + 1][x + 1] = pixel + sums[y][x + 1] + sums[y + 1][x] - sums[y][x];
+            squared_sums[y + 1][x + 1] =
+                pixel * pixel + squared_sums[y][x + 1] + squared_sums[y + 1][x]
+                    - squared_sums[y][x];
+        }
+    }
+    let window_sum = |table: &[[f64; SAMPLE_SIZE + 1]; SAMPLE_SIZE + 1],
+                      top: usize,
+                      left: usize,
+                      bottom: usize,
+                      right: usize| {
+        table[bottom][right] - table[top][right] - table[bottom][left] + table[top][left]
+    };
+
+    let radius = parameters.window_radius;
+    let mut normalized = Sample::default();
+    for (y, row) in normalized.iter_mut().enumerate() {
+        for (x, pixel) in row.iter_mut().enumerate() {
+            let top = y.saturating_sub(radius);
+            let left = x.saturating_sub(radius);
+            let bottom = (y + radius + 1).min(SAMPLE_SIZE);
+            let right = (x + radius + 1).min(SAMPLE_SIZE);
+            let count = ((bottom - top) * (right - left)) as f64;
+            let mean = window_sum(&sums, top, left, bottom, right) / count;
+            let variance = (window_sum(&squared_sums, top, left, bottom, right) / count
+                - mean * mean)
+                .max(0.0);
+            let standard_deviation =
+                (variance.sqrt() as f32).max(parameters.minimum_standard_deviation);
+            *pixel = (parameters.target_mean
+                + (sample[y][x] - mean as f32) / standard_deviation
+                    * parameters.target_standard_deviation)
+                .clamp(0.0, 255.0);
+        }
+    }
+    normalized
+}
+
+/// Writes the samples as binary PGM images for labeling.
+fn dump_patches(patches: &[Sample], directory: &Path) -> Result<()> {
+    create_dir_all(directory)
+        .wrap_err_with(|| format!("failed to create {}", directory.display()))?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .wrap_err("system time is before the unix epoch")?
+        .as_millis();
+    for (index, patch) in patches.iter().enumerate() {
+        let path = directory.join(format!("{timestamp}_{index}.pgm"));
+        let mut file =
+            File::create(&path).wrap_err_with(|| format!("failed to create {}", path.display()))?;
+        let pixels: Vec<u8> = patch
+            .iter()
+            .flatten()
+            .map(|pixel| pixel.clamp(0.0, 255.0) as u8)
+            .collect();
+        write!(file, "P5\n{SAMPLE_SIZE} {SAMPLE_SIZE}\n255\n")
+            .and_then(|_| file.write_all(&pixels))
+            .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
 fn evaluate_candidates(
     candidates: &[Circle<Pixel>],
     image: &YCbCr422Image,
@@ -202,6 +372,8 @@ fn evaluate_candidates(
     ball_radius_enlargement_factor: f32,
     classifier_confidence_threshold: f32,
     preclassifier_confidence_threshold: f32,
+    shadow_normalization: &ShadowNormalizationParameters,
+    disagreeing_patches: &mut Vec<Sample>,
 ) -> Vec<CandidateEvaluation> {
     let preclassifier = &mut networks.preclassifier;
     let classifier = &mut networks.classifier;
@@ -215,7 +387,12 @@ fn evaluate_candidates(
                 center: candidate.center,
                 radius: candidate.radius * ball_radius_enlargement_factor,
             };
-            let sample = sample_grayscale(image, enlarged_candidate);
+            let raw_sample = sample_grayscale(image, enlarged_candidate);
+            let sample = if shadow_normalization.enable {
+                normalize_local_contrast(&raw_sample, shadow_normalization)
+            } else {
+                raw_sample
+            };
             let preclassifier_confidence = preclassify_sample(preclassifier, &sample);
 
             let mut classifier_confidence = None;
@@ -223,6 +400,16 @@ fn evaluate_candidates(
                 classifier_confidence = Some(classify_sample(classifier, &sample))
             };
 
+            if shadow_normalization.enable && shadow_normalization.dump_disagreeing_patches {
+                let is_ball = classifier_confidence > Some(classifier_confidence_threshold);
+                let is_raw_ball = preclassify_sample(preclassifier, &raw_sample)
+                    > preclassifier_confidence_threshold
+                    && classify_sample(classifier, &raw_sample) > classifier_confidence_threshold;
+                if is_ball != is_raw_ball {
+                    disagreeing_patches.push(raw_sample);
+                }
+            }
+
             let mut corrected_circle = None;
             if classifier_confidence > Some(classifier_confidence_threshold) {
                 let raw_corrected_circle = position_sample(positioner, &sample);
@@ -348,6 +535,46 @@ fn project_balls_to_ground(
         .collect()
 }
 
+/// Far away detections have to be on the carpet and reachable from the filtered ball, a ball
+/// cannot cross the field faster than it rolls.
+fn decide_plausibility(
+    ball: &Ball,
+    filtered_ball: Option<&BallPosition<Ground>>,
+    ground_to_field: Option<&Isometry2<Ground, Field>>,
+    cycle_time: &CycleTime,
+    field_dimensions: &FieldDimensions,
+    parameters: &BallPlausibilityGateParameters,
+) -> PlausibilityDecision {
+    if ball.position.coords().norm() < parameters.minimum_distance {
+        return PlausibilityDecision::Unchecked;
+    }
+    if let Some(ground_to_field) = ground_to_field {
+        let position = *ground_to_field * ball.position;
+        let half_carpet_length = field_dimensions.length / 2.0
+            + field_dimensions.border_strip_width
+            + parameters.carpet_margin;
+        let half_carpet_width = field_dimensions.width / 2.0
+            + field_dimensions.border_strip_width
+            + parameters.carpet_margin;
+        if position.x().abs() > half_carpet_length || position.y().abs() > half_carpet_width {
+            return PlausibilityDecision::OutsideCarpet;
+        }
+    }
+    if let Some(filtered_ball) = filtered_ball {
+        let elapsed = cycle_time
+            .start_time
+            .duration_since(filtered_ball.last_seen)
+            .unwrap_or_default()
+            .as_secs_f32();
+        let reachable_distance =
+            parameters.jump_tolerance + parameters.maximum_ball_speed * elapsed;
+        if distance(ball.position, filtered_ball.position) > reachable_distance {
+            return PlausibilityDecision::ImpossibleJump;
+        }
+    }
+    PlausibilityDecision::Accepted
+}
+
 #[cfg(test)]
 mod tests {
     use std::{
@@ -473,6 +700,38 @@ mod tests {
         assert_relative_eq!(merge_weight, 0.5 * 0.75 * (7.0 / 8.0));
     }
 
+    #[test]
+    fn local_contrast_normalization_is_invariant_to_illumination() {
+        let parameters = ShadowNormalizationParameters {
+            enable: true,
+            window_radius: 4,
+            minimum_standard_deviation: 8.0,
+            target_mean: 128.0,
+            target_standard_deviation: 48.0,
+            ..Default::default()
+        };
+        let mut sample = Sample::default();
+        for (y, row) in sample.iter_mut().enumerate() {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                *pixel = if (x / 4 + y / 4) % 2 == 0 {
+                    40.0
+                } else {
+                    200.0
+                };
+            }
+        }
+        let shadowed = sample.map(|row| row.map(|pixel| pixel * 0.5));
+
+        let normalized = normalize_local_contrast(&sample, &parameters);
+        let normalized_shadowed = normalize_local_contrast(&shadowed, &parameters);
+
+        for (row, shadowed_row) in normalized.iter().zip(normalized_shadowed.iter()) {
+            for (pixel, shadowed_pixel) in row.iter().zip(shadowed_row.iter()) {
+                assert_relative_eq!(pixel, shadowed_pixel, epsilon = 0.01);
+            }
+        }
+    }
+
     #[test]
     fn cycle_with_loaded_image() -> Result<()> {
         let filename = "../../tests/data/rome_bottom_ball.png";
@@ -490,6 +749,8 @@ mod tests {
             image_containment_merge_factor: 1.0,
             cluster_merge_radius_factor: 1.5,
             ball_radius_enlargement_factor: 2.0,
+            shadow_normalization: Default::default(),
+            plausibility_gate: Default::default(),
         };
         let perspective_grid_candidates = PerspectiveGridCandidates {
             candidates: vec![Circle {
@@ -515,15 +776,24 @@ mod tests {
         );
 
         let mut additional_output_buffer = None;
+        let mut plausibility_decisions_buffer = None;
         let context = CycleContext {
             ball_candidates: AdditionalOutput::<Vec<CandidateEvaluation>>::new(
                 false,
                 &mut additional_output_buffer,
             ),
+            plausibility_decisions: AdditionalOutput::<Vec<GatedBall>>::new(
+                false,
+                &mut plausibility_decisions_buffer,
+            ),
             parameters: &parameters,
             ball_radius: &0.5,
+            field_dimensions: &Default::default(),
             camera_matrix: &camera_matrix,
             image: &image,
+            cycle_time: &Default::default(),
+            filtered_ball: None,
+            ground_to_field: None,
             perspective_grid_candidates: &perspective_grid_candidates,
         };
         let mut preclassifier = CompiledNN::default();
@@ -540,7 +810,11 @@ mod tests {
             classifier,
             positioner,
         };
-        let mut node = BallDetection { neural_networks };
+        let mut node = BallDetection {
+            neural_networks,
+            number_of_dumped_patches: 0,
+            patch_dumper: None,
+        };
         let balls = node.cycle(context)?.balls;
         assert!(balls.value.is_some());
 
@@ -558,4 +832,56 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn far_detections_off_the_carpet_or_out_of_reach_are_rejected() {
+        let parameters = BallPlausibilityGateParameters {
+            enable: true,
+            minimum_distance: 3.0,
+            carpet_margin: 0.3,
+            maximum_ball_speed: 5.0,
+            jump_tolerance: 1.0,
+        };
+        let field_dimensions = FieldDimensions {
+            length: 9.0,
+            width: 6.0,
+            border_strip_width: 0.7,
+            ..Default::default()
+        };
+        let cycle_time = CycleTime {
+            start_time: UNIX_EPOCH + std::time::Duration::from_secs(10),
+            last_cycle_duration: Default::default(),
+        };
+        let ground_to_field = Isometry2::identity();
+        let filtered_ball = BallPosition {
+            position: point![3.5, 0.0],
+            velocity: Vector2::zeros(),
+            last_seen: cycle_time.start_time,
+        };
+        let ball = |x, y| Ball {
+            position: point![x, y],
+            image_location: Circle {
+                center: point![0.0, 0.0],
+                radius: 1.0,
+            },
+        };
+        let decide = |ball: &Ball| {
+            decide_plausibility(
+                ball,
+                Some(&filtered_ball),
+                Some(&ground_to_field),
+                &cycle_time,
+                &field_dimensions,
+                &parameters,
+            )
+        };
+
+        assert_eq!(decide(&ball(1.0, 0.0)), PlausibilityDecision::Unchecked);
+        assert_eq!(decide(&ball(4.0, 0.0)), PlausibilityDecision::Accepted);
+        assert_eq!(decide(&ball(0.0, 4.5)), PlausibilityDecision::OutsideCarpet);
+        assert_eq!(
+            decide(&ball(3.0, -3.0)),
+            PlausibilityDecision::ImpossibleJump
+        );
+    }
 }
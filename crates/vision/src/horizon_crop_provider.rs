@@ -0,0 +1,63 @@
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use context_attribute::context;
+use framework::MainOutput;
+use projection::camera_matrix::CameraMatrix;
+use types::{horizon_crop::HorizonCrop, ycbcr422_image::YCbCr422Image};
+
+#[derive(Deserialize, Serialize)]
+pub struct HorizonCropProvider {}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    camera_matrix: Input<Option<CameraMatrix>, "camera_matrix?">,
+    image: Input<YCbCr422Image, "image">,
+
+    margin: Parameter<f32, "horizon_crop_provider.$cycler_instance.margin">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {
+    pub horizon_crop: MainOutput<HorizonCrop>,
+}
+
+impl HorizonCropProvider {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        Ok(Self {})
+    }
+
+    pub fn cycle(&mut self, context: CycleContext) -> Result<MainOutputs> {
+        let image_width = context.image.width() as f32;
+        let image_height = context.image.height() as f32;
+        let horizon = context
+            .camera_matrix
+            .and_then(|camera_matrix| camera_matrix.horizon);
+
+        let horizon_crop = match horizon {
+            Some(horizon) => {
+                let crop_y_at_x = |x: f32| horizon.y_at_x(x) - *context.margin;
+                HorizonCrop {
+                    left_y: crop_y_at_x(0.0),
+                    right_y: crop_y_at_x(image_width),
+                    image_width,
+                    image_height,
+                }
+            }
+            None => HorizonCrop {
+                left_y: 0.0,
+                right_y: 0.0,
+                image_width,
+                image_height,
+            },
+        };
+
+        Ok(MainOutputs {
+            horizon_crop: horizon_crop.into(),
+        })
+    }
+}
@@ -0,0 +1,241 @@
+use std::{
+    collections::VecDeque,
+    fs::{create_dir_all, read_dir, remove_dir_all, write},
+    path::{Path, PathBuf},
+    sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::{eyre::WrapErr, Result};
+use image::{codecs::jpeg::JpegEncoder, RgbImage};
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use context_attribute::context;
+use hardware::TimeInterface;
+use types::{
+    color::Rgb, image_dump::ImageDumpRequest, parameters::ImageRingBufferParameters,
+    ycbcr422_image::YCbCr422Image,
+};
+
+/// Compressing and writing images takes too long for the vision cycle, both happen on a worker
+/// thread which owns the buffered frames. If it falls behind, new frames are dropped.
+#[derive(Deserialize, Serialize)]
+pub struct ImageRingBuffer {
+    last_frame_time: Option<SystemTime>,
+    last_handled_request: Option<SystemTime>,
+    /// Not part of the recordings, they contain the full images anyway
+    #[serde(skip)]
+    worker: Option<SyncSender<Job>>,
+}
+
+enum Job {
+    Frame {
+        time: SystemTime,
+        image: YCbCr422Image,
+        parameters: ImageRingBufferParameters,
+    },
+    Dump {
+        directory_name: String,
+        parameters: ImageRingBufferParameters,
+    },
+    Clear,
+}
+
+struct CompressedFrame {
+    time: SystemTime,
+    jpeg: Vec<u8>,
+}
+
+#[context]
+pub struct CreationContext {}
+
+#[context]
+pub struct CycleContext {
+    hardware_interface: HardwareInterface,
+    image: Input<YCbCr422Image, "image">,
+    image_dump_request: Input<Option<ImageDumpRequest>, "Control", "last_image_dump_request?">,
+    parameters: Parameter<ImageRingBufferParameters, "image_ring_buffer.$cycler_instance">,
+}
+
+#[context]
+#[derive(Default)]
+pub struct MainOutputs {}
+
+impl ImageRingBuffer {
+    pub fn new(_context: CreationContext) -> Result<Self> {
+        let (sender, receiver) = sync_channel(2);
+        thread::Builder::new()
+            .name("image_ring_buffer".to_string())
+            .spawn(move || work(receiver))
+            .wrap_err("failed to spawn image ring buffer worker")?;
+        Ok(Self {
+            last_frame_time: None,
+            last_handled_request: None,
+            worker: Some(sender),
+        })
+    }
+
+    pub fn cycle(&mut self, context: CycleContext<impl TimeInterface>) -> Result<MainOutputs> {
+        let Some(worker) = &self.worker else {
+            return Ok(MainOutputs::default());
+        };
+        let parameters = context.parameters;
+        if !parameters.enable {
+            if self.last_frame_time.take().is_some() {
+                send(worker, Job::Clear);
+            }
+            return Ok(MainOutputs::default());
+        }
+        let now = context.hardware_interface.get_now();
+
+        let frame_interval = Duration::from_secs_f32(1.0 / parameters.frames_per_second.max(0.1));
+        let is_frame_due = self
+            .last_frame_time
+            .map_or(true, |time| elapsed_since(now, time) >= frame_interval);
+        if is_frame_due {
+            self.last_frame_time = Some(now);
+            send(
+                worker,
+                Job::Frame {
+                    time: now,
+                    image: context.image.clone(),
+                    parameters: parameters.clone(),
+                },
+            );
+        }
+
+        // requests from before the buffer was filled, e.g. after a restart, are ignored
+        let new_request = context.image_dump_request.filter(|request| {
+            self.last_handled_request
+                .map_or(true, |last_handled| request.time > last_handled)
+                && elapsed_since(now, request.time) < parameters.duration
+        });
+        if let Some(request) = new_request {
+            self.last_handled_request = Some(request.time);
+            let directory_name = format!(
+                "{}_{:?}",
+                milliseconds_since_epoch(request.time),
+                request.reason
+            );
+            send(
+                worker,
+                Job::Dump {
+                    directory_name,
+                    parameters: parameters.clone(),
+                },
+            );
+        }
+
+        Ok(MainOutputs::default())
+    }
+}
+
+fn send(worker: &SyncSender<Job>, job: Job) {
+    match worker.try_send(job) {
+        Ok(()) | Err(TrySendError::Full(Job::Frame { .. })) => {}
+        Err(TrySendError::Full(_)) => warn!("image ring buffer worker is busy, dropping dump"),
+        Err(TrySendError::Disconnected(_)) => warn!("image ring buffer worker stopped"),
+    }
+}
+
+fn work(receiver: Receiver<Job>) {
+    let mut frames = VecDeque::new();
+    for job in receiver {
+        match job {
+            Job::Frame {
+                time,
+                image,
+                parameters,
+            } => {
+                match compress(
+                    &image,
+                    parameters.downsampling_factor,
+                    parameters.jpeg_quality,
+                ) {
+                    Ok(jpeg) => frames.push_back(CompressedFrame { time, jpeg }),
+                    Err(error) => warn!("failed to compress image for ring buffer: {error:#}"),
+                }
+                while frames
+                    .front()
+                    .is_some_and(|frame| elapsed_since(time, frame.time) > parameters.duration)
+                {
+                    frames.pop_front();
+                }
+            }
+            Job::Dump {
+                directory_name,
+                parameters,
+            } => {
+                let directory = parameters.dump_directory.join(directory_name);
+                if let Err(error) = dump_frames(&frames, &directory) {
+                    warn!("failed to dump image ring buffer: {error:#}");
+                }
+                if let Err(error) = remove_old_dumps(
+                    &parameters.dump_directory,
+                    parameters.maximum_number_of_dumps,
+                ) {
+                    warn!("failed to remove old image dumps: {error:#}");
+                }
+            }
+            Job::Clear => frames.clear(),
+        }
+    }
+}
+
+fn compress(image: &YCbCr422Image, downsampling_factor: u32, quality: u8) -> Result<Vec<u8>> {
+    let downsampling_factor = downsampling_factor.max(1);
+    let rgb_image = RgbImage::from_fn(
+        image.width() / downsampling_factor,
+        image.height() / downsampling_factor,
+        |x, y| {
+            let color: Rgb = image
+                .at(x * downsampling_factor, y * downsampling_factor)
+                .into();
+            image::Rgb([color.r, color.g, color.b])
+        },
+    );
+    let mut jpeg = Vec::new();
+    JpegEncoder::new_with_quality(&mut jpeg, quality)
+        .encode_image(&rgb_image)
+        .wrap_err("failed to encode JPEG")?;
+    Ok(jpeg)
+}
+
+fn dump_frames(frames: &VecDeque<CompressedFrame>, directory: &Path) -> Result<()> {
+    create_dir_all(directory)
+        .wrap_err_with(|| format!("failed to create {}", directory.display()))?;
+    for frame in frames {
+        let path = directory.join(format!("{}.jpg", milliseconds_since_epoch(frame.time)));
+        write(&path, &frame.jpeg)
+            .wrap_err_with(|| format!("failed to write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Dump directories start with their time, the oldest ones come first in name order
+fn remove_old_dumps(dump_directory: &Path, maximum_number_of_dumps: usize) -> Result<()> {
+    let mut dumps = read_dir(dump_directory)
+        .wrap_err_with(|| format!("failed to read {}", dump_directory.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        .map(|entry| entry.path())
+        .collect::<Vec<PathBuf>>();
+    dumps.sort();
+    let number_of_old_dumps = dumps.len().saturating_sub(maximum_number_of_dumps);
+    for dump in &dumps[..number_of_old_dumps] {
+        remove_dir_all(dump).wrap_err_with(|| format!("failed to remove {}", dump.display()))?;
+    }
+    Ok(())
+}
+
+fn elapsed_since(now: SystemTime, time: SystemTime) -> Duration {
+    now.duration_since(time).unwrap_or_default()
+}
+
+fn milliseconds_since_epoch(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+}
@@ -1,7 +1,6 @@
 use std::time::{Duration, Instant};
 
 use color_eyre::Result;
-use projection::{camera_matrix::CameraMatrix, horizon::Horizon};
 use serde::{Deserialize, Serialize};
 
 use context_attribute::context;
@@ -11,6 +10,7 @@ use linear_algebra::{point, Isometry2, Transform};
 use types::{
     color::{Intensity, Rgb, RgbChannel, YCbCr444},
     field_color::FieldColor,
+    horizon_crop::HorizonCrop,
     image_segments::{EdgeType, ImageSegments, ScanGrid, ScanLine, Segment},
     interpolated::Interpolated,
     limb::{is_above_limbs, Limb, ProjectedLimbs},
@@ -32,13 +32,13 @@ pub struct CycleContext {
 
     image: Input<YCbCr422Image, "image">,
 
-    camera_matrix: Input<Option<CameraMatrix>, "camera_matrix?">,
     ground_to_field_of_home_after_coin_toss_before_second_half: Input<
         Option<Isometry2<Ground, Field>>,
         "Control",
         "ground_to_field_of_home_after_coin_toss_before_second_half?",
     >,
     field_color: Input<FieldColor, "field_color">,
+    horizon_crop: Input<HorizonCrop, "horizon_crop">,
     projected_limbs: Input<Option<ProjectedLimbs>, "projected_limbs?">,
 
     horizontal_stride: Parameter<usize, "image_segmenter.$cycler_instance.horizontal_stride">,
@@ -81,14 +81,9 @@ impl ImageSegmenter {
                 projected_limbs.limbs.as_slice()
             });
 
-        let horizon = context
-            .camera_matrix
-            .and_then(|camera_matrix| camera_matrix.horizon)
-            .unwrap_or(Horizon::ABOVE_IMAGE);
-
         let scan_grid = new_grid(
             context.image,
-            &horizon,
+            context.horizon_crop,
             context.field_color,
             *context.horizontal_stride,
             *context.vertical_stride,
@@ -113,7 +108,7 @@ impl ImageSegmenter {
 #[allow(clippy::too_many_arguments)]
 fn new_grid(
     image: &YCbCr422Image,
-    horizon: &Horizon,
+    horizon_crop: &HorizonCrop,
     field_color: &FieldColor,
     horizontal_stride: usize,
     vertical_stride: usize,
@@ -122,10 +117,6 @@ fn new_grid(
     vertical_median_mode: MedianModeParameters,
     projected_limbs: &[Limb],
 ) -> ScanGrid {
-    let horizon_y_minimum = horizon
-        .horizon_y_minimum()
-        .clamp(0.0, image.height() as f32);
-
     ScanGrid {
         vertical_scan_lines: (2..image.width() - 2)
             .step_by(horizontal_stride)
@@ -138,7 +129,7 @@ fn new_grid(
                     vertical_edge_detection_source,
                     vertical_edge_threshold,
                     vertical_median_mode,
-                    horizon_y_minimum,
+                    horizon_crop.y_at_x(x as f32),
                     projected_limbs,
                 )
             })
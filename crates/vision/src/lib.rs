@@ -3,7 +3,9 @@ pub mod camera_matrix_extractor;
 pub mod feet_detection;
 pub mod field_border_detection;
 pub mod field_color_detection;
+pub mod horizon_crop_provider;
 pub mod image_receiver;
+pub mod image_ring_buffer;
 pub mod image_segmenter;
 pub mod limb_projector;
 pub mod line_detection;
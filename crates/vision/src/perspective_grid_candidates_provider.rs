@@ -11,6 +11,7 @@ use linear_algebra::{point, vector, Point2, Vector2};
 use projection::{camera_matrix::CameraMatrix, Projection};
 use types::{
     filtered_segments::FilteredSegments,
+    horizon_crop::HorizonCrop,
     image_segments::{ScanLine, Segment},
     line_data::LineData,
     perspective_grid_candidates::{PerspectiveGridCandidates, Row},
@@ -27,6 +28,7 @@ pub struct CreationContext {}
 pub struct CycleContext {
     camera_matrix: RequiredInput<Option<CameraMatrix>, "camera_matrix?">,
     filtered_segments: Input<FilteredSegments, "filtered_segments">,
+    horizon_crop: Input<HorizonCrop, "horizon_crop">,
     line_data: RequiredInput<Option<LineData>, "line_data?">,
     image: Input<YCbCr422Image, "image">,
 
@@ -60,11 +62,18 @@ impl PerspectiveGridCandidatesProvider {
             *context.ball_radius,
         );
 
-        let candidates = generate_candidates(
+        let mut candidates = generate_candidates(
             vertical_scanlines,
             skip_segments,
             &perspective_grid_ball_sizes,
         );
+        candidates.candidates.retain(|candidate| {
+            let lowest_point = point![
+                candidate.center.x(),
+                candidate.center.y() + candidate.radius
+            ];
+            !context.horizon_crop.is_above(lowest_point)
+        });
         context
             .perspective_grid_ball_sizes
             .fill_if_subscribed(|| perspective_grid_ball_sizes);
@@ -16,17 +16,26 @@ fn main() -> Result<()> {
                 instances: vec![""],
                 setup_nodes: vec!["control::fake_data"],
                 nodes: vec![
+                    "control::action_latency_monitor",
                     "control::active_vision",
+                    "control::ball_possession_estimator",
+                    "control::ball_search",
                     "control::ball_state_composer",
                     "control::behavior::node",
+                    "control::field_coverage_mapper",
                     "control::game_controller_state_filter",
+                    "control::goal_mouth_coordinator",
                     "control::kick_selector",
                     "control::motion::look_around",
+                    "control::pass_target_selector",
                     "control::role_assignment",
                     "control::rule_obstacle_composer",
+                    "control::shot_selector",
+                    "control::teammate_striker_filter",
                     "control::time_to_reach_kick_position",
                     "control::world_state_composer",
                 ],
+                persisted_nodes: vec![],
             },
             CyclerManifest {
                 name: "SplNetwork",
@@ -34,6 +43,7 @@ fn main() -> Result<()> {
                 instances: vec![""],
                 setup_nodes: vec!["spl_network::message_receiver"],
                 nodes: vec![],
+                persisted_nodes: vec![],
             },
         ],
     };
@@ -2,13 +2,21 @@ use std::{collections::BTreeMap, sync::Arc, time::SystemTime};
 
 use color_eyre::{eyre::WrapErr, Result};
 use control::{
+    action_latency_monitor::{self, ActionLatencyMonitor},
     active_vision::{self, ActiveVision},
+    ball_possession_estimator::{self, BallPossessionEstimator},
+    ball_search::{self, BallSearch},
     ball_state_composer::{self, BallStateComposer},
     behavior::node::{self, Behavior},
+    field_coverage_mapper::{self, FieldCoverageMapper},
+    goal_mouth_coordinator::{self, GoalMouthCoordinator},
     kick_selector::{self, KickSelector},
     motion::look_around::LookAround,
+    pass_target_selector::{self, PassTargetSelector},
     role_assignment::{self, RoleAssignment},
     rule_obstacle_composer::RuleObstacleComposer,
+    shot_selector::{self, ShotSelector},
+    teammate_striker_filter::{self, TeammateStrikerFilter},
     time_to_reach_kick_position::{self, TimeToReachKickPosition},
     world_state_composer::{self, WorldStateComposer},
 };
@@ -36,13 +44,21 @@ pub struct Database {
 pub struct BehaviorCycler {
     hardware_interface: Arc<Interfake>,
     own_changed: Arc<Notify>,
+    action_latency_monitor: ActionLatencyMonitor,
     active_vision: ActiveVision,
+    ball_possession_estimator: BallPossessionEstimator,
+    ball_search: BallSearch,
     ball_state_composer: BallStateComposer,
     behavior: Behavior,
+    field_coverage_mapper: FieldCoverageMapper,
+    goal_mouth_coordinator: GoalMouthCoordinator,
     kick_selector: KickSelector,
     look_around: LookAround,
+    pass_target_selector: PassTargetSelector,
     role_assignment: RoleAssignment,
     rule_obstacle_composer: RuleObstacleComposer,
+    shot_selector: ShotSelector,
+    teammate_striker_filter: TeammateStrikerFilter,
     world_state_composer: WorldStateComposer,
     time_to_reach_kick_position: TimeToReachKickPosition,
 }
@@ -56,26 +72,53 @@ impl BehaviorCycler {
         let time_to_reach_kick_position =
             TimeToReachKickPosition::new(time_to_reach_kick_position::CreationContext {})
                 .wrap_err("failed to create node `TimeToReachKickPosition`")?;
+        let action_latency_monitor = ActionLatencyMonitor::new(
+            action_latency_monitor::CreationContext::new(&parameters.action_latency),
+        )
+        .wrap_err("failed to create node `ActionLatencyMonitor`")?;
         let active_vision = ActiveVision::new(active_vision::CreationContext::new(
             &parameters.field_dimensions,
         ))
         .wrap_err("failed to create node `ActiveVision`")?;
+        let ball_possession_estimator =
+            BallPossessionEstimator::new(ball_possession_estimator::CreationContext {})
+                .wrap_err("failed to create node `BallPossessionEstimator`")?;
+        let ball_search =
+            BallSearch::new(ball_search::CreationContext::new(&parameters.ball_search))
+                .wrap_err("failed to create node `BallSearch`")?;
         let ball_state_composer = BallStateComposer::new(ball_state_composer::CreationContext {})
             .wrap_err("failed to create node `BallStateComposer`")?;
-        let behavior = Behavior::new(node::CreationContext::new())
-            .wrap_err("failed to create node `Behavior`")?;
+        let behavior = Behavior::new(node::CreationContext::new(
+            &parameters.behavior.action_priority,
+        ))
+        .wrap_err("failed to create node `Behavior`")?;
+        let field_coverage_mapper = FieldCoverageMapper::new(
+            field_coverage_mapper::CreationContext::new(&parameters.field_coverage),
+        )
+        .wrap_err("failed to create node `FieldCoverageMapper`")?;
+        let goal_mouth_coordinator =
+            GoalMouthCoordinator::new(goal_mouth_coordinator::CreationContext {})
+                .wrap_err("failed to create node `GoalMouthCoordinator`")?;
         let kick_selector = KickSelector::new(kick_selector::CreationContext {})
             .wrap_err("failed to create node `KickSelector`")?;
         let look_around = control::motion::look_around::LookAround::new(
             control::motion::look_around::CreationContext::new(),
         )
         .wrap_err("failed to create node `LookAround`")?;
+        let pass_target_selector =
+            PassTargetSelector::new(pass_target_selector::CreationContext {})
+                .wrap_err("failed to create node `PassTargetSelector`")?;
         let role_assignment = RoleAssignment::new(role_assignment::CreationContext::new())
             .wrap_err("failed to create node `RoleAssignment`")?;
         let rule_obstacle_composer = control::rule_obstacle_composer::RuleObstacleComposer::new(
             control::rule_obstacle_composer::CreationContext {},
         )
         .wrap_err("failed to create node `RuleObstacleComposer`")?;
+        let shot_selector = ShotSelector::new(shot_selector::CreationContext {})
+            .wrap_err("failed to create node `ShotSelector`")?;
+        let teammate_striker_filter =
+            TeammateStrikerFilter::new(teammate_striker_filter::CreationContext {})
+                .wrap_err("failed to create node `TeammateStrikerFilter`")?;
         let world_state_composer =
             WorldStateComposer::new(world_state_composer::CreationContext::new())
                 .wrap_err("failed to create node `WorldStateComposer`")?;
@@ -83,14 +126,22 @@ impl BehaviorCycler {
         Ok(Self {
             hardware_interface,
             own_changed,
+            action_latency_monitor,
             active_vision,
             time_to_reach_kick_position,
+            ball_possession_estimator,
+            ball_search,
             ball_state_composer,
             behavior,
+            field_coverage_mapper,
+            goal_mouth_coordinator,
             kick_selector,
             look_around,
+            pass_target_selector,
             role_assignment,
             rule_obstacle_composer,
+            shot_selector,
+            teammate_striker_filter,
             world_state_composer,
         })
     }
@@ -119,8 +170,8 @@ impl BehaviorCycler {
                         own_database.main_outputs.ball_state.as_ref(),
                         &parameters.rule_obstacles.center_circle_obstacle_increase,
                         &parameters.field_dimensions,
-                        &parameters.rule_obstacles.free_kick_obstacle_radius,
                         &parameters.rule_obstacles.penaltykick_box_extension,
+                        &parameters.rule_set,
                     ))
                     .wrap_err("failed to execute cycle of node `RuleObstacleComposer`")?
             };
@@ -141,11 +192,24 @@ impl BehaviorCycler {
                     &own_database.main_outputs.primary_state,
                     own_database.main_outputs.ground_to_field.as_ref(),
                     &own_database.main_outputs.cycle_time,
+                    &own_database.main_outputs.heard_acoustic_signals,
+                    own_database.main_outputs.message_budget_status.as_ref(),
+                    own_database
+                        .main_outputs
+                        .game_controller_state_digest
+                        .as_ref(),
+                    &own_database.main_outputs.robot_condition,
+                    &own_database.main_outputs.filtered_ready_signal,
                     PerceptionInput {
-                        persistent: incoming_messages,
+                        persistent: incoming_messages.clone(),
                         temporary: Default::default(),
                     },
                     &mut cycler_state.time_to_reach_kick_position,
+                    &mut cycler_state.search_sector,
+                    &mut cycler_state.pass_announcement,
+                    &mut cycler_state.yields_blocked_ball,
+                    &mut cycler_state.goal_mouth_claim,
+                    &mut cycler_state.striker_intent,
                     &parameters.field_dimensions,
                     parameters.role_assignment.forced_role.as_ref(),
                     &parameters
@@ -155,6 +219,16 @@ impl BehaviorCycler {
                     &parameters.behavior.optional_roles,
                     &parameters.player_number,
                     &parameters.spl_network,
+                    &parameters.role_assignment.striker_claim_hysteresis,
+                    &parameters.role_assignment.release_striker_when_fallen,
+                    &parameters
+                        .role_assignment
+                        .fallen_time_to_reach_kick_position,
+                    &parameters.role_assignment.teammate_pose_timeout,
+                    &parameters.role_assignment.ready_role_assignment,
+                    &parameters.role_assignment.reduced_formations,
+                    &parameters.robot_capabilities,
+                    &parameters.ready_signal_filter,
                     &self.hardware_interface,
                 ))
                 .wrap_err("failed to execute cycle of node `RoleAssignment`")?;
@@ -162,6 +236,68 @@ impl BehaviorCycler {
             own_database.main_outputs.network_robot_obstacles =
                 main_outputs.network_robot_obstacles.value;
             own_database.main_outputs.role = main_outputs.role.value;
+            own_database.main_outputs.teammate_positions = main_outputs.teammate_positions.value;
+            own_database.main_outputs.teammate_defender_positions =
+                main_outputs.teammate_defender_positions.value;
+            own_database.main_outputs.keeper_incapable_players =
+                main_outputs.keeper_incapable_players.value;
+            own_database.main_outputs.unresponsive_players =
+                main_outputs.unresponsive_players.value;
+            own_database.main_outputs.announced_roles = main_outputs.announced_roles.value;
+            own_database
+                .main_outputs
+                .announced_times_to_reach_kick_position =
+                main_outputs.announced_times_to_reach_kick_position.value;
+        }
+        {
+            let main_outputs = self
+                .field_coverage_mapper
+                .cycle(field_coverage_mapper::CycleContext::new(
+                    own_database.main_outputs.ground_to_field.as_ref(),
+                    &own_database.main_outputs.cycle_time,
+                    &own_database.main_outputs.has_ground_contact,
+                    &own_database.main_outputs.sensor_data,
+                    PerceptionInput {
+                        persistent: incoming_messages.clone(),
+                        temporary: Default::default(),
+                    },
+                    &parameters.field_dimensions,
+                    &parameters.field_coverage,
+                    &parameters.player_number,
+                ))
+                .wrap_err("failed to execute cycle of node `FieldCoverageMapper`")?;
+            own_database.main_outputs.field_coverage = main_outputs.field_coverage.value;
+        }
+        {
+            let main_outputs = self
+                .ball_search
+                .cycle(ball_search::CycleContext::new(
+                    own_database.main_outputs.ball_position.as_ref(),
+                    own_database.main_outputs.team_ball.as_ref(),
+                    own_database.main_outputs.ground_to_field.as_ref(),
+                    &own_database.main_outputs.cycle_time,
+                    &own_database.main_outputs.field_coverage,
+                    &own_database.main_outputs.primary_state,
+                    &own_database.main_outputs.role,
+                    &own_database.main_outputs.heard_acoustic_signals,
+                    PerceptionInput {
+                        persistent: incoming_messages.clone(),
+                        temporary: Default::default(),
+                    },
+                    &parameters.field_dimensions,
+                    &parameters.ball_search,
+                    &parameters.behavior.search,
+                    &parameters.player_number,
+                    &mut cycler_state.search_sector,
+                    AdditionalOutput::new(
+                        true,
+                        &mut own_database.additional_outputs.search_sector_claims,
+                    ),
+                ))
+                .wrap_err("failed to execute cycle of node `BallSearch`")?;
+            own_database.main_outputs.ball_search_heatmap = main_outputs.ball_search_heatmap.value;
+            own_database.main_outputs.search_sector_position =
+                main_outputs.search_sector_position.value;
         }
         {
             let main_outputs = self
@@ -178,9 +314,12 @@ impl BehaviorCycler {
                         .filtered_game_controller_state
                         .as_ref(),
                     &parameters.field_dimensions,
+                    &parameters.ball_filter.resting_ball_velocity_threshold,
+                    &parameters.ball_filter.velocity_decay_factor,
                 ))
                 .wrap_err("failed to execute cycle of node `BallStateComposer`")?;
             own_database.main_outputs.ball_state = main_outputs.ball_state.value;
+            own_database.main_outputs.ball_motion = main_outputs.ball_motion.value;
             own_database.main_outputs.rule_ball_state = main_outputs.rule_ball_state.value;
         }
 
@@ -194,11 +333,121 @@ impl BehaviorCycler {
                     &own_database.main_outputs.obstacles,
                     &parameters.behavior.look_action,
                     own_database.main_outputs.ground_to_field.as_ref(),
+                    own_database.main_outputs.localization_uncertainty.as_ref(),
+                    AdditionalOutput::new(true, &mut own_database.additional_outputs.look_target),
                 ))
                 .wrap_err("failed to execute cycle of node `ActiveVision`")?;
             own_database.main_outputs.position_of_interest =
                 main_outputs.position_of_interest.value;
         }
+        {
+            if own_database.main_outputs.ground_to_field.as_ref().is_some()
+                && own_database.main_outputs.ball_state.as_ref().is_some()
+            {
+                let main_outputs = {
+                    self.shot_selector
+                        .cycle(shot_selector::CycleContext::new(
+                            own_database.main_outputs.ground_to_field.as_ref().unwrap(),
+                            own_database.main_outputs.ball_state.as_ref().unwrap(),
+                            &own_database.main_outputs.obstacles,
+                            &own_database.main_outputs.network_robot_obstacles,
+                            own_database
+                                .main_outputs
+                                .filtered_game_controller_state
+                                .as_ref(),
+                            &parameters.field_dimensions,
+                            &parameters.behavior.shot_selection,
+                            &parameters.behavior.endgame,
+                            &parameters.obstacle_filter.teammate_matching_distance,
+                            &mut cycler_state.striker_intent,
+                        ))
+                        .wrap_err("failed to execute cycle of node `ShotSelector`")?
+                };
+                own_database.main_outputs.shot_selection = main_outputs.shot_selection.value;
+            } else {
+                own_database.main_outputs.shot_selection = Default::default();
+            }
+        }
+        {
+            let main_outputs = self
+                .pass_target_selector
+                .cycle(pass_target_selector::CycleContext::new(
+                    &own_database.main_outputs.announced_roles,
+                    &own_database
+                        .main_outputs
+                        .announced_times_to_reach_kick_position,
+                    own_database.main_outputs.ball_state.as_ref(),
+                    &own_database.main_outputs.cycle_time,
+                    own_database
+                        .main_outputs
+                        .filtered_game_controller_state
+                        .as_ref(),
+                    own_database.main_outputs.ground_to_field.as_ref(),
+                    &own_database.main_outputs.keeper_incapable_players,
+                    &own_database.main_outputs.obstacles,
+                    &own_database.main_outputs.role,
+                    own_database.main_outputs.shot_selection.as_ref(),
+                    &own_database.main_outputs.teammate_positions,
+                    &own_database.main_outputs.unresponsive_players,
+                    PerceptionInput {
+                        persistent: incoming_messages.clone(),
+                        temporary: Default::default(),
+                    },
+                    &parameters.behavior.optional_roles,
+                    &parameters.role_assignment.reduced_formations,
+                    &parameters.role_assignment.striker_claim_hysteresis,
+                    &parameters.behavior.passing,
+                    &parameters.behavior.endgame,
+                    &parameters.obstacle_filter.teammate_matching_distance,
+                    &parameters.player_number,
+                    &mut cycler_state.pass_announcement,
+                ))
+                .wrap_err("failed to execute cycle of node `PassTargetSelector`")?;
+            own_database.main_outputs.pass_intent = main_outputs.pass_intent.value;
+            own_database.main_outputs.announced_pass = main_outputs.announced_pass.value;
+        }
+        {
+            let main_outputs = self
+                .goal_mouth_coordinator
+                .cycle(goal_mouth_coordinator::CycleContext::new(
+                    own_database.main_outputs.ball_state.as_ref(),
+                    &own_database.main_outputs.cycle_time,
+                    own_database
+                        .main_outputs
+                        .filtered_game_controller_state
+                        .as_ref(),
+                    &own_database.main_outputs.role,
+                    PerceptionInput {
+                        persistent: incoming_messages.clone(),
+                        temporary: Default::default(),
+                    },
+                    &parameters.field_dimensions,
+                    &parameters.behavior.goal_mouth,
+                    &parameters.player_number,
+                    &mut cycler_state.goal_mouth_claim,
+                ))
+                .wrap_err("failed to execute cycle of node `GoalMouthCoordinator`")?;
+            own_database.main_outputs.own_goal_mouth_claim =
+                main_outputs.own_goal_mouth_claim.value;
+            own_database.main_outputs.teammate_goal_mouth_claim =
+                main_outputs.teammate_goal_mouth_claim.value;
+        }
+        {
+            let main_outputs = self
+                .teammate_striker_filter
+                .cycle(teammate_striker_filter::CycleContext::new(
+                    &own_database.main_outputs.cycle_time,
+                    PerceptionInput {
+                        persistent: incoming_messages,
+                        temporary: Default::default(),
+                    },
+                    &parameters.behavior.teammate_striker,
+                    &parameters.player_number,
+                    &mut cycler_state.time_to_reach_kick_position,
+                ))
+                .wrap_err("failed to execute cycle of node `TeammateStrikerFilter`")?;
+            own_database.main_outputs.teammate_striker = main_outputs.teammate_striker.value;
+        }
         {
             if own_database.main_outputs.ground_to_field.as_ref().is_some()
                 && own_database.main_outputs.ball_state.as_ref().is_some()
@@ -209,7 +458,17 @@ impl BehaviorCycler {
                             own_database.main_outputs.ground_to_field.as_ref().unwrap(),
                             own_database.main_outputs.ball_state.as_ref().unwrap(),
                             &own_database.main_outputs.obstacles,
+                            &own_database.main_outputs.teammate_positions,
+                            own_database.main_outputs.shot_selection.as_ref(),
+                            own_database.main_outputs.pass_intent.as_ref(),
+                            own_database.main_outputs.own_goal_mouth_claim.as_ref(),
+                            own_database
+                                .main_outputs
+                                .filtered_game_controller_state
+                                .as_ref(),
+                            &own_database.main_outputs.role,
                             &parameters.field_dimensions,
+                            &parameters.obstacle_filter.teammate_matching_distance,
                             &parameters.in_walk_kicks,
                             &parameters.kick_selector.angle_distance_weight,
                             &parameters.kick_selector.max_kick_around_obstacle_angle,
@@ -222,10 +481,19 @@ impl BehaviorCycler {
                             &parameters.kick_selector.goal_accuracy_margin,
                             &parameters.kick_selector.default_kick_strength,
                             &parameters.kick_selector.corner_kick_strength,
+                            &parameters.behavior.shot_selection.dribble_kick_strength,
+                            &parameters.behavior.passing.kick_strength,
+                            &parameters.behavior.free_kick.goal_kick_target,
+                            &parameters.behavior.free_kick.kick_in_target,
+                            &parameters.behavior.keeper_clearance,
                             framework::AdditionalOutput::new(
                                 true,
                                 &mut own_database.additional_outputs.kick_targets,
                             ),
+                            framework::AdditionalOutput::new(
+                                true,
+                                &mut own_database.additional_outputs.evaluated_kick_targets,
+                            ),
                             framework::AdditionalOutput::new(
                                 true,
                                 &mut own_database.additional_outputs.instant_kick_targets,
@@ -241,11 +509,31 @@ impl BehaviorCycler {
                 own_database.main_outputs.instant_kick_decisions = Default::default();
             }
         }
+        {
+            let main_outputs = self
+                .ball_possession_estimator
+                .cycle(ball_possession_estimator::CycleContext::new(
+                    own_database.main_outputs.ball_state.as_ref(),
+                    &own_database.main_outputs.cycle_time,
+                    own_database
+                        .main_outputs
+                        .filtered_game_controller_state
+                        .as_ref(),
+                    own_database.main_outputs.ground_to_field.as_ref(),
+                    &own_database.main_outputs.obstacles,
+                    &own_database.main_outputs.teammate_positions,
+                    &parameters.ball_possession,
+                    &parameters.obstacle_filter.teammate_matching_distance,
+                ))
+                .wrap_err("failed to execute cycle of node `BallPossessionEstimator`")?;
+            own_database.main_outputs.ball_possession = main_outputs.ball_possession.value;
+        }
         {
             let main_outputs = self
                 .world_state_composer
                 .cycle(world_state_composer::CycleContext::new(
                     own_database.main_outputs.ball_state.as_ref(),
+                    own_database.main_outputs.ball_motion.as_ref(),
                     own_database.main_outputs.rule_ball_state.as_ref(),
                     own_database
                         .main_outputs
@@ -254,14 +542,26 @@ impl BehaviorCycler {
                     own_database.main_outputs.ground_to_field.as_ref(),
                     own_database.main_outputs.kick_decisions.as_ref(),
                     own_database.main_outputs.instant_kick_decisions.as_ref(),
+                    own_database.main_outputs.search_sector_position.as_ref(),
+                    own_database.main_outputs.pass_intent.as_ref(),
+                    own_database.main_outputs.announced_pass.as_ref(),
+                    own_database.main_outputs.teammate_goal_mouth_claim.as_ref(),
+                    own_database.main_outputs.teammate_striker.as_ref(),
                     &parameters.player_number,
+                    &parameters.obstacle_filter.teammate_matching_distance,
                     &own_database.main_outputs.fall_state,
                     &own_database.main_outputs.has_ground_contact,
                     &own_database.main_outputs.obstacles,
                     &own_database.main_outputs.rule_obstacles,
+                    &own_database.main_outputs.teammate_positions,
+                    &own_database.main_outputs.teammate_defender_positions,
                     &own_database.main_outputs.primary_state,
                     &own_database.main_outputs.role,
                     &own_database.main_outputs.position_of_interest,
+                    &own_database.main_outputs.field_coverage,
+                    &own_database.main_outputs.ball_possession,
+                    &own_database.main_outputs.robot_condition,
+                    &own_database.main_outputs.is_converging_after_reentry,
                 ))
                 .wrap_err("failed to execute cycle of node `WorldStateComposer`")?;
             own_database.main_outputs.world_state = main_outputs.world_state.value;
@@ -279,9 +579,24 @@ impl BehaviorCycler {
                         &mut own_database.additional_outputs.dribble_path_obstacles,
                     ),
                     AdditionalOutput::new(true, &mut own_database.additional_outputs.active_action),
+                    AdditionalOutput::new(
+                        true,
+                        &mut own_database.additional_outputs.action_evaluations,
+                    ),
+                    AdditionalOutput::new(
+                        true,
+                        &mut own_database.additional_outputs.action_resolution_error,
+                    ),
+                    AdditionalOutput::new(true, &mut own_database.additional_outputs.walk_in_lane),
+                    AdditionalOutput::new(
+                        true,
+                        &mut own_database.additional_outputs.predicted_goal_line_crossing,
+                    ),
                     &true,
+                    &false,
                     &own_database.main_outputs.world_state,
                     &own_database.main_outputs.cycle_time,
+                    &mut cycler_state.yields_blocked_ball,
                     &parameters.behavior,
                     &parameters.in_walk_kicks,
                     &parameters.field_dimensions,
@@ -289,11 +604,37 @@ impl BehaviorCycler {
                     &parameters.behavior.intercept_ball,
                     &parameters.step_planner.max_step_size,
                     &parameters.behavior.role_positions.striker_set_position,
+                    &parameters.rule_set,
+                    &parameters.robot_capabilities,
+                    &parameters.kick_selector.kick_pose_obstacle_radius,
+                    &parameters.kick_selector.angle_distance_weight,
                 ))
                 .wrap_err("failed to execute cycle of node `Behavior`")?;
             own_database.main_outputs.motion_command = main_outputs.motion_command.value;
             own_database.main_outputs.dribble_path = main_outputs.dribble_path.value;
         }
+        {
+            self.action_latency_monitor
+                .cycle(action_latency_monitor::CycleContext::new(
+                    own_database.main_outputs.ball_position.as_ref(),
+                    &own_database.main_outputs.cycle_time,
+                    own_database
+                        .main_outputs
+                        .filtered_game_controller_state
+                        .as_ref(),
+                    &own_database.main_outputs.motion_command,
+                    &parameters.action_latency,
+                    AdditionalOutput::new(
+                        true,
+                        &mut own_database.additional_outputs.action_latencies,
+                    ),
+                    AdditionalOutput::new(
+                        true,
+                        &mut own_database.additional_outputs.action_latency_report,
+                    ),
+                ))
+                .wrap_err("failed to execute cycle of node `ActionLatencyMonitor`")?;
+        }
         {
             let main_outputs = {
                 self.look_around
@@ -327,6 +668,7 @@ impl BehaviorCycler {
                             .time_to_reach_kick_position_output,
                     ),
                     &mut cycler_state.time_to_reach_kick_position,
+                    &mut cycler_state.yields_blocked_ball,
                     &parameters.behavior,
                     own_database
                         .main_outputs
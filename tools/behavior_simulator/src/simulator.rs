@@ -185,19 +185,19 @@ impl Simulator {
                             .ground_to_field
                             .expect("simulated robots should always have a known pose");
 
-                        self.state
-                            .lock()
+                        let mut state = self.state.lock();
+                        let obstacles = &mut state
                             .robots
                             .get_mut(&player_number)
                             .unwrap()
                             .database
                             .main_outputs
-                            .obstacles
-                            .push(Obstacle::robot(
-                                ground_to_field.inverse() * position,
-                                radius,
-                                radius,
-                            ));
+                            .obstacles;
+                        // scripted obstacles do not move, their index serves as tracking identity
+                        obstacles.push(Obstacle {
+                            id: Some(obstacles.len()),
+                            ..Obstacle::robot(ground_to_field.inverse() * position, radius, radius)
+                        });
 
                         Ok(())
                     },
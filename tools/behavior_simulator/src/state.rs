@@ -12,7 +12,7 @@ use coordinate_systems::{Field, Head};
 use geometry::line_segment::LineSegment;
 use linear_algebra::{vector, Isometry2, Orientation2, Point2, Rotation2, Vector2};
 use serialize_hierarchy::SerializeHierarchy;
-use spl_network_messages::{GamePhase, HulkMessage, PlayerNumber, Team};
+use spl_network_messages::{GamePhase, Half, HulkMessage, PlayerNumber, Team};
 use types::{
     ball_position::BallPosition,
     filtered_game_controller_state::FilteredGameControllerState,
@@ -128,6 +128,7 @@ impl State {
                     kick,
                     kicking_side,
                     strength,
+                    ..
                 } => {
                     if let Some(ball) = self.ball.as_mut() {
                         let side = match kicking_side {
@@ -236,6 +237,7 @@ impl State {
             ) {
                 (true, _) => PrimaryState::Penalized,
                 (false, FilteredGameState::Initial) => PrimaryState::Initial,
+                (false, FilteredGameState::Standby) => PrimaryState::Standby,
                 (false, FilteredGameState::Ready { .. }) => PrimaryState::Ready,
                 (false, FilteredGameState::Set) => PrimaryState::Set,
                 (false, FilteredGameState::Playing { .. }) => PrimaryState::Playing,
@@ -322,9 +324,16 @@ impl Default for State {
                 six: None,
                 seven: None,
             },
+            remaining_penalty_durations: Players::default(),
             remaining_number_of_messages: 1200,
             sub_state: None,
             own_team_is_home_after_coin_toss: false,
+            half: Half::First,
+            remaining_time_in_half: Duration::from_secs(600),
+            own_score: 0,
+            opponent_score: 0,
+            staleness: Duration::ZERO,
+            is_fallback: false,
         };
 
         Self {
@@ -1,12 +1,8 @@
-use std::str::FromStr;
-
 use clap::Parser;
-use color_eyre::{eyre::bail, Result};
-use communication::{
-    client::{Communication, CyclerOutput, SubscriberMessage},
-    messages::Format,
-};
-use log::{error, info};
+use color_eyre::Result;
+use hulk_client::Client;
+use log::error;
+use serde_json::Value;
 
 use crate::logging::setup_logger;
 
@@ -25,20 +21,15 @@ async fn main() -> Result<()> {
     setup_logger()?;
 
     let arguments = CommandlineArguments::parse();
-    let output_to_subscribe = CyclerOutput::from_str(&arguments.path)?;
-    let communication = Communication::new(Some(format!("ws://{}:1337", arguments.address)), true);
-    let (_uuid, mut receiver) = communication
-        .subscribe_output(output_to_subscribe, Format::Textual)
-        .await;
-    while let Some(message) = receiver.recv().await {
-        match message {
-            SubscriberMessage::Update { value } => println!("{value:#}"),
-            SubscriberMessage::SubscriptionSuccess => info!("Successfully subscribed"),
-            SubscriberMessage::SubscriptionFailure { info } => {
-                error!("Failed to subscribe: {info:?}");
+    let client = Client::new(Some(&arguments.address), true);
+    let mut subscription = client.subscribe::<Value>(&arguments.path).await?;
+    loop {
+        match subscription.next().await {
+            Ok(value) => println!("{value:#}"),
+            Err(error) => {
+                error!("{error:#}");
                 break;
             }
-            SubscriberMessage::UpdateBinary { .. } => bail!("Cannot print binary data"),
         }
     }
     Ok(())
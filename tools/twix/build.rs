@@ -0,0 +1,17 @@
+use std::{env::var, fs::File, path::PathBuf};
+
+use color_eyre::eyre::{Result, WrapErr};
+use hulk_manifest::collect_hulk_cyclers_in;
+use source_analyzer::graph::Graph;
+
+fn main() -> Result<()> {
+    let cyclers = collect_hulk_cyclers_in("../../crates")?;
+    for path in cyclers.watch_paths() {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+
+    let out_dir = PathBuf::from(var("OUT_DIR").wrap_err("failed to get OUT_DIR")?);
+    let file = File::create(out_dir.join("cycler_graph.json"))
+        .wrap_err("failed to create cycler graph file")?;
+    serde_json::to_writer(file, &Graph::from(&cyclers)).wrap_err("failed to write cycler graph")
+}
@@ -0,0 +1,93 @@
+use std::{fs::read_to_string, io::ErrorKind, path::PathBuf, time::Duration};
+
+use color_eyre::{
+    eyre::{bail, WrapErr},
+    Result,
+};
+use home::home_dir;
+use serde_json::Value;
+use toml::Table;
+
+const DEFAULT_CONFIGURATION: &str = include_str!("../default.toml");
+
+/// Settings and panel defaults from `default.toml`, merged with the user configuration.
+#[derive(Debug)]
+pub struct Configuration {
+    panels: Table,
+    history_duration: Duration,
+}
+
+impl Default for Configuration {
+    fn default() -> Self {
+        Self {
+            panels: Table::new(),
+            history_duration: Duration::from_secs(3),
+        }
+    }
+}
+
+impl Configuration {
+    pub fn load() -> Result<Self> {
+        let mut configuration: Table =
+            toml::from_str(DEFAULT_CONFIGURATION).wrap_err("failed to parse default.toml")?;
+        if let Some(path) = user_configuration_path() {
+            match read_to_string(&path) {
+                Ok(content) => {
+                    let user_configuration: Table = toml::from_str(&content)
+                        .wrap_err_with(|| format!("failed to parse {}", path.display()))?;
+                    merge(&mut configuration, user_configuration);
+                }
+                Err(error) if error.kind() == ErrorKind::NotFound => {}
+                Err(error) => {
+                    return Err(error)
+                        .wrap_err_with(|| format!("failed to read {}", path.display()))
+                }
+            }
+        }
+        let panels = match configuration.remove("panels") {
+            Some(toml::Value::Table(panels)) => panels,
+            _ => Table::new(),
+        };
+        let history_duration = match configuration.get("history_duration") {
+            Some(toml::Value::Float(seconds)) => Duration::try_from_secs_f64(*seconds)
+                .wrap_err("history_duration must be a non-negative number of seconds")?,
+            Some(toml::Value::Integer(seconds)) => Duration::from_secs(
+                u64::try_from(*seconds).wrap_err("history_duration must not be negative")?,
+            ),
+            Some(_) => bail!("history_duration must be a number of seconds"),
+            None => Self::default().history_duration,
+        };
+        Ok(Self {
+            panels,
+            history_duration,
+        })
+    }
+
+    /// How long received values are kept to be shown when the time cursor points into the past
+    pub fn history_duration(&self) -> Duration {
+        self.history_duration
+    }
+
+    /// Settings a panel of type `panel_name` is created with when there is no saved state.
+    pub fn panel_defaults(&self, panel_name: &str) -> Option<Value> {
+        let defaults = self.panels.get(panel_name)?;
+        serde_json::to_value(defaults).ok()
+    }
+}
+
+fn user_configuration_path() -> Option<PathBuf> {
+    home_dir().map(|home| home.join(".config/twix/twix.toml"))
+}
+
+fn merge(base: &mut Table, overrides: Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overrides)) => {
+                merge(base, overrides)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
@@ -1,3 +1,8 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, SystemTime},
+};
+
 use communication::client::{Communication, CyclerOutput, SubscriberMessage};
 use log::error;
 use tokio::{
@@ -8,9 +13,15 @@ use tokio::{
     },
 };
 
+use crate::time_cursor::TimeCursor;
+
+/// Images are large, only the most recent ones are kept for the time cursor
+const MAXIMUM_NUMBER_OF_BUFFERED_IMAGES: usize = 60;
+
 #[derive(Debug)]
 enum Message {
     GetLatest {
+        at: Option<SystemTime>,
         response_sender: oneshot::Sender<Result<Vec<u8>, String>>,
     },
     ListenToUpdates {
@@ -20,20 +31,27 @@ enum Message {
 
 pub struct ImageBuffer {
     sender: mpsc::Sender<Message>,
+    time_cursor: TimeCursor,
 }
 
 impl ImageBuffer {
-    pub fn new(communication: Communication, output: CyclerOutput) -> Self {
+    pub fn new(
+        communication: Communication,
+        output: CyclerOutput,
+        time_cursor: TimeCursor,
+    ) -> Self {
         let (command_sender, command_receiver) = mpsc::channel(10);
+        let history_duration = time_cursor.history_duration();
         spawn(async move {
             let (uuid, receiver) = communication
                 .subscribe_output(output.clone(), communication::messages::Format::Binary)
                 .await;
-            image_buffer(receiver, command_receiver).await;
+            image_buffer(receiver, command_receiver, history_duration).await;
             communication.unsubscribe_output(uuid).await;
         });
         Self {
             sender: command_sender,
+            time_cursor,
         }
     }
 
@@ -48,6 +66,7 @@ impl ImageBuffer {
         let (sender, receiver) = oneshot::channel();
         self.sender
             .blocking_send(Message::GetLatest {
+                at: self.time_cursor.get(),
                 response_sender: sender,
             })
             .unwrap();
@@ -58,8 +77,9 @@ impl ImageBuffer {
 async fn image_buffer(
     mut subscriber_receiver: mpsc::Receiver<SubscriberMessage>,
     mut command_receiver: mpsc::Receiver<Message>,
+    history_duration: Duration,
 ) {
-    let mut image_data: Option<Result<Vec<u8>, String>> = None;
+    let mut image_data: Option<Result<VecDeque<(SystemTime, Vec<u8>)>, String>> = None;
     let mut update_listeners: Vec<mpsc::Sender<()>> = Vec::new();
     loop {
         select! {
@@ -68,7 +88,7 @@ async fn image_buffer(
                     Some(message) => {
                         match message {
                             SubscriberMessage::UpdateBinary{data: new_data} => {
-                                image_data = Some(Ok(new_data));
+                                add_image(&mut image_data, new_data, history_duration);
                                 update_listeners.retain(|listener| {
                                     if let Err(TrySendError::Closed(_)) = listener.try_send(()) {
                                             return false;
@@ -92,9 +112,9 @@ async fn image_buffer(
             maybe_command = command_receiver.recv() => {
                 match maybe_command {
                     Some(command) => match command {
-                        Message::GetLatest{response_sender} => {
+                        Message::GetLatest{at, response_sender} => {
                             let response = match &image_data {
-                                Some(Ok(values)) => Ok(values.clone()),
+                                Some(Ok(images)) => Ok(image_at(images, at).clone()),
                                 Some(Err(error)) => Err(error.clone()),
                                 None => Err("No response yet".to_string()),
                             };
@@ -110,3 +130,38 @@ async fn image_buffer(
         }
     }
 }
+
+fn add_image(
+    image_data: &mut Option<Result<VecDeque<(SystemTime, Vec<u8>)>, String>>,
+    data: Vec<u8>,
+    history_duration: Duration,
+) {
+    let now = SystemTime::now();
+    match image_data {
+        Some(Ok(images)) => {
+            images.push_front((now, data));
+            let length = images
+                .iter()
+                .take(MAXIMUM_NUMBER_OF_BUFFERED_IMAGES)
+                .take_while(|(received_at, _)| {
+                    now.duration_since(*received_at)
+                        .map_or(true, |age| age <= history_duration)
+                })
+                .count();
+            images.truncate(length.max(1));
+        }
+        _ => *image_data = Some(Ok(VecDeque::from([(now, data)]))),
+    }
+}
+
+/// Returns the latest image received at or before `at`, or the oldest one if all are newer
+fn image_at(images: &VecDeque<(SystemTime, Vec<u8>)>, at: Option<SystemTime>) -> &Vec<u8> {
+    let (_, image) = match at {
+        Some(at) => images
+            .iter()
+            .find(|(received_at, _)| *received_at <= at)
+            .unwrap_or_else(|| images.back().unwrap()),
+        None => images.front().unwrap(),
+    };
+    image
+}
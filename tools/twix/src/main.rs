@@ -14,6 +14,7 @@ use color_eyre::{
 
 use communication::client::ConnectionStatus;
 use completion_edit::CompletionEdit;
+use configuration::Configuration;
 use eframe::{
     egui::{
         CentralPanel, Context, Id, Key, Layout, Modifiers, TopBottomPanel, Ui, Widget, WidgetText,
@@ -25,13 +26,14 @@ use eframe::{
 use egui_dock::{DockArea, DockState, NodeIndex, SurfaceIndex, TabAddAlign, TabIndex};
 use fern::{colors::ColoredLevelConfig, Dispatch, InitError};
 
-use log::error;
-use nao::Nao;
+use crate::nao::Nao;
+use log::{error, info};
 use panel::Panel;
 use panels::{
-    BehaviorSimulatorPanel, EnumPlotPanel, ImagePanel, ImageSegmentsPanel, LookAtPanel,
-    ManualCalibrationPanel, MapPanel, ParameterPanel, PlotPanel, RemotePanel, TextPanel,
-    VisionTunerPanel,
+    notify_alarms, AlarmsPanel, BallHypothesesPanel, BehaviorPanel, BehaviorSimulatorPanel,
+    CyclerGraphPanel, EnumPlotPanel, ImagePanel, ImageSegmentsPanel, LookAtPanel,
+    ManualCalibrationPanel, MapPanel, ParameterPanel, PlotPanel, RemotePanel, ReportPanel,
+    RobotServicePanel, TextPanel, VisionTunerPanel,
 };
 use repository::{get_repository_root, Repository};
 use serde_json::{from_str, to_string, Value};
@@ -39,10 +41,14 @@ use tokio::{
     runtime::{Builder, Runtime},
     sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
 };
+use types::parameters::LocationParameters;
+use value_buffer::ValueBuffer;
 use visuals::Visuals;
+use workspace::{Workspace, WorkspaceAddress};
 
 mod change_buffer;
 mod completion_edit;
+mod configuration;
 mod image_buffer;
 mod nao;
 mod panel;
@@ -50,9 +56,11 @@ mod panels;
 mod players_value_buffer;
 mod repository_parameters;
 mod selectable_panel_macro;
+mod time_cursor;
 mod twix_painter;
 mod value_buffer;
 mod visuals;
+mod workspace;
 
 fn setup_logger() -> Result<(), InitError> {
     Dispatch::new()
@@ -134,7 +142,11 @@ impl ReachableNaos {
 }
 
 impl_selectable_panel!(
+    AlarmsPanel,
+    BallHypothesesPanel,
+    BehaviorPanel,
     BehaviorSimulatorPanel,
+    CyclerGraphPanel,
     ImagePanel,
     ImageSegmentsPanel,
     LookAtPanel,
@@ -144,6 +156,8 @@ impl_selectable_panel!(
     PlotPanel,
     EnumPlotPanel,
     RemotePanel,
+    ReportPanel,
+    RobotServicePanel,
     TextPanel,
     VisionTunerPanel
 );
@@ -152,10 +166,14 @@ struct TwixApp {
     reachable_naos: ReachableNaos,
     connection_intent: bool,
     ip_address: String,
+    location: ValueBuffer,
     panel_selection: String,
     last_focused_tab: (NodeIndex, TabIndex),
     dock_state: DockState<Tab>,
     visual: Visuals,
+    configuration: Configuration,
+    workspace_name: String,
+    export_address: bool,
 }
 
 impl TwixApp {
@@ -174,7 +192,17 @@ impl TwixApp {
             })
             .unwrap_or(false);
 
-        let nao = Arc::new(Nao::new(ip_address.clone(), connection_intent));
+        let configuration = Configuration::load().unwrap_or_else(|error| {
+            error!("{error:#}");
+            Configuration::default()
+        });
+
+        let nao = Arc::new(Nao::new(
+            ip_address.clone(),
+            connection_intent,
+            configuration.history_duration(),
+        ));
+        let location = nao.subscribe_parameter("location");
 
         let dock_state: Option<DockState<Value>> = creation_context
             .storage
@@ -189,7 +217,7 @@ impl TwixApp {
             }),
             None => DockState::new(vec![SelectablePanel::TextPanel(TextPanel::new(
                 nao.clone(),
-                None,
+                configuration.panel_defaults(TextPanel::NAME).as_ref(),
             ))
             .into()]),
         };
@@ -212,10 +240,14 @@ impl TwixApp {
             reachable_naos,
             connection_intent,
             ip_address: ip_address.unwrap_or_default(),
+            location,
             panel_selection,
             dock_state,
             last_focused_tab: (0.into(), 0.into()),
             visual,
+            configuration,
+            workspace_name: String::new(),
+            export_address: false,
         }
     }
 }
@@ -223,6 +255,7 @@ impl TwixApp {
 impl App for TwixApp {
     fn update(&mut self, context: &Context, _frame: &mut Frame) {
         self.reachable_naos.update();
+        self.update_alarms(context);
 
         TopBottomPanel::top("top_bar").show(context, |ui| {
             ui.horizontal(|ui| {
@@ -261,6 +294,17 @@ impl App for TwixApp {
                     {
                         self.nao.set_connect(self.connection_intent);
                     }
+                    if let Ok(Some(location)) =
+                        self.location.parse_latest::<Option<LocationParameters>>()
+                    {
+                        let (color, hint) = if location.is_detected_from_network {
+                            (Color32::GREEN, "Selected from the connected network")
+                        } else {
+                            (Color32::YELLOW, "Network not mapped, using nao_location")
+                        };
+                        ui.label(WidgetText::from(location.name).color(color))
+                            .on_hover_text(hint);
+                    }
 
                     if self.active_tab_index() != Some(self.last_focused_tab) {
                         self.last_focused_tab =
@@ -286,7 +330,9 @@ impl App for TwixApp {
                         match SelectablePanel::try_from_name(
                             &self.panel_selection,
                             self.nao.clone(),
-                            None,
+                            self.configuration
+                                .panel_defaults(&self.panel_selection)
+                                .as_ref(),
                         ) {
                             Ok(panel) => {
                                 if let Some(active_panel) = self.active_panel() {
@@ -309,13 +355,36 @@ impl App for TwixApp {
                                 }
                             })
                         });
+                        ui.menu_button("Workspace", |ui| {
+                            ui.text_edit_singleline(&mut self.workspace_name)
+                                .on_hover_text("Name or path to a .json file");
+                            ui.checkbox(&mut self.export_address, "Include address");
+                            ui.horizontal(|ui| {
+                                if ui.button("Export").clicked() {
+                                    self.export_workspace();
+                                    ui.close_menu();
+                                }
+                                if ui.button("Import").clicked() {
+                                    self.import_workspace(&self.workspace_name.clone());
+                                    ui.close_menu();
+                                }
+                            });
+                            ui.separator();
+                            for name in Workspace::list() {
+                                if ui.button(&name).clicked() {
+                                    self.workspace_name = name.clone();
+                                    self.import_workspace(&name);
+                                    ui.close_menu();
+                                }
+                            }
+                        });
                     })
                 });
             })
         });
         CentralPanel::default().show(context, |ui| {
             if ui.input_mut(|input| input.consume_key(Modifiers::CTRL, Key::T)) {
-                let tab = SelectablePanel::TextPanel(TextPanel::new(self.nao.clone(), None));
+                let tab = self.new_text_panel();
                 self.dock_state.push_to_focused_leaf(tab.into());
             }
 
@@ -337,7 +406,7 @@ impl App for TwixApp {
                 .show_inside(ui, &mut tab_viewer);
 
             for (surface_index, node_id) in tab_viewer.nodes_to_add_tabs_to {
-                let tab = SelectablePanel::TextPanel(TextPanel::new(self.nao.clone(), None));
+                let tab = self.new_text_panel();
                 let index = self.dock_state[surface_index][node_id].tabs_count();
                 self.dock_state[surface_index][node_id].insert_tab(index.into(), tab.into());
             }
@@ -363,6 +432,76 @@ impl App for TwixApp {
 }
 
 impl TwixApp {
+    /// Alarms are evaluated for all open alarm panels, not only for the visible tabs
+    fn update_alarms(&mut self, context: &Context) {
+        let now = SystemTime::now();
+        let mut has_alarm_panels = false;
+        let mut went_off = Vec::new();
+        for surface in self.dock_state.iter_surfaces_mut() {
+            let Some(tree) = surface.node_tree_mut() else {
+                continue;
+            };
+            for node in tree.iter_mut() {
+                for tab in node.tabs_mut().into_iter().flatten() {
+                    if let SelectablePanel::AlarmsPanel(panel) = &mut tab.panel {
+                        has_alarm_panels = true;
+                        went_off.extend(panel.update_alarms(now));
+                    }
+                }
+            }
+        }
+        if has_alarm_panels {
+            context.request_repaint_after(AlarmsPanel::REPAINT_INTERVAL);
+        }
+        if !went_off.is_empty() {
+            notify_alarms(context, &went_off);
+        }
+    }
+
+    fn new_text_panel(&self) -> SelectablePanel {
+        SelectablePanel::TextPanel(TextPanel::new(
+            self.nao.clone(),
+            self.configuration.panel_defaults(TextPanel::NAME).as_ref(),
+        ))
+    }
+
+    fn export_workspace(&self) {
+        let workspace = Workspace {
+            address: if self.export_address {
+                WorkspaceAddress::Fixed(self.ip_address.clone())
+            } else {
+                WorkspaceAddress::Current
+            },
+            dock_state: self.dock_state.map_tabs(|tab| tab.panel.save()),
+        };
+        match workspace.store(&self.workspace_name) {
+            Ok(path) => info!("exported workspace to {}", path.display()),
+            Err(error) => error!("{error:#}"),
+        }
+    }
+
+    fn import_workspace(&mut self, name_or_path: &str) {
+        let workspace = match Workspace::load(name_or_path) {
+            Ok(workspace) => workspace,
+            Err(error) => {
+                error!("{error:#}");
+                return;
+            }
+        };
+        if let WorkspaceAddress::Fixed(address) = workspace.address {
+            self.ip_address = address;
+            self.nao.set_address(&self.ip_address);
+        }
+        self.dock_state = workspace.dock_state.map_tabs(|value| {
+            SelectablePanel::new(self.nao.clone(), Some(value))
+                .unwrap_or_else(|error| {
+                    error!("{error:#}");
+                    self.new_text_panel()
+                })
+                .into()
+        });
+    }
+
     fn active_panel(&mut self) -> Option<&mut SelectablePanel> {
         let (_viewport, tab) = self.dock_state.find_active_focused()?;
         Some(&mut tab.panel)
@@ -1,10 +1,11 @@
-use std::{collections::BTreeSet, sync::Mutex};
+use std::{collections::BTreeSet, sync::Mutex, time::Duration};
 
 use communication::{
-    client::{Communication, ConnectionStatus, CyclerOutput},
+    client::{ConnectionStatus, CyclerOutput},
     messages::{Fields, Path},
 };
-
+use hulk_client::Client;
+use log::error;
 use serde_json::Value;
 use tokio::{
     runtime::{Builder, Runtime},
@@ -12,38 +13,38 @@ use tokio::{
     sync::{broadcast::error::RecvError, watch},
 };
 
-use crate::{change_buffer::ChangeBuffer, image_buffer::ImageBuffer, value_buffer::ValueBuffer};
+use crate::{
+    change_buffer::ChangeBuffer, image_buffer::ImageBuffer, time_cursor::TimeCursor,
+    value_buffer::ValueBuffer,
+};
 
 pub struct Nao {
-    communication: Communication,
+    client: Client,
     runtime: Runtime,
     address: Mutex<Option<String>>,
     connection_status_receiver: watch::Receiver<ConnectionStatus>,
+    time_cursor: TimeCursor,
 }
 
 impl Nao {
-    pub fn new(address: Option<String>, connect: bool) -> Self {
+    pub fn new(address: Option<String>, connect: bool, history_duration: Duration) -> Self {
         let runtime = Builder::new_multi_thread().enable_all().build().unwrap();
         let _guard = runtime.enter();
-        let communication = Communication::new(
-            address
-                .as_ref()
-                .map(|ip_address| ip_address_to_communication_url(ip_address)),
-            connect,
-        );
-        let connection_status_receiver = communication.subscribe_connection_status_updates();
+        let client = Client::new(address.as_deref(), connect);
+        let connection_status_receiver =
+            client.communication().subscribe_connection_status_updates();
 
         Self {
-            communication,
+            client,
             runtime,
             address: Mutex::new(address),
             connection_status_receiver,
+            time_cursor: TimeCursor::new(history_duration),
         }
     }
 
     pub fn set_connect(&self, connect: bool) {
-        self.runtime
-            .block_on(self.communication.set_connect(connect))
+        self.runtime.block_on(self.client.set_connect(connect))
     }
 
     pub fn set_address(&self, address: &str) {
@@ -51,30 +52,40 @@ impl Nao {
             let mut current_address = self.address.lock().unwrap();
             *current_address = Some(address.to_string());
         }
-        self.runtime.block_on(
-            self.communication
-                .set_address(ip_address_to_communication_url(address)),
-        );
+        self.runtime.block_on(self.client.set_address(address));
     }
 
     pub fn subscribe_output(&self, output: CyclerOutput) -> ValueBuffer {
         let _guard = self.runtime.enter();
-        ValueBuffer::output(self.communication.clone(), output)
+        ValueBuffer::output(
+            self.client.communication().clone(),
+            output,
+            self.time_cursor.clone(),
+        )
     }
 
     pub fn subscribe_image(&self, output: CyclerOutput) -> ImageBuffer {
         let _guard = self.runtime.enter();
-        ImageBuffer::new(self.communication.clone(), output)
+        ImageBuffer::new(
+            self.client.communication().clone(),
+            output,
+            self.time_cursor.clone(),
+        )
+    }
+
+    /// Shared by all panels, see [`TimeCursor`]
+    pub fn time_cursor(&self) -> &TimeCursor {
+        &self.time_cursor
     }
 
     pub fn subscribe_parameter(&self, path: &str) -> ValueBuffer {
         let _guard = self.runtime.enter();
-        ValueBuffer::parameter(self.communication.clone(), path.to_string())
+        ValueBuffer::parameter(self.client.communication().clone(), path.to_string())
     }
 
     pub fn subscribe_changes(&self, output: CyclerOutput) -> ChangeBuffer {
         let _guard = self.runtime.enter();
-        ChangeBuffer::output(self.communication.clone(), output)
+        ChangeBuffer::output(self.client.communication().clone(), output)
     }
 
     pub fn get_address(&self) -> Option<String> {
@@ -82,18 +93,26 @@ impl Nao {
     }
 
     pub fn get_output_fields(&self) -> Option<Fields> {
-        self.runtime
-            .block_on(self.communication.get_output_fields())
+        self.runtime.block_on(self.client.output_fields())
     }
 
     pub fn get_parameter_fields(&self) -> Option<BTreeSet<Path>> {
-        self.runtime
-            .block_on(self.communication.get_parameter_fields())
+        self.runtime.block_on(self.client.parameter_fields())
     }
 
     pub fn update_parameter_value(&self, path: &str, value: Value) {
-        self.runtime
-            .block_on(self.communication.update_parameter_value(path, value));
+        if let Err(error) = self
+            .runtime
+            .block_on(self.client.set_parameter(path, value))
+        {
+            error!("{error:#}");
+        }
+    }
+
+    pub fn update_parameter_values(&self, updates: Vec<(String, Value)>) {
+        if let Err(error) = self.runtime.block_on(self.client.set_parameters(updates)) {
+            error!("{error:#}");
+        }
     }
 
     pub fn connection_status(&self) -> ConnectionStatus {
@@ -106,7 +125,7 @@ impl Nao {
     {
         let _guard = self.runtime.enter();
 
-        let communication = self.communication.clone();
+        let communication = self.client.communication().clone();
         spawn(async move {
             let mut receiver = communication.subscribe_updates();
             while !matches!(receiver.recv().await, Err(RecvError::Closed)) {
@@ -115,7 +134,3 @@ impl Nao {
         });
     }
 }
-
-fn ip_address_to_communication_url(ip_address: &str) -> String {
-    format!("ws://{ip_address}:1337")
-}
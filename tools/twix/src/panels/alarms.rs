@@ -0,0 +1,281 @@
+use std::{
+    process::Command,
+    str::FromStr,
+    sync::Arc,
+    thread::spawn,
+    time::{Duration, SystemTime},
+};
+
+use communication::client::CyclerOutput;
+use eframe::{
+    egui::{
+        viewport::{UserAttentionType, ViewportCommand},
+        Button, ComboBox, Context, DragValue, Response, RichText, Ui, Widget,
+    },
+    epaint::Color32,
+};
+use log::{error, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{completion_edit::CompletionEdit, nao::Nao, panel::Panel, value_buffer::ValueBuffer};
+
+const BUFFER_CAPACITY: usize = 100;
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+enum Condition {
+    Above { threshold: f64 },
+    Below { threshold: f64 },
+    StaleFor { seconds: f64 },
+    ChangesFasterThan { per_second: f64 },
+}
+
+impl Condition {
+    fn name(&self) -> &'static str {
+        match self {
+            Condition::Above { .. } => "above",
+            Condition::Below { .. } => "below",
+            Condition::StaleFor { .. } => "stale for",
+            Condition::ChangesFasterThan { .. } => "changes faster than",
+        }
+    }
+
+    fn value_mut(&mut self) -> &mut f64 {
+        match self {
+            Condition::Above { threshold } | Condition::Below { threshold } => threshold,
+            Condition::StaleFor { seconds } => seconds,
+            Condition::ChangesFasterThan { per_second } => per_second,
+        }
+    }
+
+    /// Values are ordered from newest to oldest, non-numeric values never trigger
+    fn is_met(&self, values: &[(SystemTime, Value)], now: SystemTime) -> bool {
+        let numbers = || {
+            values
+                .iter()
+                .filter_map(|(received_at, value)| Some((*received_at, value.as_f64()?)))
+        };
+        match *self {
+            Condition::Above { threshold } => {
+                numbers().next().is_some_and(|(_, value)| value > threshold)
+            }
+            Condition::Below { threshold } => {
+                numbers().next().is_some_and(|(_, value)| value < threshold)
+            }
+            Condition::StaleFor { seconds } => values.first().map_or(true, |(received_at, _)| {
+                seconds_between(*received_at, now) > seconds
+            }),
+            Condition::ChangesFasterThan { per_second } => {
+                let (Some((newest_time, newest_value)), Some((oldest_time, oldest_value))) =
+                    (numbers().next(), numbers().last())
+                else {
+                    return false;
+                };
+                let duration = seconds_between(oldest_time, newest_time);
+                duration > 0.0 && ((newest_value - oldest_value) / duration).abs() > per_second
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Alarm {
+    output_key: String,
+    condition: Condition,
+    #[serde(skip)]
+    value_buffer: Option<ValueBuffer>,
+    #[serde(skip)]
+    is_triggered: bool,
+    #[serde(skip)]
+    is_acknowledged: bool,
+}
+
+impl Alarm {
+    fn new() -> Self {
+        Self {
+            output_key: String::new(),
+            condition: Condition::Above { threshold: 0.0 },
+            value_buffer: None,
+            is_triggered: false,
+            is_acknowledged: false,
+        }
+    }
+
+    fn subscribe_key(&mut self, nao: &Nao) {
+        self.value_buffer = match CyclerOutput::from_str(&self.output_key) {
+            Ok(output) => {
+                let buffer = nao.subscribe_output(output);
+                buffer.reserve(BUFFER_CAPACITY);
+                Some(buffer)
+            }
+            Err(error) => {
+                error!("Failed to subscribe: {error:#}");
+                None
+            }
+        };
+    }
+
+    /// Returns whether the alarm went off in this update
+    fn update(&mut self, now: SystemTime) -> bool {
+        let Some(values) = self
+            .value_buffer
+            .as_ref()
+            .and_then(|buffer| buffer.get_buffered_with_timestamps().ok())
+        else {
+            self.is_triggered = false;
+            return false;
+        };
+        let was_triggered = self.is_triggered;
+        self.is_triggered = self.condition.is_met(&values, now);
+        if !self.is_triggered {
+            self.is_acknowledged = false;
+        }
+        self.is_triggered && !was_triggered
+    }
+
+    fn show_settings(&mut self, ui: &mut Ui, nao: &Nao, id: usize) {
+        let subscription_field = ui.add(CompletionEdit::outputs(&mut self.output_key, nao));
+        if subscription_field.changed() {
+            self.subscribe_key(nao);
+        }
+        ComboBox::from_id_source(ui.id().with("alarm_condition").with(id))
+            .selected_text(self.condition.name())
+            .show_ui(ui, |ui| {
+                let value = *self.condition.value_mut();
+                for condition in [
+                    Condition::Above { threshold: value },
+                    Condition::Below { threshold: value },
+                    Condition::StaleFor { seconds: value },
+                    Condition::ChangesFasterThan { per_second: value },
+                ] {
+                    ui.selectable_value(&mut self.condition, condition, condition.name());
+                }
+            });
+        ui.add(DragValue::new(self.condition.value_mut()).speed(0.1));
+    }
+}
+
+/// Watches subscribed values and raises an alarm when a threshold is crossed, a value stops
+/// arriving or changes too fast.
+///
+/// Alarms are evaluated by the app as long as the panel is open in any tab, also while another
+/// tab is shown on top of it.
+pub struct AlarmsPanel {
+    nao: Arc<Nao>,
+    alarms: Vec<Alarm>,
+}
+
+impl AlarmsPanel {
+    /// Stale alarms have to be checked even if no new values arrive
+    pub const REPAINT_INTERVAL: Duration = Duration::from_millis(500);
+
+    /// Returns descriptions of the alarms which went off in this update
+    pub fn update_alarms(&mut self, now: SystemTime) -> Vec<String> {
+        self.alarms
+            .iter_mut()
+            .filter_map(|alarm| {
+                alarm
+                    .update(now)
+                    .then(|| format!("{} is {}", alarm.output_key, alarm.condition.name()))
+            })
+            .collect()
+    }
+}
+
+/// Draws attention to the window and shows a desktop notification, alarms mostly go off while
+/// the operator looks at the game instead of the screen
+pub fn notify_alarms(context: &Context, alarms: &[String]) {
+    for alarm in alarms {
+        warn!("Alarm: {alarm}");
+    }
+    context.send_viewport_cmd(ViewportCommand::RequestUserAttention(
+        UserAttentionType::Critical,
+    ));
+    let body = alarms.join("\n");
+    // waiting for the notification daemon must not block the ui
+    spawn(move || {
+        if let Err(error) = Command::new("notify-send")
+            .args(["--urgency=critical", "Twix alarm", &body])
+            .status()
+        {
+            error!("Failed to show alarm notification: {error}");
+        }
+    });
+}
+
+impl Panel for AlarmsPanel {
+    const NAME: &'static str = "Alarms";
+
+    fn new(nao: Arc<Nao>, value: Option<&Value>) -> Self {
+        let alarms = value
+            .and_then(|value| value["alarms"].as_array())
+            .map(|alarms| {
+                alarms
+                    .iter()
+                    .filter_map(|alarm| serde_json::from_value::<Alarm>(alarm.clone()).ok())
+                    .map(|mut alarm| {
+                        alarm.subscribe_key(&nao);
+                        alarm
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { nao, alarms }
+    }
+
+    fn save(&self) -> Value {
+        json!({
+            "alarms": self.alarms.iter().filter_map(|alarm| serde_json::to_value(alarm).ok()).collect::<Vec<Value>>(),
+        })
+    }
+}
+
+impl Widget for &mut AlarmsPanel {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let now = SystemTime::now();
+        let is_flashing_on = now
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .is_ok_and(|time| time.as_millis() % 1000 < 500);
+        ui.vertical(|ui| {
+            let mut id = 0;
+            let nao = self.nao.clone();
+            self.alarms.retain_mut(|alarm| {
+                ui.horizontal(|ui| {
+                    let delete_button =
+                        Button::new(RichText::new("❌").color(Color32::WHITE).strong())
+                            .fill(Color32::RED);
+                    let delete_button = ui.add(delete_button);
+                    let status = if !alarm.is_triggered {
+                        RichText::new("OK").color(Color32::GREEN)
+                    } else if alarm.is_acknowledged || is_flashing_on {
+                        RichText::new("ALARM")
+                            .color(Color32::WHITE)
+                            .background_color(Color32::RED)
+                    } else {
+                        RichText::new("ALARM").color(Color32::RED)
+                    };
+                    let status = ui.add(Button::new(status.strong()).frame(false));
+                    if status.clicked() && alarm.is_triggered {
+                        alarm.is_acknowledged = true;
+                    }
+                    status.on_hover_text("Click to acknowledge");
+                    alarm.show_settings(ui, &nao, id);
+                    id += 1;
+                    !delete_button.clicked()
+                })
+                .inner
+            });
+            if ui.button("✚").clicked() {
+                self.alarms.push(Alarm::new());
+            }
+        })
+        .response
+    }
+}
+
+fn seconds_between(earlier: SystemTime, later: SystemTime) -> f64 {
+    later
+        .duration_since(earlier)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
@@ -0,0 +1,215 @@
+use std::{str::FromStr, sync::Arc, time::SystemTime};
+
+use eframe::{
+    egui::{DragValue, Grid, Response, Ui, Widget},
+    epaint::{Color32, Stroke},
+};
+use serde_json::{json, Value};
+
+use communication::client::CyclerOutput;
+use coordinate_systems::Ground;
+use linear_algebra::{distance, point, vector, Point2};
+use types::{ball_filter::Hypothesis, cycle_time::CycleTime, field_dimensions::FieldDimensions};
+
+use crate::{nao::Nao, panel::Panel, twix_painter::TwixPainter, value_buffer::ValueBuffer};
+
+/// Clicks further away from any hypothesis release the pinned one
+const MAXIMUM_PIN_DISTANCE: f32 = 0.3;
+const DEFAULT_TRAIL_LENGTH: usize = 100;
+
+pub struct BallHypothesesPanel {
+    hypotheses: ValueBuffer,
+    cycle_time: ValueBuffer,
+    field_dimensions: ValueBuffer,
+    trail_length: usize,
+    /// Hypotheses carry no identity, the pin follows the hypothesis closest to its last position
+    pinned_position: Option<Point2<Ground>>,
+}
+
+impl Panel for BallHypothesesPanel {
+    const NAME: &'static str = "Ball Hypotheses";
+
+    fn new(nao: Arc<Nao>, value: Option<&Value>) -> Self {
+        let trail_length = value
+            .and_then(|value| value.get("trail_length"))
+            .and_then(|value| value.as_u64())
+            .map_or(DEFAULT_TRAIL_LENGTH, |value| value as usize);
+        let hypotheses = nao.subscribe_output(
+            CyclerOutput::from_str("Control.additional.ball_filter_hypotheses").unwrap(),
+        );
+        hypotheses.reserve(trail_length);
+        let cycle_time =
+            nao.subscribe_output(CyclerOutput::from_str("Control.main.cycle_time").unwrap());
+        let field_dimensions = nao.subscribe_parameter("field_dimensions");
+        Self {
+            hypotheses,
+            cycle_time,
+            field_dimensions,
+            trail_length,
+            pinned_position: None,
+        }
+    }
+
+    fn save(&self) -> Value {
+        json!({
+            "trail_length": self.trail_length,
+        })
+    }
+}
+
+impl Widget for &mut BallHypothesesPanel {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let buffered_hypotheses: Vec<Vec<Hypothesis>> = match self.hypotheses.parse_buffered() {
+            Ok(value) => value,
+            Err(error) => return ui.label(format!("{error:?}")),
+        };
+        let field_dimensions: FieldDimensions = match self.field_dimensions.parse_latest() {
+            Ok(value) => value,
+            Err(error) => return ui.label(format!("{error:?}")),
+        };
+        let now = self
+            .cycle_time
+            .parse_latest::<CycleTime>()
+            .map_or_else(|_| SystemTime::now(), |cycle_time| cycle_time.start_time);
+        let latest_hypotheses = buffered_hypotheses.first().cloned().unwrap_or_default();
+
+        self.pinned_position = self.pinned_position.and_then(|pinned_position| {
+            closest_hypothesis(&latest_hypotheses, pinned_position, MAXIMUM_PIN_DISTANCE)
+                .map(position)
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Trail length");
+            if ui
+                .add(DragValue::new(&mut self.trail_length).clamp_range(1..=1000))
+                .changed()
+            {
+                self.hypotheses.reserve(self.trail_length);
+            }
+            ui.label(format!("{} hypotheses", latest_hypotheses.len()));
+        });
+        let pinned_hypothesis = self.pinned_position.and_then(|pinned_position| {
+            closest_hypothesis(&latest_hypotheses, pinned_position, MAXIMUM_PIN_DISTANCE)
+        });
+        match pinned_hypothesis {
+            Some(hypothesis) => show_values(ui, hypothesis, now),
+            None => {
+                ui.label("Click a hypothesis to pin its values");
+            }
+        }
+
+        let (response, painter) = TwixPainter::<Ground>::allocate_new(ui);
+        let painter = painter.with_ground_transforms();
+
+        for hypotheses in buffered_hypotheses.iter().skip(1).rev() {
+            for hypothesis in hypotheses {
+                painter.circle_filled(
+                    position(hypothesis),
+                    field_dimensions.ball_radius / 2.0,
+                    Color32::from_white_alpha(20),
+                );
+            }
+        }
+
+        let maximum_validity = latest_hypotheses
+            .iter()
+            .map(|hypothesis| hypothesis.validity)
+            .fold(f32::EPSILON, f32::max);
+        for hypothesis in &latest_hypotheses {
+            let state = hypothesis.moving_state;
+            let position = position(hypothesis);
+            let covariance = state.covariance.fixed_view::<2, 2>(0, 0).into_owned();
+            let alpha = (255.0 * hypothesis.validity / maximum_validity).clamp(30.0, 255.0) as u8;
+            let is_pinned = Some(position) == self.pinned_position;
+            let stroke = if is_pinned {
+                Stroke::new(0.02, Color32::RED)
+            } else {
+                Stroke::new(0.01, Color32::BLACK)
+            };
+            painter.covariance(
+                position,
+                covariance,
+                stroke,
+                Color32::from_rgba_unmultiplied(255, 255, 0, alpha / 2),
+            );
+            painter.line_segment(
+                position,
+                position + vector![state.mean.z, state.mean.w],
+                Stroke::new(0.01, Color32::LIGHT_BLUE),
+            );
+            painter.circle_filled(
+                position,
+                field_dimensions.ball_radius,
+                Color32::from_rgba_unmultiplied(255, 255, 255, alpha),
+            );
+        }
+
+        if response.clicked() {
+            if let Some(click_position) = response.interact_pointer_pos() {
+                let click_position = painter.transform_pixel_to_world(click_position);
+                self.pinned_position =
+                    closest_hypothesis(&latest_hypotheses, click_position, MAXIMUM_PIN_DISTANCE)
+                        .map(position);
+            }
+        }
+
+        response
+    }
+}
+
+fn position(hypothesis: &Hypothesis) -> Point2<Ground> {
+    let mean = hypothesis.moving_state.mean;
+    point![mean.x, mean.y]
+}
+
+fn closest_hypothesis(
+    hypotheses: &[Hypothesis],
+    target: Point2<Ground>,
+    maximum_distance: f32,
+) -> Option<&Hypothesis> {
+    hypotheses
+        .iter()
+        .map(|hypothesis| (hypothesis, distance(position(hypothesis), target)))
+        .filter(|(_, distance)| *distance < maximum_distance)
+        .min_by(|(_, left), (_, right)| left.total_cmp(right))
+        .map(|(hypothesis, _)| hypothesis)
+}
+
+fn show_values(ui: &mut Ui, hypothesis: &Hypothesis, now: SystemTime) {
+    let age = now
+        .duration_since(hypothesis.last_update)
+        .unwrap_or_default();
+    Grid::new("pinned_ball_hypothesis")
+        .num_columns(3)
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("");
+            ui.label("moving");
+            ui.label("resting");
+            ui.end_row();
+            for (label, index) in [("x", 0), ("y", 1), ("vx", 2), ("vy", 3)] {
+                ui.label(label);
+                ui.label(format!("{:.3}", hypothesis.moving_state.mean[index]));
+                ui.label(format!("{:.3}", hypothesis.resting_state.mean[index]));
+                ui.end_row();
+            }
+            for (label, index) in [("σx", 0), ("σy", 1)] {
+                ui.label(label);
+                ui.label(format!(
+                    "{:.3}",
+                    hypothesis.moving_state.covariance[(index, index)].sqrt()
+                ));
+                ui.label(format!(
+                    "{:.3}",
+                    hypothesis.resting_state.covariance[(index, index)].sqrt()
+                ));
+                ui.end_row();
+            }
+            ui.label("validity");
+            ui.label(format!("{:.3}", hypothesis.validity));
+            ui.end_row();
+            ui.label("age");
+            ui.label(format!("{:.2}s", age.as_secs_f32()));
+            ui.end_row();
+        });
+}
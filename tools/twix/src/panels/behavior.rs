@@ -0,0 +1,108 @@
+use std::{str::FromStr, sync::Arc};
+
+use communication::client::CyclerOutput;
+use eframe::egui::{Grid, Response, ScrollArea, Ui, Widget};
+use log::error;
+use serde_json::{json, Value};
+
+use crate::{completion_edit::CompletionEdit, nao::Nao, panel::Panel, value_buffer::ValueBuffer};
+
+struct Subscription {
+    key: String,
+    values: Option<ValueBuffer>,
+}
+
+pub struct BehaviorPanel {
+    nao: Arc<Nao>,
+    subscriptions: Vec<Subscription>,
+    new_key: String,
+}
+
+impl Panel for BehaviorPanel {
+    const NAME: &'static str = "Behavior";
+
+    fn new(nao: Arc<Nao>, value: Option<&Value>) -> Self {
+        let subscriptions = value
+            .and_then(|value| value.get("subscribe_keys"))
+            .and_then(|keys| keys.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|key| key.as_str())
+            .map(|key| subscribe(&nao, key.to_string()))
+            .collect();
+        Self {
+            nao,
+            subscriptions,
+            new_key: String::new(),
+        }
+    }
+
+    fn save(&self) -> Value {
+        let keys: Vec<_> = self
+            .subscriptions
+            .iter()
+            .map(|subscription| subscription.key.clone())
+            .collect();
+        json!({
+            "subscribe_keys": keys,
+        })
+    }
+}
+
+impl Widget for &mut BehaviorPanel {
+    fn ui(self, ui: &mut Ui) -> Response {
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.add(CompletionEdit::outputs(
+                    &mut self.new_key,
+                    self.nao.as_ref(),
+                ));
+                if ui.button("Add").clicked() && !self.new_key.is_empty() {
+                    let key = std::mem::take(&mut self.new_key);
+                    self.subscriptions.push(subscribe(&self.nao, key));
+                }
+            });
+            ui.separator();
+            let mut removed_subscription = None;
+            ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    Grid::new("behavior_subscriptions")
+                        .num_columns(3)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for (index, subscription) in self.subscriptions.iter().enumerate() {
+                                if ui.small_button("✖").clicked() {
+                                    removed_subscription = Some(index);
+                                }
+                                ui.label(&subscription.key);
+                                let content = match &subscription.values {
+                                    Some(values) => match values.get_latest() {
+                                        Ok(value) => value.to_string(),
+                                        Err(error) => error,
+                                    },
+                                    None => "invalid output".to_string(),
+                                };
+                                ui.label(content);
+                                ui.end_row();
+                            }
+                        });
+                });
+            if let Some(index) = removed_subscription {
+                self.subscriptions.remove(index);
+            }
+        })
+        .response
+    }
+}
+
+fn subscribe(nao: &Nao, key: String) -> Subscription {
+    let values = match CyclerOutput::from_str(&key) {
+        Ok(output) => Some(nao.subscribe_output(output)),
+        Err(error) => {
+            error!("Failed to subscribe to {key}: {error:?}");
+            None
+        }
+    };
+    Subscription { key, values }
+}
@@ -0,0 +1,204 @@
+use std::{fs::File, sync::Arc};
+
+use color_eyre::{eyre::WrapErr, Result};
+use eframe::egui::{CollapsingHeader, Response, ScrollArea, TextEdit, Ui, Widget};
+use itertools::Itertools;
+use log::error;
+use serde_json::{json, Value};
+use source_analyzer::graph::{FieldKind, Graph, GraphCycler, GraphField, GraphNode};
+
+use crate::{nao::Nao, panel::Panel};
+
+const CYCLER_GRAPH: &str = include_str!(concat!(env!("OUT_DIR"), "/cycler_graph.json"));
+
+pub struct CyclerGraphPanel {
+    graph: Graph,
+    filter: String,
+    export_path: String,
+}
+
+impl Panel for CyclerGraphPanel {
+    const NAME: &'static str = "Cycler Graph";
+
+    fn new(_nao: Arc<Nao>, value: Option<&Value>) -> Self {
+        let graph = serde_json::from_str(CYCLER_GRAPH).unwrap_or_else(|error| {
+            error!("failed to parse cycler graph: {error}");
+            Graph::default()
+        });
+        let filter = match value.and_then(|value| value.get("filter")) {
+            Some(Value::String(string)) => string.clone(),
+            _ => String::new(),
+        };
+        let export_path = match value.and_then(|value| value.get("export_path")) {
+            Some(Value::String(string)) => string.clone(),
+            _ => "cycler_graph.json".to_string(),
+        };
+        Self {
+            graph,
+            filter,
+            export_path,
+        }
+    }
+
+    fn save(&self) -> Value {
+        json!({
+            "filter": self.filter.clone(),
+            "export_path": self.export_path.clone(),
+        })
+    }
+}
+
+impl Widget for &mut CyclerGraphPanel {
+    fn ui(self, ui: &mut Ui) -> Response {
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.label("Filter:");
+                ui.add(
+                    TextEdit::singleline(&mut self.filter).hint_text("node, output or parameter"),
+                );
+                ui.separator();
+                ui.add(TextEdit::singleline(&mut self.export_path));
+                if ui.button("Export").clicked() {
+                    if let Err(error) = self.export() {
+                        error!("{error:#}");
+                    }
+                }
+            });
+            ui.separator();
+            ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    for cycler in &self.graph.cyclers {
+                        let nodes = cycler
+                            .nodes
+                            .iter()
+                            .filter(|node| matches_filter(node, &self.filter))
+                            .collect_vec();
+                        if nodes.is_empty() {
+                            continue;
+                        }
+                        CollapsingHeader::new(format!("{} ({:?})", cycler.name, cycler.kind))
+                            .default_open(!self.filter.is_empty())
+                            .show(ui, |ui| {
+                                for node in nodes {
+                                    self.show_node(ui, cycler, node);
+                                }
+                            });
+                    }
+                });
+        })
+        .response
+    }
+}
+
+impl CyclerGraphPanel {
+    fn export(&self) -> Result<()> {
+        let file = File::create(&self.export_path)
+            .wrap_err_with(|| format!("failed to create {}", self.export_path))?;
+        serde_json::to_writer_pretty(file, &self.graph).wrap_err("failed to write cycler graph")
+    }
+
+    fn show_node(&self, ui: &mut Ui, cycler: &GraphCycler, node: &GraphNode) {
+        let title = if node.is_setup_node {
+            format!("{} (setup)", node.module)
+        } else {
+            node.module.clone()
+        };
+        CollapsingHeader::new(title)
+            .id_source((&cycler.name, &node.module))
+            .show(ui, |ui| {
+                ui.strong("Inputs");
+                for field in node.cycle_context.iter().filter(|field| {
+                    matches!(
+                        field.kind,
+                        FieldKind::HistoricInput
+                            | FieldKind::Input
+                            | FieldKind::PerceptionInput
+                            | FieldKind::RequiredInput
+                    )
+                }) {
+                    let source = self
+                        .graph
+                        .source_cycler_of(cycler, field)
+                        .and_then(|source_cycler| {
+                            let path = field.path.as_deref()?;
+                            let producer = self.graph.producer_of(&source_cycler.name, path)?;
+                            Some(format!("{}::{}", source_cycler.name, producer.module))
+                        })
+                        .unwrap_or_else(|| "unknown".to_string());
+                    ui.label(format!("{} ← {source}", describe(field)));
+                }
+
+                ui.strong("Parameters");
+                for field in fields_of_kind(&node.creation_context, FieldKind::Parameter)
+                    .chain(fields_of_kind(&node.cycle_context, FieldKind::Parameter))
+                {
+                    ui.label(describe(field));
+                }
+
+                ui.strong("Main Outputs");
+                for field in &node.main_outputs {
+                    let consumers = self
+                        .graph
+                        .consumers_of(&cycler.name, &field.name)
+                        .map(|(consuming_cycler, consumer)| {
+                            format!("{}::{}", consuming_cycler.name, consumer.module)
+                        })
+                        .unique()
+                        .join(", ");
+                    ui.label(format!("{} → {consumers}", describe(field)));
+                }
+
+                let additional_outputs =
+                    fields_of_kind(&node.cycle_context, FieldKind::AdditionalOutput).collect_vec();
+                if !additional_outputs.is_empty() {
+                    ui.strong("Additional Outputs");
+                    for field in additional_outputs {
+                        ui.label(describe(field));
+                    }
+                }
+
+                let cycler_states =
+                    fields_of_kind(&node.cycle_context, FieldKind::CyclerState).collect_vec();
+                if !cycler_states.is_empty() {
+                    ui.strong("Cycler States");
+                    for field in cycler_states {
+                        ui.label(describe(field));
+                    }
+                }
+            });
+    }
+}
+
+fn fields_of_kind(fields: &[GraphField], kind: FieldKind) -> impl Iterator<Item = &GraphField> {
+    fields.iter().filter(move |field| field.kind == kind)
+}
+
+fn describe(field: &GraphField) -> String {
+    let location = match (&field.cycler_instance, &field.path) {
+        (Some(cycler_instance), Some(path)) => format!(" \"{cycler_instance}\" \"{path}\""),
+        (None, Some(path)) => format!(" \"{path}\""),
+        _ => String::new(),
+    };
+    let data_type = field.data_type.as_deref().unwrap_or("_");
+    format!("{}: {:?}<{data_type}>{location}", field.name, field.kind)
+}
+
+fn matches_filter(node: &GraphNode, filter: &str) -> bool {
+    if filter.is_empty() {
+        return true;
+    }
+    node.module.contains(filter)
+        || node
+            .creation_context
+            .iter()
+            .chain(&node.cycle_context)
+            .chain(&node.main_outputs)
+            .any(|field| {
+                field.name.contains(filter)
+                    || field
+                        .path
+                        .as_deref()
+                        .is_some_and(|path| path.contains(filter))
+            })
+}
@@ -1,10 +1,11 @@
 use std::str::FromStr;
 
 use color_eyre::Result;
-use communication::client::{Cycler, CyclerOutput};
+use communication::client::{Cycler, CyclerOutput, Output};
 use coordinate_systems::Pixel;
 use eframe::epaint::{Color32, Stroke};
 use linear_algebra::point;
+use types::horizon_crop::HorizonCrop;
 
 use crate::{
     panels::image::overlay::Overlay, twix_painter::TwixPainter, value_buffer::ValueBuffer,
@@ -12,6 +13,7 @@ use crate::{
 
 pub struct Horizon {
     horizon: ValueBuffer,
+    horizon_crop: ValueBuffer,
 }
 
 impl Overlay for Horizon {
@@ -31,10 +33,24 @@ impl Overlay for Horizon {
                 ))
                 .unwrap(),
             ),
+            horizon_crop: nao.subscribe_output(CyclerOutput {
+                cycler: selected_cycler,
+                output: Output::Main {
+                    path: "horizon_crop".to_string(),
+                },
+            }),
         }
     }
 
     fn paint(&self, painter: &TwixPainter<Pixel>) -> Result<()> {
+        if let Ok(horizon_crop) = self.horizon_crop.parse_latest::<HorizonCrop>() {
+            painter.line_segment(
+                point![0.0, horizon_crop.left_y],
+                point![horizon_crop.image_width, horizon_crop.right_y],
+                Stroke::new(2.0, Color32::YELLOW),
+            );
+        }
+
         let horizon: projection::horizon::Horizon = self.horizon.require_latest()?;
 
         let left_horizon_height = horizon.y_at_x(0.0);
@@ -1,12 +1,12 @@
 use std::{str::FromStr, sync::Arc};
 
 use color_eyre::Result;
-use eframe::epaint::Color32;
+use eframe::epaint::{Color32, Stroke};
 
 use communication::client::CyclerOutput;
 use coordinate_systems::{Field, Ground};
 use linear_algebra::Isometry2;
-use types::field_dimensions::FieldDimensions;
+use types::{ball_motion::BallMotion, field_dimensions::FieldDimensions};
 
 use crate::{
     nao::Nao, panels::map::layer::Layer, twix_painter::TwixPainter, value_buffer::ValueBuffer,
@@ -15,6 +15,7 @@ use crate::{
 pub struct BallPosition {
     ground_to_field: ValueBuffer,
     ball_position: ValueBuffer,
+    ball_motion: ValueBuffer,
 }
 
 impl Layer<Field> for BallPosition {
@@ -27,9 +28,12 @@ impl Layer<Field> for BallPosition {
         let ball_position =
             nao.subscribe_output(CyclerOutput::from_str("Control.main.ball_position").unwrap());
         ball_position.reserve(100);
+        let ball_motion =
+            nao.subscribe_output(CyclerOutput::from_str("Control.main.ball_motion").unwrap());
         Self {
             ground_to_field,
             ball_position,
+            ball_motion,
         }
     }
 
@@ -61,10 +65,30 @@ impl Layer<Field> for BallPosition {
             &ball_positions.first().map(Option::as_ref),
             ground_to_fields.first(),
         ) {
-            painter.ball(
-                ground_to_field.unwrap_or_default() * ball.position,
-                field_dimensions.ball_radius,
-            );
+            let ball_in_field = ground_to_field.unwrap_or_default() * ball.position;
+            let ball_motion: Option<BallMotion> =
+                self.ball_motion.parse_latest().unwrap_or_default();
+            if let Some(ball_motion) = ball_motion.filter(|ball_motion| ball_motion.is_rolling) {
+                painter.line_segment(
+                    ball_in_field,
+                    ball_motion.rest_position,
+                    Stroke::new(0.02, Color32::LIGHT_BLUE),
+                );
+                for crossing in [
+                    ball_motion.own_goal_line_crossing,
+                    ball_motion.sideline_crossing,
+                ]
+                .into_iter()
+                .flatten()
+                {
+                    painter.circle_stroke(
+                        crossing,
+                        field_dimensions.ball_radius,
+                        Stroke::new(0.02, Color32::RED),
+                    );
+                }
+            }
+            painter.ball(ball_in_field, field_dimensions.ball_radius);
         }
         Ok(())
     }
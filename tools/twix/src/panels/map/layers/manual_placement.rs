@@ -0,0 +1,64 @@
+use std::{str::FromStr, sync::Arc};
+
+use color_eyre::Result;
+use eframe::epaint::{Color32, Stroke};
+
+use communication::client::CyclerOutput;
+use coordinate_systems::{Field, Ground};
+use linear_algebra::{Isometry2, Pose2};
+use types::field_dimensions::FieldDimensions;
+
+use crate::{
+    nao::Nao, panels::map::layer::Layer, twix_painter::TwixPainter, value_buffer::ValueBuffer,
+};
+
+pub struct ManualPlacement {
+    ground_to_field: ValueBuffer,
+    manual_placement_pose: ValueBuffer,
+}
+
+impl Layer<Field> for ManualPlacement {
+    const NAME: &'static str = "Manual Placement";
+
+    fn new(nao: Arc<Nao>) -> Self {
+        let ground_to_field =
+            nao.subscribe_output(CyclerOutput::from_str("Control.main.ground_to_field").unwrap());
+        let manual_placement_pose = nao.subscribe_output(
+            CyclerOutput::from_str("Control.main.manual_placement_pose").unwrap(),
+        );
+        Self {
+            ground_to_field,
+            manual_placement_pose,
+        }
+    }
+
+    fn paint(
+        &self,
+        painter: &TwixPainter<Field>,
+        _field_dimensions: &FieldDimensions,
+    ) -> Result<()> {
+        let manual_placement_pose: Option<Pose2<Field>> =
+            self.manual_placement_pose.require_latest()?;
+        let Some(manual_placement_pose) = manual_placement_pose else {
+            return Ok(());
+        };
+
+        let ground_to_field: Option<Isometry2<Ground, Field>> =
+            self.ground_to_field.parse_latest().unwrap_or_default();
+        if let Some(ground_to_field) = ground_to_field {
+            painter.line_segment(
+                ground_to_field.as_pose().position(),
+                manual_placement_pose.position(),
+                Stroke::new(0.02, Color32::YELLOW),
+            );
+        }
+        painter.pose(
+            manual_placement_pose,
+            0.15,
+            0.25,
+            Color32::TRANSPARENT,
+            Stroke::new(0.02, Color32::YELLOW),
+        );
+        Ok(())
+    }
+}
@@ -7,11 +7,13 @@ mod image_segments;
 mod kick_decisions;
 mod line_correspondences;
 mod lines;
+mod manual_placement;
 mod obstacle_filter;
 mod obstacles;
 mod path;
 mod path_obstacles;
 mod robot_pose;
+mod walk_in_lane;
 
 pub use self::behavior_simulator::BehaviorSimulator;
 pub use ball_filter::BallFilter;
@@ -22,8 +24,10 @@ pub use image_segments::ImageSegments;
 pub use kick_decisions::KickDecisions;
 pub use line_correspondences::LineCorrespondences;
 pub use lines::Lines;
+pub use manual_placement::ManualPlacement;
 pub use obstacle_filter::ObstacleFilter;
 pub use obstacles::Obstacles;
 pub use path::Path;
 pub use path_obstacles::PathObstacles;
 pub use robot_pose::RobotPose;
+pub use walk_in_lane::WalkInLanes;
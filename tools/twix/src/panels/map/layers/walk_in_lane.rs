@@ -0,0 +1,63 @@
+use std::{str::FromStr, sync::Arc};
+
+use color_eyre::Result;
+use eframe::epaint::{Color32, Stroke};
+
+use communication::client::CyclerOutput;
+use coordinate_systems::{Field, Ground};
+use linear_algebra::{point, Isometry2};
+use types::{field_dimensions::FieldDimensions, walk_in_lane::WalkInLane};
+
+use crate::{
+    nao::Nao, panels::map::layer::Layer, twix_painter::TwixPainter, value_buffer::ValueBuffer,
+};
+
+pub struct WalkInLanes {
+    ground_to_field: ValueBuffer,
+    walk_in_lane: ValueBuffer,
+}
+
+impl Layer<Field> for WalkInLanes {
+    const NAME: &'static str = "Walk-In Lanes";
+
+    fn new(nao: Arc<Nao>) -> Self {
+        let ground_to_field =
+            nao.subscribe_output(CyclerOutput::from_str("Control.main.ground_to_field").unwrap());
+        let walk_in_lane = nao
+            .subscribe_output(CyclerOutput::from_str("Control.additional.walk_in_lane").unwrap());
+        Self {
+            ground_to_field,
+            walk_in_lane,
+        }
+    }
+
+    fn paint(
+        &self,
+        painter: &TwixPainter<Field>,
+        field_dimensions: &FieldDimensions,
+    ) -> Result<()> {
+        let walk_in_lane: Option<WalkInLane> = self.walk_in_lane.require_latest()?;
+        let Some(walk_in_lane) = walk_in_lane else {
+            return Ok(());
+        };
+
+        let stroke = Stroke::new(0.02, Color32::LIGHT_BLUE);
+        let half_field_length = field_dimensions.length / 2.0;
+        painter.line_segment(
+            point![-half_field_length, walk_in_lane.y],
+            point![half_field_length, walk_in_lane.y],
+            stroke,
+        );
+        let ground_to_field: Option<Isometry2<Ground, Field>> =
+            self.ground_to_field.parse_latest().unwrap_or_default();
+        if let Some(ground_to_field) = ground_to_field {
+            let robot_position = ground_to_field.as_pose().position();
+            painter.line_segment(
+                robot_position,
+                point![robot_position.x(), walk_in_lane.y],
+                stroke,
+            );
+        }
+        Ok(())
+    }
+}
@@ -78,6 +78,8 @@ pub struct MapPanel {
     feet_detection: EnabledLayer<layers::FeetDetection, Ground>,
     ball_filter: EnabledLayer<layers::BallFilter, Ground>,
     obstacle_filter: EnabledLayer<layers::ObstacleFilter, Ground>,
+    manual_placement: EnabledLayer<layers::ManualPlacement, Field>,
+    walk_in_lanes: EnabledLayer<layers::WalkInLanes, Field>,
 }
 
 impl Panel for MapPanel {
@@ -98,6 +100,8 @@ impl Panel for MapPanel {
         let feet_detection = EnabledLayer::new(nao.clone(), value, false);
         let ball_filter = EnabledLayer::new(nao.clone(), value, false);
         let obstacle_filter = EnabledLayer::new(nao.clone(), value, false);
+        let manual_placement = EnabledLayer::new(nao.clone(), value, false);
+        let walk_in_lanes = EnabledLayer::new(nao.clone(), value, false);
 
         let field_dimensions = nao.subscribe_parameter("field_dimensions");
         let ground_to_field =
@@ -123,6 +127,8 @@ impl Panel for MapPanel {
             feet_detection,
             ball_filter,
             obstacle_filter,
+            manual_placement,
+            walk_in_lanes,
         }
     }
 
@@ -143,6 +149,8 @@ impl Panel for MapPanel {
             "feet_detection": self.feet_detection.save(),
             "ball_filter": self.ball_filter.save(),
             "obstacle_filter": self.obstacle_filter.save(),
+            "manual_placement": self.manual_placement.save(),
+            "walk_in_lanes": self.walk_in_lanes.save(),
         })
     }
 }
@@ -165,6 +173,8 @@ impl Widget for &mut MapPanel {
                 self.feet_detection.checkbox(ui);
                 self.ball_filter.checkbox(ui);
                 self.obstacle_filter.checkbox(ui);
+                self.manual_placement.checkbox(ui);
+                self.walk_in_lanes.checkbox(ui);
             });
             ComboBox::from_id_source("plot_type_selector")
                 .selected_text(format!("{:?}", self.current_plot_type))
@@ -240,6 +250,12 @@ impl Widget for &mut MapPanel {
         let _ = self
             .obstacle_filter
             .generic_paint(&painter, ground_to_field, &field_dimensions);
+        let _ = self
+            .manual_placement
+            .generic_paint(&painter, ground_to_field, &field_dimensions);
+        let _ = self
+            .walk_in_lanes
+            .generic_paint(&painter, ground_to_field, &field_dimensions);
 
         self.apply_zoom_and_pan(ui, &mut painter, &response);
         if response.double_clicked() {
@@ -1,4 +1,8 @@
+mod alarms;
+mod ball_hypotheses;
+mod behavior;
 mod behavior_simulator;
+mod cycler_graph;
 mod enum_plot;
 mod image;
 mod image_segments;
@@ -8,11 +12,17 @@ mod map;
 mod parameter;
 mod plot;
 mod remote;
+mod report;
+mod robot_service;
 mod text;
 mod vision_tuner;
 
 pub use self::behavior_simulator::BehaviorSimulatorPanel;
 pub use self::image::ImagePanel;
+pub use alarms::{notify_alarms, AlarmsPanel};
+pub use ball_hypotheses::BallHypothesesPanel;
+pub use behavior::BehaviorPanel;
+pub use cycler_graph::CyclerGraphPanel;
 pub use enum_plot::EnumPlotPanel;
 pub use image_segments::ImageSegmentsPanel;
 pub use look_at::LookAtPanel;
@@ -21,5 +31,7 @@ pub use map::MapPanel;
 pub use parameter::ParameterPanel;
 pub use plot::PlotPanel;
 pub use remote::RemotePanel;
+pub use report::ReportPanel;
+pub use robot_service::RobotServicePanel;
 pub use text::TextPanel;
 pub use vision_tuner::VisionTunerPanel;
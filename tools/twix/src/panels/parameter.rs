@@ -81,7 +81,27 @@ impl Widget for &mut ParameterPanel {
                     if ui.button("Set").clicked() {
                         match serde_json::from_str(&self.parameter_value) {
                             Ok(value) => {
-                                self.nao.update_parameter_value(&self.path, value);
+                                let latest_value = self
+                                    .value_buffer
+                                    .as_ref()
+                                    .and_then(|buffer| buffer.get_latest().ok());
+                                match latest_value {
+                                    // only the edited values are sent, the robot applies all of
+                                    // them in the same cycle
+                                    Some(latest_value) => {
+                                        let mut updates = Vec::new();
+                                        collect_changed_values(
+                                            &self.path,
+                                            &latest_value,
+                                            value,
+                                            &mut updates,
+                                        );
+                                        if !updates.is_empty() {
+                                            self.nao.update_parameter_values(updates);
+                                        }
+                                    }
+                                    None => self.nao.update_parameter_value(&self.path, value),
+                                }
                             }
                             Err(error) => error!("Failed to serialize parameter value: {error:#?}"),
                         }
@@ -133,6 +153,27 @@ impl Widget for &mut ParameterPanel {
     }
 }
 
+fn collect_changed_values(
+    path: &str,
+    current: &Value,
+    edited: Value,
+    updates: &mut Vec<(String, Value)>,
+) {
+    match (current, edited) {
+        (Value::Object(current), Value::Object(edited)) => {
+            for (key, edited) in edited {
+                let path = format!("{path}.{key}");
+                match current.get(&key) {
+                    Some(current) => collect_changed_values(&path, current, edited, updates),
+                    None => updates.push((path, edited)),
+                }
+            }
+        }
+        (current, edited) if *current != edited => updates.push((path.to_string(), edited)),
+        _ => {}
+    }
+}
+
 pub fn add_save_button<SerdesJsonValueProvider>(
     ui: &mut Ui,
     parameter_path: &str,
@@ -1,4 +1,8 @@
-use std::{str::FromStr, sync::Arc};
+use std::{
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 use color_eyre::eyre::{eyre, Result, WrapErr};
 use communication::client::CyclerOutput;
@@ -8,7 +12,7 @@ use eframe::{
     },
     epaint::Color32,
 };
-use egui_plot::{Line, Plot as EguiPlot, PlotPoints};
+use egui_plot::{Line, Plot as EguiPlot, PlotPoints, VLine};
 use log::{error, info};
 use mlua::{Function, Lua, LuaSerdeExt};
 use serde::{Deserialize, Serialize};
@@ -85,23 +89,22 @@ impl LineData {
         self.is_highlighted = is_highlighted
     }
 
-    fn plot(&self, maximum_buffer_size: usize) -> Line {
+    /// Plots the values over the seconds passed since they were received
+    fn plot(&self, now: SystemTime) -> Line {
         let lua_function: Function = self.lua.globals().get("conversion_function").unwrap();
         let values = self
             .value_buffer
             .as_ref()
             .map(|buffer| {
                 buffer
-                    .get_buffered()
+                    .get_buffered_with_timestamps()
                     .map(|buffered_values| {
-                        PlotPoints::from_iter(buffered_values.iter().enumerate().map(
-                            |(i, value)| {
-                                let value = lua_function
-                                    .call::<_, f64>(self.lua.to_value(value))
-                                    .unwrap_or(f64::NAN);
-                                [(maximum_buffer_size - i - 1) as f64, value]
-                            },
-                        ))
+                        PlotPoints::from_iter(buffered_values.iter().map(|(received_at, value)| {
+                            let value = lua_function
+                                .call::<_, f64>(self.lua.to_value(value))
+                                .unwrap_or(f64::NAN);
+                            [-seconds_between(*received_at, now), value]
+                        }))
                     })
                     .unwrap_or_default()
             })
@@ -189,6 +192,7 @@ pub struct PlotPanel {
     line_datas: Vec<LineData>,
     buffer_capacity: usize,
     nao: Arc<Nao>,
+    is_setting_time_cursor: bool,
 }
 
 impl Panel for PlotPanel {
@@ -221,6 +225,7 @@ impl Panel for PlotPanel {
             line_datas,
             buffer_capacity: DEFAULT_BUFFER_CAPACITY,
             nao,
+            is_setting_time_cursor: false,
         }
     }
 
@@ -232,30 +237,46 @@ impl Panel for PlotPanel {
 }
 
 impl PlotPanel {
-    fn plot(&self, ui: &mut Ui) -> Response {
-        let maximum_buffer_size = self
-            .line_datas
-            .iter()
-            .filter_map(|line_data| {
-                let buffer = line_data.value_buffer.as_ref()?;
-                buffer.size().ok()
-            })
-            .max()
-            .unwrap_or(self.buffer_capacity);
+    /// Hovering the plot moves the time cursor of the robot, which all other panels follow
+    fn plot(&mut self, ui: &mut Ui) -> Response {
+        let now = SystemTime::now();
+        let time_cursor = self.nao.time_cursor();
 
-        EguiPlot::new(ui.id().with("value_plot"))
+        let plot_response = EguiPlot::new(ui.id().with("value_plot"))
             .view_aspect(2.0)
             .show(ui, |plot_ui| {
                 for line in self
                     .line_datas
                     .iter()
                     .filter(|line_data| !line_data.is_hidden)
-                    .map(|entry| entry.plot(maximum_buffer_size))
+                    .map(|entry| entry.plot(now))
                 {
                     plot_ui.line(line);
                 }
-            })
-            .response
+                if let Some(cursor_time) = time_cursor.get() {
+                    plot_ui.vline(
+                        VLine::new(-seconds_between(cursor_time, now)).color(Color32::YELLOW),
+                    );
+                }
+                plot_ui
+                    .pointer_coordinate()
+                    .filter(|_| plot_ui.response().hovered())
+            });
+
+        match plot_response.inner {
+            Some(pointer) => {
+                let age = Duration::from_secs_f64(pointer.x.min(0.0).abs());
+                time_cursor.set(now.checked_sub(age));
+                self.is_setting_time_cursor = true;
+            }
+            None if self.is_setting_time_cursor => {
+                time_cursor.set(None);
+                self.is_setting_time_cursor = false;
+            }
+            None => {}
+        }
+
+        plot_response.response
     }
 
     fn show_menu(&mut self, ui: &mut Ui) {
@@ -324,6 +345,13 @@ impl Widget for &mut PlotPanel {
     }
 }
 
+fn seconds_between(earlier: SystemTime, later: SystemTime) -> f64 {
+    later
+        .duration_since(earlier)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
 fn get_latest_value(value_buffer: &Option<ValueBuffer>) -> Result<Value> {
     let buffer = value_buffer
         .as_ref()
@@ -0,0 +1,458 @@
+use std::{
+    fmt::Write as _,
+    fs::{read_to_string, write},
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use color_eyre::{eyre::WrapErr, Result};
+use communication::client::CyclerOutput;
+use eframe::egui::{CollapsingHeader, Key, Response, ScrollArea, TextEdit, Ui, Widget};
+use log::{error, info};
+use repository::{get_repository_root, Repository};
+use serde_json::{json, Value};
+use tokio::runtime::Runtime;
+use types::cycle_time::CycleTime;
+
+use crate::{
+    change_buffer::ChangeBuffer, completion_edit::CompletionEdit, nao::Nao, panel::Panel,
+    value_buffer::ValueBuffer,
+};
+
+const PLOT_BUFFER_SIZE: usize = 1000;
+
+struct Note {
+    time: Duration,
+    text: String,
+}
+
+struct EventTrack {
+    key: String,
+    buffer: Option<ChangeBuffer>,
+    events: Vec<(Duration, Value)>,
+}
+
+struct PlotTrack {
+    key: String,
+    buffer: Option<ValueBuffer>,
+}
+
+struct ParameterTrack {
+    path: String,
+    buffer: ValueBuffer,
+}
+
+pub struct ReportPanel {
+    nao: Arc<Nao>,
+    cycle_time: ValueBuffer,
+    session_start: Option<SystemTime>,
+    notes: Vec<Note>,
+    events: Vec<EventTrack>,
+    plots: Vec<PlotTrack>,
+    parameters: Vec<ParameterTrack>,
+    default_parameters: Result<Value>,
+    new_note: String,
+    new_event_key: String,
+    new_plot_key: String,
+    new_parameter_path: String,
+    report_path: String,
+}
+
+impl Panel for ReportPanel {
+    const NAME: &'static str = "Report";
+
+    fn new(nao: Arc<Nao>, value: Option<&Value>) -> Self {
+        let keys = |name: &str| -> Vec<String> {
+            value
+                .and_then(|value| value.get(name))
+                .and_then(|keys| keys.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|key| key.as_str())
+                .map(ToString::to_string)
+                .collect()
+        };
+        let events = keys("event_keys")
+            .into_iter()
+            .map(|key| subscribe_events(&nao, key))
+            .collect();
+        let plots = keys("plot_keys")
+            .into_iter()
+            .map(|key| subscribe_plot(&nao, key))
+            .collect();
+        let parameters = keys("parameter_paths")
+            .into_iter()
+            .map(|path| subscribe_parameter(&nao, path))
+            .collect();
+        let report_path = value
+            .and_then(|value| value.get("report_path"))
+            .and_then(|path| path.as_str())
+            .unwrap_or("twix_report.md")
+            .to_string();
+        let cycle_time =
+            nao.subscribe_output(CyclerOutput::from_str("Control.main.cycle_time").unwrap());
+        Self {
+            nao,
+            cycle_time,
+            session_start: None,
+            notes: Vec::new(),
+            events,
+            plots,
+            parameters,
+            default_parameters: load_default_parameters(),
+            new_note: String::new(),
+            new_event_key: String::new(),
+            new_plot_key: String::new(),
+            new_parameter_path: String::new(),
+            report_path,
+        }
+    }
+
+    fn save(&self) -> Value {
+        json!({
+            "event_keys": self.events.iter().map(|track| &track.key).collect::<Vec<_>>(),
+            "plot_keys": self.plots.iter().map(|track| &track.key).collect::<Vec<_>>(),
+            "parameter_paths": self.parameters.iter().map(|track| &track.path).collect::<Vec<_>>(),
+            "report_path": self.report_path,
+        })
+    }
+}
+
+impl Widget for &mut ReportPanel {
+    fn ui(self, ui: &mut Ui) -> Response {
+        if self.session_start.is_none() {
+            self.session_start = self.robot_time();
+        }
+        let now = self.session_duration();
+        for track in &mut self.events {
+            let Some(buffer) = &track.buffer else {
+                continue;
+            };
+            match buffer.get_and_reset() {
+                Ok(update) => track
+                    .events
+                    .extend(update.updates.into_iter().map(|change| (now, change.value))),
+                Err(error) => error!("failed to get changes of {}: {error}", track.key),
+            }
+        }
+
+        ui.vertical(|ui| {
+            ui.horizontal(|ui| {
+                ui.label("Report file:");
+                ui.text_edit_singleline(&mut self.report_path);
+                if ui.button("Export").clicked() {
+                    match self.export() {
+                        Ok(()) => info!("exported session report to {}", self.report_path),
+                        Err(error) => error!("failed to export session report: {error:#}"),
+                    }
+                }
+            });
+            ui.separator();
+            ui.horizontal(|ui| {
+                let note_edit = ui.add(
+                    TextEdit::singleline(&mut self.new_note).hint_text("Describe what happened"),
+                );
+                let submitted =
+                    note_edit.lost_focus() && ui.input(|input| input.key_pressed(Key::Enter));
+                if (ui.button("Add note").clicked() || submitted) && !self.new_note.is_empty() {
+                    self.notes.push(Note {
+                        time: now,
+                        text: std::mem::take(&mut self.new_note),
+                    });
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.add(CompletionEdit::outputs(
+                    &mut self.new_event_key,
+                    self.nao.as_ref(),
+                ));
+                if ui.button("Track events").clicked() && !self.new_event_key.is_empty() {
+                    let key = std::mem::take(&mut self.new_event_key);
+                    self.events.push(subscribe_events(&self.nao, key));
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.add(CompletionEdit::outputs(
+                    &mut self.new_plot_key,
+                    self.nao.as_ref(),
+                ));
+                if ui.button("Track plot").clicked() && !self.new_plot_key.is_empty() {
+                    let key = std::mem::take(&mut self.new_plot_key);
+                    self.plots.push(subscribe_plot(&self.nao, key));
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.add(CompletionEdit::parameters(
+                    &mut self.new_parameter_path,
+                    self.nao.as_ref(),
+                ));
+                if ui.button("Track parameter").clicked() && !self.new_parameter_path.is_empty() {
+                    let path = std::mem::take(&mut self.new_parameter_path);
+                    self.parameters.push(subscribe_parameter(&self.nao, path));
+                }
+            });
+            ui.separator();
+            ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    CollapsingHeader::new(format!("Notes ({})", self.notes.len()))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            for note in &self.notes {
+                                ui.label(format!("{} {}", format_time(note.time), note.text));
+                            }
+                        });
+                    CollapsingHeader::new("Tracked outputs and parameters").show(ui, |ui| {
+                        self.events
+                            .retain(|track| !remove_button(ui, &track.key, track.events.len()));
+                        self.plots.retain(|track| !remove_button(ui, &track.key, 0));
+                        self.parameters
+                            .retain(|track| !remove_button(ui, &track.path, 0));
+                    });
+                });
+        })
+        .response
+    }
+}
+
+impl ReportPanel {
+    /// Start time of the latest control cycle, so that events are stamped when they happened on
+    /// the robot instead of when the panel got to see them.
+    fn robot_time(&self) -> Option<SystemTime> {
+        self.cycle_time
+            .parse_latest::<CycleTime>()
+            .ok()
+            .map(|cycle_time| cycle_time.start_time)
+    }
+
+    fn session_duration(&self) -> Duration {
+        self.session_start
+            .zip(self.robot_time())
+            .and_then(|(session_start, now)| now.duration_since(session_start).ok())
+            .unwrap_or_default()
+    }
+
+    fn export(&self) -> Result<()> {
+        let sections = self.sections();
+        let is_html = Path::new(&self.report_path)
+            .extension()
+            .is_some_and(|extension| extension == "html");
+        let report = if is_html {
+            render_html(&sections)
+        } else {
+            render_markdown(&sections)
+        };
+        write(&self.report_path, report)
+            .wrap_err_with(|| format!("failed to write {}", self.report_path))
+    }
+
+    fn sections(&self) -> Vec<Section> {
+        let address = self.nao.get_address().unwrap_or_default();
+        let mut sections = vec![Section {
+            title: format!("Twix session report for {address}"),
+            lines: vec![format!(
+                "Session duration: {}",
+                format_time(self.session_duration())
+            )],
+        }];
+
+        sections.push(Section {
+            title: "Notes".to_string(),
+            lines: self
+                .notes
+                .iter()
+                .map(|note| format!("{} {}", format_time(note.time), note.text))
+                .collect(),
+        });
+
+        let mut timeline: Vec<_> = self
+            .events
+            .iter()
+            .flat_map(|track| {
+                track
+                    .events
+                    .iter()
+                    .map(move |(time, value)| (*time, format!("{}: {value}", track.key)))
+            })
+            .collect();
+        timeline.sort_by_key(|(time, _)| *time);
+        sections.push(Section {
+            title: "Event timeline".to_string(),
+            lines: timeline
+                .into_iter()
+                .map(|(time, event)| format!("{} {event}", format_time(time)))
+                .collect(),
+        });
+
+        sections.push(Section {
+            title: "Plots".to_string(),
+            lines: self.plots.iter().map(summarize_plot).collect(),
+        });
+
+        sections.push(Section {
+            title: "Parameter changes".to_string(),
+            lines: self
+                .parameters
+                .iter()
+                .filter_map(|track| {
+                    let current = track.buffer.get_latest().ok()?;
+                    let default = self
+                        .default_parameters
+                        .as_ref()
+                        .ok()
+                        .and_then(|parameters| value_at_path(parameters, &track.path));
+                    (Some(&current) != default.as_ref()).then(|| {
+                        format!(
+                            "{}: {} -> {current}",
+                            track.path,
+                            default.map_or("<missing>".to_string(), |value| value.to_string())
+                        )
+                    })
+                })
+                .collect(),
+        });
+        sections
+    }
+}
+
+struct Section {
+    title: String,
+    lines: Vec<String>,
+}
+
+fn render_markdown(sections: &[Section]) -> String {
+    let mut report = String::new();
+    for (index, section) in sections.iter().enumerate() {
+        let heading = if index == 0 { "#" } else { "##" };
+        let _ = writeln!(report, "{heading} {}\n", section.title);
+        if section.lines.is_empty() {
+            let _ = writeln!(report, "_none_");
+        }
+        for line in &section.lines {
+            let _ = writeln!(report, "- {line}");
+        }
+        report.push('\n');
+    }
+    report
+}
+
+fn render_html(sections: &[Section]) -> String {
+    let mut report = String::from("<!DOCTYPE html>\n<html>\n<body>\n");
+    for (index, section) in sections.iter().enumerate() {
+        let heading = if index == 0 { "h1" } else { "h2" };
+        let _ = writeln!(
+            report,
+            "<{heading}>{}</{heading}>",
+            escape_html(&section.title)
+        );
+        if section.lines.is_empty() {
+            report.push_str("<p><em>none</em></p>\n");
+            continue;
+        }
+        report.push_str("<ul>\n");
+        for line in &section.lines {
+            let _ = writeln!(report, "<li>{}</li>", escape_html(line));
+        }
+        report.push_str("</ul>\n");
+    }
+    report.push_str("</body>\n</html>\n");
+    report
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn summarize_plot(track: &PlotTrack) -> String {
+    let values: Vec<f64> = track
+        .buffer
+        .as_ref()
+        .and_then(|buffer| buffer.get_buffered().ok())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(Value::as_f64)
+        .collect();
+    if values.is_empty() {
+        return format!("{}: no numeric data", track.key);
+    }
+    let minimum = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let maximum = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    format!(
+        "{}: {} samples, min {minimum:.3}, mean {mean:.3}, max {maximum:.3}, latest {:.3}",
+        track.key,
+        values.len(),
+        values.last().unwrap()
+    )
+}
+
+fn remove_button(ui: &mut Ui, key: &str, number_of_events: usize) -> bool {
+    ui.horizontal(|ui| {
+        let clicked = ui.small_button("✖").clicked();
+        if number_of_events > 0 {
+            ui.label(format!("{key} ({number_of_events} events)"));
+        } else {
+            ui.label(key);
+        }
+        clicked
+    })
+    .inner
+}
+
+fn format_time(time: Duration) -> String {
+    let seconds = time.as_secs();
+    format!("[{:02}:{:02}]", seconds / 60, seconds % 60)
+}
+
+fn value_at_path<'value>(value: &'value Value, path: &str) -> Option<&'value Value> {
+    path.split('.')
+        .try_fold(value, |value, segment| value.get(segment))
+}
+
+fn load_default_parameters() -> Result<Value> {
+    let runtime = Runtime::new()?;
+    let repository = Repository::new(runtime.block_on(get_repository_root())?);
+    let path = repository.parameters_root().join("default.json");
+    let content =
+        read_to_string(&path).wrap_err_with(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_str(&content).wrap_err("failed to parse default parameters")
+}
+
+fn subscribe_events(nao: &Nao, key: String) -> EventTrack {
+    let buffer = match CyclerOutput::from_str(&key) {
+        Ok(output) => Some(nao.subscribe_changes(output)),
+        Err(error) => {
+            error!("Failed to subscribe to {key}: {error:?}");
+            None
+        }
+    };
+    EventTrack {
+        key,
+        buffer,
+        events: Vec::new(),
+    }
+}
+
+fn subscribe_plot(nao: &Nao, key: String) -> PlotTrack {
+    let buffer = match CyclerOutput::from_str(&key) {
+        Ok(output) => {
+            let buffer = nao.subscribe_output(output);
+            buffer.reserve(PLOT_BUFFER_SIZE);
+            Some(buffer)
+        }
+        Err(error) => {
+            error!("Failed to subscribe to {key}: {error:?}");
+            None
+        }
+    };
+    PlotTrack { key, buffer }
+}
+
+fn subscribe_parameter(nao: &Nao, path: String) -> ParameterTrack {
+    let buffer = nao.subscribe_parameter(&path);
+    ParameterTrack { path, buffer }
+}
@@ -0,0 +1,245 @@
+use std::{future::Future, net::Ipv4Addr, sync::Arc};
+
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use eframe::egui::{
+    Button, ComboBox, Context, DragValue, Response, ScrollArea, TextEdit, Ui, Widget,
+};
+use nao::SystemctlAction;
+use serde_json::{json, Value};
+use tokio::{
+    runtime::{Builder, Runtime},
+    sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+};
+
+use crate::{nao::Nao, panel::Panel};
+
+enum TaskOutput {
+    Text(String),
+    Locations(Vec<String>),
+}
+
+/// Actions interrupting the robot or replacing its parameters are only executed once confirmed
+enum UnconfirmedAction {
+    Reboot,
+    ActivateLocation(String),
+}
+
+impl UnconfirmedAction {
+    fn question(&self) -> String {
+        match self {
+            UnconfirmedAction::Reboot => "Reboot the robot?".to_string(),
+            UnconfirmedAction::ActivateLocation(location) => {
+                format!("Replace the parameters in nao_location by {location} and restart hulk?")
+            }
+        }
+    }
+}
+
+/// Common chores of the robot handler executed via SSH on the connected robot
+pub struct RobotServicePanel {
+    nao: Arc<Nao>,
+    runtime: Runtime,
+    sender: UnboundedSender<Result<TaskOutput>>,
+    receiver: UnboundedReceiver<Result<TaskOutput>>,
+    number_of_pending_tasks: usize,
+    number_of_log_lines: usize,
+    locations: Vec<String>,
+    selected_location: String,
+    unconfirmed_action: Option<UnconfirmedAction>,
+    output: String,
+}
+
+impl Panel for RobotServicePanel {
+    const NAME: &'static str = "Robot Service";
+
+    fn new(nao: Arc<Nao>, value: Option<&Value>) -> Self {
+        let runtime = Builder::new_multi_thread().enable_all().build().unwrap();
+        let (sender, receiver) = unbounded_channel();
+        let number_of_log_lines = value
+            .and_then(|value| value.get("number_of_log_lines"))
+            .and_then(|value| value.as_u64())
+            .unwrap_or(100) as usize;
+        let selected_location = value
+            .and_then(|value| value.get("selected_location"))
+            .and_then(|value| value.as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        Self {
+            nao,
+            runtime,
+            sender,
+            receiver,
+            number_of_pending_tasks: 0,
+            number_of_log_lines,
+            locations: Vec::new(),
+            selected_location,
+            unconfirmed_action: None,
+            output: String::new(),
+        }
+    }
+
+    fn save(&self) -> Value {
+        json!({
+            "number_of_log_lines": self.number_of_log_lines,
+            "selected_location": self.selected_location,
+        })
+    }
+}
+
+impl RobotServicePanel {
+    fn host(&self) -> Result<Ipv4Addr> {
+        let address = self
+            .nao
+            .get_address()
+            .ok_or_else(|| eyre!("no robot address set"))?;
+        address
+            .split(':')
+            .next()
+            .unwrap_or_default()
+            .parse()
+            .wrap_err_with(|| format!("{address} is not a robot address"))
+    }
+
+    fn spawn<Task>(&mut self, context: &Context, task: impl FnOnce(nao::Nao) -> Task)
+    where
+        Task: Future<Output = Result<TaskOutput>> + Send + 'static,
+    {
+        let robot = match self.host() {
+            Ok(host) => nao::Nao::new(host),
+            Err(error) => {
+                self.output = format!("{error:#}");
+                return;
+            }
+        };
+        let task = task(robot);
+        let sender = self.sender.clone();
+        let context = context.clone();
+        self.number_of_pending_tasks += 1;
+        self.runtime.spawn(async move {
+            let _ = sender.send(task.await);
+            context.request_repaint();
+        });
+    }
+
+    fn execute(&mut self, context: &Context, action: UnconfirmedAction) {
+        match action {
+            UnconfirmedAction::Reboot => self.spawn(context, |robot| async move {
+                robot.reboot().await?;
+                Ok(TaskOutput::Text("Rebooting".to_string()))
+            }),
+            UnconfirmedAction::ActivateLocation(location) => {
+                self.spawn(context, |robot| async move {
+                    robot.activate_parameter_location(&location).await?;
+                    robot
+                        .execute_systemctl(SystemctlAction::Restart, "hulk")
+                        .await?;
+                    Ok(TaskOutput::Text(format!(
+                        "Activated {location} and restarted the hulk service"
+                    )))
+                })
+            }
+        }
+    }
+}
+
+impl Widget for &mut RobotServicePanel {
+    fn ui(self, ui: &mut Ui) -> Response {
+        while let Ok(result) = self.receiver.try_recv() {
+            self.number_of_pending_tasks -= 1;
+            match result {
+                Ok(TaskOutput::Text(text)) => self.output = text,
+                Ok(TaskOutput::Locations(locations)) => {
+                    if !locations.contains(&self.selected_location) {
+                        self.selected_location = locations.first().cloned().unwrap_or_default();
+                    }
+                    self.locations = locations;
+                }
+                Err(error) => self.output = format!("{error:#}"),
+            }
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("Restart hulk").clicked() {
+                self.spawn(ui.ctx(), |robot| async move {
+                    robot
+                        .execute_systemctl(SystemctlAction::Restart, "hulk")
+                        .await?;
+                    Ok(TaskOutput::Text("Restarted the hulk service".to_string()))
+                });
+            }
+            if ui.button("Reboot").clicked() {
+                self.unconfirmed_action = Some(UnconfirmedAction::Reboot);
+            }
+            if self.number_of_pending_tasks > 0 {
+                ui.spinner();
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui.button("Fetch log tail").clicked() {
+                let number_of_log_lines = self.number_of_log_lines;
+                self.spawn(ui.ctx(), move |robot| async move {
+                    let log = robot.retrieve_log_tail(number_of_log_lines).await?;
+                    Ok(TaskOutput::Text(log))
+                });
+            }
+            ui.add(
+                DragValue::new(&mut self.number_of_log_lines)
+                    .clamp_range(1..=10_000)
+                    .suffix(" lines"),
+            );
+        });
+        ui.horizontal(|ui| {
+            if ui.button("List locations").clicked() {
+                self.spawn(ui.ctx(), |robot| async move {
+                    let locations = robot.list_parameter_locations().await?;
+                    Ok(TaskOutput::Locations(locations))
+                });
+            }
+            ComboBox::from_id_source("robot_service_location")
+                .selected_text(&self.selected_location)
+                .show_ui(ui, |ui| {
+                    for location in &self.locations {
+                        ui.selectable_value(
+                            &mut self.selected_location,
+                            location.clone(),
+                            location,
+                        );
+                    }
+                });
+            let activate = ui.add_enabled(
+                !self.selected_location.is_empty(),
+                Button::new("Activate and restart"),
+            );
+            if activate.clicked() {
+                self.unconfirmed_action = Some(UnconfirmedAction::ActivateLocation(
+                    self.selected_location.clone(),
+                ));
+            }
+        });
+        if let Some(action) = self.unconfirmed_action.take() {
+            ui.horizontal(|ui| {
+                ui.label(action.question());
+                if ui.button("Confirm").clicked() {
+                    self.execute(ui.ctx(), action);
+                } else if !ui.button("Cancel").clicked() {
+                    self.unconfirmed_action = Some(action);
+                }
+            });
+        }
+
+        ScrollArea::vertical()
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                ui.add(
+                    TextEdit::multiline(&mut self.output.as_str())
+                        .code_editor()
+                        .desired_width(f32::INFINITY),
+                )
+            })
+            .inner
+    }
+}
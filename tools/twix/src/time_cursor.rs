@@ -0,0 +1,34 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+/// Point in time all panels of a robot show instead of the latest values, e.g. while hovering a
+/// plot. Values are looked up by the time they were received.
+#[derive(Clone)]
+pub struct TimeCursor {
+    time: Arc<Mutex<Option<SystemTime>>>,
+    history_duration: Duration,
+}
+
+impl TimeCursor {
+    pub fn new(history_duration: Duration) -> Self {
+        Self {
+            time: Default::default(),
+            history_duration,
+        }
+    }
+
+    /// How long received values are kept to be shown when the cursor points into the past
+    pub fn history_duration(&self) -> Duration {
+        self.history_duration
+    }
+
+    pub fn get(&self) -> Option<SystemTime> {
+        *self.time.lock().unwrap()
+    }
+
+    pub fn set(&self, time: Option<SystemTime>) {
+        *self.time.lock().unwrap() = time;
+    }
+}
@@ -1,4 +1,7 @@
-use std::collections::VecDeque;
+use std::{
+    collections::VecDeque,
+    time::{Duration, SystemTime},
+};
 
 use color_eyre::{
     eyre::{eyre, WrapErr},
@@ -19,16 +22,16 @@ use tokio::{
     },
 };
 
+use crate::time_cursor::TimeCursor;
+
 #[derive(Debug)]
 enum Message {
     GetLatest {
+        at: Option<SystemTime>,
         response_sender: oneshot::Sender<Result<Value, String>>,
     },
     GetBuffered {
-        response_sender: oneshot::Sender<Result<Vec<Value>, String>>,
-    },
-    GetSize {
-        response_sender: oneshot::Sender<Result<usize, String>>,
+        response_sender: oneshot::Sender<Result<Vec<(SystemTime, Value)>, String>>,
     },
     SetCapacity {
         buffer_capacity: usize,
@@ -43,19 +46,36 @@ enum Message {
 
 pub struct ValueBuffer {
     command_sender: mpsc::Sender<Message>,
+    time_cursor: Option<TimeCursor>,
 }
 
 impl ValueBuffer {
-    pub fn output(communication: Communication, output: CyclerOutput) -> Self {
+    /// Outputs follow the time cursor, values received within the history duration are kept
+    pub fn output(
+        communication: Communication,
+        output: CyclerOutput,
+        time_cursor: TimeCursor,
+    ) -> Self {
         let (command_sender, command_receiver) = mpsc::channel(10);
+        let history_duration = time_cursor.history_duration();
         spawn(async move {
             let (uuid, receiver) = communication
                 .subscribe_output(output.clone(), Format::Textual)
                 .await;
-            value_buffer(receiver, command_receiver, communication.clone(), None).await;
+            value_buffer(
+                receiver,
+                command_receiver,
+                communication.clone(),
+                None,
+                history_duration,
+            )
+            .await;
             communication.unsubscribe_output(uuid).await;
         });
-        Self { command_sender }
+        Self {
+            command_sender,
+            time_cursor: Some(time_cursor),
+        }
     }
 
     pub fn parameter(communication: Communication, path: String) -> Self {
@@ -67,11 +87,16 @@ impl ValueBuffer {
                 command_receiver,
                 communication.clone(),
                 Some(path),
+                // parameters are edited in place and do not need a history
+                Duration::ZERO,
             )
             .await;
             communication.unsubscribe_parameter(uuid).await;
         });
-        Self { command_sender }
+        Self {
+            command_sender,
+            time_cursor: None,
+        }
     }
 
     pub fn listen_to_updates(&self, response_sender: mpsc::Sender<()>) {
@@ -80,10 +105,12 @@ impl ValueBuffer {
             .unwrap()
     }
 
+    /// Returns the latest value received before the time cursor, if it is set
     pub fn get_latest(&self) -> Result<Value, String> {
         let (sender, receiver) = oneshot::channel();
         self.command_sender
             .blocking_send(Message::GetLatest {
+                at: self.time_cursor.as_ref().and_then(TimeCursor::get),
                 response_sender: sender,
             })
             .unwrap();
@@ -91,6 +118,15 @@ impl ValueBuffer {
     }
 
     pub fn get_buffered(&self) -> Result<Vec<Value>, String> {
+        self.get_buffered_with_timestamps().map(|values| {
+            values
+                .into_iter()
+                .map(|(_received_at, value)| value)
+                .collect()
+        })
+    }
+
+    pub fn get_buffered_with_timestamps(&self) -> Result<Vec<(SystemTime, Value)>, String> {
         let (sender, receiver) = oneshot::channel();
         self.command_sender
             .blocking_send(Message::GetBuffered {
@@ -108,16 +144,6 @@ impl ValueBuffer {
             .unwrap();
     }
 
-    pub fn size(&self) -> Result<usize, String> {
-        let (sender, receiver) = oneshot::channel();
-        self.command_sender
-            .blocking_send(Message::GetSize {
-                response_sender: sender,
-            })
-            .unwrap();
-        receiver.blocking_recv().unwrap()
-    }
-
     pub fn update_parameter_value(&self, value: Value) {
         self.command_sender
             .blocking_send(Message::UpdateParameterValue { value })
@@ -154,8 +180,9 @@ async fn value_buffer(
     mut command_receiver: mpsc::Receiver<Message>,
     communication: Communication,
     parameter_path: Option<String>,
+    history_duration: Duration,
 ) {
-    let mut values: Option<Result<VecDeque<Value>, String>> = None;
+    let mut values: Option<Result<VecDeque<(SystemTime, Value)>, String>> = None;
     let mut update_listeners: Vec<mpsc::Sender<()>> = Vec::new();
     let mut buffer_capacity = 1;
     let mut skip_updates = 0;
@@ -171,7 +198,7 @@ async fn value_buffer(
                                     skip_updates -= 1;
                                     continue;
                                 }
-                                add_element(&mut values, buffer_capacity, value);
+                                add_element(&mut values, buffer_capacity, history_duration, value);
                                 update_listeners.retain(|listener| {
                                     if let Err(TrySendError::Closed(_)) = listener.try_send(()) {
                                             return false;
@@ -193,9 +220,9 @@ async fn value_buffer(
             maybe_command = command_receiver.recv() => {
                 match maybe_command {
                     Some(command) => match command {
-                        Message::GetLatest{response_sender} => {
+                        Message::GetLatest{at, response_sender} => {
                             let response = match &values {
-                                Some(Ok(values)) => Ok(values.front().unwrap().clone()),
+                                Some(Ok(values)) => Ok(value_at(values, at).clone()),
                                 Some(Err(error)) => Err(error.clone()),
                                 None => Err("No response yet".to_string()),
                             };
@@ -203,24 +230,18 @@ async fn value_buffer(
                         },
                         Message::GetBuffered{response_sender} => {
                             let response = match &values {
-                                Some(Ok(values)) => Ok(values.iter().cloned().collect()),
+                                Some(Ok(values)) => {
+                                    Ok(values.iter().take(buffer_capacity).cloned().collect())
+                                }
                                 Some(Err(error)) => Err(error.clone()),
                                 None => Err("No response yet".to_string()),
                             };
                             response_sender.send(response).unwrap();
                         },
-                        Message::GetSize{response_sender} => {
-                            let response = match &values {
-                                Some(Ok(values)) => Ok(values.len()),
-                                Some(Err(error)) => Err(error.clone()),
-                                None => Err("No response yet".to_string())
-                            };
-                            response_sender.send(response).unwrap();
-                        }
                         Message::SetCapacity{buffer_capacity:new_buffer_capacity} => {
                             buffer_capacity = new_buffer_capacity;
                             if let Some(Ok(values)) = &mut values {
-                                values.truncate(buffer_capacity);
+                                truncate(values, buffer_capacity, history_duration);
                             }
                         },
                         Message::ListenToUpdates{response_sender} => {
@@ -228,7 +249,7 @@ async fn value_buffer(
                         },
                         Message::UpdateParameterValue{value} => {
                             skip_updates += 1;
-                            add_element(&mut values, buffer_capacity, value.clone());
+                            add_element(&mut values, buffer_capacity, history_duration, value.clone());
                             communication.update_parameter_value(
                                 parameter_path.as_ref().expect(
                                     "tried updating parameter on output value buffer"
@@ -245,19 +266,52 @@ async fn value_buffer(
 }
 
 fn add_element(
-    values: &mut Option<Result<VecDeque<Value>, String>>,
+    values: &mut Option<Result<VecDeque<(SystemTime, Value)>, String>>,
     capacity: usize,
+    history_duration: Duration,
     value: Value,
 ) {
+    let now = SystemTime::now();
     match values {
         Some(Ok(values)) => {
-            values.push_front(value);
-            values.truncate(capacity);
+            values.push_front((now, value));
+            truncate(values, capacity, history_duration);
         }
         _ => {
             let mut new_buffer = VecDeque::with_capacity(capacity);
-            new_buffer.push_back(value);
+            new_buffer.push_back((now, value));
             *values = Some(Ok(new_buffer));
         }
     }
 }
+
+/// Keeps at least `capacity` values and all values within the history duration
+fn truncate(
+    values: &mut VecDeque<(SystemTime, Value)>,
+    capacity: usize,
+    history_duration: Duration,
+) {
+    let now = SystemTime::now();
+    let is_within_history = |received_at: &SystemTime| {
+        now.duration_since(*received_at)
+            .map_or(true, |age| age < history_duration)
+    };
+    let length = values
+        .iter()
+        .enumerate()
+        .take_while(|(index, (received_at, _))| *index < capacity || is_within_history(received_at))
+        .count();
+    values.truncate(length.max(1));
+}
+
+/// Returns the latest value received at or before `at`, or the oldest one if all are newer
+fn value_at(values: &VecDeque<(SystemTime, Value)>, at: Option<SystemTime>) -> &Value {
+    let (_, value) = match at {
+        Some(at) => values
+            .iter()
+            .find(|(received_at, _)| *received_at <= at)
+            .unwrap_or_else(|| values.back().unwrap()),
+        None => values.front().unwrap(),
+    };
+    value
+}
@@ -0,0 +1,91 @@
+use std::{
+    fs::{create_dir_all, read_dir, read_to_string, write},
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{
+    eyre::{eyre, WrapErr},
+    Result,
+};
+use egui_dock::DockState;
+use home::home_dir;
+use serde::{Deserialize, Serialize};
+use serde_json::{from_str, to_string_pretty, Value};
+
+/// Complete layout with the saved state of every panel (subscriptions and settings), stored as a
+/// standalone file such that debugging setups can be shared within the team.
+#[derive(Deserialize, Serialize)]
+pub struct Workspace {
+    pub address: WorkspaceAddress,
+    pub dock_state: DockState<Value>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub enum WorkspaceAddress {
+    /// Placeholder for whichever robot the importing user is connected to
+    #[default]
+    Current,
+    Fixed(String),
+}
+
+impl Workspace {
+    /// Either a name in the workspace directory or a path to a `.json` file
+    pub fn load(name_or_path: &str) -> Result<Self> {
+        let path = resolve(name_or_path)?;
+        let content =
+            read_to_string(&path).wrap_err_with(|| format!("failed to read {}", path.display()))?;
+        from_str(&content).wrap_err_with(|| format!("failed to parse {}", path.display()))
+    }
+
+    pub fn store(&self, name_or_path: &str) -> Result<PathBuf> {
+        let path = resolve(name_or_path)?;
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)
+                .wrap_err_with(|| format!("failed to create {}", parent.display()))?;
+        }
+        let content = to_string_pretty(self).wrap_err("failed to serialize workspace")?;
+        write(&path, content).wrap_err_with(|| format!("failed to write {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Names of the workspaces in the workspace directory
+    pub fn list() -> Vec<String> {
+        let Some(directory) = workspace_directory() else {
+            return Vec::new();
+        };
+        let Ok(entries) = read_dir(directory) else {
+            return Vec::new();
+        };
+        let mut names: Vec<_> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != "json" {
+                    return None;
+                }
+                Some(path.file_stem()?.to_str()?.to_string())
+            })
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+fn resolve(name_or_path: &str) -> Result<PathBuf> {
+    let path = Path::new(name_or_path);
+    if path
+        .extension()
+        .is_some_and(|extension| extension == "json")
+    {
+        return Ok(path.to_path_buf());
+    }
+    if name_or_path.is_empty() || path.components().count() != 1 {
+        return Err(eyre!("invalid workspace name {name_or_path:?}"));
+    }
+    let directory =
+        workspace_directory().ok_or_else(|| eyre!("failed to find the home directory"))?;
+    Ok(directory.join(format!("{name_or_path}.json")))
+}
+
+fn workspace_directory() -> Option<PathBuf> {
+    home_dir().map(|home| home.join(".config/twix/workspaces"))
+}